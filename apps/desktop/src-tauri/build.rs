@@ -1,4 +1,13 @@
 fn main() {
+  // 安装版卸载信息的注册表键名取自 tauri.conf.json 的 identifier，代码中不另写一份
+  let conf: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string("tauri.conf.json").expect("读取 tauri.conf.json 失败"),
+  )
+  .expect("解析 tauri.conf.json 失败");
+  let identifier = conf["identifier"]
+    .as_str()
+    .expect("tauri.conf.json 缺少 identifier");
+  println!("cargo:rustc-env=EFGH_BUNDLE_IDENTIFIER={}", identifier);
+  println!("cargo:rerun-if-changed=tauri.conf.json");
   tauri_build::build()
 }
-
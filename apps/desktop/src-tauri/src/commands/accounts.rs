@@ -0,0 +1,741 @@
+//! 账号切换
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::config::{current_config, update_config};
+use crate::commands::hash_chain::check_hash_chain;
+use crate::commands::pity::reconcile_pity_from_records;
+use crate::db::access::with_db_write;
+use crate::db::{
+    open_db, parse_gacha_ts, parse_local_date, table_exists, table_has_column, GACHA_TYPES,
+    PULL_RECORDS_CTE,
+};
+use crate::error::AppError;
+use crate::tray::{TRAY_ID, TRAY_TOOLTIP};
+
+/// 账号摘要（账号列表展示用）
+#[derive(Clone, Serialize)]
+pub(crate) struct AccountSummary {
+    uid: String,
+    channel_name: String,
+    provider: Option<String>,
+    nick_name: Option<String>,
+    is_active: bool,
+}
+
+/// 从 roles JSON 中找出与账号 uid 对应角色的昵称
+///
+/// uid 格式为 `serverId:roleId`（国际服带 `gryphline@` 前缀）。
+fn nick_name_from_roles(uid: &str, roles_json: &str) -> Option<String> {
+    let role_id = uid.rsplit(':').next()?;
+    let roles: serde_json::Value = serde_json::from_str(roles_json).ok()?;
+    roles
+        .as_array()?
+        .iter()
+        .find(|r| r.get("roleId").and_then(|v| v.as_str()) == Some(role_id))
+        .and_then(|r| r.get("nickName"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// 把 roles JSON 中与账号 uid 对应角色的昵称改为 `nick_name`，找不到该角色时返回空
+fn with_role_nick_name(uid: &str, roles_json: &str, nick_name: &str) -> Option<String> {
+    let role_id = uid.rsplit(':').next()?;
+    let mut roles: serde_json::Value = serde_json::from_str(roles_json).ok()?;
+    let role = roles
+        .as_array_mut()?
+        .iter_mut()
+        .find(|r| r.get("roleId").and_then(|v| v.as_str()) == Some(role_id))?;
+    role.as_object_mut()?
+        .insert("nickName".to_string(), nick_name.into());
+    Some(roles.to_string())
+}
+
+/// 查询本地所有账号，并标记当前激活账号
+pub(crate) fn query_accounts(app: &AppHandle) -> Result<Vec<AccountSummary>, AppError> {
+    let active = current_config(app).active_account;
+    let conn = open_db(app)?;
+    let mut stmt = conn
+        .prepare("SELECT uid, channel_name, provider, roles FROM accounts ORDER BY added_at DESC")
+        .map_err(|e| AppError::database(format!("查询账号列表失败: {}", e)))?;
+    let accounts = stmt
+        .query_map([], |row| {
+            let uid: String = row.get(0)?;
+            let roles: String = row.get(3)?;
+            Ok(AccountSummary {
+                nick_name: nick_name_from_roles(&uid, &roles),
+                is_active: active.as_deref() == Some(uid.as_str()),
+                uid,
+                channel_name: row.get(1)?,
+                provider: row.get(2)?,
+            })
+        })
+        .map_err(|e| AppError::database(format!("查询账号列表失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取账号列表失败: {}", e)))?;
+
+    Ok(accounts)
+}
+
+/// 按当前激活账号刷新托盘提示文字
+pub(crate) fn refresh_tray_tooltip(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let active = query_accounts(app)
+        .ok()
+        .and_then(|accounts| accounts.into_iter().find(|a| a.is_active));
+    let tooltip = match active {
+        Some(account) => format!(
+            "{} - {}",
+            TRAY_TOOLTIP,
+            account.nick_name.unwrap_or(account.uid)
+        ),
+        None => TRAY_TOOLTIP.to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Tauri 命令：获取账号列表（带激活标记）
+#[tauri::command]
+pub(crate) fn list_accounts(app: AppHandle) -> Result<Vec<AccountSummary>, AppError> {
+    query_accounts(&app)
+}
+
+/// Tauri 命令：获取当前激活账号
+#[tauri::command]
+pub(crate) fn get_active_account(app: AppHandle) -> Option<String> {
+    current_config(&app).active_account
+}
+
+/// Tauri 命令：设置当前激活账号
+///
+/// 持久化到 Rust 配置，并向所有窗口广播 `active-account-changed`，
+/// 托盘提示与后台任务统一以此为准。传入 None 表示清除。
+#[tauri::command]
+pub(crate) fn set_active_account(app: AppHandle, uid: Option<String>) -> Result<(), AppError> {
+    let uid = uid.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    if current_config(&app).active_account == uid {
+        return Ok(());
+    }
+
+    if let Some(uid) = &uid {
+        let exists = query_accounts(&app)?.iter().any(|a| &a.uid == uid);
+        if !exists {
+            return Err(AppError::validation(format!("账号不存在: {}", uid)));
+        }
+    }
+
+    update_config(&app, |c| c.active_account = uid.clone())?;
+    refresh_tray_tooltip(&app);
+    let _ = app.emit("active-account-changed", serde_json::json!({ "uid": uid }));
+    Ok(())
+}
+
+/// 抽卡链接中可能携带 UID 的参数（按优先级）
+const URL_UID_PARAMS: &[&str] = &["init_app_uid", "uid", "role_id", "roleId"];
+
+/// 从链接的查询参数中取出 UID（也查找 `#/path?...` 形式的 hash 路由参数）
+fn uid_from_url(url: &str) -> Option<String> {
+    let params: Vec<(String, String)> = url
+        .split('?')
+        .skip(1)
+        .flat_map(|query| query.split(['&', '#']))
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let value = percent_encoding::percent_decode_str(value)
+                .decode_utf8()
+                .ok()?;
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect();
+    URL_UID_PARAMS.iter().find_map(|name| {
+        params
+            .iter()
+            .find(|(key, value)| {
+                key == name
+                    && !value.is_empty()
+                    && value.len() <= 20
+                    && value.bytes().all(|b| b.is_ascii_digit())
+            })
+            .map(|(_, value)| value.clone())
+    })
+}
+
+/// Tauri 命令：从粘贴的抽卡链接中解析 UID（用于预先选中对应账号），链接不含可识别的 UID 时返回空
+#[tauri::command]
+pub(crate) fn extract_uid_from_url(url: String) -> Result<Option<String>, AppError> {
+    let url = url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(AppError::validation("链接必须以 http:// 或 https:// 开头"));
+    }
+    Ok(uid_from_url(url))
+}
+
+// ============== 账号昵称 ==============
+//
+// 昵称保存在 accounts.roles 的角色信息里（前端按此展示）。前端以 INSERT OR REPLACE 写账号表，
+// 因此昵称的来源另记在 Rust 端的 account_nick_names 表：用户手动设置（manual）的昵称导入时不覆盖，
+// 导入文件带来的昵称（import）以最新一次导入为准。
+
+/// 昵称来源：用户手动设置
+const NICK_SOURCE_MANUAL: &str = "manual";
+/// 昵称来源：导入文件
+const NICK_SOURCE_IMPORT: &str = "import";
+
+/// 导入文件中的账号资料（前端导入成功后传入）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportedAccountProfile {
+    uid: String,
+    /// 文件中的 roles（与 accounts.roles 结构相同）
+    roles: serde_json::Value,
+}
+
+/// 一个账号的昵称变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NickNameChange {
+    uid: String,
+    old_nick_name: Option<String>,
+    new_nick_name: String,
+}
+
+/// `apply_imported_account_profiles` 的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportedProfileResult {
+    /// 按导入文件更新了昵称的账号
+    updated: Vec<NickNameChange>,
+    /// 文件中的昵称不同，但用户手动设置过昵称而保留的账号（`new_nick_name` 为文件中的昵称）
+    kept_manual: Vec<NickNameChange>,
+}
+
+fn save_nick_name(
+    conn: &Connection,
+    uid: &str,
+    nick_name: &str,
+    source: &str,
+) -> rusqlite::Result<bool> {
+    let roles: Option<String> = conn
+        .query_row("SELECT roles FROM accounts WHERE uid = ?1", [uid], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    let Some(roles) = roles.and_then(|roles| with_role_nick_name(uid, &roles, nick_name)) else {
+        return Ok(false);
+    };
+    conn.execute(
+        "UPDATE accounts SET roles = ?2 WHERE uid = ?1",
+        [uid, &roles],
+    )?;
+    conn.execute(
+        "INSERT OR REPLACE INTO account_nick_names (uid, nick_name, source, updated_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![uid, nick_name, source, Utc::now().timestamp_millis()],
+    )?;
+    Ok(true)
+}
+
+/// 按导入文件更新已有账号的昵称（文件中新增的账号由前端直接写入，不经过这里）
+fn apply_profiles(
+    conn: &Connection,
+    profiles: &[ImportedAccountProfile],
+) -> rusqlite::Result<ImportedProfileResult> {
+    let mut result = ImportedProfileResult::default();
+    for profile in profiles {
+        let uid = profile.uid.trim();
+        let Some(new_nick_name) = nick_name_from_roles(uid, &profile.roles.to_string())
+            .filter(|nick| !nick.trim().is_empty())
+        else {
+            continue;
+        };
+        let local_roles: Option<String> = conn
+            .query_row("SELECT roles FROM accounts WHERE uid = ?1", [uid], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        let Some(local_roles) = local_roles else {
+            continue;
+        };
+        let old_nick_name = nick_name_from_roles(uid, &local_roles);
+        if old_nick_name.as_deref() == Some(new_nick_name.as_str()) {
+            continue;
+        }
+        let change = NickNameChange {
+            uid: uid.to_string(),
+            old_nick_name,
+            new_nick_name,
+        };
+        let source: Option<String> = conn
+            .query_row(
+                "SELECT source FROM account_nick_names WHERE uid = ?1",
+                [uid],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if source.as_deref() == Some(NICK_SOURCE_MANUAL) {
+            result.kept_manual.push(change);
+        } else if save_nick_name(conn, uid, &change.new_nick_name, NICK_SOURCE_IMPORT)? {
+            result.updated.push(change);
+        }
+    }
+    Ok(result)
+}
+
+/// Tauri 命令：导入成功后按文件中的账号资料更新已有账号的昵称
+///
+/// 同一 uid 昵称不同时以本次导入为准；用户手动设置过昵称的账号保留原昵称，在结果中列出供前端提示。
+#[tauri::command]
+pub(crate) fn apply_imported_account_profiles(
+    app: AppHandle,
+    accounts: Vec<ImportedAccountProfile>,
+) -> Result<ImportedProfileResult, AppError> {
+    let result = with_db_write(&app, "更新账号昵称", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let result = apply_profiles(&tx, &accounts)?;
+        tx.commit()?;
+        Ok(result)
+    })?;
+    for change in &result.updated {
+        eprintln!(
+            "[accounts] 导入更新昵称 {}: {} -> {}",
+            change.uid,
+            change.old_nick_name.as_deref().unwrap_or("-"),
+            change.new_nick_name
+        );
+    }
+    if !result.updated.is_empty() {
+        refresh_tray_tooltip(&app);
+    }
+    Ok(result)
+}
+
+/// Tauri 命令：手动设置账号昵称（之后导入不会覆盖）
+#[tauri::command]
+pub(crate) fn set_account_nick_name(
+    app: AppHandle,
+    uid: String,
+    nick_name: String,
+) -> Result<(), AppError> {
+    let nick_name = nick_name.trim().to_string();
+    if nick_name.is_empty() {
+        return Err(AppError::validation("昵称不能为空"));
+    }
+    let saved = with_db_write(&app, "设置账号昵称", |conn| {
+        save_nick_name(conn, &uid, &nick_name, NICK_SOURCE_MANUAL)
+    })?;
+    if !saved {
+        return Err(AppError::validation(format!(
+            "未找到账号 {} 的角色信息",
+            uid
+        )));
+    }
+    refresh_tray_tooltip(&app);
+    Ok(())
+}
+
+/// 按 uid 保存数据的表（删除账号全部数据时逐表清理，accounts 放最后）
+const UID_TABLES: &[&str] = &[
+    "gacha_records",
+    "weapon_records",
+    "record_hash_chain",
+    "sync_history",
+    "account_nick_names",
+    "custom_endpoint_records",
+    "raw_response_records",
+    "accounts",
+];
+
+/// 删除账号全部数据的结果（各表删除的行数）
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct DeletedUidSummary {
+    uid: String,
+    gacha_records: u64,
+    weapon_records: u64,
+    record_hash_chain: u64,
+    sync_history: u64,
+    account_nick_names: u64,
+    custom_endpoint_records: u64,
+    raw_response_records: u64,
+    accounts: u64,
+}
+
+impl DeletedUidSummary {
+    fn count_mut(&mut self, table: &str) -> Option<&mut u64> {
+        match table {
+            "gacha_records" => Some(&mut self.gacha_records),
+            "weapon_records" => Some(&mut self.weapon_records),
+            "record_hash_chain" => Some(&mut self.record_hash_chain),
+            "sync_history" => Some(&mut self.sync_history),
+            "account_nick_names" => Some(&mut self.account_nick_names),
+            "custom_endpoint_records" => Some(&mut self.custom_endpoint_records),
+            "raw_response_records" => Some(&mut self.raw_response_records),
+            "accounts" => Some(&mut self.accounts),
+            _ => None,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.gacha_records
+            + self.weapon_records
+            + self.record_hash_chain
+            + self.sync_history
+            + self.account_nick_names
+            + self.custom_endpoint_records
+            + self.raw_response_records
+            + self.accounts
+    }
+}
+
+/// Tauri 命令：删除账号及其全部本地数据（抽卡记录、哈希链、同步历史），在同一事务中完成
+///
+/// 不可撤销，`confirm` 必须为 true 才会执行。删除的是当前激活账号时同时清除激活状态。
+#[tauri::command]
+pub(crate) fn delete_uid(
+    app: AppHandle,
+    uid: String,
+    confirm: bool,
+) -> Result<DeletedUidSummary, AppError> {
+    if !confirm {
+        return Err(AppError::validation("删除账号全部数据需要确认"));
+    }
+    let uid = uid.trim().to_string();
+    if uid.is_empty() {
+        return Err(AppError::validation("账号 uid 不能为空"));
+    }
+
+    // 旧版数据库可能还没有 Rust 端的表，只清理已存在的
+    let tables: Vec<&str> = {
+        let conn = open_db(&app)?;
+        let mut tables = Vec::new();
+        for table in UID_TABLES {
+            if table_exists(&conn, table)? {
+                tables.push(*table);
+            }
+        }
+        tables
+    };
+
+    let summary = with_db_write(&app, "删除账号数据", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut summary = DeletedUidSummary {
+            uid: uid.clone(),
+            ..Default::default()
+        };
+        for table in &tables {
+            let deleted = tx.execute(&format!("DELETE FROM {} WHERE uid = ?1", table), [&uid])?;
+            if let Some(count) = summary.count_mut(table) {
+                *count = deleted as u64;
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    })?;
+    eprintln!(
+        "[accounts] 已删除账号 {} 的全部数据（{} 行）",
+        uid,
+        summary.total()
+    );
+
+    if current_config(&app).active_account.as_deref() == Some(uid.as_str()) {
+        update_config(&app, |c| c.active_account = None)?;
+        let _ = app.emit(
+            "active-account-changed",
+            serde_json::json!({ "uid": Option::<String>::None }),
+        );
+    }
+    refresh_tray_tooltip(&app);
+    Ok(summary)
+}
+
+/// 按日期拆分账号的结果
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SplitResult {
+    uid: String,
+    new_uid: String,
+    /// 移到新 uid 的角色记录数
+    moved_character_records: u64,
+    /// 移到新 uid 的武器记录数
+    moved_weapon_records: u64,
+}
+
+/// 记录 id 以 uid 开头（`<uid>_char_<seq>` / `<uid>_weapon_<seq>`），换 uid 时一并替换前缀
+fn record_uid_for(record_uid: &str, uid: &str, new_uid: &str) -> String {
+    match record_uid
+        .strip_prefix(uid)
+        .filter(|rest| rest.starts_with('_'))
+    {
+        Some(rest) => format!("{}{}", new_uid, rest),
+        None => record_uid.to_string(),
+    }
+}
+
+/// Tauri 命令：把某 uid 下早于指定日期（本地时间 YYYY-MM-DD）的记录移到新 uid
+///
+/// 部分游戏会回收已注销的 uid，同一个 uid 下可能混有前后两个账号的记录；按日期拆成两段后分别统计。
+/// 开始写库前先校验日期格式与新 uid 不存在；账号信息复制一份给新 uid，两边的哈希链与保底随后重算。
+/// 待移动的记录含已归档记录时需要 `include_archived` 为真。
+#[tauri::command]
+pub(crate) fn split_uid_by_date(
+    app: AppHandle,
+    uid: String,
+    split_date: String,
+    new_uid: String,
+    include_archived: Option<bool>,
+) -> Result<SplitResult, AppError> {
+    let uid = uid.trim().to_string();
+    let new_uid = new_uid.trim().to_string();
+    if uid.is_empty() || new_uid.is_empty() {
+        return Err(AppError::validation("账号 uid 不能为空"));
+    }
+    if uid == new_uid {
+        return Err(AppError::validation("新 uid 不能与原 uid 相同"));
+    }
+    let cutoff = parse_local_date(&split_date)
+        .ok_or_else(|| AppError::validation(format!("日期格式应为 YYYY-MM-DD: {}", split_date)))?;
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT (SELECT COUNT(*) FROM accounts WHERE uid = ?1)
+                 + (SELECT COUNT(*) FROM pull_records WHERE uid = ?1)",
+        PULL_RECORDS_CTE
+    );
+    let existing: i64 = conn
+        .query_row(&sql, [&new_uid], |row| row.get(0))
+        .map_err(|e| AppError::database(format!("查询账号失败: {}", e)))?;
+    if existing > 0 {
+        return Err(AppError::validation(format!("uid {} 已存在", new_uid)));
+    }
+
+    let sql = format!(
+        "{} SELECT record_uid, time, archived FROM pull_records WHERE uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    // 记录时间格式不统一，逐条解析后比较
+    let to_move: Vec<(String, bool)> = stmt
+        .query_map([&uid], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)? != 0,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?
+        .filter_map(|row| match row {
+            Ok((record_uid, time, archived)) => parse_gacha_ts(&time)
+                .filter(|t| *t < cutoff)
+                .map(|_| Ok((record_uid, archived))),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<rusqlite::Result<_>>()
+        .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    drop(stmt);
+    if to_move.is_empty() {
+        return Err(AppError::validation(format!(
+            "{} 没有早于 {} 的记录",
+            uid,
+            split_date.trim()
+        )));
+    }
+    if !include_archived.unwrap_or(false) && to_move.iter().any(|(_, archived)| *archived) {
+        return Err(AppError::validation("待移动的记录中含有已归档记录"));
+    }
+
+    // 旧版 accounts 表可能没有 hg_uid / provider 列
+    let mut account_columns = vec!["channel_name", "roles"];
+    for column in ["hg_uid", "provider"] {
+        if table_has_column(&conn, "accounts", column)? {
+            account_columns.push(column);
+        }
+    }
+    let account_columns = account_columns.join(", ");
+    drop(conn);
+
+    let (moved_character_records, moved_weapon_records) = with_db_write(
+        &app,
+        "拆分账号记录",
+        |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                &format!(
+                    "INSERT INTO accounts (uid, added_at, {c}) SELECT ?1, ?2, {c} FROM accounts WHERE uid = ?3",
+                    c = account_columns
+                ),
+                rusqlite::params![new_uid, Utc::now().timestamp_millis(), uid],
+            )?;
+            let mut moved = [0u64; 2];
+            for (count, table) in moved.iter_mut().zip(["gacha_records", "weapon_records"]) {
+                let mut stmt = tx.prepare(&format!(
+                    "UPDATE {} SET uid = ?1, record_uid = ?2 WHERE uid = ?3 AND record_uid = ?4",
+                    table
+                ))?;
+                for (record_uid, _) in &to_move {
+                    *count += stmt.execute(rusqlite::params![
+                        new_uid,
+                        record_uid_for(record_uid, &uid, &new_uid),
+                        uid,
+                        record_uid
+                    ])? as u64;
+                }
+            }
+            // 原始响应索引跟着记录走，拆分后仍能查到留档原文
+            let mut stmt = tx.prepare(
+                "UPDATE raw_response_records SET uid = ?1, record_uid = ?2
+                  WHERE uid = ?3 AND record_uid = ?4",
+            )?;
+            for (record_uid, _) in &to_move {
+                stmt.execute(rusqlite::params![
+                    new_uid,
+                    record_uid_for(record_uid, &uid, &new_uid),
+                    uid,
+                    record_uid
+                ])?;
+            }
+            drop(stmt);
+            // 两边的记录都变了，按剩余记录重建哈希链
+            tx.execute(
+                "DELETE FROM record_hash_chain WHERE uid IN (?1, ?2)",
+                [&uid, &new_uid],
+            )?;
+            check_hash_chain(&tx, &uid, true)?;
+            check_hash_chain(&tx, &new_uid, true)?;
+            tx.commit()?;
+            Ok((moved[0], moved[1]))
+        },
+    )?;
+    eprintln!(
+        "[accounts] 按 {} 拆分 {} -> {}：角色 {} 条，武器 {} 条",
+        split_date.trim(),
+        uid,
+        new_uid,
+        moved_character_records,
+        moved_weapon_records
+    );
+
+    let conn = open_db(&app)?;
+    for account in [&uid, &new_uid] {
+        for gacha_type in GACHA_TYPES {
+            reconcile_pity_from_records(&app, &conn, account, gacha_type)?;
+        }
+    }
+    Ok(SplitResult {
+        uid,
+        new_uid,
+        moved_character_records,
+        moved_weapon_records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    #[test]
+    fn extracts_uid_from_query_and_hash_route() {
+        assert_eq!(
+            uid_from_url("https://ef-webview.hypergryph.com/page?token=abc&init_app_uid=123456"),
+            Some("123456".to_string())
+        );
+        assert_eq!(
+            uid_from_url("https://example.com/#/record?role_id=%20987%20&lang=zh-cn"),
+            Some("987".to_string())
+        );
+        // init_app_uid 优先于 uid
+        assert_eq!(
+            uid_from_url("https://example.com/?uid=1&init_app_uid=2"),
+            Some("2".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_missing_or_malformed_uid() {
+        assert_eq!(uid_from_url("https://example.com/page?token=abc"), None);
+        assert_eq!(uid_from_url("https://example.com/page?uid=abc123"), None);
+        assert_eq!(uid_from_url("https://example.com/page"), None);
+    }
+
+    #[test]
+    fn deleted_summary_covers_every_uid_table() {
+        let mut summary = DeletedUidSummary::default();
+        for table in UID_TABLES {
+            *summary.count_mut(table).expect(table) += 1;
+        }
+        assert_eq!(summary.total(), UID_TABLES.len() as u64);
+    }
+
+    #[test]
+    fn record_uid_prefix_follows_new_uid() {
+        assert_eq!(
+            record_uid_for("1:23_char_456", "1:23", "1:99"),
+            "1:99_char_456"
+        );
+        assert_eq!(
+            record_uid_for("1:234_char_456", "1:23", "1:99"),
+            "1:234_char_456"
+        );
+        assert_eq!(record_uid_for("legacy-id", "1:23", "1:99"), "legacy-id");
+    }
+
+    #[test]
+    fn imported_nick_names_do_not_override_manual_ones() {
+        let conn = test_db();
+        let accounts = [
+            ("1:11", r#"[{"roleId":"11","nickName":"旧名","level":30}]"#),
+            ("1:22", r#"[{"roleId":"22","nickName":"自定义"}]"#),
+        ];
+        for (uid, roles) in accounts {
+            insert_test_row(&conn, "accounts", &[("uid", &uid), ("roles", &roles)]);
+        }
+        save_nick_name(&conn, "1:22", "自定义", NICK_SOURCE_MANUAL).unwrap();
+
+        let profile = |uid: &str, nick: &str| ImportedAccountProfile {
+            uid: uid.to_string(),
+            roles: serde_json::json!([{ "roleId": uid.rsplit(':').next(), "nickName": nick }]),
+        };
+        let profiles = [
+            profile("1:11", "新名"),
+            profile("1:22", "文件名"),
+            profile("1:33", "不存在"),
+        ];
+        let result = apply_profiles(&conn, &profiles).unwrap();
+
+        assert_eq!(
+            result.updated,
+            vec![NickNameChange {
+                uid: "1:11".to_string(),
+                old_nick_name: Some("旧名".to_string()),
+                new_nick_name: "新名".to_string(),
+            }]
+        );
+        assert_eq!(result.kept_manual.len(), 1);
+        let roles = |uid: &str| -> String {
+            conn.query_row("SELECT roles FROM accounts WHERE uid = ?1", [uid], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(
+            nick_name_from_roles("1:11", &roles("1:11")).as_deref(),
+            Some("新名")
+        );
+        assert!(roles("1:11").contains("\"level\":30"));
+        assert_eq!(
+            nick_name_from_roles("1:22", &roles("1:22")).as_deref(),
+            Some("自定义")
+        );
+
+        // 再次导入相同昵称不算变化
+        assert!(apply_profiles(&conn, &profiles[..1])
+            .unwrap()
+            .updated
+            .is_empty());
+    }
+}
@@ -0,0 +1,627 @@
+//! 换池提醒
+//!
+//! 卡池在服务器时间 11:00 切换，旧池末尾的记录若不及时同步，可能超出接口可查询的时间范围。
+//! 按卡池排期计算即将到来的切换时间，在切换前后各提醒一次（可选自动同步）。
+//! 时间按当前账号所在服务器的时区计算；排期数据已过期时只记录日志，不按过期数据提醒。
+//! 排期更新后按切换日期重新把限定卡池的记录归入卡池期（banner_period 列），原始 pool_id 不变。
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Listener};
+
+use crate::commands::changelog::update_server_urls;
+use crate::commands::clock::{clock_correction_ms, trusted_now};
+use crate::commands::pity::reconcile_pity_from_records;
+use crate::config::{current_config, update_config, userdata_dir, write_file_atomic};
+use crate::db::access::with_db_write;
+use crate::db::{open_db, parse_gacha_ts, GACHA_TYPES, PULL_RECORDS_CTE};
+use crate::error::AppError;
+
+/// 随安装包附带的排期（离线时使用；过期后需从更新服务器获取新排期）
+const BUNDLED_BANNER_SCHEDULE: &str = include_str!("../../resources/banner_schedule.json");
+/// 远端排期与更新清单（latest.json）放在同一目录
+const BANNER_SCHEDULE_FILE_NAME: &str = "banner_schedule.json";
+const BANNER_SCHEDULE_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(8);
+/// 远端排期的刷新间隔
+const BANNER_SCHEDULE_REFRESH: std::time::Duration = std::time::Duration::from_secs(12 * 3600);
+/// 检查是否到达提醒时间的间隔
+const BANNER_WATCH_TICK: std::time::Duration = std::time::Duration::from_secs(30);
+/// 卡池切换时刻（服务器时间）
+const BANNER_FLIP_HOUR: u32 = 11;
+/// 排期未列出的服务器使用的时区（UTC+8，与卡池数据的 timezone 一致）
+const DEFAULT_SERVER_UTC_OFFSET_HOURS: i32 = 8;
+/// 每日刷新时刻（服务器时间），按日统计以此为日界而非零点
+const SERVER_DAY_RESET_HOUR: u32 = 4;
+
+/// 换池提醒设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct BannerWatchConfig {
+    enabled: bool,
+    /// 切换前多少分钟提醒（提醒同步旧池末尾的记录）
+    before_minutes: u32,
+    /// 切换后多少分钟提醒（新池已开放）
+    after_minutes: u32,
+    /// 提醒时自动同步当前账号
+    auto_sync: bool,
+}
+
+impl Default for BannerWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            before_minutes: 60,
+            after_minutes: 30,
+            auto_sync: false,
+        }
+    }
+}
+
+/// 卡池排期
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct BannerSchedule {
+    /// 排期可信的最后日期（服务器时间，YYYY-MM-DD），之后的切换时间未知
+    valid_until: Option<String>,
+    /// 卡池切换日期（服务器时间，YYYY-MM-DD，当天 11:00 切换）
+    transitions: Vec<String>,
+    /// 服务器（账号 key 中的 serverId，国际服带 `gryphline@` 前缀）→ UTC 偏移小时数
+    server_utc_offsets: HashMap<String, i32>,
+}
+
+/// 提醒事件（随 `banner-transition` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BannerTransitionEvent {
+    /// before：即将换池；after：已换池
+    phase: &'static str,
+    /// 切换时间（毫秒时间戳）
+    transition_at: i64,
+    auto_sync: bool,
+}
+
+/// 即将到来的卡池切换（设置页展示用）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BannerWatchStatus {
+    settings: BannerWatchConfig,
+    /// 下一次切换时间（毫秒时间戳），排期过期或没有后续切换时为空
+    next_transition_at: Option<i64>,
+    /// 排期数据已过期
+    stale: bool,
+}
+
+fn banner_schedule_cache_file() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join(BANNER_SCHEDULE_FILE_NAME))
+}
+
+fn load_banner_schedule() -> BannerSchedule {
+    banner_schedule_cache_file()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_else(|| serde_json::from_str(BUNDLED_BANNER_SCHEDULE).unwrap_or_default())
+}
+
+/// 依次尝试各个地址下载排期，成功后写入本地缓存；返回排期内容是否有变化
+async fn fetch_remote_banner_schedule(urls: &[String]) -> bool {
+    let Ok(client) = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(BANNER_SCHEDULE_FETCH_TIMEOUT)
+        .build()
+    else {
+        return false;
+    };
+    let cached = banner_schedule_cache_file()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    for url in urls {
+        let result = async {
+            let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP {}", response.status()));
+            }
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            serde_json::from_str::<BannerSchedule>(&text).map_err(|e| e.to_string())?;
+            if cached.as_deref() == Some(text.as_str()) {
+                return Ok(false);
+            }
+            banner_schedule_cache_file()
+                .and_then(|path| write_file_atomic(&path, text.as_bytes()))
+                .map(|()| true)
+        }
+        .await;
+        match result {
+            Ok(changed) => return changed,
+            Err(e) => eprintln!("[banner] 下载卡池排期失败 {}: {}", url, e),
+        }
+    }
+    false
+}
+
+/// 账号 key（`serverId:roleId` 或 `gryphline@serverId:roleId`）对应的服务器时区
+fn server_offset(schedule: &BannerSchedule, account: Option<&str>) -> FixedOffset {
+    let hours = account
+        .and_then(|account| account.rsplit_once(':'))
+        .and_then(|(server, _)| schedule.server_utc_offsets.get(server).copied())
+        .unwrap_or(DEFAULT_SERVER_UTC_OFFSET_HOURS);
+    FixedOffset::east_opt(hours * 3600).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
+/// 时间所属的服务器日（刷新时刻之前仍算前一天）
+fn server_day_at(offset: FixedOffset, time: DateTime<Utc>) -> NaiveDate {
+    (time.with_timezone(&offset) - Duration::hours(SERVER_DAY_RESET_HOUR.into())).date_naive()
+}
+
+/// 服务器日的起点（UTC）
+fn server_day_start_at(offset: FixedOffset, day: NaiveDate) -> DateTime<Utc> {
+    offset
+        .from_local_datetime(&day.and_hms_opt(SERVER_DAY_RESET_HOUR, 0, 0).unwrap())
+        .single()
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| day.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+/// 账号（`serverId:roleId`）所在服务器当天（服务器日）的起点（UTC）
+///
+/// 按日统计都应使用这里的日界，各处的"某天"才能对得上。
+pub(crate) fn server_day_start(account: &str, now: DateTime<Utc>) -> DateTime<Utc> {
+    let offset = server_offset(&load_banner_schedule(), Some(account));
+    server_day_start_at(offset, server_day_at(offset, now))
+}
+
+fn parse_schedule_date(raw: &str) -> Option<NaiveDate> {
+    let date = NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").ok();
+    if date.is_none() {
+        eprintln!("[banner] 无法解析排期日期: {}", raw);
+    }
+    date
+}
+
+/// 排期中尚可信的切换时间（UTC，升序）；排期已过期时返回 None
+fn upcoming_transitions(
+    schedule: &BannerSchedule,
+    offset: FixedOffset,
+    now: DateTime<Utc>,
+) -> Option<Vec<DateTime<Utc>>> {
+    let valid_until = parse_schedule_date(schedule.valid_until.as_deref()?)?;
+    if now.with_timezone(&offset).date_naive() > valid_until {
+        return None;
+    }
+    let mut transitions: Vec<DateTime<Utc>> = schedule
+        .transitions
+        .iter()
+        .filter_map(|date| parse_schedule_date(date))
+        .filter(|date| *date <= valid_until)
+        .filter_map(|date| {
+            offset
+                .from_local_datetime(&date.and_hms_opt(BANNER_FLIP_HOUR, 0, 0)?)
+                .single()
+        })
+        .map(|time| time.with_timezone(&Utc))
+        .collect();
+    transitions.sort();
+    Some(transitions)
+}
+
+/// 落在 (since, now] 内的提醒
+fn due_events(
+    transitions: &[DateTime<Utc>],
+    settings: &BannerWatchConfig,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<BannerTransitionEvent> {
+    let mut events = Vec::new();
+    for transition in transitions {
+        let phases = [
+            (
+                "before",
+                *transition - Duration::minutes(settings.before_minutes.into()),
+            ),
+            (
+                "after",
+                *transition + Duration::minutes(settings.after_minutes.into()),
+            ),
+        ];
+        for (phase, at) in phases {
+            if since < at && at <= now {
+                events.push(BannerTransitionEvent {
+                    phase,
+                    transition_at: transition.timestamp_millis(),
+                    auto_sync: settings.auto_sync,
+                });
+            }
+        }
+    }
+    events
+}
+
+/// 启动换池提醒线程（启动时调用一次；设置在每次检查时重新读取）
+///
+/// 切换账号可能换了服务器时区：收到 `active-account-changed` 后从当前时刻重新计算，
+/// 避免按新时区把旧区间内的切换点补发一遍。
+pub(crate) fn start_banner_watch(app: &AppHandle) {
+    let app = app.clone();
+    let account_changed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let flag = account_changed.clone();
+    app.listen("active-account-changed", move |_| {
+        flag.store(true, std::sync::atomic::Ordering::SeqCst);
+    });
+    std::thread::spawn(move || {
+        let urls = update_server_urls(&app, BANNER_SCHEDULE_FILE_NAME);
+        let mut last_fetch: Option<std::time::Instant> = None;
+        let mut last_tick = Utc::now();
+        let mut last_correction = 0;
+        let mut stale_logged = false;
+        loop {
+            if last_fetch.is_none_or(|at| at.elapsed() >= BANNER_SCHEDULE_REFRESH) {
+                if tauri::async_runtime::block_on(fetch_remote_banner_schedule(&urls)) {
+                    match reclassify_banner_periods(&app, false) {
+                        Ok(report) => eprintln!(
+                            "[banner] 排期已更新，重新归类 {} 条记录，{} 个卡池与排期不符",
+                            report.changed,
+                            report.flagged.len()
+                        ),
+                        Err(e) => eprintln!("[banner] 按新排期归类记录失败: {}", e),
+                    }
+                }
+                last_fetch = Some(std::time::Instant::now());
+            }
+
+            // 时钟偏差修正生效时时间会整体跳变，与切换账号一样从当前时刻重新计算
+            let correction = clock_correction_ms(&app);
+            let now = Utc::now() + Duration::milliseconds(correction);
+            if account_changed.swap(false, std::sync::atomic::Ordering::SeqCst)
+                || correction != last_correction
+            {
+                last_tick = now;
+                last_correction = correction;
+            }
+            let config = current_config(&app);
+            if config.banner_watch.enabled {
+                let schedule = load_banner_schedule();
+                let offset = server_offset(&schedule, config.active_account.as_deref());
+                match upcoming_transitions(&schedule, offset, now) {
+                    Some(transitions) => {
+                        stale_logged = false;
+                        for event in due_events(&transitions, &config.banner_watch, last_tick, now)
+                        {
+                            eprintln!("[banner] 换池提醒: {:?}", event);
+                            let _ = app.emit("banner-transition", event);
+                        }
+                    }
+                    None if !stale_logged => {
+                        eprintln!("[banner] 卡池排期已过期或缺失，暂停换池提醒");
+                        stale_logged = true;
+                    }
+                    None => {}
+                }
+            }
+            last_tick = now;
+            std::thread::sleep(BANNER_WATCH_TICK);
+        }
+    });
+}
+
+/// Tauri 命令：获取换池提醒设置与下一次切换时间
+#[tauri::command]
+pub(crate) fn get_banner_watch_status(app: AppHandle) -> BannerWatchStatus {
+    let config = current_config(&app);
+    let schedule = load_banner_schedule();
+    let offset = server_offset(&schedule, config.active_account.as_deref());
+    let now = trusted_now(&app);
+    let transitions = upcoming_transitions(&schedule, offset, now);
+    BannerWatchStatus {
+        settings: config.banner_watch,
+        next_transition_at: transitions
+            .as_ref()
+            .and_then(|transitions| transitions.iter().find(|time| **time > now))
+            .map(DateTime::timestamp_millis),
+        stale: transitions.is_none(),
+    }
+}
+
+/// Tauri 命令：保存换池提醒设置
+#[tauri::command]
+pub(crate) fn set_banner_watch_settings(
+    app: AppHandle,
+    settings: BannerWatchConfig,
+) -> Result<(), AppError> {
+    if settings.before_minutes > 24 * 60 || settings.after_minutes > 24 * 60 {
+        return Err(AppError::validation("提醒时间须在切换前后 24 小时内"));
+    }
+    update_config(&app, |config| config.banner_watch = settings)?;
+    Ok(())
+}
+
+/// 与排期不符的卡池（同一卡池的记录落在多个卡池期，不做改动）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FlaggedPool {
+    uid: String,
+    pool_id: String,
+    /// 按排期落入的各卡池期
+    periods: Vec<String>,
+    records: u64,
+}
+
+/// 按排期重新归类卡池期的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BannerPeriodReport {
+    dry_run: bool,
+    /// 参与归类的限定卡池记录数
+    checked: u64,
+    /// 卡池期发生变化（预览时为将要变化）的记录数
+    changed: u64,
+    flagged: Vec<FlaggedPool>,
+}
+
+/// 限定卡池才按排期分期；常驻角色池、新手池与常驻武库跨越所有卡池期
+fn is_limited_pool(gacha_type: &str, pool_id: &str) -> bool {
+    match gacha_type {
+        "special" => true,
+        "weapon" => !pool_id.contains("constant"),
+        _ => false,
+    }
+}
+
+/// 排期中全部切换时间（UTC，升序）及对应日期；已经发生的切换不受排期有效期影响
+fn period_starts(schedule: &BannerSchedule, offset: FixedOffset) -> Vec<(DateTime<Utc>, String)> {
+    let mut starts: Vec<(DateTime<Utc>, String)> = schedule
+        .transitions
+        .iter()
+        .filter_map(|raw| {
+            let date = parse_schedule_date(raw)?;
+            let time = offset
+                .from_local_datetime(&date.and_hms_opt(BANNER_FLIP_HOUR, 0, 0)?)
+                .single()?;
+            Some((
+                time.with_timezone(&Utc),
+                date.format("%Y-%m-%d").to_string(),
+            ))
+        })
+        .collect();
+    starts.sort();
+    starts
+}
+
+/// 某时间所在的卡池期（最近一次切换的日期），早于排期时返回空
+fn period_at(starts: &[(DateTime<Utc>, String)], time: DateTime<Utc>) -> Option<&str> {
+    starts
+        .iter()
+        .rev()
+        .find(|(start, _)| *start <= time)
+        .map(|(_, date)| date.as_str())
+}
+
+/// 按当前排期重新归类限定卡池记录的卡池期
+///
+/// 同一账号同一 pool_id 的记录应落在同一期；落在多期说明排期与原始卡池编码不一致，
+/// 这些记录保持原样并在结果中列出。实际写入后重新计算受影响卡池的保底。
+fn reclassify_banner_periods(
+    app: &AppHandle,
+    dry_run: bool,
+) -> Result<BannerPeriodReport, AppError> {
+    let schedule = load_banner_schedule();
+    let conn = open_db(app)?;
+    let sql = format!(
+        "{} SELECT record_uid, uid, pool_id, gacha_type, time, banner_period FROM pull_records",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+
+    // (uid, pool_id) → 各记录（record_uid, gacha_type, 现有卡池期, 按排期的卡池期）
+    type Assignment = (String, String, Option<String>, Option<String>);
+    let mut pools: HashMap<(String, String), Vec<Assignment>> = HashMap::new();
+    let mut starts_by_uid: HashMap<String, Vec<(DateTime<Utc>, String)>> = HashMap::new();
+    for row in rows {
+        let (record_uid, uid, pool_id, gacha_type, time, current) =
+            row.map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+        if !is_limited_pool(&gacha_type, &pool_id) {
+            continue;
+        }
+        let starts = starts_by_uid
+            .entry(uid.clone())
+            .or_insert_with(|| period_starts(&schedule, server_offset(&schedule, Some(&uid))));
+        let period = parse_gacha_ts(&time)
+            .and_then(|time| period_at(starts, time))
+            .map(str::to_string);
+        pools
+            .entry((uid, pool_id))
+            .or_default()
+            .push((record_uid, gacha_type, current, period));
+    }
+    drop(stmt);
+
+    let mut checked = 0;
+    let mut changes: Vec<(String, Option<String>)> = Vec::new();
+    let mut affected: HashSet<(String, String)> = HashSet::new();
+    let mut flagged = Vec::new();
+    for ((uid, pool_id), records) in pools {
+        checked += records.len() as u64;
+        let periods: BTreeSet<&String> = records.iter().filter_map(|r| r.3.as_ref()).collect();
+        if periods.len() > 1 {
+            flagged.push(FlaggedPool {
+                periods: periods.into_iter().cloned().collect(),
+                records: records.len() as u64,
+                uid,
+                pool_id,
+            });
+            continue;
+        }
+        let target = periods.into_iter().next().cloned();
+        for (record_uid, gacha_type, current, _) in records {
+            if current != target {
+                affected.insert((uid.clone(), gacha_type));
+                changes.push((record_uid, target.clone()));
+            }
+        }
+    }
+    flagged.sort_by(|a, b| (&a.uid, &a.pool_id).cmp(&(&b.uid, &b.pool_id)));
+
+    if !dry_run && !changes.is_empty() {
+        with_db_write(app, "按排期归类卡池期", |conn| {
+            let tx = conn.unchecked_transaction()?;
+            for table in ["gacha_records", "weapon_records"] {
+                let mut stmt = tx.prepare(&format!(
+                    "UPDATE {} SET banner_period = ?1 WHERE record_uid = ?2",
+                    table
+                ))?;
+                for (record_uid, period) in &changes {
+                    stmt.execute(rusqlite::params![period, record_uid])?;
+                }
+            }
+            tx.commit()
+        })?;
+        for (uid, gacha_type) in &affected {
+            if GACHA_TYPES.contains(&gacha_type.as_str()) {
+                reconcile_pity_from_records(app, &conn, uid, gacha_type)?;
+            }
+        }
+    }
+    Ok(BannerPeriodReport {
+        dry_run,
+        checked,
+        changed: changes.len() as u64,
+        flagged,
+    })
+}
+
+/// Tauri 命令：按当前卡池排期重新归类记录的卡池期；`dry_run` 为真时只预览影响，不写入
+#[tauri::command]
+pub(crate) fn reclassify_records(
+    app: AppHandle,
+    dry_run: bool,
+) -> Result<BannerPeriodReport, AppError> {
+    let report = reclassify_banner_periods(&app, dry_run)?;
+    if !dry_run {
+        eprintln!(
+            "[banner] 按排期归类 {} 条记录，{} 个卡池与排期不符",
+            report.changed,
+            report.flagged.len()
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> BannerSchedule {
+        BannerSchedule {
+            valid_until: Some("2026-05-01".to_string()),
+            transitions: vec!["2026-04-02".to_string(), "2026-06-01".to_string()],
+            server_utc_offsets: HashMap::from([("gryphline@3".to_string(), -5)]),
+        }
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn transitions_use_server_timezone_and_skip_unverified_dates() {
+        let schedule = schedule();
+        let now = utc("2026-04-01T00:00:00Z");
+
+        let cn = upcoming_transitions(&schedule, server_offset(&schedule, Some("1:123")), now);
+        assert_eq!(cn.unwrap(), vec![utc("2026-04-02T03:00:00Z")]);
+
+        let offset = server_offset(&schedule, Some("gryphline@3:456"));
+        let global = upcoming_transitions(&schedule, offset, now);
+        assert_eq!(global.unwrap(), vec![utc("2026-04-02T16:00:00Z")]);
+    }
+
+    #[test]
+    fn server_day_turns_over_at_reset_hour_in_server_time() {
+        let schedule = schedule();
+        let cn = server_offset(&schedule, Some("1:123"));
+        // UTC+8 的 04:00 为 UTC 20:00
+        let before = utc("2026-04-01T19:59:00Z");
+        let after = utc("2026-04-01T20:00:00Z");
+        assert_eq!(server_day_at(cn, before).to_string(), "2026-04-01");
+        assert_eq!(server_day_at(cn, after).to_string(), "2026-04-02");
+        assert_eq!(server_day_start_at(cn, server_day_at(cn, after)), after);
+
+        let global = server_offset(&schedule, Some("gryphline@3:456"));
+        assert_eq!(server_day_at(global, after).to_string(), "2026-04-01");
+        assert_eq!(
+            server_day_start_at(global, server_day_at(global, after)),
+            utc("2026-04-01T09:00:00Z")
+        );
+    }
+
+    #[test]
+    fn stale_schedule_yields_no_transitions() {
+        let schedule = schedule();
+        let offset = server_offset(&schedule, None);
+        assert!(upcoming_transitions(&schedule, offset, utc("2026-05-02T00:00:00Z")).is_none());
+        assert!(upcoming_transitions(&BannerSchedule::default(), offset, Utc::now()).is_none());
+    }
+
+    #[test]
+    fn events_fire_once_inside_tick_window() {
+        let transitions = [utc("2026-04-02T03:00:00Z")];
+        let settings = BannerWatchConfig::default();
+        let before = due_events(
+            &transitions,
+            &settings,
+            utc("2026-04-02T01:59:50Z"),
+            utc("2026-04-02T02:00:20Z"),
+        );
+        assert_eq!(before.len(), 1);
+        assert_eq!(before[0].phase, "before");
+        let none = due_events(
+            &transitions,
+            &settings,
+            utc("2026-04-02T02:00:20Z"),
+            utc("2026-04-02T02:00:50Z"),
+        );
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn records_fall_into_latest_transition_before_them() {
+        let schedule = schedule();
+        let starts = period_starts(&schedule, server_offset(&schedule, Some("1:123")));
+        assert_eq!(period_at(&starts, utc("2026-04-02T02:59:00Z")), None);
+        assert_eq!(
+            period_at(&starts, utc("2026-04-02T03:00:00Z")),
+            Some("2026-04-02")
+        );
+        // 超出有效期的切换日期同样用于归类
+        assert_eq!(
+            period_at(&starts, utc("2026-06-01T02:59:00Z")),
+            Some("2026-04-02")
+        );
+        assert_eq!(
+            period_at(&starts, utc("2026-06-01T03:00:00Z")),
+            Some("2026-06-01")
+        );
+    }
+
+    #[test]
+    fn only_limited_pools_are_split_into_periods() {
+        assert!(is_limited_pool("special", "special_1_0_1"));
+        assert!(is_limited_pool("weapon", "weponbox_1_0_1"));
+        assert!(!is_limited_pool("weapon", "weaponbox_constant_1"));
+        assert!(!is_limited_pool("standard", "standard_1"));
+    }
+}
@@ -0,0 +1,182 @@
+//! 更新日志
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::config::{current_config, update_config, userdata_dir, write_file_atomic};
+use crate::error::AppError;
+
+/// 随安装包附带的更新日志（离线时至少能展示当前版本的说明）
+const BUNDLED_CHANGELOG: &str = include_str!("../../resources/changelog.json");
+/// 远端更新日志与更新清单（latest.json）放在同一目录
+const CHANGELOG_FILE_NAME: &str = "changelog.json";
+const CHANGELOG_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(8);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChangelogEntry {
+    version: String,
+    #[serde(default)]
+    date: Option<String>,
+    /// Markdown 格式的更新说明
+    notes: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ChangelogFile {
+    versions: Vec<ChangelogEntry>,
+}
+
+/// `get_changelog` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Changelog {
+    current_version: String,
+    /// 晚于 since_version、不晚于当前版本的条目（新版本在前）
+    entries: Vec<ChangelogEntry>,
+    /// 数据来源：remote / cache / bundled
+    source: String,
+    /// 当前版本的说明尚未被确认过，前端应弹出"新版本说明"
+    should_show: bool,
+}
+
+/// 把 "v1.2.3-beta" 之类的版本号解析成可比较的数字序列（非数字部分忽略）
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse::<u64>().ok())
+        .collect()
+}
+
+fn changelog_cache_file() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join(CHANGELOG_FILE_NAME))
+}
+
+/// 由更新器的接口列表推导与更新清单同目录的文件地址（更新器的镜像顺序即尝试顺序）
+pub(crate) fn update_server_urls(app: &AppHandle, file_name: &str) -> Vec<String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("endpoints"))
+        .and_then(|endpoints| endpoints.as_array())
+        .map(|endpoints| {
+            endpoints
+                .iter()
+                .filter_map(|endpoint| endpoint.as_str())
+                .filter_map(|endpoint| {
+                    endpoint
+                        .rsplit_once('/')
+                        .map(|(base, _)| format!("{}/{}", base, file_name))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 依次尝试各个地址下载更新日志，成功后写入本地缓存
+async fn fetch_remote_changelog(urls: &[String]) -> Option<ChangelogFile> {
+    let client = tauri_plugin_http::reqwest::Client::builder()
+        .timeout(CHANGELOG_FETCH_TIMEOUT)
+        .build()
+        .ok()?;
+    for url in urls {
+        let result = async {
+            let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP {}", response.status()));
+            }
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            serde_json::from_str::<ChangelogFile>(&text)
+                .map(|file| (file, text))
+                .map_err(|e| e.to_string())
+        }
+        .await;
+        match result {
+            Ok((file, text)) => {
+                if let Err(e) = changelog_cache_file()
+                    .and_then(|path| write_file_atomic(&path, text.as_bytes()))
+                {
+                    eprintln!("[changelog] 缓存更新日志失败: {}", e);
+                }
+                return Some(file);
+            }
+            Err(e) => eprintln!("[changelog] 下载更新日志失败 {}: {}", url, e),
+        }
+    }
+    None
+}
+
+fn load_cached_changelog() -> Option<ChangelogFile> {
+    let text = std::fs::read_to_string(changelog_cache_file().ok()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn bundled_changelog() -> ChangelogFile {
+    serde_json::from_str(BUNDLED_CHANGELOG).unwrap_or_default()
+}
+
+/// Tauri 命令：获取更新日志
+///
+/// 优先下载远端日志（失败时使用上次的缓存），远端/缓存都没有当前版本时补上安装包附带的说明。
+/// `since_version` 为空时以用户上次确认过的版本为起点。
+#[tauri::command]
+pub(crate) async fn get_changelog(
+    app: AppHandle,
+    since_version: Option<String>,
+) -> Result<Changelog, AppError> {
+    let current_version = app.package_info().version.to_string();
+    let acknowledged = current_config(&app).changelog_acknowledged;
+
+    let (mut file, source) =
+        match fetch_remote_changelog(&update_server_urls(&app, CHANGELOG_FILE_NAME)).await {
+            Some(file) => (file, "remote"),
+            None => match load_cached_changelog() {
+                Some(file) => (file, "cache"),
+                None => (ChangelogFile::default(), "bundled"),
+            },
+        };
+    let current_key = version_key(&current_version);
+    if !file
+        .versions
+        .iter()
+        .any(|entry| version_key(&entry.version) == current_key)
+    {
+        file.versions.extend(
+            bundled_changelog()
+                .versions
+                .into_iter()
+                .filter(|entry| version_key(&entry.version) == current_key),
+        );
+    }
+
+    let since_key = since_version
+        .as_deref()
+        .or(acknowledged.as_deref())
+        .map(version_key);
+    let mut entries: Vec<ChangelogEntry> = file
+        .versions
+        .into_iter()
+        .filter(|entry| {
+            let key = version_key(&entry.version);
+            key <= current_key && since_key.as_ref().is_none_or(|since| key > *since)
+        })
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(version_key(&entry.version)));
+
+    Ok(Changelog {
+        should_show: acknowledged.as_deref().map(version_key) != Some(current_key),
+        current_version,
+        entries,
+        source: source.to_string(),
+    })
+}
+
+/// Tauri 命令：确认已看过某版本的更新说明（之后不再弹出）
+#[tauri::command]
+pub(crate) fn acknowledge_changelog(app: AppHandle, version: String) -> Result<(), AppError> {
+    update_config(&app, |c| c.changelog_acknowledged = Some(version))?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! 系统时间校验
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_state;
+use crate::db::access::with_db_write;
+use crate::error::AppError;
+
+/// 本地时间与服务器时间相差超过该阈值时视为时钟异常
+const CLOCK_SKEW_THRESHOLD_MS: i64 = 5 * 60 * 1000;
+
+/// 时钟偏差状态（以首个 API 响应的 HTTP Date 头为准）
+pub(crate) struct ClockSkewState {
+    /// 服务器时间 - 本地时间（毫秒），尚未测量时为 None
+    offset_ms: Mutex<Option<i64>>,
+    /// 本次会话开始时的本地时间（毫秒），用于圈定受影响的记录
+    session_started_at_ms: i64,
+}
+
+impl Default for ClockSkewState {
+    fn default() -> Self {
+        Self {
+            offset_ms: Mutex::new(None),
+            session_started_at_ms: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+impl ClockSkewState {
+    /// 仅在偏差超过阈值时返回偏差值，正常误差不做修正
+    fn skew_ms(&self) -> Option<i64> {
+        let offset = (*self.offset_ms.lock().unwrap())?;
+        (offset.abs() > CLOCK_SKEW_THRESHOLD_MS).then_some(offset)
+    }
+}
+
+/// 时钟状态（返回给前端）
+#[derive(Clone, Serialize)]
+pub(crate) struct ClockStatus {
+    offset_ms: Option<i64>,
+    skewed: bool,
+    trusted_now_ms: i64,
+}
+
+/// 本地时间需要加上的修正量（毫秒）：时钟正常或尚未测量时为 0
+pub(crate) fn clock_correction_ms(app: &AppHandle) -> i64 {
+    app_state(app).clock.skew_ms().unwrap_or(0)
+}
+
+/// 获取可信的当前时间：检测到时钟异常时使用服务器推算时间
+pub(crate) fn trusted_now(app: &AppHandle) -> DateTime<Utc> {
+    Utc::now() + chrono::Duration::milliseconds(clock_correction_ms(app))
+}
+
+fn clock_status(app: &AppHandle) -> ClockStatus {
+    let state = &app_state(app).clock;
+    let offset_ms = *state.offset_ms.lock().unwrap();
+    ClockStatus {
+        offset_ms,
+        skewed: state.skew_ms().is_some(),
+        trusted_now_ms: trusted_now(app).timestamp_millis(),
+    }
+}
+
+/// Tauri 命令：上报 API 响应的 HTTP Date 头，用于检测本地时钟偏差
+///
+/// 只取本次会话的第一个响应；偏差超过阈值时发送 `clock-skew-detected` 事件。
+#[tauri::command]
+pub(crate) fn report_server_date(app: AppHandle, date: String) -> Result<ClockStatus, AppError> {
+    let server_time = DateTime::parse_from_rfc2822(date.trim())
+        .map_err(|e| format!("无法解析服务器时间: {}", e))?
+        .with_timezone(&Utc);
+
+    {
+        let state = &app_state(&app).clock;
+        let mut offset = state.offset_ms.lock().unwrap();
+        if offset.is_some() {
+            drop(offset);
+            return Ok(clock_status(&app));
+        }
+        let local_time = Utc::now();
+        let measured = server_time.signed_duration_since(local_time).num_milliseconds();
+        *offset = Some(measured);
+
+        if measured.abs() > CLOCK_SKEW_THRESHOLD_MS {
+            eprintln!("[clock] 检测到系统时间偏差 {} ms", measured);
+            let _ = app.emit(
+                "clock-skew-detected",
+                serde_json::json!({
+                    "offsetMs": measured,
+                    "serverTime": server_time.to_rfc3339(),
+                    "localTime": local_time.to_rfc3339(),
+                }),
+            );
+        }
+    }
+
+    Ok(clock_status(&app))
+}
+
+/// Tauri 命令：获取当前时钟状态（含服务器推算的可信时间，供过期判断与同步日志使用）
+#[tauri::command]
+pub(crate) fn get_clock_status(app: AppHandle) -> ClockStatus {
+    clock_status(&app)
+}
+
+/// Tauri 命令：为本次会话中入库的记录标注时钟偏差
+///
+/// 时钟异常时 fetched_at 等本地写入时间不可信，写入 clock_skew_ms 供后续分析排除。
+/// 返回本次标注的记录数；时钟正常时不做任何修改。
+#[tauri::command]
+pub(crate) fn annotate_skewed_records(app: AppHandle) -> Result<usize, AppError> {
+    let state = &app_state(&app).clock;
+    let Some(offset) = state.skew_ms() else {
+        return Ok(0);
+    };
+
+    with_db_write(&app, "标注时钟偏差记录", |conn| {
+        let mut annotated = 0;
+        for table in ["gacha_records", "weapon_records"] {
+            annotated += conn.execute(
+                &format!(
+                    "UPDATE {} SET clock_skew_ms = ?1 WHERE fetched_at >= ?2 AND clock_skew_ms IS NULL",
+                    table
+                ),
+                rusqlite::params![offset, state.session_started_at_ms],
+            )?;
+        }
+        Ok(annotated)
+    })
+}
@@ -0,0 +1,284 @@
+//! 欧气对比（分享码）
+//!
+//! 分享码只包含统计摘要：各卡池总抽数、每次出最高稀有度时的抽数位置以及欧气百分位，
+//! 不含 uid、物品名、时间等可识别信息。格式为
+//!   "EFGC" + base64url( 版本号(1 字节) | sha256 前 4 字节 | deflate(json) )
+//! 校验和覆盖压缩后的数据，解析时先校验再解压，避免坏数据显示成错乱的图表。
+
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::pity::PityWalker;
+use crate::config::current_config;
+use crate::db::{
+    open_db, query_pulls_by_time, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY, TOP_RARITY_BASE_RATE,
+    TOP_RARITY_HARD_PITY, TOP_RARITY_SOFT_PITY, TOP_RARITY_SOFT_PITY_STEP,
+};
+use crate::error::AppError;
+
+/// 分享码前缀
+const COMPARE_PAYLOAD_PREFIX: &str = "EFGC";
+/// 分享码格式版本
+const COMPARE_PAYLOAD_VERSION: u8 = 1;
+/// 分享码解压后的最大字节数（防止恶意构造的压缩炸弹）
+const COMPARE_PAYLOAD_MAX_BYTES: u64 = 256 * 1024;
+
+/// 单个卡池的统计摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ComparePool {
+    gacha_type: String,
+    total_pulls: u64,
+    /// 每次出最高稀有度时距上一次的抽数（免费抽不计入）
+    five_star_positions: Vec<u32>,
+}
+
+/// 分享码内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ComparePayload {
+    version: u8,
+    created_at: i64,
+    total_pulls: u64,
+    /// 欧气百分位（0~100，越高越欧，50 为平均水平）
+    luck_percentile: Option<f64>,
+    pools: Vec<ComparePool>,
+}
+
+/// 按保底模型计算第 n 抽出最高稀有度的条件概率
+fn top_rarity_rate(pull: u32) -> f64 {
+    if pull >= TOP_RARITY_HARD_PITY {
+        1.0
+    } else if pull > TOP_RARITY_SOFT_PITY {
+        (TOP_RARITY_BASE_RATE + TOP_RARITY_SOFT_PITY_STEP * f64::from(pull - TOP_RARITY_SOFT_PITY))
+            .min(1.0)
+    } else {
+        TOP_RARITY_BASE_RATE
+    }
+}
+
+/// 随机玩家需要至少 `position` 抽才出最高稀有度的概率（越大说明这次出得越早、越欧）
+fn top_rarity_survival(position: u32) -> f64 {
+    (1..position).fold(1.0, |survival, pull| {
+        survival * (1.0 - top_rarity_rate(pull))
+    })
+}
+
+/// 计算欧气百分位：对每次出金位置的生存概率取平均（随机玩家期望为 50）
+///
+/// 武器池保底规则不同，只统计角色卡池；没有出过最高稀有度时返回 None。
+fn luck_percentile(pools: &[ComparePool]) -> Option<f64> {
+    let positions: Vec<u32> = pools
+        .iter()
+        .filter(|pool| pool.gacha_type != "weapon")
+        .flat_map(|pool| pool.five_star_positions.iter().copied())
+        .collect();
+    if positions.is_empty() {
+        return None;
+    }
+    let sum: f64 = positions.iter().map(|&p| top_rarity_survival(p)).sum();
+    Some((sum / positions.len() as f64 * 1000.0).round() / 10.0)
+}
+
+/// 汇总某账号各卡池的出金位置分布
+fn compare_pools(conn: &Connection, uid: &str) -> Result<Vec<ComparePool>, AppError> {
+    let sql = format!(
+        "{} SELECT gacha_type, rarity, is_free, time, gacha_id FROM pull_records
+           WHERE uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let rows = query_pulls_by_time(&mut stmt, [uid], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })
+    .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+
+    let mut pools: Vec<(ComparePool, PityWalker)> = GACHA_TYPES
+        .iter()
+        .map(|gacha_type| {
+            let pool = ComparePool {
+                gacha_type: gacha_type.to_string(),
+                total_pulls: 0,
+                five_star_positions: Vec::new(),
+            };
+            (pool, PityWalker::new(TOP_RARITY))
+        })
+        .collect();
+    for (gacha_type, rarity, is_free) in rows {
+        let Some((pool, walker)) = pools
+            .iter_mut()
+            .find(|(pool, _)| pool.gacha_type == gacha_type)
+        else {
+            continue;
+        };
+        pool.total_pulls += 1;
+        if let Some(pity) = walker.step(rarity, is_free != 0) {
+            if rarity >= TOP_RARITY {
+                pool.five_star_positions.push(pity);
+            }
+        }
+    }
+    Ok(pools
+        .into_iter()
+        .map(|(pool, _)| pool)
+        .filter(|pool| pool.total_pulls > 0)
+        .collect())
+}
+
+fn compare_checksum(data: &[u8]) -> [u8; 4] {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(data);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// 把统计摘要编码为分享码
+fn encode_compare_payload(payload: &ComparePayload) -> Result<String, String> {
+    use base64::Engine;
+    use flate2::{write::DeflateEncoder, Compression};
+    use std::io::Write;
+
+    let json = serde_json::to_vec(payload).map_err(|e| format!("序列化对比数据失败: {}", e))?;
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&json)
+        .map_err(|e| format!("压缩对比数据失败: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("压缩对比数据失败: {}", e))?;
+
+    let mut bytes = Vec::with_capacity(compressed.len() + 5);
+    bytes.push(COMPARE_PAYLOAD_VERSION);
+    bytes.extend_from_slice(&compare_checksum(&compressed));
+    bytes.extend_from_slice(&compressed);
+    Ok(format!(
+        "{}{}",
+        COMPARE_PAYLOAD_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    ))
+}
+
+/// 解析分享码；格式、版本或校验和不对时返回错误
+fn decode_compare_payload(text: &str) -> Result<ComparePayload, String> {
+    use base64::Engine;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    let body: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let body = body
+        .strip_prefix(COMPARE_PAYLOAD_PREFIX)
+        .ok_or("不是有效的对比分享码")?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(body)
+        .map_err(|_| "分享码内容已损坏（无法解码）".to_string())?;
+    if bytes.len() < 5 {
+        return Err("分享码内容不完整".to_string());
+    }
+    let (header, compressed) = bytes.split_at(5);
+    if header[0] != COMPARE_PAYLOAD_VERSION {
+        return Err(format!(
+            "不支持的分享码版本: {}（当前版本 {}），请双方升级到同一版本",
+            header[0], COMPARE_PAYLOAD_VERSION
+        ));
+    }
+    if header[1..] != compare_checksum(compressed) {
+        return Err("分享码校验失败，可能复制不完整".to_string());
+    }
+
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(COMPARE_PAYLOAD_MAX_BYTES + 1)
+        .read_to_end(&mut json)
+        .map_err(|_| "分享码内容已损坏（无法解压）".to_string())?;
+    if json.len() as u64 > COMPARE_PAYLOAD_MAX_BYTES {
+        return Err("分享码内容过大".to_string());
+    }
+    let payload: ComparePayload =
+        serde_json::from_slice(&json).map_err(|_| "分享码内容格式错误".to_string())?;
+    if payload.version != COMPARE_PAYLOAD_VERSION
+        || payload
+            .pools
+            .iter()
+            .any(|pool| !GACHA_TYPES.contains(&pool.gacha_type.as_str()))
+    {
+        return Err("分享码内容格式错误".to_string());
+    }
+    Ok(payload)
+}
+
+/// Tauri 命令：为当前激活账号生成欧气对比分享码
+#[tauri::command]
+pub(crate) fn create_compare_payload(app: AppHandle) -> Result<String, AppError> {
+    let uid = current_config(&app)
+        .active_account
+        .ok_or_else(|| AppError::validation("请先选择账号"))?;
+    let conn = open_db(&app)?;
+    let pools = compare_pools(&conn, &uid)?;
+    if pools.is_empty() {
+        return Err(AppError::validation("当前账号没有抽卡记录"));
+    }
+    let payload = ComparePayload {
+        version: COMPARE_PAYLOAD_VERSION,
+        created_at: Utc::now().timestamp_millis(),
+        total_pulls: pools.iter().map(|pool| pool.total_pulls).sum(),
+        luck_percentile: luck_percentile(&pools),
+        pools,
+    };
+    Ok(encode_compare_payload(&payload)?)
+}
+
+/// Tauri 命令：解析朋友发来的分享码
+#[tauri::command]
+pub(crate) fn parse_compare_payload(text: String) -> Result<ComparePayload, AppError> {
+    decode_compare_payload(&text).map_err(AppError::validation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(gacha_type: &str, positions: &[u32]) -> ComparePool {
+        ComparePool {
+            gacha_type: gacha_type.to_string(),
+            total_pulls: positions.iter().map(|&p| u64::from(p)).sum(),
+            five_star_positions: positions.to_vec(),
+        }
+    }
+
+    #[test]
+    fn rate_follows_soft_and_hard_pity() {
+        assert_eq!(top_rarity_rate(1), TOP_RARITY_BASE_RATE);
+        assert_eq!(top_rarity_rate(TOP_RARITY_SOFT_PITY), TOP_RARITY_BASE_RATE);
+        assert!(top_rarity_rate(TOP_RARITY_SOFT_PITY + 1) > TOP_RARITY_BASE_RATE);
+        assert_eq!(top_rarity_rate(TOP_RARITY_HARD_PITY), 1.0);
+        for pull in 1..TOP_RARITY_HARD_PITY {
+            assert!(top_rarity_rate(pull) <= top_rarity_rate(pull + 1));
+        }
+    }
+
+    #[test]
+    fn survival_decreases_with_position() {
+        assert_eq!(top_rarity_survival(1), 1.0);
+        assert!(top_rarity_survival(40) > top_rarity_survival(70));
+        assert_eq!(top_rarity_survival(TOP_RARITY_HARD_PITY + 1), 0.0);
+    }
+
+    #[test]
+    fn luck_percentile_ignores_weapon_pool() {
+        assert_eq!(luck_percentile(&[]), None);
+        assert_eq!(luck_percentile(&[pool("weapon", &[1])]), None);
+        assert_eq!(luck_percentile(&[pool("special", &[1])]), Some(100.0));
+        assert_eq!(
+            luck_percentile(&[pool("special", &[1]), pool("weapon", &[80])]),
+            Some(100.0)
+        );
+    }
+}
@@ -0,0 +1,698 @@
+//! 自定义抽卡记录接口（高级模式）
+//!
+//! 新服务器区域上线后，内置接口要等新版本发布才能支持。高级模式允许用户自己描述接口：
+//! 基础地址、查询参数模板、分页参数名以及响应字段映射，由 Rust 端按描述请求并解析。
+//! 配置保存前必须发一次测试请求并解析出可识别的记录；配置可导出为 JSON 分享给其他用户。
+//! 通过自定义接口写入的记录在 custom_endpoint_records 中标记来源配置名。
+//!
+//! 整个功能需要先在设置中开启，默认关闭。
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::commands::endpoints::normalize_endpoint;
+use crate::commands::raw_responses::{keep_raw_response, list_item_pointer, RawPageIndex};
+use crate::config::{current_config, update_config};
+use crate::db::access::with_db_write;
+use crate::db::{parse_gacha_ts, TOP_RARITY};
+use crate::error::AppError;
+
+const CUSTOM_ENDPOINT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+/// 翻页间隔，与前端内置接口的节奏相近，避免触发风控
+const CUSTOM_ENDPOINT_PAGE_DELAY_MS: u64 = 1000;
+/// 单次同步最多翻页数（防止分页字段配错时无限请求）
+const CUSTOM_ENDPOINT_MAX_PAGES: usize = 500;
+/// 测试请求返回给前端预览的记录数
+const CUSTOM_ENDPOINT_PREVIEW_RECORDS: usize = 5;
+/// 查询参数值需要转义的字符
+const QUERY_VALUE: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// 响应中各字段的位置（点分隔路径，相对于单条记录；数组下标写数字）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct CustomFieldMapping {
+    /// 记录唯一序号（作为 seq_id，用于去重与排序）
+    id: String,
+    /// 抽卡时间（毫秒/秒级时间戳或日期字符串）
+    time: String,
+    /// 物品名称
+    name: String,
+    /// 星级
+    rank: String,
+    /// 卡池名称
+    pool: String,
+}
+
+/// 分页方式：下一页请求带上 cursor_param = 上一页最后一条记录的 cursor_field
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct CustomPagination {
+    /// 游标查询参数名（为空表示接口不分页）
+    cursor_param: String,
+    /// 游标取值字段（相对于单条记录）
+    cursor_field: String,
+    /// 响应中"是否还有下一页"的字段（相对于响应根，为空时翻到空页为止）
+    has_more_path: String,
+}
+
+/// 自定义接口配置（也是分享用的 JSON 格式）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct CustomEndpointProfile {
+    /// 配置名（唯一，同时作为记录来源标记）
+    name: String,
+    /// 记录类型：character / weapon
+    category: String,
+    base_url: String,
+    /// 查询参数模板，值中的 `{变量}` 在请求时替换（如 `{token}`、`{serverId}`）
+    query: BTreeMap<String, String>,
+    /// 记录数组在响应中的路径（如 `data.list`）
+    list_path: String,
+    pagination: CustomPagination,
+    fields: CustomFieldMapping,
+}
+
+/// 高级模式设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct CustomEndpointConfig {
+    enabled: bool,
+    profiles: Vec<CustomEndpointProfile>,
+}
+
+/// 按字段映射解析出的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CustomRecord {
+    id: String,
+    /// 规范化后的毫秒时间戳
+    gacha_ts: i64,
+    name: String,
+    rarity: i64,
+    pool: String,
+}
+
+/// 测试请求结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CustomEndpointTest {
+    /// 实际请求的地址
+    url: String,
+    /// 首页记录数
+    page_records: usize,
+    /// 前几条记录预览
+    preview: Vec<CustomRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CustomFetchResult {
+    pages: usize,
+    fetched: usize,
+    added: usize,
+}
+
+// ============== 配置校验与解析 ==============
+
+/// 校验并规范化配置（去空白、规范化地址），返回可保存的配置
+fn validate_profile(profile: &CustomEndpointProfile) -> Result<CustomEndpointProfile, String> {
+    let mut profile = profile.clone();
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("配置名不能为空".to_string());
+    }
+    profile.category = profile.category.trim().to_string();
+    if profile.category != "character" && profile.category != "weapon" {
+        return Err(format!(
+            "记录类型必须为 character 或 weapon: {}",
+            profile.category
+        ));
+    }
+    profile.base_url = normalize_endpoint(&profile.base_url)?;
+    let fields = &profile.fields;
+    for (label, path) in [
+        ("id", &fields.id),
+        ("time", &fields.time),
+        ("name", &fields.name),
+        ("rank", &fields.rank),
+        ("pool", &fields.pool),
+    ] {
+        if path.trim().is_empty() {
+            return Err(format!("字段映射缺少 {}", label));
+        }
+    }
+    let pagination = &profile.pagination;
+    if !pagination.cursor_param.trim().is_empty() && pagination.cursor_field.trim().is_empty() {
+        return Err("设置了分页参数时必须指定游标字段".to_string());
+    }
+    Ok(profile)
+}
+
+/// 按点分隔路径取值；空路径返回自身
+fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => current.get(segment),
+        })
+}
+
+fn value_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// 替换模板中的 `{变量}`；引用了未提供的变量时报错
+fn render_template(template: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 1..start + len];
+        let value = vars
+            .get(key)
+            .ok_or_else(|| format!("查询参数模板引用了未提供的变量: {{{}}}", key))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// 拼出请求地址（cursor 为 None 时是首页，不带游标参数）
+fn build_request_url(
+    profile: &CustomEndpointProfile,
+    vars: &BTreeMap<String, String>,
+    cursor: Option<&str>,
+) -> Result<String, String> {
+    let mut pairs = Vec::new();
+    for (key, template) in &profile.query {
+        pairs.push((key.clone(), render_template(template, vars)?));
+    }
+    if let Some(cursor) = cursor {
+        pairs.push((profile.pagination.cursor_param.clone(), cursor.to_string()));
+    }
+    let query = pairs
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                percent_encoding::utf8_percent_encode(key, QUERY_VALUE),
+                percent_encoding::utf8_percent_encode(value, QUERY_VALUE)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    let separator = if profile.base_url.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    Ok(if query.is_empty() {
+        profile.base_url.clone()
+    } else {
+        format!("{}{}{}", profile.base_url, separator, query)
+    })
+}
+
+/// 按字段映射解析一页响应；任一记录缺字段或字段无法识别都视为配置错误
+fn map_records(profile: &CustomEndpointProfile, body: &Value) -> Result<Vec<CustomRecord>, String> {
+    let list = json_path(body, &profile.list_path)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("响应中 {} 不是记录数组", profile.list_path))?;
+    let fields = &profile.fields;
+    list.iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let field = |label: &str, path: &str| {
+                value_text(json_path(item, path))
+                    .ok_or_else(|| format!("第 {} 条记录缺少字段 {}（{}）", i + 1, label, path))
+            };
+            let time = field("time", &fields.time)?;
+            let gacha_ts = parse_gacha_ts(&time)
+                .ok_or_else(|| format!("第 {} 条记录的时间无法识别: {}", i + 1, time))?
+                .timestamp_millis();
+            let rank = field("rank", &fields.rank)?;
+            let rarity = rank
+                .parse::<i64>()
+                .ok()
+                .filter(|r| (1..=TOP_RARITY).contains(r))
+                .ok_or_else(|| format!("第 {} 条记录的星级无法识别: {}", i + 1, rank))?;
+            Ok(CustomRecord {
+                id: field("id", &fields.id)?,
+                gacha_ts,
+                name: field("name", &fields.name)?,
+                rarity,
+                pool: field("pool", &fields.pool)?,
+            })
+        })
+        .collect()
+}
+
+/// 是否还有下一页：配置了 has_more_path 时以其为准，否则非空页即继续
+fn has_more(profile: &CustomEndpointProfile, body: &Value, page_len: usize) -> bool {
+    if page_len == 0 || profile.pagination.cursor_param.trim().is_empty() {
+        return false;
+    }
+    match profile.pagination.has_more_path.trim() {
+        "" => true,
+        path => match json_path(body, path) {
+            Some(Value::Bool(more)) => *more,
+            Some(Value::Number(n)) => n.as_i64() != Some(0),
+            _ => false,
+        },
+    }
+}
+
+// ============== 请求 ==============
+
+fn ensure_enabled(app: &AppHandle) -> Result<CustomEndpointConfig, AppError> {
+    let config = current_config(app).custom_endpoint;
+    if !config.enabled {
+        return Err(AppError::validation(
+            "请先在设置中开启自定义接口（高级模式）",
+        ));
+    }
+    Ok(config)
+}
+
+/// 请求一页，返回响应原文
+async fn fetch_text(
+    client: &tauri_plugin_http::reqwest::Client,
+    url: &str,
+) -> Result<String, AppError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| AppError::network(None, format!("请求自定义接口失败: {}", e)))?;
+    let status = response.status();
+    // 自定义接口的 token 由用户在变量中填写，401 多半是 token 过期，提示重新获取
+    if status.as_u16() == 401 {
+        return Err(AppError::TokenExpired {
+            message: "自定义接口返回 HTTP 401，token 可能已过期，请更新后重试".to_string(),
+        });
+    }
+    if !status.is_success() {
+        return Err(AppError::network(
+            Some(status.as_u16()),
+            format!("自定义接口返回 HTTP {}", status),
+        ));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| AppError::network(None, format!("读取自定义接口响应失败: {}", e)))
+}
+
+fn parse_json(text: &str) -> Result<Value, AppError> {
+    serde_json::from_str(text)
+        .map_err(|e| AppError::validation(format!("自定义接口响应不是 JSON: {}", e)))
+}
+
+/// 自定义接口记录的 record_uid，与官方接口一样按 `<uid>_<char|weapon>_<id>` 生成
+fn custom_record_uid(profile: &CustomEndpointProfile, uid: &str, id: &str) -> String {
+    let prefix = if profile.category == "weapon" {
+        "weapon"
+    } else {
+        "char"
+    };
+    format!("{}_{}_{}", uid, prefix, id)
+}
+
+/// 一页响应的留档索引：按映射取出每条记录的 id，缺 id 的记录跳过
+fn custom_page_index(
+    profile: &CustomEndpointProfile,
+    uid: &str,
+    page: u32,
+    body: &Value,
+) -> RawPageIndex {
+    let records = json_path(body, &profile.list_path)
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    let id = value_text(json_path(item, &profile.fields.id))?;
+                    Some((
+                        custom_record_uid(profile, uid, &id),
+                        list_item_pointer(&profile.list_path, i),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    RawPageIndex {
+        uid: uid.to_string(),
+        page,
+        records,
+    }
+}
+
+fn http_client() -> Result<tauri_plugin_http::reqwest::Client, AppError> {
+    tauri_plugin_http::reqwest::Client::builder()
+        .timeout(CUSTOM_ENDPOINT_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::network(None, format!("创建 HTTP 客户端失败: {}", e)))
+}
+
+/// 请求首页并按映射解析；首页没有记录时无法确认映射是否正确，按失败处理
+async fn run_test_request(
+    app: &AppHandle,
+    profile: &CustomEndpointProfile,
+    vars: &BTreeMap<String, String>,
+) -> Result<CustomEndpointTest, AppError> {
+    let url = build_request_url(profile, vars, None)?;
+    let text = fetch_text(&http_client()?, &url).await?;
+    // 测试请求的记录不入库，只留文件不建索引
+    keep_raw_response(app, "custom", &text, None);
+    let body = parse_json(&text)?;
+    let records = map_records(profile, &body)?;
+    if records.is_empty() {
+        return Err(AppError::validation(
+            "测试请求没有返回记录，无法确认字段映射，请换一个有记录的卡池再试",
+        ));
+    }
+    Ok(CustomEndpointTest {
+        url,
+        page_records: records.len(),
+        preview: records
+            .into_iter()
+            .take(CUSTOM_ENDPOINT_PREVIEW_RECORDS)
+            .collect(),
+    })
+}
+
+fn find_profile(
+    config: &CustomEndpointConfig,
+    name: &str,
+) -> Result<CustomEndpointProfile, AppError> {
+    config
+        .profiles
+        .iter()
+        .find(|p| p.name == name.trim())
+        .cloned()
+        .ok_or_else(|| AppError::validation(format!("未找到自定义接口配置: {}", name)))
+}
+
+/// 写入记录并标记来源配置（已存在的记录不覆盖、不改标记），返回实际新增条数
+fn save_custom_records(
+    conn: &rusqlite::Connection,
+    profile: &CustomEndpointProfile,
+    uid: &str,
+    records: &[CustomRecord],
+) -> rusqlite::Result<usize> {
+    let weapon = profile.category == "weapon";
+    let sql = if weapon {
+        "INSERT OR IGNORE INTO weapon_records (record_uid, uid, pool_id, pool_name, weapon_id,
+             weapon_name, weapon_type, rarity, is_new, gacha_ts, seq_id, fetched_at, category)
+         VALUES (?1, ?2, ?3, ?3, ?4, ?4, '', ?5, 0, ?6, ?7, ?8, 'weapon')"
+    } else {
+        "INSERT OR IGNORE INTO gacha_records (record_uid, uid, pool_id, pool_name, char_id,
+             char_name, rarity, is_new, is_free, gacha_ts, seq_id, fetched_at, category)
+         VALUES (?1, ?2, ?3, ?3, ?4, ?4, ?5, 0, 0, ?6, ?7, ?8, 'character')"
+    };
+    let fetched_at = Utc::now().timestamp_millis();
+    let mut added = 0;
+    let mut insert = conn.prepare(sql)?;
+    let mut tag = conn.prepare(
+        "INSERT OR IGNORE INTO custom_endpoint_records (record_uid, uid, profile, fetched_at)
+             VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for record in records {
+        let record_uid = custom_record_uid(profile, uid, &record.id);
+        let inserted = insert.execute(params![
+            record_uid,
+            uid,
+            record.pool,
+            record.name,
+            record.rarity,
+            record.gacha_ts.to_string(),
+            record.id,
+            fetched_at,
+        ])?;
+        if inserted > 0 {
+            tag.execute(params![record_uid, uid, profile.name, fetched_at])?;
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+// ============== 命令 ==============
+
+/// Tauri 命令：获取高级模式设置与已保存的配置
+#[tauri::command]
+pub(crate) fn get_custom_endpoint_config(app: AppHandle) -> CustomEndpointConfig {
+    current_config(&app).custom_endpoint
+}
+
+/// Tauri 命令：开关高级模式（关闭不删除已保存的配置）
+#[tauri::command]
+pub(crate) fn set_custom_endpoint_enabled(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    update_config(&app, |c| c.custom_endpoint.enabled = enabled)?;
+    Ok(())
+}
+
+/// Tauri 命令：只发测试请求，不保存配置
+#[tauri::command]
+pub(crate) async fn test_custom_endpoint(
+    app: AppHandle,
+    profile: CustomEndpointProfile,
+    vars: BTreeMap<String, String>,
+) -> Result<CustomEndpointTest, AppError> {
+    ensure_enabled(&app)?;
+    let profile = validate_profile(&profile).map_err(AppError::validation)?;
+    run_test_request(&app, &profile, &vars).await
+}
+
+/// Tauri 命令：测试通过后保存配置（同名覆盖）
+#[tauri::command]
+pub(crate) async fn save_custom_endpoint_profile(
+    app: AppHandle,
+    profile: CustomEndpointProfile,
+    vars: BTreeMap<String, String>,
+) -> Result<CustomEndpointTest, AppError> {
+    ensure_enabled(&app)?;
+    let profile = validate_profile(&profile).map_err(AppError::validation)?;
+    let test = run_test_request(&app, &profile, &vars).await?;
+    update_config(&app, |c| {
+        let profiles = &mut c.custom_endpoint.profiles;
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile.clone(),
+            None => profiles.push(profile.clone()),
+        }
+    })?;
+    Ok(test)
+}
+
+/// Tauri 命令：删除配置（已写入的记录与来源标记保留）
+#[tauri::command]
+pub(crate) fn delete_custom_endpoint_profile(app: AppHandle, name: String) -> Result<(), AppError> {
+    update_config(&app, |c| {
+        c.custom_endpoint.profiles.retain(|p| p.name != name.trim())
+    })?;
+    Ok(())
+}
+
+/// Tauri 命令：导出配置为 JSON（分享用）
+#[tauri::command]
+pub(crate) fn export_custom_endpoint_profile(
+    app: AppHandle,
+    name: String,
+) -> Result<String, AppError> {
+    let profile = find_profile(&current_config(&app).custom_endpoint, &name)?;
+    serde_json::to_string_pretty(&profile)
+        .map_err(|e| AppError::io(format!("序列化配置失败: {}", e)))
+}
+
+/// Tauri 命令：解析他人分享的配置 JSON。只做格式校验，保存仍需走 save_custom_endpoint_profile 的测试请求
+#[tauri::command]
+pub(crate) fn parse_custom_endpoint_profile(
+    json: String,
+) -> Result<CustomEndpointProfile, AppError> {
+    let profile: CustomEndpointProfile = serde_json::from_str(json.trim())
+        .map_err(|e| AppError::validation(format!("配置 JSON 格式错误: {}", e)))?;
+    validate_profile(&profile).map_err(AppError::validation)
+}
+
+/// Tauri 命令：按已保存的配置拉取全部记录并写入账号 uid
+#[tauri::command]
+pub(crate) async fn fetch_custom_endpoint_records(
+    app: AppHandle,
+    name: String,
+    uid: String,
+    vars: BTreeMap<String, String>,
+) -> Result<CustomFetchResult, AppError> {
+    let profile = find_profile(&ensure_enabled(&app)?, &name)?;
+    let uid = uid.trim().to_string();
+    if uid.is_empty() {
+        return Err(AppError::validation("uid 不能为空"));
+    }
+    let client = http_client()?;
+    let mut records = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0;
+    while pages < CUSTOM_ENDPOINT_MAX_PAGES {
+        if pages > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(
+                CUSTOM_ENDPOINT_PAGE_DELAY_MS,
+            ))
+            .await;
+        }
+        let url = build_request_url(&profile, &vars, cursor.as_deref())?;
+        let text = fetch_text(&client, &url).await?;
+        let body = parse_json(&text);
+        let index = body
+            .as_ref()
+            .ok()
+            .map(|body| custom_page_index(&profile, &uid, pages as u32 + 1, body));
+        keep_raw_response(&app, "custom", &text, index);
+        let body = body?;
+        let page = map_records(&profile, &body)?;
+        pages += 1;
+        let more = has_more(&profile, &body, page.len());
+        let next = json_path(&body, &profile.list_path)
+            .and_then(Value::as_array)
+            .and_then(|list| list.last())
+            .and_then(|last| value_text(json_path(last, &profile.pagination.cursor_field)));
+        records.extend(page);
+        // 游标不再前进时停止，防止配置错误导致反复请求同一页
+        if !more || next.is_none() || next == cursor {
+            break;
+        }
+        cursor = next;
+    }
+
+    let fetched = records.len();
+    let added = with_db_write(&app, "保存自定义接口记录", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let added = save_custom_records(&tx, &profile, &uid, &records)?;
+        tx.commit()?;
+        Ok(added)
+    })?;
+    eprintln!(
+        "[custom-endpoint] {} 拉取 {} 页 {} 条，新增 {} 条",
+        profile.name, pages, fetched, added
+    );
+    Ok(CustomFetchResult {
+        pages,
+        fetched,
+        added,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn profile() -> CustomEndpointProfile {
+        serde_json::from_value(json!({
+            "name": " New Region ",
+            "category": "character",
+            "baseUrl": "https://example.com/api/record/",
+            "query": { "token": "{token}", "server_id": "{serverId}", "lang": "en-us" },
+            "listPath": "data.list",
+            "pagination": { "cursorParam": "seq_id", "cursorField": "seqId", "hasMorePath": "data.hasMore" },
+            "fields": { "id": "seqId", "time": "gachaTs", "name": "item.name", "rank": "rarity", "pool": "poolName" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn validates_and_renders_request_url() {
+        let profile = validate_profile(&profile()).unwrap();
+        assert_eq!(profile.name, "New Region");
+        assert_eq!(profile.base_url, "https://example.com/api/record");
+
+        let vars = BTreeMap::from([
+            ("token".to_string(), "a b/c".to_string()),
+            ("serverId".to_string(), "3".to_string()),
+        ]);
+        assert_eq!(
+            build_request_url(&profile, &vars, Some("42")).unwrap(),
+            "https://example.com/api/record?lang=en-us&server_id=3&token=a%20b%2Fc&seq_id=42"
+        );
+        assert!(build_request_url(&profile, &BTreeMap::new(), None).is_err());
+
+        let mut broken = profile.clone();
+        broken.fields.pool.clear();
+        assert!(validate_profile(&broken).is_err());
+    }
+
+    #[test]
+    fn maps_records_and_pagination() {
+        let profile = validate_profile(&profile()).unwrap();
+        let body = json!({ "data": { "hasMore": true, "list": [
+            { "seqId": "12", "gachaTs": "1767225600000", "item": { "name": "Alpha" }, "rarity": 6, "poolName": "Special" },
+            { "seqId": 11, "gachaTs": "1767225600", "item": { "name": "Beta" }, "rarity": "4", "poolName": "Standard" }
+        ]}});
+        let records = map_records(&profile, &body).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name, "Alpha");
+        assert_eq!(records[1].id, "11");
+        assert_eq!(records[1].gacha_ts, 1_767_225_600_000);
+        assert!(has_more(&profile, &body, records.len()));
+        assert!(!has_more(
+            &profile,
+            &json!({ "data": { "hasMore": false } }),
+            2
+        ));
+        // 留档索引与入库时的 record_uid 一致
+        assert_eq!(
+            custom_page_index(&profile, "100", 2, &body).records,
+            vec![
+                ("100_char_12".to_string(), "/data/list/0".to_string()),
+                ("100_char_11".to_string(), "/data/list/1".to_string()),
+            ]
+        );
+
+        let bad = json!({ "data": { "list": [{ "seqId": "1", "gachaTs": "x", "item": {}, "rarity": 6, "poolName": "P" }] }});
+        assert!(map_records(&profile, &bad).is_err());
+        assert!(map_records(&profile, &json!({ "data": {} })).is_err());
+    }
+
+    #[test]
+    fn saves_records_with_profile_tag() {
+        let conn = crate::db::test_db();
+        let profile = validate_profile(&profile()).unwrap();
+        let record = CustomRecord {
+            id: "12".to_string(),
+            gacha_ts: 1_767_225_600_000,
+            name: "Alpha".to_string(),
+            rarity: 6,
+            pool: "Special".to_string(),
+        };
+        let records = vec![record.clone(), record];
+        assert_eq!(
+            save_custom_records(&conn, &profile, "100", &records).unwrap(),
+            1
+        );
+        let tag: (String, String) = conn
+            .query_row(
+                "SELECT record_uid, profile FROM custom_endpoint_records",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(tag, ("100_char_12".to_string(), "New Region".to_string()));
+    }
+}
@@ -0,0 +1,249 @@
+//! 网络诊断：把一次请求拆成 DNS / TCP / TLS / HTTP 几步分别计时，定位同步失败卡在哪一步
+
+use serde::Serialize;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+const TLS_TIMEOUT: Duration = Duration::from_secs(8);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 单个诊断步骤的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiagnoseStage {
+    /// dns / tcp / tls / http
+    stage: &'static str,
+    /// ok / failed / skipped
+    status: &'static str,
+    elapsed_ms: Option<u64>,
+    /// 成功时为解析到的地址、HTTP 状态码等，失败时为错误信息
+    detail: Option<String>,
+}
+
+impl DiagnoseStage {
+    fn ok(stage: &'static str, started: Instant, detail: String) -> Self {
+        Self {
+            stage,
+            status: "ok",
+            elapsed_ms: Some(started.elapsed().as_millis() as u64),
+            detail: Some(detail),
+        }
+    }
+
+    fn failed(stage: &'static str, started: Instant, detail: String) -> Self {
+        Self {
+            stage,
+            status: "failed",
+            elapsed_ms: Some(started.elapsed().as_millis() as u64),
+            detail: Some(detail),
+        }
+    }
+
+    fn skipped(stage: &'static str, reason: &str) -> Self {
+        Self {
+            stage,
+            status: "skipped",
+            elapsed_ms: None,
+            detail: Some(reason.to_string()),
+        }
+    }
+}
+
+/// `network_diagnose` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiagnoseResult {
+    url: String,
+    stages: Vec<DiagnoseStage>,
+    /// 第一个失败的步骤；全部通过时为空
+    failed_stage: Option<&'static str>,
+}
+
+/// 在后台线程里解析域名，超时后直接放弃（系统解析器本身不支持超时）
+fn resolve(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let (tx, rx) = mpsc::channel();
+    let target = (host.to_string(), port);
+    std::thread::spawn(move || {
+        let _ = tx.send(
+            target
+                .to_socket_addrs()
+                .map(|addrs| addrs.collect::<Vec<_>>()),
+        );
+    });
+    match rx.recv_timeout(DNS_TIMEOUT) {
+        Ok(Ok(addrs)) if !addrs.is_empty() => Ok(addrs),
+        Ok(Ok(_)) => Err("没有解析到任何地址".to_string()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("解析超时（{} 秒）", DNS_TIMEOUT.as_secs())),
+    }
+}
+
+/// 依次尝试解析到的地址，返回第一个连上的连接
+fn connect(addrs: &[SocketAddr]) -> Result<TcpStream, String> {
+    let mut last_error = String::new();
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, TCP_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("{}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// 在已建立的连接上单独完成一次 TLS 握手，返回协商出的协议版本
+fn tls_handshake(host: &str, mut stream: TcpStream) -> Result<String, String> {
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| e.to_string())?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let server_name =
+        rustls::pki_types::ServerName::try_from(host.to_string()).map_err(|e| e.to_string())?;
+    let mut conn =
+        rustls::ClientConnection::new(Arc::new(config), server_name).map_err(|e| e.to_string())?;
+
+    stream
+        .set_read_timeout(Some(TLS_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TLS_TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream).map_err(|e| e.to_string())?;
+    }
+    Ok(conn
+        .protocol_version()
+        .map(|version| format!("{:?}", version))
+        .unwrap_or_else(|| "TLS".to_string()))
+}
+
+/// DNS → TCP → TLS 三步（同步网络调用，放在阻塞线程中执行）
+fn diagnose_transport(host: &str, port: u16, https: bool) -> Vec<DiagnoseStage> {
+    let mut stages = Vec::with_capacity(3);
+
+    let started = Instant::now();
+    let addrs = match resolve(host, port) {
+        Ok(addrs) => {
+            let detail = addrs
+                .iter()
+                .map(|addr| addr.ip().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            stages.push(DiagnoseStage::ok("dns", started, detail));
+            addrs
+        }
+        Err(e) => {
+            stages.push(DiagnoseStage::failed("dns", started, e));
+            stages.push(DiagnoseStage::skipped("tcp", "域名解析失败"));
+            stages.push(DiagnoseStage::skipped("tls", "域名解析失败"));
+            return stages;
+        }
+    };
+
+    let started = Instant::now();
+    let stream = match connect(&addrs) {
+        Ok(stream) => {
+            let peer = stream
+                .peer_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_default();
+            stages.push(DiagnoseStage::ok("tcp", started, peer));
+            stream
+        }
+        Err(e) => {
+            stages.push(DiagnoseStage::failed("tcp", started, e));
+            stages.push(DiagnoseStage::skipped("tls", "TCP 连接失败"));
+            return stages;
+        }
+    };
+
+    if !https {
+        stages.push(DiagnoseStage::skipped("tls", "非 HTTPS 地址"));
+        return stages;
+    }
+    let started = Instant::now();
+    stages.push(match tls_handshake(host, stream) {
+        Ok(version) => DiagnoseStage::ok("tls", started, version),
+        Err(e) => DiagnoseStage::failed("tls", started, e),
+    });
+    stages
+}
+
+/// 完整发起一次 GET 请求；前面的步骤失败也照样尝试（代理环境下直连失败但 HTTP 可能是通的）
+async fn diagnose_http(url: &str) -> DiagnoseStage {
+    let started = Instant::now();
+    let client = match tauri_plugin_http::reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return DiagnoseStage::failed("http", started, e.to_string()),
+    };
+    match client.get(url).send().await {
+        // 4xx 说明服务器可达（接口根路径常常就是 404），只有 5xx 算失败
+        Ok(response) if response.status().is_server_error() => {
+            DiagnoseStage::failed("http", started, format!("HTTP {}", response.status()))
+        }
+        Ok(response) => DiagnoseStage::ok("http", started, format!("HTTP {}", response.status())),
+        Err(e) if e.is_timeout() => DiagnoseStage::failed(
+            "http",
+            started,
+            format!("请求超时（{} 秒）", HTTP_TIMEOUT.as_secs()),
+        ),
+        Err(e) => DiagnoseStage::failed("http", started, e.to_string()),
+    }
+}
+
+/// Tauri 命令：诊断到指定地址的网络连通性
+///
+/// 依次测试 DNS 解析、TCP 连接、TLS 握手（仅 HTTPS）和 HTTP 请求，每一步单独计时、单独捕获错误。
+#[tauri::command]
+pub(crate) async fn network_diagnose(url: String) -> Result<DiagnoseResult, AppError> {
+    let parsed = tauri_plugin_http::reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::validation(format!("地址格式不正确: {}", e)))?;
+    let https = match parsed.scheme() {
+        "https" => true,
+        "http" => false,
+        other => return Err(AppError::validation(format!("不支持的协议: {}", other))),
+    };
+    let host = parsed
+        .host_str()
+        .map(|host| {
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string()
+        })
+        .ok_or_else(|| AppError::validation("地址缺少主机名"))?;
+    let port = parsed
+        .port_or_known_default()
+        .unwrap_or(if https { 443 } else { 80 });
+
+    let transport_host = host.clone();
+    let mut stages = tauri::async_runtime::spawn_blocking(move || {
+        diagnose_transport(&transport_host, port, https)
+    })
+    .await
+    .map_err(|e| AppError::network(None, e.to_string()))?;
+    stages.push(diagnose_http(parsed.as_str()).await);
+
+    let failed_stage = stages
+        .iter()
+        .find(|stage| stage.status == "failed")
+        .map(|stage| stage.stage);
+    if let Some(stage) = failed_stage {
+        eprintln!("[diagnose] {} 在 {} 步骤失败", host, stage);
+    }
+    Ok(DiagnoseResult {
+        url: parsed.to_string(),
+        stages,
+        failed_stage,
+    })
+}
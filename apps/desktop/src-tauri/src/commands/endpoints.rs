@@ -0,0 +1,191 @@
+//! 云同步接口切换
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_state;
+use crate::config::{current_config, update_config};
+use crate::error::AppError;
+
+/// 同一接口连续失败达到该次数后切换到下一个接口
+const ENDPOINT_MAX_CONSECUTIVE_FAILURES: u32 = 2;
+
+/// 各接口的健康状态（仅内存保存，重启后从主接口重新开始）
+#[derive(Default)]
+struct EndpointHealth {
+    /// 当前生效的接口
+    active: Option<String>,
+    /// 各接口的连续失败次数
+    failures: HashMap<String, u32>,
+    /// 各接口最近一次错误信息
+    last_errors: HashMap<String, String>,
+}
+
+impl EndpointHealth {
+    /// 记录一次请求结果，返回是否应继续重试该接口
+    ///
+    /// 任一接口成功即结束本轮切换：所有接口的连续失败次数清零，
+    /// 否则切换前失败过的接口再次轮到时只会尝试一次就被跳过。
+    fn record(&mut self, endpoint: &str, ok: bool, error: Option<String>) -> bool {
+        if ok {
+            self.failures
+                .values_mut()
+                .for_each(|failures| *failures = 0);
+            self.last_errors.remove(endpoint);
+            self.active = Some(endpoint.to_string());
+            return true;
+        }
+
+        let failures = self.failures.entry(endpoint.to_string()).or_insert(0);
+        *failures += 1;
+        let keep_trying = *failures < ENDPOINT_MAX_CONSECUTIVE_FAILURES;
+        if let Some(error) = error {
+            self.last_errors.insert(endpoint.to_string(), error);
+        }
+        keep_trying
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct EndpointState(Mutex<EndpointHealth>);
+
+/// 接口状态（排障用）
+#[derive(Clone, Serialize)]
+pub(crate) struct EndpointStatus {
+    endpoint: String,
+    active: bool,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+}
+
+/// 规范化接口地址：仅允许 http/https，去掉末尾斜杠
+pub(crate) fn normalize_endpoint(raw: &str) -> Result<String, String> {
+    let url = raw.trim().trim_end_matches('/');
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(format!("接口地址必须以 http:// 或 https:// 开头: {}", raw));
+    }
+    Ok(url.to_string())
+}
+
+/// Tauri 命令：获取设置中的云同步接口列表（为空表示使用内置地址）
+#[tauri::command]
+pub(crate) fn get_sync_endpoints(app: AppHandle) -> Vec<String> {
+    current_config(&app).sync_endpoints
+}
+
+/// Tauri 命令：覆盖云同步接口列表（第一个为主接口，其余为备用）
+#[tauri::command]
+pub(crate) fn set_sync_endpoints(
+    app: AppHandle,
+    endpoints: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for endpoint in &endpoints {
+        let url = normalize_endpoint(endpoint).map_err(AppError::validation)?;
+        if !normalized.contains(&url) {
+            normalized.push(url);
+        }
+    }
+
+    let config = update_config(&app, |c| c.sync_endpoints = normalized.clone())?;
+    // 接口列表变化后从主接口重新开始
+    *app_state(&app).endpoints.0.lock().unwrap() = EndpointHealth::default();
+    Ok(config.sync_endpoints)
+}
+
+/// Tauri 命令：获取当前生效的云同步接口（尚未发起请求时为 None）
+#[tauri::command]
+pub(crate) fn get_active_endpoint(app: AppHandle) -> Option<String> {
+    app_state(&app).endpoints.0.lock().unwrap().active.clone()
+}
+
+/// Tauri 命令：获取各接口的健康状态（排障用）
+#[tauri::command]
+pub(crate) fn get_endpoint_statuses(app: AppHandle) -> Vec<EndpointStatus> {
+    let health = &app_state(&app).endpoints;
+    let health = health.0.lock().unwrap();
+    let mut endpoints: Vec<String> = health.failures.keys().cloned().collect();
+    if let Some(active) = &health.active {
+        if !endpoints.contains(active) {
+            endpoints.push(active.clone());
+        }
+    }
+    endpoints.sort();
+
+    endpoints
+        .into_iter()
+        .map(|endpoint| EndpointStatus {
+            active: health.active.as_deref() == Some(endpoint.as_str()),
+            consecutive_failures: health.failures.get(&endpoint).copied().unwrap_or(0),
+            last_error: health.last_errors.get(&endpoint).cloned(),
+            endpoint,
+        })
+        .collect()
+}
+
+/// Tauri 命令：上报一次接口请求结果
+///
+/// 成功时将该接口记为当前生效接口；失败时累计连续失败次数。
+/// 返回 true 表示应继续重试该接口，false 表示已达到上限、应切换到下一个接口。
+#[tauri::command]
+pub(crate) fn report_endpoint_result(
+    app: AppHandle,
+    endpoint: String,
+    ok: bool,
+    error: Option<String>,
+) -> Result<bool, AppError> {
+    let endpoint = normalize_endpoint(&endpoint).map_err(AppError::validation)?;
+    let state = &app_state(&app).endpoints;
+    let mut health = state.0.lock().unwrap();
+
+    let previous = health.active.clone();
+    if let Some(error) = &error {
+        eprintln!("[sync] 接口请求失败 ({}): {}", endpoint, error);
+    }
+    let keep_trying = health.record(&endpoint, ok, error);
+    if health.active != previous {
+        eprintln!("[sync] 当前生效接口: {}", endpoint);
+        let _ = app.emit("sync-endpoint-changed", serde_json::json!({ "endpoint": endpoint }));
+    }
+    Ok(keep_trying)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_trims_whitespace_and_trailing_slash() {
+        assert_eq!(
+            normalize_endpoint("  https://sync.example.com/api/ ").unwrap(),
+            "https://sync.example.com/api"
+        );
+        assert_eq!(
+            normalize_endpoint("http://127.0.0.1:8080//").unwrap(),
+            "http://127.0.0.1:8080"
+        );
+    }
+
+    #[test]
+    fn endpoint_requires_http_scheme() {
+        assert!(normalize_endpoint("ftp://sync.example.com").is_err());
+        assert!(normalize_endpoint("sync.example.com").is_err());
+        assert!(normalize_endpoint("").is_err());
+    }
+
+    #[test]
+    fn success_after_failover_resets_all_failure_counts() {
+        let mut health = EndpointHealth::default();
+        let (primary, backup) = ("https://a.example.com", "https://b.example.com");
+        assert!(health.record(primary, false, Some("timeout".into())));
+        assert!(!health.record(primary, false, Some("timeout".into())));
+        assert!(health.record(backup, true, None));
+        assert_eq!(health.active.as_deref(), Some(backup));
+        assert_eq!(health.failures[primary], 0);
+        // 备用接口之后出错时，主接口重新获得完整的重试次数
+        assert!(health.record(primary, false, None));
+        assert_eq!(health.last_errors[primary], "timeout");
+    }
+}
@@ -0,0 +1,985 @@
+//! 记录导出（UIGF、鸣潮工具兼容格式）与导出历史
+
+use chrono::{Local, TimeZone, Utc};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::commands::hash_chain::canonical_chain_hash;
+use crate::commands::pdf_report::write_pull_history_pdf;
+use crate::commands::tasks::{is_cancelled, ExportTask};
+use crate::config::{current_config, update_config, userdata_dir, write_file_atomic};
+use crate::db::revision::{read_revision, RevisionInfo};
+use crate::db::wal::checkpoint_before_snapshot;
+use crate::db::{
+    open_db, parse_gacha_ts, query_pulls_by_time, table_has_column, GACHA_TYPES, PULL_RECORDS_CTE,
+    TOP_RARITY,
+};
+use crate::error::AppError;
+
+// ============== 记录导出（UIGF） ==============
+//
+// 参照 UIGF v4 的结构导出：info + 按游戏分组的账号列表，每条记录字段均为字符串。
+// 终末地不在 UIGF 规定的游戏列表中，账号列表放在 `endfield` 键下，字段含义保持一致。
+
+/// 导出文件使用的时区（终末地国服为 UTC+8）
+const UIGF_TIMEZONE: i32 = 8;
+
+/// 匿名化导出时的占位 uid 起始值（保持为合法的纯数字 uid）
+const ANONYMOUS_UID_BASE: u64 = 100_000_000;
+
+/// 匿名化导出时记录时间的起点：所有时间整体平移，保留抽卡间隔
+const ANONYMOUS_TIME_BASE_MS: i64 = 1_577_836_800_000; // 2020-01-01 00:00:00 UTC
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UigfInfo {
+    export_timestamp: i64,
+    export_app: String,
+    export_app_version: String,
+    version: String,
+    /// 导出时的数据库修订信息（匿名导出不含此字段）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revision: Option<RevisionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UigfItem {
+    /// 规范卡池类型（special / standard / beginner / weapon）
+    uigf_gacha_type: String,
+    /// 原始卡池编码（pool_id）
+    gacha_type: String,
+    item_id: String,
+    count: String,
+    /// 本地时间 `YYYY-MM-DD HH:MM:SS`（按账号的 timezone）
+    time: String,
+    name: String,
+    item_type: String,
+    rank_type: String,
+    /// 记录 id（seq_id）
+    id: String,
+    /// 用户备注（仅在导出时选择包含个人备注时输出）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    favorite: Option<bool>,
+    /// 已归档（仅归档记录输出，导入时据此恢复归档标记）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UigfAccount {
+    uid: String,
+    timezone: i32,
+    lang: String,
+    /// 记录内容哈希（见 `canonical_chain_hash`），接收方可用同样的记录重算比对；匿名导出不含此字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain_hash: Option<String>,
+    list: Vec<UigfItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UigfFile {
+    info: UigfInfo,
+    endfield: Vec<UigfAccount>,
+}
+
+/// 导出用的原始记录
+struct ExportRow {
+    uid: String,
+    pool_id: String,
+    item_id: String,
+    name: String,
+    rarity: i64,
+    time_ms: i64,
+    gacha_id: String,
+    item_type: String,
+    gacha_type: String,
+    note: Option<String>,
+    favorite: bool,
+    archived: bool,
+}
+
+/// 读取待导出的记录（按账号、时间、gacha_id 排序）
+fn query_export_rows(conn: &Connection, uid: Option<&str>) -> Result<Vec<ExportRow>, AppError> {
+    let sql = format!(
+        "{} SELECT uid, pool_id, item_id, name, rarity, time, gacha_id, item_type, gacha_type,
+                  note, favorite, archived
+           FROM pull_records
+           WHERE ?1 IS NULL OR uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询导出记录失败: {}", e)))?;
+    let mut rows = query_pulls_by_time(&mut stmt, [uid], |row| {
+        let time: String = row.get(5)?;
+        Ok(ExportRow {
+            uid: row.get(0)?,
+            pool_id: row.get(1)?,
+            item_id: row.get(2)?,
+            name: row.get(3)?,
+            rarity: row.get(4)?,
+            time_ms: parse_gacha_ts(&time)
+                .map(|t| t.timestamp_millis())
+                .unwrap_or_default(),
+            gacha_id: row.get(6)?,
+            item_type: row.get(7)?,
+            gacha_type: row.get(8)?,
+            note: row.get(9)?,
+            favorite: row.get::<_, i64>(10)? != 0,
+            archived: row.get::<_, i64>(11)? != 0,
+        })
+    })
+    .map_err(|e| AppError::database(format!("读取导出记录失败: {}", e)))?;
+    // 稳定排序，同一账号内保持时间顺序
+    rows.sort_by(|a, b| a.uid.cmp(&b.uid));
+    Ok(rows)
+}
+
+/// 毫秒时间戳格式化为指定时区的本地时间字符串
+fn format_uigf_time(time_ms: i64, timezone: i32) -> String {
+    let offset = chrono::FixedOffset::east_opt(timezone * 3600)
+        .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+    Utc.timestamp_millis_opt(time_ms)
+        .single()
+        .unwrap_or_default()
+        .with_timezone(&offset)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// 导出时的卡池顺序：按 `pool_order` 中的先后排列，未列出的卡池按内置顺序追加在后；无效的卡池名忽略
+fn resolve_pool_order(pool_order: Option<&[String]>) -> Vec<&'static str> {
+    let mut order: Vec<&'static str> = Vec::with_capacity(GACHA_TYPES.len());
+    for name in pool_order.unwrap_or_default() {
+        match GACHA_TYPES.iter().find(|t| **t == name.trim()) {
+            Some(gacha_type) if !order.contains(gacha_type) => order.push(gacha_type),
+            Some(_) => {}
+            None => eprintln!("[export] 忽略无效的卡池名: {}", name),
+        }
+    }
+    for gacha_type in GACHA_TYPES {
+        if !order.contains(&gacha_type) {
+            order.push(gacha_type);
+        }
+    }
+    order
+}
+
+/// 卡池在导出顺序中的位置（未知卡池排在最后）
+fn pool_rank(order: &[&str], gacha_type: &str) -> usize {
+    order
+        .iter()
+        .position(|t| *t == gacha_type)
+        .unwrap_or(order.len())
+}
+
+/// 把记录按账号分组组装成 UIGF 账号列表
+///
+/// 匿名化时：uid 替换为占位数字，时间整体平移到固定起点（保留间隔与先后顺序），
+/// 记录 id 按顺序重新编号，时区统一为 UTC；物品、稀有度与卡池信息保持不变，仍可用于统计分析。
+fn build_uigf_accounts(
+    rows: Vec<ExportRow>,
+    anonymize: bool,
+    include_notes: bool,
+) -> Vec<UigfAccount> {
+    let mut accounts: Vec<UigfAccount> = Vec::new();
+    let mut time_shift = 0;
+    for row in rows {
+        if accounts.last().map(|a| a.uid.as_str()) != Some(row.uid.as_str()) {
+            // 账号首条记录（时间最早）决定该账号的平移量
+            time_shift = ANONYMOUS_TIME_BASE_MS - row.time_ms;
+            accounts.push(UigfAccount {
+                uid: row.uid.clone(),
+                timezone: if anonymize { 0 } else { UIGF_TIMEZONE },
+                lang: "zh-cn".to_string(),
+                chain_hash: None,
+                list: Vec::new(),
+            });
+        }
+        let account = accounts.last_mut().unwrap();
+        let (time, id) = if anonymize {
+            (
+                format_uigf_time(row.time_ms + time_shift, 0),
+                (account.list.len() + 1).to_string(),
+            )
+        } else {
+            (format_uigf_time(row.time_ms, UIGF_TIMEZONE), row.gacha_id)
+        };
+        account.list.push(UigfItem {
+            uigf_gacha_type: row.gacha_type,
+            gacha_type: row.pool_id,
+            item_id: row.item_id,
+            count: "1".to_string(),
+            time,
+            name: row.name,
+            item_type: row.item_type,
+            rank_type: row.rarity.to_string(),
+            id,
+            note: row.note.filter(|_| include_notes),
+            favorite: include_notes.then_some(row.favorite),
+            archived: row.archived,
+        });
+    }
+
+    if anonymize {
+        for (index, account) in accounts.iter_mut().enumerate() {
+            account.uid = (ANONYMOUS_UID_BASE + index as u64 + 1).to_string();
+        }
+    }
+    accounts
+}
+
+/// Tauri 命令：导出抽卡记录为 UIGF 格式 JSON，返回导出的记录条数
+///
+/// `uid` 为空时导出全部账号；`anonymize` 为真时隐去 uid 与真实时间（导出文件不含昵称）；
+/// `include_notes` 为真时附带备注与收藏标记；`pool_order` 指定 list 中卡池的先后（同一卡池内按时间）。
+/// 导出进度通过 `export-progress` 事件推送，可用 `cancel_task` 取消。
+#[tauri::command]
+pub(crate) fn export_uigf(
+    app: AppHandle,
+    uid: Option<String>,
+    output_path: String,
+    anonymize: bool,
+    include_notes: Option<bool>,
+    pool_order: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    ExportTask::start(&app, "uigf").run(|task| {
+        write_uigf(
+            &app,
+            task,
+            uid,
+            output_path,
+            anonymize,
+            include_notes,
+            pool_order,
+        )
+    })
+}
+
+fn write_uigf(
+    app: &AppHandle,
+    task: &ExportTask,
+    uid: Option<String>,
+    output_path: String,
+    anonymize: bool,
+    include_notes: Option<bool>,
+    pool_order: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    checkpoint_before_snapshot(app, "导出");
+    task.progress("querying", 0, 0);
+    let conn = open_db(app)?;
+    let rows = query_export_rows(&conn, uid.as_deref())?;
+    let count = rows.len();
+    task.check()?;
+    task.progress("building", 0, count);
+    let mut accounts = build_uigf_accounts(rows, anonymize, include_notes.unwrap_or(false));
+    // 组装后再排序：匿名化的时间平移与重新编号仍按时间顺序
+    let order = resolve_pool_order(pool_order.as_deref());
+    for account in &mut accounts {
+        account
+            .list
+            .sort_by_key(|item| pool_rank(&order, &item.uigf_gacha_type));
+    }
+    if !anonymize {
+        let mut done = 0;
+        for account in &mut accounts {
+            task.check()?;
+            account.chain_hash = Some(
+                canonical_chain_hash(&conn, &account.uid)
+                    .map_err(|e| format!("计算记录哈希失败: {}", e))?,
+            );
+            done += account.list.len();
+            task.progress("building", done, count);
+        }
+    }
+    let file = UigfFile {
+        info: UigfInfo {
+            export_timestamp: Utc::now().timestamp(),
+            export_app: app.package_info().name.clone(),
+            export_app_version: app.package_info().version.to_string(),
+            version: "v4.0".to_string(),
+            revision: if anonymize {
+                None
+            } else {
+                Some(read_revision(&conn)?)
+            },
+        },
+        endfield: accounts,
+    };
+    let text =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("序列化导出数据失败: {}", e))?;
+    task.write_output(std::path::Path::new(&output_path), text.as_bytes())?;
+    let options = ExportOptions {
+        uid,
+        anonymize,
+        include_notes: include_notes.unwrap_or(false),
+        pool_order,
+        gacha_type: None,
+    };
+    record_export("uigf", &output_path, Some(options), Some(count), true);
+    eprintln!(
+        "[export] UIGF 导出 {} 条记录{}",
+        count,
+        if anonymize { "（匿名）" } else { "" }
+    );
+    Ok(count)
+}
+
+// ============== 记录导出（鸣潮工具兼容格式） ==============
+//
+// 部分社区抽卡分析工具同时支持鸣潮，读取的是 UIGF v3 风格的单账号文件（info + list），
+// 并要求鸣潮的卡池编码与 quality_type 字段。这里把终末地记录换算成该格式导出。
+
+/// 国服 / 国际服对应的 game_biz
+const WW_GAME_BIZ_CN: &str = "wuwa_cn";
+const WW_GAME_BIZ_GLOBAL: &str = "wuwa_global";
+
+/// 规范卡池类型 → 鸣潮卡池编码（角色活动 / 武器活动 / 角色常驻 / 新手）
+fn ww_gacha_type(gacha_type: &str) -> &'static str {
+    match gacha_type {
+        "special" => "1",
+        "weapon" => "2",
+        "standard" => "3",
+        "beginner" => "5",
+        _ => "3",
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WwCompatInfo {
+    uid: String,
+    lang: String,
+    export_timestamp: i64,
+    export_app: String,
+    export_app_version: String,
+    uigf_version: String,
+    region_time_zone: i32,
+    game_biz: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WwCompatItem {
+    /// 鸣潮卡池编码（见 `ww_gacha_type`）
+    gacha_type: String,
+    /// 终末地原始卡池编码（pool_id）
+    pool_id: String,
+    item_id: String,
+    count: String,
+    time: String,
+    name: String,
+    item_type: String,
+    /// 终末地原始稀有度（6 为最高）
+    rank_type: String,
+    /// 换算到鸣潮 3~5 星口径的稀有度（最高稀有度对应 5）
+    quality_type: String,
+    id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WwCompatFile {
+    info: WwCompatInfo,
+    list: Vec<WwCompatItem>,
+}
+
+/// Tauri 命令：按鸣潮工具兼容格式导出某账号的抽卡记录（`pool_order`、进度与取消同 `export_uigf`）
+#[tauri::command]
+pub(crate) fn export_for_ww_compat(
+    app: AppHandle,
+    uid: String,
+    output_path: String,
+    pool_order: Option<Vec<String>>,
+) -> Result<(), AppError> {
+    ExportTask::start(&app, "ww_compat")
+        .run(|task| write_ww_compat(&app, task, uid, output_path, pool_order))
+}
+
+fn write_ww_compat(
+    app: &AppHandle,
+    task: &ExportTask,
+    uid: String,
+    output_path: String,
+    pool_order: Option<Vec<String>>,
+) -> Result<(), AppError> {
+    checkpoint_before_snapshot(app, "导出");
+    task.progress("querying", 0, 0);
+    let conn = open_db(app)?;
+    let provider: Option<String> = if table_has_column(&conn, "accounts", "provider")? {
+        conn.query_row(
+            "SELECT provider FROM accounts WHERE uid = ?1",
+            [&uid],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::database(format!("查询账号失败: {}", e)))?
+        .flatten()
+    } else {
+        None
+    };
+    let game_biz = if provider.as_deref() == Some("gryphline") {
+        WW_GAME_BIZ_GLOBAL
+    } else {
+        WW_GAME_BIZ_CN
+    };
+
+    let mut rows = query_export_rows(&conn, Some(&uid))?;
+    if rows.is_empty() {
+        return Err(AppError::validation(format!(
+            "账号 {} 没有可导出的抽卡记录",
+            uid
+        )));
+    }
+    let count = rows.len();
+    task.check()?;
+    task.progress("building", 0, count);
+    let order = resolve_pool_order(pool_order.as_deref());
+    rows.sort_by_key(|row| pool_rank(&order, &row.gacha_type));
+    let list = rows
+        .into_iter()
+        .map(|row| WwCompatItem {
+            gacha_type: ww_gacha_type(&row.gacha_type).to_string(),
+            pool_id: row.pool_id,
+            item_id: row.item_id,
+            count: "1".to_string(),
+            time: format_uigf_time(row.time_ms, UIGF_TIMEZONE),
+            name: row.name,
+            item_type: if row.item_type == "weapon" {
+                "武器"
+            } else {
+                "角色"
+            }
+            .to_string(),
+            rank_type: row.rarity.to_string(),
+            quality_type: (row.rarity - (TOP_RARITY - 5)).to_string(),
+            id: row.gacha_id,
+        })
+        .collect();
+
+    let file = WwCompatFile {
+        info: WwCompatInfo {
+            uid,
+            lang: "zh-cn".to_string(),
+            export_timestamp: Utc::now().timestamp(),
+            export_app: app.package_info().name.clone(),
+            export_app_version: app.package_info().version.to_string(),
+            uigf_version: "v3.0".to_string(),
+            region_time_zone: UIGF_TIMEZONE,
+            game_biz: game_biz.to_string(),
+        },
+        list,
+    };
+    let text =
+        serde_json::to_string_pretty(&file).map_err(|e| format!("序列化导出数据失败: {}", e))?;
+    task.write_output(std::path::Path::new(&output_path), text.as_bytes())?;
+    let options = ExportOptions {
+        uid: Some(file.info.uid.clone()),
+        pool_order,
+        ..Default::default()
+    };
+    record_export("ww_compat", &output_path, Some(options), Some(count), true);
+    eprintln!("[export] 鸣潮兼容格式导出 {} 条记录", count);
+    Ok(())
+}
+
+// ============== 导出历史 ==============
+//
+// 记录每次导出的时间、格式、路径、选项与结果（保存在 userdata/export_history.json），
+// 查询时检查文件是否仍存在，前端可重新打开、删除，或按原选项重新导出一份。
+
+/// 导出历史最多保留的条数
+const EXPORT_HISTORY_LIMIT: usize = 50;
+
+/// 支持记录的导出格式
+const EXPORT_FORMATS: [&str; 5] = ["json", "csv", "uigf", "ww_compat", "pdf"];
+
+/// 后端导出使用的选项（随导出历史保存，重新导出时沿用）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ExportOptions {
+    /// 为空时导出全部账号（仅 UIGF）
+    uid: Option<String>,
+    anonymize: bool,
+    include_notes: bool,
+    pool_order: Option<Vec<String>>,
+    /// PDF 报告的卡池筛选
+    gacha_type: Option<String>,
+}
+
+/// 一条导出记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExportEntry {
+    /// 记录 id（导出时间 + 格式；旧版历史缺少该字段，读取时补齐）
+    #[serde(default)]
+    id: String,
+    /// 导出时间（毫秒时间戳）
+    exported_at: i64,
+    format: String,
+    path: String,
+    /// 导出选项；前端完成的导出（JSON / CSV）没有，不能重新导出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    options: Option<ExportOptions>,
+    /// 导出的记录条数（未知时为空）
+    #[serde(default)]
+    row_count: Option<usize>,
+    #[serde(default = "default_export_success")]
+    success: bool,
+    /// 文件是否仍存在（查询时计算，不持久化）
+    #[serde(default)]
+    exists: bool,
+}
+
+fn default_export_success() -> bool {
+    true
+}
+
+fn export_entry_id(exported_at: i64, format: &str) -> String {
+    format!("{}-{}", exported_at, format)
+}
+
+fn export_history_file() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join("export_history.json"))
+}
+
+/// 读取导出历史（新的在前）；文件不存在或损坏时视为空
+fn load_export_history() -> Vec<ExportEntry> {
+    let mut entries: Vec<ExportEntry> = export_history_file()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    for entry in entries.iter_mut().filter(|entry| entry.id.is_empty()) {
+        entry.id = export_entry_id(entry.exported_at, &entry.format);
+    }
+    entries
+}
+
+fn save_export_history(entries: &[ExportEntry]) -> Result<(), String> {
+    let path = export_history_file()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let text =
+        serde_json::to_string_pretty(entries).map_err(|e| format!("序列化导出历史失败: {}", e))?;
+    write_file_atomic(&path, text.as_bytes())
+}
+
+/// 追加一条导出记录（同一路径只保留最新一次），超出上限时丢弃最旧的
+fn record_export(
+    format: &str,
+    path: &str,
+    options: Option<ExportOptions>,
+    row_count: Option<usize>,
+    success: bool,
+) {
+    let mut entries = load_export_history();
+    entries.retain(|entry| entry.path != path);
+    let exported_at = Utc::now().timestamp_millis();
+    entries.insert(
+        0,
+        ExportEntry {
+            id: export_entry_id(exported_at, format),
+            exported_at,
+            format: format.to_string(),
+            path: path.to_string(),
+            options,
+            row_count,
+            success,
+            exists: success,
+        },
+    );
+    entries.truncate(EXPORT_HISTORY_LIMIT);
+    if let Err(e) = save_export_history(&entries) {
+        eprintln!("[export] 保存导出历史失败: {}", e);
+    }
+}
+
+/// Tauri 命令：记录前端完成的导出（JSON / CSV 由前端直接写文件）
+#[tauri::command]
+pub(crate) fn record_export_entry(format: String, path: String) -> Result<(), AppError> {
+    if !EXPORT_FORMATS.contains(&format.as_str()) {
+        return Err(AppError::validation(format!("未知的导出格式: {}", format)));
+    }
+    record_export(&format, &path, None, None, true);
+    Ok(())
+}
+
+/// Tauri 命令：获取导出历史（新的在前，`limit` 为空时返回全部），文件已被删除的标记 exists = false
+#[tauri::command]
+pub(crate) fn get_export_history(limit: Option<usize>) -> Vec<ExportEntry> {
+    load_export_history()
+        .into_iter()
+        .take(limit.unwrap_or(EXPORT_HISTORY_LIMIT))
+        .map(|entry| ExportEntry {
+            exists: std::path::Path::new(&entry.path).is_file(),
+            ..entry
+        })
+        .collect()
+}
+
+fn find_export_entry(id: &str) -> Result<ExportEntry, AppError> {
+    load_export_history()
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| AppError::validation(format!("导出历史中没有该记录: {}", id)))
+}
+
+/// 按选项导出到 `directory`，文件名带当前时间戳；返回导出文件路径
+fn export_to_dir(
+    app: &AppHandle,
+    kind: &str,
+    options: &ExportOptions,
+    directory: Option<PathBuf>,
+) -> Result<String, AppError> {
+    let Some(&(kind, tag, extension, _)) = dialog_export_kind(kind) else {
+        return Err(AppError::validation(format!(
+            "{} 导出由界面生成，无法重新执行",
+            kind
+        )));
+    };
+    let directory = directory
+        .filter(|dir| dir.is_dir())
+        .or_else(|| current_config(app).last_export_dir.map(PathBuf::from))
+        .filter(|dir| dir.is_dir())
+        .or_else(|| app.path().download_dir().ok())
+        .ok_or_else(|| AppError::io("找不到可用的导出目录"))?;
+    let file_name = default_export_file_name(
+        tag,
+        options.uid.as_deref(),
+        &Local::now().format("%Y%m%d-%H%M%S").to_string(),
+        extension,
+    );
+    let output_path = directory.join(file_name).to_string_lossy().into_owned();
+    ExportTask::start(app, kind).run(|task| run_export(app, task, kind, options, &output_path))?;
+    Ok(output_path)
+}
+
+/// 按导出历史中的一条记录重新导出（与原文件同目录）；返回导出文件路径
+fn rerun_entry(app: &AppHandle, entry: ExportEntry) -> Result<String, AppError> {
+    let Some(options) = entry.options else {
+        return Err(AppError::validation(format!(
+            "{} 导出由界面生成，无法重新执行",
+            entry.format
+        )));
+    };
+    let directory = std::path::Path::new(&entry.path)
+        .parent()
+        .map(PathBuf::from);
+    export_to_dir(app, &entry.format, &options, directory)
+}
+
+/// 快速导出：重新执行最近一次可重复的导出；从未导出过时为当前账号导出 UIGF。返回导出文件路径
+pub(crate) fn export_latest(app: &AppHandle) -> Result<String, AppError> {
+    let latest = load_export_history()
+        .into_iter()
+        .find(|entry| entry.options.is_some() && dialog_export_kind(&entry.format).is_some());
+    match latest {
+        Some(entry) => rerun_entry(app, entry),
+        None => {
+            let options = ExportOptions {
+                uid: current_config(app).active_account,
+                ..Default::default()
+            };
+            export_to_dir(app, "uigf", &options, None)
+        }
+    }
+}
+
+/// Tauri 命令：按导出历史中的选项重新导出一份（与原文件同目录，文件名带新的时间戳），返回新的导出记录
+///
+/// 原目录已不存在时改用上次导出目录或下载目录。JSON / CSV 由前端生成，不能在这里重新导出。
+#[tauri::command]
+pub(crate) async fn rerun_export(app: AppHandle, id: String) -> Result<ExportEntry, AppError> {
+    let entry = find_export_entry(&id)?;
+    let output_path = tauri::async_runtime::spawn_blocking(move || rerun_entry(&app, entry))
+        .await
+        .map_err(|e| AppError::io(format!("导出任务异常结束: {}", e)))??;
+    load_export_history()
+        .into_iter()
+        .find(|entry| entry.path == output_path)
+        .map(|entry| ExportEntry {
+            exists: true,
+            ..entry
+        })
+        .ok_or_else(|| AppError::io("导出完成但未写入导出历史"))
+}
+
+/// Tauri 命令：在文件管理器中定位导出的文件；文件已被删除时返回 false（不报错）
+#[tauri::command]
+pub(crate) fn reveal_export(id: String) -> Result<bool, AppError> {
+    let entry = find_export_entry(&id)?;
+    if !std::path::Path::new(&entry.path).is_file() {
+        return Ok(false);
+    }
+    tauri_plugin_opener::reveal_item_in_dir(&entry.path)
+        .map_err(|e| AppError::io(format!("打开文件所在目录失败: {}", e)))?;
+    Ok(true)
+}
+
+/// Tauri 命令：删除一条导出历史；`delete_file` 为真时同时删除导出的文件
+#[tauri::command]
+pub(crate) fn delete_export_entry(path: String, delete_file: bool) -> Result<(), AppError> {
+    let mut entries = load_export_history();
+    let before = entries.len();
+    entries.retain(|entry| entry.path != path);
+    if entries.len() == before {
+        return Err(AppError::validation(format!(
+            "导出历史中没有该文件: {}",
+            path
+        )));
+    }
+    if delete_file {
+        let file = std::path::Path::new(&path);
+        if file.is_file() {
+            std::fs::remove_file(file)
+                .map_err(|e| AppError::io(format!("删除导出文件失败: {}", e)))?;
+        }
+    }
+    save_export_history(&entries).map_err(AppError::io)
+}
+
+// ============== 由后端弹出保存对话框的导出 ==============
+//
+// 前端只传导出类型：后端按上次导出目录与"类型-uid-日期"生成默认文件名，弹出原生保存对话框，
+// 完成导出后记住所选目录。用户取消时返回 cancelled，而不是错误。
+
+/// `export_with_dialog` 支持的导出类型：(类型, 文件名中的标识, 扩展名, 过滤器名称)
+const DIALOG_EXPORT_KINDS: [(&str, &str, &str, &str); 3] = [
+    ("uigf", "uigf", "json", "UIGF JSON"),
+    ("ww_compat", "ww", "json", "JSON"),
+    ("pdf", "report", "pdf", "PDF"),
+];
+
+fn dialog_export_kind(
+    kind: &str,
+) -> Option<&'static (&'static str, &'static str, &'static str, &'static str)> {
+    DIALOG_EXPORT_KINDS.iter().find(|(name, ..)| *name == kind)
+}
+
+/// 按类型执行一次后端导出；失败时也写入导出历史（success = false），取消的导出不记录
+fn run_export(
+    app: &AppHandle,
+    task: &ExportTask,
+    kind: &str,
+    options: &ExportOptions,
+    output_path: &str,
+) -> Result<(), AppError> {
+    let result = match kind {
+        "uigf" => write_uigf(
+            app,
+            task,
+            options.uid.clone(),
+            output_path.to_string(),
+            options.anonymize,
+            Some(options.include_notes),
+            options.pool_order.clone(),
+        )
+        .map(|_| ()),
+        "ww_compat" => write_ww_compat(
+            app,
+            task,
+            options.uid.clone().unwrap_or_default(),
+            output_path.to_string(),
+            options.pool_order.clone(),
+        ),
+        _ => write_pull_history_pdf(
+            app,
+            task,
+            options.uid.clone().unwrap_or_default(),
+            options.gacha_type.clone(),
+            output_path.to_string(),
+        )
+        .map(|_| record_export("pdf", output_path, Some(options.clone()), None, true)),
+    };
+    if let Some(e) = result.as_ref().err().filter(|e| !is_cancelled(e)) {
+        eprintln!("[export] {} 导出失败: {}", kind, e);
+        record_export(kind, output_path, Some(options.clone()), None, false);
+    }
+    result
+}
+
+/// `export_with_dialog` 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub(crate) enum DialogExportResult {
+    #[serde(rename_all = "camelCase")]
+    Exported { path: String, task_id: String },
+    /// 用户关闭了保存对话框、拒绝覆盖已有文件，或导出过程中取消（此时带任务 id，未写入任何文件）
+    #[serde(rename_all = "camelCase")]
+    Cancelled { task_id: Option<String> },
+}
+
+/// 默认文件名，如 `efgh-uigf-123456-20240501.json`（未指定账号时为 all）
+fn default_export_file_name(tag: &str, uid: Option<&str>, date: &str, extension: &str) -> String {
+    format!(
+        "efgh-{}-{}-{}.{}",
+        tag,
+        uid.unwrap_or("all"),
+        date,
+        extension
+    )
+}
+
+/// 用户输入的文件名缺少扩展名时补上
+fn with_extension(path: PathBuf, extension: &str) -> PathBuf {
+    let matches = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+    if matches {
+        path
+    } else {
+        let mut name = path.into_os_string();
+        name.push(".");
+        name.push(extension);
+        PathBuf::from(name)
+    }
+}
+
+/// Tauri 命令：弹出保存对话框并导出
+///
+/// `kind` 为 uigf / ww_compat / pdf；`uid` 为空时 UIGF 导出全部账号，其余类型使用当前激活账号。
+/// 原生对话框已对用户选中的已有文件确认过覆盖；补上扩展名后才撞上的已有文件在这里再确认一次。
+/// 开始写出后通过 `export-progress` 事件推送任务 id 与进度，`cancel_task` 取消后返回 cancelled。
+#[tauri::command]
+pub(crate) async fn export_with_dialog(
+    app: AppHandle,
+    kind: String,
+    uid: Option<String>,
+) -> Result<DialogExportResult, AppError> {
+    let Some(&(kind, tag, extension, filter_name)) = dialog_export_kind(&kind) else {
+        return Err(AppError::validation(format!("未知的导出类型: {}", kind)));
+    };
+    let uid = match uid {
+        Some(uid) => Some(uid),
+        None if kind == "uigf" => None,
+        None => Some(
+            current_config(&app)
+                .active_account
+                .ok_or_else(|| AppError::validation("请先选择要导出的账号"))?,
+        ),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let file_name = default_export_file_name(
+            tag,
+            uid.as_deref(),
+            &Local::now().format("%Y%m%d").to_string(),
+            extension,
+        );
+        let directory = current_config(&app)
+            .last_export_dir
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_dir())
+            .or_else(|| app.path().download_dir().ok());
+
+        let mut dialog = app
+            .dialog()
+            .file()
+            .set_file_name(file_name)
+            .add_filter(filter_name, &[extension]);
+        if let Some(directory) = directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(window) = app.get_webview_window("main") {
+            dialog = dialog.set_parent(&window);
+        }
+        let Some(chosen) = dialog.blocking_save_file() else {
+            return Ok(DialogExportResult::Cancelled { task_id: None });
+        };
+        let chosen = chosen
+            .into_path()
+            .map_err(|e| AppError::validation(format!("无效的保存路径: {}", e)))?;
+        let path = with_extension(chosen.clone(), extension);
+        if path != chosen && path.exists() {
+            let replace = app
+                .dialog()
+                .message(format!("{} 已存在，要替换它吗？", path.display()))
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    "替换".to_string(),
+                    "取消".to_string(),
+                ))
+                .blocking_show();
+            if !replace {
+                return Ok(DialogExportResult::Cancelled { task_id: None });
+            }
+        }
+
+        let output_path = path.to_string_lossy().into_owned();
+        let options = ExportOptions {
+            uid,
+            ..Default::default()
+        };
+        let task = ExportTask::start(&app, kind);
+        let task_id = task.id().to_string();
+        match task.run(|task| run_export(&app, task, kind, &options, &output_path)) {
+            Ok(()) => {}
+            Err(e) if is_cancelled(&e) => {
+                eprintln!("[export] {} 导出已取消", kind);
+                return Ok(DialogExportResult::Cancelled {
+                    task_id: Some(task_id),
+                });
+            }
+            Err(e) => return Err(e),
+        }
+
+        if let Some(dir) = path.parent() {
+            let dir = dir.to_string_lossy().into_owned();
+            if let Err(e) = update_config(&app, |c| c.last_export_dir = Some(dir)) {
+                eprintln!("[export] 保存导出目录失败: {}", e);
+            }
+        }
+        Ok(DialogExportResult::Exported {
+            path: output_path,
+            task_id,
+        })
+    })
+    .await
+    .map_err(|e| AppError::io(format!("导出任务异常结束: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(order: &[&str]) -> Vec<String> {
+        order.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn pool_order_defaults_to_builtin() {
+        assert_eq!(resolve_pool_order(None), GACHA_TYPES.to_vec());
+    }
+
+    #[test]
+    fn pool_order_appends_unlisted_and_skips_invalid() {
+        let requested = names(&["weapon", "limited", "standard", "weapon"]);
+        assert_eq!(
+            resolve_pool_order(Some(&requested)),
+            vec!["weapon", "standard", "special", "beginner"]
+        );
+    }
+
+    #[test]
+    fn dialog_export_file_names() {
+        assert_eq!(
+            default_export_file_name("uigf", Some("123456"), "20240501", "json"),
+            "efgh-uigf-123456-20240501.json"
+        );
+        assert_eq!(
+            default_export_file_name("uigf", None, "20240501", "json"),
+            "efgh-uigf-all-20240501.json"
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out"), "json"),
+            PathBuf::from("out.json")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out.JSON"), "json"),
+            PathBuf::from("out.JSON")
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! 记录哈希链（防篡改校验）
+//!
+//! 每个账号的角色/武器记录各自按入库顺序（rowid）串成一条哈希链：
+//!   hash_n = sha256(hash_{n-1} | record_uid | item_id | gacha_ts)
+//! 新记录只会追加在链尾，已封存位置的哈希保持不变；记录被手动修改或删除后，
+//! 重算的链会在对应位置与 record_hash_chain 表中保存的哈希不一致。
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::db::access::with_db_write;
+use crate::db::open_db;
+use crate::error::AppError;
+
+/// 哈希链来源：(source, 表名, 物品 ID 列)
+const CHAIN_SOURCES: [(&str, &str, &str); 2] = [
+    ("character", "gacha_records", "char_id"),
+    ("weapon", "weapon_records", "weapon_id"),
+];
+
+/// 参与哈希的记录字段
+pub(crate) struct ChainRecord {
+    record_uid: String,
+    item_id: String,
+    gacha_ts: String,
+}
+
+/// 链上第一处不一致
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainDivergence {
+    source: String,
+    /// 从 1 开始的链上位置
+    position: usize,
+    /// 封存时该位置的记录
+    record_uid: String,
+    /// modified：记录内容被改动；missing：记录被删除或顺序被打乱
+    reason: String,
+}
+
+/// 哈希链校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainReport {
+    uid: String,
+    record_count: usize,
+    /// 已封存（写入哈希）的记录数
+    sealed_count: usize,
+    /// 按时间顺序计算的内容哈希，与导出文件中的 chain_hash 对应
+    canonical_hash: String,
+    divergence: Option<ChainDivergence>,
+}
+
+/// 对各字段（以分隔符隔开）计算 sha256，返回十六进制字符串
+pub(crate) fn sha256_parts(parts: &[&str]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0x1f]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+pub(crate) fn chain_hash(prev: &str, record: &ChainRecord) -> String {
+    sha256_parts(&[prev, &record.record_uid, &record.item_id, &record.gacha_ts])
+}
+
+/// 依次计算整条链的哈希
+fn chain_hashes<'a>(records: impl IntoIterator<Item = &'a ChainRecord>) -> Vec<String> {
+    let mut prev = String::new();
+    records
+        .into_iter()
+        .map(|record| {
+            prev = chain_hash(&prev, record);
+            prev.clone()
+        })
+        .collect()
+}
+
+fn load_chain_records(
+    conn: &Connection,
+    uid: &str,
+    table: &str,
+    item_column: &str,
+    order_by: &str,
+) -> rusqlite::Result<Vec<ChainRecord>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT record_uid, {}, gacha_ts FROM {} WHERE uid = ?1 ORDER BY {}",
+        item_column, table, order_by
+    ))?;
+    let rows = stmt.query_map([uid], |row| {
+        Ok(ChainRecord {
+            record_uid: row.get(0)?,
+            item_id: row.get(1)?,
+            gacha_ts: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 已封存的链：(record_uid, hash)，按位置排序
+fn load_sealed_chain(
+    conn: &Connection,
+    uid: &str,
+    source: &str,
+) -> rusqlite::Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT record_uid, hash FROM record_hash_chain
+         WHERE uid = ?1 AND source = ?2 ORDER BY position",
+    )?;
+    let rows = stmt.query_map([uid, source], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// 比对重算结果与已封存的链，返回第一处不一致
+fn find_divergence(
+    source: &str,
+    records: &[ChainRecord],
+    hashes: &[String],
+    sealed: &[(String, String)],
+) -> Option<ChainDivergence> {
+    sealed
+        .iter()
+        .enumerate()
+        .find_map(|(index, (record_uid, hash))| {
+            let reason = match records.get(index) {
+                Some(record) if &record.record_uid != record_uid => "missing",
+                Some(_) if &hashes[index] != hash => "modified",
+                Some(_) => return None,
+                None => "missing",
+            };
+            Some(ChainDivergence {
+                source: source.to_string(),
+                position: index + 1,
+                record_uid: record_uid.clone(),
+                reason: reason.to_string(),
+            })
+        })
+}
+
+/// 按时间顺序计算账号全部记录的内容哈希（与入库顺序无关，不同设备上相同的记录得到相同结果）
+pub(crate) fn canonical_chain_hash(conn: &Connection, uid: &str) -> rusqlite::Result<String> {
+    let mut heads = Vec::new();
+    for (_, table, item_column) in CHAIN_SOURCES {
+        let records = load_chain_records(
+            conn,
+            uid,
+            table,
+            item_column,
+            "CAST(gacha_ts AS INTEGER), CAST(seq_id AS INTEGER), record_uid",
+        )?;
+        heads.push(chain_hashes(&records).pop().unwrap_or_default());
+    }
+    Ok(sha256_parts(&[uid, &heads[0], &heads[1]]))
+}
+
+/// 校验账号的哈希链；`seal` 为真且未发现不一致时，把新追加的记录写入链中
+///
+/// 封存时会写入多行，调用方负责把它放在事务中执行。
+pub(crate) fn check_hash_chain(
+    conn: &Connection,
+    uid: &str,
+    seal: bool,
+) -> rusqlite::Result<ChainReport> {
+    let mut report = ChainReport {
+        uid: uid.to_string(),
+        record_count: 0,
+        sealed_count: 0,
+        canonical_hash: canonical_chain_hash(conn, uid)?,
+        divergence: None,
+    };
+    let mut pending = Vec::new();
+
+    for (source, table, item_column) in CHAIN_SOURCES {
+        let records = load_chain_records(conn, uid, table, item_column, "rowid")?;
+        let sealed = load_sealed_chain(conn, uid, source)?;
+        report.record_count += records.len();
+
+        // 该账号的记录被整体清空（删除账号/清空记录）属于正常操作，不视为篡改
+        if records.is_empty() {
+            if seal && !sealed.is_empty() {
+                pending.push((source, records, Vec::new(), sealed.len()));
+            }
+            continue;
+        }
+
+        let hashes = chain_hashes(&records);
+        if report.divergence.is_none() {
+            report.divergence = find_divergence(source, &records, &hashes, &sealed);
+        }
+        report.sealed_count += sealed.len().min(records.len());
+        pending.push((source, records, hashes, sealed.len()));
+    }
+
+    if !seal || report.divergence.is_some() {
+        return Ok(report);
+    }
+
+    for (source, records, hashes, sealed_len) in pending {
+        if records.is_empty() {
+            conn.execute(
+                "DELETE FROM record_hash_chain WHERE uid = ?1 AND source = ?2",
+                [uid, source],
+            )?;
+            continue;
+        }
+        let mut stmt = conn.prepare_cached(
+            "INSERT OR REPLACE INTO record_hash_chain (uid, source, position, record_uid, hash)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for (index, (record, hash)) in records.iter().zip(&hashes).enumerate().skip(sealed_len) {
+            stmt.execute(rusqlite::params![
+                uid,
+                source,
+                index as i64 + 1,
+                record.record_uid,
+                hash
+            ])?;
+        }
+        report.sealed_count += records.len() - sealed_len;
+    }
+    Ok(report)
+}
+
+/// Tauri 命令：封存账号新追加的记录（前端在同步/导入入库后调用）
+///
+/// 封存前先校验已有部分；发现不一致时不写入，直接返回带 divergence 的结果。
+#[tauri::command]
+pub(crate) fn seal_hash_chain(app: AppHandle, uid: String) -> Result<ChainReport, AppError> {
+    let report = with_db_write(&app, "更新记录哈希链", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let report = check_hash_chain(&tx, &uid, true)?;
+        tx.commit()?;
+        Ok(report)
+    })?;
+    if let Some(divergence) = &report.divergence {
+        eprintln!(
+            "[chain] {} 的 {} 记录在第 {} 条处与封存哈希不一致 ({})",
+            uid, divergence.source, divergence.position, divergence.reason
+        );
+    }
+    Ok(report)
+}
+
+/// Tauri 命令：重算账号的哈希链并报告第一处不一致
+#[tauri::command]
+pub(crate) fn verify_chain(app: AppHandle, uid: String) -> Result<ChainReport, AppError> {
+    let conn = open_db(&app)?;
+    check_hash_chain(&conn, &uid, false)
+        .map_err(|e| AppError::database(format!("校验记录哈希链失败: {}", e)))
+}
@@ -0,0 +1,286 @@
+//! 物品图标本地缓存
+//!
+//! 内置图片只覆盖部分角色，其余图标需要从 CDN 获取；每次都直连 CDN 离线时无法显示，也会把使用情况暴露给第三方。
+//! 图标下载一次后保存在 userdata/icons/，通过 asset 协议提供给前端，按最近访问时间淘汰以限制占用空间。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+use crate::app_state;
+use crate::commands::endpoints::normalize_endpoint;
+use crate::config::{current_config, update_config, userdata_dir, write_file_atomic};
+use crate::db::{open_db, PULL_RECORDS_CTE};
+use crate::error::AppError;
+
+/// 图标缓存目录总大小上限
+const ICON_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+/// 单个图标大小上限（超过视为异常响应，不缓存）
+const ICON_MAX_BYTES: usize = 2 * 1024 * 1024;
+/// 图标下载超时
+const ICON_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 正在下载的图标（物品 id → 下载锁），同一图标的并发请求只下载一次
+#[derive(Default)]
+pub(crate) struct IconCacheState(Mutex<HashMap<String, Arc<tauri::async_runtime::Mutex<()>>>>);
+
+fn icon_cache_dir() -> Result<PathBuf, AppError> {
+    Ok(userdata_dir().map_err(AppError::io)?.join("icons"))
+}
+
+/// 物品 id 只允许字母、数字、下划线与连字符（同时用作文件名）
+fn validate_item_id(item_id: &str) -> Result<(), AppError> {
+    let valid = !item_id.is_empty()
+        && item_id.len() <= 64
+        && item_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::validation(format!("无效的物品 id: {}", item_id)))
+    }
+}
+
+fn icon_path(dir: &Path, item_id: &str) -> PathBuf {
+    dir.join(format!("{}.png", item_id))
+}
+
+/// 命中缓存时刷新修改时间，作为 LRU 的最近访问时间
+fn touch_icon(path: &Path) {
+    let result = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = result {
+        eprintln!("[icons] 更新访问时间失败 {}: {}", path.display(), e);
+    }
+}
+
+/// 超出总大小上限时按最近访问时间从旧到新删除图标（`keep` 为刚写入的图标，不删除）
+fn evict_icons(dir: &Path, keep: &Path) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("读取图标缓存目录失败: {}", e))?;
+    let mut icons: Vec<(PathBuf, u64, SystemTime)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            meta.is_file().then(|| {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                (entry.path(), meta.len(), modified)
+            })
+        })
+        .collect();
+    let mut total: u64 = icons.iter().map(|(_, len, _)| len).sum();
+    if total <= ICON_CACHE_MAX_BYTES {
+        return Ok(());
+    }
+    icons.sort_by_key(|(_, _, modified)| *modified);
+    for (path, len, _) in icons {
+        if total <= ICON_CACHE_MAX_BYTES {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => total = total.saturating_sub(len),
+            Err(e) => eprintln!("[icons] 淘汰图标失败 {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+fn icon_client() -> Result<tauri_plugin_http::reqwest::Client, String> {
+    tauri_plugin_http::reqwest::Client::builder()
+        .timeout(ICON_FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+}
+
+async fn download_icon(
+    client: &tauri_plugin_http::reqwest::Client,
+    cdn: &str,
+    item_id: &str,
+) -> Result<Vec<u8>, AppError> {
+    let url = format!("{}/{}.png", cdn, item_id);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::network(None, format!("下载图标失败 {}: {}", url, e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::network(
+            Some(status.as_u16()),
+            format!("下载图标失败 {}: HTTP {}", url, status),
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::network(None, format!("下载图标失败 {}: {}", url, e)))?;
+    if bytes.is_empty() || bytes.len() > ICON_MAX_BYTES {
+        return Err(AppError::network(
+            None,
+            format!("图标大小异常 {}: {} 字节", url, bytes.len()),
+        ));
+    }
+    Ok(bytes.to_vec())
+}
+
+/// 确保图标已缓存，返回本地路径；未配置 CDN 且缓存未命中时返回 None
+async fn ensure_icon(
+    app: &AppHandle,
+    client: &tauri_plugin_http::reqwest::Client,
+    item_id: &str,
+) -> Result<Option<PathBuf>, AppError> {
+    validate_item_id(item_id)?;
+    let dir = icon_cache_dir()?;
+    let path = icon_path(&dir, item_id);
+    if path.is_file() {
+        touch_icon(&path);
+        return Ok(Some(path));
+    }
+    let Some(cdn) = current_config(app).icon_cdn else {
+        return Ok(None);
+    };
+
+    let lock = app_state(app)
+        .icon_cache
+        .0
+        .lock()
+        .unwrap()
+        .entry(item_id.to_string())
+        .or_default()
+        .clone();
+    let _guard = lock.lock().await;
+    // 等待期间其他请求可能已经下载完成
+    let result = if path.is_file() {
+        Ok(Some(path))
+    } else {
+        match download_icon(client, &cdn, item_id).await {
+            Ok(bytes) => std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("创建图标缓存目录失败: {}", e))
+                .and_then(|_| write_file_atomic(&path, &bytes))
+                .and_then(|_| evict_icons(&dir, &path))
+                .map(|_| Some(path))
+                .map_err(AppError::io),
+            Err(e) => Err(e),
+        }
+    };
+    app_state(app).icon_cache.0.lock().unwrap().remove(item_id);
+    result
+}
+
+/// 允许前端通过 asset 协议读取图标缓存目录（启动时调用）
+pub(crate) fn init_icon_cache(app: &AppHandle) {
+    let result = icon_cache_dir().and_then(|dir| {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::io(format!("创建图标缓存目录失败: {}", e)))?;
+        app.asset_protocol_scope()
+            .allow_directory(&dir, false)
+            .map_err(|e| AppError::io(e.to_string()))
+    });
+    if let Err(e) = result {
+        eprintln!("[icons] 初始化图标缓存失败: {}", e);
+    }
+}
+
+/// Tauri 命令：获取物品图标的本地路径（前端用 `convertFileSrc` 转换），缺失时从 CDN 下载
+///
+/// 未配置图标 CDN 且本地没有缓存时返回 None，前端回退到内置图片或占位图。
+#[tauri::command]
+pub(crate) async fn get_item_icon(
+    app: AppHandle,
+    item_id: String,
+) -> Result<Option<String>, AppError> {
+    let client = icon_client()?;
+    let path = ensure_icon(&app, &client, &item_id).await?;
+    Ok(path.map(|path| path.to_string_lossy().into_owned()))
+}
+
+/// 指定账号记录中出现过的物品 id
+fn distinct_item_ids(app: &AppHandle, uids: &[String]) -> Result<Vec<String>, AppError> {
+    if uids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let conn = open_db(app)?;
+    let placeholders = vec!["?"; uids.len()].join(", ");
+    let sql = format!(
+        "{} SELECT DISTINCT item_id FROM pull_records WHERE uid IN ({}) AND item_id != ''",
+        PULL_RECORDS_CTE, placeholders
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询物品列表失败: {}", e)))?;
+    let ids = stmt
+        .query_map(rusqlite::params_from_iter(uids), |row| row.get(0))
+        .map_err(|e| AppError::database(format!("查询物品列表失败: {}", e)))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| AppError::database(format!("读取物品列表失败: {}", e)))?;
+    Ok(ids)
+}
+
+/// Tauri 命令：预下载指定账号记录中尚未缓存的物品图标（同步完成后调用），返回新下载的数量
+///
+/// 逐个下载以免短时间内大量请求 CDN；单个图标失败只记录日志。
+#[tauri::command]
+pub(crate) async fn prefetch_icons(app: AppHandle, uids: Vec<String>) -> Result<u32, AppError> {
+    if current_config(&app).icon_cdn.is_none() {
+        return Ok(0);
+    }
+    let dir = icon_cache_dir()?;
+    let missing: Vec<String> = distinct_item_ids(&app, &uids)?
+        .into_iter()
+        .filter(|id| validate_item_id(id).is_ok() && !icon_path(&dir, id).is_file())
+        .collect();
+    if missing.is_empty() {
+        return Ok(0);
+    }
+
+    let client = icon_client()?;
+    let mut downloaded = 0;
+    for item_id in &missing {
+        match ensure_icon(&app, &client, item_id).await {
+            Ok(Some(_)) => downloaded += 1,
+            Ok(None) => break,
+            Err(e) => eprintln!("[icons] 预下载图标失败 {}: {}", item_id, e),
+        }
+    }
+    Ok(downloaded)
+}
+
+/// Tauri 命令：获取图标 CDN 地址（为空表示不下载图标）
+#[tauri::command]
+pub(crate) fn get_icon_cdn(app: AppHandle) -> Option<String> {
+    current_config(&app).icon_cdn
+}
+
+/// Tauri 命令：设置图标 CDN 地址（图标地址为 `<cdn>/<物品 id>.png`），传空则停止下载
+#[tauri::command]
+pub(crate) fn set_icon_cdn(app: AppHandle, url: Option<String>) -> Result<(), AppError> {
+    let url = match url.as_deref().map(str::trim) {
+        Some(url) if !url.is_empty() => {
+            Some(normalize_endpoint(url).map_err(AppError::validation)?)
+        }
+        _ => None,
+    };
+    update_config(&app, |c| c.icon_cdn = url.clone())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_ids_are_safe_file_names() {
+        assert!(validate_item_id("chr_0016_laevat").is_ok());
+        assert!(validate_item_id("wpn-01").is_ok());
+        assert!(validate_item_id("").is_err());
+        assert!(validate_item_id("../config").is_err());
+        assert!(validate_item_id("a/b").is_err());
+    }
+}
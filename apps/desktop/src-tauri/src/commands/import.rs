@@ -0,0 +1,775 @@
+//! 记录导入（账号 uid 校验）
+//!
+//! 导入文件里的 uid 与数据库现有账号对不上时，多半是选错了文件（例如导入了别人的备份），
+//! 直接合并会把两个账号的记录混在一起污染统计。默认拒绝，前端确认后带 force 重试。
+//! 导入预览（dry-run）只读取比对结果，不报错也不记录日志，由前端在预览中提示。
+//!
+//! 导入去重时同一条记录可能两份来源字段不全（例如一份缺星级），合并时按字段互相补齐而不是直接丢弃。
+//!
+//! 默认按合并模式导入；覆盖模式（replace）先备份数据库，再在同一事务中清空文件中各账号的已有记录并写入文件内容，
+//! 导入后只剩文件内容，中途失败不会留下被清空的账号。
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::db::access::with_db_write;
+use crate::db::backup::backup_database;
+use crate::db::demo::is_demo_mode;
+use crate::db::{open_db, open_db_read_only, table_exists};
+use crate::error::AppError;
+
+/// 导入 uid 不匹配时返回的错误码前缀
+const IMPORT_UID_MISMATCH: &str = "import-uid-mismatch";
+
+/// 导入文件 uid 校验结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ImportUidStatus {
+    /// 文件中的 uid 均已存在于数据库
+    Match,
+    /// 文件中存在数据库里没有的 uid
+    Mismatch,
+    /// 数据库尚无任何账号/记录，接受任意 uid
+    EmptyDatabase,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportUidCheck {
+    status: ImportUidStatus,
+    file_uids: Vec<String>,
+    existing_uids: Vec<String>,
+    /// 数据库中不存在的 uid
+    unknown_uids: Vec<String>,
+}
+
+/// 读取数据库中已有的全部 uid（账号表与两张记录表的并集）
+fn existing_uids(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let parts: Vec<String> = ["accounts", "gacha_records", "weapon_records"]
+        .into_iter()
+        .filter_map(|table| match table_exists(conn, table) {
+            Ok(true) => Some(Ok(format!("SELECT uid FROM {}", table))),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<Result<_, _>>()?;
+    if parts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sql = format!("{} ORDER BY uid", parts.join(" UNION "));
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询已有账号失败: {}", e)))?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::database(format!("查询已有账号失败: {}", e)))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| AppError::database(format!("查询已有账号失败: {}", e)))
+}
+
+/// 比对导入文件中的 uid 与数据库已有 uid
+fn check_import_uids(conn: &Connection, file_uids: &[String]) -> Result<ImportUidCheck, AppError> {
+    let mut file_uids: Vec<String> = file_uids
+        .iter()
+        .map(|uid| uid.trim().to_string())
+        .filter(|uid| !uid.is_empty())
+        .collect();
+    file_uids.sort();
+    file_uids.dedup();
+
+    let existing = existing_uids(conn)?;
+    let unknown: Vec<String> = file_uids
+        .iter()
+        .filter(|uid| !existing.contains(uid))
+        .cloned()
+        .collect();
+    let status = if existing.is_empty() {
+        ImportUidStatus::EmptyDatabase
+    } else if unknown.is_empty() {
+        ImportUidStatus::Match
+    } else {
+        ImportUidStatus::Mismatch
+    };
+    Ok(ImportUidCheck {
+        status,
+        file_uids,
+        existing_uids: existing,
+        unknown_uids: unknown,
+    })
+}
+
+/// Tauri 命令：导入前校验文件 uid。不匹配且未 force 时返回带错误码的警告；
+/// dry_run 时原样返回比对结果，不报错、无副作用
+#[tauri::command]
+pub(crate) fn check_import_uid(
+    app: AppHandle,
+    file_uids: Vec<String>,
+    force: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<ImportUidCheck, AppError> {
+    if dry_run.unwrap_or(false) {
+        let conn = open_db_read_only(&app)?;
+        return check_import_uids(&conn, &file_uids);
+    }
+    let conn = open_db(&app)?;
+    let check = check_import_uids(&conn, &file_uids)?;
+    match check.status {
+        ImportUidStatus::Mismatch if !force.unwrap_or(false) => {
+            return Err(AppError::conflict(
+                IMPORT_UID_MISMATCH,
+                format!(
+                    "{}: 导入文件中的账号 {} 与当前数据中的账号 {} 不一致，可能选错了文件。确认无误后可强制导入",
+                    IMPORT_UID_MISMATCH,
+                    check.unknown_uids.join(", "),
+                    check.existing_uids.join(", ")
+                ),
+            ));
+        }
+        ImportUidStatus::Mismatch => {
+            eprintln!(
+                "[import] 强制导入不匹配的账号: {}",
+                check.unknown_uids.join(", ")
+            );
+        }
+        ImportUidStatus::EmptyDatabase => {
+            eprintln!(
+                "[import] 数据库为空，接受导入账号: {}",
+                check.file_uids.join(", ")
+            );
+        }
+        ImportUidStatus::Match => {}
+    }
+    Ok(check)
+}
+
+// ============== 去重合并时补齐缺失字段 ==============
+
+/// 导入记录中去重时可能缺失的字段（其余字段参与 record_uid 计算，同一条记录必然一致）
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportedRecordFields {
+    record_uid: String,
+    /// character / weapon
+    category: String,
+    pool_name: Option<String>,
+    /// char_id / weapon_id
+    item_id: Option<String>,
+    /// char_name / weapon_name
+    name: Option<String>,
+    /// 武器类型（角色记录没有该字段）
+    item_type: Option<String>,
+    rarity: Option<i64>,
+    note: Option<String>,
+}
+
+fn text_missing(value: &Option<String>) -> bool {
+    value.as_deref().is_none_or(|text| text.trim().is_empty())
+}
+
+/// 用 source 补齐 target 中缺失（为空或空串、星级非正数）的字段，已有的值不覆盖；返回是否有改动
+fn fill_missing_fields(target: &mut ImportedRecordFields, source: &ImportedRecordFields) -> bool {
+    let mut changed = false;
+    for (field, incoming) in [
+        (&mut target.pool_name, &source.pool_name),
+        (&mut target.item_id, &source.item_id),
+        (&mut target.name, &source.name),
+        (&mut target.item_type, &source.item_type),
+        (&mut target.note, &source.note),
+    ] {
+        if text_missing(field) && !text_missing(incoming) {
+            *field = incoming.clone();
+            changed = true;
+        }
+    }
+    let rarity_known = |rarity: Option<i64>| rarity.is_some_and(|r| r > 0);
+    if !rarity_known(target.rarity) && rarity_known(source.rarity) {
+        target.rarity = source.rarity;
+        changed = true;
+    }
+    changed
+}
+
+/// 记录表名与 (物品 id, 名称, 物品类型) 列名
+fn record_columns(category: &str) -> (&'static str, &'static str, &'static str, &'static str) {
+    if category == "weapon" {
+        ("weapon_records", "weapon_id", "weapon_name", "weapon_type")
+    } else {
+        ("gacha_records", "char_id", "char_name", "NULL")
+    }
+}
+
+/// 把导入文件中与已有记录重复的条目合并进数据库，返回被补齐的记录数
+fn merge_into_existing(
+    conn: &Connection,
+    records: &[ImportedRecordFields],
+) -> rusqlite::Result<usize> {
+    let mut merged = 0;
+    for incoming in records {
+        let (table, id_col, name_col, type_col) = record_columns(&incoming.category);
+        let existing = conn
+            .query_row(
+                &format!(
+                    "SELECT pool_name, {}, {}, {}, rarity, note FROM {} WHERE record_uid = ?1",
+                    id_col, name_col, type_col, table
+                ),
+                [&incoming.record_uid],
+                |row| {
+                    Ok(ImportedRecordFields {
+                        record_uid: incoming.record_uid.clone(),
+                        category: incoming.category.clone(),
+                        pool_name: row.get(0)?,
+                        item_id: row.get(1)?,
+                        name: row.get(2)?,
+                        item_type: row.get(3)?,
+                        rarity: row.get(4)?,
+                        note: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut existing) = existing else {
+            continue;
+        };
+        if !fill_missing_fields(&mut existing, incoming) {
+            continue;
+        }
+        let type_set = if incoming.category == "weapon" {
+            format!(", {} = ?5", type_col)
+        } else {
+            String::new()
+        };
+        conn.execute(
+            &format!(
+                "UPDATE {} SET pool_name = ?1, {} = ?2, {} = ?3, rarity = ?4{}, note = ?6
+                 WHERE record_uid = ?7",
+                table, id_col, name_col, type_set
+            ),
+            rusqlite::params![
+                existing.pool_name.unwrap_or_default(),
+                existing.item_id.unwrap_or_default(),
+                existing.name.unwrap_or_default(),
+                existing.rarity.unwrap_or_default(),
+                existing.item_type.unwrap_or_default(),
+                existing.note,
+                existing.record_uid,
+            ],
+        )?;
+        merged += 1;
+    }
+    Ok(merged)
+}
+
+/// Tauri 命令：导入时与已有记录重复的条目不再直接丢弃，用导入文件补齐已有记录缺失的字段
+///
+/// 只补空缺，不覆盖已有的值；返回被补齐的记录数。
+#[tauri::command]
+pub(crate) fn merge_duplicate_records(
+    app: AppHandle,
+    records: Vec<ImportedRecordFields>,
+) -> Result<usize, AppError> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+    let merged = with_db_write(&app, "导入合并重复记录", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let merged = merge_into_existing(&tx, &records)?;
+        tx.commit()?;
+        Ok(merged)
+    })?;
+    if merged > 0 {
+        eprintln!("[import] 用导入文件补齐了 {} 条已有记录的缺失字段", merged);
+    }
+    Ok(merged)
+}
+
+// ============== 覆盖导入 ==============
+
+/// 覆盖导入时按 uid 清空的表（账号、昵称与同步历史保留，账号信息随后由导入文件更新）
+const REPLACE_TABLES: &[&str] = &[
+    "gacha_records",
+    "weapon_records",
+    "record_hash_chain",
+    "custom_endpoint_records",
+];
+
+/// 覆盖导入写入的角色记录（字段与 gacha_records 表一致）
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ReplaceCharRecord {
+    record_uid: String,
+    uid: String,
+    pool_id: String,
+    pool_name: String,
+    char_id: String,
+    char_name: String,
+    rarity: i64,
+    is_new: i64,
+    is_free: i64,
+    gacha_ts: String,
+    seq_id: String,
+    fetched_at: i64,
+}
+
+/// 覆盖导入写入的武器记录（字段与 weapon_records 表一致）
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ReplaceWeaponRecord {
+    record_uid: String,
+    uid: String,
+    pool_id: String,
+    pool_name: String,
+    weapon_id: String,
+    weapon_name: String,
+    weapon_type: String,
+    rarity: i64,
+    is_new: i64,
+    gacha_ts: String,
+    seq_id: String,
+    fetched_at: i64,
+}
+
+/// 覆盖导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportReplaceResult {
+    /// 清空前创建的备份（演示模式不备份）
+    backup_path: Option<String>,
+    /// 删除的抽卡记录数（角色 + 武器）
+    deleted_records: u64,
+    /// 写入的抽卡记录数（角色 + 武器）
+    inserted_records: u64,
+}
+
+/// 清空指定账号在各表中的数据，返回删除的抽卡记录数
+fn clear_uid_records(conn: &Connection, tables: &[&str], uids: &[String]) -> rusqlite::Result<u64> {
+    let mut deleted = 0;
+    for table in tables {
+        for uid in uids {
+            let rows = conn.execute(&format!("DELETE FROM {} WHERE uid = ?1", table), [uid])?;
+            if *table != "record_hash_chain" {
+                deleted += rows as u64;
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+/// 写入导入文件中的记录，返回实际新增条数
+fn insert_replace_records(
+    conn: &Connection,
+    chars: &[ReplaceCharRecord],
+    weapons: &[ReplaceWeaponRecord],
+) -> rusqlite::Result<u64> {
+    let mut inserted = 0;
+    let mut insert = conn.prepare(
+        "INSERT OR IGNORE INTO gacha_records (record_uid, uid, pool_id, pool_name, char_id,
+             char_name, rarity, is_new, is_free, gacha_ts, seq_id, fetched_at, category)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'character')",
+    )?;
+    for r in chars {
+        inserted += insert.execute(rusqlite::params![
+            r.record_uid,
+            r.uid,
+            r.pool_id,
+            r.pool_name,
+            r.char_id,
+            r.char_name,
+            r.rarity,
+            r.is_new,
+            r.is_free,
+            r.gacha_ts,
+            r.seq_id,
+            r.fetched_at,
+        ])? as u64;
+    }
+    if weapons.is_empty() {
+        return Ok(inserted);
+    }
+    let mut insert = conn.prepare(
+        "INSERT OR IGNORE INTO weapon_records (record_uid, uid, pool_id, pool_name, weapon_id,
+             weapon_name, weapon_type, rarity, is_new, gacha_ts, seq_id, fetched_at, category)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'weapon')",
+    )?;
+    for r in weapons {
+        inserted += insert.execute(rusqlite::params![
+            r.record_uid,
+            r.uid,
+            r.pool_id,
+            r.pool_name,
+            r.weapon_id,
+            r.weapon_name,
+            r.weapon_type,
+            r.rarity,
+            r.is_new,
+            r.gacha_ts,
+            r.seq_id,
+            r.fetched_at,
+        ])? as u64;
+    }
+    Ok(inserted)
+}
+
+/// 在同一事务中清空账号记录并写入导入文件中的记录，任一步失败都回滚，返回（删除数, 新增数）
+fn replace_uid_records(
+    conn: &Connection,
+    tables: &[&str],
+    uids: &[String],
+    chars: &[ReplaceCharRecord],
+    weapons: &[ReplaceWeaponRecord],
+) -> rusqlite::Result<(u64, u64)> {
+    let tx = conn.unchecked_transaction()?;
+    let deleted = clear_uid_records(&tx, tables, uids)?;
+    let inserted = insert_replace_records(&tx, chars, weapons)?;
+    tx.commit()?;
+    Ok((deleted, inserted))
+}
+
+/// Tauri 命令：覆盖导入，备份数据库后清空导入文件中各账号的已有记录并写入文件中的记录
+///
+/// 不可撤销（只能从备份恢复），`confirm_replace` 必须为 true 才会执行；备份失败时不清空。
+/// 清空与写入在同一事务中完成，写入失败时数据库保持导入前的状态。
+#[tauri::command]
+pub(crate) fn import_replace_records(
+    app: AppHandle,
+    uids: Vec<String>,
+    char_records: Vec<ReplaceCharRecord>,
+    weapon_records: Vec<ReplaceWeaponRecord>,
+    confirm_replace: bool,
+) -> Result<ImportReplaceResult, AppError> {
+    if !confirm_replace {
+        return Err(AppError::validation(
+            "覆盖导入会清空账号的现有记录，需要确认",
+        ));
+    }
+    let mut uids: Vec<String> = uids
+        .iter()
+        .map(|uid| uid.trim().to_string())
+        .filter(|uid| !uid.is_empty())
+        .collect();
+    uids.sort();
+    uids.dedup();
+    if uids.is_empty() {
+        return Err(AppError::validation("导入文件中没有账号"));
+    }
+    if let Some(r) = char_records
+        .iter()
+        .map(|r| &r.uid)
+        .chain(weapon_records.iter().map(|r| &r.uid))
+        .find(|uid| uids.binary_search(uid).is_err())
+    {
+        return Err(AppError::validation(format!(
+            "记录的账号 {} 不在覆盖范围内",
+            r
+        )));
+    }
+
+    let backup_path = if is_demo_mode(&app) {
+        None
+    } else {
+        let backup = backup_database(&app, "before-import-replace")?;
+        Some(backup.to_string_lossy().into_owned())
+    };
+
+    // 旧版数据库可能还没有 Rust 端的表，只清理已存在的
+    let tables: Vec<&str> = {
+        let conn = open_db(&app)?;
+        let mut tables = Vec::new();
+        for table in REPLACE_TABLES {
+            if table_exists(&conn, table)? {
+                tables.push(*table);
+            }
+        }
+        tables
+    };
+    let (deleted_records, inserted_records) = with_db_write(&app, "覆盖导入", |conn| {
+        replace_uid_records(conn, &tables, &uids, &char_records, &weapon_records)
+    })?;
+    eprintln!(
+        "[import] 覆盖导入：已清空 {} 的 {} 条记录，写入 {} 条",
+        uids.join(", "),
+        deleted_records,
+        inserted_records
+    );
+    Ok(ImportReplaceResult {
+        backup_path,
+        deleted_records,
+        inserted_records,
+    })
+}
+
+#[cfg(test)]
+mod import_uid_tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    fn db_with(uids: &[&str]) -> Connection {
+        let conn = test_db();
+        for uid in uids {
+            insert_test_row(&conn, "accounts", &[("uid", uid)]);
+        }
+        conn
+    }
+
+    fn uids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|uid| uid.to_string()).collect()
+    }
+
+    #[test]
+    fn matching_uid_is_accepted() {
+        let conn = db_with(&["1001", "1002"]);
+        let check = check_import_uids(&conn, &uids(&["1002", "1002"])).unwrap();
+        assert_eq!(check.status, ImportUidStatus::Match);
+        assert_eq!(check.file_uids, uids(&["1002"]));
+        assert!(check.unknown_uids.is_empty());
+    }
+
+    #[test]
+    fn mismatched_uid_is_reported() {
+        let conn = db_with(&["1001"]);
+        insert_test_row(
+            &conn,
+            "weapon_records",
+            &[
+                ("record_uid", &"1003_weapon_1"),
+                ("uid", &"1003"),
+                ("seq_id", &"1"),
+            ],
+        );
+        let check = check_import_uids(&conn, &uids(&["1003", "2002"])).unwrap();
+        assert_eq!(check.status, ImportUidStatus::Mismatch);
+        assert_eq!(check.existing_uids, uids(&["1001", "1003"]));
+        assert_eq!(check.unknown_uids, uids(&["2002"]));
+    }
+
+    #[test]
+    fn empty_database_accepts_any_uid() {
+        let conn = db_with(&[]);
+        let check = check_import_uids(&conn, &uids(&["3003"])).unwrap();
+        assert_eq!(check.status, ImportUidStatus::EmptyDatabase);
+        assert_eq!(check.unknown_uids, uids(&["3003"]));
+
+        let bare = Connection::open_in_memory().unwrap();
+        let check = check_import_uids(&bare, &uids(&["3003"])).unwrap();
+        assert_eq!(check.status, ImportUidStatus::EmptyDatabase);
+    }
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    fn weapon(record_uid: &str) -> ImportedRecordFields {
+        ImportedRecordFields {
+            record_uid: record_uid.to_string(),
+            category: "weapon".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn duplicate_records_complement_each_other() {
+        let mut first = ImportedRecordFields {
+            pool_name: Some("武器池".to_string()),
+            name: Some("某武器".to_string()),
+            rarity: None,
+            item_type: Some(String::new()),
+            ..weapon("r1")
+        };
+        let second = ImportedRecordFields {
+            pool_name: Some("另一个名字".to_string()),
+            item_id: Some("wpn_001".to_string()),
+            item_type: Some("sword".to_string()),
+            rarity: Some(6),
+            note: Some("备注".to_string()),
+            ..weapon("r1")
+        };
+        assert!(fill_missing_fields(&mut first, &second));
+        assert_eq!(
+            first,
+            ImportedRecordFields {
+                pool_name: Some("武器池".to_string()),
+                item_id: Some("wpn_001".to_string()),
+                name: Some("某武器".to_string()),
+                item_type: Some("sword".to_string()),
+                rarity: Some(6),
+                note: Some("备注".to_string()),
+                ..weapon("r1")
+            }
+        );
+        assert!(!fill_missing_fields(&mut first, &second));
+    }
+
+    #[test]
+    fn merge_fills_existing_row() {
+        let conn = test_db();
+        insert_test_row(
+            &conn,
+            "weapon_records",
+            &[
+                ("record_uid", &"r1"),
+                ("pool_name", &"武器池"),
+                ("weapon_name", &"某武器"),
+                ("weapon_type", &"sword"),
+            ],
+        );
+        let incoming = ImportedRecordFields {
+            item_id: Some("wpn_001".to_string()),
+            rarity: Some(5),
+            ..weapon("r1")
+        };
+        assert_eq!(
+            merge_into_existing(&conn, &[incoming, weapon("r2")]).unwrap(),
+            1
+        );
+        let row: (String, String, i64) = conn
+            .query_row(
+                "SELECT weapon_id, pool_name, rarity FROM weapon_records WHERE record_uid = 'r1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(row, ("wpn_001".to_string(), "武器池".to_string(), 5));
+    }
+}
+
+#[cfg(test)]
+mod replace_tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    const TABLES: &[&str] = &["gacha_records", "weapon_records"];
+
+    /// 两个账号的角色记录与一条武器记录
+    fn records_db() -> Connection {
+        let conn = test_db();
+        for (uid, seq_id) in [("1", "1"), ("1", "2"), ("2", "1")] {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &format!("{}_char_{}", uid, seq_id)),
+                    ("uid", &uid),
+                    ("rarity", &4),
+                    ("gacha_ts", &seq_id),
+                    ("seq_id", &seq_id),
+                ],
+            );
+        }
+        insert_test_row(
+            &conn,
+            "weapon_records",
+            &[
+                ("record_uid", &"1_weapon_1"),
+                ("uid", &"1"),
+                ("rarity", &4),
+                ("gacha_ts", &"1"),
+                ("seq_id", &"1"),
+            ],
+        );
+        conn
+    }
+
+    fn char_record(uid: &str, seq_id: &str) -> ReplaceCharRecord {
+        ReplaceCharRecord {
+            record_uid: format!("{}_char_{}", uid, seq_id),
+            uid: uid.to_string(),
+            pool_id: "p".to_string(),
+            pool_name: "p".to_string(),
+            char_id: "c".to_string(),
+            char_name: "c".to_string(),
+            rarity: 5,
+            is_new: 0,
+            is_free: 0,
+            gacha_ts: seq_id.to_string(),
+            seq_id: seq_id.to_string(),
+            fetched_at: 0,
+        }
+    }
+
+    fn rows(conn: &Connection, sql: &str) -> Vec<String> {
+        conn.prepare(sql)
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn replace_leaves_only_file_records() {
+        let conn = records_db();
+        // 导入文件中只有 1_char_2 与 1_char_3
+        let chars = [char_record("1", "2"), char_record("1", "3")];
+        let (deleted, inserted) =
+            replace_uid_records(&conn, TABLES, &["1".to_string()], &chars, &[]).unwrap();
+        assert_eq!((deleted, inserted), (3, 2));
+
+        assert_eq!(
+            rows(
+                &conn,
+                "SELECT record_uid FROM gacha_records WHERE uid = '1' ORDER BY record_uid"
+            ),
+            ["1_char_2", "1_char_3"]
+        );
+        assert_eq!(
+            rows(
+                &conn,
+                "SELECT CAST(rarity AS TEXT) FROM gacha_records WHERE record_uid = '1_char_2'"
+            ),
+            ["5"]
+        );
+        assert!(rows(
+            &conn,
+            "SELECT record_uid FROM weapon_records WHERE uid = '1'"
+        )
+        .is_empty());
+        // 其他账号不受影响
+        assert_eq!(
+            rows(
+                &conn,
+                "SELECT record_uid FROM gacha_records WHERE uid = '2'"
+            ),
+            ["2_char_1"]
+        );
+    }
+
+    #[test]
+    fn failed_insert_keeps_existing_records() {
+        let conn = records_db();
+        // 写入中途失败（武器表缺列），清空也应一并回滚
+        conn.execute_batch(
+            "DROP TABLE weapon_records;
+             CREATE TABLE weapon_records (record_uid TEXT PRIMARY KEY, uid TEXT);",
+        )
+        .unwrap();
+        let weapon = ReplaceWeaponRecord {
+            record_uid: "1_weapon_2".to_string(),
+            uid: "1".to_string(),
+            pool_id: "w".to_string(),
+            pool_name: "w".to_string(),
+            weapon_id: "x".to_string(),
+            weapon_name: "x".to_string(),
+            weapon_type: "sword".to_string(),
+            rarity: 4,
+            is_new: 0,
+            gacha_ts: "2".to_string(),
+            seq_id: "2".to_string(),
+            fetched_at: 0,
+        };
+        assert!(replace_uid_records(
+            &conn,
+            TABLES,
+            &["1".to_string()],
+            &[char_record("1", "3")],
+            &[weapon],
+        )
+        .is_err());
+        assert_eq!(
+            rows(
+                &conn,
+                "SELECT record_uid FROM gacha_records WHERE uid = '1' ORDER BY record_uid"
+            ),
+            ["1_char_1", "1_char_2"]
+        );
+    }
+}
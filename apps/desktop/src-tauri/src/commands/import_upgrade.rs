@@ -0,0 +1,187 @@
+//! 旧版本导出文件升级
+//!
+//! 本工具历次导出的 JSON 格式以 `schemaVersion` 区分，导入前统一升级为当前格式，前端与预检只处理最新结构：
+//! - v1：`records` 为全部记录，早期武器记录也混在其中（带 weaponId / weaponName），没有 `weaponRecords`
+//! - v2（当前）：角色记录在 `records`，武器记录单独放在 `weaponRecords`
+//!
+//! 没有版本标记的文件按最早格式（v1）尝试；版本号高于当前的文件来自更新版本的程序，拒绝导入而不是猜测字段。
+
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+/// 当前导出格式版本（与前端 exportData 写入的 schemaVersion 一致）
+const CURRENT_EXPORT_SCHEMA_VERSION: u64 = 2;
+/// 没有版本标记时按此版本解析
+const EARLIEST_EXPORT_SCHEMA_VERSION: u64 = 1;
+
+/// 读取版本标记（兼容写成字符串的数字），缺失时返回 None
+fn schema_version(data: &Map<String, Value>) -> Result<Option<u64>, String> {
+    match data.get("schemaVersion") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("无效的 schemaVersion: {}", n)),
+        Some(Value::String(s)) => s
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("无效的 schemaVersion: {:?}", s)),
+        Some(other) => Err(format!("无效的 schemaVersion: {}", other)),
+    }
+}
+
+/// v1 记录是否其实是武器记录（当时角色与武器共用 records 数组）
+fn is_v1_weapon_record(record: &Value) -> bool {
+    record.get("category").and_then(Value::as_str) == Some("weapon")
+        || record.get("weaponId").is_some_and(|v| !v.is_null())
+        || record.get("weaponName").is_some_and(|v| !v.is_null())
+}
+
+/// v1 → v2：把混在 records 中的武器记录拆到 weaponRecords，并补上 category
+fn upgrade_v1(data: &mut Map<String, Value>) -> Result<(), String> {
+    let records = match data.remove("records") {
+        Some(Value::Array(records)) => records,
+        _ => return Err("无效的数据格式：缺少 records 数组".to_string()),
+    };
+    let (weapons, chars): (Vec<Value>, Vec<Value>) =
+        records.into_iter().partition(is_v1_weapon_record);
+    let tag = |mut records: Vec<Value>, category: &str| {
+        for record in &mut records {
+            if let Some(record) = record.as_object_mut() {
+                record.insert("category".to_string(), Value::from(category));
+            }
+        }
+        Value::Array(records)
+    };
+    data.insert("records".to_string(), tag(chars, "character"));
+    data.insert("weaponRecords".to_string(), tag(weapons, "weapon"));
+    Ok(())
+}
+
+/// 把任一历史版本的导出数据升级为当前格式，返回升级后的数据与原始版本号
+pub(crate) fn upgrade_export_data(data: Value) -> Result<(Value, u64), String> {
+    let Value::Object(mut data) = data else {
+        return Err("无效的数据格式：顶层不是 JSON 对象".to_string());
+    };
+    let source = schema_version(&data)?.unwrap_or(EARLIEST_EXPORT_SCHEMA_VERSION);
+    if source == 0 || source > CURRENT_EXPORT_SCHEMA_VERSION {
+        return Err(format!(
+            "不支持的导出格式版本 {}（当前程序支持 1-{}），请升级后再导入",
+            source, CURRENT_EXPORT_SCHEMA_VERSION
+        ));
+    }
+    if source < 2 {
+        upgrade_v1(&mut data)?;
+    }
+    if !data.get("records").is_some_and(Value::is_array) {
+        return Err("无效的数据格式：缺少 records 数组".to_string());
+    }
+    if !data.get("accounts").is_some_and(Value::is_array) {
+        data.insert("accounts".to_string(), Value::Array(Vec::new()));
+    }
+    if !data.get("weaponRecords").is_some_and(Value::is_array) {
+        data.insert("weaponRecords".to_string(), Value::Array(Vec::new()));
+    }
+    data.insert(
+        "schemaVersion".to_string(),
+        Value::from(CURRENT_EXPORT_SCHEMA_VERSION),
+    );
+    Ok((Value::Object(data), source))
+}
+
+/// 解析导出文件文本（忽略 BOM）并升级为当前格式
+pub(crate) fn parse_export_json(content: &str) -> Result<Value, String> {
+    let data: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let (data, source) = upgrade_export_data(data)?;
+    if source < CURRENT_EXPORT_SCHEMA_VERSION {
+        eprintln!(
+            "[import] 导入文件为 v{} 格式，已升级为 v{}",
+            source, CURRENT_EXPORT_SCHEMA_VERSION
+        );
+    }
+    Ok(data)
+}
+
+/// Tauri 命令：解析导入的 JSON 文本，旧版本格式升级为当前格式后返回
+#[tauri::command]
+pub(crate) fn upgrade_import_json(content: String) -> Result<Value, AppError> {
+    parse_export_json(&content).map_err(AppError::validation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(data: &Value, key: &str) -> Vec<String> {
+        data[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["recordUid"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn upgrades_v1_with_mixed_records() {
+        let v1 = r#"{"schemaVersion":1,"exportedAt":1767225600000,
+            "accounts":[{"uid":"100","channelName":"官服"}],
+            "records":[
+                {"recordUid":"c1","uid":"100","charId":"chr_0016_laevat","charName":"莱万汀","rarity":6,"gachaTs":"1767225600000","seqId":"1"},
+                {"recordUid":"w1","uid":"100","weaponId":"wpn_1","weaponName":"Blade","rarity":6,"gachaTs":"1767225600000","seqId":"2"},
+                {"recordUid":"w2","uid":"100","category":"weapon","rarity":4,"gachaTs":"1767225600000","seqId":"3"}
+            ]}"#;
+        let data = parse_export_json(v1).unwrap();
+        assert_eq!(data["schemaVersion"], 2);
+        assert_eq!(names(&data, "records"), vec!["c1"]);
+        assert_eq!(names(&data, "weaponRecords"), vec!["w1", "w2"]);
+        assert_eq!(data["records"][0]["category"], "character");
+        assert_eq!(data["weaponRecords"][0]["category"], "weapon");
+        assert_eq!(data["accounts"][0]["uid"], "100");
+    }
+
+    #[test]
+    fn unmarked_file_is_read_as_earliest_format() {
+        let unmarked = "\u{feff}{\"records\":[\
+            {\"recordUid\":\"c1\",\"uid\":\"100\",\"charName\":\"Alpha\"},\
+            {\"recordUid\":\"w1\",\"uid\":\"100\",\"weaponName\":\"Blade\"}]}";
+        let data = parse_export_json(unmarked).unwrap();
+        assert_eq!(data["schemaVersion"], 2);
+        assert_eq!(names(&data, "records"), vec!["c1"]);
+        assert_eq!(names(&data, "weaponRecords"), vec!["w1"]);
+        assert_eq!(data["accounts"], json!([]));
+    }
+
+    #[test]
+    fn current_format_passes_through_unchanged() {
+        let v2 = json!({
+            "schemaVersion": 2,
+            "exportedAt": 1767225600000u64,
+            "accounts": [],
+            "records": [{"recordUid": "c1", "uid": "100", "category": "character"}],
+            "weaponRecords": [{"recordUid": "w1", "uid": "100", "category": "weapon"}],
+        });
+        let (data, source) = upgrade_export_data(v2.clone()).unwrap();
+        assert_eq!(source, 2);
+        assert_eq!(data, v2);
+        assert_eq!(
+            upgrade_export_data(json!({"schemaVersion": "2", "records": []}))
+                .unwrap()
+                .1,
+            2
+        );
+    }
+
+    #[test]
+    fn rejects_newer_or_malformed_files() {
+        assert!(upgrade_export_data(json!({"schemaVersion": 3, "records": []})).is_err());
+        assert!(upgrade_export_data(json!({"schemaVersion": "v2", "records": []})).is_err());
+        assert!(upgrade_export_data(json!({"schemaVersion": 2})).is_err());
+        assert!(upgrade_export_data(json!({})).is_err());
+        assert!(upgrade_export_data(json!([])).is_err());
+        assert!(parse_export_json("not json").is_err());
+    }
+}
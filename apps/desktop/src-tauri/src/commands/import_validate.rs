@@ -0,0 +1,390 @@
+//! 导入文件预检（dry-run）
+//!
+//! 正式导入前先把文件完整解析一遍，逐行校验字段，汇总错误行与预计新增条数，全程只读数据库。
+//! 支持格式与前端导入一致：JSON（历史版本先升级为当前格式，见 import_upgrade）
+//! 以及角色、武器、统一三种表头的 CSV。
+
+use std::collections::HashSet;
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::commands::import_upgrade::parse_export_json;
+use crate::db::{current_db_file, open_db_read_only, parse_gacha_ts, table_exists, TOP_RARITY};
+use crate::error::AppError;
+
+/// 报告中最多列出的错误行数（计数仍覆盖全部行），避免坏文件把几万条错误塞给前端
+const MAX_REPORTED_ERRORS: usize = 200;
+
+/// 单行校验错误。row 对 CSV 为文件行号（表头为第 1 行），对 JSON 为记录序号（从 1 开始）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RowError {
+    row: u64,
+    field: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportValidationReport {
+    total_rows: u64,
+    valid_rows: u64,
+    invalid_rows: u64,
+    errors: Vec<RowError>,
+    /// 按当前数据库估算的新增条数（文件内重复与库中已有的 record_uid 不计）
+    estimated_new_records: u64,
+}
+
+/// 解析出的一行待导入记录（统一成角色/武器共用的字段）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ImportRow {
+    row: u64,
+    category: String,
+    record_uid: String,
+    uid: String,
+    item_id: String,
+    item_name: String,
+    rarity: String,
+    gacha_ts: String,
+    seq_id: String,
+}
+
+fn row_error(row: u64, field: Option<&str>, message: impl Into<String>) -> RowError {
+    RowError {
+        row,
+        field: field.map(str::to_string),
+        message: message.into(),
+    }
+}
+
+// ============== 解析 ==============
+
+/// 按前端 parseCSVLine 的规则拆分一行：双引号包裹的字段可含逗号，`""` 表示字面双引号
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// 解析 CSV 内容。表头无法识别时直接返回错误；列数不足的行记为错误行（前端导入会跳过这些行）
+fn parse_csv_rows(content: &str) -> Result<(Vec<ImportRow>, Vec<RowError>), String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header_line)) = lines.next() else {
+        return Err("CSV 文件为空或格式错误".to_string());
+    };
+    let headers = parse_csv_line(header_line);
+    let has = |name: &str| headers.iter().any(|h| h == name);
+    let (id_col, name_col) = if has("itemId") && has("itemName") && has("category") {
+        ("itemId", "itemName")
+    } else if has("charId") && has("charName") {
+        ("charId", "charName")
+    } else if has("weaponId") && has("weaponName") {
+        ("weaponId", "weaponName")
+    } else {
+        return Err("无法识别的 CSV 格式，请使用本软件导出的 CSV 文件".to_string());
+    };
+    let default_category = match id_col {
+        "charId" => "character",
+        "weaponId" => "weapon",
+        _ => "",
+    };
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in lines {
+        let row = index as u64 + 1;
+        let fields = parse_csv_line(line);
+        if fields.len() < headers.len() {
+            errors.push(row_error(
+                row,
+                None,
+                format!(
+                    "列数不足：期望 {} 列，实际 {} 列",
+                    headers.len(),
+                    fields.len()
+                ),
+            ));
+            continue;
+        }
+        let get = |key: &str| -> String {
+            headers
+                .iter()
+                .position(|h| h == key)
+                .and_then(|i| fields.get(i))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let category = get("category");
+        rows.push(ImportRow {
+            row,
+            category: if category.is_empty() {
+                default_category.to_string()
+            } else {
+                category
+            },
+            record_uid: get("recordUid"),
+            uid: get("uid"),
+            item_id: get(id_col),
+            item_name: get(name_col),
+            rarity: get("rarity"),
+            gacha_ts: get("gachaTs"),
+            seq_id: get("seqId"),
+        });
+    }
+    Ok((rows, errors))
+}
+
+/// JSON 字段按文本读取（数字也接受，导出文件里 rarity 等为数字）
+fn json_text(record: &Value, key: &str) -> String {
+    match record.get(key) {
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// 解析 JSON 导出文件（旧版本格式先升级；records 为角色记录，weaponRecords 为武器记录）
+fn parse_json_rows(content: &str) -> Result<(Vec<ImportRow>, Vec<RowError>), String> {
+    let data = parse_export_json(content)?;
+    let Some(records) = data.get("records").and_then(Value::as_array) else {
+        return Err("无效的数据格式：缺少 records 数组".to_string());
+    };
+    let weapons = data
+        .get("weaponRecords")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let sections = [
+        (records.as_slice(), "character", "charId", "charName"),
+        (weapons, "weapon", "weaponId", "weaponName"),
+    ];
+    for (items, category, id_key, name_key) in sections {
+        for record in items {
+            let row = (rows.len() + errors.len()) as u64 + 1;
+            if !record.is_object() {
+                errors.push(row_error(row, None, "记录不是 JSON 对象"));
+                continue;
+            }
+            rows.push(ImportRow {
+                row,
+                category: category.to_string(),
+                record_uid: json_text(record, "recordUid"),
+                uid: json_text(record, "uid"),
+                item_id: json_text(record, id_key),
+                item_name: json_text(record, name_key),
+                rarity: json_text(record, "rarity"),
+                gacha_ts: json_text(record, "gachaTs"),
+                seq_id: json_text(record, "seqId"),
+            });
+        }
+    }
+    Ok((rows, errors))
+}
+
+// ============== 校验 ==============
+
+/// 校验一行的各字段，返回该行全部错误（空表示通过）。
+/// 星级缺失（空或 0）不算错误：导入时会按内置多语言元数据补全
+fn validate_row(row: &ImportRow) -> Vec<RowError> {
+    let mut errors = Vec::new();
+    let mut fail =
+        |field: &str, message: String| errors.push(row_error(row.row, Some(field), message));
+
+    if row.category != "character" && row.category != "weapon" {
+        fail("category", format!("未知的记录类型: {:?}", row.category));
+    }
+    if row.record_uid.is_empty() {
+        fail("recordUid", "recordUid 为空".to_string());
+    }
+    if row.uid.is_empty() {
+        fail("uid", "uid 为空".to_string());
+    }
+    if row.item_id.is_empty() && row.item_name.is_empty() {
+        fail("itemName", "物品 ID 与名称均为空".to_string());
+    }
+    if !row.rarity.is_empty() {
+        match row.rarity.parse::<i64>() {
+            Ok(0) => {}
+            Ok(r) if (1..=TOP_RARITY).contains(&r) => {}
+            _ => fail("rarity", format!("星级无效: {}", row.rarity)),
+        }
+    }
+    if parse_gacha_ts(&row.gacha_ts).is_none() {
+        fail("gachaTs", format!("抽卡时间无法解析: {:?}", row.gacha_ts));
+    }
+    if row.seq_id.parse::<u64>().is_err() {
+        fail("seqId", format!("seqId 不是数字: {:?}", row.seq_id));
+    }
+    errors
+}
+
+/// 已存在于数据库的 record_uid 判定（两张记录表 record_uid 都是主键，逐条查询足够快）
+fn record_exists(conn: &Connection, tables: &[&str], record_uid: &str) -> Result<bool, AppError> {
+    for table in tables {
+        let found = conn
+            .query_row(
+                &format!("SELECT 1 FROM {} WHERE record_uid = ?1", table),
+                [record_uid],
+                |_| Ok(()),
+            )
+            .optional()
+            .map_err(|e| AppError::database(format!("查询已有记录失败: {}", e)))?;
+        if found.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 汇总校验结果。conn 为 None（尚无数据库）时所有有效且不重复的记录都算新增
+fn build_report(
+    conn: Option<&Connection>,
+    rows: &[ImportRow],
+    parse_errors: Vec<RowError>,
+) -> Result<ImportValidationReport, AppError> {
+    let tables: Vec<&str> = match conn {
+        Some(conn) => ["gacha_records", "weapon_records"]
+            .into_iter()
+            .filter_map(|t| table_exists(conn, t).map(|ok| ok.then_some(t)).transpose())
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+
+    let mut report = ImportValidationReport {
+        total_rows: (rows.len() + parse_errors.len()) as u64,
+        invalid_rows: parse_errors.len() as u64,
+        errors: parse_errors,
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    for row in rows {
+        let row_errors = validate_row(row);
+        if !row_errors.is_empty() {
+            report.invalid_rows += 1;
+            report.errors.extend(row_errors);
+            continue;
+        }
+        report.valid_rows += 1;
+        if !seen.insert(row.record_uid.as_str()) {
+            continue;
+        }
+        let exists = match conn {
+            Some(conn) => record_exists(conn, &tables, &row.record_uid)?,
+            None => false,
+        };
+        if !exists {
+            report.estimated_new_records += 1;
+        }
+    }
+    report.errors.sort_by_key(|e| e.row);
+    report.errors.truncate(MAX_REPORTED_ERRORS);
+    Ok(report)
+}
+
+/// Tauri 命令：预检导入文件（format 为 "json" 或 "csv"），只读，不写入数据库
+#[tauri::command]
+pub(crate) fn validate_import_file(
+    app: AppHandle,
+    input_path: String,
+    format: String,
+) -> Result<ImportValidationReport, AppError> {
+    let content = std::fs::read_to_string(&input_path)
+        .map_err(|e| AppError::io(format!("读取导入文件失败: {}", e)))?;
+    let (rows, parse_errors) = match format.trim().to_ascii_lowercase().as_str() {
+        "json" => parse_json_rows(&content),
+        "csv" => parse_csv_rows(&content),
+        other => return Err(AppError::validation(format!("不支持的导入格式: {}", other))),
+    }
+    .map_err(AppError::validation)?;
+
+    // 首次使用尚未建库时按空库估算；库文件存在却打不开（被占用、损坏等）时如实报错
+    let conn = if current_db_file(&app)?.exists() {
+        Some(open_db_read_only(&app)?)
+    } else {
+        None
+    };
+    build_report(conn.as_deref(), &rows, parse_errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    const CSV: &str = "\u{feff}recordUid,uid,category,poolId,poolName,itemId,itemName,itemType,rarity,isNew,isFree,gachaTs,seqId,fetchedAt\n\
+        r1,100,character,p,\"Pool, A\",c1,Alpha,,6,1,0,1767225600000,11,0\n\
+        r2,100,weapon,p,Pool,w1,\"Blade \"\"X\"\"\",sword,9,0,0,1767225600000,12,0\n\
+        r3,100,character,p,Pool,c2,Beta,,5,0,0,not-a-time,x,0\n\
+        r1,100,character,p,Pool,c1,Alpha,,6,1,0,1767225600000,11,0\n\
+        short,row\n";
+
+    #[test]
+    fn parse_csv_line_handles_quotes() {
+        assert_eq!(
+            parse_csv_line(r#"a,"b, c","d ""e""",,"#),
+            vec!["a", "b, c", "d \"e\"", "", ""]
+        );
+    }
+
+    #[test]
+    fn validates_csv_rows_and_counts_new_records() {
+        let (rows, parse_errors) = parse_csv_rows(CSV).unwrap();
+        assert_eq!(rows[1].item_name, "Blade \"X\"");
+        assert_eq!(parse_errors.len(), 1);
+        assert_eq!(parse_errors[0].row, 6);
+
+        let conn = test_db();
+        insert_test_row(&conn, "gacha_records", &[("record_uid", &"r1")]);
+        let report = build_report(Some(&conn), &rows, parse_errors).unwrap();
+        assert_eq!(report.total_rows, 5);
+        assert_eq!(report.valid_rows, 2);
+        assert_eq!(report.invalid_rows, 3);
+        assert_eq!(report.estimated_new_records, 0);
+        let fields: Vec<Option<&str>> = report.errors.iter().map(|e| e.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            vec![Some("rarity"), Some("gachaTs"), Some("seqId"), None]
+        );
+
+        let report = build_report(None, &rows, Vec::new()).unwrap();
+        assert_eq!(report.estimated_new_records, 1);
+    }
+
+    #[test]
+    fn parses_json_sections_and_rejects_unknown_csv() {
+        let json = r#"{"schemaVersion":2,"records":[{"recordUid":"r1","uid":"100","charId":"c1","charName":"Alpha","rarity":6,"gachaTs":"1767225600000","seqId":"3"}],
+            "weaponRecords":[{"recordUid":"w1","uid":"100","weaponId":"w","weaponName":"Blade","rarity":5,"gachaTs":"2026-01-01 00:00:00","seqId":"4"}, 7]}"#;
+        let (rows, parse_errors) = parse_json_rows(json).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].category, "weapon");
+        assert_eq!(parse_errors, vec![row_error(3, None, "记录不是 JSON 对象")]);
+        assert!(rows.iter().all(|row| validate_row(row).is_empty()));
+
+        assert!(parse_json_rows("{}").is_err());
+        assert!(parse_csv_rows("a,b\n1,2").is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! 安装版 ↔ 便携版迁移
+//!
+//! 两种模式的数据都放在 exe 旁的 userdata/ 中，迁移即把整个 userdata/（数据库、配置、备份、图标缓存等）
+//! 复制到目标模式 exe 所在目录下，逐个文件校验后在旧位置留下说明文件；旧数据不删除，
+//! 由用户看过迁移报告、确认无误后自行删除旧版本。
+//!
+//! 数据库不直接复制主文件：已提交但还留在 WAL 中的数据不在主文件里，检查点又可能因占用而未完成。
+//! 先用 `VACUUM INTO` 生成包含全部已提交数据的快照，再把快照复制过去并与快照比对校验。
+
+use chrono::Utc;
+use serde::Serialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::config::{is_portable, same_dir, userdata_dir, write_file_atomic, DB_FILE_NAME};
+use crate::db::demo::is_demo_mode;
+use crate::db::disk_space::ensure_free_space;
+use crate::error::AppError;
+use crate::tray::tray_progress_active;
+
+/// 旧位置留下的说明文件
+const POINTER_FILE_NAME: &str = "已迁移-MOVED.txt";
+/// 目标位置保存的迁移报告
+const REPORT_FILE_NAME: &str = "migration-report.json";
+
+/// 单个复制的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MigratedFile {
+    /// 相对 userdata/ 的路径
+    path: String,
+    bytes: u64,
+    /// 内容哈希一致（数据库文件还需通过完整性检查）
+    verified: bool,
+}
+
+/// 注册表项的处理结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistryChange {
+    key: String,
+    /// updated / unchanged / manual（需要用户自行处理）
+    action: &'static str,
+    detail: String,
+}
+
+/// `migrate_install_mode` 的迁移报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MigrationReport {
+    /// portable / installed
+    target: String,
+    source_dir: String,
+    target_dir: String,
+    files: Vec<MigratedFile>,
+    total_bytes: u64,
+    /// 全部文件校验通过
+    verified: bool,
+    registry: Vec<RegistryChange>,
+    /// 旧位置的说明文件（校验未通过时不写）
+    pointer_file: Option<String>,
+    warnings: Vec<String>,
+    migrated_at: i64,
+}
+
+/// 迁移时跳过的文件：WAL/SHM（数据库以快照迁移，已包含其中的提交）、会话标记、临时文件与旧的说明文件
+fn skip_file(name: &str) -> bool {
+    name.ends_with("-wal")
+        || name.ends_with("-shm")
+        || name.ends_with(".tmp")
+        || name.ends_with(".partial")
+        || name == ".session"
+        || name == POINTER_FILE_NAME
+}
+
+/// 递归列出目录下需要迁移的文件（相对路径）
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("读取目录 {} 失败: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if !path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(skip_file)
+        {
+            files.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+fn file_sha256(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 复制后的数据库能否正常打开并通过快速检查
+fn db_copy_ok(path: &Path) -> bool {
+    rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)))
+        .is_ok_and(|result| result == "ok")
+}
+
+/// 复制并校验单个文件（先复制到 `.partial` 临时文件再改名）
+fn copy_and_verify(source: &Path, target: &Path) -> Result<MigratedFile, String> {
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("创建目录 {} 失败: {}", dir.display(), e))?;
+    }
+    let partial = PathBuf::from(format!("{}.partial", target.to_string_lossy()));
+    let bytes = std::fs::copy(source, &partial)
+        .and_then(|bytes| std::fs::rename(&partial, target).map(|_| bytes))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("复制 {} 失败: {}", source.display(), e)
+        })?;
+    let mut verified = file_sha256(source)? == file_sha256(target)?;
+    if verified && target.extension().is_some_and(|ext| ext == "db") {
+        verified = db_copy_ok(target);
+    }
+    Ok(MigratedFile {
+        path: String::new(),
+        bytes,
+        verified,
+    })
+}
+
+/// 用 `VACUUM INTO` 生成数据库快照（只读打开，包含 WAL 中已提交的数据）
+fn snapshot_db(db_file: &Path, snapshot: &Path) -> Result<(), String> {
+    let _ = std::fs::remove_file(snapshot);
+    let conn =
+        rusqlite::Connection::open_with_flags(db_file, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("打开数据库失败: {}", e))?;
+    conn.execute("VACUUM INTO ?1", [snapshot.to_string_lossy()])
+        .map_err(|e| {
+            let _ = std::fs::remove_file(snapshot);
+            format!("生成数据库快照失败: {}", e)
+        })?;
+    Ok(())
+}
+
+/// 复制 userdata/ 到目标目录，返回逐个文件的结果（目标卷空间不足时不开始复制）。
+/// 数据库文件复制 `db_snapshot`（为空表示没有数据库）并与快照比对
+fn copy_userdata(
+    source: &Path,
+    target: &Path,
+    db_snapshot: Option<&Path>,
+) -> Result<Vec<MigratedFile>, AppError> {
+    let mut relative_paths = Vec::new();
+    collect_files(source, source, &mut relative_paths).map_err(AppError::io)?;
+    relative_paths.sort();
+    let from = |relative: &Path| match db_snapshot {
+        Some(snapshot) if relative == Path::new(DB_FILE_NAME) => snapshot.to_path_buf(),
+        _ => source.join(relative),
+    };
+    let total_bytes: u64 = relative_paths
+        .iter()
+        .filter_map(|relative| std::fs::metadata(from(relative)).ok())
+        .map(|meta| meta.len())
+        .sum();
+    ensure_free_space(target, total_bytes)?;
+    relative_paths
+        .into_iter()
+        .map(|relative| {
+            let mut file =
+                copy_and_verify(&from(&relative), &target.join(&relative)).map_err(AppError::io)?;
+            file.path = relative.to_string_lossy().replace('\\', "/");
+            Ok(file)
+        })
+        .collect()
+}
+
+/// 迁移的目标目录：安装版默认使用注册表中的安装目录
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+fn resolve_target_dir(target: &str, target_dir: Option<String>) -> Result<PathBuf, AppError> {
+    let dir = match target_dir
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+    {
+        Some(dir) => PathBuf::from(dir),
+        #[cfg(target_os = "windows")]
+        None if target == "installed" => crate::config::installed_location().ok_or_else(|| {
+            AppError::validation("未检测到安装版，请先运行安装程序或指定安装目录")
+        })?,
+        None => return Err(AppError::validation("请指定目标目录")),
+    };
+    if !dir.is_absolute() || !dir.is_dir() {
+        return Err(AppError::validation(format!(
+            "目标目录不存在: {}",
+            dir.display()
+        )));
+    }
+    let exe_dir = userdata_dir()
+        .map_err(AppError::io)?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    if same_dir(&dir, &exe_dir) {
+        return Err(AppError::validation("目标目录与当前程序目录相同"));
+    }
+    Ok(dir)
+}
+
+/// 数据文件关联（.efgh）指向目标模式的 exe
+#[cfg(target_os = "windows")]
+fn update_registry(target: &str, target_dir: &Path) -> Vec<RegistryChange> {
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_READ, KEY_SET_VALUE};
+    use winreg::RegKey;
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let mut changes = Vec::new();
+
+    let uninstall_key = crate::config::detect_install()
+        .entries
+        .into_iter()
+        .next()
+        .map(|entry| entry.key)
+        .unwrap_or_else(|| format!(r"HKCU\{}", crate::config::UNINSTALL_KEY));
+    changes.push(if target == "portable" {
+        RegistryChange {
+            key: uninstall_key,
+            action: "manual",
+            detail: "确认迁移无误后，请在系统设置中卸载安装版（卸载程序会清除安装信息）；\
+                     便携版按所在目录判断，不受残留的安装信息影响"
+                .to_string(),
+        }
+    } else {
+        RegistryChange {
+            key: uninstall_key,
+            action: "unchanged",
+            detail: "安装信息由安装程序写入".to_string(),
+        }
+    });
+
+    let exe_name = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_name().map(|name| name.to_os_string()));
+    let target_exe = exe_name.map(|name| target_dir.join(name));
+    let prog_id = hkcu
+        .open_subkey(r"Software\Classes\.efgh")
+        .and_then(|key| key.get_value::<String, _>(""))
+        .ok();
+    let Some(prog_id) = prog_id else {
+        changes.push(RegistryChange {
+            key: r"HKCU\Software\Classes\.efgh".to_string(),
+            action: "unchanged",
+            detail: "未注册数据文件关联".to_string(),
+        });
+        return changes;
+    };
+    let command_key = format!(r"Software\Classes\{}\shell\open\command", prog_id);
+    let change = match target_exe.filter(|exe| exe.is_file()) {
+        Some(exe) => {
+            let command = format!("\"{}\" \"%1\"", exe.display());
+            match hkcu
+                .open_subkey_with_flags(&command_key, KEY_READ | KEY_SET_VALUE)
+                .and_then(|key| key.set_value("", &command))
+            {
+                Ok(()) => RegistryChange {
+                    key: format!(r"HKCU\{}", command_key),
+                    action: "updated",
+                    detail: command,
+                },
+                Err(e) => RegistryChange {
+                    key: format!(r"HKCU\{}", command_key),
+                    action: "manual",
+                    detail: format!("更新数据文件关联失败: {}", e),
+                },
+            }
+        }
+        None => RegistryChange {
+            key: format!(r"HKCU\{}", command_key),
+            action: "manual",
+            detail: "目标目录中还没有程序文件，放入程序后双击 .efgh 文件可重新选择打开方式"
+                .to_string(),
+        },
+    };
+    changes.push(change);
+    changes
+}
+
+#[cfg(not(target_os = "windows"))]
+fn update_registry(_target: &str, _target_dir: &Path) -> Vec<RegistryChange> {
+    Vec::new()
+}
+
+/// 旧位置的说明文件内容
+fn pointer_text(report: &MigrationReport) -> String {
+    let mode = if report.target == "portable" {
+        "便携版"
+    } else {
+        "安装版"
+    };
+    format!(
+        "本目录的数据已于 {} 迁移到{}：\r\n{}\r\n\r\n\
+         请使用新位置的程序。确认新位置的数据完整后，可以删除本目录（迁移报告保存在新位置的 {}）。\r\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        mode,
+        report.target_dir,
+        REPORT_FILE_NAME
+    )
+}
+
+/// Tauri 命令：在安装版与便携版之间迁移数据
+///
+/// `target` 为 portable / installed；`target_dir` 为目标模式 exe 所在目录（迁移到安装版时可为空，使用安装目录）。
+/// 复制 userdata/ 全部内容并逐个校验，更新数据文件关联，在旧位置留下说明文件，返回迁移报告。
+/// 正在同步、演示模式下或目标目录已有数据库时拒绝执行；旧数据不会被删除。
+#[tauri::command]
+pub(crate) async fn migrate_install_mode(
+    app: AppHandle,
+    target: String,
+    target_dir: Option<String>,
+) -> Result<MigrationReport, AppError> {
+    let target = target.trim().to_string();
+    if target != "portable" && target != "installed" {
+        return Err(AppError::validation(format!(
+            "未知的目标模式: {}（可选值: portable, installed）",
+            target
+        )));
+    }
+    if (target == "portable") == is_portable() {
+        return Err(AppError::validation("当前已是目标模式，无需迁移"));
+    }
+    if tray_progress_active(&app) {
+        return Err(AppError::conflict(
+            "sync-active",
+            "正在同步，请等待同步完成后再迁移",
+        ));
+    }
+    if is_demo_mode(&app) {
+        return Err(AppError::validation("请先退出演示模式再迁移"));
+    }
+
+    let target_root = resolve_target_dir(&target, target_dir)?;
+    let source = userdata_dir().map_err(AppError::io)?;
+    let destination = target_root.join("userdata");
+    if destination.join(DB_FILE_NAME).exists() {
+        return Err(AppError::conflict(
+            "target-has-data",
+            format!("目标位置已有数据库: {}", destination.display()),
+        ));
+    }
+
+    let copy_source = source.clone();
+    let copy_destination = destination.clone();
+    let files = tauri::async_runtime::spawn_blocking(move || {
+        let db_file = copy_source.join(DB_FILE_NAME);
+        let snapshot =
+            std::env::temp_dir().join(format!("efgacha-migrate-{}.db", std::process::id()));
+        let has_db = db_file.is_file();
+        if has_db {
+            snapshot_db(&db_file, &snapshot).map_err(AppError::io)?;
+        }
+        let result = copy_userdata(
+            &copy_source,
+            &copy_destination,
+            has_db.then_some(snapshot.as_path()),
+        );
+        let _ = std::fs::remove_file(&snapshot);
+        result
+    })
+    .await
+    .map_err(|e| AppError::io(e.to_string()))??;
+
+    let verified = files.iter().all(|file| file.verified);
+    let mut warnings: Vec<String> = files
+        .iter()
+        .filter(|file| !file.verified)
+        .map(|file| format!("校验失败: {}", file.path))
+        .collect();
+    if !cfg!(target_os = "windows") {
+        warnings.push("当前系统没有注册表，跳过文件关联处理".to_string());
+    }
+    let registry = if verified {
+        update_registry(&target, &target_root)
+    } else {
+        Vec::new()
+    };
+
+    let mut report = MigrationReport {
+        target,
+        source_dir: source.to_string_lossy().into_owned(),
+        target_dir: target_root.to_string_lossy().into_owned(),
+        total_bytes: files.iter().map(|file| file.bytes).sum(),
+        files,
+        verified,
+        registry,
+        pointer_file: None,
+        warnings,
+        migrated_at: Utc::now().timestamp_millis(),
+    };
+    if verified {
+        let pointer = source.join(POINTER_FILE_NAME);
+        match write_file_atomic(&pointer, pointer_text(&report).as_bytes()) {
+            Ok(()) => report.pointer_file = Some(pointer.to_string_lossy().into_owned()),
+            Err(e) => report.warnings.push(format!("写入说明文件失败: {}", e)),
+        }
+    }
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            if let Err(e) = write_file_atomic(&destination.join(REPORT_FILE_NAME), json.as_bytes())
+            {
+                report.warnings.push(format!("保存迁移报告失败: {}", e));
+            }
+        }
+        Err(e) => report.warnings.push(format!("保存迁移报告失败: {}", e)),
+    }
+
+    eprintln!(
+        "[migrate] {} -> {}（{}）: {} 个文件，{} bytes，校验{}",
+        report.source_dir,
+        report.target_dir,
+        report.target,
+        report.files.len(),
+        report.total_bytes,
+        if report.verified {
+            "通过"
+        } else {
+            "未通过"
+        }
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_transient_files() {
+        assert!(skip_file("efgacha.db-wal"));
+        assert!(skip_file("efgacha.db-shm"));
+        assert!(skip_file("config.json.tmp"));
+        assert!(skip_file(".session"));
+        assert!(skip_file(POINTER_FILE_NAME));
+        assert!(!skip_file("efgacha.db"));
+        assert!(!skip_file("config.json"));
+    }
+
+    #[test]
+    fn copied_db_includes_rows_still_in_wal() {
+        let dir = std::env::temp_dir().join(format!("efgacha-migrate-test-{}", std::process::id()));
+        let (source, target) = (dir.join("source"), dir.join("target"));
+        std::fs::create_dir_all(&source).unwrap();
+        let db_file = source.join(DB_FILE_NAME);
+        let writer = rusqlite::Connection::open(&db_file).unwrap();
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA wal_autocheckpoint = 0;
+                 CREATE TABLE t (v INTEGER); INSERT INTO t VALUES (1), (2), (3);",
+            )
+            .unwrap();
+
+        let snapshot = dir.join("snapshot.db");
+        snapshot_db(&db_file, &snapshot).unwrap();
+        let files = copy_userdata(&source, &target, Some(&snapshot)).unwrap();
+        drop(writer);
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].verified);
+        let copy = rusqlite::Connection::open(target.join(DB_FILE_NAME)).unwrap();
+        let count: i64 = copy
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+        drop(copy);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
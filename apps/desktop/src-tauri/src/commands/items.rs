@@ -0,0 +1,504 @@
+//! 物品元数据（多语言名称映射）
+//!
+//! 国际服记录里的物品名是英文，与中文元数据对不上会导致星级补全、搜索失败。
+//! 内置表按物品 id 维护各语言名称，解析时忽略大小写与空白差异，任一语言的名称或 id 都能命中。
+//!
+//! 内部比较（UP 判断等）一律使用物品 id：导入时按名称解析 id，精确匹配不到时做模糊匹配，
+//! 仍解析不了的列入报告，由用户手动指定后保存为别名（userdata/item_aliases.json），以后导入直接命中。
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use tauri::AppHandle;
+
+use crate::config::{userdata_dir, write_file_atomic};
+use crate::db::access::with_db_write;
+use crate::db::{open_db, PULL_RECORDS_CTE};
+use crate::error::AppError;
+
+/// 内置物品表（id、类别、稀有度与各语言名称）
+const BUILTIN_ITEMS: &str = include_str!("../../resources/items.json");
+/// 用户别名文件（归一化名称 → 物品 id）
+const ITEM_ALIASES_FILE_NAME: &str = "item_aliases.json";
+/// 模糊匹配的最短名称长度（过短的名称编辑距离没有区分度）
+const FUZZY_MIN_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ItemMeta {
+    id: String,
+    /// character / weapon
+    #[serde(rename = "type")]
+    item_type: String,
+    rarity: i64,
+    /// 语言代码（zh / en / ja …）→ 名称
+    names: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ItemTable {
+    items: Vec<ItemMeta>,
+}
+
+/// 内置物品表及索引（归一化后的名称与 id → 下标）
+struct ItemIndex {
+    items: Vec<ItemMeta>,
+    by_name: HashMap<String, usize>,
+    by_id: HashMap<String, usize>,
+}
+
+/// 名称解析方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ItemMatchKind {
+    /// 任一语言的名称或 id 精确命中
+    Exact,
+    /// 命中用户保存的别名
+    Alias,
+    /// 去掉标点后相同或仅差个别字符
+    Fuzzy,
+}
+
+/// 名称归一化：忽略大小写与全部空白（含全角空格）
+fn normalize_item_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 模糊匹配用的宽松键：只保留字母数字（含中日文字），忽略标点与符号
+fn loose_item_key(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+fn build_item_index(items: Vec<ItemMeta>) -> ItemIndex {
+    let mut by_name = HashMap::new();
+    let mut by_id = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        by_id.insert(item.id.clone(), i);
+        by_name.insert(normalize_item_name(&item.id), i);
+        for name in item.names.values() {
+            by_name.entry(normalize_item_name(name)).or_insert(i);
+        }
+    }
+    ItemIndex {
+        items,
+        by_name,
+        by_id,
+    }
+}
+
+fn item_index() -> &'static ItemIndex {
+    static INDEX: OnceLock<ItemIndex> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let items = serde_json::from_str::<ItemTable>(BUILTIN_ITEMS)
+            .map(|table| table.items)
+            .unwrap_or_else(|e| {
+                eprintln!("[items] 解析内置物品表失败: {}", e);
+                Vec::new()
+            });
+        build_item_index(items)
+    })
+}
+
+fn item_aliases_file() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join(ITEM_ALIASES_FILE_NAME))
+}
+
+/// 用户保存的别名（首次使用时从文件读取）
+fn item_aliases() -> &'static RwLock<BTreeMap<String, String>> {
+    static ALIASES: OnceLock<RwLock<BTreeMap<String, String>>> = OnceLock::new();
+    ALIASES.get_or_init(|| {
+        let aliases = item_aliases_file()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        RwLock::new(aliases)
+    })
+}
+
+fn lookup_item<'a>(index: &'a ItemIndex, name: &str) -> Option<&'a ItemMeta> {
+    let key = normalize_item_name(name);
+    if key.is_empty() {
+        return None;
+    }
+    index.by_name.get(&key).map(|&i| &index.items[i])
+}
+
+/// 模糊匹配：宽松键相同，或编辑距离在阈值内且最优结果唯一（有并列时宁可不匹配）
+fn fuzzy_lookup_item<'a>(index: &'a ItemIndex, name: &str) -> Option<&'a ItemMeta> {
+    let key = loose_item_key(name);
+    if key.len() < FUZZY_MIN_CHARS {
+        return None;
+    }
+    let threshold = (key.len() / 4).max(1);
+    let mut best: Option<(usize, usize)> = None;
+    let mut tied = false;
+    for (i, item) in index.items.iter().enumerate() {
+        let distance = item
+            .names
+            .values()
+            .map(|candidate| edit_distance(&key, &loose_item_key(candidate)))
+            .min()
+            .unwrap_or(usize::MAX);
+        match best {
+            Some((d, _)) if distance == d => tied = true,
+            Some((d, _)) if distance >= d => {}
+            _ => {
+                best = Some((distance, i));
+                tied = false;
+            }
+        }
+    }
+    match best {
+        Some((distance, i)) if !tied && distance <= threshold => Some(&index.items[i]),
+        _ => None,
+    }
+}
+
+/// 按名称解析物品：精确 → 别名 → 模糊（fuzzy 为 false 时不做模糊匹配）
+fn match_item<'a>(
+    index: &'a ItemIndex,
+    aliases: &BTreeMap<String, String>,
+    name: &str,
+    fuzzy: bool,
+) -> Option<(&'a ItemMeta, ItemMatchKind)> {
+    if let Some(item) = lookup_item(index, name) {
+        return Some((item, ItemMatchKind::Exact));
+    }
+    if let Some(item) = aliases
+        .get(&normalize_item_name(name))
+        .and_then(|id| index.by_id.get(id))
+        .map(|&i| &index.items[i])
+    {
+        return Some((item, ItemMatchKind::Alias));
+    }
+    if fuzzy {
+        return fuzzy_lookup_item(index, name).map(|item| (item, ItemMatchKind::Fuzzy));
+    }
+    None
+}
+
+/// 按任一语言的物品名（或用户别名）解析物品 id，未收录时返回空
+pub(crate) fn item_id_for_name(name: &str) -> Option<&'static str> {
+    let aliases = item_aliases().read().unwrap();
+    match_item(item_index(), &aliases, name, false).map(|(item, _)| item.id.as_str())
+}
+
+/// Tauri 命令：按任一语言的物品名（或物品 id、用户别名）解析内置元数据，未收录时返回空
+#[tauri::command]
+pub(crate) fn resolve_item(name: String) -> Option<ItemMeta> {
+    let aliases = item_aliases().read().unwrap();
+    match_item(item_index(), &aliases, &name, false).map(|(item, _)| item.clone())
+}
+
+/// 一个名称的解析结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolvedItemName {
+    name: String,
+    id: String,
+    kind: ItemMatchKind,
+}
+
+/// 批量解析结果（导入时使用）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemIdReport {
+    resolved: Vec<ResolvedItemName>,
+    /// 无法解析的名称（去重）
+    unresolved: Vec<String>,
+}
+
+fn resolve_names(
+    index: &ItemIndex,
+    aliases: &BTreeMap<String, String>,
+    names: &[String],
+) -> ItemIdReport {
+    let mut names: Vec<&str> = names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    let mut report = ItemIdReport::default();
+    for name in names {
+        match match_item(index, aliases, name, true) {
+            Some((item, kind)) => report.resolved.push(ResolvedItemName {
+                name: name.to_string(),
+                id: item.id.clone(),
+                kind,
+            }),
+            None => report.unresolved.push(name.to_string()),
+        }
+    }
+    report
+}
+
+/// Tauri 命令：批量把物品名解析为 id（精确 → 别名 → 模糊），附带无法解析的名称
+#[tauri::command]
+pub(crate) fn resolve_item_ids(names: Vec<String>) -> ItemIdReport {
+    let aliases = item_aliases().read().unwrap();
+    resolve_names(item_index(), &aliases, &names)
+}
+
+/// 数据库中无法对应到物品 id 的记录（按名称汇总）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UnresolvedItem {
+    name: String,
+    category: String,
+    records: i64,
+}
+
+/// 物品 id 缺失（或旧版本把名称写进了 id）且名称也解析不了的记录
+fn query_unresolved_items(
+    conn: &Connection,
+    index: &ItemIndex,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<UnresolvedItem>, AppError> {
+    let sql = format!(
+        "{} SELECT name, item_type, COUNT(*) FROM pull_records
+           WHERE item_id = '' OR item_id = name
+           GROUP BY name, item_type ORDER BY COUNT(*) DESC, name",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询未识别物品失败: {}", e)))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(UnresolvedItem {
+                name: row.get(0)?,
+                category: row.get(1)?,
+                records: row.get(2)?,
+            })
+        })
+        .map_err(|e| AppError::database(format!("查询未识别物品失败: {}", e)))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| AppError::database(format!("读取未识别物品失败: {}", e)))?;
+    Ok(rows
+        .into_iter()
+        .filter(|item| match_item(index, aliases, &item.name, false).is_none())
+        .collect())
+}
+
+/// Tauri 命令：列出数据库中无法对应到物品 id 的物品名（供手动映射）
+#[tauri::command]
+pub(crate) fn get_unresolved_items(app: AppHandle) -> Result<Vec<UnresolvedItem>, AppError> {
+    // PULL_RECORDS_CTE 依赖 Rust 端补齐的列，只读连接不会升级表结构
+    let conn = open_db(&app)?;
+    let aliases = item_aliases().read().unwrap();
+    query_unresolved_items(&conn, item_index(), &aliases)
+}
+
+/// 用户指定的一条名称 → 物品 id 映射
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemAliasMapping {
+    name: String,
+    item_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolveUnmatchedResult {
+    aliases_saved: usize,
+    records_updated: usize,
+}
+
+/// 把已有记录中该名称缺失的物品 id 补上（只改对应类别的表）
+fn apply_item_alias(conn: &Connection, item: &ItemMeta, name: &str) -> rusqlite::Result<usize> {
+    let (table, id_column, name_column) = if item.item_type == "weapon" {
+        ("weapon_records", "weapon_id", "weapon_name")
+    } else {
+        ("gacha_records", "char_id", "char_name")
+    };
+    conn.execute(
+        &format!(
+            "UPDATE {t} SET {id} = ?1 WHERE {name} = ?2 AND ({id} = '' OR {id} = {name})",
+            t = table,
+            id = id_column,
+            name = name_column
+        ),
+        [item.id.as_str(), name],
+    )
+}
+
+/// Tauri 命令：手动指定无法解析的物品名对应的物品 id，保存为别名并补全已有记录
+#[tauri::command]
+pub(crate) fn resolve_unmatched_items(
+    app: AppHandle,
+    mappings: Vec<ItemAliasMapping>,
+) -> Result<ResolveUnmatchedResult, AppError> {
+    let index = item_index();
+    let mut resolved = Vec::new();
+    for mapping in &mappings {
+        let name = mapping.name.trim();
+        if normalize_item_name(name).is_empty() {
+            return Err(AppError::validation("物品名不能为空"));
+        }
+        let item = index
+            .by_id
+            .get(mapping.item_id.trim())
+            .map(|&i| &index.items[i])
+            .ok_or_else(|| AppError::validation(format!("未知的物品 id: {}", mapping.item_id)))?;
+        resolved.push((name, item));
+    }
+
+    let aliases = {
+        let mut aliases = item_aliases().write().unwrap();
+        for (name, item) in &resolved {
+            aliases.insert(normalize_item_name(name), item.id.clone());
+        }
+        aliases.clone()
+    };
+    let json = serde_json::to_string_pretty(&aliases)
+        .map_err(|e| AppError::io(format!("序列化物品别名失败: {}", e)))?;
+    item_aliases_file()
+        .and_then(|path| write_file_atomic(&path, json.as_bytes()))
+        .map_err(AppError::io)?;
+
+    let records_updated = with_db_write(&app, "补全物品 id", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = 0;
+        for (name, item) in &resolved {
+            updated += apply_item_alias(&tx, item, name)?;
+        }
+        tx.commit()?;
+        Ok(updated)
+    })?;
+    eprintln!(
+        "[items] 保存 {} 个物品别名，补全 {} 条记录的物品 id",
+        resolved.len(),
+        records_updated
+    );
+    Ok(ResolveUnmatchedResult {
+        aliases_saved: resolved.len(),
+        records_updated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    #[test]
+    fn builtin_table_parses() {
+        let table: ItemTable = serde_json::from_str(BUILTIN_ITEMS).unwrap();
+        assert!(!table.items.is_empty());
+        assert!(table.items.iter().all(|item| item.names.contains_key("zh")));
+    }
+
+    #[test]
+    fn resolves_across_languages_ignoring_case_and_spaces() {
+        let index = item_index();
+        let zh = lookup_item(index, "莱万汀").unwrap();
+        let en = lookup_item(index, "  laeVATAIN ").unwrap();
+        assert_eq!(zh.id, en.id);
+        assert_eq!(en.rarity, 6);
+
+        let spaced = lookup_item(index, "lastrite").unwrap();
+        assert_eq!(spaced.names.get("zh").map(String::as_str), Some("别礼"));
+        assert_eq!(lookup_item(index, "CHR_0016_LAEVAT").unwrap().id, zh.id);
+    }
+
+    #[test]
+    fn fuzzy_and_alias_matching() {
+        let index = item_index();
+        let laevatain = lookup_item(index, "莱万汀").unwrap();
+        let aliases = BTreeMap::from([("火龙".to_string(), laevatain.id.clone())]);
+
+        let (item, kind) = match_item(index, &aliases, "Laevatian", true).unwrap();
+        assert_eq!(
+            (item.id.as_str(), kind),
+            (laevatain.id.as_str(), ItemMatchKind::Fuzzy)
+        );
+        assert!(match_item(index, &aliases, "Laevatian", false).is_none());
+        let (item, kind) = match_item(index, &aliases, " 火龙", false).unwrap();
+        assert_eq!(
+            (item.id.as_str(), kind),
+            (laevatain.id.as_str(), ItemMatchKind::Alias)
+        );
+
+        let report = resolve_names(
+            index,
+            &aliases,
+            &[
+                "Laevatain".into(),
+                "laevatain ".into(),
+                "Unknown Thing".into(),
+                " ".into(),
+            ],
+        );
+        assert_eq!(report.resolved.len(), 2);
+        assert_eq!(report.unresolved, vec!["Unknown Thing".to_string()]);
+    }
+
+    #[test]
+    fn unresolved_items_skip_known_names_and_aliases_fill_ids() {
+        let conn = test_db();
+        let rows = [
+            ("a", "", "火龙"),
+            ("b", "", "火龙"),
+            ("c", "", "Laevatain"),
+            ("d", "chr_0016_laevat", "莱万汀"),
+        ];
+        for (id, char_id, char_name) in rows {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &id),
+                    ("char_id", &char_id),
+                    ("char_name", &char_name),
+                ],
+            );
+        }
+        let index = item_index();
+        let unresolved = query_unresolved_items(&conn, index, &BTreeMap::new()).unwrap();
+        assert_eq!(
+            unresolved,
+            vec![UnresolvedItem {
+                name: "火龙".to_string(),
+                category: "character".to_string(),
+                records: 2,
+            }]
+        );
+
+        let item = lookup_item(index, "莱万汀").unwrap();
+        assert_eq!(apply_item_alias(&conn, item, "火龙").unwrap(), 2);
+        let aliases = BTreeMap::from([("火龙".to_string(), item.id.clone())]);
+        assert!(query_unresolved_items(&conn, index, &aliases)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn unknown_or_blank_names_resolve_to_none() {
+        let index = item_index();
+        assert!(lookup_item(index, "不存在的物品").is_none());
+        assert!(lookup_item(index, " \u{3000} ").is_none());
+    }
+}
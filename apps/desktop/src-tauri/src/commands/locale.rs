@@ -0,0 +1,86 @@
+//! Rust 端文案的多语言表
+//!
+//! 界面文案由前端 i18n 维护；Rust 端产生、需要直接展示给用户的文案（错误类别、任务状态等）
+//! 放在内置表 `resources/locales.json`（语言 → 键 → 文案）中，按前端当前语言取用。
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+
+/// 内置文案表
+const BUILTIN_LOCALES: &str = include_str!("../../resources/locales.json");
+
+/// 未知语言时使用的语言
+const FALLBACK_LOCALE: &str = "zh-cn";
+
+type LocaleTable = HashMap<String, HashMap<String, String>>;
+
+fn locale_table() -> Result<&'static LocaleTable, AppError> {
+    static TABLE: OnceLock<Result<LocaleTable, String>> = OnceLock::new();
+    TABLE
+        .get_or_init(|| {
+            serde_json::from_str(BUILTIN_LOCALES).map_err(|e| format!("解析内置文案表失败: {}", e))
+        })
+        .as_ref()
+        .map_err(|e| AppError::from(e.clone()))
+}
+
+/// 把前端传入的语言代码对应到表中的语言：忽略大小写，`zh_CN` 与 `zh-cn` 等价，
+/// 只有语言部分（如 `en`）时取同语言的第一个地区，都对不上时使用 `FALLBACK_LOCALE`
+fn resolve_locale<'a>(table: &'a LocaleTable, locale: &str) -> Option<&'a HashMap<String, String>> {
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let language = locale.split('-').next().unwrap_or_default();
+    table
+        .get(&locale)
+        .or_else(|| {
+            let mut same_language: Vec<_> = table
+                .iter()
+                .filter(|(name, _)| name.split('-').next() == Some(language))
+                .collect();
+            same_language.sort_by_key(|(name, _)| name.as_str());
+            same_language.first().map(|(_, strings)| *strings)
+        })
+        .or_else(|| table.get(FALLBACK_LOCALE))
+}
+
+fn lookup(table: &LocaleTable, key: &str, locale: &str) -> String {
+    resolve_locale(table, locale)
+        .and_then(|strings| strings.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Tauri 命令：取 Rust 端文案；语言未收录时使用简体中文，缺少该键时原样返回键名
+#[tauri::command]
+pub(crate) fn get_localized_string(key: String, locale: String) -> Result<String, AppError> {
+    Ok(lookup(locale_table()?, &key, &locale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_locales_have_the_same_keys() {
+        let table = locale_table().unwrap();
+        let zh = &table[FALLBACK_LOCALE];
+        for (locale, strings) in table {
+            let mut missing: Vec<_> = zh
+                .keys()
+                .filter(|key| !strings.contains_key(*key))
+                .collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{} 缺少 {:?}", locale, missing);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_chinese_then_key() {
+        let table = locale_table().unwrap();
+        assert_eq!(lookup(table, "task.cancelled", "en-US"), "Cancelled");
+        assert_eq!(lookup(table, "task.cancelled", "en"), "Cancelled");
+        assert_eq!(lookup(table, "task.cancelled", "fr-fr"), "已取消");
+        assert_eq!(lookup(table, "no.such.key", "en-us"), "no.such.key");
+    }
+}
@@ -0,0 +1,34 @@
+//! 前端调用的业务命令（按功能分模块）
+
+pub(crate) mod accounts;
+pub(crate) mod banner_watch;
+pub(crate) mod changelog;
+pub(crate) mod clock;
+pub(crate) mod compare;
+pub(crate) mod custom_endpoint;
+pub(crate) mod diagnose;
+pub(crate) mod endpoints;
+pub(crate) mod export;
+pub(crate) mod hash_chain;
+pub(crate) mod icons;
+pub(crate) mod import;
+pub(crate) mod import_upgrade;
+pub(crate) mod import_validate;
+pub(crate) mod install_mode;
+pub(crate) mod items;
+pub(crate) mod locale;
+pub(crate) mod open_file;
+pub(crate) mod pdf_report;
+pub(crate) mod pity;
+pub(crate) mod raw_responses;
+pub(crate) mod records;
+pub(crate) mod resource_usage;
+pub(crate) mod retrospective;
+pub(crate) mod stats;
+pub(crate) mod sync_history;
+pub(crate) mod tasks;
+pub(crate) mod telemetry;
+pub(crate) mod text_export;
+pub(crate) mod updater;
+pub(crate) mod webdav;
+pub(crate) mod webdav_backup;
@@ -0,0 +1,64 @@
+//! 启动参数打开文件（文件关联）
+//!
+//! 双击关联的数据文件（.efgh）或把导出的 JSON 拖到 exe 上时，路径以启动参数传入。
+//! 首次启动时暂存，待前端就绪后通过 `take_pending_open_file` 取走；
+//! 程序已在运行时由单实例插件把新进程的参数转给当前实例，直接发送 `open-file` 事件。
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::app_state;
+
+/// 允许通过启动参数打开的文件扩展名
+const OPEN_FILE_EXTENSIONS: [&str; 2] = ["efgh", "json"];
+
+/// 允许打开的文件大小上限
+const OPEN_FILE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 待导入的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OpenFileRequest {
+    path: String,
+    content: String,
+}
+
+/// 启动时传入、尚未被前端取走的文件
+#[derive(Default)]
+pub(crate) struct PendingOpenFile(pub(crate) Mutex<Option<OpenFileRequest>>);
+
+/// 从启动参数中找出要打开的文件；非法或不存在的路径直接忽略
+pub(crate) fn open_file_from_args(args: &[String]) -> Option<OpenFileRequest> {
+    args.iter().skip(1).find_map(|arg| {
+        if arg.starts_with('-') {
+            return None;
+        }
+        let path = PathBuf::from(arg);
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        if !OPEN_FILE_EXTENSIONS.contains(&ext.as_str()) {
+            return None;
+        }
+        let meta = std::fs::metadata(&path).ok()?;
+        if !meta.is_file() || meta.len() > OPEN_FILE_MAX_BYTES {
+            return None;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Some(OpenFileRequest {
+                path: path.to_string_lossy().into_owned(),
+                content,
+            }),
+            Err(e) => {
+                eprintln!("[open-file] 读取文件失败 {}: {}", arg, e);
+                None
+            }
+        }
+    })
+}
+
+/// Tauri 命令：取走启动时传入的待导入文件（只返回一次）
+#[tauri::command]
+pub(crate) fn take_pending_open_file(app: AppHandle) -> Option<OpenFileRequest> {
+    app_state(&app).pending_open_file.0.lock().unwrap().take()
+}
@@ -0,0 +1,383 @@
+//! 抽卡记录 PDF 报告
+//!
+//! 生成可打印的抽卡记录：概览统计、6★ 保底历史（文本柱状图）与逐条记录表格，自动分页。
+//! 内置 PDF 字体不含中文，优先嵌入系统中文字体；找不到时退回 Helvetica（物品名可能无法显示）。
+
+use chrono::Local;
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+};
+use rusqlite::Connection;
+use tauri::AppHandle;
+
+use crate::commands::pity::PityWalker;
+use crate::commands::tasks::ExportTask;
+use crate::db::{
+    open_db, parse_gacha_ts, query_pulls_by_time, validate_gacha_type, GACHA_TYPES,
+    PULL_RECORDS_CTE, TOP_RARITY,
+};
+use crate::error::AppError;
+
+/// 报告最多包含的记录行数（限制文件体积）
+const PDF_ROW_LIMIT: usize = 10_000;
+
+/// A4 纸张尺寸（毫米）
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+/// 页边距（毫米）
+const PAGE_MARGIN_MM: f32 = 15.0;
+/// 正文行高（毫米）
+const LINE_HEIGHT_MM: f32 = 6.0;
+
+const TITLE_FONT_SIZE: f32 = 18.0;
+const HEADING_FONT_SIZE: f32 = 13.0;
+const BODY_FONT_SIZE: f32 = 10.0;
+
+/// 记录表格各列的横坐标（毫米）：时间、名称、星级、保底
+const TABLE_COLUMNS_MM: [f32; 4] = [15.0, 60.0, 140.0, 165.0];
+
+/// 保底柱状图中每个字符代表的抽数
+const PITY_BAR_STEP: u32 = 2;
+
+/// 可嵌入的系统中文字体（仅支持单字体 .ttf 文件）
+const CJK_FONT_CANDIDATES: &[&str] = &[
+    "Fonts/simhei.ttf",
+    "Fonts/Deng.ttf",
+    "Fonts/simkai.ttf",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+];
+
+/// 卡池类型的中文名称
+pub(crate) fn gacha_type_label(gacha_type: &str) -> &'static str {
+    match gacha_type {
+        "special" => "限定池",
+        "standard" => "常驻池",
+        "beginner" => "新手池",
+        "weapon" => "武器池",
+        _ => "其他",
+    }
+}
+
+/// 报告中的一行记录
+struct ReportRow {
+    gacha_type: String,
+    time: String,
+    name: String,
+    rarity: i64,
+    /// 距上一次出 6★ 的抽数（含本抽，免费抽为空）
+    pity: Option<u32>,
+}
+
+/// 单个卡池的概览统计
+struct PoolSummary {
+    gacha_type: String,
+    total: u32,
+    top_rarity: u32,
+    second_rarity: u32,
+    /// 当前保底计数（免费抽不计入也不重置）
+    pity: PityWalker,
+    /// 每次出 6★ 时的（名称, 保底数）
+    top_history: Vec<(String, u32)>,
+}
+
+/// 读取记录并计算保底（按卡池分组、时间升序）
+fn query_report_rows(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: Option<&str>,
+) -> Result<(Vec<ReportRow>, Vec<PoolSummary>), AppError> {
+    let sql = format!(
+        "{} SELECT gacha_type, time, name, rarity, is_free, gacha_id FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询报告记录失败: {}", e)))?;
+    let mut rows = query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, i64>(4)?,
+        ))
+    })
+    .map_err(|e| AppError::database(format!("读取报告记录失败: {}", e)))?;
+    // 稳定排序，同一卡池内保持时间顺序
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut report_rows = Vec::new();
+    let mut summaries: Vec<PoolSummary> = Vec::new();
+    for (gacha_type, time, name, rarity, is_free) in rows {
+        if summaries
+            .last()
+            .map(|s| s.gacha_type != gacha_type)
+            .unwrap_or(true)
+        {
+            summaries.push(PoolSummary {
+                gacha_type: gacha_type.clone(),
+                total: 0,
+                top_rarity: 0,
+                second_rarity: 0,
+                pity: PityWalker::new(TOP_RARITY),
+                top_history: Vec::new(),
+            });
+        }
+        let summary = summaries.last_mut().expect("已按卡池插入统计");
+        summary.total += 1;
+        let pity = summary.pity.step(rarity, is_free != 0);
+        if rarity >= TOP_RARITY {
+            summary.top_rarity += 1;
+            if let Some(pity) = pity {
+                summary.top_history.push((name.clone(), pity));
+            }
+        } else if rarity == TOP_RARITY - 1 {
+            summary.second_rarity += 1;
+        }
+        report_rows.push(ReportRow {
+            gacha_type,
+            time: parse_gacha_ts(&time)
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or(time),
+            name,
+            rarity,
+            pity,
+        });
+    }
+    summaries.sort_by_key(|s| GACHA_TYPES.iter().position(|t| *t == s.gacha_type));
+    report_rows.sort_by_key(|r| GACHA_TYPES.iter().position(|t| *t == r.gacha_type));
+    Ok((report_rows, summaries))
+}
+
+/// 嵌入系统中文字体，找不到时退回内置 Helvetica
+fn load_report_font(doc: &PdfDocumentReference) -> Result<IndirectFontRef, String> {
+    let windows_dir = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    for candidate in CJK_FONT_CANDIDATES {
+        let path = if candidate.starts_with("Fonts/") {
+            std::path::Path::new(&windows_dir).join(candidate)
+        } else {
+            std::path::PathBuf::from(candidate)
+        };
+        let Ok(file) = std::fs::File::open(&path) else {
+            continue;
+        };
+        match doc.add_external_font(std::io::BufReader::new(file)) {
+            Ok(font) => return Ok(font),
+            Err(e) => eprintln!("[pdf] 加载字体 {} 失败: {}", path.display(), e),
+        }
+    }
+    eprintln!("[pdf] 未找到可用的中文字体，使用内置字体");
+    doc.add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| format!("加载 PDF 字体失败: {}", e))
+}
+
+/// 逐行写入文本，空间不足时自动换页
+struct ReportWriter {
+    doc: PdfDocumentReference,
+    font: IndirectFontRef,
+    layer: PdfLayerReference,
+    /// 当前行的纵坐标（毫米，自页面底部算起）
+    y: f32,
+}
+
+impl ReportWriter {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "报告");
+        let font = load_report_font(&doc)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            doc,
+            font,
+            layer,
+            y: PAGE_HEIGHT_MM - PAGE_MARGIN_MM,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "报告");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT_MM - PAGE_MARGIN_MM;
+    }
+
+    /// 确保剩余空间还能放下 `lines` 行，否则换页
+    fn ensure_space(&mut self, lines: f32) {
+        if self.y - LINE_HEIGHT_MM * lines < PAGE_MARGIN_MM {
+            self.new_page();
+        }
+    }
+
+    fn text_at(&self, text: &str, size: f32, x: f32) {
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y), &self.font);
+    }
+
+    fn line(&mut self, text: &str, size: f32) {
+        self.ensure_space(1.0);
+        self.text_at(text, size, PAGE_MARGIN_MM);
+        self.y -= LINE_HEIGHT_MM * (size / BODY_FONT_SIZE).max(1.0);
+    }
+
+    fn columns(&mut self, cells: [&str; 4]) {
+        self.ensure_space(1.0);
+        for (cell, x) in cells.iter().zip(TABLE_COLUMNS_MM) {
+            self.text_at(cell, BODY_FONT_SIZE, x);
+        }
+        self.y -= LINE_HEIGHT_MM;
+    }
+
+    fn gap(&mut self) {
+        self.y -= LINE_HEIGHT_MM / 2.0;
+    }
+}
+
+/// 写入概览统计
+fn write_summary(writer: &mut ReportWriter, summaries: &[PoolSummary]) {
+    writer.line("概览", HEADING_FONT_SIZE);
+    let total: u32 = summaries.iter().map(|s| s.total).sum();
+    let top: u32 = summaries.iter().map(|s| s.top_rarity).sum();
+    writer.line(
+        &format!(
+            "总抽数：{}　6★：{}　5★：{}",
+            total,
+            top,
+            summaries.iter().map(|s| s.second_rarity).sum::<u32>()
+        ),
+        BODY_FONT_SIZE,
+    );
+    for summary in summaries {
+        let average = if summary.top_history.is_empty() {
+            "-".to_string()
+        } else {
+            let sum: u32 = summary.top_history.iter().map(|(_, pity)| pity).sum();
+            format!("{:.1}", f64::from(sum) / summary.top_history.len() as f64)
+        };
+        writer.line(
+            &format!(
+                "{}：{} 抽，6★ {} 次，5★ {} 次，6★ 平均 {} 抽，当前已垫 {} 抽",
+                gacha_type_label(&summary.gacha_type),
+                summary.total,
+                summary.top_rarity,
+                summary.second_rarity,
+                average,
+                summary.pity.current()
+            ),
+            BODY_FONT_SIZE,
+        );
+    }
+    writer.gap();
+}
+
+/// 写入 6★ 保底历史（每次出货的抽数，以文本柱状图呈现）
+fn write_pity_history(writer: &mut ReportWriter, summaries: &[PoolSummary]) {
+    writer.line("6★ 保底历史", HEADING_FONT_SIZE);
+    for summary in summaries.iter().filter(|s| !s.top_history.is_empty()) {
+        writer.line(gacha_type_label(&summary.gacha_type), BODY_FONT_SIZE);
+        for (name, pity) in &summary.top_history {
+            let bar = "#".repeat(pity.div_ceil(PITY_BAR_STEP) as usize);
+            writer.line(&format!("  {:>3} {} {}", pity, bar, name), BODY_FONT_SIZE);
+        }
+    }
+    writer.gap();
+}
+
+/// 写入记录表格（按卡池分组）
+fn write_record_table(writer: &mut ReportWriter, rows: &[ReportRow]) {
+    let mut current_pool: Option<&str> = None;
+    for row in rows {
+        if current_pool != Some(row.gacha_type.as_str()) {
+            writer.ensure_space(3.0);
+            writer.line(gacha_type_label(&row.gacha_type), HEADING_FONT_SIZE);
+            writer.columns(["时间", "名称", "星级", "保底"]);
+            current_pool = Some(row.gacha_type.as_str());
+        }
+        let rarity = format!("{}★", row.rarity);
+        let pity = row
+            .pity
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "免费".to_string());
+        writer.columns([&row.time, &row.name, &rarity, &pity]);
+    }
+}
+
+/// Tauri 命令：生成抽卡记录 PDF 报告
+///
+/// `gacha_type` 为空时包含全部卡池；记录表格最多 `PDF_ROW_LIMIT` 行，超出部分省略，
+/// 概览与保底历史仍按全部记录统计。进度与取消同 `export_uigf`。
+#[tauri::command]
+pub(crate) fn generate_pull_history_pdf(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    output_path: String,
+) -> Result<(), AppError> {
+    ExportTask::start(&app, "pdf")
+        .run(|task| write_pull_history_pdf(&app, task, uid, gacha_type, output_path))
+}
+
+pub(crate) fn write_pull_history_pdf(
+    app: &AppHandle,
+    task: &ExportTask,
+    uid: String,
+    gacha_type: Option<String>,
+    output_path: String,
+) -> Result<(), AppError> {
+    if let Some(gacha_type) = gacha_type.as_deref() {
+        validate_gacha_type(gacha_type)?;
+    }
+    task.progress("querying", 0, 0);
+    let conn = open_db(app)?;
+    let (mut rows, summaries) = query_report_rows(&conn, &uid, gacha_type.as_deref())?;
+    let total_rows = rows.len();
+    rows.truncate(PDF_ROW_LIMIT);
+    task.check()?;
+    task.progress("building", 0, rows.len());
+
+    let app_name = app.package_info().name.clone();
+    let export_date = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let title = format!("{} 抽卡记录 - {}", app_name, uid);
+    let mut writer = ReportWriter::new(&title)?;
+
+    writer.line(&title, TITLE_FONT_SIZE);
+    writer.line(
+        &format!(
+            "卡池：{}　导出时间：{}",
+            gacha_type
+                .as_deref()
+                .map(gacha_type_label)
+                .unwrap_or("全部"),
+            export_date
+        ),
+        BODY_FONT_SIZE,
+    );
+    if total_rows > PDF_ROW_LIMIT {
+        writer.line(
+            &format!(
+                "记录共 {} 条，表格仅包含前 {} 条",
+                total_rows, PDF_ROW_LIMIT
+            ),
+            BODY_FONT_SIZE,
+        );
+    }
+    writer.gap();
+    write_summary(&mut writer, &summaries);
+    write_pity_history(&mut writer, &summaries);
+    write_record_table(&mut writer, &rows);
+
+    let doc = writer
+        .doc
+        .with_creator(app_name.clone())
+        .with_producer(format!("{} {}", app_name, app.package_info().version))
+        .with_author(app_name)
+        .with_subject(format!("导出日期 {}", export_date));
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| format!("生成 PDF 失败: {}", e))?;
+    task.write_output(std::path::Path::new(&output_path), &bytes)?;
+    eprintln!("[export] PDF 报告导出 {} 条记录", rows.len());
+    Ok(())
+}
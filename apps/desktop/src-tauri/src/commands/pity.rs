@@ -0,0 +1,775 @@
+//! 保底计数
+//!
+//! 除距上次最高稀有度的抽数外，还按卡池元数据统计距上次 UP 的抽数与各里程碑奖励的进度。
+//! 里程碑（如限定池 120 抽必得 UP、每 240 抽的信物）与各期 UP 物品在 `banner_meta.json` 中声明。
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::commands::items::item_id_for_name;
+use crate::commands::text_export::export_pity_text;
+use crate::db::access::with_db_write;
+use crate::db::{
+    open_db, query_pulls_by_time, validate_gacha_type, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY,
+    TOP_RARITY_HARD_PITY, TOP_RARITY_SOFT_PITY,
+};
+use crate::error::AppError;
+use crate::windows::emit_throttled;
+
+/// 内置卡池元数据（各类卡池的里程碑、各期卡池的 UP 物品）
+const BUILTIN_BANNER_META: &str = include_str!("../../resources/banner_meta.json");
+
+/// 卡池元数据
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct BannerMeta {
+    /// gacha_type → 该类卡池的里程碑
+    gacha_types: HashMap<String, GachaTypeMeta>,
+    /// pool_id → 该期卡池信息
+    pools: HashMap<String, PoolMeta>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct GachaTypeMeta {
+    milestones: Vec<MilestoneMeta>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct PoolMeta {
+    /// UP 物品 id（与语言无关，名称在比较前先解析为 id）
+    featured: Vec<String>,
+}
+
+/// 里程碑奖励声明
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MilestoneMeta {
+    id: String,
+    /// 达成所需抽数（免费抽不计入）
+    pulls: u32,
+    /// 可重复领取（每满 `pulls` 抽一次）
+    repeat: bool,
+    /// 只统计当期卡池的抽数（换池后重新计数）
+    per_pool: bool,
+    /// 抽到 UP 最高稀有度时视为已达成
+    reset_on_featured: bool,
+}
+
+fn banner_meta() -> &'static BannerMeta {
+    static META: OnceLock<BannerMeta> = OnceLock::new();
+    META.get_or_init(|| {
+        serde_json::from_str(BUILTIN_BANNER_META).unwrap_or_else(|e| {
+            eprintln!("[pity] 解析内置卡池元数据失败: {}", e);
+            BannerMeta::default()
+        })
+    })
+}
+
+/// 一个抽数计数器：累计抽数及最近一次使其归零的记录
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PityCounter {
+    pulls: i64,
+    /// 最近一次重置计数的记录 gacha_id，从未重置则为空
+    reset_by: Option<String>,
+}
+
+/// 某个里程碑奖励的进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MilestoneProgress {
+    id: String,
+    pulls_required: u32,
+    repeat: bool,
+    /// 按期统计时为当期卡池 id
+    pool_id: Option<String>,
+    /// 计入该里程碑的总抽数
+    lifetime_pulls: i64,
+    /// 距上次达成的抽数（从未达成时等于总抽数）
+    since_reached: PityCounter,
+    times_reached: i64,
+}
+
+/// 某卡池的当前保底进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PityStatus {
+    pub(crate) uid: String,
+    pub(crate) gacha_type: String,
+    /// 距上次出最高稀有度已累计的抽数（免费抽不计入）
+    pub(crate) pity: i64,
+    /// 上一次出最高稀有度的 gacha_id，从未出过则为空
+    last_five_star_gacha_id: Option<String>,
+    /// 上一次出的最高稀有度物品名
+    pub(crate) last_five_star_name: Option<String>,
+    /// 距上次出 UP 最高稀有度的抽数（按卡池元数据判断 UP，跨期累计）
+    since_featured: PityCounter,
+    /// 该类卡池声明的里程碑进度
+    milestones: Vec<MilestoneProgress>,
+}
+
+/// 参与保底计算的一条记录（按时间正序）
+struct PityRow {
+    gacha_id: String,
+    /// 物品 id（旧记录可能为空，此时按名称解析）
+    item_id: String,
+    name: String,
+    pool_id: String,
+    rarity: i64,
+    is_free: bool,
+}
+
+/// 记录是否为所在卡池的 UP 最高稀有度物品
+fn is_featured(meta: &BannerMeta, row: &PityRow) -> bool {
+    if row.rarity < TOP_RARITY {
+        return false;
+    }
+    let Some(pool) = meta.pools.get(&row.pool_id) else {
+        return false;
+    };
+    let item_id = if row.item_id.is_empty() || row.item_id == row.name {
+        item_id_for_name(&row.name)
+    } else {
+        Some(row.item_id.as_str())
+    };
+    item_id.is_some_and(|id| pool.featured.iter().any(|f| f == id))
+}
+
+/// 按内置卡池元数据判断一次抽卡是否出了该期 UP 的最高稀有度物品
+pub(crate) fn is_featured_pull(pool_id: &str, item_id: &str, name: &str, rarity: i64) -> bool {
+    let row = PityRow {
+        gacha_id: String::new(),
+        item_id: item_id.to_string(),
+        name: name.to_string(),
+        pool_id: pool_id.to_string(),
+        rarity,
+        is_free: false,
+    };
+    is_featured(banner_meta(), &row)
+}
+
+fn milestone_progress(
+    meta: &BannerMeta,
+    milestone: &MilestoneMeta,
+    rows: &[PityRow],
+) -> MilestoneProgress {
+    let pool_id = milestone
+        .per_pool
+        .then(|| rows.last().map(|row| row.pool_id.clone()))
+        .flatten();
+    let mut lifetime_pulls = 0;
+    let mut since_reached = PityCounter::default();
+    let mut times_reached = 0;
+    for row in rows {
+        if row.is_free || pool_id.as_ref().is_some_and(|id| *id != row.pool_id) {
+            continue;
+        }
+        lifetime_pulls += 1;
+        since_reached.pulls += 1;
+        if times_reached > 0 && !milestone.repeat {
+            continue;
+        }
+        let by_count = milestone.pulls > 0 && since_reached.pulls >= i64::from(milestone.pulls);
+        if by_count || (milestone.reset_on_featured && is_featured(meta, row)) {
+            times_reached += 1;
+            since_reached = PityCounter {
+                pulls: 0,
+                reset_by: Some(row.gacha_id.clone()),
+            };
+        }
+    }
+    MilestoneProgress {
+        id: milestone.id.clone(),
+        pulls_required: milestone.pulls,
+        repeat: milestone.repeat,
+        pool_id,
+        lifetime_pulls,
+        since_reached,
+        times_reached,
+    }
+}
+
+/// 按时间正序读取某卡池参与保底计算的记录
+fn query_pity_rows(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: &str,
+) -> Result<Vec<PityRow>, AppError> {
+    let sql = format!(
+        "{} SELECT gacha_id, name, pool_id, rarity, is_free, item_id, time FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询保底记录失败: {}", e)))?;
+    query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        Ok(PityRow {
+            gacha_id: row.get(0)?,
+            name: row.get(1)?,
+            pool_id: row.get(2)?,
+            rarity: row.get(3)?,
+            is_free: row.get::<_, i64>(4)? != 0,
+            item_id: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        })
+    })
+    .map_err(|e| AppError::database(format!("读取保底记录失败: {}", e)))
+}
+
+/// 按记录计算某卡池的保底进度
+pub(crate) fn compute_pity_status(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: &str,
+) -> Result<PityStatus, AppError> {
+    let rows = query_pity_rows(conn, uid, gacha_type)?;
+    Ok(tally_pity(uid, gacha_type, &rows))
+}
+
+/// 保底计数：按时间正序逐抽推进，所有保底相关的命令都经由这里计数
+///
+/// 免费抽完全跳过——不计数，抽到高稀有度也不重置，与前端 `calculatePityStatus` 口径一致。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PityWalker {
+    /// 抽到该稀有度及以上时清零
+    reset_rarity: i64,
+    pity: u32,
+}
+
+impl PityWalker {
+    pub(crate) fn new(reset_rarity: i64) -> Self {
+        Self {
+            reset_rarity,
+            pity: 0,
+        }
+    }
+
+    /// 推进一抽，返回含本抽的计数；免费抽返回 None，计数不变
+    pub(crate) fn step(&mut self, rarity: i64, is_free: bool) -> Option<u32> {
+        if is_free {
+            return None;
+        }
+        self.pity += 1;
+        let pity = self.pity;
+        if rarity >= self.reset_rarity {
+            self.pity = 0;
+        }
+        Some(pity)
+    }
+
+    /// 距上次重置已累计的抽数
+    pub(crate) fn current(&self) -> u32 {
+        self.pity
+    }
+}
+
+fn tally_pity(uid: &str, gacha_type: &str, rows: &[PityRow]) -> PityStatus {
+    let meta = banner_meta();
+    let mut walker = PityWalker::new(TOP_RARITY);
+    let mut last_five_star: Option<&PityRow> = None;
+    let mut since_featured = PityCounter::default();
+    for row in rows {
+        if walker.step(row.rarity, row.is_free).is_none() {
+            continue;
+        }
+        since_featured.pulls += 1;
+        if row.rarity >= TOP_RARITY {
+            last_five_star = Some(row);
+        }
+        if is_featured(meta, row) {
+            since_featured = PityCounter {
+                pulls: 0,
+                reset_by: Some(row.gacha_id.clone()),
+            };
+        }
+    }
+    let milestones = meta
+        .gacha_types
+        .get(gacha_type)
+        .map(|t| {
+            t.milestones
+                .iter()
+                .map(|m| milestone_progress(meta, m, rows))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PityStatus {
+        uid: uid.to_string(),
+        gacha_type: gacha_type.to_string(),
+        pity: i64::from(walker.current()),
+        last_five_star_gacha_id: last_five_star.map(|row| row.gacha_id.clone()),
+        last_five_star_name: last_five_star.map(|row| row.name.clone()),
+        since_featured,
+        milestones,
+    }
+}
+
+/// 按记录重新计算某卡池的保底进度，并通过 `pity-updated` 事件通知前端
+///
+/// 记录被改动（纠正卡池、删除等）后调用，保证前端展示的保底数与库中记录一致。
+pub(crate) fn reconcile_pity_from_records(
+    app: &AppHandle,
+    conn: &Connection,
+    uid: &str,
+    gacha_type: &str,
+) -> Result<PityStatus, AppError> {
+    let status = compute_pity_status(conn, uid, gacha_type)?;
+    emit_throttled(app, "pity-updated", status.clone());
+    export_pity_text(app, &status);
+    Ok(status)
+}
+
+/// Tauri 命令：某卡池的保底进度（含距上次 UP 的抽数与里程碑进度）
+#[tauri::command]
+pub(crate) fn get_pity_status(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+) -> Result<PityStatus, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let conn = open_db(&app)?;
+    compute_pity_status(&conn, &uid, &gacha_type)
+}
+
+/// Tauri 命令：纠正单条记录的卡池类型
+///
+/// 从其他工具导入的数据可能使用了错误的卡池编码，导致记录被归到错误的 gacha_type。
+/// 纠正结果写入 gacha_type_override 列，不改动原始 pool_id；完成后重新计算新旧两个卡池的保底。
+/// 武器记录与角色记录分表存储，只能在角色卡池（special / standard / beginner）之间调整。
+/// 已归档的记录默认拒绝修改，`include_archived` 为真时才允许。
+#[tauri::command]
+pub(crate) fn reclassify_pull(
+    app: AppHandle,
+    uid: String,
+    gacha_id: String,
+    new_gacha_type: String,
+    include_archived: Option<bool>,
+) -> Result<(), AppError> {
+    validate_gacha_type(&new_gacha_type)?;
+    if new_gacha_type == "weapon" {
+        return Err(AppError::validation("角色记录不能归入武器卡池"));
+    }
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_type, archived FROM pull_records
+           WHERE uid = ?1 AND gacha_id = ?2 AND gacha_type != 'weapon'",
+        PULL_RECORDS_CTE
+    );
+    let (old_gacha_type, archived): (String, i64) = conn
+        .query_row(&sql, rusqlite::params![uid, gacha_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .optional()
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?
+        .ok_or_else(|| AppError::validation(format!("未找到角色抽卡记录: {}", gacha_id)))?;
+    if archived != 0 && !include_archived.unwrap_or(false) {
+        return Err(AppError::validation(format!(
+            "记录 {} 已归档，不能修改卡池类型",
+            gacha_id
+        )));
+    }
+    if old_gacha_type == new_gacha_type {
+        return Ok(());
+    }
+
+    with_db_write(&app, "更新卡池类型", |conn| {
+        conn.execute(
+            "UPDATE gacha_records SET gacha_type_override = ?1, edited_at = ?4
+              WHERE uid = ?2 AND seq_id = ?3",
+            rusqlite::params![new_gacha_type, uid, gacha_id, Utc::now().timestamp_millis()],
+        )
+    })?;
+    eprintln!(
+        "[reclassify] {} #{}: {} -> {}",
+        uid, gacha_id, old_gacha_type, new_gacha_type
+    );
+
+    if GACHA_TYPES.contains(&old_gacha_type.as_str()) {
+        reconcile_pity_from_records(&app, &conn, &uid, &old_gacha_type)?;
+    }
+    reconcile_pity_from_records(&app, &conn, &uid, &new_gacha_type)?;
+    Ok(())
+}
+
+/// 次高稀有度（5★）保底抽数，所有卡池相同
+///
+/// 角色卡池（限定 / 常驻 / 新手）每 10 抽必出 5★ 及以上；
+/// 武器池按 10 抽一轮申领，每轮同样至少一把 5★ 及以上。
+const FOUR_STAR_HARD_PITY: u32 = 10;
+
+/// 一次出次高稀有度及以上时的保底数
+#[derive(Debug, Clone, Serialize)]
+struct PityPoint {
+    gacha_id: String,
+    name: String,
+    rarity: i64,
+    /// 距上一次出 5★ 及以上的抽数（含本抽，免费抽不计入）
+    pity: u32,
+    /// 抽卡时间（毫秒时间戳）
+    time: String,
+}
+
+/// 次高稀有度保底历史
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FourStarPityHistory {
+    /// 该卡池的 5★ 保底抽数
+    hard_pity: u32,
+    /// 当前已累计的抽数
+    current_four_star_pity: u32,
+    history: Vec<PityPoint>,
+}
+
+/// Tauri 命令：某卡池次高稀有度（5★）的保底历史
+///
+/// 与最高稀有度的保底口径一致，只是出 5★ 或 6★ 都会重置计数。
+#[tauri::command]
+pub(crate) fn get_four_star_pity_history(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+) -> Result<FourStarPityHistory, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_id, name, rarity, is_free, time FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询保底记录失败: {}", e)))?;
+    let rows = query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })
+    .map_err(|e| AppError::database(format!("读取保底记录失败: {}", e)))?;
+
+    let mut walker = PityWalker::new(TOP_RARITY - 1);
+    let mut history = Vec::new();
+    for (gacha_id, name, rarity, is_free, time) in rows {
+        let Some(pity) = walker.step(rarity, is_free != 0) else {
+            continue;
+        };
+        if rarity >= TOP_RARITY - 1 {
+            history.push(PityPoint {
+                gacha_id,
+                name,
+                rarity,
+                pity,
+                time,
+            });
+        }
+    }
+    Ok(FourStarPityHistory {
+        hard_pity: FOUR_STAR_HARD_PITY,
+        current_four_star_pity: walker.current(),
+        history,
+    })
+}
+
+/// 武器池按 10 抽一轮申领，4 轮内必出 6★
+const WEAPON_TOP_RARITY_HARD_PITY: u32 = 40;
+
+/// 某卡池最高稀有度的保底区间（按距上次出金的抽数）
+#[derive(Debug, Clone, Copy)]
+struct PityThresholds {
+    /// 超过该抽数后概率提升，为空表示没有概率提升
+    soft: Option<u32>,
+    hard: u32,
+}
+
+impl PityThresholds {
+    fn for_gacha_type(gacha_type: &str) -> Self {
+        match gacha_type {
+            "weapon" => Self {
+                soft: None,
+                hard: WEAPON_TOP_RARITY_HARD_PITY,
+            },
+            _ => Self {
+                soft: Some(TOP_RARITY_SOFT_PITY),
+                hard: TOP_RARITY_HARD_PITY,
+            },
+        }
+    }
+
+    /// 第 `pity` 抽是否处于（概率提升区，保底抽）
+    fn flags(&self, pity: u32) -> (bool, bool) {
+        let in_hard_pity = pity >= self.hard;
+        let in_soft_pity = !in_hard_pity && self.soft.is_some_and(|soft| pity > soft);
+        (in_soft_pity, in_hard_pity)
+    }
+}
+
+/// 保底走势中的一抽
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PityPointWithFlags {
+    gacha_id: String,
+    time: String,
+    rarity: i64,
+    /// 距上次出最高稀有度的抽数（含本抽）
+    pity: u32,
+    /// 处于概率提升区
+    in_soft_pity: bool,
+    /// 到达保底抽数
+    in_hard_pity: bool,
+}
+
+/// Tauri 命令：某卡池每一抽的最高稀有度保底计数（按时间正序，免费抽不计入也不返回）
+///
+/// 区间按该卡池类型的保底规则在这里判断，图表只需按标记绘制背景，无需知道阈值。
+#[tauri::command]
+pub(crate) fn get_pity_over_time(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+) -> Result<Vec<PityPointWithFlags>, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let thresholds = PityThresholds::for_gacha_type(&gacha_type);
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_id, time, rarity, is_free FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询保底记录失败: {}", e)))?;
+    let rows = query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })
+    .map_err(|e| AppError::database(format!("读取保底记录失败: {}", e)))?;
+
+    let mut walker = PityWalker::new(TOP_RARITY);
+    let mut points = Vec::new();
+    for (gacha_id, time, rarity, is_free) in rows {
+        let Some(pity) = walker.step(rarity, is_free != 0) else {
+            continue;
+        };
+        let (in_soft_pity, in_hard_pity) = thresholds.flags(pity);
+        points.push(PityPointWithFlags {
+            gacha_id,
+            time,
+            rarity,
+            pity,
+            in_soft_pity,
+            in_hard_pity,
+        });
+    }
+    Ok(points)
+}
+
+/// 最长的一次"不出金"（两次最高稀有度之间，或至今仍未出的一段）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct DroughtInfo {
+    /// 最长一段的抽数（出金那一抽计入，免费抽不计入）
+    max_pity: u32,
+    /// 该段第一抽的 gacha_id
+    drought_start_gacha_id: Option<String>,
+    /// 结束该段的那一抽；最长一段就是当前仍在继续的一段时为空
+    drought_end_gacha_id: Option<String>,
+    /// 结束该段时抽到的物品名
+    ended_by: Option<String>,
+    /// 当前保底计数（与 `PityStatus::pity` 一致）
+    current_drought_pity: u32,
+}
+
+fn longest_drought(rows: &[PityRow]) -> DroughtInfo {
+    let mut info = DroughtInfo::default();
+    let mut walker = PityWalker::new(TOP_RARITY);
+    let mut span_start: Option<&str> = None;
+    for row in rows {
+        let Some(pity) = walker.step(row.rarity, row.is_free) else {
+            continue;
+        };
+        span_start.get_or_insert(row.gacha_id.as_str());
+        if row.rarity >= TOP_RARITY {
+            if pity > info.max_pity {
+                info.max_pity = pity;
+                info.drought_start_gacha_id = span_start.map(str::to_string);
+                info.drought_end_gacha_id = Some(row.gacha_id.clone());
+                info.ended_by = Some(row.name.clone());
+            }
+            span_start = None;
+        }
+    }
+    let pity = walker.current();
+    if pity > info.max_pity {
+        info.max_pity = pity;
+        info.drought_start_gacha_id = span_start.map(str::to_string);
+        info.drought_end_gacha_id = None;
+        info.ended_by = None;
+    }
+    info.current_drought_pity = pity;
+    info
+}
+
+/// Tauri 命令：某卡池最长的一次不出金（按时间顺序扫描，当前仍未出金的一段也参与比较）
+#[tauri::command]
+pub(crate) fn get_longest_five_star_drought(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+) -> Result<DroughtInfo, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let conn = open_db(&app)?;
+    Ok(longest_drought(&query_pity_rows(&conn, &uid, &gacha_type)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(gacha_id: u32, pool_id: &str, name: &str, rarity: i64) -> PityRow {
+        PityRow {
+            gacha_id: gacha_id.to_string(),
+            item_id: String::new(),
+            name: name.to_string(),
+            pool_id: pool_id.to_string(),
+            rarity,
+            is_free: false,
+        }
+    }
+
+    #[test]
+    fn builtin_meta_parses() {
+        assert!(!banner_meta().gacha_types["special"].milestones.is_empty());
+        assert!(banner_meta().pools.contains_key("special_1_0_1"));
+    }
+
+    #[test]
+    fn featured_counter_ignores_off_banner_top_rarity() {
+        let mut rows: Vec<_> = (1..=5).map(|i| row(i, "special_1_0_1", "x", 3)).collect();
+        rows.push(row(6, "special_1_0_1", "莱万汀", 6));
+        rows.extend((7..=9).map(|i| row(i, "special_1_0_1", "x", 3)));
+        rows.push(row(10, "special_1_0_1", "余烬", 6));
+        rows.push(row(11, "special_1_0_1", "x", 3));
+
+        let status = tally_pity("1", "special", &rows);
+        assert_eq!(status.pity, 1);
+        assert_eq!(status.last_five_star_gacha_id.as_deref(), Some("10"));
+        assert_eq!(status.since_featured.pulls, 5);
+        assert_eq!(status.since_featured.reset_by.as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn free_top_rarity_mid_streak_does_not_reset_pity() {
+        // 付费 3 抽 → 免费 6★ → 付费 2 抽：免费抽既不计数也不重置
+        let mut rows: Vec<_> = (1..=3).map(|i| row(i, "special_1_0_1", "x", 3)).collect();
+        let mut free = row(4, "special_1_0_1", "莱万汀", 6);
+        free.is_free = true;
+        rows.push(free);
+        rows.extend((5..=6).map(|i| row(i, "special_1_0_1", "x", 3)));
+
+        let status = tally_pity("1", "special", &rows);
+        assert_eq!(status.pity, 5);
+        assert_eq!(status.last_five_star_gacha_id, None);
+        assert_eq!(status.since_featured.pulls, 5);
+        assert_eq!(status.since_featured.reset_by, None);
+
+        let info = longest_drought(&rows);
+        assert_eq!(info.max_pity, 5);
+        assert_eq!(info.current_drought_pity, 5);
+        assert_eq!(info.drought_end_gacha_id, None);
+
+        let mut walker = PityWalker::new(TOP_RARITY);
+        let counts: Vec<_> = rows
+            .iter()
+            .map(|r| walker.step(r.rarity, r.is_free))
+            .collect();
+        assert_eq!(counts, [Some(1), Some(2), Some(3), None, Some(4), Some(5)]);
+    }
+
+    #[test]
+    fn featured_matches_item_id_across_languages() {
+        let meta = banner_meta();
+        for pool in meta.pools.values() {
+            for id in &pool.featured {
+                assert!(
+                    crate::commands::items::resolve_item(id.clone()).is_some(),
+                    "{}",
+                    id
+                );
+            }
+        }
+        assert!(is_featured(meta, &row(1, "special_1_0_1", "Laevatain", 6)));
+        let mut renamed = row(2, "special_1_0_1", "Laevatain (Alt Name)", 6);
+        renamed.item_id = "chr_0016_laevat".to_string();
+        assert!(is_featured(meta, &renamed));
+        assert!(!is_featured(meta, &row(3, "special_1_0_1", "Ember", 6)));
+    }
+
+    #[test]
+    fn repeating_milestone_counts_only_current_pool() {
+        let milestone = MilestoneMeta {
+            id: "token".to_string(),
+            pulls: 4,
+            repeat: true,
+            per_pool: true,
+            reset_on_featured: false,
+        };
+        let mut rows: Vec<_> = (1..=3).map(|i| row(i, "special_1_0_1", "x", 3)).collect();
+        rows.extend((4..=13).map(|i| row(i, "special_1_0_2", "x", 3)));
+
+        let progress = milestone_progress(banner_meta(), &milestone, &rows);
+        assert_eq!(progress.pool_id.as_deref(), Some("special_1_0_2"));
+        assert_eq!(progress.lifetime_pulls, 10);
+        assert_eq!(progress.times_reached, 2);
+        assert_eq!(progress.since_reached.pulls, 2);
+        assert_eq!(progress.since_reached.reset_by.as_deref(), Some("11"));
+    }
+
+    #[test]
+    fn pity_flags_follow_gacha_type_thresholds() {
+        let character = PityThresholds::for_gacha_type("special");
+        assert_eq!(character.flags(TOP_RARITY_SOFT_PITY), (false, false));
+        assert_eq!(character.flags(TOP_RARITY_SOFT_PITY + 1), (true, false));
+        assert_eq!(character.flags(TOP_RARITY_HARD_PITY), (false, true));
+
+        let weapon = PityThresholds::for_gacha_type("weapon");
+        assert_eq!(weapon.flags(39), (false, false));
+        assert_eq!(weapon.flags(40), (false, true));
+    }
+
+    #[test]
+    fn longest_drought_spans_between_top_rarity() {
+        let mut rows: Vec<_> = (1..=3).map(|i| row(i, "p", "x", 3)).collect();
+        rows.push(row(4, "p", "A", 6));
+        rows.extend((5..=9).map(|i| row(i, "p", "x", 3)));
+        rows[5].is_free = true;
+        rows.push(row(10, "p", "B", 6));
+        rows.extend((11..=12).map(|i| row(i, "p", "x", 3)));
+
+        let info = longest_drought(&rows);
+        assert_eq!(info.max_pity, 5);
+        assert_eq!(info.drought_start_gacha_id.as_deref(), Some("5"));
+        assert_eq!(info.drought_end_gacha_id.as_deref(), Some("10"));
+        assert_eq!(info.ended_by.as_deref(), Some("B"));
+        assert_eq!(info.current_drought_pity, 2);
+
+        rows.extend((13..=20).map(|i| row(i, "p", "x", 3)));
+        let info = longest_drought(&rows);
+        assert_eq!(info.max_pity, 10);
+        assert_eq!(info.drought_start_gacha_id.as_deref(), Some("11"));
+        assert_eq!(info.drought_end_gacha_id, None);
+        assert_eq!(info.current_drought_pity, 10);
+    }
+}
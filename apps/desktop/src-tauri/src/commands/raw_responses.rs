@@ -0,0 +1,409 @@
+//! 抽卡接口原始响应留档
+//!
+//! 排查"这条记录为什么解析错了"时需要接口当时返回的原文。开启 keep_raw_responses 后，
+//! 同步时每页响应原样保存到 `userdata/raw/`，文件名带时间戳与接口类型；不保存请求地址（含 token）。
+//! 同时在 raw_response_records 表记下每条记录来自哪个文件的第几页、在响应中的位置，供记录溯源取回原文。
+//! 默认关闭以节省空间；超过保留天数的留档在启动与修改设置时清理（关闭后旧留档同样按天数清理）。
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::config::{current_config, update_config, userdata_dir, write_file_atomic};
+use crate::db::access::with_db_write;
+use crate::error::AppError;
+
+const RAW_RESPONSE_DIR: &str = "raw";
+/// 未设置保留天数时的默认值
+const DEFAULT_RAW_RESPONSE_KEEP_DAYS: u32 = 7;
+/// 文件名中的时间戳格式（本地时间，精确到毫秒，同一秒内多页不会重名）
+const RAW_RESPONSE_TS_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// 一页响应中各条记录的位置，保存留档时一并写入索引
+pub(crate) struct RawPageIndex {
+    pub(crate) uid: String,
+    /// 页码（从 1 开始）
+    pub(crate) page: u32,
+    /// (record_uid, 记录对象在响应中的 JSON Pointer)
+    pub(crate) records: Vec<(String, String)>,
+}
+
+/// 在留档中查找一条记录原文的结果
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RawRecordLookup {
+    Found {
+        file_name: String,
+        page: u32,
+        object: Value,
+    },
+    /// 有索引但留档文件已被清理（超过保留天数或手动删除）
+    Pruned { file_name: String, page: u32 },
+    /// 没有索引：同步时未开启留档，或记录早于留档索引
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawResponseSettings {
+    enabled: bool,
+    keep_days: u32,
+    dir: Option<String>,
+}
+
+fn raw_response_dir() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join(RAW_RESPONSE_DIR))
+}
+
+fn keep_days(app: &AppHandle) -> u32 {
+    current_config(app)
+        .raw_response_keep_days
+        .unwrap_or(DEFAULT_RAW_RESPONSE_KEEP_DAYS)
+        .max(1)
+}
+
+/// 留档文件名：`<时间戳>-<接口类型>.json`，类型中的非字母数字字符替换为 `_`
+fn raw_file_name(now: DateTime<Local>, kind: &str) -> String {
+    let kind: String = kind
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!(
+        "{}-{}.json",
+        now.format(RAW_RESPONSE_TS_FORMAT),
+        if kind.is_empty() { "unknown" } else { &kind }
+    )
+}
+
+/// 从文件名解析保存时间（不是本功能生成的文件返回 None，不会被清理）
+fn saved_at(file_name: &str) -> Option<DateTime<Local>> {
+    let ts = file_name.get(..19)?;
+    let naive = NaiveDateTime::parse_from_str(ts, RAW_RESPONSE_TS_FORMAT).ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// 删除早于 now - keep_days 的留档，返回删除的文件数
+fn prune_raw_responses(dir: &Path, keep_days: u32, now: DateTime<Local>) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let cutoff = now - Duration::days(i64::from(keep_days));
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(saved_at)
+                .is_some_and(|at| at < cutoff)
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// 按当前设置清理过期留档（启动时调用）
+pub(crate) fn prune_expired_raw_responses(app: &AppHandle) {
+    let Ok(dir) = raw_response_dir() else {
+        return;
+    };
+    let removed = prune_raw_responses(&dir, keep_days(app), Local::now());
+    if removed > 0 {
+        eprintln!("[raw] 已清理 {} 份过期的原始响应留档", removed);
+    }
+}
+
+/// 把点分路径（如 `data.list`）与数组下标拼成 JSON Pointer（`/data/list/3`）
+pub(crate) fn list_item_pointer(list_path: &str, index: usize) -> String {
+    let mut pointer = String::new();
+    for segment in list_path
+        .split('.')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        pointer.push('/');
+        pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    }
+    format!("{}/{}", pointer, index)
+}
+
+/// 官方接口（角色池/武器池）一页响应的索引：`data.list[i].seqId` 对应 `<uid>_<kind>_<seqId>`
+fn official_page_index(uid: &str, kind: &str, page: u32, body: &str) -> Option<RawPageIndex> {
+    if !matches!(kind, "char" | "weapon") {
+        return None;
+    }
+    let body: Value = serde_json::from_str(body).ok()?;
+    let list = body.pointer("/data/list")?.as_array()?;
+    let records = list
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let seq_id = match item.get("seqId")? {
+                Value::String(s) if !s.trim().is_empty() => s.trim().to_string(),
+                Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some((
+                format!("{}_{}_{}", uid, kind, seq_id),
+                list_item_pointer("data.list", i),
+            ))
+        })
+        .collect();
+    Some(RawPageIndex {
+        uid: uid.to_string(),
+        page,
+        records,
+    })
+}
+
+fn index_raw_page(
+    conn: &Connection,
+    file_name: &str,
+    index: &RawPageIndex,
+) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO raw_response_records (record_uid, uid, file_name, page, pointer)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for (record_uid, pointer) in &index.records {
+            stmt.execute(rusqlite::params![
+                record_uid, index.uid, file_name, index.page, pointer
+            ])?;
+        }
+    }
+    tx.commit()
+}
+
+/// 开启留档时保存一份响应原文并写入记录索引（Rust 端请求的接口直接调用）；失败只记日志，不影响同步
+///
+/// 测试请求等不对应入库记录的响应传 `index: None`，只留文件。
+pub(crate) fn keep_raw_response(
+    app: &AppHandle,
+    kind: &str,
+    body: &str,
+    index: Option<RawPageIndex>,
+) {
+    if !current_config(app).keep_raw_responses {
+        return;
+    }
+    let file_name = raw_file_name(Local::now(), kind);
+    let result = raw_response_dir().and_then(|dir| {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("创建留档目录失败: {}", e))?;
+        write_file_atomic(&dir.join(&file_name), body.as_bytes())
+    });
+    if let Err(e) = result {
+        eprintln!("[raw] 保存原始响应失败: {}", e);
+        return;
+    }
+    let Some(index) = index.filter(|index| !index.records.is_empty()) else {
+        return;
+    };
+    if let Err(e) = with_db_write(app, "写入原始响应索引", |conn| {
+        index_raw_page(conn, &file_name, &index)
+    }) {
+        eprintln!("[raw] 写入原始响应索引失败: {}", e);
+    }
+}
+
+/// 按索引在 dir 中查找记录原文；同一记录出现在多份留档时取最新的一份
+fn find_raw_record_in(
+    conn: &Connection,
+    dir: Option<&Path>,
+    record_uid: &str,
+) -> rusqlite::Result<RawRecordLookup> {
+    let mut stmt = conn.prepare(
+        "SELECT file_name, page, pointer FROM raw_response_records
+          WHERE record_uid = ?1
+          ORDER BY file_name DESC",
+    )?;
+    let rows = stmt
+        .query_map([record_uid], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (file_name, page, pointer) in &rows {
+        let object = dir
+            .and_then(|dir| std::fs::read_to_string(dir.join(file_name)).ok())
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .and_then(|body| body.pointer(pointer).cloned());
+        if let Some(object) = object {
+            return Ok(RawRecordLookup::Found {
+                file_name: file_name.clone(),
+                page: *page,
+                object,
+            });
+        }
+    }
+    Ok(match rows.into_iter().next() {
+        Some((file_name, page, _)) => RawRecordLookup::Pruned { file_name, page },
+        None => RawRecordLookup::Missing,
+    })
+}
+
+/// 查找一条记录在原始响应留档中的原文
+pub(crate) fn find_raw_record(
+    conn: &Connection,
+    record_uid: &str,
+) -> Result<RawRecordLookup, AppError> {
+    let dir = raw_response_dir().ok();
+    find_raw_record_in(conn, dir.as_deref(), record_uid)
+        .map_err(|e| AppError::database(format!("查询原始响应索引失败: {}", e)))
+}
+
+/// Tauri 命令：获取原始响应留档设置
+#[tauri::command]
+pub(crate) fn get_raw_response_settings(app: AppHandle) -> RawResponseSettings {
+    RawResponseSettings {
+        enabled: current_config(&app).keep_raw_responses,
+        keep_days: keep_days(&app),
+        dir: raw_response_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().into_owned()),
+    }
+}
+
+/// Tauri 命令：修改留档开关与保留天数（keep_days 为空表示使用默认值），随后按新天数清理
+#[tauri::command]
+pub(crate) fn set_raw_response_settings(
+    app: AppHandle,
+    enabled: bool,
+    keep_days: Option<u32>,
+) -> Result<RawResponseSettings, AppError> {
+    if keep_days == Some(0) {
+        return Err(AppError::validation("保留天数至少为 1 天"));
+    }
+    update_config(&app, |c| {
+        c.keep_raw_responses = enabled;
+        c.raw_response_keep_days = keep_days;
+    })?;
+    prune_expired_raw_responses(&app);
+    Ok(get_raw_response_settings(app))
+}
+
+/// Tauri 命令：前端请求的接口（角色池/武器池）把每页响应原文交给 Rust 端保存
+///
+/// uid 为本地账号 uid，page 为该卡池的第几页；响应能解析时同时为其中每条记录写入索引。
+#[tauri::command]
+pub(crate) fn save_raw_response(
+    app: AppHandle,
+    uid: String,
+    kind: String,
+    body: String,
+    page: u32,
+) {
+    let index = official_page_index(uid.trim(), kind.trim(), page, &body);
+    keep_raw_response(&app, &kind, &body, index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn file_name_round_trips_timestamp() {
+        let now = at(2026, 10, 17);
+        let name = raw_file_name(now, "record/char");
+        assert_eq!(name, "20261017-120000.000-record_char.json");
+        assert_eq!(saved_at(&name), Some(now));
+        assert_eq!(saved_at("notes.txt"), None);
+    }
+
+    #[test]
+    fn prunes_only_expired_raw_files() {
+        let dir = std::env::temp_dir().join(format!("efgacha-raw-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let old = raw_file_name(at(2026, 10, 1), "char");
+        let recent = raw_file_name(at(2026, 10, 15), "weapon");
+        for name in [old.as_str(), recent.as_str(), "keep-me.txt"] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        assert_eq!(prune_raw_responses(&dir, 7, at(2026, 10, 17)), 1);
+        assert!(!dir.join(&old).exists());
+        assert!(dir.join(&recent).exists());
+        assert!(dir.join("keep-me.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn list_item_pointer_escapes_segments() {
+        assert_eq!(list_item_pointer("data.list", 3), "/data/list/3");
+        assert_eq!(
+            list_item_pointer(" result . a/b~c ", 0),
+            "/result/a~1b~0c/0"
+        );
+    }
+
+    #[test]
+    fn indexes_official_pages_by_record_uid() {
+        let body = r#"{"code":0,"data":{"list":[{"seqId":"12"},{"seqId":13},{"name":"x"}]}}"#;
+        let index = official_page_index("u1", "char", 2, body).unwrap();
+        assert_eq!(index.page, 2);
+        assert_eq!(
+            index.records,
+            vec![
+                ("u1_char_12".to_string(), "/data/list/0".to_string()),
+                ("u1_char_13".to_string(), "/data/list/1".to_string()),
+            ]
+        );
+        assert!(official_page_index("u1", "custom", 1, body).is_none());
+        assert!(official_page_index("u1", "weapon", 1, "not json").is_none());
+    }
+
+    #[test]
+    fn finds_newest_retained_record_or_reports_pruned() {
+        let dir = std::env::temp_dir().join(format!("efgacha-raw-find-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = crate::db::test_db();
+        let old = raw_file_name(at(2026, 10, 1), "char");
+        let new = raw_file_name(at(2026, 10, 15), "char");
+        std::fs::write(
+            dir.join(&new),
+            r#"{"data":{"list":[{"seqId":"7"},{"seqId":"8","name":"Ardelia"}]}}"#,
+        )
+        .unwrap();
+        for (file_name, page, pointer) in [(&old, 3, "/data/list/0"), (&new, 1, "/data/list/1")] {
+            let index = RawPageIndex {
+                uid: "u1".to_string(),
+                page,
+                records: vec![("u1_char_8".to_string(), pointer.to_string())],
+            };
+            index_raw_page(&conn, file_name, &index).unwrap();
+        }
+
+        assert_eq!(
+            find_raw_record_in(&conn, Some(&dir), "u1_char_8").unwrap(),
+            RawRecordLookup::Found {
+                file_name: new.clone(),
+                page: 1,
+                object: serde_json::json!({ "seqId": "8", "name": "Ardelia" }),
+            }
+        );
+        std::fs::remove_file(dir.join(&new)).unwrap();
+        assert_eq!(
+            find_raw_record_in(&conn, Some(&dir), "u1_char_8").unwrap(),
+            RawRecordLookup::Pruned {
+                file_name: new,
+                page: 1
+            }
+        );
+        assert_eq!(
+            find_raw_record_in(&conn, Some(&dir), "u1_char_9").unwrap(),
+            RawRecordLookup::Missing
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1677 @@
+//! 记录查询、备注与收藏
+
+use chrono::{TimeZone, Utc};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::hash_chain::check_hash_chain;
+use crate::commands::pity::{reconcile_pity_from_records, PityWalker};
+use crate::commands::raw_responses::{find_raw_record, RawRecordLookup};
+use crate::db::access::with_db_write;
+use crate::db::{
+    open_db, parse_gacha_ts, parse_local_date, pull_sort_key, query_pulls_by_time,
+    validate_gacha_type, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY, TOP_RARITY_HARD_PITY,
+    TOP_RARITY_SOFT_PITY,
+};
+use crate::error::AppError;
+
+/// 抽卡记录（pull_records 视图中的一行）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PullRecord {
+    record_uid: String,
+    uid: String,
+    pool_id: String,
+    pool_name: String,
+    item_id: String,
+    name: String,
+    rarity: i64,
+    is_free: bool,
+    time: String,
+    gacha_id: String,
+    item_type: String,
+    gacha_type: String,
+    note: Option<String>,
+    favorite: bool,
+    /// 已归档（不可再核对，修复类操作默认跳过）
+    archived: bool,
+    /// 保底标注（仅 `get_annotated_pull_history` 填充）：soft_pity / hard_pity
+    pity_annotation: Option<String>,
+    /// 距上次最高稀有度的抽数，含本抽（仅 `get_pull_history_sorted` 填充，免费抽为空）
+    pity: Option<u32>,
+}
+
+/// pull_records 的完整列（与 `PullRecord` 字段顺序一致）
+const PULL_RECORD_COLUMNS: &str = "record_uid, uid, pool_id, pool_name, item_id, name, rarity, \
+     is_free, time, gacha_id, item_type, gacha_type, note, favorite, archived";
+
+fn pull_record_from_row(row: &rusqlite::Row) -> rusqlite::Result<PullRecord> {
+    Ok(PullRecord {
+        record_uid: row.get(0)?,
+        uid: row.get(1)?,
+        pool_id: row.get(2)?,
+        pool_name: row.get(3)?,
+        item_id: row.get(4)?,
+        name: row.get(5)?,
+        rarity: row.get(6)?,
+        is_free: row.get::<_, i64>(7)? != 0,
+        time: row.get(8)?,
+        gacha_id: row.get(9)?,
+        item_type: row.get(10)?,
+        gacha_type: row.get(11)?,
+        note: row.get(12)?,
+        favorite: row.get::<_, i64>(13)? != 0,
+        archived: row.get::<_, i64>(14)? != 0,
+        pity_annotation: None,
+        pity: None,
+    })
+}
+
+/// Tauri 命令：按账号（可选卡池类型）查询抽卡记录，按时间倒序，包含备注与收藏字段
+#[tauri::command]
+pub(crate) fn get_pull_records(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+) -> Result<Vec<PullRecord>, AppError> {
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let mut records = query_pulls_by_time(
+        &mut stmt,
+        rusqlite::params![uid, gacha_type],
+        pull_record_from_row,
+    )
+    .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    records.reverse();
+    Ok(records)
+}
+
+/// 按时间倒序读取后逐条通过通道发送，发送失败（前端已关闭通道）时停止
+fn send_pull_records(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: Option<&str>,
+    mut send: impl FnMut(PullRecord) -> Result<(), String>,
+) -> Result<u64, AppError> {
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let records = query_pulls_by_time(
+        &mut stmt,
+        rusqlite::params![uid, gacha_type],
+        pull_record_from_row,
+    )
+    .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    let mut sent = 0;
+    for record in records.into_iter().rev() {
+        send(record).map_err(|e| AppError::io(format!("发送抽卡记录失败: {}", e)))?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// Tauri 命令：按时间倒序逐条推送抽卡记录到前端通道（大数据量导出用，前端不必整体缓存）
+///
+/// 返回推送的条数；前端在命令完成后即收到了全部记录。
+#[tauri::command]
+pub(crate) async fn stream_pull_records(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    channel: tauri::ipc::Channel<PullRecord>,
+) -> Result<u64, AppError> {
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = open_db(&app)?;
+        send_pull_records(&conn, &uid, gacha_type.as_deref(), |record| {
+            channel.send(record).map_err(|e| e.to_string())
+        })
+    })
+    .await
+    .map_err(|e| AppError::io(format!("推送任务异常结束: {}", e)))?
+}
+
+/// 单页大小上限
+const PULL_PAGE_SIZE_MAX: u32 = 500;
+
+/// 分页的抽卡记录（按时间倒序）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PullPage {
+    records: Vec<PullRecord>,
+    /// 该卡池的记录总数
+    total: u64,
+    /// 页码（从 1 开始）
+    page: u32,
+    page_size: u32,
+}
+
+/// 按距上次最高稀有度的抽数（含本抽，免费抽不计）判断保底区间
+fn pity_annotation(pity: u32) -> Option<&'static str> {
+    if pity >= TOP_RARITY_HARD_PITY {
+        Some("hard_pity")
+    } else if pity > TOP_RARITY_SOFT_PITY {
+        Some("soft_pity")
+    } else {
+        None
+    }
+}
+
+/// 按时间正序为每条记录标注保底区间（免费抽不计入保底，也不标注）
+fn annotate_pity(records: &mut [PullRecord]) {
+    let mut walker = PityWalker::new(TOP_RARITY);
+    for record in records.iter_mut() {
+        if let Some(pity) = walker.step(record.rarity, record.is_free) {
+            record.pity_annotation = pity_annotation(pity).map(str::to_string);
+        }
+    }
+}
+
+/// Tauri 命令：分页查询指定卡池的抽卡记录，并标注落在软保底 / 硬保底区间的抽数
+///
+/// 保底计数需要完整历史，因此先按时间正序读出整个卡池计算标注，再按时间倒序取出所需的一页。
+#[tauri::command]
+pub(crate) fn get_annotated_pull_history(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PullPage, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    if page == 0 {
+        return Err(AppError::validation("页码从 1 开始"));
+    }
+    if page_size == 0 || page_size > PULL_PAGE_SIZE_MAX {
+        return Err(AppError::validation(format!(
+            "每页条数须在 1~{} 之间",
+            PULL_PAGE_SIZE_MAX
+        )));
+    }
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let mut records = query_pulls_by_time(
+        &mut stmt,
+        rusqlite::params![uid, gacha_type],
+        pull_record_from_row,
+    )
+    .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    annotate_pity(&mut records);
+
+    let total = records.len() as u64;
+    let skip = (page as usize - 1).saturating_mul(page_size as usize);
+    let records = records
+        .into_iter()
+        .rev()
+        .skip(skip)
+        .take(page_size as usize)
+        .collect();
+    Ok(PullPage {
+        records,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// `get_pull_history_sorted` 可用的排序列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Time,
+    Name,
+    RankType,
+    Pity,
+    GachaId,
+}
+
+impl SortColumn {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "time" => Ok(Self::Time),
+            "name" => Ok(Self::Name),
+            "rank_type" => Ok(Self::RankType),
+            "pity" => Ok(Self::Pity),
+            "gacha_id" => Ok(Self::GachaId),
+            _ => Err(AppError::validation(format!(
+                "未知的排序列: {}（可选值: time, name, rank_type, pity, gacha_id）",
+                value
+            ))),
+        }
+    }
+
+    /// 按该列比较两条记录（免费抽没有保底计数，按保底排序时排在最前）
+    fn compare(self, a: &PullRecord, b: &PullRecord) -> std::cmp::Ordering {
+        match self {
+            Self::Time => {
+                pull_sort_key(&a.time, &a.gacha_id).cmp(&pull_sort_key(&b.time, &b.gacha_id))
+            }
+            Self::Name => a.name.cmp(&b.name),
+            Self::RankType => a.rarity.cmp(&b.rarity),
+            Self::Pity => a.pity.cmp(&b.pity),
+            Self::GachaId => sort_int(&a.gacha_id).cmp(&sort_int(&b.gacha_id)),
+        }
+    }
+}
+
+/// 按整数比较的排序键（不是数字时为 0）
+fn sort_int(value: &str) -> i64 {
+    value.trim().parse().unwrap_or(0)
+}
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value {
+            "asc" => Ok(Self::Asc),
+            "desc" => Ok(Self::Desc),
+            _ => Err(AppError::validation(format!(
+                "未知的排序方向: {}（可选值: asc, desc）",
+                value
+            ))),
+        }
+    }
+}
+
+/// 带保底抽数的排序分页查询
+///
+/// 保底计数需要完整历史：先按时间正序读出，用 `PityWalker` 在各卡池内分别计数（与 `annotate_pity` 口径一致），
+/// 再按所选列稳定排序后取出所需的一页；同值时按时间、gacha_id 同方向排序。
+fn query_sorted_history(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: Option<&str>,
+    column: SortColumn,
+    direction: SortDirection,
+    page: u32,
+    page_size: u32,
+) -> rusqlite::Result<Vec<PullRecord>> {
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut records = query_pulls_by_time(
+        &mut stmt,
+        rusqlite::params![uid, gacha_type],
+        pull_record_from_row,
+    )?;
+
+    let mut walkers: HashMap<String, PityWalker> = HashMap::new();
+    for record in &mut records {
+        record.pity = walkers
+            .entry(record.gacha_type.clone())
+            .or_insert_with(|| PityWalker::new(TOP_RARITY))
+            .step(record.rarity, record.is_free);
+    }
+
+    // 已是时间正序；倒序时先整体反转，稳定排序后同值记录保持时间倒序
+    match direction {
+        SortDirection::Asc => records.sort_by(|a, b| column.compare(a, b)),
+        SortDirection::Desc => {
+            records.reverse();
+            records.sort_by(|a, b| column.compare(b, a));
+        }
+    }
+    let offset = (page as usize - 1).saturating_mul(page_size as usize);
+    Ok(records
+        .into_iter()
+        .skip(offset)
+        .take(page_size as usize)
+        .collect())
+}
+
+/// Tauri 命令：按指定列与方向排序分页查询抽卡记录
+///
+/// `sort_column` 可选 time / name / rank_type / pity / gacha_id，`sort_direction` 可选 asc / desc；
+/// 同值时按时间、gacha_id 同方向排序。`gacha_type` 为空时查询该账号全部卡池（保底仍按各自卡池计算）。
+#[tauri::command]
+pub(crate) fn get_pull_history_sorted(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    sort_column: String,
+    sort_direction: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PullPage, AppError> {
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+    let column = SortColumn::parse(sort_column.trim())?;
+    let direction = SortDirection::parse(sort_direction.trim())?;
+    if page == 0 {
+        return Err(AppError::validation("页码从 1 开始"));
+    }
+    if page_size == 0 || page_size > PULL_PAGE_SIZE_MAX {
+        return Err(AppError::validation(format!(
+            "每页条数须在 1~{} 之间",
+            PULL_PAGE_SIZE_MAX
+        )));
+    }
+
+    let conn = open_db(&app)?;
+    let total: u64 = conn
+        .query_row(
+            &format!(
+                "{} SELECT COUNT(*) FROM pull_records
+                   WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+                PULL_RECORDS_CTE
+            ),
+            rusqlite::params![uid, gacha_type],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::database(format!("统计抽卡记录失败: {}", e)))?;
+    let records = query_sorted_history(
+        &conn,
+        &uid,
+        gacha_type.as_deref(),
+        column,
+        direction,
+        page,
+        page_size,
+    )
+    .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    Ok(PullPage {
+        records,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// 游标分页方向（相对游标 gacha_id）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PullCursor {
+    /// 比游标更早的记录
+    Before,
+    /// 比游标更新的记录
+    After,
+}
+
+/// 按 gacha_id 游标查询一页记录，结果统一按 gacha_id 倒序（与列表展示顺序一致）
+///
+/// 向后翻页时先正序取紧邻游标的 `limit` 条再反转，避免 OFFSET 扫描跳过的行。
+fn query_pulls_by_cursor(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: Option<&str>,
+    cursor: PullCursor,
+    gacha_id: i64,
+    limit: u32,
+) -> rusqlite::Result<Vec<PullRecord>> {
+    let (op, order) = match cursor {
+        PullCursor::Before => ("<", "DESC"),
+        PullCursor::After => (">", "ASC"),
+    };
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)
+             AND CAST(gacha_id AS INTEGER) {} ?3
+           ORDER BY CAST(gacha_id AS INTEGER) {} LIMIT ?4",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS, op, order
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut records = stmt
+        .query_map(
+            rusqlite::params![uid, gacha_type, gacha_id, limit],
+            pull_record_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    if cursor == PullCursor::After {
+        records.reverse();
+    }
+    Ok(records)
+}
+
+fn pulls_by_cursor(
+    app: &AppHandle,
+    uid: &str,
+    gacha_type: Option<&str>,
+    cursor: PullCursor,
+    gacha_id: &str,
+    limit: u32,
+) -> Result<Vec<PullRecord>, AppError> {
+    if let Some(gacha_type) = gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+    let gacha_id: i64 = gacha_id
+        .trim()
+        .parse()
+        .map_err(|_| AppError::validation(format!("无效的 gacha_id 游标: {}", gacha_id)))?;
+    if limit == 0 || limit > PULL_PAGE_SIZE_MAX {
+        return Err(AppError::validation(format!(
+            "每页条数须在 1~{} 之间",
+            PULL_PAGE_SIZE_MAX
+        )));
+    }
+    let conn = open_db(app)?;
+    let records = query_pulls_by_cursor(&conn, uid, gacha_type, cursor, gacha_id, limit)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    Ok(records)
+}
+
+/// Tauri 命令：游标分页，查询 gacha_id 小于 `before_gacha_id` 的 `limit` 条记录（倒序）
+///
+/// 以上一页最后一条的 gacha_id 作为游标，翻页耗时不随页码增长（OFFSET 需要逐行跳过）。
+#[tauri::command]
+pub(crate) fn get_pulls_before(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    before_gacha_id: String,
+    limit: u32,
+) -> Result<Vec<PullRecord>, AppError> {
+    pulls_by_cursor(
+        &app,
+        &uid,
+        gacha_type.as_deref(),
+        PullCursor::Before,
+        &before_gacha_id,
+        limit,
+    )
+}
+
+/// Tauri 命令：游标分页，查询 gacha_id 大于 `after_gacha_id` 的紧邻 `limit` 条记录（倒序）
+#[tauri::command]
+pub(crate) fn get_pulls_after(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    after_gacha_id: String,
+    limit: u32,
+) -> Result<Vec<PullRecord>, AppError> {
+    pulls_by_cursor(
+        &app,
+        &uid,
+        gacha_type.as_deref(),
+        PullCursor::After,
+        &after_gacha_id,
+        limit,
+    )
+}
+
+/// 记录列表的筛选条件（各项均可省略）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct RecordFilter {
+    gacha_type: Option<String>,
+    /// 最低稀有度（含）
+    min_rarity: Option<i64>,
+    /// 名称包含的文字
+    keyword: Option<String>,
+    favorites_only: bool,
+    hide_archived: bool,
+}
+
+/// 键集分页的游标：上一页最后一条记录的时间与 gacha_id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordCursor {
+    time: String,
+    gacha_id: String,
+}
+
+/// 按筛选条件分页的记录（按时间倒序）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordPage {
+    records: Vec<PullRecord>,
+    /// 符合筛选条件的记录总数
+    total: u64,
+    /// 本页最后一条的游标，传给 `query_records_keyset` 取下一页；已到末尾时为空
+    next_cursor: Option<RecordCursor>,
+}
+
+/// 筛选条件对应的 WHERE 子句（固定字符串）。参数：?1 uid，?2 gacha_type，?3 最低稀有度，
+/// ?4 关键字，?5 仅收藏，?6 隐藏已归档
+const RECORD_FILTER_SQL: &str = "uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)
+      AND (?3 IS NULL OR rarity >= ?3) AND (?4 IS NULL OR instr(name, ?4) > 0)
+      AND (?5 = 0 OR favorite != 0) AND (?6 = 0 OR archived = 0)";
+
+impl RecordFilter {
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(gacha_type) = &self.gacha_type {
+            validate_gacha_type(gacha_type)?;
+        }
+        Ok(())
+    }
+
+    fn keyword(&self) -> Option<&str> {
+        self.keyword
+            .as_deref()
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+    }
+}
+
+fn validate_page_limit(limit: u32) -> Result<(), AppError> {
+    if limit == 0 || limit > PULL_PAGE_SIZE_MAX {
+        return Err(AppError::validation(format!(
+            "每页条数须在 1~{} 之间",
+            PULL_PAGE_SIZE_MAX
+        )));
+    }
+    Ok(())
+}
+
+/// 按筛选条件查询一页；`cursor` 为空时按 `offset` 跳过，否则从游标之后开始
+///
+/// 时间格式不一，游标比较用 `pull_sort_key`，因此先按时间读出全部符合条件的记录再定位。
+fn query_record_page(
+    conn: &Connection,
+    uid: &str,
+    filter: &RecordFilter,
+    cursor: Option<&RecordCursor>,
+    offset: u32,
+    limit: u32,
+) -> rusqlite::Result<RecordPage> {
+    let sql = format!(
+        "{} SELECT {} FROM pull_records WHERE {}",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS, RECORD_FILTER_SQL
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let matched = query_pulls_by_time(
+        &mut stmt,
+        rusqlite::params![
+            uid,
+            filter.gacha_type,
+            filter.min_rarity,
+            filter.keyword(),
+            filter.favorites_only,
+            filter.hide_archived
+        ],
+        pull_record_from_row,
+    )?;
+    let total = matched.len() as u64;
+    let after = cursor.map(|c| pull_sort_key(&c.time, &c.gacha_id));
+    let records: Vec<PullRecord> = matched
+        .into_iter()
+        .rev()
+        .skip_while(|r| after.is_some_and(|after| pull_sort_key(&r.time, &r.gacha_id) >= after))
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+    let next_cursor = records
+        .last()
+        .filter(|_| records.len() == limit as usize)
+        .map(|last| RecordCursor {
+            time: last.time.clone(),
+            gacha_id: last.gacha_id.clone(),
+        });
+    Ok(RecordPage {
+        records,
+        total,
+        next_cursor,
+    })
+}
+
+/// Tauri 命令：按筛选条件分页查询记录（LIMIT / OFFSET），同时返回总数
+///
+/// 顺序翻页时优先用返回的 `next_cursor` 调用 `query_records_keyset`，期间新增记录不会造成重复或遗漏。
+#[tauri::command]
+pub(crate) fn query_records_paged(
+    app: AppHandle,
+    uid: String,
+    filter: RecordFilter,
+    offset: u32,
+    limit: u32,
+) -> Result<RecordPage, AppError> {
+    filter.validate()?;
+    validate_page_limit(limit)?;
+    let conn = open_db(&app)?;
+    let page = query_record_page(&conn, &uid, &filter, None, offset, limit)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    Ok(page)
+}
+
+/// Tauri 命令：按筛选条件键集分页，从 `cursor`（上一页的 `next_cursor`）之后取 `limit` 条
+///
+/// 按 (时间, gacha_id) 定位（与 `pull_sort_key` 口径一致）；`cursor` 为空时返回第一页。
+#[tauri::command]
+pub(crate) fn query_records_keyset(
+    app: AppHandle,
+    uid: String,
+    filter: RecordFilter,
+    cursor: Option<RecordCursor>,
+    limit: u32,
+) -> Result<RecordPage, AppError> {
+    filter.validate()?;
+    validate_page_limit(limit)?;
+    let conn = open_db(&app)?;
+    let page = query_record_page(&conn, &uid, &filter, cursor.as_ref(), 0, limit)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    Ok(page)
+}
+
+/// 在角色/武器两张记录表中按 record_uid 更新，返回受影响的行数
+fn update_record_by_id(
+    conn: &Connection,
+    set_clause: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> rusqlite::Result<usize> {
+    let mut changed = 0;
+    for table in ["gacha_records", "weapon_records"] {
+        changed += conn.execute(
+            &format!(
+                "UPDATE {} SET {}, edited_at = {} WHERE record_uid = ?1",
+                table,
+                set_clause,
+                Utc::now().timestamp_millis()
+            ),
+            params,
+        )?;
+    }
+    Ok(changed)
+}
+
+/// Tauri 命令：设置记录备注（传空字符串或 null 清除备注）
+#[tauri::command]
+pub(crate) fn set_record_note(
+    app: AppHandle,
+    id: String,
+    note: Option<String>,
+) -> Result<(), AppError> {
+    let note = note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+    let changed = with_db_write(&app, "保存备注", |conn| {
+        update_record_by_id(conn, "note = ?2", &[&id, &note])
+    })?;
+    if changed == 0 {
+        return Err(AppError::validation(format!("未找到抽卡记录: {}", id)));
+    }
+    Ok(())
+}
+
+/// Tauri 命令：切换记录的收藏状态，返回切换后的状态
+#[tauri::command]
+pub(crate) fn toggle_favorite(app: AppHandle, id: String) -> Result<bool, AppError> {
+    with_db_write(&app, "切换收藏", |conn| {
+        update_record_by_id(conn, "favorite = 1 - favorite", &[&id])?;
+        let sql = format!(
+            "{} SELECT favorite FROM pull_records WHERE record_uid = ?1",
+            PULL_RECORDS_CTE
+        );
+        conn.query_row(&sql, [&id], |row| row.get::<_, i64>(0))
+            .optional()
+    })?
+    .map(|favorite| favorite != 0)
+    .ok_or_else(|| AppError::validation(format!("未找到抽卡记录: {}", id)))
+}
+
+/// 单次批量删除的记录数上限
+const BATCH_DELETE_LIMIT: usize = 1000;
+
+/// 批量删除结果（随 `pull-records-deleted` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+struct PullRecordsDeleted {
+    uid: String,
+    count: u64,
+}
+
+/// Tauri 命令：按 record_uid 列表批量删除某账号的记录（用于撤销重复导入），返回删除条数
+///
+/// 不同卡池（角色池与武器池）的 gacha_id 可能相同，按 record_uid 定位才不会误删另一张表的记录。
+/// record_uid 全部通过参数绑定传入；删除后重建该账号的哈希链并重算受影响卡池的保底。
+/// 已归档的记录默认跳过，`include_archived` 为真时才一并删除。
+#[tauri::command]
+pub(crate) fn batch_delete_pulls(
+    app: AppHandle,
+    uid: String,
+    record_uids: Vec<String>,
+    include_archived: Option<bool>,
+) -> Result<u64, AppError> {
+    if record_uids.is_empty() {
+        return Ok(0);
+    }
+    if record_uids.len() > BATCH_DELETE_LIMIT {
+        return Err(AppError::validation(format!(
+            "单次最多删除 {} 条记录（本次 {} 条）",
+            BATCH_DELETE_LIMIT,
+            record_uids.len()
+        )));
+    }
+
+    let placeholders = vec!["?"; record_uids.len()].join(", ");
+    let params = || std::iter::once(&uid).chain(record_uids.iter());
+    let archived_filter = if include_archived.unwrap_or(false) {
+        ""
+    } else {
+        " AND archived = 0"
+    };
+
+    let (deleted, affected_types) = with_db_write(&app, "删除抽卡记录", |conn| {
+        let sql = format!(
+            "{} SELECT DISTINCT gacha_type FROM pull_records WHERE uid = ? AND record_uid IN ({}){}",
+            PULL_RECORDS_CTE, placeholders, archived_filter
+        );
+        let affected_types = conn
+            .prepare(&sql)?
+            .query_map(rusqlite::params_from_iter(params()), |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut deleted = 0;
+        for table in ["gacha_records", "weapon_records"] {
+            deleted += tx.execute(
+                &format!(
+                    "DELETE FROM {} WHERE uid = ? AND record_uid IN ({}){}",
+                    table, placeholders, archived_filter
+                ),
+                rusqlite::params_from_iter(params()),
+            )?;
+        }
+        // 主动删除会改变链上位置，删除后按剩余记录重建哈希链
+        if deleted > 0 {
+            tx.execute("DELETE FROM record_hash_chain WHERE uid = ?1", [&uid])?;
+            check_hash_chain(&tx, &uid, true)?;
+        }
+        tx.commit()?;
+        Ok((deleted as u64, affected_types))
+    })?;
+
+    if deleted > 0 {
+        eprintln!("[delete] {} 批量删除 {} 条记录", uid, deleted);
+        let _ = app.emit(
+            "pull-records-deleted",
+            PullRecordsDeleted {
+                uid: uid.clone(),
+                count: deleted,
+            },
+        );
+        let conn = open_db(&app)?;
+        for gacha_type in affected_types
+            .iter()
+            .filter(|t| GACHA_TYPES.contains(&t.as_str()))
+        {
+            reconcile_pity_from_records(&app, &conn, &uid, gacha_type)?;
+        }
+    }
+    Ok(deleted)
+}
+
+/// 游戏正式上线日期（本地时间），早于此的抽卡时间视为无效
+const GAME_RELEASE_DATE: &str = "2026-01-22";
+
+/// 解析 ISO 8601 时间；不带时区时按本地时间处理
+fn parse_iso_time(raw: &str) -> Option<chrono::DateTime<Utc>> {
+    let s = raw.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(s, format).ok())
+        .and_then(|naive| chrono::Local.from_local_datetime(&naive).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// 解析并校验手动设置的抽卡时间（须在游戏上线之后、不晚于当前时间）
+fn validate_pull_time(raw: &str) -> Result<chrono::DateTime<Utc>, AppError> {
+    let time = parse_iso_time(raw).ok_or_else(|| {
+        AppError::validation(format!(
+            "时间格式应为 ISO 8601（如 2026-02-01T12:00:00+08:00）: {}",
+            raw.trim()
+        ))
+    })?;
+    let earliest = parse_local_date(GAME_RELEASE_DATE).unwrap_or_default();
+    if time < earliest {
+        return Err(AppError::validation(format!(
+            "时间早于游戏上线日期 {}",
+            GAME_RELEASE_DATE
+        )));
+    }
+    if time > Utc::now() {
+        return Err(AppError::validation("时间不能晚于当前时间"));
+    }
+    Ok(time)
+}
+
+/// 按 record_uid 找到要修改的唯一一条记录，返回 (uid, gacha_type, archived)
+///
+/// 角色与武器记录分表保存，同一 record_uid 在两张表里都出现时无法确定目标，按错误处理。
+fn find_single_record(conn: &Connection, id: &str) -> Result<(String, String, bool), AppError> {
+    let sql = format!(
+        "{} SELECT uid, gacha_type, archived FROM pull_records WHERE record_uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut records = conn
+        .prepare(&sql)
+        .and_then(|mut stmt| {
+            stmt.query_map([id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? != 0))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    match records.len() {
+        0 => Err(AppError::validation(format!("未找到抽卡记录: {}", id))),
+        1 => Ok(records.remove(0)),
+        n => Err(AppError::validation(format!(
+            "记录 {} 匹配到 {} 条，无法确定要修改哪一条",
+            id, n
+        ))),
+    }
+}
+
+/// Tauri 命令：修正单条记录的抽卡时间（用于修复导入时丢失或错误的时间）
+///
+/// `id` 为 record_uid，只修改这一条记录（不同卡池的 gacha_id 可能相同，不能按 gacha_id 定位）。
+/// `new_time` 为 ISO 8601 时间，按库中格式保存为毫秒时间戳。修改后重建该账号的哈希链
+/// （链中包含时间），并重算记录所在卡池的保底。已归档的记录不允许修改。
+#[tauri::command]
+pub(crate) fn set_pull_time(app: AppHandle, id: String, new_time: String) -> Result<(), AppError> {
+    let time = validate_pull_time(&new_time)?;
+    let conn = open_db(&app)?;
+    let (uid, gacha_type, archived) = find_single_record(&conn, &id)?;
+    if archived {
+        return Err(AppError::validation(format!(
+            "记录 {} 已归档，不能修改时间",
+            id
+        )));
+    }
+
+    let millis = time.timestamp_millis().to_string();
+    with_db_write(&app, "修改抽卡时间", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        update_record_by_id(&tx, "gacha_ts = ?2", &[&id, &millis])?;
+        tx.execute("DELETE FROM record_hash_chain WHERE uid = ?1", [&uid])?;
+        check_hash_chain(&tx, &uid, true)?;
+        tx.commit()
+    })?;
+    eprintln!("[records] {} 时间改为 {}", id, time.to_rfc3339());
+
+    if GACHA_TYPES.contains(&gacha_type.as_str()) {
+        reconcile_pity_from_records(&app, &conn, &uid, &gacha_type)?;
+    }
+    Ok(())
+}
+
+/// 归档日期（本地时间 YYYY-MM-DD）当天零点，早于此时间的记录会被归档
+fn archive_cutoff(before_date: &str) -> Result<chrono::DateTime<Utc>, AppError> {
+    parse_local_date(before_date)
+        .ok_or_else(|| AppError::validation(format!("日期格式应为 YYYY-MM-DD: {}", before_date)))
+}
+
+/// 把指定记录标记为已归档，返回新归档的条数
+fn mark_archived(app: &AppHandle, op: &str, record_uids: &[String]) -> Result<u64, AppError> {
+    if record_uids.is_empty() {
+        return Ok(0);
+    }
+    with_db_write(app, op, |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut archived = 0;
+        for table in ["gacha_records", "weapon_records"] {
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE {} SET archived = 1 WHERE record_uid = ?1 AND archived = 0",
+                table
+            ))?;
+            for record_uid in record_uids {
+                archived += stmt.execute([record_uid])?;
+            }
+        }
+        tx.commit()?;
+        Ok(archived as u64)
+    })
+}
+
+/// Tauri 命令：把早于指定日期（本地时间 YYYY-MM-DD）的记录标记为已归档，返回新归档的条数
+///
+/// 超出官方接口保留期的记录再也无法与服务器核对，归档后去重、批量删除、卡池纠正等修复操作默认跳过它们，
+/// 缺口检查中落在归档区间内的缺口也只作提示。归档不可撤销；`uid` 为空时处理全部账号。
+#[tauri::command]
+pub(crate) fn archive_records(
+    app: AppHandle,
+    before_date: String,
+    uid: Option<String>,
+) -> Result<u64, AppError> {
+    let cutoff = archive_cutoff(&before_date)?;
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT record_uid, time FROM pull_records
+           WHERE archived = 0 AND (?1 IS NULL OR uid = ?1)",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    // 记录时间格式不统一（秒/毫秒/日期字符串），逐条解析后比较
+    let record_uids = stmt
+        .query_map([&uid], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?
+        .filter_map(|row| match row {
+            Ok((record_uid, time)) => parse_gacha_ts(&time)
+                .filter(|t| *t < cutoff)
+                .map(|_| Ok(record_uid)),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<rusqlite::Result<Vec<String>>>()
+        .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+
+    let archived = mark_archived(&app, "归档抽卡记录", &record_uids)?;
+    eprintln!(
+        "[archive] 归档 {} 之前的记录 {} 条",
+        before_date.trim(),
+        archived
+    );
+    Ok(archived)
+}
+
+/// Tauri 命令：按记录 id 标记为已归档（导入带归档标记的文件后调用，保留原来的归档状态）
+#[tauri::command]
+pub(crate) fn mark_records_archived(
+    app: AppHandle,
+    record_uids: Vec<String>,
+) -> Result<u64, AppError> {
+    mark_archived(&app, "恢复归档标记", &record_uids)
+}
+
+/// Tauri 命令：查询从某次同步开始（含该次）首次入库的记录，按时间倒序
+///
+/// 以记录首次入库时标记的 sync_run_id 为准，而不是按时间筛选：增量同步重复拉到的旧记录不会出现在结果中。
+#[tauri::command]
+pub(crate) fn get_records_since(
+    app: AppHandle,
+    sync_run_id: i64,
+) -> Result<Vec<PullRecord>, AppError> {
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT {} FROM pull_records
+           WHERE sync_run_id >= ?1",
+        PULL_RECORDS_CTE, PULL_RECORD_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let mut records = query_pulls_by_time(&mut stmt, [sync_run_id], pull_record_from_row)
+        .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    records.reverse();
+    Ok(records)
+}
+
+/// 原始响应的保留状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum RawResponseStatus {
+    /// 留档中找到的记录原文：所在留档文件、第几页，以及该条记录在响应中的 JSON 对象
+    Retained {
+        file: String,
+        page: u32,
+        object: serde_json::Value,
+    },
+    /// 记录所属的同步进行时没有开启原始响应留档
+    Disabled { reason: String },
+    /// 找不到原文（留档已被清理，或记录来自导入、早于留档索引），只能看到入库后的行
+    NotRetained { reason: String },
+}
+
+/// 原文中疑似凭据的字段名（转小写后包含其一即视为敏感）
+const SENSITIVE_RAW_KEYS: &[&str] = &["token", "cookie", "authorization", "password", "secret"];
+const REDACTED: &str = "<redacted>";
+
+/// 隐去字符串中 `token=...` 形式的查询参数值（接口有时会回显带 token 的地址）
+fn redact_token_params(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find("token=") {
+        let value_start = pos + "token=".len();
+        out.push_str(&rest[..value_start]);
+        out.push_str(REDACTED);
+        let value_len = rest[value_start..]
+            .find(['&', '#', '"', ' '])
+            .unwrap_or(rest.len() - value_start);
+        rest = &rest[value_start + value_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 把原文中疑似凭据的字段值替换为占位符（递归处理嵌套对象与数组）
+fn redact_raw_object(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SENSITIVE_RAW_KEYS.iter().any(|s| key.contains(s)) {
+                    *value = REDACTED.into();
+                } else {
+                    redact_raw_object(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_raw_object),
+        serde_json::Value::String(text) if text.contains("token=") => {
+            *text = redact_token_params(text);
+        }
+        _ => {}
+    }
+}
+
+/// 根据留档查找结果与所属同步批次得出原始响应状态
+///
+/// 只有确知该批次同步时关闭了留档才返回 Disabled；较早的同步没有记下开关状态，按未保留处理。
+fn raw_response_status(
+    lookup: RawRecordLookup,
+    sync_run: Option<&ProvenanceSyncRun>,
+) -> RawResponseStatus {
+    match lookup {
+        RawRecordLookup::Found {
+            file_name,
+            page,
+            mut object,
+        } => {
+            redact_raw_object(&mut object);
+            RawResponseStatus::Retained {
+                file: file_name,
+                page,
+                object,
+            }
+        }
+        RawRecordLookup::Pruned { file_name, page } => RawResponseStatus::NotRetained {
+            reason: format!("第 {} 页的留档 {} 已超过保留天数被清理", page, file_name),
+        },
+        RawRecordLookup::Missing => match sync_run {
+            Some(run) if run.raw_responses_kept == Some(false) => RawResponseStatus::Disabled {
+                reason: format!("同步批次 {} 进行时未开启原始响应留档", run.id),
+            },
+            _ => RawResponseStatus::NotRetained {
+                reason: "没有这条记录的原始响应留档".to_string(),
+            },
+        },
+    }
+}
+
+/// 记录所属的同步批次（优先取入库时标记的 sync_run_id，较早的记录按 fetched_at 落在哪次同步的时间范围内推断）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProvenanceSyncRun {
+    id: i64,
+    started_at: i64,
+    total_ms: i64,
+    records_added: i64,
+    /// 该次同步时是否开启了原始响应留档（较早的同步未记录，为空）
+    raw_responses_kept: Option<bool>,
+}
+
+/// 入库后对记录做过的处理
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NormalizationStep {
+    /// clock_skew / gacha_type_override / manual_edit / banner_period / archived
+    kind: &'static str,
+    detail: String,
+}
+
+/// `get_record_provenance` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordProvenance {
+    record_uid: String,
+    /// gacha_records / weapon_records
+    table: &'static str,
+    raw: RawResponseStatus,
+    fetched_at: Option<i64>,
+    sync_run: Option<ProvenanceSyncRun>,
+    normalization: Vec<NormalizationStep>,
+    /// 最终入库的行（全部列）
+    stored_row: serde_json::Map<String, serde_json::Value>,
+}
+
+fn column_to_json(value: rusqlite::types::ValueRef) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(v) => v.into(),
+        ValueRef::Real(v) => v.into(),
+        ValueRef::Text(v) => String::from_utf8_lossy(v).into_owned().into(),
+        ValueRef::Blob(v) => format!("<{} bytes>", v.len()).into(),
+    }
+}
+
+/// 根据 Rust 端追加的列还原入库后做过的处理
+fn normalization_steps(row: &serde_json::Map<String, serde_json::Value>) -> Vec<NormalizationStep> {
+    let text = |key: &str| {
+        row.get(key)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+    };
+    let int = |key: &str| row.get(key).and_then(|v| v.as_i64());
+    let mut steps = Vec::new();
+    if let Some(skew) = int("clock_skew_ms") {
+        steps.push(NormalizationStep {
+            kind: "clock_skew",
+            detail: format!("入库时检测到本地时钟偏差 {} ms", skew),
+        });
+    }
+    if let Some(gacha_type) = text("gacha_type_override") {
+        steps.push(NormalizationStep {
+            kind: "gacha_type_override",
+            detail: format!("卡池类型手动纠正为 {}", gacha_type),
+        });
+    }
+    if let Some(period) = text("banner_period") {
+        steps.push(NormalizationStep {
+            kind: "banner_period",
+            detail: format!("按卡池排期归入 {} 开始的卡池期", period),
+        });
+    }
+    if let Some(edited_at) = int("edited_at") {
+        let time = Utc
+            .timestamp_millis_opt(edited_at)
+            .single()
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_else(|| edited_at.to_string());
+        steps.push(NormalizationStep {
+            kind: "manual_edit",
+            detail: format!("{} 手动修改过（备注 / 收藏 / 卡池 / 时间）", time),
+        });
+    }
+    if int("archived").is_some_and(|archived| archived != 0) {
+        steps.push(NormalizationStep {
+            kind: "archived",
+            detail: "已归档".to_string(),
+        });
+    }
+    steps
+}
+
+/// Tauri 命令：查看一条记录的来源（排查保底数不对等问题）
+///
+/// 返回最终入库的行、入库后做过的处理以及所属的同步批次。
+/// 同步时开启了原始响应留档且留档未被清理时，`raw` 给出该条记录在接口响应中的原文（隐去 token 等凭据）
+/// 及其所在的留档文件与页码；同步时未开启留档标注为 disabled，其余情况标注未保留及原因，而不是返回空值。
+#[tauri::command]
+pub(crate) fn get_record_provenance(
+    app: AppHandle,
+    record_id: String,
+) -> Result<RecordProvenance, AppError> {
+    let conn = open_db(&app)?;
+    let mut found = None;
+    for table in ["gacha_records", "weapon_records"] {
+        let row = conn
+            .query_row(
+                &format!("SELECT * FROM {} WHERE record_uid = ?1", table),
+                [&record_id],
+                |row| {
+                    let mut map = serde_json::Map::new();
+                    for (i, name) in row.as_ref().column_names().iter().enumerate() {
+                        map.insert(name.to_string(), column_to_json(row.get_ref(i)?));
+                    }
+                    Ok(map)
+                },
+            )
+            .optional()
+            .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+        if let Some(row) = row {
+            found = Some((table, row));
+            break;
+        }
+    }
+    let (table, stored_row) =
+        found.ok_or_else(|| AppError::validation(format!("未找到抽卡记录: {}", record_id)))?;
+
+    let fetched_at = stored_row.get("fetched_at").and_then(|v| v.as_i64());
+    let uid = stored_row
+        .get("uid")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let sync_run_id = stored_row.get("sync_run_id").and_then(|v| v.as_i64());
+    let sync_run = match (sync_run_id, fetched_at) {
+        (None, None) => None,
+        _ => conn
+            .query_row(
+                "SELECT id, started_at, total_ms, records_added, raw_responses_kept FROM sync_history
+                  WHERE id = ?3
+                     OR (?3 IS NULL AND uid = ?1 AND started_at <= ?2 AND started_at + total_ms >= ?2)
+                  ORDER BY started_at DESC LIMIT 1",
+                rusqlite::params![uid, fetched_at, sync_run_id],
+                |row| {
+                    Ok(ProvenanceSyncRun {
+                        id: row.get(0)?,
+                        started_at: row.get(1)?,
+                        total_ms: row.get(2)?,
+                        records_added: row.get(3)?,
+                        raw_responses_kept: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| AppError::database(format!("查询同步历史失败: {}", e)))?,
+    };
+
+    let raw = raw_response_status(find_raw_record(&conn, &record_id)?, sync_run.as_ref());
+    Ok(RecordProvenance {
+        record_uid: record_id,
+        table,
+        raw,
+        fetched_at,
+        sync_run,
+        normalization: normalization_steps(&stored_row),
+        stored_row,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    #[test]
+    fn pity_annotation_boundaries() {
+        assert_eq!(pity_annotation(1), None);
+        assert_eq!(pity_annotation(TOP_RARITY_SOFT_PITY), None);
+        assert_eq!(pity_annotation(TOP_RARITY_SOFT_PITY + 1), Some("soft_pity"));
+        assert_eq!(pity_annotation(TOP_RARITY_HARD_PITY - 1), Some("soft_pity"));
+        assert_eq!(pity_annotation(TOP_RARITY_HARD_PITY), Some("hard_pity"));
+    }
+
+    #[test]
+    fn pull_time_accepts_iso_8601_within_range() {
+        assert_eq!(
+            validate_pull_time("2026-02-01T12:00:00+08:00")
+                .unwrap()
+                .timestamp(),
+            1_769_918_400
+        );
+        assert!(validate_pull_time("2026-02-01T12:00:00").is_ok());
+        assert!(validate_pull_time("2026/02/01").is_err());
+        assert!(validate_pull_time("1970-01-01T00:00:00Z").is_err());
+        assert!(validate_pull_time("2999-01-01T00:00:00Z").is_err());
+    }
+
+    #[test]
+    fn archive_cutoff_requires_plain_date() {
+        assert!(archive_cutoff("2025-01-01").is_ok());
+        assert!(archive_cutoff(" 2025-01-01 ").is_ok());
+        assert!(archive_cutoff("2025/01/01").is_err());
+        assert!(archive_cutoff("2025-13-01").is_err());
+    }
+
+    #[test]
+    fn normalization_steps_follow_extra_columns() {
+        let row: serde_json::Map<String, serde_json::Value> = serde_json::from_str(
+            r#"{"clock_skew_ms": null, "gacha_type_override": "standard",
+                "banner_period": "", "edited_at": 1769918400000, "archived": 0}"#,
+        )
+        .unwrap();
+        let kinds: Vec<_> = normalization_steps(&row)
+            .iter()
+            .map(|step| step.kind)
+            .collect();
+        assert_eq!(kinds, ["gacha_type_override", "manual_edit"]);
+    }
+
+    #[test]
+    fn raw_status_redacts_tokens_and_reports_disabled_runs() {
+        let found = RawRecordLookup::Found {
+            file_name: "20261017-120000.000-char.json".to_string(),
+            page: 2,
+            object: serde_json::json!({
+                "seqId": "8",
+                "u8Token": "secret-1",
+                "extra": { "url": "https://x/api?lang=en&token=abc&server_id=1" },
+                "items": [{ "accessToken": "secret-2" }]
+            }),
+        };
+        let RawResponseStatus::Retained { file, page, object } = raw_response_status(found, None)
+        else {
+            panic!("应返回留档原文");
+        };
+        assert_eq!((file.as_str(), page), ("20261017-120000.000-char.json", 2));
+        assert_eq!(
+            object,
+            serde_json::json!({
+                "seqId": "8",
+                "u8Token": "<redacted>",
+                "extra": { "url": "https://x/api?lang=en&token=<redacted>&server_id=1" },
+                "items": [{ "accessToken": "<redacted>" }]
+            })
+        );
+
+        let run = |kept| ProvenanceSyncRun {
+            id: 3,
+            started_at: 0,
+            total_ms: 0,
+            records_added: 0,
+            raw_responses_kept: kept,
+        };
+        assert!(matches!(
+            raw_response_status(RawRecordLookup::Missing, Some(&run(Some(false)))),
+            RawResponseStatus::Disabled { .. }
+        ));
+        // 开关状态未知或同步时开着（索引缺失）都不算"未开启"
+        for kept in [None, Some(true)] {
+            assert!(matches!(
+                raw_response_status(RawRecordLookup::Missing, Some(&run(kept))),
+                RawResponseStatus::NotRetained { .. }
+            ));
+        }
+        assert!(matches!(
+            raw_response_status(
+                RawRecordLookup::Pruned {
+                    file_name: "a.json".to_string(),
+                    page: 1
+                },
+                Some(&run(Some(false)))
+            ),
+            RawResponseStatus::NotRetained { .. }
+        ));
+    }
+
+    #[test]
+    fn sort_options_are_whitelisted() {
+        assert_eq!(
+            SortColumn::parse("rank_type").unwrap(),
+            SortColumn::RankType
+        );
+        assert!(SortColumn::parse("name; DROP TABLE gacha_records").is_err());
+        assert!(SortColumn::parse("Time").is_err());
+        assert_eq!(SortDirection::parse("desc").unwrap(), SortDirection::Desc);
+        assert!(SortDirection::parse("descending").is_err());
+    }
+
+    #[test]
+    fn single_record_lookup_rejects_missing_and_ambiguous_ids() {
+        let conn = test_db();
+        // 角色池与武器池的 gacha_id 相同，按 record_uid 仍能唯一定位
+        let rows = [
+            ("gacha_records", "1:1_char_7", "special_1", "7", 0),
+            ("gacha_records", "dup", "standard_1", "8", 0),
+            ("weapon_records", "1:1_weapon_7", "weapon_1", "7", 1),
+            ("weapon_records", "dup", "weapon_1", "8", 0),
+        ];
+        for (table, id, pool, seq, archived) in rows {
+            insert_test_row(
+                &conn,
+                table,
+                &[
+                    ("record_uid", &id),
+                    ("uid", &"1:1"),
+                    ("pool_id", &pool),
+                    ("seq_id", &seq),
+                    ("archived", &archived),
+                ],
+            );
+        }
+
+        assert_eq!(
+            find_single_record(&conn, "1:1_char_7").unwrap(),
+            ("1:1".to_string(), "special".to_string(), false)
+        );
+        assert_eq!(
+            find_single_record(&conn, "1:1_weapon_7").unwrap(),
+            ("1:1".to_string(), "weapon".to_string(), true)
+        );
+        assert!(find_single_record(&conn, "dup").is_err());
+        assert!(find_single_record(&conn, "missing").is_err());
+    }
+
+    #[test]
+    fn sorted_history_counts_pity_per_pool() {
+        let conn = test_db();
+        // 限定池：4★、6★、免费 6★、4★；常驻池：4★
+        let rows = [
+            ("a", "special_1", "甲", 4, 0, "1000", "1"),
+            ("b", "special_1", "乙", 6, 0, "2000", "2"),
+            ("c", "special_1", "丙", 6, 1, "3000", "3"),
+            ("d", "special_1", "丁", 4, 0, "4000", "4"),
+            ("e", "standard_1", "戊", 4, 0, "1500", "5"),
+        ];
+        for (id, pool, name, rarity, free, ts, seq) in rows {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &id),
+                    ("uid", &"1:1"),
+                    ("pool_id", &pool),
+                    ("char_name", &name),
+                    ("rarity", &rarity),
+                    ("is_free", &free),
+                    ("gacha_ts", &ts),
+                    ("seq_id", &seq),
+                ],
+            );
+        }
+
+        let records = query_sorted_history(
+            &conn,
+            "1:1",
+            None,
+            SortColumn::Time,
+            SortDirection::Asc,
+            1,
+            10,
+        )
+        .unwrap();
+        let pities: Vec<_> = records
+            .iter()
+            .map(|r| (r.record_uid.as_str(), r.pity))
+            .collect();
+        assert_eq!(
+            pities,
+            [
+                ("a", Some(1)),
+                ("e", Some(1)),
+                ("b", Some(2)),
+                ("c", None),
+                ("d", Some(1))
+            ]
+        );
+
+        let page = query_sorted_history(
+            &conn,
+            "1:1",
+            Some("special"),
+            SortColumn::Pity,
+            SortDirection::Desc,
+            1,
+            2,
+        )
+        .unwrap();
+        let ids: Vec<_> = page.iter().map(|r| r.record_uid.as_str()).collect();
+        assert_eq!(ids, ["b", "d"]);
+    }
+
+    #[test]
+    fn cursor_pages_are_adjacent_and_descending() {
+        let conn = test_db();
+        // seq_id 跨位数，确认按数值而非字符串比较
+        for seq in [8, 9, 10, 11, 12] {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &seq.to_string()),
+                    ("uid", &"1:1"),
+                    ("pool_id", &"special_1"),
+                    ("rarity", &4),
+                    ("gacha_ts", &(seq * 1000).to_string()),
+                    ("seq_id", &seq.to_string()),
+                ],
+            );
+        }
+        let ids = |records: Vec<PullRecord>| -> Vec<String> {
+            records.into_iter().map(|r| r.gacha_id).collect()
+        };
+
+        let before = query_pulls_by_cursor(&conn, "1:1", None, PullCursor::Before, 11, 2).unwrap();
+        assert_eq!(ids(before), ["10", "9"]);
+        let after = query_pulls_by_cursor(&conn, "1:1", None, PullCursor::After, 8, 2).unwrap();
+        assert_eq!(ids(after), ["10", "9"]);
+        let none = query_pulls_by_cursor(&conn, "1:1", Some("standard"), PullCursor::Before, 99, 2)
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn keyset_pages_match_offset_pages() {
+        let conn = test_db();
+        // 两条记录同一时间，靠 gacha_id 区分先后
+        let rows = [
+            ("a", "甲", 4, 0, "1000", "1"),
+            ("b", "乙", 6, 1, "2000", "2"),
+            ("c", "丙", 5, 0, "2000", "3"),
+            ("d", "甲", 6, 0, "3000", "4"),
+            ("e", "丁", 4, 0, "4000", "5"),
+        ];
+        for (id, name, rarity, favorite, ts, seq) in rows {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &id),
+                    ("uid", &"1:1"),
+                    ("pool_id", &"special_1"),
+                    ("char_name", &name),
+                    ("rarity", &rarity),
+                    ("gacha_ts", &ts),
+                    ("seq_id", &seq),
+                    ("favorite", &favorite),
+                ],
+            );
+        }
+        let ids = |page: &RecordPage| -> Vec<String> {
+            page.records.iter().map(|r| r.record_uid.clone()).collect()
+        };
+        let all = RecordFilter::default();
+
+        let first = query_record_page(&conn, "1:1", &all, None, 0, 2).unwrap();
+        assert_eq!(
+            (ids(&first), first.total),
+            (vec!["e".into(), "d".into()], 5)
+        );
+        let second =
+            query_record_page(&conn, "1:1", &all, first.next_cursor.as_ref(), 0, 2).unwrap();
+        let by_offset = query_record_page(&conn, "1:1", &all, None, 2, 2).unwrap();
+        assert_eq!(ids(&second), ["c", "b"]);
+        assert_eq!(ids(&second), ids(&by_offset));
+        let last =
+            query_record_page(&conn, "1:1", &all, second.next_cursor.as_ref(), 0, 2).unwrap();
+        assert_eq!(ids(&last), ["a"]);
+        assert!(last.next_cursor.is_none());
+
+        let filter = RecordFilter {
+            min_rarity: Some(5),
+            keyword: Some(" 甲 ".to_string()),
+            ..RecordFilter::default()
+        };
+        let page = query_record_page(&conn, "1:1", &filter, None, 0, 10).unwrap();
+        assert_eq!((ids(&page), page.total), (vec!["d".into()], 1));
+        let favorites = RecordFilter {
+            favorites_only: true,
+            ..RecordFilter::default()
+        };
+        let page = query_record_page(&conn, "1:1", &favorites, None, 0, 10).unwrap();
+        assert_eq!(ids(&page), ["b"]);
+    }
+
+    #[test]
+    fn keyset_pages_follow_parsed_time_across_formats() {
+        let conn = test_db();
+        // 秒级、毫秒级时间戳与日期字符串混存：按数值比较会把日期串当作年份、秒级排在毫秒级之前
+        let rows = [
+            ("a", "2024-05-01 12:00:00", "1"),
+            ("b", "1717000000", "2"),
+            ("c", "1717185600000", "3"),
+            ("d", "1717185700", "4"),
+        ];
+        for (id, ts, seq) in rows {
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &id),
+                    ("uid", &"1:1"),
+                    ("pool_id", &"special_1"),
+                    ("rarity", &4),
+                    ("gacha_ts", &ts),
+                    ("seq_id", &seq),
+                ],
+            );
+        }
+        let ids = |page: &RecordPage| -> Vec<String> {
+            page.records.iter().map(|r| r.record_uid.clone()).collect()
+        };
+        let all = RecordFilter::default();
+
+        let first = query_record_page(&conn, "1:1", &all, None, 0, 2).unwrap();
+        assert_eq!(ids(&first), ["d", "c"]);
+        let second =
+            query_record_page(&conn, "1:1", &all, first.next_cursor.as_ref(), 0, 2).unwrap();
+        assert_eq!(ids(&second), ["b", "a"]);
+    }
+
+    #[test]
+    fn streaming_stops_when_receiver_fails() {
+        let conn = test_db();
+        for seq in 1..=3 {
+            let seq = seq.to_string();
+            insert_test_row(
+                &conn,
+                "gacha_records",
+                &[
+                    ("record_uid", &seq),
+                    ("uid", &"1:1"),
+                    ("pool_id", &"special_1"),
+                    ("rarity", &4),
+                    ("gacha_ts", &seq),
+                    ("seq_id", &seq),
+                ],
+            );
+        }
+        let mut received = Vec::new();
+        let sent = send_pull_records(&conn, "1:1", None, |record| {
+            received.push(record.gacha_id);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(sent, 3);
+        assert_eq!(received, ["3", "2", "1"]);
+
+        let mut calls = 0;
+        let result = send_pull_records(&conn, "1:1", None, |_| {
+            calls += 1;
+            Err("closed".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
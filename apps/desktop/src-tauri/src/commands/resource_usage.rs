@@ -0,0 +1,129 @@
+//! 资源占用自检：进程内存、Rust 端数据库连接数、后台任务数，用于排查泄漏（"关于"页展示）
+//!
+//! 内存只统计主进程（WebView2 渲染进程的占用在任务管理器中单独显示）。
+//! 每项都是一次系统调用或原子计数读取，可以频繁调用。
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::app_state;
+use crate::db::open_connection_count;
+
+/// `get_resource_usage` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResourceUsage {
+    /// 进程常驻内存（字节），查询失败时为空
+    memory_bytes: Option<u64>,
+    /// 进程常驻内存峰值（字节），系统不提供时为空
+    peak_memory_bytes: Option<u64>,
+    /// Rust 端当前打开的数据库连接数（不含前端 SQL 插件的连接）
+    db_connections: usize,
+    /// 正在运行的可取消后台任务数（导出等）
+    background_tasks: usize,
+}
+
+/// 进程内存：(当前, 峰值)
+#[cfg(target_os = "windows")]
+fn process_memory() -> (Option<u64>, Option<u64>) {
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+    counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    let ok = unsafe { K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) };
+    if ok == 0 {
+        return (None, None);
+    }
+    (
+        Some(counters.WorkingSetSize as u64),
+        Some(counters.PeakWorkingSetSize as u64),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn process_memory() -> (Option<u64>, Option<u64>) {
+    let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+        return (None, None);
+    };
+    (status_kb(&status, "VmRSS:"), status_kb(&status, "VmHWM:"))
+}
+
+/// 读取 /proc/self/status 中以 kB 为单位的字段
+#[cfg(any(target_os = "linux", test))]
+fn status_kb(status: &str, key: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn process_memory() -> (Option<u64>, Option<u64>) {
+    /// mach_task_basic_info（MACH_TASK_BASIC_INFO_COUNT = 12）
+    #[repr(C)]
+    #[derive(Default)]
+    struct MachTaskBasicInfo {
+        virtual_size: u64,
+        resident_size: u64,
+        resident_size_max: u64,
+        user_time: [i32; 2],
+        system_time: [i32; 2],
+        policy: i32,
+        suspend_count: i32,
+    }
+    const MACH_TASK_BASIC_INFO: i32 = 20;
+    extern "C" {
+        static mach_task_self_: u32;
+        fn task_info(task: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+    }
+
+    let mut info = MachTaskBasicInfo::default();
+    let mut count = (std::mem::size_of::<MachTaskBasicInfo>() / std::mem::size_of::<i32>()) as u32;
+    let result = unsafe {
+        task_info(
+            mach_task_self_,
+            MACH_TASK_BASIC_INFO,
+            &mut info as *mut MachTaskBasicInfo as *mut i32,
+            &mut count,
+        )
+    };
+    if result != 0 {
+        return (None, None);
+    }
+    (Some(info.resident_size), Some(info.resident_size_max))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn process_memory() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// Tauri 命令：查询当前资源占用
+#[tauri::command]
+pub(crate) fn get_resource_usage(app: AppHandle) -> ResourceUsage {
+    let (memory_bytes, peak_memory_bytes) = process_memory();
+    ResourceUsage {
+        memory_bytes,
+        peak_memory_bytes,
+        db_connections: open_connection_count(),
+        background_tasks: app_state(&app).tasks.running_count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_status_fields() {
+        let status = "Name:\tefgachahelper\nVmHWM:\t  204800 kB\nVmRSS:\t  102400 kB\n";
+        assert_eq!(status_kb(status, "VmRSS:"), Some(102400 * 1024));
+        assert_eq!(status_kb(status, "VmHWM:"), Some(204800 * 1024));
+        assert_eq!(status_kb(status, "VmSwap:"), None);
+    }
+}
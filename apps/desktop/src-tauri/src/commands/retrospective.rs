@@ -0,0 +1,442 @@
+//! 回溯模拟：换一套保底规则，同样的运气会怎样
+//!
+//! 把每次出最高稀有度时的抽数换算成"运气分位"（当前规则下随机玩家至少要抽这么多次的概率），
+//! 再在替代规则下按同样的分位重放真实的抽卡序列，得到替代规则下的出金位置。
+//! 是否出 UP 沿用真实结果（小保底的输赢），替代规则带来的保底（硬保底、UP 保底、歪后必 UP）单独标注。
+//! 武器池规则不同，只模拟角色卡池；免费抽不计入保底，不参与模拟。
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::pity::is_featured_pull;
+use crate::db::{
+    open_db, query_pulls_by_time, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY, TOP_RARITY_BASE_RATE,
+    TOP_RARITY_HARD_PITY, TOP_RARITY_SOFT_PITY, TOP_RARITY_SOFT_PITY_STEP,
+};
+use crate::error::AppError;
+
+/// 替代规则允许的最大保底抽数
+const MAX_HARD_PITY: u32 = 1000;
+/// 角色限定池累计多少抽必得 UP（与统计页一致）
+const FEATURED_PITY: u32 = 120;
+
+/// 最高稀有度的概率与保底规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct RateModel {
+    /// 基础概率
+    base_rate: f64,
+    /// 超过该抽数后概率开始提升
+    soft_pity: u32,
+    /// 概率提升后每抽增加的概率
+    soft_pity_step: f64,
+    /// 保底抽数
+    hard_pity: u32,
+    /// 出最高稀有度时可能不是 UP（为假表示每次都是 UP）
+    fifty_fifty: bool,
+    /// 歪了之后下一次最高稀有度必定是 UP
+    guarantee_after_loss: bool,
+    /// 每期卡池累计多少抽必得 UP（本期出过 UP 后不再触发），为空表示没有
+    featured_pity: Option<u32>,
+}
+
+impl Default for RateModel {
+    /// 当前游戏规则
+    fn default() -> Self {
+        Self {
+            base_rate: TOP_RARITY_BASE_RATE,
+            soft_pity: TOP_RARITY_SOFT_PITY,
+            soft_pity_step: TOP_RARITY_SOFT_PITY_STEP,
+            hard_pity: TOP_RARITY_HARD_PITY,
+            fifty_fifty: true,
+            guarantee_after_loss: false,
+            featured_pity: Some(FEATURED_PITY),
+        }
+    }
+}
+
+impl RateModel {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.base_rate > 0.0 && self.base_rate <= 1.0) {
+            return Err("基础概率应在 0~1 之间".to_string());
+        }
+        if !(self.soft_pity_step >= 0.0 && self.soft_pity_step <= 1.0) {
+            return Err("概率提升幅度应在 0~1 之间".to_string());
+        }
+        if self.hard_pity < 1 || self.hard_pity > MAX_HARD_PITY {
+            return Err(format!("保底抽数应在 1~{} 之间", MAX_HARD_PITY));
+        }
+        if self.featured_pity == Some(0) {
+            return Err("UP 保底抽数至少为 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// 距上次出金第 `pull` 抽出最高稀有度的条件概率
+    fn rate(&self, pull: u32) -> f64 {
+        if pull >= self.hard_pity {
+            1.0
+        } else if pull > self.soft_pity {
+            (self.base_rate + self.soft_pity_step * f64::from(pull - self.soft_pity)).min(1.0)
+        } else {
+            self.base_rate
+        }
+    }
+
+    /// 第 `position` 抽出金对应的运气分位（取该抽出金概率区间的中点）
+    fn luck_for_position(&self, position: u32) -> f64 {
+        let position = position.clamp(1, self.hard_pity);
+        let survival = (1..position).fold(1.0, |s, pull| s * (1.0 - self.rate(pull)));
+        survival * (1.0 - self.rate(position) / 2.0)
+    }
+
+    /// 同样的运气分位在本规则下第几抽出金
+    fn position_for_luck(&self, luck: f64) -> u32 {
+        let mut survival = 1.0;
+        for pull in 1..self.hard_pity {
+            survival *= 1.0 - self.rate(pull);
+            if survival < luck {
+                return pull;
+            }
+        }
+        self.hard_pity
+    }
+}
+
+/// 参与模拟的一抽（已排除免费抽，按时间正序）
+#[derive(Debug, Clone)]
+struct ReplayPull {
+    gacha_id: String,
+    pool_id: String,
+    top_rarity: bool,
+    featured: bool,
+}
+
+/// 替代规则下的一次出金
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SimulatedTopRarity {
+    /// 第几抽（从 1 开始，只计非免费抽）
+    pull_index: u32,
+    /// 真实序列中这一抽的记录
+    gacha_id: String,
+    pool_id: String,
+    featured: bool,
+    /// 起作用的保底：hardPity / featuredPity / lossGuarantee
+    guarantees: Vec<&'static str>,
+}
+
+/// 单个卡池类型的模拟结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RetrospectiveBanner {
+    gacha_type: String,
+    pulls: u32,
+    actual_top_rarity: u32,
+    actual_featured: u32,
+    simulated_top_rarity: u32,
+    simulated_featured: u32,
+    top_rarity_delta: i64,
+    featured_delta: i64,
+    /// 真实出金次数多于模拟时，没用上的运气次数
+    unused_luck: u32,
+    simulated: Vec<SimulatedTopRarity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RetrospectiveResult {
+    model: RateModel,
+    banners: Vec<RetrospectiveBanner>,
+    top_rarity_delta: i64,
+    featured_delta: i64,
+}
+
+/// 按真实规则把每次出金换算成（运气分位，是否 UP）
+fn luck_draws(actual: &RateModel, pulls: &[ReplayPull]) -> Vec<(f64, bool)> {
+    let mut pity = 0;
+    let mut draws = Vec::new();
+    for pull in pulls {
+        pity += 1;
+        if pull.top_rarity {
+            draws.push((actual.luck_for_position(pity), pull.featured));
+            pity = 0;
+        }
+    }
+    draws
+}
+
+/// 在替代规则下按同样的运气重放抽卡序列
+///
+/// UP 保底触发时不消耗运气，只重置保底计数；运气用完后只有保底会出金，且视为未出 UP。
+fn replay(
+    alt: &RateModel,
+    pulls: &[ReplayPull],
+    draws: &[(f64, bool)],
+) -> (Vec<SimulatedTopRarity>, u32) {
+    let mut draws = draws.iter();
+    let mut next = draws.next();
+    let mut target = next.map(|&(luck, _)| alt.position_for_luck(luck));
+    let mut pity = 0;
+    let mut pool: Option<&str> = None;
+    let mut pool_pulls = 0;
+    let mut featured_in_pool = false;
+    let mut guaranteed = false;
+    let mut simulated = Vec::new();
+
+    for (i, pull) in pulls.iter().enumerate() {
+        if pool != Some(pull.pool_id.as_str()) {
+            pool = Some(pull.pool_id.as_str());
+            pool_pulls = 0;
+            featured_in_pool = false;
+        }
+        pity += 1;
+        pool_pulls += 1;
+
+        let featured_pity = !featured_in_pool && alt.featured_pity.is_some_and(|n| pool_pulls >= n);
+        let luck_hit = target.is_some_and(|t| pity >= t);
+        let hard_pity = pity >= alt.hard_pity;
+        if !(featured_pity || luck_hit || hard_pity) {
+            continue;
+        }
+
+        let mut guarantees = Vec::new();
+        if hard_pity {
+            guarantees.push("hardPity");
+        }
+        let featured = if featured_pity {
+            guarantees.push("featuredPity");
+            true
+        } else if !alt.fifty_fifty {
+            true
+        } else if guaranteed {
+            guarantees.push("lossGuarantee");
+            true
+        } else {
+            luck_hit && next.is_some_and(|&(_, featured)| featured)
+        };
+
+        if luck_hit {
+            next = draws.next();
+            target = next.map(|&(luck, _)| alt.position_for_luck(luck));
+        }
+        pity = 0;
+        if featured {
+            featured_in_pool = true;
+            guaranteed = false;
+        } else if alt.guarantee_after_loss {
+            guaranteed = true;
+        }
+        simulated.push(SimulatedTopRarity {
+            pull_index: i as u32 + 1,
+            gacha_id: pull.gacha_id.clone(),
+            pool_id: pull.pool_id.clone(),
+            featured,
+            guarantees,
+        });
+    }
+    let unused_luck = next.map_or(0, |_| draws.len() as u32 + 1);
+    (simulated, unused_luck)
+}
+
+fn simulate_banner(
+    actual: &RateModel,
+    alt: &RateModel,
+    gacha_type: &str,
+    pulls: &[ReplayPull],
+) -> RetrospectiveBanner {
+    let draws = luck_draws(actual, pulls);
+    let (simulated, unused_luck) = replay(alt, pulls, &draws);
+    let actual_top_rarity = draws.len() as u32;
+    let actual_featured = draws.iter().filter(|(_, featured)| *featured).count() as u32;
+    let simulated_top_rarity = simulated.len() as u32;
+    let simulated_featured = simulated.iter().filter(|s| s.featured).count() as u32;
+    RetrospectiveBanner {
+        gacha_type: gacha_type.to_string(),
+        pulls: pulls.len() as u32,
+        actual_top_rarity,
+        actual_featured,
+        simulated_top_rarity,
+        simulated_featured,
+        top_rarity_delta: i64::from(simulated_top_rarity) - i64::from(actual_top_rarity),
+        featured_delta: i64::from(simulated_featured) - i64::from(actual_featured),
+        unused_luck,
+        simulated,
+    }
+}
+
+/// 读取某卡池类型的非免费抽卡序列
+fn replay_pulls(
+    conn: &Connection,
+    uid: &str,
+    gacha_type: &str,
+) -> Result<Vec<ReplayPull>, AppError> {
+    let sql = format!(
+        "{} SELECT gacha_id, pool_id, name, rarity, item_id, time FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2 AND is_free = 0",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        let pool_id: String = row.get(1)?;
+        let name: String = row.get(2)?;
+        let rarity: i64 = row.get(3)?;
+        let item_id: Option<String> = row.get(4)?;
+        Ok(ReplayPull {
+            gacha_id: row.get(0)?,
+            featured: is_featured_pull(&pool_id, item_id.as_deref().unwrap_or(""), &name, rarity),
+            top_rarity: rarity >= TOP_RARITY,
+            pool_id,
+        })
+    })
+    .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))
+}
+
+/// Tauri 命令：用替代规则重放真实抽卡序列，比较出金与出 UP 次数
+#[tauri::command]
+pub(crate) fn simulate_retrospective(
+    app: AppHandle,
+    uid: String,
+    alt_rate_model: RateModel,
+) -> Result<RetrospectiveResult, AppError> {
+    alt_rate_model.validate().map_err(AppError::validation)?;
+    let actual = RateModel::default();
+    let conn = open_db(&app)?;
+    let mut banners = Vec::new();
+    for gacha_type in GACHA_TYPES.iter().filter(|t| **t != "weapon") {
+        let pulls = replay_pulls(&conn, &uid, gacha_type)?;
+        if !pulls.is_empty() {
+            banners.push(simulate_banner(
+                &actual,
+                &alt_rate_model,
+                gacha_type,
+                &pulls,
+            ));
+        }
+    }
+    Ok(RetrospectiveResult {
+        top_rarity_delta: banners.iter().map(|b| b.top_rarity_delta).sum(),
+        featured_delta: banners.iter().map(|b| b.featured_delta).sum(),
+        model: alt_rate_model,
+        banners,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按出金位置构造序列：每个元素为（距上次出金的抽数，是否 UP），`tail` 为末尾未出金的抽数
+    fn sequence(hits: &[(u32, bool)], tail: u32) -> Vec<ReplayPull> {
+        let mut pulls = Vec::new();
+        let mut push = |top_rarity, featured| {
+            pulls.push(ReplayPull {
+                gacha_id: (pulls.len() + 1).to_string(),
+                pool_id: "special_1_0_1".to_string(),
+                top_rarity,
+                featured,
+            })
+        };
+        for &(position, featured) in hits {
+            for _ in 1..position {
+                push(false, false);
+            }
+            push(true, featured);
+        }
+        for _ in 0..tail {
+            push(false, false);
+        }
+        pulls
+    }
+
+    fn positions(banner: &RetrospectiveBanner) -> Vec<u32> {
+        banner.simulated.iter().map(|s| s.pull_index).collect()
+    }
+
+    #[test]
+    fn same_model_reproduces_actual_pulls() {
+        let model = RateModel {
+            featured_pity: None,
+            ..RateModel::default()
+        };
+        let pulls = sequence(&[(3, true), (70, false), (80, true), (41, false)], 12);
+        let banner = simulate_banner(&model, &model, "special", &pulls);
+        assert_eq!(positions(&banner), vec![3, 73, 153, 194]);
+        assert_eq!(banner.top_rarity_delta, 0);
+        assert_eq!(banner.featured_delta, 0);
+        assert_eq!(banner.unused_luck, 0);
+    }
+
+    #[test]
+    fn lower_hard_pity_turns_unlucky_streaks_into_extra_pulls() {
+        let actual = RateModel::default();
+        let alt = RateModel {
+            hard_pity: 50,
+            soft_pity: 40,
+            featured_pity: None,
+            ..RateModel::default()
+        };
+        let pulls = sequence(&[(80, false), (80, true)], 0);
+        let banner = simulate_banner(&actual, &alt, "special", &pulls);
+        assert_eq!(positions(&banner), vec![50, 100, 150]);
+        assert!(banner.simulated[0].guarantees.contains(&"hardPity"));
+        assert_eq!(banner.top_rarity_delta, 1);
+        // 第三次出金用的是真实规则下没有发生的运气，视为未出 UP
+        assert!(!banner.simulated[2].featured);
+    }
+
+    #[test]
+    fn loss_guarantee_flips_second_lost_fifty_fifty() {
+        let actual = RateModel {
+            featured_pity: None,
+            ..RateModel::default()
+        };
+        let alt = RateModel {
+            guarantee_after_loss: true,
+            ..actual.clone()
+        };
+        let pulls = sequence(&[(30, false), (30, false), (30, false)], 0);
+        let banner = simulate_banner(&actual, &alt, "special", &pulls);
+        let featured: Vec<bool> = banner.simulated.iter().map(|s| s.featured).collect();
+        assert_eq!(featured, vec![false, true, false]);
+        assert_eq!(banner.simulated[1].guarantees, vec!["lossGuarantee"]);
+        assert_eq!(banner.featured_delta, 1);
+    }
+
+    #[test]
+    fn featured_pity_fires_once_per_pool_without_consuming_luck() {
+        let actual = RateModel {
+            featured_pity: None,
+            ..RateModel::default()
+        };
+        let alt = RateModel {
+            featured_pity: Some(100),
+            ..actual.clone()
+        };
+        let pulls = sequence(&[(70, false), (60, false)], 40);
+        let banner = simulate_banner(&actual, &alt, "special", &pulls);
+        // 第 100 抽触发 UP 保底后，第二次的运气从头计数，落在第 160 抽
+        assert_eq!(positions(&banner), vec![70, 100, 160]);
+        assert_eq!(banner.simulated[1].guarantees, vec!["featuredPity"]);
+        assert!(banner.simulated[1].featured);
+        assert_eq!(banner.featured_delta, 1);
+    }
+
+    #[test]
+    fn rejects_invalid_models() {
+        let model = RateModel {
+            hard_pity: 0,
+            ..RateModel::default()
+        };
+        assert!(model.validate().is_err());
+        let model = RateModel {
+            base_rate: 1.5,
+            ..RateModel::default()
+        };
+        assert!(model.validate().is_err());
+        assert!(RateModel::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,858 @@
+//! 抽卡统计查询
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::clock::trusted_now;
+use crate::config::{current_config, update_config};
+use crate::db::{
+    open_db, parse_gacha_ts, query_pulls_by_time, validate_gacha_type, validate_item_type,
+    DisplayTimezone, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY,
+};
+use crate::error::AppError;
+
+/// 账号年龄估算（基于最早一条本地抽卡记录）
+#[derive(Clone, Serialize)]
+pub(crate) struct AccountAgeEstimate {
+    earliest_pull: String,
+    days_active: f64,
+    weeks_active: f64,
+}
+
+/// 最早一条可解析的抽卡时间（无法解析的记录忽略）
+fn earliest_pull_time(times: &[String]) -> Option<DateTime<Utc>> {
+    times.iter().filter_map(|time| parse_gacha_ts(time)).min()
+}
+
+/// Tauri 命令：根据最早抽卡记录估算账号年龄
+///
+/// 本地不保存账号创建时间，只能用最早的抽卡时间近似。
+/// 注意：如果本地记录没有覆盖到建号初期（例如超出官方接口可查询范围），结果会偏小。
+#[tauri::command]
+pub(crate) fn get_account_age_estimate(app: AppHandle, uid: String) -> Result<Option<AccountAgeEstimate>, AppError> {
+    let conn = open_db(&app)?;
+
+    // 时间有秒/毫秒时间戳与日期字符串多种格式，不能交给 SQL 的 MIN，逐条解析后取最早
+    let sql = format!(
+        "{} SELECT time FROM pull_records WHERE uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询最早抽卡记录失败: {}", e)))?;
+    let times = stmt
+        .query_map([&uid], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::database(format!("查询最早抽卡记录失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取最早抽卡记录失败: {}", e)))?;
+
+    let Some(earliest) = earliest_pull_time(&times) else {
+        return Ok(None);
+    };
+
+    let elapsed = trusted_now(&app).signed_duration_since(earliest);
+    let days_active = (elapsed.num_seconds().max(0) as f64) / 86_400.0;
+
+    Ok(Some(AccountAgeEstimate {
+        earliest_pull: earliest.to_rfc3339(),
+        days_active,
+        weeks_active: days_active / 7.0,
+    }))
+}
+
+/// Tauri 命令：获取指定 gacha_id 之后新增的最高稀有度物品名
+///
+/// 自动同步前记下最新的 gacha_id，同步后调用本命令即可得到本次新增的出金列表，用于生成通知文案。
+#[tauri::command]
+pub(crate) fn count_five_stars_since_gacha_id(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+    since_gacha_id: String,
+) -> Result<Vec<String>, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let since: i64 = since_gacha_id
+        .trim()
+        .parse()
+        .map_err(|_| AppError::validation(format!("无效的 gacha_id: {}", since_gacha_id)))?;
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT name FROM pull_records
+           WHERE uid = ?1 AND gacha_type = ?2 AND rarity = ?3
+             AND CAST(gacha_id AS INTEGER) > ?4
+           ORDER BY CAST(gacha_id AS INTEGER) ASC",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询新增出金记录失败: {}", e)))?;
+    let names = stmt
+        .query_map(rusqlite::params![uid, gacha_type, TOP_RARITY, since], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| AppError::database(format!("查询新增出金记录失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取新增出金记录失败: {}", e)))?;
+
+    Ok(names)
+}
+
+/// 次高稀有度（5★，与 `RankDistributionBucket::four_star` 口径一致）
+const FOUR_STAR_RARITY: i64 = TOP_RARITY - 1;
+
+/// 某个物品的获得次数
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CharacterCount {
+    name: String,
+    count: u32,
+}
+
+/// Tauri 命令：获取抽到过的 5★ 物品名（去重，按名称排序），用于图鉴收集进度
+///
+/// `gacha_type` 为空时统计该账号全部卡池。
+#[tauri::command]
+pub(crate) fn get_four_star_names(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    Ok(get_four_star_count_by_name(app, uid, gacha_type)?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect())
+}
+
+/// Tauri 命令：按物品统计 5★ 获得次数（次数多的在前，同次数按名称排序），用于潜能/精炼进度
+///
+/// `gacha_type` 为空时统计该账号全部卡池。
+#[tauri::command]
+pub(crate) fn get_four_star_count_by_name(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+) -> Result<Vec<CharacterCount>, AppError> {
+    count_by_name(&app, &uid, gacha_type.as_deref(), None, FOUR_STAR_RARITY)
+}
+
+/// Tauri 命令：按物品统计最高稀有度（6★，口径同 `TOP_RARITY`）获得次数，角色与武器分开统计
+///
+/// `item_type` 为 character（角色潜能）或 weapon（武器精炼）；`gacha_type` 为空时统计该账号全部卡池。
+#[tauri::command]
+pub(crate) fn get_five_star_by_item_type(
+    app: AppHandle,
+    uid: String,
+    item_type: String,
+    gacha_type: Option<String>,
+) -> Result<Vec<CharacterCount>, AppError> {
+    validate_item_type(&item_type)?;
+    count_by_name(
+        &app,
+        &uid,
+        gacha_type.as_deref(),
+        Some(&item_type),
+        TOP_RARITY,
+    )
+}
+
+/// 按物品名统计指定稀有度的获得次数（次数多的在前，同次数按名称排序）
+fn count_by_name(
+    app: &AppHandle,
+    uid: &str,
+    gacha_type: Option<&str>,
+    item_type: Option<&str>,
+    rarity: i64,
+) -> Result<Vec<CharacterCount>, AppError> {
+    if let Some(gacha_type) = gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+
+    let conn = open_db(app)?;
+    let sql = format!(
+        "{} SELECT name, COUNT(*) FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2) AND rarity = ?3
+             AND (?4 IS NULL OR item_type = ?4)
+           GROUP BY name
+           ORDER BY COUNT(*) DESC, name ASC",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询 {}★ 记录失败: {}", rarity, e)))?;
+    let counts = stmt
+        .query_map(
+            rusqlite::params![uid, gacha_type, rarity, item_type],
+            |row| {
+                Ok(CharacterCount {
+                    name: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            },
+        )
+        .map_err(|e| AppError::database(format!("查询 {}★ 记录失败: {}", rarity, e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取 {}★ 记录失败: {}", rarity, e)))?;
+    Ok(counts)
+}
+
+/// 稀有度分布的一个分桶（连续 bucket_size 抽）
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RankDistributionBucket {
+    /// 桶内第一抽的序号（从 1 开始）
+    start_pull: u64,
+    /// 4★ 数量
+    three_star: u32,
+    /// 5★ 数量
+    four_star: u32,
+    /// 6★ 数量
+    five_star: u32,
+}
+
+/// Tauri 命令：按抽数分桶统计稀有度分布（用于堆叠面积图）
+///
+/// 一次按时间顺序查询后在内存中分桶；稀有度口径与 `TOP_RARITY` 一致（five_star 即 6★，依次往下）。
+/// `gacha_type` 为空时统计该账号全部卡池。
+#[tauri::command]
+pub(crate) fn get_rank_distribution_over_time(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    bucket_size: u32,
+) -> Result<Vec<RankDistributionBucket>, AppError> {
+    if bucket_size == 0 {
+        return Err(AppError::validation("分桶大小必须大于 0"));
+    }
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT rarity, time, gacha_id FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询稀有度分布失败: {}", e)))?;
+    let rarities = query_pulls_by_time(&mut stmt, rusqlite::params![uid, gacha_type], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|e| AppError::database(format!("读取稀有度分布失败: {}", e)))?;
+
+    let mut buckets: Vec<RankDistributionBucket> = Vec::new();
+    for (index, rarity) in rarities.into_iter().enumerate() {
+        if index % bucket_size as usize == 0 {
+            buckets.push(RankDistributionBucket {
+                start_pull: index as u64 + 1,
+                three_star: 0,
+                four_star: 0,
+                five_star: 0,
+            });
+        }
+        let bucket = buckets.last_mut().unwrap();
+        match TOP_RARITY - rarity {
+            0 => bucket.five_star += 1,
+            1 => bucket.four_star += 1,
+            2 => bucket.three_star += 1,
+            _ => {}
+        }
+    }
+    Ok(buckets)
+}
+
+/// 一次连续抽卡（相邻两抽间隔不超过设定值）
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PullSession {
+    /// 第一抽时间（RFC 3339）
+    start_time: String,
+    /// 最后一抽时间（RFC 3339）
+    end_time: String,
+    pull_count: u32,
+    /// 6★ 数量
+    five_star_count: u32,
+}
+
+/// Tauri 命令：按时间间隔把抽卡记录划分为若干「次」（一次坐下来连续抽卡）
+///
+/// 按时间顺序遍历，相邻两抽间隔超过 `gap_minutes` 分钟时开始新的一次。
+/// `gacha_type` 为空时统计该账号全部卡池。
+#[tauri::command]
+pub(crate) fn get_pull_sessions(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+    gap_minutes: u32,
+) -> Result<Vec<PullSession>, AppError> {
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT time, CAST(gacha_id AS INTEGER), rarity FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let pulls = stmt
+        .query_map(rusqlite::params![uid, gacha_type], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+    Ok(pull_sessions(pulls, gap_minutes))
+}
+
+/// 按时间顺序划分抽卡「次」；时间格式不一，逐条解析后按毫秒比较（无法解析的记录忽略）
+fn pull_sessions(pulls: Vec<(String, i64, i64)>, gap_minutes: u32) -> Vec<PullSession> {
+    let mut pulls: Vec<(DateTime<Utc>, i64, i64)> = pulls
+        .into_iter()
+        .filter_map(|(time, gacha_id, rarity)| {
+            parse_gacha_ts(&time).map(|time| (time, gacha_id, rarity))
+        })
+        .collect();
+    pulls.sort_by_key(|(time, gacha_id, _)| (*time, *gacha_id));
+
+    let gap_ms = i64::from(gap_minutes) * 60 * 1000;
+    // (开始时间, 结束时间, 抽数, 6★ 数)
+    let mut sessions: Vec<(DateTime<Utc>, DateTime<Utc>, u32, u32)> = Vec::new();
+    for (time, _, rarity) in pulls {
+        match sessions.last_mut() {
+            Some(session) if time.timestamp_millis() - session.1.timestamp_millis() <= gap_ms => {
+                session.1 = time;
+                session.2 += 1;
+                session.3 += u32::from(rarity >= TOP_RARITY);
+            }
+            _ => sessions.push((time, time, 1, u32::from(rarity >= TOP_RARITY))),
+        }
+    }
+
+    sessions
+        .into_iter()
+        .map(|(start, end, pull_count, five_star_count)| PullSession {
+            start_time: start.to_rfc3339(),
+            end_time: end.to_rfc3339(),
+            pull_count,
+            five_star_count,
+        })
+        .collect()
+}
+
+/// 统计页汇总矩阵的一行（一个卡池 × 各稀有度）
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PullMatrixRow {
+    gacha_type: String,
+    /// 4★ 数量
+    three_star: u64,
+    /// 5★ 数量
+    four_star: u64,
+    /// 6★ 数量
+    five_star: u64,
+    /// 该卡池总抽数（含更低稀有度）
+    total: u64,
+}
+
+/// Tauri 命令：按卡池 × 稀有度汇总抽数（统计页二维矩阵）
+///
+/// 单次 `GROUP BY gacha_type, rarity` 查询后在 Rust 端转置；每个卡池都有一行，未抽过的为 0。
+#[tauri::command]
+pub(crate) fn get_pull_matrix(app: AppHandle, uid: String) -> Result<Vec<PullMatrixRow>, AppError> {
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_type, rarity, COUNT(*) FROM pull_records
+           WHERE uid = ?1
+           GROUP BY gacha_type, rarity",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡汇总失败: {}", e)))?;
+    let counts = stmt
+        .query_map([&uid], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡汇总失败: {}", e)))?;
+
+    let mut rows: Vec<PullMatrixRow> = GACHA_TYPES
+        .iter()
+        .map(|gacha_type| PullMatrixRow {
+            gacha_type: gacha_type.to_string(),
+            three_star: 0,
+            four_star: 0,
+            five_star: 0,
+            total: 0,
+        })
+        .collect();
+    for entry in counts {
+        let (gacha_type, rarity, count) =
+            entry.map_err(|e| AppError::database(format!("读取抽卡汇总失败: {}", e)))?;
+        let index = match rows.iter().position(|row| row.gacha_type == gacha_type) {
+            Some(index) => index,
+            None => {
+                rows.push(PullMatrixRow {
+                    gacha_type,
+                    three_star: 0,
+                    four_star: 0,
+                    five_star: 0,
+                    total: 0,
+                });
+                rows.len() - 1
+            }
+        };
+        let row = &mut rows[index];
+        let count = count as u64;
+        match TOP_RARITY - rarity {
+            0 => row.five_star += count,
+            1 => row.four_star += count,
+            2 => row.three_star += count,
+            _ => {}
+        }
+        row.total += count;
+    }
+    Ok(rows)
+}
+
+/// 多账号汇总统计
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct GlobalStats {
+    total_pulls_all_accounts: u64,
+    /// 最高稀有度（6★）总数
+    total_five_stars_all_accounts: u64,
+    uid_count: u32,
+    /// 抽数最多的账号
+    most_active_uid: Option<String>,
+}
+
+/// Tauri 命令：汇总所有账号的抽卡统计（多账号视图顶部的合计卡片）
+#[tauri::command]
+pub(crate) fn get_global_pull_stats(app: AppHandle) -> Result<GlobalStats, AppError> {
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT COUNT(*),
+                  COALESCE(SUM(CASE WHEN rarity >= ?1 THEN 1 ELSE 0 END), 0),
+                  COUNT(DISTINCT uid),
+                  (SELECT uid FROM pull_records GROUP BY uid ORDER BY COUNT(*) DESC, uid LIMIT 1)
+           FROM pull_records",
+        PULL_RECORDS_CTE
+    );
+    conn.query_row(&sql, [TOP_RARITY], |row| {
+        Ok(GlobalStats {
+            total_pulls_all_accounts: row.get::<_, i64>(0)? as u64,
+            total_five_stars_all_accounts: row.get::<_, i64>(1)? as u64,
+            uid_count: row.get::<_, i64>(2)? as u32,
+            most_active_uid: row.get(3)?,
+        })
+    })
+    .map_err(|e| AppError::database(format!("查询汇总统计失败: {}", e)))
+}
+
+/// 单抽价格设置（用于把抽数折算成资源/金额）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct PullPriceConfig {
+    /// 单抽价格，为空表示未设置（不估算）
+    price_per_pull: Option<f64>,
+    /// 货币或资源名称（仅用于显示，如 CNY、USD、嵌晶玉）
+    currency: String,
+}
+
+impl Default for PullPriceConfig {
+    fn default() -> Self {
+        Self {
+            price_per_pull: None,
+            currency: "CNY".to_string(),
+        }
+    }
+}
+
+/// 货币名称长度上限
+const CURRENCY_MAX_CHARS: usize = 16;
+
+/// 按单抽价格折算的资源估算
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResourceEstimate {
+    price_per_pull: f64,
+    currency: String,
+    /// 估算消耗：付费抽 + 无法区分的抽数
+    estimated_cost: f64,
+    /// 免费抽折算的价值（不计入消耗）
+    free_value: f64,
+    /// 含有无法区分付费/免费的记录（这部分按总数计入消耗）
+    includes_unclassified: bool,
+}
+
+/// 账号抽卡统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Statistics {
+    total_pulls: u64,
+    paid_pulls: u64,
+    free_pulls: u64,
+    /// 无法区分付费/免费的抽数（武器池记录没有免费标记）
+    unclassified_pulls: u64,
+    /// 未设置单抽价格时为空
+    resource_estimate: Option<ResourceEstimate>,
+}
+
+fn resource_estimate(
+    paid_pulls: u64,
+    free_pulls: u64,
+    unclassified_pulls: u64,
+    price: &PullPriceConfig,
+) -> Option<ResourceEstimate> {
+    let price_per_pull = price.price_per_pull?;
+    Some(ResourceEstimate {
+        price_per_pull,
+        currency: price.currency.clone(),
+        estimated_cost: (paid_pulls + unclassified_pulls) as f64 * price_per_pull,
+        free_value: free_pulls as f64 * price_per_pull,
+        includes_unclassified: unclassified_pulls > 0,
+    })
+}
+
+/// Tauri 命令：账号抽卡统计（含按单抽价格折算的资源估算）
+///
+/// 角色池记录带免费标记，可区分付费/免费；武器池记录没有，统一按总数计入消耗并在结果中标注。
+#[tauri::command]
+pub(crate) fn get_statistics(app: AppHandle, uid: String) -> Result<Statistics, AppError> {
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT COUNT(*),
+                  COALESCE(SUM(CASE WHEN item_type != 'weapon' AND is_free = 0 THEN 1 ELSE 0 END), 0),
+                  COALESCE(SUM(CASE WHEN item_type != 'weapon' AND is_free != 0 THEN 1 ELSE 0 END), 0)
+           FROM pull_records WHERE uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let (total, paid, free) = conn
+        .query_row(&sql, [&uid], |row| {
+            Ok((
+                row.get::<_, i64>(0)? as u64,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, i64>(2)? as u64,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡统计失败: {}", e)))?;
+    let unclassified = total.saturating_sub(paid + free);
+    let price = current_config(&app).pull_price;
+    Ok(Statistics {
+        total_pulls: total,
+        paid_pulls: paid,
+        free_pulls: free,
+        unclassified_pulls: unclassified,
+        resource_estimate: resource_estimate(paid, free, unclassified, &price),
+    })
+}
+
+/// Tauri 命令：获取单抽价格设置
+#[tauri::command]
+pub(crate) fn get_pull_price_settings(app: AppHandle) -> PullPriceConfig {
+    current_config(&app).pull_price
+}
+
+/// Tauri 命令：保存单抽价格设置（价格传空表示不估算）
+#[tauri::command]
+pub(crate) fn set_pull_price_settings(
+    app: AppHandle,
+    settings: PullPriceConfig,
+) -> Result<(), AppError> {
+    if let Some(price) = settings.price_per_pull {
+        if !price.is_finite() || price < 0.0 {
+            return Err(AppError::validation("单抽价格须为非负数"));
+        }
+    }
+    let currency = settings.currency.trim().to_string();
+    if currency.is_empty() || currency.chars().count() > CURRENCY_MAX_CHARS {
+        return Err(AppError::validation(format!(
+            "货币名称须为 1~{} 个字符",
+            CURRENCY_MAX_CHARS
+        )));
+    }
+    let settings = PullPriceConfig {
+        currency,
+        ..settings
+    };
+    update_config(&app, |config| config.pull_price = settings)?;
+    Ok(())
+}
+
+/// 可设置的 UTC 偏移范围（小时）
+const DISPLAY_OFFSET_RANGE: std::ops::RangeInclusive<i32> = -12..=14;
+
+/// 统计分桶使用的时区
+fn display_timezone(app: &AppHandle) -> DisplayTimezone {
+    DisplayTimezone::from_offset_hours(current_config(app).display_utc_offset_hours)
+}
+
+/// Tauri 命令：获取统计使用的 UTC 偏移（小时），为空表示跟随系统时区
+#[tauri::command]
+pub(crate) fn get_display_timezone(app: AppHandle) -> Option<i32> {
+    current_config(&app).display_utc_offset_hours
+}
+
+/// Tauri 命令：设置统计使用的 UTC 偏移（小时，-12~14）；传空恢复为系统时区
+///
+/// 只影响按日 / 月分桶的统计口径（如月度出金率的月界），记录仍按 UTC 存储。
+#[tauri::command]
+pub(crate) fn set_display_timezone(app: AppHandle, offset: Option<i32>) -> Result<(), AppError> {
+    if let Some(offset) = offset {
+        if !DISPLAY_OFFSET_RANGE.contains(&offset) {
+            return Err(AppError::validation(format!(
+                "UTC 偏移须在 {}~{} 小时之间",
+                DISPLAY_OFFSET_RANGE.start(),
+                DISPLAY_OFFSET_RANGE.end()
+            )));
+        }
+    }
+    update_config(&app, |config| config.display_utc_offset_hours = offset)?;
+    Ok(())
+}
+
+/// 相邻 gacha_id 之间缺失的不超过此数量时视为分页误差（不报告）
+const GAP_TOLERANCE: u64 = 1;
+/// 相邻 gacha_id 差距达到此数量时多半是跨卡池/跨批次编号，不视为漏记录
+const GAP_UPPER_BOUND: u64 = 1_000_000;
+
+/// gacha_id 序列中的一段疑似缺口
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct GapRange {
+    after_gacha_id: String,
+    before_gacha_id: String,
+    /// 估算缺失的记录数（两端 gacha_id 之差减一）
+    estimated_missing: u64,
+    /// 缺口两端都是已归档记录：无法再从服务器补回，仅作提示
+    informational: bool,
+}
+
+/// Tauri 命令：检查某卡池 gacha_id 序列中的缺口
+///
+/// gacha_id 由服务器顺序分配，拉取中断可能导致中间一段记录缺失。
+/// 非数字的 gacha_id 无法比较，直接跳过。落在归档区间内的缺口标记为 informational。
+#[tauri::command]
+pub(crate) fn find_gacha_id_gaps(
+    app: AppHandle,
+    uid: String,
+    gacha_type: String,
+) -> Result<Vec<GapRange>, AppError> {
+    validate_gacha_type(&gacha_type)?;
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_id, archived FROM pull_records WHERE uid = ?1 AND gacha_type = ?2",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+    let rows = stmt
+        .query_map(rusqlite::params![uid, gacha_type], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? != 0))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡记录失败: {}", e)))?;
+
+    let mut ids: Vec<(u64, String, bool)> = Vec::new();
+    for row in rows {
+        let (gacha_id, archived) =
+            row.map_err(|e| AppError::database(format!("读取抽卡记录失败: {}", e)))?;
+        if let Ok(value) = gacha_id.trim().parse::<u64>() {
+            ids.push((value, gacha_id, archived));
+        }
+    }
+    ids.sort();
+    ids.dedup_by_key(|(value, _, _)| *value);
+
+    Ok(ids
+        .windows(2)
+        .filter_map(|pair| {
+            let missing = pair[1].0 - pair[0].0 - 1;
+            (missing > GAP_TOLERANCE && missing < GAP_UPPER_BOUND).then(|| GapRange {
+                after_gacha_id: pair[0].1.clone(),
+                before_gacha_id: pair[1].1.clone(),
+                estimated_missing: missing,
+                informational: pair[0].2 && pair[1].2,
+            })
+        })
+        .collect())
+}
+
+/// 某个月的出金率（6★ 即 five_star，口径与 `TOP_RARITY` 一致）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct MonthlyRate {
+    /// 统计时区下的年月，如 "2024-05"
+    year_month: String,
+    total_pulls: u32,
+    five_star_count: u32,
+    /// 百分比（0~100）
+    rate: f64,
+}
+
+/// 按统计时区的年月分组统计出金率；时间无法解析的记录不计入任何月份
+fn monthly_rates(
+    pulls: impl IntoIterator<Item = (String, i64)>,
+    timezone: DisplayTimezone,
+) -> Vec<MonthlyRate> {
+    let mut months: std::collections::BTreeMap<String, (u32, u32)> = Default::default();
+    for (time, rarity) in pulls {
+        let Some(ts) = parse_gacha_ts(&time) else {
+            continue;
+        };
+        let month = timezone.format(ts, "%Y-%m");
+        let entry = months.entry(month).or_default();
+        entry.0 += 1;
+        if rarity == TOP_RARITY {
+            entry.1 += 1;
+        }
+    }
+    months
+        .into_iter()
+        .map(|(year_month, (total_pulls, five_star_count))| MonthlyRate {
+            year_month,
+            total_pulls,
+            five_star_count,
+            rate: five_star_count as f64 / total_pulls as f64 * 100.0,
+        })
+        .collect()
+}
+
+/// Tauri 命令：按月统计出金率（用于"月度表现"折线图）
+///
+/// 记录时间有秒/毫秒时间戳与日期字符串多种格式，无法直接交给 SQLite 的 strftime，
+/// 因此查询后用 `parse_gacha_ts` 在内存中按统计时区（`set_display_timezone`，默认系统时区）的年月分组。
+/// 只返回有抽卡的月份，按时间升序。
+#[tauri::command]
+pub(crate) fn get_five_star_rate_by_month(
+    app: AppHandle,
+    uid: String,
+    gacha_type: Option<String>,
+) -> Result<Vec<MonthlyRate>, AppError> {
+    if let Some(gacha_type) = &gacha_type {
+        validate_gacha_type(gacha_type)?;
+    }
+
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT time, rarity FROM pull_records
+           WHERE uid = ?1 AND (?2 IS NULL OR gacha_type = ?2)",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询月度出金率失败: {}", e)))?;
+    let pulls = stmt
+        .query_map(rusqlite::params![uid, gacha_type], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| AppError::database(format!("查询月度出金率失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::database(format!("读取月度出金率失败: {}", e)))?;
+    Ok(monthly_rates(pulls, display_timezone(&app)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn estimate_counts_unclassified_pulls_as_cost() {
+        let mut price = PullPriceConfig::default();
+        assert!(resource_estimate(10, 5, 0, &price).is_none());
+
+        price.price_per_pull = Some(2.5);
+        let estimate = resource_estimate(10, 5, 0, &price).unwrap();
+        assert_eq!(estimate.estimated_cost, 25.0);
+        assert_eq!(estimate.free_value, 12.5);
+        assert!(!estimate.includes_unclassified);
+
+        let estimate = resource_estimate(10, 5, 4, &price).unwrap();
+        assert_eq!(estimate.estimated_cost, 35.0);
+        assert!(estimate.includes_unclassified);
+    }
+
+    #[test]
+    fn earliest_pull_compares_parsed_times() {
+        // 按数值比较时日期字符串会被当作 2024，秒级时间戳也会排在毫秒级之前
+        let times = [
+            "1717185600000".to_string(),
+            "2024-05-01 12:00:00".to_string(),
+            "1717000000".to_string(),
+            "not a time".to_string(),
+        ];
+        let earliest = earliest_pull_time(&times).unwrap();
+        assert_eq!(earliest.to_rfc3339(), "2024-05-01T12:00:00+00:00");
+        assert!(earliest_pull_time(&["".to_string()]).is_none());
+    }
+
+    #[test]
+    fn sessions_split_on_parsed_time_gaps() {
+        // 秒级、毫秒级与日期字符串混在一起，按解析后的时间排序与比较
+        let pulls = vec![
+            ("2024-05-01 12:05:00".to_string(), 3, TOP_RARITY),
+            ("1714565100000".to_string(), 2, 4),
+            ("1714565100".to_string(), 1, 4),
+            ("2024-05-01 14:00:00".to_string(), 4, 4),
+            ("not a time".to_string(), 5, TOP_RARITY),
+        ];
+        let sessions = pull_sessions(pulls, 30);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].start_time, "2024-05-01T12:05:00+00:00");
+        assert_eq!(
+            (sessions[0].pull_count, sessions[0].five_star_count),
+            (3, 1)
+        );
+        assert_eq!(sessions[1].start_time, "2024-05-01T14:00:00+00:00");
+        assert_eq!(sessions[1].pull_count, 1);
+    }
+
+    #[test]
+    fn monthly_rates_groups_by_month_in_order() {
+        let local_ms = |y, m, d| {
+            chrono::Local
+                .with_ymd_and_hms(y, m, d, 12, 0, 0)
+                .unwrap()
+                .timestamp_millis()
+                .to_string()
+        };
+        let rates = monthly_rates(
+            vec![
+                (local_ms(2024, 5, 3), TOP_RARITY),
+                (local_ms(2024, 4, 30), 4),
+                (local_ms(2024, 5, 20), 5),
+                ("not a time".to_string(), TOP_RARITY),
+                (local_ms(2024, 5, 21), 4),
+                (local_ms(2024, 5, 22), 4),
+            ],
+            DisplayTimezone::Local,
+        );
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].year_month, "2024-04");
+        assert_eq!((rates[0].total_pulls, rates[0].five_star_count), (1, 0));
+        assert_eq!(rates[1].year_month, "2024-05");
+        assert_eq!((rates[1].total_pulls, rates[1].five_star_count), (4, 1));
+        assert_eq!(rates[1].rate, 25.0);
+    }
+
+    #[test]
+    fn monthly_rates_use_display_offset_for_month_boundary() {
+        // 2024-05-31 20:00 UTC：UTC+8 已是 6 月 1 日，UTC-5 仍是 5 月 31 日
+        let pulls = || vec![("1717185600000".to_string(), TOP_RARITY)];
+        let east = monthly_rates(pulls(), DisplayTimezone::from_offset_hours(Some(8)));
+        assert_eq!(east[0].year_month, "2024-06");
+        let west = monthly_rates(pulls(), DisplayTimezone::from_offset_hours(Some(-5)));
+        assert_eq!(west[0].year_month, "2024-05");
+    }
+}
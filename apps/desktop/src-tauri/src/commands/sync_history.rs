@@ -0,0 +1,251 @@
+//! 同步历史与耗时统计
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::clock::clock_correction_ms;
+use crate::commands::telemetry;
+use crate::config::current_config;
+use crate::db::access::with_db_write;
+use crate::db::{open_db, query_pulls_by_time, PULL_RECORDS_CTE, TOP_RARITY};
+use crate::error::AppError;
+
+/// 单次同步的耗时统计（网络拉取与写库分开计时，便于定位慢在哪一段）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncRun {
+    /// 同步批次 id（sync_history.id），用于 `get_records_since`
+    id: i64,
+    uid: String,
+    /// 同步开始时间（毫秒时间戳）
+    started_at: i64,
+    /// 全过程耗时（含鉴权、网络拉取与写库）
+    total_ms: i64,
+    network_ms: i64,
+    db_write_ms: i64,
+    /// 本次从接口拉取并处理的记录数
+    records_processed: i64,
+    /// 其中新入库的记录数
+    records_added: i64,
+    records_per_second: f64,
+}
+
+fn records_per_second(records: i64, total_ms: i64) -> f64 {
+    if total_ms <= 0 {
+        return 0.0;
+    }
+    records as f64 * 1000.0 / total_ms as f64
+}
+
+/// `sync-finished` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncFinished {
+    uid: String,
+    sync_run_id: i64,
+    /// 本次首次入库的记录（record_uid，按时间倒序）
+    new_record_ids: Vec<String>,
+    /// 新增记录中的最高稀有度（没有新增时为空）
+    top_rarity: Option<i64>,
+}
+
+/// 把本次同步期间首次入库的记录标记为该批次，返回这些记录的 (record_uid, rarity)
+///
+/// 前端以 INSERT OR IGNORE 写入，已存在的记录保留原来的 fetched_at 与 sync_run_id，
+/// 因此增量同步在已知 id 边界附近重复拉取的旧记录不会被算作本批次新增。
+fn tag_new_records(
+    conn: &rusqlite::Connection,
+    sync_run_id: i64,
+    uid: &str,
+    started_at: i64,
+) -> rusqlite::Result<Vec<(String, i64)>> {
+    for table in ["gacha_records", "weapon_records"] {
+        conn.execute(
+            &format!(
+                "UPDATE {} SET sync_run_id = ?1
+                  WHERE uid = ?2 AND sync_run_id IS NULL AND fetched_at >= ?3",
+                table
+            ),
+            rusqlite::params![sync_run_id, uid, started_at],
+        )?;
+    }
+    let sql = format!(
+        "{} SELECT record_uid, rarity, time, gacha_id FROM pull_records WHERE sync_run_id = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = query_pulls_by_time(&mut stmt, [sync_run_id], |row| {
+        Ok((row.get(0)?, row.get(1)?))
+    })?;
+    rows.reverse();
+    Ok(rows)
+}
+
+/// Tauri 命令：记录一次同步的耗时统计并写入同步历史，返回含处理速率的统计结果
+///
+/// 同时把本次首次入库的记录标记为该批次，并发送 `sync-finished` 事件（新增记录 id 与最高稀有度），
+/// 前端据此跳转并高亮新记录。`started_at` 为本地时间，检测到时钟异常时按服务器时间修正后保存。
+/// 一并记下本次同步时是否开启了原始响应留档，记录溯源据此区分"未开启"与"留档缺失"。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn record_sync_run(
+    app: AppHandle,
+    uid: String,
+    started_at: i64,
+    total_ms: i64,
+    network_ms: i64,
+    db_write_ms: i64,
+    records_processed: i64,
+    records_added: i64,
+) -> Result<SyncRun, AppError> {
+    if total_ms < 0 || network_ms < 0 || db_write_ms < 0 {
+        return Err(AppError::validation("同步耗时不能为负数"));
+    }
+    // 新记录的 fetched_at 是本地时间，按本地开始时间圈定；历史里保存修正后的时间
+    let local_started_at = started_at;
+    let started_at = local_started_at + clock_correction_ms(&app);
+    let raw_responses_kept = current_config(&app).keep_raw_responses;
+    let (id, new_records) = with_db_write(&app, "写入同步历史", |conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO sync_history
+               (uid, started_at, total_ms, network_ms, db_write_ms, records_processed, records_added,
+                raw_responses_kept)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                uid,
+                started_at,
+                total_ms,
+                network_ms,
+                db_write_ms,
+                records_processed,
+                records_added,
+                raw_responses_kept
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        let new_records = tag_new_records(&tx, id, &uid, local_started_at)?;
+        tx.commit()?;
+        Ok((id, new_records))
+    })?;
+
+    let new_six_stars = new_records
+        .iter()
+        .filter(|(_, rarity)| *rarity >= TOP_RARITY)
+        .count();
+    let _ = app.emit(
+        "sync-finished",
+        SyncFinished {
+            uid: uid.clone(),
+            sync_run_id: id,
+            top_rarity: new_records.iter().map(|(_, rarity)| *rarity).max(),
+            new_record_ids: new_records.into_iter().map(|(id, _)| id).collect(),
+        },
+    );
+
+    let run = SyncRun {
+        id,
+        records_per_second: records_per_second(records_processed, total_ms),
+        uid,
+        started_at,
+        total_ms,
+        network_ms,
+        db_write_ms,
+        records_processed,
+        records_added,
+    };
+    telemetry::on_sync_completed(&app, run.records_added, new_six_stars);
+    Ok(run)
+}
+
+/// Tauri 命令：获取同步历史（按时间倒序，不传 uid 时返回全部账号）
+#[tauri::command]
+pub(crate) fn get_sync_history(
+    app: AppHandle,
+    uid: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<SyncRun>, AppError> {
+    let conn = open_db(&app)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT uid, started_at, total_ms, network_ms, db_write_ms, records_processed, records_added, id
+               FROM sync_history
+              WHERE ?1 IS NULL OR uid = ?1
+              ORDER BY started_at DESC, id DESC
+              LIMIT ?2",
+        )
+        .map_err(|e| AppError::database(format!("查询同步历史失败: {}", e)))?;
+    let rows = stmt
+        .query_map(rusqlite::params![uid, limit.unwrap_or(50)], |row| {
+            let total_ms: i64 = row.get(2)?;
+            let records_processed: i64 = row.get(5)?;
+            Ok(SyncRun {
+                id: row.get(7)?,
+                uid: row.get(0)?,
+                started_at: row.get(1)?,
+                total_ms,
+                network_ms: row.get(3)?,
+                db_write_ms: row.get(4)?,
+                records_processed,
+                records_added: row.get(6)?,
+                records_per_second: records_per_second(records_processed, total_ms),
+            })
+        })
+        .map_err(|e| AppError::database(format!("查询同步历史失败: {}", e)))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| AppError::database(format!("读取同步历史失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_test_row, test_db};
+
+    #[test]
+    fn only_records_first_inserted_during_the_run_are_tagged() {
+        let conn = test_db();
+        // (表, record_uid, uid, seq_id, 稀有度, 批次, 抓取时间)
+        let rows = [
+            ("gacha_records", "old", "u1", 1, 6, Some(1), 100),
+            ("gacha_records", "new", "u1", 3, 4, None, 600),
+            ("gacha_records", "other", "u2", 3, 5, None, 600),
+            ("weapon_records", "weapon", "u1", 2, 5, None, 700),
+        ];
+        for (table, id, uid, seq, rarity, run, fetched_at) in rows {
+            let pool = if table == "weapon_records" {
+                "weapon"
+            } else {
+                "standard"
+            };
+            insert_test_row(
+                &conn,
+                table,
+                &[
+                    ("record_uid", &id),
+                    ("uid", &uid),
+                    ("pool_id", &pool),
+                    ("rarity", &rarity),
+                    ("gacha_ts", &(seq * 1000).to_string()),
+                    ("seq_id", &seq.to_string()),
+                    ("sync_run_id", &run),
+                    ("fetched_at", &fetched_at),
+                ],
+            );
+        }
+
+        let tagged = tag_new_records(&conn, 2, "u1", 500).unwrap();
+        assert_eq!(
+            tagged,
+            vec![("new".to_string(), 4), ("weapon".to_string(), 5)]
+        );
+        // 已有批次的记录不会被改写
+        let old_run: i64 = conn
+            .query_row(
+                "SELECT sync_run_id FROM gacha_records WHERE record_uid = 'old'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(old_run, 1);
+    }
+}
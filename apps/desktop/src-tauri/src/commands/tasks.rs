@@ -0,0 +1,162 @@
+//! 可取消的后台任务（目前用于导出）
+//!
+//! 任务开始时分配 id 并通过 `export-progress` 事件推送进度，前端可用 `cancel_task` 请求取消。
+//! 导出在各阶段之间检查取消标记；输出先写临时文件，确认未取消后才替换目标文件，
+//! 因此取消不会留下写了一半的文件，也不会覆盖已有的同名文件。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::app_state;
+use crate::config::write_file_atomic_with;
+use crate::db::disk_space::ensure_free_space;
+use crate::error::AppError;
+
+/// 任务被取消时返回的错误码
+pub(crate) const TASK_CANCELLED: &str = "task-cancelled";
+
+/// 进行中的任务（id → 取消标记）
+#[derive(Default)]
+pub(crate) struct TaskState {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    next_id: AtomicU64,
+}
+
+impl TaskState {
+    /// 进行中的任务数
+    pub(crate) fn running_count(&self) -> usize {
+        self.running.lock().unwrap().len()
+    }
+}
+
+/// `export-progress` 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress<'a> {
+    task_id: &'a str,
+    /// uigf / ww_compat / pdf
+    kind: &'a str,
+    /// started / querying / building / writing / finished / cancelled / failed
+    stage: &'a str,
+    done: usize,
+    total: usize,
+}
+
+/// 一次导出任务；析构时从进行中的任务里移除
+pub(crate) struct ExportTask {
+    app: AppHandle,
+    id: String,
+    kind: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ExportTask {
+    pub(crate) fn start(app: &AppHandle, kind: &str) -> Self {
+        let state = &app_state(app).tasks;
+        let id = format!(
+            "export-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            state.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let cancel = Arc::new(AtomicBool::new(false));
+        state
+            .running
+            .lock()
+            .unwrap()
+            .insert(id.clone(), cancel.clone());
+        let task = Self {
+            app: app.clone(),
+            id,
+            kind: kind.to_string(),
+            cancel,
+        };
+        task.progress("started", 0, 0);
+        task
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn progress(&self, stage: &str, done: usize, total: usize) {
+        let _ = self.app.emit(
+            "export-progress",
+            ExportProgress {
+                task_id: &self.id,
+                kind: &self.kind,
+                stage,
+                done,
+                total,
+            },
+        );
+    }
+
+    /// 已请求取消时返回 `task-cancelled` 错误
+    pub(crate) fn check(&self) -> Result<(), AppError> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(cancelled_error());
+        }
+        Ok(())
+    }
+
+    /// 写入导出文件：临时文件写完后再检查一次取消，取消时删除临时文件、不替换目标
+    pub(crate) fn write_output(&self, path: &Path, content: &[u8]) -> Result<(), AppError> {
+        self.check()?;
+        ensure_free_space(path, content.len() as u64)?;
+        self.progress("writing", 0, content.len());
+        write_file_atomic_with(path, content, || self.check())
+    }
+
+    /// 执行任务并在结束时推送最终状态（finished / cancelled / failed）
+    pub(crate) fn run<T>(
+        &self,
+        f: impl FnOnce(&Self) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let result = f(self);
+        let stage = match &result {
+            Ok(_) => "finished",
+            Err(e) if is_cancelled(e) => "cancelled",
+            Err(_) => "failed",
+        };
+        self.progress(stage, 0, 0);
+        result
+    }
+}
+
+impl Drop for ExportTask {
+    fn drop(&mut self) {
+        app_state(&self.app)
+            .tasks
+            .running
+            .lock()
+            .unwrap()
+            .remove(&self.id);
+    }
+}
+
+fn cancelled_error() -> AppError {
+    AppError::conflict(TASK_CANCELLED, "已取消")
+}
+
+/// 错误是否为任务被取消
+pub(crate) fn is_cancelled(error: &AppError) -> bool {
+    matches!(error, AppError::Conflict { code, .. } if code == TASK_CANCELLED)
+}
+
+/// Tauri 命令：取消进行中的任务；任务已结束（或不存在）时返回 false
+#[tauri::command]
+pub(crate) fn cancel_task(app: AppHandle, task_id: String) -> bool {
+    let running = app_state(&app).tasks.running.lock().unwrap();
+    match running.get(&task_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            eprintln!("[tasks] 请求取消 {}", task_id);
+            true
+        }
+        None => false,
+    }
+}
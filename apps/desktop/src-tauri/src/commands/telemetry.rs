@@ -0,0 +1,53 @@
+//! 匿名统计（预留）
+//!
+//! 只保存用户是否同意；同意后同步完成时会上报汇总数据（新增抽数与其中的六星数，不含 uid / token）。
+//! 上报端点尚未确定，目前 `on_sync_completed` 只在日志中记下将要上报的内容，不发送任何数据。
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::config::{current_config, update_config};
+use crate::error::AppError;
+
+/// Tauri 命令：是否已同意发送匿名统计
+#[tauri::command]
+pub(crate) fn get_telemetry_opt_in(app: AppHandle) -> bool {
+    current_config(&app).telemetry_enabled
+}
+
+/// Tauri 命令：设置是否发送匿名统计
+#[tauri::command]
+pub(crate) fn set_telemetry_opt_in(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    update_config(&app, |config| config.telemetry_enabled = enabled)?;
+    eprintln!(
+        "[telemetry] 匿名统计已{}",
+        if enabled { "开启" } else { "关闭" }
+    );
+    Ok(())
+}
+
+/// 同步完成后上报的汇总数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncSummary {
+    /// 本次新增的抽数
+    pulls: i64,
+    /// 其中的六星数
+    new_six_stars: usize,
+}
+
+/// 一次同步完成后的上报钩子（由 `record_sync_run` 调用）
+///
+/// 未同意时直接返回；同意后暂不发送（端点未上线），只记日志。
+pub(crate) fn on_sync_completed(app: &AppHandle, pulls: i64, new_six_stars: usize) {
+    if !current_config(app).telemetry_enabled {
+        return;
+    }
+    let summary = SyncSummary {
+        pulls,
+        new_six_stars,
+    };
+    if let Ok(payload) = serde_json::to_string(&summary) {
+        eprintln!("[telemetry] 待上报（端点未上线，未发送）: {}", payload);
+    }
+}
@@ -0,0 +1,131 @@
+//! OBS 文本导出
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::commands::pity::{reconcile_pity_from_records, PityStatus};
+use crate::config::{current_config, update_config, write_file_atomic};
+use crate::db::open_db;
+use crate::error::AppError;
+
+/// OBS 文本导出设置
+///
+/// 每次同步/保底重算后把当前账号限定池的保底信息写成小文本文件，供 OBS「文本」源读取。
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct TextExportConfig {
+    enabled: bool,
+    /// 输出目录（用户选择）
+    directory: Option<String>,
+    templates: TextExportTemplates,
+}
+
+/// 各文本文件的内容模板，支持 `{pity}`（当前保底抽数）与 `{name}`（上一次出的 6★ 名称）
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TextExportTemplates {
+    /// 写入 pity_limited.txt
+    pity_limited: String,
+    /// 写入 last_sixstar.txt
+    last_sixstar: String,
+}
+
+impl Default for TextExportTemplates {
+    fn default() -> Self {
+        Self {
+            pity_limited: "限定池已垫 {pity} 抽".to_string(),
+            last_sixstar: "上一个 6★：{name}".to_string(),
+        }
+    }
+}
+
+/// 渲染模板中的占位符
+fn render_text_template(template: &str, pity: i64, name: &str) -> String {
+    template
+        .replace("{pity}", &pity.to_string())
+        .replace("{name}", name)
+}
+
+/// 按设置渲染并写出所有文本文件
+fn write_text_export(config: &TextExportConfig, pity: i64, name: &str) -> Result<(), String> {
+    let dir = config
+        .directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .ok_or_else(|| "未设置文本导出目录".to_string())?;
+    let dir = PathBuf::from(dir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建文本导出目录失败: {}", e))?;
+
+    let files = [
+        ("pity_limited.txt", &config.templates.pity_limited),
+        ("last_sixstar.txt", &config.templates.last_sixstar),
+    ];
+    for (file_name, template) in files {
+        // 原子写入，避免 OBS 读到写了一半的内容
+        write_file_atomic(
+            &dir.join(file_name),
+            render_text_template(template, pity, name).as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+/// 保底重算后刷新文本导出（仅当前账号的限定池；失败只记日志，不影响统计流程）
+pub(crate) fn export_pity_text(app: &AppHandle, status: &PityStatus) {
+    if status.gacha_type != "special" {
+        return;
+    }
+    let config = current_config(app);
+    if !config.text_export.enabled || config.active_account.as_deref() != Some(status.uid.as_str())
+    {
+        return;
+    }
+    let name = status.last_five_star_name.as_deref().unwrap_or("暂无");
+    if let Err(e) = write_text_export(&config.text_export, status.pity, name) {
+        eprintln!("[text-export] {}", e);
+    }
+}
+
+/// Tauri 命令：读取 OBS 文本导出设置
+#[tauri::command]
+pub(crate) fn get_text_export_settings(app: AppHandle) -> TextExportConfig {
+    current_config(&app).text_export
+}
+
+/// Tauri 命令：保存 OBS 文本导出设置，启用时立即按当前数据刷新一次
+#[tauri::command]
+pub(crate) fn set_text_export_settings(
+    app: AppHandle,
+    settings: TextExportConfig,
+) -> Result<(), AppError> {
+    let has_directory = settings
+        .directory
+        .as_deref()
+        .is_some_and(|dir| !dir.trim().is_empty());
+    if settings.enabled && !has_directory {
+        return Err(AppError::validation("启用文本导出前请先选择输出目录"));
+    }
+    update_config(&app, |config| config.text_export = settings)?;
+    refresh_text_export(app)
+}
+
+/// Tauri 命令：按当前账号的记录刷新文本导出（前端在每次同步完成后调用）
+#[tauri::command]
+pub(crate) fn refresh_text_export(app: AppHandle) -> Result<(), AppError> {
+    let config = current_config(&app);
+    let Some(uid) = config.active_account.filter(|_| config.text_export.enabled) else {
+        return Ok(());
+    };
+    let conn = open_db(&app)?;
+    reconcile_pity_from_records(&app, &conn, &uid, "special")?;
+    Ok(())
+}
+
+/// Tauri 命令：用示例数据立即写出文本文件，方便主播在没有新抽卡时先搭好场景
+#[tauri::command]
+pub(crate) fn test_text_export(app: AppHandle) -> Result<(), AppError> {
+    let config = current_config(&app);
+    write_text_export(&config.text_export, 42, "示例干员").map_err(AppError::io)
+}
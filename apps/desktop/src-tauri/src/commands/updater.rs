@@ -0,0 +1,258 @@
+//! 自动更新失败处理：区分失败原因，同一版本反复以同样原因失败时停止自动提醒
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::config::{current_config, update_config};
+use crate::db::disk_space::ensure_free_space;
+use crate::error::AppError;
+
+/// 发布页（自动更新不可用时手动下载）
+const RELEASE_PAGE_URL: &str = "https://github.com/xccccya/EndfieldGachaHelper/releases";
+/// 同一版本以同样原因失败多少次后停止自动提醒
+const SUPPRESS_AFTER_FAILURES: u32 = 2;
+/// 更新包的预计大小（下载前还不知道实际大小，按安装包上限估计）
+const UPDATE_SIZE_ESTIMATE: u64 = 64 * 1024 * 1024;
+
+/// 更新失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UpdateFailureKind {
+    /// 安装包签名校验失败（常见于被代理 / 安全软件替换了下载内容）
+    Signature,
+    /// 下载被代理或网关拦截（407、403、证书被替换等）
+    ProxyBlocked,
+    /// 没有写入安装目录的权限
+    PermissionDenied,
+    /// 普通网络错误（断网、超时），不会触发停止提醒
+    Network,
+    Other,
+}
+
+impl UpdateFailureKind {
+    /// 环境本身导致的失败（重试也不会好），才会在反复出现后停止自动提醒
+    fn is_persistent(self) -> bool {
+        matches!(
+            self,
+            Self::Signature | Self::ProxyBlocked | Self::PermissionDenied
+        )
+    }
+}
+
+/// 最近一次更新失败的记录（保存在配置中）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct UpdateFailureRecord {
+    /// 失败时要更新到的版本
+    version: Option<String>,
+    kind: Option<UpdateFailureKind>,
+    /// 同一版本、同一原因的连续失败次数
+    count: u32,
+    /// "手动更新"提示已经显示过
+    notice_shown: bool,
+}
+
+impl UpdateFailureRecord {
+    fn suppressed(&self) -> bool {
+        self.count >= SUPPRESS_AFTER_FAILURES && self.kind.is_some_and(|kind| kind.is_persistent())
+    }
+}
+
+/// `get_update_status` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateStatus {
+    current_version: String,
+    failed_version: Option<String>,
+    failure_kind: Option<UpdateFailureKind>,
+    failure_count: u32,
+    /// 已停止自动检查与提醒，需要用户手动更新或在设置中重试
+    suppressed: bool,
+    /// 应显示一次"手动更新"提示
+    show_manual_notice: bool,
+    release_url: &'static str,
+}
+
+/// 根据更新器返回的错误信息判断失败原因
+fn classify_update_error(message: &str) -> UpdateFailureKind {
+    let message = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+    if has(&["signature", "minisign", "签名"]) {
+        UpdateFailureKind::Signature
+    } else if has(&[
+        "permission denied",
+        "access is denied",
+        "os error 5)",
+        "os error 13)",
+        "拒绝访问",
+        "elevation",
+        "administrator",
+    ]) {
+        UpdateFailureKind::PermissionDenied
+    } else if has(&[
+        "proxy",
+        "407",
+        "403",
+        "forbidden",
+        "blocked",
+        "tunnel",
+        "certificate",
+        "unknownissuer",
+        "代理",
+    ]) {
+        UpdateFailureKind::ProxyBlocked
+    } else if has(&[
+        "timed out",
+        "timeout",
+        "connection",
+        "dns",
+        "error sending request",
+        "network",
+    ]) {
+        UpdateFailureKind::Network
+    } else {
+        UpdateFailureKind::Other
+    }
+}
+
+/// 记一次失败：版本或原因变了就重新计数
+fn record_failure(record: &mut UpdateFailureRecord, version: &str, kind: UpdateFailureKind) {
+    if record.version.as_deref() == Some(version) && record.kind == Some(kind) {
+        record.count = record.count.saturating_add(1);
+    } else {
+        *record = UpdateFailureRecord {
+            version: Some(version.to_string()),
+            kind: Some(kind),
+            count: 1,
+            notice_shown: false,
+        };
+    }
+}
+
+fn update_status(app: &AppHandle, record: &UpdateFailureRecord) -> UpdateStatus {
+    let suppressed = record.suppressed();
+    UpdateStatus {
+        current_version: app.package_info().version.to_string(),
+        failed_version: record.version.clone(),
+        failure_kind: record.kind,
+        failure_count: record.count,
+        suppressed,
+        show_manual_notice: suppressed && !record.notice_shown,
+        release_url: RELEASE_PAGE_URL,
+    }
+}
+
+/// Tauri 命令：获取自动更新的失败 / 停止提醒状态
+#[tauri::command]
+pub(crate) fn get_update_status(app: AppHandle) -> UpdateStatus {
+    update_status(&app, &current_config(&app).update_failure)
+}
+
+/// Tauri 命令：上报一次更新失败
+///
+/// `version` 为要更新到的版本；检查阶段就失败、还不知道新版本时传空，按当前版本计数。
+#[tauri::command]
+pub(crate) fn report_update_failure(
+    app: AppHandle,
+    version: Option<String>,
+    error: String,
+) -> Result<UpdateStatus, AppError> {
+    let version = version
+        .filter(|version| !version.trim().is_empty())
+        .unwrap_or_else(|| app.package_info().version.to_string());
+    let kind = classify_update_error(&error);
+    let config = update_config(&app, |c| {
+        record_failure(&mut c.update_failure, &version, kind)
+    })?;
+    let record = &config.update_failure;
+    eprintln!(
+        "[updater] 更新到 {} 失败（{:?}，第 {} 次）: {}",
+        version, kind, record.count, error
+    );
+    if record.suppressed() {
+        eprintln!(
+            "[updater] 同一原因已连续失败 {} 次，停止自动提醒",
+            record.count
+        );
+    }
+    Ok(update_status(&app, record))
+}
+
+/// Tauri 命令：确认已看过"手动更新"提示（之后不再弹出）
+#[tauri::command]
+pub(crate) fn dismiss_update_notice(app: AppHandle) -> Result<UpdateStatus, AppError> {
+    let config = update_config(&app, |c| c.update_failure.notice_shown = true)?;
+    Ok(update_status(&app, &config.update_failure))
+}
+
+/// Tauri 命令：清除失败记录，恢复自动检查（设置中的"重试"）
+#[tauri::command]
+pub(crate) fn reset_update_failures(app: AppHandle) -> Result<UpdateStatus, AppError> {
+    let config = update_config(&app, |c| c.update_failure = UpdateFailureRecord::default())?;
+    Ok(update_status(&app, &config.update_failure))
+}
+
+/// Tauri 命令：下载更新前检查空间（安装包先下载到临时目录，再安装到程序所在目录）
+#[tauri::command]
+pub(crate) fn check_update_disk_space() -> Result<(), AppError> {
+    ensure_free_space(&std::env::temp_dir(), UPDATE_SIZE_ESTIMATE)?;
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+    {
+        ensure_free_space(&exe_dir, UPDATE_SIZE_ESTIMATE)?;
+    }
+    Ok(())
+}
+
+/// Tauri 命令：在浏览器中打开发布页手动下载
+#[tauri::command]
+pub(crate) fn open_release_page() -> Result<(), AppError> {
+    tauri_plugin_opener::open_url(RELEASE_PAGE_URL, None::<&str>)
+        .map_err(|e| AppError::io(format!("打开发布页失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_common_updater_errors() {
+        assert_eq!(
+            classify_update_error("The signature verification failed"),
+            UpdateFailureKind::Signature
+        );
+        assert_eq!(
+            classify_update_error("error sending request: proxy authentication required (407)"),
+            UpdateFailureKind::ProxyBlocked
+        );
+        assert_eq!(
+            classify_update_error("failed to write installer: Access is denied. (os error 5)"),
+            UpdateFailureKind::PermissionDenied
+        );
+        assert_eq!(
+            classify_update_error("error sending request: operation timed out"),
+            UpdateFailureKind::Network
+        );
+    }
+
+    #[test]
+    fn suppresses_after_repeated_identical_failures() {
+        let mut record = UpdateFailureRecord::default();
+        record_failure(&mut record, "1.2.0", UpdateFailureKind::Signature);
+        assert!(!record.suppressed());
+        record_failure(&mut record, "1.2.0", UpdateFailureKind::Signature);
+        assert!(record.suppressed());
+
+        // 新版本重新计数
+        record_failure(&mut record, "1.2.1", UpdateFailureKind::Signature);
+        assert_eq!(record.count, 1);
+        assert!(!record.suppressed());
+
+        // 普通网络错误不会停止提醒
+        let mut record = UpdateFailureRecord::default();
+        record_failure(&mut record, "1.2.0", UpdateFailureKind::Network);
+        record_failure(&mut record, "1.2.0", UpdateFailureKind::Network);
+        assert!(!record.suppressed());
+    }
+}
@@ -0,0 +1,428 @@
+//! WebDAV（导出文件上传）
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::commands::endpoints::normalize_endpoint;
+use crate::error::AppError;
+
+const WEBDAV_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// 路径段中需要转义的字符（保留 RFC 3986 的 unreserved 字符）
+const WEBDAV_PATH_SEGMENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:displayname/>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+  </d:prop>
+</d:propfind>"#;
+
+/// WebDAV 远端目录中的一项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebdavEntry {
+    pub(crate) name: String,
+    /// 相对于 WebDAV 根地址的路径（以 / 开头，目录以 / 结尾）
+    path: String,
+    pub(crate) is_dir: bool,
+    size: Option<u64>,
+    /// 服务器返回的最后修改时间（RFC 1123 格式，原样透传）
+    modified: Option<String>,
+}
+
+/// WebDAV 连接信息（各命令共用）
+pub(crate) struct WebdavTarget {
+    client: tauri_plugin_http::reqwest::Client,
+    base: String,
+    user: String,
+    pass: String,
+}
+
+impl WebdavTarget {
+    pub(crate) fn new(url: &str, user: String, pass: String) -> Result<Self, String> {
+        let base = normalize_endpoint(url)?;
+        let client = tauri_plugin_http::reqwest::Client::builder()
+            .timeout(WEBDAV_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 WebDAV 客户端失败: {}", e))?;
+        Ok(Self {
+            client,
+            base,
+            user,
+            pass,
+        })
+    }
+
+    /// 拼接远端地址；路径按段转义，`dir` 为 true 时以 / 结尾
+    pub(crate) fn url(&self, segments: &[&str], dir: bool) -> String {
+        let mut url = self.base.clone();
+        for segment in segments {
+            url.push('/');
+            url.push_str(
+                &percent_encoding::utf8_percent_encode(segment, WEBDAV_PATH_SEGMENT).to_string(),
+            );
+        }
+        if dir {
+            url.push('/');
+        }
+        url
+    }
+
+    pub(crate) fn request(
+        &self,
+        method: &[u8],
+        url: &str,
+    ) -> tauri_plugin_http::reqwest::RequestBuilder {
+        let method = tauri_plugin_http::reqwest::Method::from_bytes(method)
+            .expect("WebDAV 方法名均为合法的 HTTP token");
+        self.client
+            .request(method, url)
+            .basic_auth(&self.user, Some(&self.pass))
+    }
+
+    /// 根地址自身的路径部分（用于把服务器返回的 href 换算成相对路径）
+    fn base_path(&self) -> String {
+        let without_scheme = self
+            .base
+            .split_once("://")
+            .map_or(self.base.as_str(), |(_, rest)| rest);
+        let path = without_scheme
+            .find('/')
+            .map_or("", |i| &without_scheme[i..]);
+        percent_encoding::percent_decode_str(path)
+            .decode_utf8_lossy()
+            .into_owned()
+    }
+}
+
+/// 把用户输入的远端路径拆成段（忽略多余的 / 与 .，拒绝 ..）
+fn webdav_segments(path: &str) -> Result<Vec<&str>, AppError> {
+    let segments: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    if segments.contains(&"..") {
+        return Err(AppError::validation("WebDAV 路径不能包含 .."));
+    }
+    Ok(segments)
+}
+
+/// 把 WebDAV 的错误状态码转换成用户能看懂的说明
+pub(crate) fn webdav_status_error(
+    action: &str,
+    status: tauri_plugin_http::reqwest::StatusCode,
+) -> AppError {
+    let message = match status.as_u16() {
+        401 => format!("{}失败：认证失败，请检查用户名和密码", action),
+        403 => format!("{}失败：没有该目录的访问权限", action),
+        404 | 409 => format!("{}失败：远端目录不存在", action),
+        405 => format!("{}失败：服务器不允许该操作（可能不是 WebDAV 地址）", action),
+        429 => {
+            return AppError::RateLimited {
+                retry_after: None,
+                message: format!("{}失败：请求过于频繁，请稍后再试", action),
+            }
+        }
+        507 => format!("{}失败：远端存储空间不足", action),
+        _ => format!("{}失败: HTTP {}", action, status),
+    };
+    AppError::network(Some(status.as_u16()), message)
+}
+
+/// 解析 PROPFIND 的 207 响应（只看元素的本地名，兼容各家服务器不同的命名空间前缀）
+fn parse_propfind(xml: &str, base_path: &str) -> Result<Vec<WebdavEntry>, String> {
+    use quick_xml::events::Event;
+
+    #[derive(Default)]
+    struct Response {
+        href: String,
+        display_name: Option<String>,
+        is_dir: bool,
+        size: Option<u64>,
+        modified: Option<String>,
+    }
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut responses: Vec<Response> = Vec::new();
+    let mut current: Option<Response> = None;
+    let mut field: Option<String> = None;
+    let mut text = String::new();
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| format!("解析 WebDAV 响应失败: {}", e))?
+        {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match name.as_str() {
+                    "response" => current = Some(Response::default()),
+                    "collection" => {
+                        if let Some(response) = current.as_mut() {
+                            response.is_dir = true;
+                        }
+                    }
+                    _ => {}
+                }
+                field = Some(name);
+                text.clear();
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"collection" => {
+                if let Some(response) = current.as_mut() {
+                    response.is_dir = true;
+                }
+            }
+            Event::Text(e) => {
+                text.push_str(
+                    &e.decode()
+                        .map_err(|e| format!("解析 WebDAV 响应失败: {}", e))?,
+                );
+            }
+            Event::CData(e) => {
+                text.push_str(
+                    &e.decode()
+                        .map_err(|e| format!("解析 WebDAV 响应失败: {}", e))?,
+                );
+            }
+            Event::GeneralRef(e) => {
+                let name = e
+                    .decode()
+                    .map_err(|e| format!("解析 WebDAV 响应失败: {}", e))?;
+                if let Some(resolved) = quick_xml::escape::resolve_predefined_entity(&name) {
+                    text.push_str(resolved);
+                } else if let Ok(Some(ch)) = e.resolve_char_ref() {
+                    text.push(ch);
+                }
+            }
+            Event::End(e) => {
+                let name = e.local_name();
+                let value = text.trim().to_string();
+                if let Some(response) = current.as_mut() {
+                    if field.as_deref() == Some(String::from_utf8_lossy(name.as_ref()).as_ref()) {
+                        match name.as_ref() {
+                            b"href" => response.href = value,
+                            b"displayname" if !value.is_empty() => {
+                                response.display_name = Some(value)
+                            }
+                            b"getcontentlength" => response.size = value.parse().ok(),
+                            b"getlastmodified" if !value.is_empty() => {
+                                response.modified = Some(value)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                if name.as_ref() == b"response" {
+                    if let Some(response) = current.take() {
+                        responses.push(response);
+                    }
+                }
+                field = None;
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let base_path = base_path.trim_end_matches('/');
+    let entries = responses
+        .into_iter()
+        .filter(|response| !response.href.is_empty())
+        .map(|response| {
+            // href 可能是完整 URL，也可能只是路径
+            let href = match response.href.split_once("://") {
+                Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]).to_string(),
+                None => response.href.clone(),
+            };
+            let decoded = percent_encoding::percent_decode_str(&href)
+                .decode_utf8_lossy()
+                .into_owned();
+            let relative = decoded.strip_prefix(base_path).unwrap_or(&decoded);
+            let trimmed = relative.trim_matches('/');
+            let name = response
+                .display_name
+                .clone()
+                .unwrap_or_else(|| trimmed.rsplit('/').next().unwrap_or("").to_string());
+            let path = match (trimmed.is_empty(), response.is_dir) {
+                (true, _) => "/".to_string(),
+                (false, true) => format!("/{}/", trimmed),
+                (false, false) => format!("/{}", trimmed),
+            };
+            WebdavEntry {
+                name,
+                path,
+                is_dir: response.is_dir,
+                size: response.size,
+                modified: response.modified,
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// 逐级创建目录（已存在的目录服务器返回 405，视为成功）
+pub(crate) async fn webdav_mkcol_all(
+    target: &WebdavTarget,
+    segments: &[&str],
+) -> Result<(), AppError> {
+    for depth in 1..=segments.len() {
+        let url = target.url(&segments[..depth], true);
+        let response = target
+            .request(b"MKCOL", &url)
+            .send()
+            .await
+            .map_err(|e| AppError::network(None, format!("创建 WebDAV 目录失败: {}", e)))?;
+        let status = response.status();
+        if !(status.is_success() || status.as_u16() == 405) {
+            return Err(webdav_status_error("创建 WebDAV 目录", status));
+        }
+    }
+    Ok(())
+}
+
+/// 列出远端目录内容（不含目录自身，目录在前、按名称排序）
+pub(crate) async fn webdav_propfind_dir(
+    target: &WebdavTarget,
+    segments: &[&str],
+) -> Result<Vec<WebdavEntry>, AppError> {
+    let response = target
+        .request(b"PROPFIND", &target.url(segments, true))
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(PROPFIND_BODY)
+        .send()
+        .await
+        .map_err(|e| AppError::network(None, format!("连接 WebDAV 失败: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(webdav_status_error("读取 WebDAV 目录", status));
+    }
+    let xml = response
+        .text()
+        .await
+        .map_err(|e| AppError::network(None, format!("读取 WebDAV 响应失败: {}", e)))?;
+
+    let own_path = if segments.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/{}/", segments.join("/"))
+    };
+    let mut entries: Vec<WebdavEntry> = parse_propfind(&xml, &target.base_path())?
+        .into_iter()
+        // 结果中包含目录自身（部分服务器不带末尾 /），去掉
+        .filter(|entry| entry.path.trim_end_matches('/') != own_path.trim_end_matches('/'))
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// 上传文件到远端目录，返回远端路径（目录不存在时自动逐级创建后重试一次）
+pub(crate) async fn webdav_put_file(
+    target: &WebdavTarget,
+    segments: &[&str],
+    file_name: &str,
+    content: Vec<u8>,
+) -> Result<String, AppError> {
+    let mut file_segments = segments.to_vec();
+    file_segments.push(file_name);
+    let file_url = target.url(&file_segments, false);
+    let mut created_dir = false;
+    loop {
+        let response = target
+            .request(b"PUT", &file_url)
+            .body(content.clone())
+            .send()
+            .await
+            .map_err(|e| AppError::network(None, format!("上传到 WebDAV 失败: {}", e)))?;
+        let status = response.status();
+        if status.is_success() {
+            break;
+        }
+        // 目录不存在：多数服务器返回 409，部分返回 404
+        if matches!(status.as_u16(), 404 | 409) && !created_dir && !segments.is_empty() {
+            webdav_mkcol_all(target, segments).await?;
+            created_dir = true;
+            continue;
+        }
+        return Err(webdav_status_error("上传到 WebDAV", status));
+    }
+    Ok(format!("/{}", file_segments.join("/")))
+}
+
+/// Tauri 命令：列出 WebDAV 远端目录内容（不含目录自身，目录在前、按名称排序）
+#[tauri::command]
+pub(crate) async fn webdav_list(
+    url: String,
+    user: String,
+    pass: String,
+    path: String,
+) -> Result<Vec<WebdavEntry>, AppError> {
+    let target = WebdavTarget::new(&url, user, pass)?;
+    webdav_propfind_dir(&target, &webdav_segments(&path)?).await
+}
+
+/// Tauri 命令：在 WebDAV 上创建目录（含上级目录）
+#[tauri::command]
+pub(crate) async fn webdav_mkdir(
+    url: String,
+    user: String,
+    pass: String,
+    path: String,
+) -> Result<(), AppError> {
+    let target = WebdavTarget::new(&url, user, pass)?;
+    let segments = webdav_segments(&path)?;
+    if segments.is_empty() {
+        return Ok(());
+    }
+    webdav_mkcol_all(&target, &segments).await
+}
+
+/// Tauri 命令：把本地文件（如导出的 UIGF）上传到 WebDAV 指定目录，返回远端路径
+#[tauri::command]
+pub(crate) async fn webdav_upload(
+    url: String,
+    user: String,
+    pass: String,
+    path: String,
+    local_path: String,
+) -> Result<String, AppError> {
+    let target = WebdavTarget::new(&url, user, pass)?;
+    let segments = webdav_segments(&path)?;
+    let local = PathBuf::from(&local_path);
+    let file_name = local
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::validation(format!("无效的文件路径: {}", local_path)))?
+        .to_string();
+    let content =
+        std::fs::read(&local).map_err(|e| AppError::io(format!("读取文件失败: {}", e)))?;
+    webdav_put_file(&target, &segments, &file_name, content).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_ignore_empty_and_current_dir() {
+        assert_eq!(
+            webdav_segments("/efgh//backups/./2024\\").unwrap(),
+            vec!["efgh", "backups", "2024"]
+        );
+        assert!(webdav_segments("/").unwrap().is_empty());
+    }
+
+    #[test]
+    fn segments_reject_parent_dir() {
+        assert!(webdav_segments("efgh/../secret").is_err());
+        assert!(webdav_segments("..\\secret").is_err());
+    }
+}
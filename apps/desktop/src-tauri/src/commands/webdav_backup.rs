@@ -0,0 +1,511 @@
+//! WebDAV 异地备份
+//!
+//! 本地备份与硬盘同生共死，配置 WebDAV（如坚果云）后每次备份可自动上传到远端
+//! `efgh/backup-<时间>.db`，远端只保留最近 N 份；换机时可从远端下载备份恢复。
+
+use chrono::Utc;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::endpoints::normalize_endpoint;
+use crate::commands::webdav::{
+    webdav_mkcol_all, webdav_propfind_dir, webdav_put_file, webdav_status_error, WebdavEntry,
+    WebdavTarget,
+};
+use crate::config::{current_config, update_config, write_file_atomic};
+use crate::db::backup::backup_database;
+use crate::db::current_db_file;
+use crate::db::disk_space::ensure_free_space;
+use crate::db::revision::{compare_revisions, read_revision_of};
+use crate::db::schema::{backup_db_snapshot, backup_dir};
+use crate::error::AppError;
+
+/// 远端备份目录
+const WEBDAV_BACKUP_DIR: &str = "efgh";
+/// 上传失败后的重试间隔
+const WEBDAV_UPLOAD_RETRY_DELAYS_MS: [u64; 3] = [1000, 3000, 10000];
+/// 备份比本地数据旧、恢复会丢失改动时返回的错误码
+const RESTORE_OLDER_BACKUP: &str = "restore-older-backup";
+
+/// WebDAV 备份设置（保存在 AppConfig 中，密码经 DPAPI 加密）
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct WebdavConfig {
+    url: String,
+    username: String,
+    /// 加密后的密码（十六进制）
+    password: String,
+    /// 每次创建备份后自动上传
+    upload_after_backup: bool,
+    /// 远端保留的备份份数
+    keep_remote: u32,
+}
+
+impl Default for WebdavConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            upload_after_backup: false,
+            keep_remote: 10,
+        }
+    }
+}
+
+/// 返回给前端的 WebDAV 设置（不含密码）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebdavSettings {
+    url: String,
+    username: String,
+    has_password: bool,
+    upload_after_backup: bool,
+    keep_remote: u32,
+}
+
+/// 远端备份上传结果（随 `webdav-backup-uploaded` / `webdav-backup-failed` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebdavBackupEvent {
+    local_path: String,
+    remote_path: Option<String>,
+    error: Option<String>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("无效的加密数据".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| "无效的加密数据".to_string()))
+        .collect()
+}
+
+/// 用 DPAPI 加密（仅当前 Windows 用户可解密）
+#[cfg(windows)]
+fn protect_secret(secret: &str) -> Result<String, String> {
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{
+        CryptProtectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let mut data = secret.as_bytes().to_vec();
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_mut_ptr(),
+    };
+    let mut output = CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: std::ptr::null_mut(),
+    };
+    unsafe {
+        if CryptProtectData(
+            &input,
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        ) == 0
+        {
+            return Err(format!("加密密码失败: {}", std::io::Error::last_os_error()));
+        }
+        let bytes = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+        LocalFree(output.pbData as _);
+        Ok(to_hex(&bytes))
+    }
+}
+
+#[cfg(windows)]
+fn unprotect_secret(protected: &str) -> Result<String, String> {
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{
+        CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let mut data = from_hex(protected)?;
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_mut_ptr(),
+    };
+    let mut output = CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: std::ptr::null_mut(),
+    };
+    unsafe {
+        if CryptUnprotectData(
+            &input,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        ) == 0
+        {
+            return Err(
+                "解密 WebDAV 密码失败（可能换了电脑或 Windows 账户），请重新填写密码".to_string(),
+            );
+        }
+        let bytes = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+        LocalFree(output.pbData as _);
+        String::from_utf8(bytes).map_err(|_| "WebDAV 密码数据已损坏，请重新填写密码".to_string())
+    }
+}
+
+/// 非 Windows 平台没有 DPAPI，仅做编码存储（配置文件位于用户目录，依赖文件权限保护）
+#[cfg(not(windows))]
+fn protect_secret(secret: &str) -> Result<String, String> {
+    Ok(to_hex(secret.as_bytes()))
+}
+
+#[cfg(not(windows))]
+fn unprotect_secret(protected: &str) -> Result<String, String> {
+    String::from_utf8(from_hex(protected)?)
+        .map_err(|_| "WebDAV 密码数据已损坏，请重新填写密码".to_string())
+}
+
+/// 按设置创建 WebDAV 连接
+fn webdav_target_from_config(app: &AppHandle) -> Result<WebdavTarget, AppError> {
+    let config = current_config(app).webdav;
+    if config.url.is_empty() {
+        return Err(AppError::validation("尚未配置 WebDAV"));
+    }
+    let password = if config.password.is_empty() {
+        String::new()
+    } else {
+        unprotect_secret(&config.password)?
+    };
+    Ok(WebdavTarget::new(&config.url, config.username, password)?)
+}
+
+/// 远端备份文件名是否由本程序生成（清理与下载只处理这些文件）
+fn is_remote_backup_name(name: &str) -> bool {
+    name.starts_with("backup-")
+        && name.ends_with(".db")
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+/// 列出远端备份（新的在前；文件名含时间，按名称倒序即按时间倒序）
+async fn remote_backups(target: &WebdavTarget) -> Result<Vec<WebdavEntry>, AppError> {
+    let entries = match webdav_propfind_dir(target, &[WEBDAV_BACKUP_DIR]).await {
+        Ok(entries) => entries,
+        // 从未上传过：远端目录还不存在
+        Err(AppError::Network {
+            status: Some(404 | 409),
+            ..
+        }) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    let mut backups: Vec<WebdavEntry> = entries
+        .into_iter()
+        .filter(|entry| !entry.is_dir && is_remote_backup_name(&entry.name))
+        .collect();
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// 删除超出保留份数的旧备份
+async fn prune_remote_backups(target: &WebdavTarget, keep: u32) -> Result<(), AppError> {
+    for entry in remote_backups(target)
+        .await?
+        .iter()
+        .skip(keep.max(1) as usize)
+    {
+        let url = target.url(&[WEBDAV_BACKUP_DIR, &entry.name], false);
+        let response = target
+            .request(b"DELETE", &url)
+            .send()
+            .await
+            .map_err(|e| AppError::network(None, format!("删除远端旧备份失败: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(webdav_status_error("删除远端旧备份", response.status()));
+        }
+        let manifest = entry.name.replace(".db", ".json");
+        let _ = target
+            .request(
+                b"DELETE",
+                &target.url(&[WEBDAV_BACKUP_DIR, &manifest], false),
+            )
+            .send()
+            .await;
+        eprintln!("[webdav] 已删除远端旧备份 {}", entry.name);
+    }
+    Ok(())
+}
+
+/// 上传一份本地备份（失败按间隔重试），成功后按保留份数清理远端，返回远端路径
+async fn upload_backup(app: &AppHandle, local_path: &std::path::Path) -> Result<String, AppError> {
+    let target = webdav_target_from_config(app)?;
+    let keep = current_config(app).webdav.keep_remote;
+    let content =
+        std::fs::read(local_path).map_err(|e| AppError::io(format!("读取备份文件失败: {}", e)))?;
+    let file_name = format!("backup-{}.db", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+
+    let mut attempt = 0;
+    let remote_path = loop {
+        match webdav_put_file(&target, &[WEBDAV_BACKUP_DIR], &file_name, content.clone()).await {
+            Ok(path) => break path,
+            // 认证/权限问题重试也不会好
+            Err(
+                e @ AppError::Network {
+                    status: Some(401 | 403),
+                    ..
+                },
+            ) => return Err(e),
+            Err(e) => {
+                let Some(delay) = WEBDAV_UPLOAD_RETRY_DELAYS_MS.get(attempt) else {
+                    return Err(e);
+                };
+                eprintln!("[webdav] 上传备份失败，{}ms 后重试: {}", delay, e);
+                sleep_ms(*delay).await;
+                attempt += 1;
+            }
+        }
+    };
+
+    // 备份清单：恢复前无需下载整个备份即可比较修订号
+    let manifest = serde_json::json!({
+        "revision": read_revision_of(local_path).unwrap_or_default(),
+        "appVersion": app.package_info().version.to_string(),
+        "createdAt": Utc::now().timestamp_millis(),
+    });
+    let manifest_name = file_name.replace(".db", ".json");
+    if let Err(e) = webdav_put_file(
+        &target,
+        &[WEBDAV_BACKUP_DIR],
+        &manifest_name,
+        manifest.to_string().into_bytes(),
+    )
+    .await
+    {
+        eprintln!("[webdav] 上传备份清单失败: {}", e);
+    }
+
+    if let Err(e) = prune_remote_backups(&target, keep).await {
+        eprintln!("[webdav] 清理远端旧备份失败: {}", e);
+    }
+    Ok(remote_path)
+}
+
+/// 异步等待（在 Tauri 的异步运行时中使用，不阻塞线程）
+async fn sleep_ms(ms: u64) {
+    let _ = tauri::async_runtime::spawn_blocking(move || {
+        std::thread::sleep(std::time::Duration::from_millis(ms))
+    })
+    .await;
+}
+
+/// 本地备份创建后调用：开启了自动上传时在后台上传
+pub(crate) fn after_backup_created(app: &AppHandle, local_path: PathBuf) {
+    let config = current_config(app).webdav;
+    if !config.upload_after_backup || config.url.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = upload_backup(&app, &local_path).await;
+        let local_path = local_path.to_string_lossy().into_owned();
+        match result {
+            Ok(remote_path) => {
+                eprintln!("[webdav] 备份已上传: {}", remote_path);
+                let _ = app.emit(
+                    "webdav-backup-uploaded",
+                    WebdavBackupEvent {
+                        local_path,
+                        remote_path: Some(remote_path),
+                        error: None,
+                    },
+                );
+            }
+            Err(e) => {
+                eprintln!("[webdav] 备份上传失败: {}", e);
+                let _ = app.emit(
+                    "webdav-backup-failed",
+                    WebdavBackupEvent {
+                        local_path,
+                        remote_path: None,
+                        error: Some(e.to_string()),
+                    },
+                );
+            }
+        }
+    });
+}
+
+/// Tauri 命令：获取 WebDAV 备份设置（不返回密码）
+#[tauri::command]
+pub(crate) fn get_webdav_settings(app: AppHandle) -> WebdavSettings {
+    let config = current_config(&app).webdav;
+    WebdavSettings {
+        url: config.url,
+        username: config.username,
+        has_password: !config.password.is_empty(),
+        upload_after_backup: config.upload_after_backup,
+        keep_remote: config.keep_remote,
+    }
+}
+
+/// Tauri 命令：保存 WebDAV 连接信息；`password` 为空时保留原密码，`url` 为空表示关闭
+#[tauri::command]
+pub(crate) fn set_webdav(
+    app: AppHandle,
+    url: String,
+    username: String,
+    password: String,
+) -> Result<(), AppError> {
+    let url = if url.trim().is_empty() {
+        String::new()
+    } else {
+        normalize_endpoint(&url).map_err(AppError::validation)?
+    };
+    let protected = if password.is_empty() {
+        None
+    } else {
+        Some(protect_secret(&password)?)
+    };
+    update_config(&app, |c| {
+        if url.is_empty() {
+            c.webdav.password.clear();
+        } else if let Some(protected) = protected {
+            c.webdav.password = protected;
+        }
+        c.webdav.url = url;
+        c.webdav.username = username.trim().to_string();
+    })?;
+    Ok(())
+}
+
+/// Tauri 命令：设置备份后自动上传与远端保留份数
+#[tauri::command]
+pub(crate) fn set_webdav_backup_options(
+    app: AppHandle,
+    upload_after_backup: bool,
+    keep_remote: u32,
+) -> Result<(), AppError> {
+    if keep_remote == 0 {
+        return Err(AppError::validation("远端至少保留 1 份备份"));
+    }
+    update_config(&app, |c| {
+        c.webdav.upload_after_backup = upload_after_backup;
+        c.webdav.keep_remote = keep_remote;
+    })?;
+    Ok(())
+}
+
+/// Tauri 命令：测试 WebDAV 连接与写入权限（在备份目录写入并删除一个测试文件）
+#[tauri::command]
+pub(crate) async fn test_webdav(app: AppHandle) -> Result<(), AppError> {
+    let target = webdav_target_from_config(&app)?;
+    webdav_propfind_dir(&target, &[]).await?;
+    webdav_mkcol_all(&target, &[WEBDAV_BACKUP_DIR]).await?;
+    let probe = ".efgh-write-test";
+    webdav_put_file(&target, &[WEBDAV_BACKUP_DIR], probe, b"ok".to_vec()).await?;
+    let response = target
+        .request(b"DELETE", &target.url(&[WEBDAV_BACKUP_DIR, probe], false))
+        .send()
+        .await
+        .map_err(|e| AppError::network(None, format!("删除测试文件失败: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(webdav_status_error("删除测试文件", response.status()));
+    }
+    Ok(())
+}
+
+/// Tauri 命令：立即创建一份本地备份（开启自动上传时随后上传），返回备份路径
+#[tauri::command]
+pub(crate) fn create_db_backup(app: AppHandle) -> Result<String, AppError> {
+    let backup = backup_database(&app, "manual")?;
+    Ok(backup.to_string_lossy().into_owned())
+}
+
+/// Tauri 命令：列出远端备份（新的在前）
+#[tauri::command]
+pub(crate) async fn list_remote_backups(app: AppHandle) -> Result<Vec<WebdavEntry>, AppError> {
+    remote_backups(&webdav_target_from_config(&app)?).await
+}
+
+/// Tauri 命令：下载远端备份到本地备份目录，返回本地路径（用于换机后恢复）
+#[tauri::command]
+pub(crate) async fn download_remote_backup(
+    app: AppHandle,
+    name: String,
+) -> Result<String, AppError> {
+    if !is_remote_backup_name(&name) {
+        return Err(AppError::validation(format!("无效的备份文件名: {}", name)));
+    }
+    let target = webdav_target_from_config(&app)?;
+    let response = target
+        .request(b"GET", &target.url(&[WEBDAV_BACKUP_DIR, &name], false))
+        .send()
+        .await
+        .map_err(|e| AppError::network(None, format!("下载远端备份失败: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(webdav_status_error("下载远端备份", response.status()));
+    }
+    let content = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::network(None, format!("下载远端备份失败: {}", e)))?;
+
+    let dir = backup_dir(&current_db_file(&app)?)?;
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::io(format!("创建备份目录失败: {}", e)))?;
+    let local = dir.join(format!("remote-{}", name));
+    ensure_free_space(&local, content.len() as u64)?;
+    write_file_atomic(&local, &content).map_err(AppError::io)?;
+    Ok(local.to_string_lossy().into_owned())
+}
+
+/// Tauri 命令：用备份文件替换当前数据库（调用前前端需关闭数据库连接，完成后重新加载）
+///
+/// 替换前校验备份完整性，并把当前数据库再备份一份。备份比本地旧时需 `force` 才会覆盖。
+#[tauri::command]
+pub(crate) fn restore_db_backup(
+    app: AppHandle,
+    path: String,
+    force: Option<bool>,
+) -> Result<(), AppError> {
+    let source = PathBuf::from(&path);
+    if !force.unwrap_or(false) {
+        if let Some(warning) = compare_revisions(&app, &source)?.warning {
+            return Err(AppError::conflict(RESTORE_OLDER_BACKUP, warning));
+        }
+    }
+    {
+        let conn = Connection::open_with_flags(&source, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| AppError::database(format!("打开备份文件失败: {}", e)))?;
+        let result: String = conn
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| AppError::database(format!("校验备份文件失败: {}", e)))?;
+        if result != "ok" {
+            return Err(AppError::database(format!("备份文件已损坏: {}", result)));
+        }
+    }
+
+    let db_file = current_db_file(&app)?;
+    if db_file.exists() {
+        let conn = Connection::open(&db_file)
+            .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+        backup_db_snapshot(&conn, &db_file, "before-restore")?;
+    }
+    let content =
+        std::fs::read(&source).map_err(|e| AppError::io(format!("读取备份文件失败: {}", e)))?;
+    ensure_free_space(&db_file, content.len() as u64)?;
+    write_file_atomic(&db_file, &content).map_err(AppError::io)?;
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = PathBuf::from(format!("{}{}", db_file.to_string_lossy(), suffix));
+        let _ = std::fs::remove_file(sidecar);
+    }
+    eprintln!("[backup] 已从 {} 恢复数据库", path);
+    Ok(())
+}
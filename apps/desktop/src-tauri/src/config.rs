@@ -0,0 +1,385 @@
+//! 应用配置与 userdata 目录
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+#[cfg(target_os = "windows")]
+use winreg::enums::HKEY_CURRENT_USER;
+#[cfg(target_os = "windows")]
+use winreg::RegKey;
+
+use crate::app_state;
+use crate::commands::banner_watch::BannerWatchConfig;
+use crate::commands::custom_endpoint::CustomEndpointConfig;
+use crate::commands::stats::PullPriceConfig;
+use crate::commands::text_export::TextExportConfig;
+use crate::commands::updater::UpdateFailureRecord;
+use crate::commands::webdav_backup::WebdavConfig;
+use crate::error::AppError;
+
+/// 默认数据库文件名
+pub(crate) const DB_FILE_NAME: &str = "efgacha.db";
+
+/// 获取 exe 所在目录下的 userdata/ 目录（不保证已创建）
+pub(crate) fn userdata_dir() -> Result<PathBuf, String> {
+    let exe_dir = std::env::current_exe()
+        .map_err(|e| format!("获取 exe 路径失败: {}", e))?
+        .parent()
+        .ok_or_else(|| "无法获取 exe 所在目录".to_string())?
+        .to_path_buf();
+
+    Ok(exe_dir.join("userdata"))
+}
+
+/// 原子写入文件：先写同目录下的临时文件再替换，避免中途失败留下半截文件
+pub(crate) fn write_file_atomic(path: &std::path::Path, content: &[u8]) -> Result<(), String> {
+    write_file_atomic_with(path, content, || Ok(())).map_err(|e: AppError| e.to_string())
+}
+
+/// 同 `write_file_atomic`，临时文件写完后、替换目标前调用 `before_rename`；
+/// 其返回错误时删除临时文件，目标文件保持不变
+pub(crate) fn write_file_atomic_with<E: From<AppError>>(
+    path: &std::path::Path,
+    content: &[u8],
+    before_rename: impl FnOnce() -> Result<(), E>,
+) -> Result<(), E> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    std::fs::write(&tmp, content)
+        .map_err(|e| AppError::io(format!("写入 {} 失败: {}", tmp.display(), e)))?;
+    if let Err(e) = before_rename() {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        E::from(AppError::io(format!("写入 {} 失败: {}", path.display(), e)))
+    })
+}
+
+// ============== 应用配置（Rust 端持久化） ==============
+
+/// 应用配置（保存在 userdata/config.json）
+///
+/// 新增字段一律带默认值，旧版配置文件缺字段时可直接读取。
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct AppConfig {
+    /// 当前激活的账号（accounts.uid）
+    pub(crate) active_account: Option<String>,
+    /// 云同步接口列表（按优先级排列，第一个为主接口）；为空时使用前端内置地址
+    pub(crate) sync_endpoints: Vec<String>,
+    /// OBS 文本导出设置
+    pub(crate) text_export: TextExportConfig,
+    /// 程序位于可移动存储时使用 synchronous=FULL（更慢但更不易因拔出而损坏）
+    pub(crate) removable_full_sync: bool,
+    /// 用户已看过更新说明的版本（用于"新版本说明"只弹一次）
+    pub(crate) changelog_acknowledged: Option<String>,
+    /// WebDAV 异地备份设置
+    pub(crate) webdav: WebdavConfig,
+    /// 主窗口界面缩放（为空表示 100%）
+    pub(crate) zoom: Option<f64>,
+    /// 托盘菜单窗口是否跟随主窗口缩放
+    pub(crate) tray_menu_follow_zoom: bool,
+    /// 本机设备标识（首次写库时生成，用于区分多台电脑的修改）
+    pub(crate) device_id: Option<String>,
+    /// 定时自动备份间隔（小时），为空表示未开启
+    pub(crate) backup_interval_hours: Option<u32>,
+    /// 外部备份目录（U 盘、网盘同步文件夹等），为空表示只备份到本地
+    pub(crate) backup_external_dir: Option<String>,
+    /// 上次通过保存对话框导出时选择的目录
+    pub(crate) last_export_dir: Option<String>,
+    /// 物品图标 CDN 地址（为空表示不下载图标，只使用内置图片与本地缓存）
+    pub(crate) icon_cdn: Option<String>,
+    /// 换池提醒设置
+    pub(crate) banner_watch: BannerWatchConfig,
+    /// 单抽价格（资源估算）
+    pub(crate) pull_price: PullPriceConfig,
+    /// 统计分桶使用的 UTC 偏移（小时），为空表示跟随系统时区
+    pub(crate) display_utc_offset_hours: Option<i32>,
+    /// 自动更新失败记录（同一原因反复失败后停止自动提醒）
+    pub(crate) update_failure: UpdateFailureRecord,
+    /// 是否同意发送匿名统计（默认关闭）
+    pub(crate) telemetry_enabled: bool,
+    /// 自定义抽卡记录接口（高级模式，默认关闭）
+    pub(crate) custom_endpoint: CustomEndpointConfig,
+    /// 同步时把接口原始响应保存到 userdata/raw/（排查解析问题用，默认关闭）
+    pub(crate) keep_raw_responses: bool,
+    /// 原始响应留档保留天数，为空表示使用默认值
+    pub(crate) raw_response_keep_days: Option<u32>,
+}
+
+/// 应用配置的托管状态
+#[derive(Default)]
+pub(crate) struct AppConfigState(pub(crate) Mutex<AppConfig>);
+
+fn config_file() -> Result<PathBuf, String> {
+    Ok(userdata_dir()?.join("config.json"))
+}
+
+/// 读取配置文件；文件不存在或损坏时使用默认配置
+pub(crate) fn load_config() -> AppConfig {
+    let Ok(path) = config_file() else {
+        return AppConfig::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("[config] 配置文件解析失败，使用默认配置: {}", e);
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    }
+}
+
+/// 写入配置文件（先写临时文件再替换，避免写一半导致配置损坏）
+fn save_config(config: &AppConfig) -> Result<(), String> {
+    let path = config_file()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| format!("写入配置失败: {}", e))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("保存配置失败: {}", e))
+}
+
+/// 修改并持久化配置，返回修改后的配置
+pub(crate) fn update_config(
+    app: &AppHandle,
+    f: impl FnOnce(&mut AppConfig),
+) -> Result<AppConfig, AppError> {
+    let state = &app_state(app).config;
+    let mut config = state.0.lock().unwrap();
+    let mut next = config.clone();
+    f(&mut next);
+    save_config(&next).map_err(AppError::io)?;
+    *config = next.clone();
+    Ok(next)
+}
+
+/// 读取当前配置快照
+pub(crate) fn current_config(app: &AppHandle) -> AppConfig {
+    app_state(app).config.0.lock().unwrap().clone()
+}
+
+/// 安装程序写入的卸载信息（安装版的注册表标记）；键名为 tauri.conf.json 中的 identifier（构建时注入）
+#[cfg(target_os = "windows")]
+pub(crate) const UNINSTALL_KEY: &str = concat!(
+    r"Software\Microsoft\Windows\CurrentVersion\Uninstall\",
+    env!("EFGH_BUNDLE_IDENTIFIER")
+);
+
+/// 一处卸载信息的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UninstallEntry {
+    /// 完整注册表路径（含根键与视图）
+    pub(crate) key: String,
+    /// 安装目录：InstallLocation，缺失时取 UninstallString 所在目录
+    install_location: Option<String>,
+    /// 安装目录与当前 exe 所在目录一致
+    matches_exe: bool,
+}
+
+/// 安装方式检测结果（`get_app_info` 中用于排查误判）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InstallDetection {
+    portable: bool,
+    exe_dir: Option<String>,
+    /// 与当前 exe 目录一致的卸载信息
+    matched_key: Option<String>,
+    /// 依次检查过的卸载信息（只列出存在的键）
+    pub(crate) entries: Vec<UninstallEntry>,
+}
+
+/// 从卸载信息读出安装目录
+#[cfg(target_os = "windows")]
+fn uninstall_location(key: &RegKey) -> Option<PathBuf> {
+    let value = |name: &str| {
+        key.get_value::<String, _>(name)
+            .ok()
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    };
+    if let Some(location) = value("InstallLocation") {
+        return Some(PathBuf::from(location));
+    }
+    uninstall_string_dir(&key.get_value::<String, _>("UninstallString").ok()?)
+}
+
+/// UninstallString（形如 `"C:\...\uninstall.exe" /S`）中卸载程序所在的目录
+#[cfg(any(target_os = "windows", test))]
+fn uninstall_string_dir(uninstall: &str) -> Option<PathBuf> {
+    let uninstall = uninstall.trim();
+    let exe = match uninstall.strip_prefix('"') {
+        Some(rest) => rest.split('"').next()?,
+        None => uninstall.split_whitespace().next()?,
+    };
+    exe.rsplit_once(['\\', '/'])
+        .map(|(dir, _)| dir)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 依次检查当前用户安装（HKCU）与全机安装（HKLM 的 64 / 32 位视图）的卸载信息
+#[cfg(target_os = "windows")]
+pub(crate) fn detect_install() -> InstallDetection {
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_32KEY, KEY_WOW64_64KEY};
+
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from));
+    let candidates = [
+        (HKEY_CURRENT_USER, KEY_READ, "HKCU"),
+        (
+            HKEY_LOCAL_MACHINE,
+            KEY_READ | KEY_WOW64_64KEY,
+            "HKLM (64-bit)",
+        ),
+        (
+            HKEY_LOCAL_MACHINE,
+            KEY_READ | KEY_WOW64_32KEY,
+            "HKLM (32-bit)",
+        ),
+    ];
+    let mut entries: Vec<UninstallEntry> = Vec::new();
+    for (root, flags, label) in candidates {
+        let Ok(key) = RegKey::predef(root).open_subkey_with_flags(UNINSTALL_KEY, flags) else {
+            continue;
+        };
+        let location = uninstall_location(&key);
+        let matches_exe = match (&location, &exe_dir) {
+            (Some(location), Some(exe_dir)) => same_dir(exe_dir, location),
+            _ => false,
+        };
+        entries.push(UninstallEntry {
+            key: format!(r"{}\{}", label, UNINSTALL_KEY),
+            install_location: location.map(|p| p.to_string_lossy().into_owned()),
+            matches_exe,
+        });
+    }
+    let matched_key = entries
+        .iter()
+        .find(|entry| entry.matches_exe)
+        .map(|entry| entry.key.clone());
+    InstallDetection {
+        portable: matched_key.is_none(),
+        exe_dir: exe_dir.map(|dir| dir.to_string_lossy().into_owned()),
+        matched_key,
+        entries,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn detect_install() -> InstallDetection {
+    InstallDetection {
+        portable: true,
+        exe_dir: std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.to_string_lossy().into_owned())),
+        matched_key: None,
+        entries: Vec::new(),
+    }
+}
+
+/// 安装版的安装目录（优先与当前 exe 一致的那处）；未安装时为空
+#[cfg(target_os = "windows")]
+pub(crate) fn installed_location() -> Option<PathBuf> {
+    let detection = detect_install();
+    detection
+        .entries
+        .iter()
+        .find(|entry| entry.matches_exe)
+        .or_else(|| {
+            detection
+                .entries
+                .iter()
+                .find(|entry| entry.install_location.is_some())
+        })
+        .and_then(|entry| entry.install_location.as_ref())
+        .map(PathBuf::from)
+}
+
+/// Tauri 命令：检测是否为便携版
+///
+/// 只有卸载信息中的安装目录与当前 exe 所在目录一致才算安装版：已安装的同时另解压一份便携版时，
+/// 两份各自判断；卸载信息缺少安装目录时按便携版处理。
+#[tauri::command]
+pub(crate) fn is_portable() -> bool {
+    detect_install().portable
+}
+
+/// `get_app_info` 的返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppInfo {
+    version: String,
+    identifier: String,
+    userdata_dir: Option<String>,
+    install: InstallDetection,
+}
+
+/// Tauri 命令：应用信息（版本、数据目录，以及判断安装版 / 便携版的依据）
+#[tauri::command]
+pub(crate) fn get_app_info(app: AppHandle) -> AppInfo {
+    AppInfo {
+        version: app.package_info().version.to_string(),
+        identifier: app.config().identifier.clone(),
+        userdata_dir: userdata_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().into_owned()),
+        install: detect_install(),
+    }
+}
+
+/// 比较两个目录是否相同（Windows 路径不区分大小写）
+pub(crate) fn same_dir(a: &std::path::Path, b: &std::path::Path) -> bool {
+    let normalize = |p: &std::path::Path| {
+        std::fs::canonicalize(p)
+            .unwrap_or_else(|_| p.to_path_buf())
+            .to_string_lossy()
+            .trim_end_matches(['\\', '/'])
+            .to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_keeps_target_when_aborted() {
+        let dir = std::env::temp_dir().join(format!("efgh-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("export.json");
+        std::fs::write(&target, "old").unwrap();
+
+        let aborted =
+            write_file_atomic_with(&target, b"new", || Err(AppError::validation("已取消")));
+        assert!(aborted.is_err());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "old");
+        assert!(!dir.join("export.json.tmp").exists());
+
+        write_file_atomic(&target, b"new").unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uninstall_string_points_to_install_dir() {
+        assert_eq!(
+            uninstall_string_dir(r#""C:\Program Files\EndfieldGachaHelper\uninstall.exe" /S"#),
+            Some(PathBuf::from(r"C:\Program Files\EndfieldGachaHelper"))
+        );
+        assert_eq!(
+            uninstall_string_dir(r"D:\Apps\efgh\uninstall.exe"),
+            Some(PathBuf::from(r"D:\Apps\efgh"))
+        );
+        assert_eq!(uninstall_string_dir("uninstall.exe"), None);
+        assert_eq!(uninstall_string_dir("  "), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! 数据库文件被占用/隔离（杀毒软件干扰）
+//!
+//! 部分杀毒软件会在写入过程中锁定或隔离 efgacha.db-wal / -shm，导致后续写入报 I/O 错误。
+//! Rust 端写库时遇到文件访问类错误先退避重试，仍失败则返回带 `db-av-interference` 前缀的错误。
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::db::revision::{bump_revision, device_id};
+use crate::db::{current_db_file, open_db};
+use crate::error::AppError;
+
+/// 杀毒软件干扰错误的前缀（前端据此识别错误类型）
+const DB_AV_INTERFERENCE: &str = "db-av-interference";
+
+/// 文件访问类错误的重试间隔
+const DB_WRITE_RETRY_DELAYS_MS: [u64; 3] = [200, 500, 1000];
+
+/// 数据库文件异常
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DbFileIssue {
+    pub(crate) file: String,
+    /// missing / readonly / permission_mismatch
+    pub(crate) problem: String,
+}
+
+/// 数据库主文件及 WAL/SHM 附属文件
+fn db_file_set(db_file: &std::path::Path) -> [PathBuf; 3] {
+    let with_suffix = |suffix: &str| {
+        let mut name = db_file.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        db_file.with_file_name(name)
+    };
+    [
+        db_file.to_path_buf(),
+        with_suffix("-wal"),
+        with_suffix("-shm"),
+    ]
+}
+
+/// 检查数据库文件：主文件必须存在；已存在的文件都不能是只读，且权限需一致
+///
+/// `expect_sidecars` 为真时（写入过程中）WAL/SHM 缺失也视为异常。
+pub(crate) fn inspect_db_files(
+    db_file: &std::path::Path,
+    expect_sidecars: bool,
+) -> Vec<DbFileIssue> {
+    let mut issues = Vec::new();
+    let mut readonly_flags = Vec::new();
+    for (index, path) in db_file_set(db_file).iter().enumerate() {
+        let file = path.display().to_string();
+        match std::fs::metadata(path) {
+            Ok(meta) => {
+                let readonly = meta.permissions().readonly();
+                if readonly {
+                    issues.push(DbFileIssue {
+                        file: file.clone(),
+                        problem: "readonly".to_string(),
+                    });
+                }
+                readonly_flags.push((file, readonly));
+            }
+            Err(_) if index == 0 || expect_sidecars => issues.push(DbFileIssue {
+                file,
+                problem: "missing".to_string(),
+            }),
+            Err(_) => {}
+        }
+    }
+    if readonly_flags.iter().any(|(_, r)| *r) && readonly_flags.iter().any(|(_, r)| !*r) {
+        for (file, _) in readonly_flags.iter().filter(|(_, r)| !*r) {
+            issues.push(DbFileIssue {
+                file: file.clone(),
+                problem: "permission_mismatch".to_string(),
+            });
+        }
+    }
+    issues
+}
+
+/// 是否为文件被锁定/删除/拒绝访问一类的错误
+fn is_db_file_access_error(e: &rusqlite::Error) -> bool {
+    matches!(
+        e,
+        rusqlite::Error::SqliteFailure(err, _)
+            if matches!(
+                err.code,
+                rusqlite::ErrorCode::CannotOpen
+                    | rusqlite::ErrorCode::ReadOnly
+                    | rusqlite::ErrorCode::PermissionDenied
+                    | rusqlite::ErrorCode::SystemIoFailure
+            )
+    )
+}
+
+/// 根据文件现状生成杀毒软件干扰错误（指出具体文件并建议添加排除项）
+fn db_av_interference_error(
+    db_file: &std::path::Path,
+    issues: &[DbFileIssue],
+    cause: &str,
+) -> String {
+    let file = issues
+        .first()
+        .map(|issue| issue.file.clone())
+        .unwrap_or_else(|| db_file.display().to_string());
+    let dir = db_file
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    format!(
+        "{}: 数据库文件 {} 无法访问（{}）。可能被杀毒软件锁定或隔离，请将数据目录 {} 加入杀毒软件的排除项后重试",
+        DB_AV_INTERFERENCE, file, cause, dir
+    )
+}
+
+/// 带重试的数据库写操作
+///
+/// 遇到文件访问类错误时按退避间隔重新打开连接重试；重试用尽后返回 `db-av-interference` 错误。
+/// `op` 为操作描述，用于普通错误的提示文字。
+pub(crate) fn with_db_write<T>(
+    app: &AppHandle,
+    op: &str,
+    mut f: impl FnMut(&Connection) -> rusqlite::Result<T>,
+) -> Result<T, AppError> {
+    let mut attempt = 0;
+    loop {
+        let conn = open_db(app)?;
+        match f(&conn) {
+            Ok(value) => {
+                if let Err(e) = bump_revision(&conn, &device_id(app)) {
+                    eprintln!("[revision] 更新修订号失败: {}", e);
+                }
+                return Ok(value);
+            }
+            Err(e) if is_db_file_access_error(&e) => {
+                drop(conn);
+                if let Some(delay) = DB_WRITE_RETRY_DELAYS_MS.get(attempt) {
+                    eprintln!("[db] {}遇到文件访问错误，{}ms 后重试: {}", op, delay, e);
+                    std::thread::sleep(std::time::Duration::from_millis(*delay));
+                    attempt += 1;
+                    continue;
+                }
+                let db_file = current_db_file(app)?;
+                let issues = inspect_db_files(&db_file, true);
+                return Err(AppError::database(db_av_interference_error(
+                    &db_file,
+                    &issues,
+                    &e.to_string(),
+                )));
+            }
+            Err(e) => return Err(AppError::database(format!("{}失败: {}", op, e))),
+        }
+    }
+}
+
+/// Tauri 命令：检查数据库文件（主文件/WAL/SHM）是否存在只读或权限不一致
+///
+/// 启动时由前端调用一次，结果用于提示用户修复文件属性。
+#[tauri::command]
+pub(crate) fn check_database_files(app: AppHandle) -> Result<Vec<DbFileIssue>, AppError> {
+    let db_file = current_db_file(&app)?;
+    if !db_file.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(inspect_db_files(&db_file, false))
+}
+
+/// Tauri 命令：诊断前端写库失败的原因
+///
+/// 前端通过 SQL 插件写入失败时调用；若错误属于文件访问类且数据库文件确有异常，
+/// 返回 `db-av-interference` 提示文字，否则返回空。
+#[tauri::command]
+pub(crate) fn diagnose_db_write_error(
+    app: AppHandle,
+    error: String,
+) -> Result<Option<String>, AppError> {
+    let lower = error.to_lowercase();
+    let looks_like_file_error = [
+        "disk i/o error",
+        "unable to open database file",
+        "readonly database",
+        "access is denied",
+        "os error 5",
+        "os error 2",
+        "os error 32",
+    ]
+    .iter()
+    .any(|pattern| lower.contains(pattern));
+    if !looks_like_file_error {
+        return Ok(None);
+    }
+
+    let db_file = current_db_file(&app)?;
+    let issues = inspect_db_files(&db_file, true);
+    Ok(Some(db_av_interference_error(&db_file, &issues, &error)))
+}
@@ -0,0 +1,236 @@
+//! 定时自动备份
+//!
+//! 按设定的间隔（小时）在后台线程中创建本地备份（开启 WebDAV 自动上传时随后上传），
+//! 本地只保留最近若干份自动备份。间隔保存在配置中，启动时在 setup 里恢复。
+//! 设置了外部备份目录时，每份备份同时复制一份过去；外部目录不可用不影响本地备份。
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use crate::app_state;
+use crate::commands::webdav_backup::after_backup_created;
+use crate::config::{current_config, update_config};
+use crate::db::current_db_file;
+use crate::db::demo::is_demo_mode;
+use crate::db::disk_space::ensure_free_space;
+use crate::db::schema::{backup_db_snapshot, backup_dir};
+use crate::db::wal::checkpoint_before_snapshot;
+use crate::error::AppError;
+
+/// 自动备份的文件名标记
+const AUTO_BACKUP_TAG: &str = "auto";
+/// 本地保留的自动备份份数
+const AUTO_BACKUP_KEEP: usize = 10;
+
+/// 当前定时备份线程的取消标志（重新设置间隔时取消旧线程）
+#[derive(Default)]
+pub(crate) struct BackupScheduleState(Mutex<Option<Arc<AtomicBool>>>);
+
+/// 定时备份设置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupSchedule {
+    /// 备份间隔（小时），为空表示未开启
+    interval_hours: Option<u32>,
+    /// 最近一次自动备份的路径
+    last_backup: Option<String>,
+    /// 外部备份目录
+    external_dir: Option<String>,
+}
+
+/// 外部备份失败事件（随 `backup-external-failed` 事件发送）
+#[derive(Debug, Clone, Serialize)]
+struct ExternalBackupFailed {
+    error: String,
+}
+
+/// 创建一份本地备份并触发后续处理（自动上传等）
+pub(crate) fn backup_database(app: &AppHandle, tag: &str) -> Result<PathBuf, AppError> {
+    // 演示期间的临时库不是真实数据，不做备份
+    if is_demo_mode(app) {
+        return Err(AppError::validation("演示模式下不创建备份"));
+    }
+    checkpoint_before_snapshot(app, "备份");
+    let db_file = current_db_file(app)?;
+    let conn = Connection::open(&db_file)
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+    let backup = backup_db_snapshot(&conn, &db_file, tag)?;
+    if let Some(dir) = current_config(app).backup_external_dir {
+        match copy_to_external_dir(&backup, Path::new(&dir)) {
+            Ok(copied) => {
+                eprintln!("[backup] 已复制到外部目录: {}", copied.display());
+                if tag == AUTO_BACKUP_TAG {
+                    prune_auto_backups(Path::new(&dir), &db_file);
+                }
+            }
+            // 本地备份已完成，外部目录失败只提示，不影响返回结果
+            Err(error) => {
+                eprintln!("[backup] 复制到外部目录失败: {}", error);
+                let _ = app.emit("backup-external-failed", ExternalBackupFailed { error });
+            }
+        }
+    }
+    after_backup_created(app, backup.clone());
+    Ok(backup)
+}
+
+/// 把本地备份复制到外部目录（先写临时文件再改名，避免留下不完整的备份）
+fn copy_to_external_dir(backup: &Path, dir: &Path) -> Result<PathBuf, String> {
+    if !dir.is_dir() {
+        return Err(format!("外部备份目录不可用: {}", dir.display()));
+    }
+    let file_name = backup
+        .file_name()
+        .ok_or_else(|| "无效的备份文件名".to_string())?;
+    let target = dir.join(file_name);
+    let size = std::fs::metadata(backup).map(|meta| meta.len()).unwrap_or(0);
+    ensure_free_space(dir, size).map_err(|e| e.to_string())?;
+    let partial = target.with_extension("db.partial");
+    std::fs::copy(backup, &partial).map_err(|e| format!("复制备份失败: {}", e))?;
+    std::fs::rename(&partial, &target).map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("复制备份失败: {}", e)
+    })?;
+    Ok(target)
+}
+
+/// 备份目录中已有的自动备份（按文件名排序，即从旧到新）
+fn auto_backups(dir: &Path, db_file: &Path) -> Vec<PathBuf> {
+    let stem = db_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("efgacha");
+    let prefix = format!("{}-{}-", stem, AUTO_BACKUP_TAG);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+/// 删除备份目录中多余的旧自动备份（手动备份与恢复前备份不受影响）
+fn prune_auto_backups(dir: &Path, db_file: &Path) {
+    let backups = auto_backups(dir, db_file);
+    let excess = backups.len().saturating_sub(AUTO_BACKUP_KEEP);
+    for path in &backups[..excess] {
+        if let Err(e) = std::fs::remove_file(path) {
+            eprintln!("[backup] 删除旧备份 {} 失败: {}", path.display(), e);
+        }
+    }
+}
+
+/// 启动定时备份线程（取消之前的线程）
+pub(crate) fn start_backup_schedule(app: &AppHandle, interval_hours: u32) {
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = app_state(app)
+        .backup_schedule
+        .0
+        .lock()
+        .unwrap()
+        .replace(cancel.clone())
+    {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    let app = app.clone();
+    let interval = std::time::Duration::from_secs(u64::from(interval_hours) * 3600);
+    std::thread::spawn(move || loop {
+        // 分段睡眠，便于取消后尽快退出
+        let started = std::time::Instant::now();
+        while started.elapsed() < interval {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        match backup_database(&app, AUTO_BACKUP_TAG) {
+            Ok(path) => {
+                eprintln!("[backup] 已创建自动备份: {}", path.display());
+                match current_db_file(&app).and_then(|db| Ok((backup_dir(&db)?, db))) {
+                    Ok((dir, db)) => prune_auto_backups(&dir, &db),
+                    Err(e) => eprintln!("[backup] 清理旧备份失败: {}", e),
+                }
+            }
+            Err(e) => eprintln!("[backup] 自动备份失败: {}", e),
+        }
+    });
+}
+
+/// 停止定时备份线程
+fn stop_backup_schedule(app: &AppHandle) {
+    if let Some(cancel) = app_state(app).backup_schedule.0.lock().unwrap().take() {
+        cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Tauri 命令：设置定时自动备份间隔（小时，至少 1），持久化并立即按新间隔重新计时
+#[tauri::command]
+pub(crate) fn schedule_db_backup(app: AppHandle, interval_hours: u32) -> Result<(), AppError> {
+    if interval_hours < 1 {
+        return Err(AppError::validation("备份间隔至少为 1 小时"));
+    }
+    update_config(&app, |c| c.backup_interval_hours = Some(interval_hours))?;
+    start_backup_schedule(&app, interval_hours);
+    Ok(())
+}
+
+/// Tauri 命令：关闭定时自动备份
+#[tauri::command]
+pub(crate) fn cancel_db_backup_schedule(app: AppHandle) -> Result<(), AppError> {
+    update_config(&app, |c| c.backup_interval_hours = None)?;
+    stop_backup_schedule(&app);
+    Ok(())
+}
+
+/// Tauri 命令：获取定时自动备份设置
+#[tauri::command]
+pub(crate) fn get_db_backup_schedule(app: AppHandle) -> Result<BackupSchedule, AppError> {
+    let db_file = current_db_file(&app)?;
+    let last_backup = auto_backups(&backup_dir(&db_file)?, &db_file)
+        .pop()
+        .map(|path| path.to_string_lossy().into_owned());
+    let config = current_config(&app);
+    Ok(BackupSchedule {
+        interval_hours: config.backup_interval_hours,
+        last_backup,
+        external_dir: config.backup_external_dir,
+    })
+}
+
+/// Tauri 命令：设置外部备份目录（为空则取消），之后每次备份同时写入本地与外部目录
+#[tauri::command]
+pub(crate) fn set_backup_dir(app: AppHandle, path: Option<String>) -> Result<(), AppError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(dir) = path.as_deref().map(Path::new) {
+        if !dir.is_absolute() {
+            return Err(AppError::validation("外部备份目录须为绝对路径"));
+        }
+        if !dir.is_dir() {
+            return Err(AppError::validation(format!(
+                "外部备份目录不存在: {}",
+                dir.display()
+            )));
+        }
+        let local = backup_dir(&current_db_file(&app)?)?;
+        if local == dir {
+            return Err(AppError::validation("外部备份目录不能与本地备份目录相同"));
+        }
+    }
+    update_config(&app, |c| c.backup_external_dir = path)?;
+    Ok(())
+}
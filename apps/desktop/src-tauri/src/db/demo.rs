@@ -0,0 +1,115 @@
+//! 演示模式
+//!
+//! 录屏或给别人演示时不希望改到真实数据。开启后把当前数据库快照到系统临时目录，
+//! 之后前端与 Rust 端的读写都指向这份临时库（演示期间的修改照常可见）；关闭或退出时删除临时库，真实数据不受影响。
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_state;
+use crate::db::current_db_file;
+use crate::error::AppError;
+
+/// 临时库文件名前缀（后接进程 id）
+const DEMO_DB_PREFIX: &str = "efgacha-demo-";
+
+/// 演示模式使用的临时库（为空表示未开启）
+#[derive(Default)]
+pub(crate) struct DemoState(pub(crate) Mutex<Option<PathBuf>>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DemoStatus {
+    enabled: bool,
+}
+
+/// 当前演示用临时库（未开启演示模式时为 None）
+pub(crate) fn demo_db_file(app: &AppHandle) -> Option<PathBuf> {
+    app_state(app).demo.0.lock().unwrap().clone()
+}
+
+pub(crate) fn is_demo_mode(app: &AppHandle) -> bool {
+    app_state(app).demo.0.lock().unwrap().is_some()
+}
+
+/// 删除临时库及其 WAL/SHM 文件
+fn remove_demo_files(db_file: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let path = PathBuf::from(format!("{}{}", db_file.to_string_lossy(), suffix));
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("[demo] 删除临时库 {} 失败: {}", path.display(), e),
+        }
+    }
+}
+
+/// 清理上次异常退出遗留的临时库（启动时调用）
+pub(crate) fn cleanup_stale_demo_dbs() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name.starts_with(DEMO_DB_PREFIX) && name.ends_with(".db") {
+            eprintln!("[demo] 清理遗留的演示临时库: {}", name);
+            remove_demo_files(&entry.path());
+        }
+    }
+}
+
+/// 退出演示模式并删除临时库（关闭演示或程序退出时调用）
+pub(crate) fn end_demo_mode(app: &AppHandle) {
+    if let Some(db_file) = app_state(app).demo.0.lock().unwrap().take() {
+        remove_demo_files(&db_file);
+    }
+}
+
+/// Tauri 命令：查询是否处于演示模式
+#[tauri::command]
+pub(crate) fn get_demo_mode(app: AppHandle) -> DemoStatus {
+    DemoStatus {
+        enabled: is_demo_mode(&app),
+    }
+}
+
+/// Tauri 命令：开启/关闭演示模式（调用前前端需关闭数据库连接，完成后重新加载）
+///
+/// 开启时用 VACUUM INTO 把当前数据库快照到临时目录，之后所有读写都落在临时库；
+/// 关闭时切回真实数据库并删除临时库。
+#[tauri::command]
+pub(crate) fn set_demo_mode(app: AppHandle, enabled: bool) -> Result<DemoStatus, AppError> {
+    if enabled == is_demo_mode(&app) {
+        return Ok(DemoStatus { enabled });
+    }
+    if enabled {
+        let source = current_db_file(&app)?;
+        if !source.exists() {
+            return Err(AppError::validation("数据库尚未初始化"));
+        }
+        let demo_db =
+            std::env::temp_dir().join(format!("{}{}.db", DEMO_DB_PREFIX, std::process::id()));
+        remove_demo_files(&demo_db);
+        let conn = Connection::open_with_flags(
+            &source,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+        conn.execute("VACUUM INTO ?1", [demo_db.to_string_lossy()])
+            .map_err(|e| AppError::database(format!("创建演示临时库失败: {}", e)))?;
+        eprintln!("[demo] 已进入演示模式: {}", demo_db.display());
+        *app_state(&app).demo.0.lock().unwrap() = Some(demo_db);
+    } else {
+        end_demo_mode(&app);
+        eprintln!("[demo] 已退出演示模式");
+    }
+    let status = DemoStatus { enabled };
+    let _ = app.emit("demo-mode-changed", status.clone());
+    Ok(status)
+}
@@ -0,0 +1,121 @@
+//! 写入前的磁盘空间检查
+//!
+//! 备份、导出、迁移、下载更新等会写入较大的文件。剩余空间不足时写到一半失败，可能留下被截断的文件
+//! （曾出现过截断的备份在恢复时覆盖了正常数据）。写入前先查询目标卷的可用空间，
+//! 不足预计大小的 2 倍时直接返回 `disk-full` 错误；写入本身仍统一走"临时文件 + 改名"。
+
+use std::path::{Component, Path};
+
+use crate::error::AppError;
+
+/// 剩余空间不足的错误码
+pub(crate) const DISK_FULL: &str = "disk-full";
+/// 要求的剩余空间为预计写入大小的倍数
+const FREE_SPACE_HEADROOM: u64 = 2;
+
+/// 路径本身或最近的已存在的上级目录（目标文件通常还不存在）
+fn existing_dir(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|dir| dir.is_dir())
+}
+
+/// 目标卷的可用空间（字节）；查询失败时返回空
+#[cfg(target_os = "windows")]
+fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let dir = existing_dir(path)?;
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// 用于提示的卷名（Windows 上为盘符，其他情况为所在目录）
+fn volume_label(path: &Path) -> String {
+    match path.components().next() {
+        Some(Component::Prefix(prefix)) => prefix.as_os_str().to_string_lossy().into_owned(),
+        _ => existing_dir(path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned(),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.1} GB", bytes as f64 / 1024.0 / MB)
+    } else {
+        format!("{:.1} MB", bytes as f64 / MB)
+    }
+}
+
+/// 写入 `expected_bytes` 需要的最少剩余空间
+fn required_space(expected_bytes: u64) -> u64 {
+    expected_bytes.saturating_mul(FREE_SPACE_HEADROOM)
+}
+
+/// 检查目标路径所在卷能否容纳预计大小的写入（查询不到可用空间时不阻止写入）
+pub(crate) fn ensure_free_space(path: &Path, expected_bytes: u64) -> Result<(), AppError> {
+    let Some(available) = available_space(path) else {
+        return Ok(());
+    };
+    let required = required_space(expected_bytes);
+    if available >= required {
+        return Ok(());
+    }
+    eprintln!(
+        "[disk] {} 空间不足: 需要 {} bytes，可用 {} bytes",
+        path.display(),
+        required,
+        available
+    );
+    Err(AppError::conflict(
+        DISK_FULL,
+        format!(
+            "{} 剩余空间不足：需要至少 {}，当前可用 {}。请清理磁盘后重试",
+            volume_label(path),
+            format_size(required),
+            format_size(available)
+        ),
+    ))
+}
+
+/// 数据库文件连同 WAL 的大小（作为备份 / 复制的预计大小）
+pub(crate) fn db_file_size(db_file: &Path) -> u64 {
+    let size = |path: &Path| std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    size(db_file) + size(Path::new(&format!("{}-wal", db_file.to_string_lossy())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_twice_the_expected_size() {
+        assert_eq!(required_space(200 * 1024 * 1024), 400 * 1024 * 1024);
+        assert_eq!(required_space(u64::MAX), u64::MAX);
+        assert_eq!(format_size(300 * 1024 * 1024), "300.0 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn finds_existing_parent_of_missing_file() {
+        let dir = std::env::temp_dir();
+        let missing = dir.join("efgacha-missing").join("export.json");
+        assert_eq!(existing_dir(&missing), Some(dir.as_path()));
+    }
+}
@@ -0,0 +1,774 @@
+//! Rust 端数据库访问
+//!
+//! 表结构由前端（lib/db.ts）通过 SQL 插件创建，Rust 端直接打开同一个 SQLite 文件做统计查询。
+//! 角色记录（gacha_records）与武器记录（weapon_records）字段不同，查询时统一成 pull_records：
+//! - gacha_id  ← seq_id（服务端按抽取顺序分配的序号）
+//! - time      ← gacha_ts（毫秒时间戳字符串）
+//! - name      ← char_name / weapon_name
+//! - item_type ← category（character / weapon）
+//! - gacha_type：由 pool_id 前缀归类为 special / standard / beginner / weapon（可被手动纠正覆盖）
+
+pub(crate) mod access;
+pub(crate) mod backup;
+pub(crate) mod demo;
+pub(crate) mod disk_space;
+pub(crate) mod revision;
+pub(crate) mod schema;
+pub(crate) mod storage;
+pub(crate) mod wal;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app_state;
+use crate::commands::webdav_backup::after_backup_created;
+use crate::config::{userdata_dir, DB_FILE_NAME};
+use crate::db::access::inspect_db_files;
+use crate::db::demo::demo_db_file;
+use crate::db::schema::check_db_schema;
+use crate::db::storage::{begin_storage_session, check_db_integrity};
+use crate::error::AppError;
+
+/// 统一抽卡记录视图（作为 CTE 拼接在查询前）
+pub(crate) const PULL_RECORDS_CTE: &str = "
+    WITH pull_records AS (
+        SELECT record_uid, uid, pool_id, pool_name,
+               char_id AS item_id, char_name AS name, rarity, is_free,
+               gacha_ts AS time, seq_id AS gacha_id, category AS item_type,
+               COALESCE(gacha_type_override, CASE
+                   WHEN pool_id LIKE 'special%' THEN 'special'
+                   WHEN pool_id LIKE 'standard%' THEN 'standard'
+                   WHEN pool_id LIKE 'beginner%' THEN 'beginner'
+                   ELSE 'unknown'
+               END) AS gacha_type,
+               note, favorite, archived, banner_period, sync_run_id
+        FROM gacha_records
+        UNION ALL
+        SELECT record_uid, uid, pool_id, pool_name,
+               weapon_id, weapon_name, rarity, 0,
+               gacha_ts, seq_id, category,
+               COALESCE(gacha_type_override, 'weapon'),
+               note, favorite, archived, banner_period, sync_run_id
+        FROM weapon_records
+    )";
+
+/// 当前数据库文件路径（演示模式下为临时库；未调用过 `prepare_db_path` 时使用默认库）
+pub(crate) fn current_db_file(app: &AppHandle) -> Result<PathBuf, AppError> {
+    if let Some(path) = demo_db_file(app) {
+        return Ok(path);
+    }
+    if let Some(path) = app_state(app).db_path.0.lock().unwrap().clone() {
+        return Ok(path);
+    }
+    Ok(userdata_dir().map_err(AppError::io)?.join(DB_FILE_NAME))
+}
+
+/// 卡池类型（gacha_type）规范取值
+pub(crate) const GACHA_TYPES: [&str; 4] = ["special", "standard", "beginner", "weapon"];
+
+/// 最高稀有度。
+///
+/// 通用抽卡工具里的「五星」即最高稀有度，在终末地中对应 6★；
+/// 命令与字段名沿用通用命名（five_star），统计口径均以该常量为准。
+pub(crate) const TOP_RARITY: i64 = 6;
+
+/// 角色卡池最高稀有度基础概率
+pub(crate) const TOP_RARITY_BASE_RATE: f64 = 0.008;
+/// 从第几抽开始概率提升
+pub(crate) const TOP_RARITY_SOFT_PITY: u32 = 65;
+/// 概率提升后每抽增加的概率
+pub(crate) const TOP_RARITY_SOFT_PITY_STEP: f64 = 0.05;
+/// 最高稀有度保底抽数
+pub(crate) const TOP_RARITY_HARD_PITY: u32 = 80;
+
+/// 校验 gacha_type 是否为规范取值
+pub(crate) fn validate_gacha_type(gacha_type: &str) -> Result<(), AppError> {
+    if GACHA_TYPES.contains(&gacha_type) {
+        Ok(())
+    } else {
+        Err(AppError::validation(format!(
+            "未知的卡池类型: {}（可选值: {}）",
+            gacha_type,
+            GACHA_TYPES.join(", ")
+        )))
+    }
+}
+
+/// 物品类型（pull_records.item_type）的规范取值
+pub(crate) const ITEM_TYPES: [&str; 2] = ["character", "weapon"];
+
+/// 校验 item_type 是否为规范取值
+pub(crate) fn validate_item_type(item_type: &str) -> Result<(), AppError> {
+    if ITEM_TYPES.contains(&item_type) {
+        Ok(())
+    } else {
+        Err(AppError::validation(format!(
+            "未知的物品类型: {}（可选值: {}）",
+            item_type,
+            ITEM_TYPES.join(", ")
+        )))
+    }
+}
+
+/// Rust 端在记录表上追加的列（列名, 类型）
+///
+/// 均为可空列，前端按 `SELECT *` 读取时会被忽略，不影响旧逻辑。
+const RECORD_EXTRA_COLUMNS: &[(&str, &str)] = &[
+    // 入库时检测到的本地时钟偏差（毫秒），为空表示时间可信
+    ("clock_skew_ms", "INTEGER"),
+    // 手动纠正后的卡池类型，为空时按 pool_id 前缀归类
+    ("gacha_type_override", "TEXT"),
+    // 用户备注
+    ("note", "TEXT"),
+    // 是否收藏（0/1）
+    ("favorite", "INTEGER NOT NULL DEFAULT 0"),
+    // 备注/收藏/卡池纠正等手动修改的时间（毫秒），合并时按此取较新的一方
+    ("edited_at", "INTEGER"),
+    // 已归档（0/1）：超出官方接口可查询范围、无法再核对的记录，去重/修复/改类等操作默认跳过
+    ("archived", "INTEGER NOT NULL DEFAULT 0"),
+    // 按卡池排期归入的卡池期（该期开始的切换日期 YYYY-MM-DD），常驻卡池与排期之前的记录为空
+    ("banner_period", "TEXT"),
+    // 首次入库的同步批次（sync_history.id），之后的同步不会改写；导入或更早版本入库的记录为空
+    ("sync_run_id", "INTEGER"),
+];
+
+/// 检查表是否存在
+pub(crate) fn table_exists(conn: &Connection, table: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+    .map_err(|e| AppError::database(format!("读取数据库结构失败: {}", e)))
+}
+
+/// 检查表是否包含指定列
+pub(crate) fn table_has_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+) -> Result<bool, AppError> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| AppError::database(format!("读取 {} 表结构失败: {}", table, e)))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| AppError::database(format!("读取 {} 表结构失败: {}", table, e)))?;
+    for name in names {
+        if name.map_err(|e| AppError::database(e.to_string()))? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Rust 端自有的表（前端不读写）
+const RUST_TABLES_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS record_hash_chain (
+        uid TEXT NOT NULL,
+        source TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        record_uid TEXT NOT NULL,
+        hash TEXT NOT NULL,
+        PRIMARY KEY (uid, source, position)
+    );
+    CREATE TABLE IF NOT EXISTS db_revision (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        revision INTEGER NOT NULL,
+        device_id TEXT,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS sync_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        uid TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        total_ms INTEGER NOT NULL,
+        network_ms INTEGER NOT NULL,
+        db_write_ms INTEGER NOT NULL,
+        records_processed INTEGER NOT NULL,
+        records_added INTEGER NOT NULL,
+        raw_responses_kept INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS idx_sync_history_uid ON sync_history(uid, started_at);
+    CREATE TABLE IF NOT EXISTS account_nick_names (
+        uid TEXT PRIMARY KEY,
+        nick_name TEXT NOT NULL,
+        source TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS custom_endpoint_records (
+        record_uid TEXT PRIMARY KEY,
+        uid TEXT NOT NULL,
+        profile TEXT NOT NULL,
+        fetched_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS raw_response_records (
+        record_uid TEXT NOT NULL,
+        uid TEXT NOT NULL,
+        file_name TEXT NOT NULL,
+        page INTEGER NOT NULL,
+        pointer TEXT NOT NULL,
+        PRIMARY KEY (record_uid, file_name)
+    );
+";
+
+/// 补齐 Rust 端使用的表与列（与前端 ensureAccountsSchema 的做法一致，只加不删）
+///
+/// 返回 false 表示记录表尚未由前端创建，列还没有补齐，之后需要再执行一次。
+fn ensure_rust_schema(conn: &Connection) -> Result<bool, AppError> {
+    conn.execute_batch(RUST_TABLES_SQL)
+        .map_err(|e| AppError::database(format!("初始化数据库表失败: {}", e)))?;
+    // 旧版同步历史没有记录当时是否开启原始响应留档，缺省为 NULL（未知）
+    if !table_has_column(conn, "sync_history", "raw_responses_kept")? {
+        conn.execute(
+            "ALTER TABLE sync_history ADD COLUMN raw_responses_kept INTEGER",
+            [],
+        )
+        .map_err(|e| AppError::database(format!("升级 sync_history 表结构失败: {}", e)))?;
+    }
+    let mut complete = true;
+    for table in ["gacha_records", "weapon_records"] {
+        if !table_exists(conn, table)? {
+            complete = false;
+            continue;
+        }
+        for (column, ty) in RECORD_EXTRA_COLUMNS {
+            if !table_has_column(conn, table, column)? {
+                conn.execute(
+                    &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, ty),
+                    [],
+                )
+                .map_err(|e| AppError::database(format!("升级 {} 表结构失败: {}", table, e)))?;
+            }
+        }
+    }
+    Ok(complete)
+}
+
+/// 已补齐 Rust 端表结构的数据库文件（每个库只在本次运行中升级一次）
+#[derive(Default)]
+pub(crate) struct RustSchemaState(Mutex<HashSet<PathBuf>>);
+
+/// 补齐表结构并记录结果；已升级过的库直接跳过，避免每次打开连接都检查表结构
+fn upgrade_rust_schema(app: &AppHandle, db_file: &Path, conn: &Connection) -> Result<(), AppError> {
+    let state = &app_state(app).rust_schema;
+    if state.0.lock().unwrap().contains(db_file) {
+        return Ok(());
+    }
+    if ensure_rust_schema(conn)? {
+        state.0.lock().unwrap().insert(db_file.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Rust 端当前打开的数据库连接数（不含前端 SQL 插件的连接）
+static OPEN_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// `open_db` / `open_db_read_only` 返回的连接，存活期间计入 `open_connection_count`
+pub(crate) struct DbConnection(Connection);
+
+impl DbConnection {
+    fn new(conn: Connection) -> Self {
+        OPEN_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+        Self(conn)
+    }
+}
+
+impl Drop for DbConnection {
+    fn drop(&mut self) {
+        OPEN_CONNECTIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Deref for DbConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.0
+    }
+}
+
+impl DerefMut for DbConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.0
+    }
+}
+
+/// Rust 端当前打开的数据库连接数
+pub(crate) fn open_connection_count() -> usize {
+    OPEN_CONNECTIONS.load(Ordering::Relaxed)
+}
+
+/// 打开数据库（只打开已存在的库，不负责建表）
+pub(crate) fn open_db(app: &AppHandle) -> Result<DbConnection, AppError> {
+    let db_file = current_db_file(app)?;
+    if !db_file.exists() {
+        return Err(AppError::database("数据库尚未初始化"));
+    }
+
+    let conn = DbConnection::new(
+        Connection::open_with_flags(
+            &db_file,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?,
+    );
+    // 前端 SQL 插件可能同时持有写锁，稍作等待而不是立即报 busy
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| AppError::database(format!("设置数据库超时失败: {}", e)))?;
+    upgrade_rust_schema(app, &db_file, &conn)?;
+    if app_state(app).storage.0.lock().unwrap().full_sync {
+        conn.pragma_update(None, "synchronous", "FULL")
+            .map_err(|e| AppError::database(format!("设置数据库同步模式失败: {}", e)))?;
+    }
+
+    Ok(conn)
+}
+
+/// 以只读方式打开数据库（不建表、不改 pragma，用于预览等无副作用的查询）
+pub(crate) fn open_db_read_only(app: &AppHandle) -> Result<DbConnection, AppError> {
+    let db_file = current_db_file(app)?;
+    if !db_file.exists() {
+        return Err(AppError::database("数据库尚未初始化"));
+    }
+
+    let conn = DbConnection::new(
+        Connection::open_with_flags(
+            &db_file,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?,
+    );
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| AppError::database(format!("设置数据库超时失败: {}", e)))?;
+
+    Ok(conn)
+}
+
+/// 解析 gacha_ts（兼容毫秒/秒级时间戳与常见日期字符串，与前端 parseDate 保持一致）
+pub(crate) fn parse_gacha_ts(raw: &str) -> Option<DateTime<Utc>> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Ok(num) = s.parse::<i64>() {
+        // 秒级时间戳通常小于 10^11
+        let millis = if num < 10_000_000_000 { num * 1000 } else { num };
+        return Utc.timestamp_millis_opt(millis).single();
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// 抽卡记录的时间排序键：解析后的毫秒时间戳（无法解析的排最前），时间相同按 gacha_id 数值
+///
+/// 时间混有秒/毫秒时间戳与日期字符串，SQL 里的 `CAST(time AS INTEGER)` 无法正确比较，
+/// 需要按时间排序、比较先后（包括分页游标）时统一用这个键。
+pub(crate) fn pull_sort_key(time: &str, gacha_id: &str) -> (i64, i64) {
+    (
+        parse_gacha_ts(time).map_or(i64::MIN, |t| t.timestamp_millis()),
+        gacha_id.trim().parse().unwrap_or(0),
+    )
+}
+
+/// 执行抽卡记录查询，结果按 `pull_sort_key` 正序排列
+///
+/// 查询需选出名为 `time` 与 `gacha_id` 的两列；`map` 照常按列读取每行。
+pub(crate) fn query_pulls_by_time<T>(
+    stmt: &mut rusqlite::Statement<'_>,
+    params: impl rusqlite::Params,
+    mut map: impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    let mut rows = stmt
+        .query_map(params, |row| {
+            let key = pull_sort_key(
+                &row.get::<_, String>("time")?,
+                &row.get::<_, String>("gacha_id")?,
+            );
+            Ok((key, map(row)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    rows.sort_by_key(|(key, _)| *key);
+    Ok(rows.into_iter().map(|(_, row)| row).collect())
+}
+
+/// 解析本地日期 `YYYY-MM-DD`，返回当天本地零点（用于按日期筛选记录）
+pub(crate) fn parse_local_date(raw: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").ok()?;
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// 统计按日 / 月分桶时使用的时区（存储始终为 UTC）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DisplayTimezone {
+    /// 系统本地时区（随夏令时变化）
+    Local,
+    /// 用户设置的固定 UTC 偏移
+    Fixed(FixedOffset),
+}
+
+impl DisplayTimezone {
+    /// 由配置中的偏移（小时）构造；未设置或超出范围时使用系统本地时区
+    pub(crate) fn from_offset_hours(hours: Option<i32>) -> Self {
+        hours
+            .and_then(|hours| FixedOffset::east_opt(hours * 3600))
+            .map_or(Self::Local, Self::Fixed)
+    }
+
+    /// 按该时区格式化时间（如 "%Y-%m" 得到所属月份）
+    pub(crate) fn format(self, time: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            Self::Local => time.with_timezone(&Local).format(fmt).to_string(),
+            Self::Fixed(offset) => time.with_timezone(&offset).format(fmt).to_string(),
+        }
+    }
+}
+
+/// 当前使用的数据库文件（由 `prepare_db_path` 确定，Rust 端查询共用同一个库）
+#[derive(Default)]
+pub(crate) struct DbPathState(Mutex<Option<PathBuf>>);
+
+/// 校验并规范化自定义数据库文件名
+///
+/// 只允许单个文件名（不含目录），过滤 Windows 非法字符与保留设备名；
+/// 未带 `.db` 后缀时自动补全。
+fn sanitize_db_filename(raw: &str) -> Result<String, String> {
+    let name = raw.trim();
+    if name.is_empty() {
+        return Ok(DB_FILE_NAME.to_string());
+    }
+
+    if name == "." || name == ".." || name.contains("..") {
+        return Err("数据库文件名不能包含 \"..\"".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control())
+    {
+        return Err(format!("数据库文件名包含非法字符: {:?}", c));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Err("数据库文件名不能以点或空格结尾".to_string());
+    }
+
+    let file_name = if name.to_ascii_lowercase().ends_with(".db") {
+        name.to_string()
+    } else {
+        format!("{}.db", name)
+    };
+    if file_name.chars().count() > 100 {
+        return Err("数据库文件名过长（最多 100 个字符）".to_string());
+    }
+
+    // Windows 保留设备名（不区分大小写，忽略扩展名）
+    let stem = file_name.split('.').next().unwrap_or_default().to_ascii_uppercase();
+    let reserved = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && stem.as_bytes()[3].is_ascii_digit());
+    if reserved {
+        return Err(format!("数据库文件名不能使用系统保留名称: {}", stem));
+    }
+
+    Ok(file_name)
+}
+
+/// Tauri 命令：准备数据库路径
+///
+/// 在 Rust 端完成所有文件系统操作（不受前端 FS 插件 scope 限制）：
+/// 1. 在 exe 所在目录下创建 userdata/ 文件夹
+/// 2. 如果新位置没有数据库，尝试从旧版默认位置（$APPDATA/<identifier>/）复制
+/// 3. 返回完整的 sqlite: 连接字符串
+///
+/// `filename` 为空时使用默认的 `efgacha.db`；自定义文件名不触发旧版数据迁移。
+#[tauri::command]
+pub(crate) fn prepare_db_path(
+    app: AppHandle,
+    filename: Option<String>,
+) -> Result<String, AppError> {
+    // —— 定位 exe 目录并构建目标路径 ——
+    let userdata_dir = userdata_dir().map_err(AppError::io)?;
+    let db_file_name = sanitize_db_filename(filename.as_deref().unwrap_or_default())
+        .map_err(AppError::validation)?;
+    let is_default_db = db_file_name == DB_FILE_NAME;
+    let new_db = userdata_dir.join(&db_file_name);
+
+    // —— 确保 userdata 目录存在 ——
+    if !userdata_dir.exists() {
+        std::fs::create_dir_all(&userdata_dir)
+            .map_err(|e| AppError::io(format!("创建 userdata 目录失败: {}", e)))?;
+    }
+
+    // —— 可移动存储：上次会话未正常结束时，先检查数据库完整性再交给前端写入 ——
+    if begin_storage_session(&app, &userdata_dir) && new_db.exists() {
+        eprintln!("[storage] 上次会话未正常结束，检查数据库完整性");
+        check_db_integrity(&new_db).map_err(AppError::database)?;
+    }
+
+    // —— 旧版数据自动迁移 ——
+    // 旧版数据库存放在 Tauri 默认的 app_config_dir（$APPDATA/<identifier>/efgacha.db）。
+    // 仅当新位置尚无数据库时才尝试迁移，防止覆盖已有数据。
+    // 使用「复制」而非「移动」，旧文件保留作为安全备份。
+    // 旧版只有默认库，自定义文件名的库不参与迁移。
+    if is_default_db && !new_db.exists() {
+        if let Ok(old_dir) = app.path().app_config_dir() {
+            let old_db = old_dir.join(DB_FILE_NAME);
+            if old_db.exists() {
+                // 先复制到临时文件再改名：复制到一半失败时不能留下被截断的数据库
+                let partial = new_db.with_extension("db.partial");
+                let copied = std::fs::copy(&old_db, &partial)
+                    .and_then(|bytes| std::fs::rename(&partial, &new_db).map(|_| bytes));
+                if copied.is_err() {
+                    let _ = std::fs::remove_file(&partial);
+                }
+                match copied {
+                    Ok(bytes) => {
+                        eprintln!(
+                            "[db] 已从旧路径迁移数据库 ({} bytes): {:?} -> {:?}",
+                            bytes, old_db, new_db
+                        );
+                    }
+                    Err(e) => {
+                        // 迁移失败不阻塞启动，程序会在新路径创建空数据库
+                        eprintln!("[db] 数据库迁移失败: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    // —— 返回 sqlite: 连接字符串 ——
+    let db_path = new_db
+        .to_str()
+        .ok_or_else(|| AppError::io("数据库路径编码无效"))?
+        .to_string();
+
+    // —— 结构校验：在前端建表/写入之前发现旧备份导致的缺列 ——
+    if new_db.exists() {
+        if let Some(mismatch) = check_db_schema(&new_db)? {
+            eprintln!("[schema] {}", mismatch.message);
+            if let Some(backup) = &mismatch.backup_path {
+                after_backup_created(&app, PathBuf::from(backup));
+            }
+            *app_state(&app).db_path.0.lock().unwrap() = Some(new_db);
+            *app_state(&app).schema.0.lock().unwrap() = Some(mismatch.clone());
+            let _ = app.emit("schema-mismatch", &mismatch);
+            return Err(AppError::conflict("schema-mismatch", mismatch.message));
+        }
+    }
+
+    // —— 启动健康检查：主文件与 WAL/SHM 不应为只读或权限不一致 ——
+    if new_db.exists() {
+        for issue in inspect_db_files(&new_db, false) {
+            eprintln!("[db] 数据库文件异常 ({}): {}", issue.problem, issue.file);
+        }
+    }
+
+    // —— 补齐 Rust 端表结构：文件可能刚被恢复或替换，重新检查一次，之后的查询不再检查 ——
+    let existed = new_db.exists();
+    let state = app_state(&app);
+    state.rust_schema.0.lock().unwrap().remove(&new_db);
+    *state.db_path.0.lock().unwrap() = Some(new_db);
+    if existed {
+        if let Err(e) = open_db(&app) {
+            eprintln!("[db] 升级表结构失败: {}", e);
+        }
+    }
+
+    // 演示模式下前端连接临时库（重新加载页面后仍保持演示状态）
+    if let Some(demo_db) = demo_db_file(&app) {
+        return Ok(format!("sqlite:{}", demo_db.to_string_lossy()));
+    }
+    Ok(format!("sqlite:{}", db_path))
+}
+
+/// Tauri 命令：获取数据库文件路径（纯文件路径，不是 sqlite: 连接字符串）
+///
+/// 与 `prepare_db_path` 的定位逻辑一致，但不创建目录、不迁移数据，
+/// 供「数据位置」设置面板与备份界面展示使用。
+#[tauri::command]
+pub(crate) fn get_database_path(app: AppHandle) -> Result<String, AppError> {
+    let db_file = current_db_file(&app)?;
+    db_file
+        .to_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::validation("数据库路径编码无效"))
+}
+
+/// 前端 src/lib/db.ts initTables 的建表语句（测试用，修改前端建表时同步更新）
+#[cfg(test)]
+const FRONTEND_TABLES_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS accounts (
+        uid TEXT PRIMARY KEY,
+        hg_uid TEXT,
+        provider TEXT,
+        channel_name TEXT NOT NULL,
+        roles TEXT NOT NULL,
+        added_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS gacha_records (
+        record_uid TEXT PRIMARY KEY,
+        uid TEXT NOT NULL,
+        pool_id TEXT NOT NULL,
+        pool_name TEXT NOT NULL,
+        char_id TEXT NOT NULL,
+        char_name TEXT NOT NULL,
+        rarity INTEGER NOT NULL,
+        is_new INTEGER NOT NULL,
+        is_free INTEGER NOT NULL,
+        gacha_ts TEXT NOT NULL,
+        seq_id TEXT NOT NULL,
+        fetched_at INTEGER NOT NULL,
+        category TEXT NOT NULL DEFAULT 'character',
+        FOREIGN KEY (uid) REFERENCES accounts(uid)
+    );
+    CREATE TABLE IF NOT EXISTS weapon_records (
+        record_uid TEXT PRIMARY KEY,
+        uid TEXT NOT NULL,
+        pool_id TEXT NOT NULL,
+        pool_name TEXT NOT NULL,
+        weapon_id TEXT NOT NULL,
+        weapon_name TEXT NOT NULL,
+        weapon_type TEXT NOT NULL,
+        rarity INTEGER NOT NULL,
+        is_new INTEGER NOT NULL,
+        gacha_ts TEXT NOT NULL,
+        seq_id TEXT NOT NULL,
+        fetched_at INTEGER NOT NULL,
+        category TEXT NOT NULL DEFAULT 'weapon',
+        FOREIGN KEY (uid) REFERENCES accounts(uid)
+    );
+    CREATE INDEX IF NOT EXISTS idx_gacha_uid ON gacha_records(uid);
+    CREATE INDEX IF NOT EXISTS idx_gacha_ts ON gacha_records(gacha_ts);
+    CREATE INDEX IF NOT EXISTS idx_weapon_uid ON weapon_records(uid);
+    CREATE INDEX IF NOT EXISTS idx_weapon_ts ON weapon_records(gacha_ts);
+    CREATE INDEX IF NOT EXISTS idx_gacha_uid_seq ON gacha_records(uid, CAST(seq_id AS INTEGER));
+    CREATE INDEX IF NOT EXISTS idx_weapon_uid_seq ON weapon_records(uid, CAST(seq_id AS INTEGER));
+";
+
+/// 测试用内存库：前端建表后再补齐 Rust 端的表与列，与实际运行时的库结构一致
+#[cfg(test)]
+pub(crate) fn test_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(FRONTEND_TABLES_SQL).unwrap();
+    assert!(ensure_rust_schema(&conn).unwrap());
+    conn
+}
+
+/// 测试用：按列名插入一行，没有给出且没有默认值的 NOT NULL 列填空串或 0
+#[cfg(test)]
+pub(crate) fn insert_test_row(
+    conn: &Connection,
+    table: &str,
+    values: &[(&str, &dyn rusqlite::ToSql)],
+) {
+    let mut columns: Vec<String> = values
+        .iter()
+        .map(|(column, _)| column.to_string())
+        .collect();
+    let mut params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|(_, value)| *value).collect();
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .unwrap();
+    let required = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, bool>(3)? && row.get::<_, Option<String>>(4)?.is_none(),
+            ))
+        })
+        .unwrap()
+        .map(Result::unwrap)
+        .filter(|(name, _, required)| *required && !columns.contains(name))
+        .collect::<Vec<_>>();
+    for (name, ty, _) in required {
+        params.push(if ty == "INTEGER" { &0 } else { &"" });
+        columns.push(name);
+    }
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    conn.execute(
+        &format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns.join(", "),
+            placeholders
+        ),
+        params.as_slice(),
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn db_filename_defaults_and_appends_extension() {
+        assert_eq!(sanitize_db_filename("  ").unwrap(), DB_FILE_NAME);
+        assert_eq!(sanitize_db_filename("alt").unwrap(), "alt.db");
+        assert_eq!(sanitize_db_filename("Alt.DB").unwrap(), "Alt.DB");
+    }
+
+    #[test]
+    fn db_filename_rejects_paths_and_illegal_chars() {
+        assert!(sanitize_db_filename("../efgacha").is_err());
+        assert!(sanitize_db_filename("dir/efgacha").is_err());
+        assert!(sanitize_db_filename("dir\\efgacha").is_err());
+        assert!(sanitize_db_filename("efgacha?").is_err());
+        assert!(sanitize_db_filename("efgacha.").is_err());
+        assert!(sanitize_db_filename(&"a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn db_filename_rejects_reserved_device_names() {
+        assert!(sanitize_db_filename("con").is_err());
+        assert!(sanitize_db_filename("NUL.db").is_err());
+        assert!(sanitize_db_filename("com1").is_err());
+        assert!(sanitize_db_filename("lpt9.db").is_err());
+        assert_eq!(sanitize_db_filename("com10").unwrap(), "com10.db");
+    }
+
+    #[test]
+    fn rust_schema_is_incomplete_until_record_tables_exist() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(!ensure_rust_schema(&conn).unwrap());
+        conn.execute_batch(FRONTEND_TABLES_SQL).unwrap();
+        assert!(ensure_rust_schema(&conn).unwrap());
+        assert!(table_has_column(&conn, "weapon_records", "gacha_type_override").unwrap());
+    }
+
+    #[test]
+    fn pulls_sort_by_parsed_time_then_gacha_id() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE pulls (time TEXT, gacha_id TEXT);
+             INSERT INTO pulls VALUES
+                 ('1717185600000', '10'), ('2024-05-01 12:00:00', '3'),
+                 ('1717185600', '9'), ('1717185600000', '9'), ('', '1');",
+        )
+        .unwrap();
+        let mut stmt = conn.prepare("SELECT gacha_id, time FROM pulls").unwrap();
+        let ids = query_pulls_by_time(&mut stmt, [], |row| row.get::<_, String>(0)).unwrap();
+        // 秒级与毫秒级时间戳指向同一时刻，按 gacha_id 数值排序；无法解析的时间排最前
+        assert_eq!(ids, ["1", "3", "9", "9", "10"]);
+        assert!(pull_sort_key("1717185600", "9") < pull_sort_key("1717185600000", "10"));
+    }
+}
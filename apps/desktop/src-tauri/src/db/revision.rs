@@ -0,0 +1,253 @@
+//! 修订号（多设备冲突检测）
+//!
+//! 两台电脑共用同一账号时，抽卡记录会从接口收敛，但备注、收藏等手动修改不会；
+//! 用一台机器的备份覆盖另一台会丢掉较新的修改。库中维护单调递增的修订号与最后写入的设备，
+//! Rust 端每次写库都递增；恢复前比较修订号给出提示，合并时逐行按 edited_at 取较新的一方。
+
+use chrono::Utc;
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::commands::hash_chain::sha256_parts;
+use crate::config::{current_config, update_config};
+use crate::db::access::with_db_write;
+use crate::db::{current_db_file, open_db, table_exists};
+use crate::error::AppError;
+
+/// 数据库修订信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RevisionInfo {
+    revision: i64,
+    /// 最后一次写入的设备
+    device_id: Option<String>,
+    /// 最后一次写入的时间（毫秒）
+    updated_at: i64,
+}
+
+/// 备份与本地数据的修订号比较结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RevisionComparison {
+    local: RevisionInfo,
+    backup: RevisionInfo,
+    /// 直接覆盖时会丢失的本地修改次数
+    lost_changes: i64,
+    /// 需要提示用户时的说明
+    pub(crate) warning: Option<String>,
+}
+
+/// 合并备份的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MergeReport {
+    /// 本地没有、从备份补入的记录数
+    inserted: u64,
+    /// 备份中修改较新、覆盖本地备注/收藏/卡池纠正的记录数
+    updated: u64,
+}
+
+/// 本机设备标识（首次调用时生成并写入配置）
+pub(crate) fn device_id(app: &AppHandle) -> String {
+    if let Some(id) = current_config(app).device_id {
+        return id;
+    }
+    let seed = format!(
+        "{}|{}|{}",
+        Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        std::process::id(),
+        std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_default()
+    );
+    let id = sha256_parts(&[&seed])[..16].to_string();
+    match update_config(app, |c| {
+        c.device_id.get_or_insert_with(|| id.clone());
+    }) {
+        Ok(config) => config.device_id.unwrap_or(id),
+        Err(e) => {
+            eprintln!("[revision] 保存设备标识失败: {}", e);
+            id
+        }
+    }
+}
+
+/// 修订号加一并记录写入设备
+pub(crate) fn bump_revision(conn: &Connection, device_id: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO db_revision (id, revision, device_id, updated_at) VALUES (1, 1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET
+           revision = revision + 1,
+           device_id = excluded.device_id,
+           updated_at = excluded.updated_at",
+        rusqlite::params![device_id, Utc::now().timestamp_millis()],
+    )?;
+    Ok(())
+}
+
+/// 读取修订信息（旧库没有修订表时视为 0）
+pub(crate) fn read_revision(conn: &Connection) -> Result<RevisionInfo, AppError> {
+    if !table_exists(conn, "db_revision")? {
+        return Ok(RevisionInfo::default());
+    }
+    conn.query_row(
+        "SELECT revision, device_id, updated_at FROM db_revision WHERE id = 1",
+        [],
+        |row| {
+            Ok(RevisionInfo {
+                revision: row.get(0)?,
+                device_id: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+    .map(Option::unwrap_or_default)
+    .map_err(|e| AppError::database(format!("读取修订号失败: {}", e)))
+}
+
+/// 读取某个数据库文件（如备份）的修订信息
+pub(crate) fn read_revision_of(path: &std::path::Path) -> Result<RevisionInfo, AppError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| AppError::database(format!("打开数据库文件失败: {}", e)))?;
+    read_revision(&conn)
+}
+
+/// 比较备份与当前数据库的修订号
+pub(crate) fn compare_revisions(
+    app: &AppHandle,
+    backup_path: &std::path::Path,
+) -> Result<RevisionComparison, AppError> {
+    let backup = read_revision_of(backup_path)?;
+    let db_file = current_db_file(app)?;
+    let local = if db_file.exists() {
+        read_revision_of(&db_file)?
+    } else {
+        RevisionInfo::default()
+    };
+    let lost_changes = (local.revision - backup.revision).max(0);
+    let warning = (lost_changes > 0).then(|| {
+        format!(
+            "备份比本地数据旧，直接恢复会丢失本地 {} 次修改（备注、收藏等）。建议改为合并。",
+            lost_changes
+        )
+    });
+    Ok(RevisionComparison {
+        local,
+        backup,
+        lost_changes,
+        warning,
+    })
+}
+
+/// Tauri 命令：获取当前数据库的修订信息
+#[tauri::command]
+pub(crate) fn get_db_revision(app: AppHandle) -> Result<RevisionInfo, AppError> {
+    read_revision(&*open_db(&app)?)
+}
+
+/// Tauri 命令：恢复/合并前比较备份与本地数据的修订号
+#[tauri::command]
+pub(crate) fn compare_backup_revision(
+    app: AppHandle,
+    path: String,
+) -> Result<RevisionComparison, AppError> {
+    compare_revisions(&app, std::path::Path::new(&path))
+}
+
+/// Tauri 命令：把备份合并进当前数据库（不删除本地数据）
+///
+/// 本地没有的记录直接补入；两边都有的记录，备注/收藏/卡池纠正取 edited_at 较新的一方。
+#[tauri::command]
+pub(crate) fn merge_db_backup(app: AppHandle, path: String) -> Result<MergeReport, AppError> {
+    let source = PathBuf::from(&path);
+    if !source.exists() {
+        return Err(AppError::validation(format!("备份文件不存在: {}", path)));
+    }
+    with_db_write(&app, "合并备份", |conn| {
+        conn.execute("ATTACH DATABASE ?1 AS backup", [source.to_string_lossy()])?;
+        let result = (|| {
+            let tx = conn.unchecked_transaction()?;
+            let mut report = MergeReport {
+                inserted: 0,
+                updated: 0,
+            };
+            for table in ["accounts", "gacha_records", "weapon_records"] {
+                let backup_columns: Vec<String> = {
+                    let mut stmt = tx.prepare(&format!("PRAGMA backup.table_info({})", table))?;
+                    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+                    rows.collect::<rusqlite::Result<_>>()?
+                };
+                if backup_columns.is_empty() {
+                    continue;
+                }
+                let columns: Vec<&str> = backup_columns
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|column| {
+                        tx.query_row(
+                            &format!(
+                                "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+                                table
+                            ),
+                            [column],
+                            |row| row.get::<_, i64>(0),
+                        )
+                        .unwrap_or(0)
+                            > 0
+                    })
+                    .collect();
+                let column_list = columns.join(", ");
+                let inserted = tx.execute(
+                    &format!(
+                        "INSERT OR IGNORE INTO main.{t} ({c}) SELECT {c} FROM backup.{t}",
+                        t = table,
+                        c = column_list
+                    ),
+                    [],
+                )?;
+                if table != "accounts" {
+                    report.inserted += inserted as u64;
+                }
+
+                if table != "accounts" && columns.contains(&"edited_at") {
+                    report.updated += tx.execute(
+                        &format!(
+                            "UPDATE main.{t} SET
+                               note = b.note,
+                               favorite = b.favorite,
+                               gacha_type_override = b.gacha_type_override,
+                               edited_at = b.edited_at
+                             FROM backup.{t} AS b
+                             WHERE b.record_uid = main.{t}.record_uid
+                               AND b.edited_at IS NOT NULL
+                               AND b.edited_at > COALESCE(main.{t}.edited_at, 0)",
+                            t = table
+                        ),
+                        [],
+                    )? as u64;
+                }
+            }
+            // 合并后的修订号不低于备份，之后再用该备份恢复时能正确提示
+            let backup_has_revision: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM backup.sqlite_master WHERE type = 'table' AND name = 'db_revision'",
+                [],
+                |row| row.get(0),
+            )?;
+            if backup_has_revision > 0 {
+                tx.execute(
+                    "INSERT INTO main.db_revision (id, revision, device_id, updated_at)
+                     SELECT 1, revision, device_id, updated_at FROM backup.db_revision WHERE id = 1
+                     ON CONFLICT(id) DO UPDATE SET revision = MAX(revision, excluded.revision)",
+                    [],
+                )?;
+            }
+            tx.commit()?;
+            Ok(report)
+        })();
+        conn.execute("DETACH DATABASE backup", [])?;
+        result
+    })
+}
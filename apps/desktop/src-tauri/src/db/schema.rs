@@ -0,0 +1,226 @@
+//! 数据库结构校验
+//!
+//! 表结构由前端用 CREATE TABLE IF NOT EXISTS 创建，已存在的旧表不会被补列。
+//! 把旧备份恢复到新版本时，表存在但缺列会导致写入全部失败（只在 devtools 里看得到错误）。
+//! 启动时（前端建表之前）比对实际结构：可自动处理的情况直接修正，否则先备份数据库，
+//! 再发送 `schema-mismatch` 事件，由前端提示用户执行修复。
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::app_state;
+use crate::db::disk_space::{db_file_size, ensure_free_space};
+use crate::db::{current_db_file, table_exists, table_has_column};
+use crate::error::AppError;
+
+/// 前端建表语句（lib/db.ts initTables）中的列：(表名, [(列名, 补列时使用的定义)])
+const EXPECTED_SCHEMA: &[(&str, &[(&str, &str)])] = &[
+    (
+        "accounts",
+        &[
+            ("uid", "TEXT"),
+            ("channel_name", "TEXT NOT NULL DEFAULT ''"),
+            ("roles", "TEXT NOT NULL DEFAULT '[]'"),
+            ("added_at", "INTEGER NOT NULL DEFAULT 0"),
+        ],
+    ),
+    (
+        "gacha_records",
+        &[
+            ("record_uid", "TEXT"),
+            ("uid", "TEXT NOT NULL DEFAULT ''"),
+            ("pool_id", "TEXT NOT NULL DEFAULT ''"),
+            ("pool_name", "TEXT NOT NULL DEFAULT ''"),
+            ("char_id", "TEXT NOT NULL DEFAULT ''"),
+            ("char_name", "TEXT NOT NULL DEFAULT ''"),
+            ("rarity", "INTEGER NOT NULL DEFAULT 0"),
+            ("is_new", "INTEGER NOT NULL DEFAULT 0"),
+            ("is_free", "INTEGER NOT NULL DEFAULT 0"),
+            ("gacha_ts", "TEXT NOT NULL DEFAULT ''"),
+            ("seq_id", "TEXT NOT NULL DEFAULT ''"),
+            ("fetched_at", "INTEGER NOT NULL DEFAULT 0"),
+            ("category", "TEXT NOT NULL DEFAULT 'character'"),
+        ],
+    ),
+    (
+        "weapon_records",
+        &[
+            ("record_uid", "TEXT"),
+            ("uid", "TEXT NOT NULL DEFAULT ''"),
+            ("pool_id", "TEXT NOT NULL DEFAULT ''"),
+            ("pool_name", "TEXT NOT NULL DEFAULT ''"),
+            ("weapon_id", "TEXT NOT NULL DEFAULT ''"),
+            ("weapon_name", "TEXT NOT NULL DEFAULT ''"),
+            ("weapon_type", "TEXT NOT NULL DEFAULT ''"),
+            ("rarity", "INTEGER NOT NULL DEFAULT 0"),
+            ("is_new", "INTEGER NOT NULL DEFAULT 0"),
+            ("gacha_ts", "TEXT NOT NULL DEFAULT ''"),
+            ("seq_id", "TEXT NOT NULL DEFAULT ''"),
+            ("fetched_at", "INTEGER NOT NULL DEFAULT 0"),
+            ("category", "TEXT NOT NULL DEFAULT 'weapon'"),
+        ],
+    ),
+];
+
+/// 数据库结构与当前版本不一致的详情
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SchemaMismatch {
+    /// 缺失的列（表名.列名）
+    missing_columns: Vec<String>,
+    /// 修复前自动创建的备份
+    pub(crate) backup_path: Option<String>,
+    /// 面向用户的说明
+    pub(crate) message: String,
+}
+
+/// 启动时检测到、尚未修复的结构问题
+#[derive(Default)]
+pub(crate) struct SchemaState(pub(crate) Mutex<Option<SchemaMismatch>>);
+
+/// 列出已存在的表中缺失的列（整张表缺失由前端建表，不算问题）
+fn find_missing_columns(
+    conn: &Connection,
+) -> Result<Vec<(&'static str, &'static str, &'static str)>, AppError> {
+    let mut missing = Vec::new();
+    for (table, columns) in EXPECTED_SCHEMA {
+        if !table_exists(conn, table)? {
+            continue;
+        }
+        for (column, definition) in columns.iter() {
+            if !table_has_column(conn, table, column)? {
+                missing.push((*table, *column, *definition));
+            }
+        }
+    }
+    Ok(missing)
+}
+
+/// SQL 插件迁移记录中失败的条目，结构本身无误时直接标记为成功（避免反复执行迁移）
+fn stamp_failed_migrations(conn: &Connection) -> Result<(), AppError> {
+    if !table_exists(conn, "_sqlx_migrations")? {
+        return Ok(());
+    }
+    let stamped = conn
+        .execute(
+            "UPDATE _sqlx_migrations SET success = 1 WHERE success = 0",
+            [],
+        )
+        .map_err(|e| AppError::database(format!("更新迁移记录失败: {}", e)))?;
+    if stamped > 0 {
+        eprintln!("[schema] 已将 {} 条失败的迁移记录标记为完成", stamped);
+    }
+    Ok(())
+}
+
+/// 本地备份目录（数据库所在目录下的 backups）
+pub(crate) fn backup_dir(db_file: &std::path::Path) -> Result<PathBuf, AppError> {
+    Ok(db_file
+        .parent()
+        .ok_or_else(|| AppError::io("无法获取数据库所在目录"))?
+        .join("backups"))
+}
+
+/// 备份数据库（VACUUM INTO 生成一致的快照，WAL 中的数据也会包含在内）
+///
+/// 先写入 `.partial` 临时文件，完成后再改名，失败时不会留下不完整的备份。
+pub(crate) fn backup_db_snapshot(
+    conn: &Connection,
+    db_file: &std::path::Path,
+    tag: &str,
+) -> Result<PathBuf, AppError> {
+    let dir = backup_dir(db_file)?;
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::io(format!("创建备份目录失败: {}", e)))?;
+    let stem = db_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("efgacha");
+    let backup = dir.join(format!(
+        "{}-{}-{}.db",
+        stem,
+        tag,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    ensure_free_space(&dir, db_file_size(db_file))?;
+    let partial = backup.with_extension("db.partial");
+    let _ = std::fs::remove_file(&partial);
+    let result = conn
+        .execute("VACUUM INTO ?1", [partial.to_string_lossy()])
+        .map_err(|e| AppError::database(format!("备份数据库失败: {}", e)))
+        .and_then(|_| {
+            std::fs::rename(&partial, &backup)
+                .map_err(|e| AppError::io(format!("保存备份失败: {}", e)))
+        });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    Ok(backup)
+}
+
+/// 启动时校验数据库结构；返回无法自动处理的问题
+pub(crate) fn check_db_schema(
+    db_file: &std::path::Path,
+) -> Result<Option<SchemaMismatch>, AppError> {
+    let conn = Connection::open(db_file)
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+    let missing = find_missing_columns(&conn)?;
+    if missing.is_empty() {
+        stamp_failed_migrations(&conn)?;
+        return Ok(None);
+    }
+
+    let missing_columns: Vec<String> = missing
+        .iter()
+        .map(|(table, column, _)| format!("{}.{}", table, column))
+        .collect();
+    let backup_path = match backup_db_snapshot(&conn, db_file, "schema") {
+        Ok(path) => Some(path.to_string_lossy().into_owned()),
+        Err(e) => {
+            eprintln!("[schema] {}", e);
+            None
+        }
+    };
+    Ok(Some(SchemaMismatch {
+        message: format!(
+            "数据库结构与当前版本不一致（缺少 {}），可能是恢复了旧版本的备份。修复会为这些列补上默认值，已有记录不会丢失。",
+            missing_columns.join("、")
+        ),
+        missing_columns,
+        backup_path,
+    }))
+}
+
+/// Tauri 命令：获取启动时检测到的数据库结构问题（没有问题时为空）
+#[tauri::command]
+pub(crate) fn get_schema_mismatch(app: AppHandle) -> Option<SchemaMismatch> {
+    app_state(&app).schema.0.lock().unwrap().clone()
+}
+
+/// Tauri 命令：修复数据库结构（为缺失的列补默认值），完成后前端需重新加载
+#[tauri::command]
+pub(crate) fn repair_schema(app: AppHandle) -> Result<(), AppError> {
+    let db_file = current_db_file(&app)?;
+    let conn = Connection::open(&db_file)
+        .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| AppError::database(format!("修复数据库结构失败: {}", e)))?;
+    for (table, column, definition) in find_missing_columns(&tx)? {
+        tx.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .map_err(|e| AppError::database(format!("为 {}.{} 补列失败: {}", table, column, e)))?;
+        eprintln!("[schema] 已补齐列 {}.{}", table, column);
+    }
+    stamp_failed_migrations(&tx)?;
+    tx.commit()
+        .map_err(|e| AppError::database(format!("修复数据库结构失败: {}", e)))?;
+
+    *app_state(&app).schema.0.lock().unwrap() = None;
+    Ok(())
+}
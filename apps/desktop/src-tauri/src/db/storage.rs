@@ -0,0 +1,292 @@
+//! 可移动存储（U 盘运行便携版）
+//!
+//! 便携版放在 U 盘上运行时，拔出 U 盘可能损坏数据库：
+//! - 启动时在 userdata/ 下写入会话标记，正常退出时删除；标记残留说明上次非正常结束（例如 U 盘被直接拔出），
+//!   本次启动在前端写库前先做完整性检查。
+//! - Windows 上注册设备移除通知：系统询问/准备移除时先做 WAL checkpoint 并通知前端关闭数据库。
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::app_state;
+use crate::config::{current_config, update_config, userdata_dir};
+use crate::error::AppError;
+
+/// 会话标记文件名
+const SESSION_MARKER_FILE: &str = ".session";
+
+/// 存储状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StorageStatus {
+    /// 程序是否位于可移动存储
+    removable: bool,
+    /// 是否对数据库使用 synchronous=FULL
+    pub(crate) full_sync: bool,
+    /// 上次会话是否未正常结束
+    dirty_last_session: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct StorageState(pub(crate) Mutex<StorageStatus>);
+
+/// 判断路径是否位于可移动存储
+#[cfg(target_os = "windows")]
+pub(crate) fn is_on_removable_drive(path: &std::path::Path) -> bool {
+    use std::path::Component;
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+    use windows_sys::Win32::System::WindowsProgramming::DRIVE_REMOVABLE;
+
+    let Some(Component::Prefix(prefix)) = path.components().next() else {
+        return false;
+    };
+    let root: Vec<u16> = format!("{}\\", prefix.as_os_str().to_string_lossy())
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOVABLE }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn is_on_removable_drive(_path: &std::path::Path) -> bool {
+    false
+}
+
+/// 开始会话：检测存储类型并写入会话标记，返回上次会话是否未正常结束
+pub(crate) fn begin_storage_session(app: &AppHandle, userdata_dir: &std::path::Path) -> bool {
+    let removable = is_on_removable_drive(userdata_dir);
+    let marker = userdata_dir.join(SESSION_MARKER_FILE);
+    let dirty_last_session = removable && marker.exists();
+    if removable {
+        if let Err(e) = std::fs::write(&marker, std::process::id().to_string()) {
+            eprintln!("[storage] 写入会话标记失败: {}", e);
+        }
+    }
+
+    let state = &app_state(app).storage;
+    let mut status = state.0.lock().unwrap();
+    status.removable = removable;
+    status.full_sync = removable && current_config(app).removable_full_sync;
+    status.dirty_last_session = dirty_last_session;
+    dirty_last_session
+}
+
+/// 结束会话：删除会话标记（正常退出或存储即将移除且数据库已关闭时调用）
+pub(crate) fn end_storage_session() {
+    if let Ok(dir) = userdata_dir() {
+        let _ = std::fs::remove_file(dir.join(SESSION_MARKER_FILE));
+    }
+}
+
+/// 对数据库做完整性检查（上次会话未正常结束时，在前端写库前执行）
+pub(crate) fn check_db_integrity(db_file: &std::path::Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(db_file, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("打开数据库失败: {}", e))?;
+    let result: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| format!("数据库完整性检查失败: {}", e))?;
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(format!(
+            "数据库在上次运行时可能因存储设备被移除而损坏（{}），请从备份恢复后再使用",
+            result
+        ))
+    }
+}
+
+/// 监听程序所在卷的移除通知（隐藏的消息窗口 + 目录句柄通知）
+#[cfg(target_os = "windows")]
+pub(crate) mod removal_watcher {
+    use super::*;
+    use crate::db::current_db_file;
+    use crate::db::wal::checkpoint_db;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+    use tauri::Emitter;
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, HANDLE, HWND, INVALID_HANDLE_VALUE, LPARAM, LRESULT, WPARAM,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE,
+        FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        RegisterDeviceNotificationW, UnregisterDeviceNotification, DBT_DEVICEQUERYREMOVE,
+        DBT_DEVICEQUERYREMOVEFAILED, DBT_DEVICEREMOVECOMPLETE, DBT_DEVICEREMOVEPENDING,
+        DBT_DEVTYP_HANDLE, DEVICE_NOTIFY_WINDOW_HANDLE, DEV_BROADCAST_HANDLE, HDEVNOTIFY,
+        HWND_MESSAGE, MSG, WM_DEVICECHANGE, WNDCLASSW,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    /// 当前监听句柄（目录句柄, 通知句柄）；准备移除时必须关闭，否则会阻止系统弹出设备
+    static WATCH: Mutex<Option<(isize, isize)>> = Mutex::new(None);
+
+    /// 本次是否已完成移除前的准备（未准备就收到移除完成即为意外拔出）
+    static PREPARED: Mutex<bool> = Mutex::new(false);
+
+    /// 存储即将被移除：checkpoint 后通知前端关闭数据库
+    fn prepare_storage_removal(app: &AppHandle) {
+        match current_db_file(app).and_then(|db_file| checkpoint_db(&db_file)) {
+            Ok(_) => end_storage_session(),
+            Err(e) => eprintln!("[storage] {}", e),
+        }
+        let _ = app.emit("storage-removing", ());
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn watch(hwnd: HWND, dir: &std::path::Path) {
+        let path = wide(&dir.to_string_lossy());
+        unsafe {
+            let handle = CreateFileW(
+                path.as_ptr(),
+                FILE_READ_ATTRIBUTES,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                std::ptr::null_mut(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                eprintln!("[storage] 打开目录句柄失败，无法监听设备移除");
+                return;
+            }
+            let mut filter: DEV_BROADCAST_HANDLE = std::mem::zeroed();
+            filter.dbch_size = std::mem::size_of::<DEV_BROADCAST_HANDLE>() as u32;
+            filter.dbch_devicetype = DBT_DEVTYP_HANDLE;
+            filter.dbch_handle = handle;
+            let notify = RegisterDeviceNotificationW(
+                hwnd as HANDLE,
+                &filter as *const _ as *const core::ffi::c_void,
+                DEVICE_NOTIFY_WINDOW_HANDLE,
+            );
+            if notify.is_null() {
+                CloseHandle(handle);
+                eprintln!("[storage] 注册设备移除通知失败");
+                return;
+            }
+            *WATCH.lock().unwrap() = Some((handle as isize, notify as isize));
+        }
+    }
+
+    fn unwatch() {
+        if let Some((handle, notify)) = WATCH.lock().unwrap().take() {
+            unsafe {
+                UnregisterDeviceNotification(notify as HDEVNOTIFY);
+                CloseHandle(handle as HANDLE);
+            }
+        }
+    }
+
+    unsafe extern "system" fn wndproc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_DEVICECHANGE {
+            if let Some(app) = APP.get() {
+                match wparam as u32 {
+                    DBT_DEVICEQUERYREMOVE | DBT_DEVICEREMOVEPENDING => {
+                        let mut prepared = PREPARED.lock().unwrap();
+                        if !*prepared {
+                            eprintln!("[storage] 存储设备即将被移除，关闭数据库");
+                            unwatch();
+                            prepare_storage_removal(app);
+                            *prepared = true;
+                        }
+                        return 1;
+                    }
+                    DBT_DEVICEQUERYREMOVEFAILED => {
+                        // 移除被取消：恢复会话标记与监听
+                        *PREPARED.lock().unwrap() = false;
+                        if let Ok(dir) = userdata_dir() {
+                            let _ = std::fs::write(
+                                dir.join(SESSION_MARKER_FILE),
+                                std::process::id().to_string(),
+                            );
+                            watch(hwnd, &dir);
+                        }
+                        let _ = app.emit("storage-restored", ());
+                    }
+                    DBT_DEVICEREMOVECOMPLETE if !*PREPARED.lock().unwrap() => {
+                        // 意外拔出：会话标记保留在设备上，下次启动会先做完整性检查
+                        eprintln!("[storage] 存储设备被意外移除");
+                        unwatch();
+                        app_state(app).storage.0.lock().unwrap().dirty_last_session = true;
+                        let _ = app.emit("storage-removed", ());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    /// 启动监听线程（仅在程序位于可移动存储时调用）
+    pub fn start(app: AppHandle, dir: PathBuf) {
+        if APP.set(app).is_err() {
+            return;
+        }
+        std::thread::spawn(move || unsafe {
+            let class_name = wide("EfghRemovalWatcher");
+            let instance = GetModuleHandleW(std::ptr::null());
+            let mut class: WNDCLASSW = std::mem::zeroed();
+            class.lpfnWndProc = Some(wndproc);
+            class.hInstance = instance;
+            class.lpszClassName = class_name.as_ptr();
+            RegisterClassW(&class);
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                eprintln!("[storage] 创建消息窗口失败");
+                return;
+            }
+            watch(hwnd, &dir);
+
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+}
+
+/// Tauri 命令：获取存储状态（前端据此决定是否对数据库启用 synchronous=FULL）
+#[tauri::command]
+pub(crate) fn get_storage_status(app: AppHandle) -> StorageStatus {
+    app_state(&app).storage.0.lock().unwrap().clone()
+}
+
+/// Tauri 命令：设置位于可移动存储时是否使用 synchronous=FULL（重启后对前端连接生效）
+#[tauri::command]
+pub(crate) fn set_removable_full_sync(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<StorageStatus, AppError> {
+    update_config(&app, |config| config.removable_full_sync = enabled)?;
+    let state = &app_state(&app).storage;
+    let mut status = state.0.lock().unwrap();
+    status.full_sync = status.removable && enabled;
+    Ok(status.clone())
+}
@@ -0,0 +1,159 @@
+//! WAL 检查点
+//!
+//! 前端 SQL 插件以 WAL 模式写库，长时间运行时若一直有连接持有读快照，自动检查点无法截断 -wal 文件，
+//! 文件会越来越大。后台线程在数据库空闲（WAL 一段时间没有写入）且 WAL 超过阈值时执行
+//! `wal_checkpoint(TRUNCATE)`；备份与导出前也先做一次，确保主文件包含全部已提交的数据。
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+use crate::db::current_db_file;
+use crate::error::AppError;
+
+/// 后台检查 WAL 大小的间隔
+const WAL_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// WAL 超过该大小才做后台检查点
+const WAL_CHECKPOINT_THRESHOLD: u64 = 4 * 1024 * 1024;
+/// WAL 至少这么久没有写入才视为空闲
+const WAL_IDLE_AFTER: Duration = Duration::from_secs(30);
+
+/// 一次检查点的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WalCheckpoint {
+    /// 有其他连接正在读写，检查点未能完成（数据仍在 WAL 中，不影响完整性）
+    busy: bool,
+    /// WAL 中的页数
+    log_frames: i64,
+    /// 已写回主文件的页数
+    checkpointed_frames: i64,
+    wal_bytes_before: u64,
+    wal_bytes_after: u64,
+}
+
+fn wal_file(db_file: &Path) -> PathBuf {
+    PathBuf::from(format!("{}-wal", db_file.to_string_lossy()))
+}
+
+fn wal_size(db_file: &Path) -> u64 {
+    std::fs::metadata(wal_file(db_file))
+        .map(|meta| meta.len())
+        .unwrap_or(0)
+}
+
+/// 对指定数据库执行 `wal_checkpoint(TRUNCATE)`（不会创建不存在的库）
+pub(crate) fn checkpoint_db(db_file: &Path) -> Result<WalCheckpoint, AppError> {
+    let wal_bytes_before = wal_size(db_file);
+    let conn = Connection::open_with_flags(
+        db_file,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| AppError::database(format!("打开数据库失败: {}", e)))?;
+    conn.busy_timeout(Duration::from_secs(5))
+        .map_err(|e| AppError::database(format!("设置数据库超时失败: {}", e)))?;
+    let (busy, log_frames, checkpointed_frames) = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| AppError::database(format!("WAL checkpoint 失败: {}", e)))?;
+    drop(conn);
+    Ok(WalCheckpoint {
+        busy: busy != 0,
+        log_frames,
+        checkpointed_frames,
+        wal_bytes_before,
+        wal_bytes_after: wal_size(db_file),
+    })
+}
+
+/// 备份 / 导出前对当前库做一次检查点；失败只记录日志（VACUUM INTO 与查询本身仍能读到 WAL 中的数据）
+pub(crate) fn checkpoint_before_snapshot(app: &AppHandle, purpose: &str) {
+    let result = current_db_file(app)
+        .and_then(|db_file| checkpoint_db(&db_file))
+        .map(|checkpoint| checkpoint.busy);
+    match result {
+        Ok(false) => {}
+        Ok(true) => eprintln!("[wal] {}前检查点未完成：数据库正被占用", purpose),
+        Err(e) => eprintln!("[wal] {}前检查点失败: {}", purpose, e),
+    }
+}
+
+/// WAL 超过阈值且最近没有写入
+fn wal_needs_checkpoint(db_file: &Path) -> bool {
+    let Ok(meta) = std::fs::metadata(wal_file(db_file)) else {
+        return false;
+    };
+    let idle = meta
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed >= WAL_IDLE_AFTER);
+    meta.len() > WAL_CHECKPOINT_THRESHOLD && idle
+}
+
+/// 启动后台检查点线程
+pub(crate) fn start_wal_maintenance(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WAL_CHECK_INTERVAL);
+        let Ok(db_file) = current_db_file(&app) else {
+            continue;
+        };
+        if !wal_needs_checkpoint(&db_file) {
+            continue;
+        }
+        match checkpoint_db(&db_file) {
+            Ok(checkpoint) if checkpoint.busy => {
+                eprintln!("[wal] 数据库正被占用，稍后再试");
+            }
+            Ok(checkpoint) => eprintln!(
+                "[wal] 已执行检查点: {} -> {} bytes",
+                checkpoint.wal_bytes_before, checkpoint.wal_bytes_after
+            ),
+            Err(e) => eprintln!("[wal] {}", e),
+        }
+    });
+}
+
+/// Tauri 命令：手动执行 WAL 检查点
+///
+/// `db_path` 为空时使用当前数据库。
+#[tauri::command]
+pub(crate) fn checkpoint_wal(
+    app: AppHandle,
+    db_path: Option<String>,
+) -> Result<WalCheckpoint, AppError> {
+    let db_file = match db_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+    {
+        Some(path) => PathBuf::from(path),
+        None => current_db_file(&app)?,
+    };
+    if !db_file.is_file() {
+        return Err(AppError::validation(format!(
+            "数据库文件不存在: {}",
+            db_file.display()
+        )));
+    }
+    let checkpoint = checkpoint_db(&db_file)?;
+    eprintln!(
+        "[wal] 手动检查点 {}: {} -> {} bytes{}",
+        db_file.display(),
+        checkpoint.wal_bytes_before,
+        checkpoint.wal_bytes_after,
+        if checkpoint.busy {
+            "（数据库正被占用）"
+        } else {
+            ""
+        }
+    );
+    Ok(checkpoint)
+}
@@ -0,0 +1,197 @@
+//! 命令统一错误类型
+//!
+//! 前端原先只能对中文错误信息做字符串匹配，界面切换语言后就会失效。`AppError` 带稳定的 `kind`，
+//! 前端按 kind（及附带字段）判断错误类别，`message` 仅作展示用。
+//!
+//! 兼容：旧版前端把错误当字符串处理，因此默认仍序列化为纯文本信息；
+//! 前端启动时调用 `set_error_format(true)` 后才返回结构化对象。
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 是否以结构化对象返回错误（由前端启动时开启）
+static STRUCTURED_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// 错误类别及其附带字段（TypeScript 写法），与 `AppError::kind` 一一对应
+const ERROR_KINDS: &[(&str, &str)] = &[
+    ("io", ""),
+    ("database", ""),
+    ("network", "status?: number; code?: string"),
+    ("validation", ""),
+    ("rate_limited", "retryAfter?: number"),
+    ("token_expired", ""),
+    ("conflict", "code: string"),
+    ("internal", ""),
+];
+
+#[derive(Debug, Clone)]
+pub(crate) enum AppError {
+    /// 文件读写失败
+    Io { message: String },
+    /// 数据库打开、查询或写入失败
+    Database { message: String },
+    /// 网络请求失败；`status` 为 HTTP 状态码（连接失败时为空）
+    Network {
+        status: Option<u16>,
+        code: Option<String>,
+        message: String,
+    },
+    /// 参数不合法
+    Validation { message: String },
+    /// 请求过于频繁；`retry_after` 为建议等待的秒数
+    RateLimited {
+        retry_after: Option<u64>,
+        message: String,
+    },
+    /// 登录凭据已过期，需要重新授权（如自定义接口返回 401）
+    TokenExpired { message: String },
+    /// 与现有数据冲突，`code` 为稳定的错误码（如 `import-uid-mismatch`）
+    Conflict { code: String, message: String },
+    /// 其他未分类的错误
+    Internal { message: String },
+}
+
+impl AppError {
+    pub(crate) fn io(message: impl Into<String>) -> Self {
+        Self::Io {
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn database(message: impl Into<String>) -> Self {
+        Self::Database {
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn network(status: Option<u16>, message: impl Into<String>) -> Self {
+        Self::Network {
+            status,
+            code: None,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn validation(message: impl Into<String>) -> Self {
+        Self::Validation {
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn conflict(code: &str, message: impl Into<String>) -> Self {
+        Self::Conflict {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// 稳定的错误类别标识
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "io",
+            Self::Database { .. } => "database",
+            Self::Network { .. } => "network",
+            Self::Validation { .. } => "validation",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::TokenExpired { .. } => "token_expired",
+            Self::Conflict { .. } => "conflict",
+            Self::Internal { .. } => "internal",
+        }
+    }
+
+    pub(crate) fn message(&self) -> &str {
+        match self {
+            Self::Io { message }
+            | Self::Database { message }
+            | Self::Network { message, .. }
+            | Self::Validation { message }
+            | Self::RateLimited { message, .. }
+            | Self::TokenExpired { message }
+            | Self::Conflict { message, .. }
+            | Self::Internal { message } => message,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// 尚未细分类别的错误信息（序列化、PDF 生成、WebDAV 响应解析等）；
+/// 数据库与文件操作的错误在出错处用 `AppError::database` / `AppError::io` 归类，不经过这里
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::Internal { message }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::database(format!("数据库操作失败: {}", e))
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        Self::io(format!("文件操作失败: {}", e))
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !STRUCTURED_ERRORS.load(Ordering::Relaxed) {
+            return serializer.serialize_str(self.message());
+        }
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", self.message())?;
+        match self {
+            Self::Network { status, code, .. } => {
+                if let Some(status) = status {
+                    map.serialize_entry("status", status)?;
+                }
+                if let Some(code) = code {
+                    map.serialize_entry("code", code)?;
+                }
+            }
+            Self::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } => {
+                map.serialize_entry("retryAfter", retry_after)?;
+            }
+            Self::Conflict { code, .. } => {
+                map.serialize_entry("code", code)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+/// Tauri 命令：切换错误返回格式（true 为结构化对象，false 为兼容旧版的纯文本）
+#[tauri::command]
+pub(crate) fn set_error_format(structured: bool) {
+    STRUCTURED_ERRORS.store(structured, Ordering::Relaxed);
+}
+
+/// Tauri 命令（开发用）：生成错误类型的 TypeScript 声明，用于核对前端 `lib/errors.ts`
+#[tauri::command]
+pub(crate) fn get_error_kinds() -> String {
+    let variants: Vec<String> = ERROR_KINDS
+        .iter()
+        .map(|(kind, fields)| {
+            if fields.is_empty() {
+                format!("  | {{ kind: '{}'; message: string }}", kind)
+            } else {
+                format!("  | {{ kind: '{}'; message: string; {} }}", kind, fields)
+            }
+        })
+        .collect();
+    format!("export type AppError =\n{};\n", variants.join("\n"))
+}
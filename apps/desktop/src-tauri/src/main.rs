@@ -1,273 +1,119 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::Serialize;
-#[cfg(target_os = "windows")]
-use winreg::enums::*;
-#[cfg(target_os = "windows")]
-use winreg::RegKey;
-use tauri::{
-    image::Image,
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindowBuilder,
-    WindowEvent,
+mod commands;
+mod config;
+mod db;
+mod error;
+mod tray;
+mod windows;
+
+use std::sync::Mutex;
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, WindowEvent};
+
+use commands::accounts::refresh_tray_tooltip;
+use commands::banner_watch::start_banner_watch;
+use commands::clock::ClockSkewState;
+use commands::endpoints::EndpointState;
+use commands::icons::{init_icon_cache, IconCacheState};
+use commands::open_file::{open_file_from_args, OpenFileRequest, PendingOpenFile};
+use commands::raw_responses::prune_expired_raw_responses;
+use commands::tasks::TaskState;
+use config::{current_config, load_config, AppConfig, AppConfigState};
+use db::backup::{start_backup_schedule, BackupScheduleState};
+use db::demo::{cleanup_stale_demo_dbs, end_demo_mode, DemoState};
+use db::schema::SchemaState;
+use db::storage::{end_storage_session, StorageState};
+use db::wal::start_wal_maintenance;
+use db::{DbPathState, RustSchemaState};
+use tray::{
+    ensure_tray_menu_window, hide_tray_menu, load_tray_icon, show_tray_menu,
+    start_taskbar_theme_watch, taskbar_theme, update_tray_icon_for_theme, TrayMenuState,
+    TrayProgressState, TRAY_ID, TRAY_TOOLTIP,
+};
+use windows::{
+    apply_saved_zoom, on_scale_factor_changed, show_main_window, start_heartbeat_monitor,
+    watch_webview_process, FrontendHealthState, MainWebviewState, ScaleChangeState,
+    WindowRouteState,
 };
 
-/// 托盘菜单位置数据
-#[derive(Clone, Serialize)]
-struct TrayMenuPosition {
-    x: i32,
-    y: i32,
-}
-
-/// 显示主窗口
-fn show_main_window(app: &AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
-        // 确保窗口可见
-        let _ = window.show();
-        let _ = window.unminimize();
-        let _ = window.set_focus();
-    }
-}
-
-/// 确保托盘菜单窗口已创建（常驻隐藏，避免首次展示闪白）
-fn ensure_tray_menu_window(app: &AppHandle) {
-    if app.get_webview_window("tray-menu").is_some() {
-        return;
-    }
-
-    // 初次创建时隐藏窗口，让 WebView 在后台完成初始渲染
-    // 之后右键仅 reposition + show，不再每次重建窗口。
-    let _ = WebviewWindowBuilder::new(
-        app,
-        "tray-menu",
-        WebviewUrl::App("/tray-menu".into()),
-    )
-    .title("托盘菜单")
-    .inner_size(236.0, 244.0)
-    .decorations(false)
-    .resizable(false)
-    .always_on_top(true)
-    .skip_taskbar(true)
-    .transparent(true)
-    .shadow(false) // 禁用阴影以支持透明
-    .visible(false) // 关键：初始隐藏，避免首次弹出露出白底
-    .focused(false)
-    .build();
+/// 应用共享状态（由 Tauri 托管，各模块通过 `app_state` 访问）
+pub(crate) struct AppState {
+    pub(crate) config: AppConfigState,
+    pub(crate) db_path: DbPathState,
+    pub(crate) rust_schema: RustSchemaState,
+    pub(crate) demo: DemoState,
+    pub(crate) schema: SchemaState,
+    pub(crate) storage: StorageState,
+    pub(crate) clock: ClockSkewState,
+    pub(crate) endpoints: EndpointState,
+    pub(crate) icon_cache: IconCacheState,
+    pub(crate) scale_change: ScaleChangeState,
+    pub(crate) backup_schedule: BackupScheduleState,
+    pub(crate) main_webview: MainWebviewState,
+    pub(crate) window_routes: WindowRouteState,
+    pub(crate) frontend_health: FrontendHealthState,
+    pub(crate) tray_menu: TrayMenuState,
+    pub(crate) tray_progress: TrayProgressState,
+    pub(crate) tasks: TaskState,
+    pub(crate) pending_open_file: PendingOpenFile,
+    /// 进入 main 的时刻（统计启动耗时与运行时长）
+    pub(crate) started_at: std::time::Instant,
 }
 
-/// 显示托盘菜单窗口
-fn show_tray_menu(app: &AppHandle, x: i32, y: i32) {
-    // 菜单窗口尺寸
-    // 适当缩小整体体积，并确保内容不触发滚动条
-    const MENU_WIDTH: f64 = 236.0;
-    const MENU_HEIGHT: f64 = 244.0;
-    const MARGIN: f64 = 8.0;
-
-    // 确保窗口存在（常驻隐藏）
-    ensure_tray_menu_window(app);
-
-    // 获取点击位置所在的显示器信息
-    let mut screen_width: f64 = 1920.0;
-    let mut screen_height: f64 = 1080.0;
-    let mut screen_x: f64 = 0.0;
-    let mut screen_y: f64 = 0.0;
-    
-    if let Ok(monitors) = app.available_monitors() {
-        for monitor in monitors {
-            let pos = monitor.position();
-            let size = monitor.size();
-            let mon_x = pos.x as f64;
-            let mon_y = pos.y as f64;
-            let mon_w = size.width as f64;
-            let mon_h = size.height as f64;
-            
-            // 检查点击位置是否在此显示器范围内
-            if (x as f64) >= mon_x && (x as f64) < mon_x + mon_w 
-                && (y as f64) >= mon_y && (y as f64) < mon_y + mon_h {
-                screen_width = mon_w;
-                screen_height = mon_h;
-                screen_x = mon_x;
-                screen_y = mon_y;
-                break;
-            }
+impl AppState {
+    fn new(
+        config: AppConfig,
+        open_file: Option<OpenFileRequest>,
+        started_at: std::time::Instant,
+    ) -> Self {
+        Self {
+            config: AppConfigState(Mutex::new(config)),
+            db_path: DbPathState::default(),
+            rust_schema: RustSchemaState::default(),
+            demo: DemoState::default(),
+            schema: SchemaState::default(),
+            storage: StorageState::default(),
+            clock: ClockSkewState::default(),
+            endpoints: EndpointState::default(),
+            icon_cache: IconCacheState::default(),
+            scale_change: ScaleChangeState::default(),
+            backup_schedule: BackupScheduleState::default(),
+            main_webview: MainWebviewState::default(),
+            window_routes: WindowRouteState::default(),
+            frontend_health: FrontendHealthState::default(),
+            tray_menu: TrayMenuState::default(),
+            tray_progress: TrayProgressState::default(),
+            tasks: TaskState::default(),
+            pending_open_file: PendingOpenFile(Mutex::new(open_file)),
+            started_at,
         }
     }
-
-    // 计算菜单位置（默认在托盘图标上方居中）
-    let mut menu_x = (x as f64) - MENU_WIDTH / 2.0;
-    let mut menu_y = (y as f64) - MENU_HEIGHT - MARGIN;
-
-    // 确保菜单不超出屏幕右边界
-    if menu_x + MENU_WIDTH > screen_x + screen_width - MARGIN {
-        menu_x = screen_x + screen_width - MENU_WIDTH - MARGIN;
-    }
-    // 确保菜单不超出屏幕左边界
-    if menu_x < screen_x + MARGIN {
-        menu_x = screen_x + MARGIN;
-    }
-    // 如果上方空间不足，显示在托盘图标下方
-    if menu_y < screen_y + MARGIN {
-        menu_y = (y as f64) + MARGIN;
-    }
-    // 确保菜单不超出屏幕下边界
-    if menu_y + MENU_HEIGHT > screen_y + screen_height - MARGIN {
-        menu_y = screen_y + screen_height - MENU_HEIGHT - MARGIN;
-    }
-
-    // 展示/定位窗口（不重建，避免白框闪烁）
-    if let Some(window) = app.get_webview_window("tray-menu") {
-        // 右键再次点击：行为更贴近原生（可视时直接收起）
-        if window.is_visible().unwrap_or(false) {
-            let _ = window.hide();
-            return;
-        }
-
-        let _ = window.set_size(LogicalSize::new(MENU_WIDTH, MENU_HEIGHT));
-        let _ = window.set_position(LogicalPosition::new(menu_x, menu_y));
-        let _ = window.show();
-        let _ = window.set_focus();
-        let _ = window.emit("tray-menu-position", TrayMenuPosition { x, y });
-    }
-}
-
-/// 隐藏托盘菜单窗口
-fn hide_tray_menu(app: &AppHandle) {
-    if let Some(menu_window) = app.get_webview_window("tray-menu") {
-        let _ = menu_window.hide();
-    }
 }
 
-/// Tauri 命令：关闭托盘菜单
-#[tauri::command]
-fn close_tray_menu(app: AppHandle) {
-    hide_tray_menu(&app);
-}
-
-/// Tauri 命令：显示主窗口
-#[tauri::command]
-fn show_main_window_cmd(app: AppHandle) {
-    show_main_window(&app);
-}
-
-/// Tauri 命令：让主窗口跳转到指定路由（用于托盘菜单等子窗口）
-#[tauri::command]
-fn navigate_main(app: AppHandle, path: String) {
-    // 确保主窗口可见并聚焦
-    show_main_window(&app);
-    if let Some(window) = app.get_webview_window("main") {
-        // 发送导航事件到主窗口（前端在 MainLayout 中监听）
-        let _ = window.emit("efgh:navigate", serde_json::json!({ "path": path, "replace": true }));
-    }
-}
-
-/// Tauri 命令：退出应用
-#[tauri::command]
-fn quit_app(app: AppHandle) {
-    // 发送退出事件到主窗口
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.emit("tray-quit", ());
-    }
-    // 关闭托盘菜单
-    hide_tray_menu(&app);
-    // 延迟退出
-    std::thread::spawn(move || {
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        std::process::exit(0);
-    });
-}
-
-/// Tauri 命令：切换同步状态
-#[tauri::command]
-fn toggle_sync(app: AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.emit("tray-toggle-sync", ());
-    }
-}
-
-/// Tauri 命令：设置自动同步开关（避免“反向 toggle”导致状态不同步）
-#[tauri::command]
-fn set_auto_sync(app: AppHandle, enabled: bool) {
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.emit(
-            "tray-set-auto-sync",
-            serde_json::json!({ "enabled": enabled }),
-        );
-    }
+/// 读取应用共享状态
+pub(crate) fn app_state(app: &AppHandle) -> &AppState {
+    app.state::<AppState>().inner()
 }
 
-/// Tauri 命令：准备数据库路径
-///
-/// 在 Rust 端完成所有文件系统操作（不受前端 FS 插件 scope 限制）：
-/// 1. 在 exe 所在目录下创建 userdata/ 文件夹
-/// 2. 如果新位置没有数据库，尝试从旧版默认位置（$APPDATA/<identifier>/）复制
-/// 3. 返回完整的 sqlite: 连接字符串
-#[tauri::command]
-fn prepare_db_path(app: AppHandle) -> Result<String, String> {
-    // —— 定位 exe 目录并构建目标路径 ——
-    let exe_dir = std::env::current_exe()
-        .map_err(|e| format!("获取 exe 路径失败: {}", e))?
-        .parent()
-        .ok_or_else(|| "无法获取 exe 所在目录".to_string())?
-        .to_path_buf();
-
-    let userdata_dir = exe_dir.join("userdata");
-    let new_db = userdata_dir.join("efgacha.db");
-
-    // —— 确保 userdata 目录存在 ——
-    if !userdata_dir.exists() {
-        std::fs::create_dir_all(&userdata_dir)
-            .map_err(|e| format!("创建 userdata 目录失败: {}", e))?;
-    }
+fn main() {
+    let started_at = std::time::Instant::now();
 
-    // —— 旧版数据自动迁移 ——
-    // 旧版数据库存放在 Tauri 默认的 app_config_dir（$APPDATA/<identifier>/efgacha.db）。
-    // 仅当新位置尚无数据库时才尝试迁移，防止覆盖已有数据。
-    // 使用「复制」而非「移动」，旧文件保留作为安全备份。
-    if !new_db.exists() {
-        if let Ok(old_dir) = app.path().app_config_dir() {
-            let old_db = old_dir.join("efgacha.db");
-            if old_db.exists() {
-                match std::fs::copy(&old_db, &new_db) {
-                    Ok(bytes) => {
-                        eprintln!(
-                            "[db] 已从旧路径迁移数据库 ({} bytes): {:?} -> {:?}",
-                            bytes, old_db, new_db
-                        );
-                    }
-                    Err(e) => {
-                        // 迁移失败不阻塞启动，程序会在新路径创建空数据库
-                        eprintln!("[db] 数据库迁移失败: {}", e);
-                    }
-                }
-            }
-        }
+    #[cfg(target_os = "windows")]
+    if !windows::ensure_webview2() {
+        return;
     }
 
-    // —— 返回 sqlite: 连接字符串 ——
-    let db_path = new_db
-        .to_str()
-        .ok_or_else(|| "数据库路径编码无效".to_string())?;
+    let args: Vec<String> = std::env::args().collect();
 
-    Ok(format!("sqlite:{}", db_path))
-}
-
-/// Tauri 命令：检测是否为便携版（通过注册表判断）
-#[cfg(target_os = "windows")]
-#[tauri::command]
-fn is_portable() -> bool {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    hkcu.open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Uninstall\com.efgachahelper.dev")
-        .is_err()
-}
-
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-fn is_portable() -> bool {
-    true
-}
-
-fn main() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // 已在运行时再次启动：唤起主窗口，并把新进程要打开的文件转给前端
+            show_main_window(app);
+            if let Some(request) = open_file_from_args(&argv) {
+                let _ = app.emit("open-file", request);
+            }
+        }))
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
@@ -275,30 +121,193 @@ fn main() {
         .plugin(tauri_plugin_sql::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .manage(AppState::new(
+            load_config(),
+            open_file_from_args(&args),
+            started_at,
+        ))
         .invoke_handler(tauri::generate_handler![
-            close_tray_menu,
-            show_main_window_cmd,
-            navigate_main,
-            quit_app,
-            toggle_sync,
-            set_auto_sync,
-            prepare_db_path,
-            is_portable
+            tray::close_tray_menu,
+            tray::report_tray_menu_width,
+            windows::show_main_window_cmd,
+            windows::get_zoom,
+            windows::set_zoom,
+            windows::set_tray_menu_follow_zoom,
+            windows::navigate_main,
+            windows::report_route,
+            windows::heartbeat,
+            windows::get_frontend_health,
+            windows::get_startup_duration_ms,
+            windows::get_application_uptime_seconds,
+            tray::quit_app,
+            tray::toggle_sync,
+            tray::set_auto_sync,
+            tray::get_tray_menu_data,
+            tray::export_latest,
+            tray::open_data_dir,
+            tray::copy_summary,
+            tray::get_today_summary,
+            tray::set_tray_progress,
+            tray::finish_tray_progress,
+            db::prepare_db_path,
+            db::get_database_path,
+            db::demo::get_demo_mode,
+            db::demo::set_demo_mode,
+            db::access::check_database_files,
+            db::revision::get_db_revision,
+            db::revision::compare_backup_revision,
+            db::revision::merge_db_backup,
+            db::schema::get_schema_mismatch,
+            db::schema::repair_schema,
+            db::access::diagnose_db_write_error,
+            commands::stats::get_account_age_estimate,
+            commands::clock::report_server_date,
+            commands::clock::get_clock_status,
+            commands::clock::annotate_skewed_records,
+            commands::stats::count_five_stars_since_gacha_id,
+            commands::stats::get_rank_distribution_over_time,
+            commands::stats::get_five_star_rate_by_month,
+            commands::stats::get_display_timezone,
+            commands::stats::set_display_timezone,
+            commands::stats::get_four_star_names,
+            commands::stats::get_four_star_count_by_name,
+            commands::stats::get_five_star_by_item_type,
+            commands::stats::get_pull_sessions,
+            commands::stats::get_pull_matrix,
+            commands::stats::get_global_pull_stats,
+            commands::stats::find_gacha_id_gaps,
+            commands::stats::get_statistics,
+            commands::stats::get_pull_price_settings,
+            commands::stats::set_pull_price_settings,
+            commands::pity::reclassify_pull,
+            commands::pity::get_pity_status,
+            commands::pity::get_pity_over_time,
+            commands::pity::get_longest_five_star_drought,
+            commands::pity::get_four_star_pity_history,
+            commands::records::get_pull_records,
+            commands::records::get_annotated_pull_history,
+            commands::records::get_pull_history_sorted,
+            commands::records::get_pulls_before,
+            commands::records::get_pulls_after,
+            commands::records::query_records_paged,
+            commands::records::query_records_keyset,
+            commands::records::stream_pull_records,
+            commands::records::set_record_note,
+            commands::records::set_pull_time,
+            commands::records::get_record_provenance,
+            commands::records::get_records_since,
+            commands::records::toggle_favorite,
+            commands::records::batch_delete_pulls,
+            commands::records::archive_records,
+            commands::records::mark_records_archived,
+            commands::sync_history::record_sync_run,
+            commands::sync_history::get_sync_history,
+            commands::telemetry::get_telemetry_opt_in,
+            commands::telemetry::set_telemetry_opt_in,
+            commands::compare::create_compare_payload,
+            commands::compare::parse_compare_payload,
+            commands::retrospective::simulate_retrospective,
+            commands::diagnose::network_diagnose,
+            commands::text_export::get_text_export_settings,
+            commands::text_export::set_text_export_settings,
+            commands::text_export::refresh_text_export,
+            commands::text_export::test_text_export,
+            commands::export::export_uigf,
+            commands::export::export_for_ww_compat,
+            commands::export::export_with_dialog,
+            commands::pdf_report::generate_pull_history_pdf,
+            commands::tasks::cancel_task,
+            commands::import::check_import_uid,
+            commands::import::merge_duplicate_records,
+            commands::import::import_replace_records,
+            commands::import_upgrade::upgrade_import_json,
+            commands::import_validate::validate_import_file,
+            commands::items::resolve_item,
+            commands::items::resolve_item_ids,
+            commands::items::get_unresolved_items,
+            commands::items::resolve_unmatched_items,
+            commands::locale::get_localized_string,
+            commands::icons::get_item_icon,
+            commands::icons::prefetch_icons,
+            commands::icons::get_icon_cdn,
+            commands::icons::set_icon_cdn,
+            commands::export::record_export_entry,
+            commands::export::get_export_history,
+            commands::export::delete_export_entry,
+            commands::export::rerun_export,
+            commands::export::reveal_export,
+            commands::hash_chain::seal_hash_chain,
+            commands::hash_chain::verify_chain,
+            commands::accounts::list_accounts,
+            commands::accounts::get_active_account,
+            commands::accounts::set_active_account,
+            commands::accounts::apply_imported_account_profiles,
+            commands::accounts::set_account_nick_name,
+            commands::accounts::extract_uid_from_url,
+            commands::accounts::delete_uid,
+            commands::accounts::split_uid_by_date,
+            commands::changelog::get_changelog,
+            commands::changelog::acknowledge_changelog,
+            commands::updater::get_update_status,
+            commands::updater::report_update_failure,
+            commands::updater::dismiss_update_notice,
+            commands::updater::reset_update_failures,
+            commands::updater::open_release_page,
+            commands::updater::check_update_disk_space,
+            commands::banner_watch::get_banner_watch_status,
+            commands::banner_watch::set_banner_watch_settings,
+            commands::banner_watch::reclassify_records,
+            commands::webdav::webdav_list,
+            commands::webdav::webdav_mkdir,
+            commands::webdav::webdav_upload,
+            commands::webdav_backup::get_webdav_settings,
+            commands::webdav_backup::set_webdav,
+            commands::webdav_backup::set_webdav_backup_options,
+            commands::webdav_backup::test_webdav,
+            commands::webdav_backup::create_db_backup,
+            commands::webdav_backup::list_remote_backups,
+            commands::webdav_backup::download_remote_backup,
+            commands::webdav_backup::restore_db_backup,
+            db::backup::schedule_db_backup,
+            db::backup::cancel_db_backup_schedule,
+            db::backup::get_db_backup_schedule,
+            db::backup::set_backup_dir,
+            commands::endpoints::get_sync_endpoints,
+            commands::endpoints::set_sync_endpoints,
+            commands::endpoints::get_active_endpoint,
+            commands::endpoints::get_endpoint_statuses,
+            commands::endpoints::report_endpoint_result,
+            commands::custom_endpoint::get_custom_endpoint_config,
+            commands::custom_endpoint::set_custom_endpoint_enabled,
+            commands::custom_endpoint::test_custom_endpoint,
+            commands::custom_endpoint::save_custom_endpoint_profile,
+            commands::custom_endpoint::delete_custom_endpoint_profile,
+            commands::custom_endpoint::export_custom_endpoint_profile,
+            commands::custom_endpoint::parse_custom_endpoint_profile,
+            commands::custom_endpoint::fetch_custom_endpoint_records,
+            commands::raw_responses::get_raw_response_settings,
+            commands::raw_responses::set_raw_response_settings,
+            commands::raw_responses::save_raw_response,
+            db::storage::get_storage_status,
+            commands::resource_usage::get_resource_usage,
+            db::storage::set_removable_full_sync,
+            db::wal::checkpoint_wal,
+            commands::open_file::take_pending_open_file,
+            config::is_portable,
+            config::get_app_info,
+            commands::install_mode::migrate_install_mode,
+            error::set_error_format,
+            error::get_error_kinds
         ])
         .setup(|app| {
-            // 加载托盘图标
-            let icon = Image::from_path("icons/icon.png")
-                .or_else(|_| Image::from_path("icons/32x32.png"))
-                .unwrap_or_else(|_| {
-                    // 如果找不到图标文件，使用默认图标
-                    app.default_window_icon().cloned().unwrap()
-                });
+            // 加载托盘图标（按任务栏主题选择深色/浅色变体）
+            let icon = load_tray_icon(app.handle(), taskbar_theme(app.handle()));
 
             // 创建托盘图标（不使用原生菜单）
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(icon)
-                .tooltip("终末地抽卡助手")
-                .menu_on_left_click(false)
+                .tooltip(TRAY_TOOLTIP)
+                .show_menu_on_left_click(false)
                 .on_tray_icon_event(|tray, event| {
                     match event {
                         TrayIconEvent::Click {
@@ -326,12 +335,57 @@ fn main() {
                 })
                 .build(app)?;
 
+            // 主窗口 WebView 崩溃时自动恢复
+            if let Some(window) = app.get_webview_window("main") {
+                watch_webview_process(&window);
+            }
+            start_heartbeat_monitor(app.handle());
+
+            // 任务栏深浅色切换时实时更换托盘图标
+            start_taskbar_theme_watch(app.handle());
+
             // 预创建托盘菜单窗口（隐藏），避免首次弹出闪白
             let app_handle = app.handle().clone();
             ensure_tray_menu_window(&app_handle);
 
+            // 托盘提示显示当前激活账号
+            refresh_tray_tooltip(&app_handle);
+
+            // 清理上次异常退出遗留的演示临时库
+            cleanup_stale_demo_dbs();
+
+            // 图标缓存目录开放给 asset 协议
+            init_icon_cache(&app_handle);
+
+            // 恢复定时自动备份
+            if let Some(hours) = current_config(&app_handle).backup_interval_hours {
+                start_backup_schedule(&app_handle, hours.max(1));
+            }
+
+            // 清理过期的接口原始响应留档
+            prune_expired_raw_responses(&app_handle);
+
+            // 空闲时截断膨胀的 WAL 文件
+            start_wal_maintenance(&app_handle);
+
+            // 按卡池排期提醒换池
+            start_banner_watch(&app_handle);
+
+            // 程序位于可移动存储时监听设备移除
+            #[cfg(target_os = "windows")]
+            if let Ok(dir) = config::userdata_dir() {
+                if db::storage::is_on_removable_drive(&dir) {
+                    db::storage::removal_watcher::start(app_handle.clone(), dir);
+                }
+            }
+
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Finished {
+                apply_saved_zoom(webview);
+            }
+        })
         .on_window_event(|window, event| {
             match event {
                 WindowEvent::CloseRequested { api, .. } => {
@@ -343,16 +397,30 @@ fn main() {
                     }
                     // 托盘菜单窗口：允许正常关闭
                 }
-                WindowEvent::Focused(focused) => {
-                    // 托盘菜单窗口失去焦点时自动隐藏（不要 close，避免下次重建闪白）
-                    if !focused && window.label() == "tray-menu" {
-                        let _ = window.hide();
-                    }
+                WindowEvent::ThemeChanged(_) if window.label() == "main" => {
+                    // 系统深浅色切换时同步更换托盘图标，避免图标与任务栏背景融为一体；
+                    // 以任务栏主题为准，它可能与应用主题不同
+                    update_tray_icon_for_theme(
+                        window.app_handle(),
+                        taskbar_theme(window.app_handle()),
+                    );
+                }
+                WindowEvent::ScaleFactorChanged { .. } => {
+                    on_scale_factor_changed(window);
+                }
+                // 托盘菜单窗口失去焦点时自动隐藏（不要 close，避免下次重建闪白）
+                WindowEvent::Focused(false) if window.label() == "tray-menu" => {
+                    let _ = window.hide();
                 }
                 _ => {}
             }
         })
-        .run(tauri::generate_context!())
-        .expect("启动 Tauri 应用失败");
+        .build(tauri::generate_context!())
+        .expect("启动 Tauri 应用失败")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                end_demo_mode(app);
+                end_storage_session();
+            }
+        });
 }
-
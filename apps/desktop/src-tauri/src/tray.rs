@@ -0,0 +1,955 @@
+//! 托盘图标与托盘菜单窗口
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::{
+    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, Theme, WebviewUrl,
+    WebviewWindowBuilder,
+};
+
+use crate::app_state;
+use crate::commands::accounts::{query_accounts, AccountSummary};
+use crate::commands::banner_watch::server_day_start;
+use crate::commands::clock::trusted_now;
+use crate::commands::pdf_report::gacha_type_label;
+use crate::commands::pity::{compute_pity_status, PityWalker};
+use crate::config::{current_config, userdata_dir};
+use crate::db::storage::end_storage_session;
+use crate::db::{
+    open_db, parse_gacha_ts, query_pulls_by_time, GACHA_TYPES, PULL_RECORDS_CTE, TOP_RARITY,
+};
+use crate::error::AppError;
+use crate::windows::{last_window_route, tray_menu_zoom};
+
+/// 托盘菜单位置数据
+#[derive(Clone, Serialize)]
+struct TrayMenuPosition {
+    x: i32,
+    y: i32,
+}
+
+/// 确保托盘菜单窗口已创建（常驻隐藏，避免首次展示闪白）
+pub(crate) fn ensure_tray_menu_window(app: &AppHandle) {
+    if app.get_webview_window("tray-menu").is_some() {
+        return;
+    }
+
+    // 初次创建时隐藏窗口，让 WebView 在后台完成初始渲染
+    // 之后右键仅 reposition + show，不再每次重建窗口。
+    let route = last_window_route(app, "tray-menu", "/tray-menu");
+    let _ = WebviewWindowBuilder::new(app, "tray-menu", WebviewUrl::App(route.into()))
+        .title("托盘菜单")
+        .inner_size(TRAY_MENU_WIDTH, TRAY_MENU_HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .transparent(true)
+        .shadow(false) // 禁用阴影以支持透明
+        .visible(false) // 关键：初始隐藏，避免首次弹出露出白底
+        .focused(false)
+        .build();
+}
+
+/// 托盘菜单窗口尺寸（逻辑像素）
+///
+/// 适当缩小整体体积，并确保内容不触发滚动条
+const TRAY_MENU_WIDTH: f64 = 236.0;
+const TRAY_MENU_HEIGHT: f64 = 284.0;
+/// 前端上报内容宽度时允许的最大宽度（避免超长邮箱等把菜单撑得过宽）
+const TRAY_MENU_MAX_WIDTH: f64 = 360.0;
+
+/// 托盘菜单的运行时状态
+#[derive(Default)]
+pub(crate) struct TrayMenuState {
+    /// 前端渲染后上报的内容宽度（CSS 像素，未乘界面缩放）；切换语言后文字长度不同
+    content_width: Mutex<Option<f64>>,
+    /// 最近一次右键点击托盘的位置（物理像素），菜单显示期间宽度变化时据此重新定位
+    last_click: Mutex<Option<(i32, i32)>>,
+}
+
+/// 菜单宽度：按上报的内容宽度，不小于默认宽度、不超过上限
+fn tray_menu_width(content_width: Option<f64>) -> f64 {
+    content_width
+        .map(|w| w.clamp(TRAY_MENU_WIDTH, TRAY_MENU_MAX_WIDTH))
+        .unwrap_or(TRAY_MENU_WIDTH)
+}
+
+/// 托盘菜单当前应使用的尺寸（跟随界面缩放时同比放大）
+pub(crate) fn tray_menu_size(app: &AppHandle) -> (f64, f64) {
+    let zoom = tray_menu_zoom(app);
+    let width = tray_menu_width(*app_state(app).tray_menu.content_width.lock().unwrap());
+    (width * zoom, TRAY_MENU_HEIGHT * zoom)
+}
+
+/// 托盘菜单圆角半径（与前端 `rounded-lg` 保持一致）
+#[cfg(target_os = "windows")]
+const TRAY_MENU_CORNER_RADIUS: f64 = 8.0;
+
+/// 将托盘菜单窗口裁剪为圆角矩形
+///
+/// 透明窗口的四角虽然看不见，但默认仍会命中点击。
+/// Windows 下通过 SetWindowRgn 设置窗口区域，圆角外的区域不再属于窗口，点击直接穿透到下层。
+#[cfg(target_os = "windows")]
+pub(crate) fn apply_tray_menu_region(window: &tauri::WebviewWindow, width: f64, height: f64) {
+    use windows_sys::Win32::Graphics::Gdi::{CreateRoundRectRgn, SetWindowRgn};
+
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    // 窗口区域使用物理像素，需按当前缩放比例换算
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let w = (width * scale).round() as i32;
+    let h = (height * scale).round() as i32;
+    let d = (TRAY_MENU_CORNER_RADIUS * 2.0 * scale).round() as i32;
+
+    unsafe {
+        // 右下角坐标不包含在区域内，因此 +1
+        let region = CreateRoundRectRgn(0, 0, w + 1, h + 1, d, d);
+        if region.is_null() {
+            return;
+        }
+        // 设置成功后区域归系统所有，无需手动释放
+        SetWindowRgn(hwnd.0 as _, region, 1);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn apply_tray_menu_region(_window: &tauri::WebviewWindow, _width: f64, _height: f64) {
+    // 其他平台由前端把圆角外的点击视为「点击外部」处理
+}
+
+/// 计算托盘菜单左上角位置（均为物理像素）
+///
+/// `screen` 为点击处所在显示器的 (x, y, 宽, 高)。默认在点击处上方居中，
+/// 超出显示器时贴边，上方空间不足时改为显示在下方。
+fn tray_menu_origin(
+    (x, y): (f64, f64),
+    (screen_x, screen_y, screen_width, screen_height): (f64, f64, f64, f64),
+    (phys_width, phys_height): (f64, f64),
+    margin: f64,
+) -> (f64, f64) {
+    // 计算菜单位置（默认在托盘图标上方居中）
+    let mut menu_x = x - phys_width / 2.0;
+    let mut menu_y = y - phys_height - margin;
+
+    // 确保菜单不超出屏幕右边界
+    if menu_x + phys_width > screen_x + screen_width - margin {
+        menu_x = screen_x + screen_width - phys_width - margin;
+    }
+    // 确保菜单不超出屏幕左边界
+    if menu_x < screen_x + margin {
+        menu_x = screen_x + margin;
+    }
+    // 如果上方空间不足，显示在托盘图标下方
+    if menu_y < screen_y + margin {
+        menu_y = y + margin;
+    }
+    // 确保菜单不超出屏幕下边界
+    if menu_y + phys_height > screen_y + screen_height - margin {
+        menu_y = screen_y + screen_height - phys_height - margin;
+    }
+
+    (menu_x, menu_y)
+}
+
+/// 按点击位置与当前菜单尺寸设置托盘菜单窗口的位置、尺寸与裁剪区域
+///
+/// 托盘事件给出的是物理像素坐标，定位时按点击处显示器的缩放比例换算菜单尺寸，
+/// 系统 DPI 在运行中变化后下次弹出也能正确贴边。
+fn place_tray_menu(app: &AppHandle, window: &tauri::WebviewWindow, x: i32, y: i32) {
+    const MARGIN: f64 = 8.0;
+    let (menu_width, menu_height) = tray_menu_size(app);
+
+    // 获取点击位置所在的显示器信息
+    let mut screen_width: f64 = 1920.0;
+    let mut screen_height: f64 = 1080.0;
+    let mut screen_x: f64 = 0.0;
+    let mut screen_y: f64 = 0.0;
+    let mut scale: f64 = 1.0;
+
+    if let Ok(monitors) = app.available_monitors() {
+        for monitor in monitors {
+            let pos = monitor.position();
+            let size = monitor.size();
+            let mon_x = pos.x as f64;
+            let mon_y = pos.y as f64;
+            let mon_w = size.width as f64;
+            let mon_h = size.height as f64;
+            
+            // 检查点击位置是否在此显示器范围内
+            if (x as f64) >= mon_x && (x as f64) < mon_x + mon_w 
+                && (y as f64) >= mon_y && (y as f64) < mon_y + mon_h {
+                screen_width = mon_w;
+                screen_height = mon_h;
+                screen_x = mon_x;
+                screen_y = mon_y;
+                scale = monitor.scale_factor();
+                break;
+            }
+        }
+    }
+
+    // 以下均为物理像素
+    let (menu_x, menu_y) = tray_menu_origin(
+        (x as f64, y as f64),
+        (screen_x, screen_y, screen_width, screen_height),
+        (menu_width * scale, menu_height * scale),
+        MARGIN * scale,
+    );
+
+    // 先移动到目标显示器，再按该显示器的缩放设置尺寸与裁剪区域
+    let _ = window.set_position(PhysicalPosition::new(menu_x, menu_y));
+    let _ = window.set_size(LogicalSize::new(menu_width, menu_height));
+    apply_tray_menu_region(window, menu_width, menu_height);
+}
+
+/// 显示托盘菜单窗口
+pub(crate) fn show_tray_menu(app: &AppHandle, x: i32, y: i32) {
+    // 确保窗口存在（常驻隐藏）
+    ensure_tray_menu_window(app);
+
+    // 展示/定位窗口（不重建，避免白框闪烁）
+    if let Some(window) = app.get_webview_window("tray-menu") {
+        // 右键再次点击：行为更贴近原生（可视时直接收起）
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+            return;
+        }
+
+        *app_state(app).tray_menu.last_click.lock().unwrap() = Some((x, y));
+        place_tray_menu(app, &window, x, y);
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit("tray-menu-position", TrayMenuPosition { x, y });
+    }
+}
+
+/// Tauri 命令：托盘菜单页面渲染后上报内容所需宽度（CSS 像素）
+///
+/// 切换语言后菜单文字长度不同，固定宽度会溢出。菜单正在显示时按新宽度重新定位，保证不超出屏幕。
+#[tauri::command]
+pub(crate) fn report_tray_menu_width(app: AppHandle, width: f64) {
+    if !width.is_finite() || width <= 0.0 {
+        return;
+    }
+    let state = &app_state(&app).tray_menu;
+    let previous = state.content_width.lock().unwrap().replace(width);
+    if tray_menu_width(previous) == tray_menu_width(Some(width)) {
+        return;
+    }
+    let Some(window) = app.get_webview_window("tray-menu") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        if let Some((x, y)) = *state.last_click.lock().unwrap() {
+            place_tray_menu(&app, &window, x, y);
+        }
+    }
+}
+
+/// 隐藏托盘菜单窗口
+pub(crate) fn hide_tray_menu(app: &AppHandle) {
+    if let Some(menu_window) = app.get_webview_window("tray-menu") {
+        let _ = menu_window.hide();
+    }
+}
+
+/// 浅色托盘图标（浅底深色图形），用于深色任务栏
+const TRAY_ICON_LIGHT: &[u8] = include_bytes!("../icons/tray-light.png");
+/// 深色托盘图标（深底浅色图形），用于浅色任务栏
+const TRAY_ICON_DARK: &[u8] = include_bytes!("../icons/tray-dark.png");
+
+/// 任务栏主题轮询间隔（只切换任务栏深浅色时窗口收不到 ThemeChanged）
+const TASKBAR_THEME_POLL_MS: u64 = 2000;
+
+/// 任务栏（系统）主题所在的注册表键
+#[cfg(target_os = "windows")]
+const PERSONALIZE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+
+/// 读取任务栏主题
+///
+/// Windows 上任务栏跟随 `SystemUsesLightTheme`（与应用主题 `AppsUseLightTheme` 可以不同）；
+/// 值缺失的旧系统任务栏固定为深色。其他平台按主窗口主题处理。
+#[cfg(target_os = "windows")]
+pub(crate) fn taskbar_theme(_app: &AppHandle) -> Theme {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    match RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(PERSONALIZE_KEY)
+        .and_then(|key| key.get_value::<u32, _>("SystemUsesLightTheme"))
+    {
+        Ok(0) | Err(_) => Theme::Dark,
+        Ok(_) => Theme::Light,
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn taskbar_theme(app: &AppHandle) -> Theme {
+    app.get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .unwrap_or(Theme::Dark)
+}
+
+/// 加载托盘图标
+///
+/// 深色任务栏使用浅色图标，浅色任务栏使用深色图标；两套图标都内置在程序中。
+pub(crate) fn load_tray_icon(app: &AppHandle, theme: Theme) -> Image<'static> {
+    let bytes = match theme {
+        Theme::Light => TRAY_ICON_DARK,
+        _ => TRAY_ICON_LIGHT,
+    };
+    Image::from_bytes(bytes)
+        .map(|icon| icon.to_owned())
+        .unwrap_or_else(|e| {
+            eprintln!("[tray] 解码内置托盘图标失败: {}", e);
+            app.default_window_icon().cloned().unwrap().to_owned()
+        })
+}
+
+/// 按任务栏主题切换托盘图标
+pub(crate) fn update_tray_icon_for_theme(app: &AppHandle, theme: Theme) {
+    // 同步进度显示期间保持进度图标，结束后会按当前主题恢复
+    if tray_progress_active(app) {
+        return;
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_icon(Some(load_tray_icon(app, theme))) {
+            eprintln!("[tray] 切换托盘图标失败: {}", e);
+        }
+    }
+}
+
+/// 后台轮询任务栏主题，变化时实时更换托盘图标
+pub(crate) fn start_taskbar_theme_watch(app: &AppHandle) {
+    let app = app.clone();
+    let mut last = taskbar_theme(&app);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_millis(TASKBAR_THEME_POLL_MS));
+        let theme = taskbar_theme(&app);
+        if theme != last {
+            last = theme;
+            update_tray_icon_for_theme(&app, theme);
+        }
+    });
+}
+
+/// Tauri 命令：关闭托盘菜单
+#[tauri::command]
+pub(crate) fn close_tray_menu(app: AppHandle) {
+    hide_tray_menu(&app);
+}
+
+/// Tauri 命令：退出应用
+#[tauri::command]
+pub(crate) fn quit_app(app: AppHandle) {
+    // 发送退出事件到主窗口
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("tray-quit", ());
+    }
+    // 关闭托盘菜单
+    hide_tray_menu(&app);
+    end_storage_session();
+    // 延迟退出
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        std::process::exit(0);
+    });
+}
+
+/// Tauri 命令：切换同步状态
+#[tauri::command]
+pub(crate) fn toggle_sync(app: AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("tray-toggle-sync", ());
+    }
+}
+
+/// Tauri 命令：设置自动同步开关（避免“反向 toggle”导致状态不同步）
+#[tauri::command]
+pub(crate) fn set_auto_sync(app: AppHandle, enabled: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit(
+            "tray-set-auto-sync",
+            serde_json::json!({ "enabled": enabled }),
+        );
+    }
+}
+
+// ============== 托盘快捷操作 ==============
+//
+// 快速导出、打开数据目录、复制摘要都直接在后端执行，主窗口隐藏（或尚未加载）时同样可用。
+
+/// 托盘菜单中的一个快捷操作（文字由前端按 id 翻译）
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrayMenuAction {
+    /// export_latest / open_data_dir / copy_summary，即对应的命令名
+    id: &'static str,
+    enabled: bool,
+}
+
+/// 托盘菜单的动态数据
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TrayMenuData {
+    active_uid: Option<String>,
+    /// 本地账号（带激活标记），托盘中直接切换当前账号
+    accounts: Vec<AccountSummary>,
+    actions: Vec<TrayMenuAction>,
+}
+
+/// Tauri 命令：托盘菜单的账号列表与快捷操作（需要账号的操作在未选择账号时禁用）
+#[tauri::command]
+pub(crate) fn get_tray_menu_data(app: AppHandle) -> TrayMenuData {
+    let active_uid = current_config(&app).active_account;
+    let has_account = active_uid.is_some();
+    let accounts = query_accounts(&app).unwrap_or_else(|e| {
+        eprintln!("[tray] 读取账号列表失败: {}", e);
+        Vec::new()
+    });
+    TrayMenuData {
+        active_uid,
+        accounts,
+        actions: vec![
+            TrayMenuAction {
+                id: "export_latest",
+                enabled: has_account,
+            },
+            TrayMenuAction {
+                id: "open_data_dir",
+                enabled: true,
+            },
+            TrayMenuAction {
+                id: "copy_summary",
+                enabled: has_account,
+            },
+        ],
+    }
+}
+
+/// Tauri 命令：快速导出（重复最近一次导出，没有时导出当前账号的 UIGF），完成后在文件管理器中定位
+#[tauri::command]
+pub(crate) async fn export_latest(app: AppHandle) -> Result<String, AppError> {
+    hide_tray_menu(&app);
+    let handle = app.clone();
+    let path = tauri::async_runtime::spawn_blocking(move || {
+        crate::commands::export::export_latest(&handle)
+    })
+    .await
+    .map_err(|e| AppError::io(format!("导出任务异常结束: {}", e)))??;
+    if let Err(e) = tauri_plugin_opener::reveal_item_in_dir(&path) {
+        eprintln!("[tray] 打开导出文件所在目录失败: {}", e);
+    }
+    Ok(path)
+}
+
+/// Tauri 命令：在文件管理器中打开数据目录
+#[tauri::command]
+pub(crate) fn open_data_dir(app: AppHandle) -> Result<(), AppError> {
+    hide_tray_menu(&app);
+    tauri_plugin_opener::open_path(userdata_dir().map_err(AppError::io)?, None::<&str>)
+        .map_err(|e| AppError::io(format!("打开数据目录失败: {}", e)))
+}
+
+/// 当前账号各卡池的抽数、6★ 数与保底进度
+fn summary_text(conn: &rusqlite::Connection, uid: &str) -> Result<String, AppError> {
+    let sql = format!(
+        "{} SELECT gacha_type, COUNT(*), SUM(rarity = ?2) FROM pull_records
+           WHERE uid = ?1 GROUP BY gacha_type",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询抽卡摘要失败: {}", e)))?;
+    let counts = stmt
+        .query_map(rusqlite::params![uid, TOP_RARITY], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?),
+            ))
+        })
+        .map_err(|e| AppError::database(format!("查询抽卡摘要失败: {}", e)))?
+        .collect::<Result<std::collections::HashMap<_, _>, _>>()
+        .map_err(|e| AppError::database(format!("读取抽卡摘要失败: {}", e)))?;
+
+    let mut lines = vec![format!("{} · {}", TRAY_TOOLTIP, uid)];
+    for gacha_type in GACHA_TYPES {
+        let Some(&(total, top)) = counts.get(gacha_type) else {
+            continue;
+        };
+        let status = compute_pity_status(conn, uid, gacha_type)?;
+        lines.push(format!(
+            "{}：共 {} 抽，6★ {} 个，已垫 {} 抽",
+            gacha_type_label(gacha_type),
+            total,
+            top,
+            status.pity
+        ));
+    }
+    if lines.len() == 1 {
+        lines.push("暂无抽卡记录".to_string());
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(target_os = "windows")]
+fn set_clipboard_text(text: &str) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::GlobalFree;
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows_sys::Win32::System::Ole::CF_UNICODETEXT;
+
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            return Err("打开剪贴板失败".to_string());
+        }
+        let result = (|| {
+            EmptyClipboard();
+            let handle = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>());
+            if handle.is_null() {
+                return Err("分配剪贴板内存失败".to_string());
+            }
+            let target = GlobalLock(handle) as *mut u16;
+            if target.is_null() {
+                GlobalFree(handle);
+                return Err("分配剪贴板内存失败".to_string());
+            }
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), target, wide.len());
+            GlobalUnlock(handle);
+            // 设置成功后内存归系统所有，失败时才需要释放
+            if SetClipboardData(CF_UNICODETEXT as u32, handle).is_null() {
+                GlobalFree(handle);
+                return Err("写入剪贴板失败".to_string());
+            }
+            Ok(())
+        })();
+        CloseClipboard();
+        result
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_clipboard_text(_text: &str) -> Result<(), String> {
+    Err("当前平台不支持从托盘复制".to_string())
+}
+
+/// Tauri 命令：把当前账号的抽卡摘要复制到剪贴板，返回复制的文字
+#[tauri::command]
+pub(crate) fn copy_summary(app: AppHandle) -> Result<String, AppError> {
+    let uid = current_config(&app)
+        .active_account
+        .ok_or_else(|| AppError::validation("请先选择账号"))?;
+    let conn = open_db(&app)?;
+    let text = summary_text(&conn, &uid)?;
+    set_clipboard_text(&text).map_err(AppError::io)?;
+    hide_tray_menu(&app);
+    Ok(text)
+}
+
+/// 今日某卡池的保底变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TodayPity {
+    gacha_type: String,
+    /// 今日开始时已垫的抽数
+    before: u32,
+    /// 当前已垫的抽数
+    now: u32,
+}
+
+/// 今日（服务器日）抽卡概况，托盘菜单每次弹出时刷新
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TodaySummary {
+    /// 今日起点（毫秒时间戳，服务器时间刷新时刻）
+    day_start: i64,
+    /// 今日抽数（含免费抽）
+    pulls: u32,
+    /// 今日出过的最高稀有度，今日没有记录时为空
+    highest_rarity: Option<i64>,
+    /// 今日有记录的卡池的保底变化（按 `GACHA_TYPES` 顺序）
+    pity: Vec<TodayPity>,
+}
+
+/// 统计今日概况用的记录：(gacha_type, rarity, is_free, 抽卡时间)
+type TodayRow = (String, i64, bool, Option<chrono::DateTime<chrono::Utc>>);
+
+/// 按卡池、时间正序的记录统计今日概况
+///
+/// 保底经由 `PityWalker` 计数，与 `compute_pity_status` 口径一致。
+fn tally_today(rows: &[TodayRow], day_start: chrono::DateTime<chrono::Utc>) -> TodaySummary {
+    let mut pulls = 0;
+    let mut highest_rarity: Option<i64> = None;
+    // gacha_type -> (今日开始时的保底, 保底计数, 今日有记录)
+    let mut pools: std::collections::HashMap<&str, (u32, PityWalker, bool)> =
+        std::collections::HashMap::new();
+    for (gacha_type, rarity, is_free, time) in rows {
+        let pool = pools
+            .entry(gacha_type.as_str())
+            .or_insert_with(|| (0, PityWalker::new(TOP_RARITY), false));
+        pool.1.step(*rarity, *is_free);
+        if time.is_some_and(|time| time >= day_start) {
+            pool.2 = true;
+            pulls += 1;
+            highest_rarity = highest_rarity.max(Some(*rarity));
+        } else {
+            pool.0 = pool.1.current();
+        }
+    }
+    let pity = GACHA_TYPES
+        .iter()
+        .filter_map(|gacha_type| {
+            let (before, walker, today) = pools.get(gacha_type)?;
+            today.then(|| TodayPity {
+                gacha_type: gacha_type.to_string(),
+                before: *before,
+                now: walker.current(),
+            })
+        })
+        .collect();
+    TodaySummary {
+        day_start: day_start.timestamp_millis(),
+        pulls,
+        highest_rarity,
+        pity,
+    }
+}
+
+/// Tauri 命令：账号今日（服务器日，刷新时刻换日）的抽数、最高稀有度与保底变化
+#[tauri::command]
+pub(crate) fn get_today_summary(app: AppHandle, uid: String) -> Result<TodaySummary, AppError> {
+    let day_start = server_day_start(&uid, trusted_now(&app));
+    let conn = open_db(&app)?;
+    let sql = format!(
+        "{} SELECT gacha_type, rarity, is_free, time, gacha_id FROM pull_records
+           WHERE uid = ?1",
+        PULL_RECORDS_CTE
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database(format!("查询今日抽卡失败: {}", e)))?;
+    let rows = query_pulls_by_time(&mut stmt, rusqlite::params![uid], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)? != 0,
+            parse_gacha_ts(&row.get::<_, String>(3)?),
+        ))
+    })
+    .map_err(|e| AppError::database(format!("读取今日抽卡失败: {}", e)))?;
+    Ok(tally_today(&rows, day_start))
+}
+
+/// 托盘图标 ID
+pub(crate) const TRAY_ID: &str = "main";
+
+/// 默认托盘提示文字
+pub(crate) const TRAY_TOOLTIP: &str = "终末地抽卡助手";
+
+// ============== 托盘同步进度 ==============
+//
+// 同步期间在托盘图标右下角绘制进度环，完成后短暂显示对勾再恢复原图标。
+// 重绘托盘图标开销不小，进度每跨过 5% 才重绘，且两次重绘至少间隔 1 秒。
+
+/// 进度重绘的最小步长（百分比）
+const TRAY_PROGRESS_STEP_PERCENT: f64 = 5.0;
+/// 两次重绘的最小间隔
+const TRAY_PROGRESS_MIN_INTERVAL_MS: u64 = 1000;
+/// 完成标记的显示时长
+const TRAY_PROGRESS_DONE_MS: u64 = 2500;
+
+const TRAY_PROGRESS_COLOR: [u8; 4] = [0x22, 0xc5, 0x5e, 0xff];
+const TRAY_PROGRESS_TRACK: [u8; 4] = [0x52, 0x52, 0x5b, 0xff];
+const TRAY_PROGRESS_BACKDROP: [u8; 4] = [0x18, 0x18, 0x1b, 0xff];
+const TRAY_PROGRESS_CHECK: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+#[derive(Default)]
+struct TrayProgress {
+    /// 正在显示进度
+    active: bool,
+    /// 上次重绘时的进度档位（每档 5%）
+    last_step: Option<u32>,
+    last_draw: Option<std::time::Instant>,
+    /// 每次开始/结束递增，用于判断完成标记的恢复是否已过期
+    generation: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct TrayProgressState(Mutex<TrayProgress>);
+
+/// 点 (px, py) 到线段 (ax, ay)-(bx, by) 的距离
+fn distance_to_segment(px: f64, py: f64, (ax, ay): (f64, f64), (bx, by): (f64, f64)) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let t = (((px - ax) * dx + (py - ay) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+    ((px - ax - t * dx).powi(2) + (py - ay - t * dy).powi(2)).sqrt()
+}
+
+/// 在图标右下角绘制进度环；`progress` 为空时绘制完成对勾
+fn draw_tray_progress(base: &Image<'_>, progress: Option<f64>) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let size = f64::from(width.min(height));
+    let (cx, cy) = (f64::from(width) * 0.7, f64::from(height) * 0.7);
+    let outer = size * 0.3;
+    let inner = outer * 0.55;
+    let check = [
+        (cx - outer * 0.45, cy),
+        (cx - outer * 0.1, cy + outer * 0.35),
+        (cx + outer * 0.45, cy - outer * 0.3),
+    ];
+
+    for y in 0..height {
+        for x in 0..width {
+            let (px, py) = (f64::from(x) + 0.5, f64::from(y) + 0.5);
+            let (dx, dy) = (px - cx, py - cy);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance > outer {
+                continue;
+            }
+            let color = match progress {
+                None => {
+                    let stroke = distance_to_segment(px, py, check[0], check[1])
+                        .min(distance_to_segment(px, py, check[1], check[2]));
+                    if stroke <= outer * 0.14 {
+                        TRAY_PROGRESS_CHECK
+                    } else {
+                        TRAY_PROGRESS_COLOR
+                    }
+                }
+                Some(_) if distance < inner => TRAY_PROGRESS_BACKDROP,
+                Some(progress) => {
+                    // 从正上方开始顺时针
+                    let angle = dx.atan2(-dy).rem_euclid(std::f64::consts::TAU);
+                    if angle / std::f64::consts::TAU <= progress {
+                        TRAY_PROGRESS_COLOR
+                    } else {
+                        TRAY_PROGRESS_TRACK
+                    }
+                }
+            };
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+/// 当前任务栏主题对应的托盘图标
+fn themed_tray_icon(app: &AppHandle) -> Image<'static> {
+    load_tray_icon(app, taskbar_theme(app))
+}
+
+fn set_tray_icon_image(app: &AppHandle, icon: Image<'static>) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_icon(Some(icon)) {
+            eprintln!("[tray] 更新托盘图标失败: {}", e);
+        }
+    }
+}
+
+/// 是否正在托盘上显示同步进度，即正在同步（此时不响应主题切换换图标）
+pub(crate) fn tray_progress_active(app: &AppHandle) -> bool {
+    app_state(app).tray_progress.0.lock().unwrap().active
+}
+
+/// Tauri 命令：更新托盘同步进度（0~1），内部节流
+#[tauri::command]
+pub(crate) fn set_tray_progress(app: AppHandle, progress: f64) {
+    if !progress.is_finite() {
+        return;
+    }
+    let progress = progress.clamp(0.0, 1.0);
+    let step = (progress * 100.0 / TRAY_PROGRESS_STEP_PERCENT).floor() as u32;
+    {
+        let state = &app_state(&app).tray_progress;
+        let mut tray = state.0.lock().unwrap();
+        if tray.active {
+            let throttled = tray.last_draw.is_some_and(|at| {
+                at.elapsed() < std::time::Duration::from_millis(TRAY_PROGRESS_MIN_INTERVAL_MS)
+            });
+            if tray.last_step == Some(step) || throttled {
+                return;
+            }
+        } else {
+            tray.active = true;
+            tray.generation += 1;
+        }
+        tray.last_step = Some(step);
+        tray.last_draw = Some(std::time::Instant::now());
+    }
+    set_tray_icon_image(
+        &app,
+        draw_tray_progress(&themed_tray_icon(&app), Some(progress)),
+    );
+}
+
+/// Tauri 命令：结束托盘同步进度；成功时短暂显示完成标记后恢复原图标
+#[tauri::command]
+pub(crate) fn finish_tray_progress(app: AppHandle, success: bool) {
+    let generation = {
+        let state = &app_state(&app).tray_progress;
+        let mut tray = state.0.lock().unwrap();
+        if !tray.active {
+            return;
+        }
+        *tray = TrayProgress {
+            generation: tray.generation + 1,
+            ..TrayProgress::default()
+        };
+        tray.generation
+    };
+    if !success {
+        set_tray_icon_image(&app, themed_tray_icon(&app));
+        return;
+    }
+    set_tray_icon_image(&app, draw_tray_progress(&themed_tray_icon(&app), None));
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(TRAY_PROGRESS_DONE_MS));
+        let current = app_state(&app).tray_progress.0.lock().unwrap().generation;
+        // 期间开始了新的同步则不再恢复
+        if current == generation {
+            set_tray_icon_image(&app, themed_tray_icon(&app));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: (f64, f64, f64, f64) = (0.0, 0.0, 1920.0, 1080.0);
+    const MENU: (f64, f64) = (236.0, 244.0);
+
+    #[test]
+    fn builtin_tray_icons_are_distinct_square_pngs() {
+        let light = Image::from_bytes(TRAY_ICON_LIGHT).unwrap();
+        let dark = Image::from_bytes(TRAY_ICON_DARK).unwrap();
+        for icon in [&light, &dark] {
+            assert!(icon.width() > 0);
+            assert_eq!(icon.width(), icon.height());
+        }
+        assert_ne!(light.rgba(), dark.rgba());
+    }
+
+    #[test]
+    fn menu_centered_above_click() {
+        let (x, y) = tray_menu_origin((960.0, 1000.0), SCREEN, MENU, 8.0);
+        assert_eq!(x, 960.0 - 118.0);
+        assert_eq!(y, 1000.0 - 244.0 - 8.0);
+    }
+
+    #[test]
+    fn menu_clamped_to_right_edge() {
+        let (x, _) = tray_menu_origin((1910.0, 1060.0), SCREEN, MENU, 8.0);
+        assert_eq!(x, 1920.0 - 236.0 - 8.0);
+    }
+
+    #[test]
+    fn menu_clamped_to_left_edge_of_secondary_monitor() {
+        let screen = (-1920.0, 0.0, 1920.0, 1080.0);
+        let (x, _) = tray_menu_origin((-1915.0, 1060.0), screen, MENU, 8.0);
+        assert_eq!(x, -1920.0 + 8.0);
+    }
+
+    #[test]
+    fn menu_below_click_when_no_room_above() {
+        // 任务栏在顶部时点击处靠近屏幕上沿
+        let (_, y) = tray_menu_origin((960.0, 20.0), SCREEN, MENU, 8.0);
+        assert_eq!(y, 28.0);
+    }
+
+    #[test]
+    fn menu_scaled_margin_on_high_dpi() {
+        let (x, y) = tray_menu_origin(
+            (3830.0, 2150.0),
+            (0.0, 0.0, 3840.0, 2160.0),
+            (MENU.0 * 2.0, MENU.1 * 2.0),
+            16.0,
+        );
+        assert_eq!(x, 3840.0 - 472.0 - 16.0);
+        assert_eq!(y, 2150.0 - 488.0 - 16.0);
+    }
+
+    #[test]
+    fn menu_width_follows_reported_content() {
+        assert_eq!(tray_menu_width(None), TRAY_MENU_WIDTH);
+        assert_eq!(tray_menu_width(Some(180.0)), TRAY_MENU_WIDTH);
+        assert_eq!(tray_menu_width(Some(280.0)), 280.0);
+        assert_eq!(tray_menu_width(Some(1000.0)), TRAY_MENU_MAX_WIDTH);
+    }
+
+    #[test]
+    fn today_summary_tracks_pity_from_day_start() {
+        let at = |s: &str| {
+            Some(
+                chrono::DateTime::parse_from_rfc3339(s)
+                    .unwrap()
+                    .with_timezone(&chrono::Utc),
+            )
+        };
+        let day_start = at("2026-04-01T20:00:00Z").unwrap();
+        let row = |gacha_type: &str, rarity, is_free, time: &str| {
+            (gacha_type.to_string(), rarity, is_free, at(time))
+        };
+        let rows = [
+            row("special", 4, false, "2026-04-01T10:00:00Z"),
+            row("special", 4, false, "2026-04-01T11:00:00Z"),
+            row("special", 4, false, "2026-04-01T21:00:00Z"),
+            row("special", TOP_RARITY, false, "2026-04-01T21:01:00Z"),
+            row("special", 4, true, "2026-04-01T21:02:00Z"),
+            row("special", 5, false, "2026-04-01T21:03:00Z"),
+            row("standard", 4, false, "2026-04-01T12:00:00Z"),
+        ];
+        let summary = tally_today(&rows, day_start);
+        assert_eq!(summary.pulls, 4);
+        assert_eq!(summary.highest_rarity, Some(TOP_RARITY));
+        assert_eq!(
+            summary.pity,
+            [TodayPity {
+                gacha_type: "special".to_string(),
+                before: 2,
+                now: 1,
+            }]
+        );
+
+        let quiet = tally_today(&rows[..2], day_start);
+        assert_eq!((quiet.pulls, quiet.highest_rarity), (0, None));
+        assert!(quiet.pity.is_empty());
+    }
+
+    #[test]
+    fn today_summary_free_top_rarity_keeps_pity() {
+        let at = |s: &str| {
+            Some(
+                chrono::DateTime::parse_from_rfc3339(s)
+                    .unwrap()
+                    .with_timezone(&chrono::Utc),
+            )
+        };
+        let day_start = at("2026-04-01T20:00:00Z").unwrap();
+        let row = |rarity, is_free, time: &str| ("special".to_string(), rarity, is_free, at(time));
+        let rows = [
+            row(4, false, "2026-04-01T10:00:00Z"),
+            row(4, false, "2026-04-01T21:00:00Z"),
+            // 免费抽出的最高稀有度不重置保底
+            row(TOP_RARITY, true, "2026-04-01T21:01:00Z"),
+            row(4, false, "2026-04-01T21:02:00Z"),
+        ];
+        let summary = tally_today(&rows, day_start);
+        assert_eq!(summary.pulls, 3);
+        assert_eq!(
+            summary.pity,
+            [TodayPity {
+                gacha_type: "special".to_string(),
+                before: 1,
+                now: 3,
+            }]
+        );
+    }
+}
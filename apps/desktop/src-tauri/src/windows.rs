@@ -0,0 +1,702 @@
+//! 主窗口：界面缩放、系统缩放变化、WebView 崩溃恢复、前端心跳与 WebView2 运行时检测
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, WebviewUrl, WebviewWindowBuilder};
+#[cfg(target_os = "windows")]
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+#[cfg(target_os = "windows")]
+use winreg::RegKey;
+
+use crate::app_state;
+use crate::config::{current_config, update_config};
+use crate::error::AppError;
+use crate::tray::{apply_tray_menu_region, tray_menu_size};
+
+/// 显示主窗口
+pub(crate) fn show_main_window(app: &AppHandle) {
+    ensure_main_window_alive(app);
+    if let Some(window) = app.get_webview_window("main") {
+        // 确保窗口可见
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Tauri 命令：显示主窗口
+#[tauri::command]
+pub(crate) fn show_main_window_cmd(app: AppHandle) {
+    show_main_window(&app);
+}
+
+/// Tauri 命令：让主窗口跳转到指定路由（用于托盘菜单等子窗口）
+#[tauri::command]
+pub(crate) fn navigate_main(app: AppHandle, path: String) {
+    // 确保主窗口可见并聚焦
+    show_main_window(&app);
+    if let Some(window) = app.get_webview_window("main") {
+        // 发送导航事件到主窗口（前端在 MainLayout 中监听）
+        let _ = window.emit("efgh:navigate", serde_json::json!({ "path": path, "replace": true }));
+    }
+}
+
+// ============== 界面缩放 ==============
+
+const ZOOM_MIN: f64 = 0.5;
+const ZOOM_MAX: f64 = 2.0;
+
+/// 当前保存的主窗口缩放
+fn saved_zoom(app: &AppHandle) -> f64 {
+    current_config(app)
+        .zoom
+        .map_or(1.0, |zoom| zoom.clamp(ZOOM_MIN, ZOOM_MAX))
+}
+
+/// 托盘菜单窗口使用的缩放（不跟随时固定 100%）
+pub(crate) fn tray_menu_zoom(app: &AppHandle) -> f64 {
+    if current_config(app).tray_menu_follow_zoom {
+        saved_zoom(app)
+    } else {
+        1.0
+    }
+}
+
+/// 设置 WebView 缩放
+///
+/// WebView2（Windows）、WKWebView（macOS 11+）、WebKitGTK（Linux）都支持原生缩放；
+/// 不支持时（如 macOS 11 以下）退回到 CSS zoom，页面重新加载后由 `on_page_load` 再次应用。
+fn apply_zoom(webview: &tauri::Webview, factor: f64) {
+    if let Err(e) = webview.set_zoom(factor) {
+        eprintln!("[zoom] 原生缩放不可用，改用 CSS zoom: {}", e);
+        let _ = webview.eval(format!(
+            "document.documentElement.style.zoom = '{}'",
+            factor
+        ));
+    }
+}
+
+/// 按窗口应用保存的缩放（页面加载完成时调用）
+pub(crate) fn apply_saved_zoom(webview: &tauri::Webview) {
+    let app = webview.app_handle();
+    match webview.label() {
+        "main" => apply_zoom(webview, saved_zoom(app)),
+        "tray-menu" => apply_zoom(webview, tray_menu_zoom(app)),
+        _ => {}
+    }
+}
+
+/// 界面缩放设置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ZoomSettings {
+    factor: f64,
+    tray_menu_follow: bool,
+}
+
+/// Tauri 命令：获取界面缩放设置
+#[tauri::command]
+pub(crate) fn get_zoom(app: AppHandle) -> ZoomSettings {
+    ZoomSettings {
+        factor: saved_zoom(&app),
+        tray_menu_follow: current_config(&app).tray_menu_follow_zoom,
+    }
+}
+
+/// Tauri 命令：设置主窗口缩放（限制在 0.5–2.0），持久化后立即生效，返回实际应用的值
+#[tauri::command]
+pub(crate) fn set_zoom(app: AppHandle, factor: f64) -> Result<f64, AppError> {
+    if !factor.is_finite() {
+        return Err(AppError::validation("无效的缩放比例"));
+    }
+    let factor = factor.clamp(ZOOM_MIN, ZOOM_MAX);
+    update_config(&app, |c| c.zoom = Some(factor))?;
+    if let Some(window) = app.get_webview_window("main") {
+        apply_zoom(window.as_ref(), factor);
+    }
+    if let Some(window) = app.get_webview_window("tray-menu") {
+        apply_zoom(window.as_ref(), tray_menu_zoom(&app));
+    }
+    Ok(factor)
+}
+
+/// Tauri 命令：设置托盘菜单是否跟随主窗口缩放
+#[tauri::command]
+pub(crate) fn set_tray_menu_follow_zoom(app: AppHandle, follow: bool) -> Result<(), AppError> {
+    update_config(&app, |c| c.tray_menu_follow_zoom = follow)?;
+    if let Some(window) = app.get_webview_window("tray-menu") {
+        // 已显示时先收起，下次弹出按新尺寸定位
+        let _ = window.hide();
+        apply_zoom(window.as_ref(), tray_menu_zoom(&app));
+    }
+    Ok(())
+}
+
+// ============== 系统缩放变化 ==============
+//
+// 用户在运行中修改系统 DPI 时，窗口会收到 ScaleFactorChanged。拖动窗口跨越不同缩放的
+// 显示器时该事件会连续触发，这里按窗口去抖，只在最后一次变化稳定后处理。
+
+/// 缩放变化去抖间隔
+const SCALE_CHANGE_DEBOUNCE_MS: u64 = 250;
+
+/// 各窗口最近一次缩放变化的序号（用于去抖）
+#[derive(Default)]
+pub(crate) struct ScaleChangeState(Mutex<HashMap<String, u64>>);
+
+/// 收到 ScaleFactorChanged：记录序号，延迟后若没有更新的变化再处理
+pub(crate) fn on_scale_factor_changed(window: &tauri::Window) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let generation = {
+        let state = &app_state(&app).scale_change;
+        let mut map = state.0.lock().unwrap();
+        let generation = map.entry(label.clone()).or_insert(0);
+        *generation += 1;
+        *generation
+    };
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(SCALE_CHANGE_DEBOUNCE_MS));
+        let latest = app_state(&app)
+            .scale_change
+            .0
+            .lock()
+            .unwrap()
+            .get(&label)
+            .copied();
+        if latest == Some(generation) {
+            apply_scale_change(&app, &label);
+        }
+    });
+}
+
+/// 缩放稳定后按新的缩放比例刷新窗口
+fn apply_scale_change(app: &AppHandle, label: &str) {
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let scale = window.scale_factor().unwrap_or(1.0);
+    eprintln!("[scale] {} 缩放比例变为 {}", label, scale);
+    match label {
+        "tray-menu" => {
+            // 裁剪区域按物理像素设置，需要按新比例重算；
+            // 旧位置是按原比例算出的，直接收起，下次右键重新定位
+            let (width, height) = tray_menu_size(app);
+            let _ = window.set_size(LogicalSize::new(width, height));
+            apply_tray_menu_region(&window, width, height);
+            let _ = window.hide();
+        }
+        "main" => {
+            // 部分 WebView 在 DPI 变化后会丢失页面缩放，重新应用一次
+            apply_zoom(window.as_ref(), saved_zoom(app));
+            let _ = window.emit(
+                "scale-factor-changed",
+                serde_json::json!({ "scaleFactor": scale }),
+            );
+        }
+        _ => {}
+    }
+}
+
+// ============== WebView 崩溃恢复 ==============
+//
+// WebView2 渲染进程崩溃后主窗口会变白，所有基于事件的功能随之失效，但 Rust 进程与托盘仍在运行。
+// 监听 ProcessFailed：渲染进程退出时重新加载到崩溃前的路由；浏览器进程退出时 WebView 已不可用，
+// 销毁并按配置重建主窗口。托盘「打开主界面」遇到尚未恢复的崩溃时也会先恢复再显示。
+
+/// 主窗口 WebView 状态
+#[derive(Default)]
+pub(crate) struct MainWebviewState {
+    /// 已崩溃、尚未恢复
+    crashed: AtomicBool,
+}
+
+/// 各窗口前端最近一次上报的路由（窗口标签 → 路径），窗口重建时回到该页面
+#[derive(Default)]
+pub(crate) struct WindowRouteState(Mutex<HashMap<String, String>>);
+
+/// 窗口最近的路由，未上报过时使用 `default`
+pub(crate) fn last_window_route(app: &AppHandle, label: &str, default: &str) -> String {
+    app_state(app)
+        .window_routes
+        .0
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// WebView 崩溃类型（只有 Windows 会上报渲染进程崩溃）
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+enum WebviewFailure {
+    /// 渲染进程退出或无响应：重新加载页面即可
+    Renderer,
+    /// 浏览器进程退出：WebView 已失效，需要重建窗口
+    Browser,
+}
+
+/// 主窗口崩溃或关闭前的路由
+fn last_main_route(app: &AppHandle) -> String {
+    last_window_route(app, "main", "/")
+}
+
+/// 按 tauri.conf.json 中的主窗口配置重新创建主窗口，并打开指定路由
+fn create_main_window(app: &AppHandle, route: &str) -> Result<(), String> {
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned()
+        .ok_or_else(|| "缺少主窗口配置".to_string())?;
+    config.url = WebviewUrl::App(route.trim_start_matches('/').into());
+    let window = WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("重建主窗口失败: {}", e))?;
+    watch_webview_process(&window);
+    Ok(())
+}
+
+/// 销毁（已失效的）主窗口后重建；等待旧窗口注销，避免标签冲突
+fn rebuild_main_window(app: &AppHandle, route: &str) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.destroy();
+    }
+    for _ in 0..50 {
+        if app.get_webview_window("main").is_none() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    if let Err(e) = create_main_window(app, route) {
+        eprintln!("[webview] {}", e);
+    }
+}
+
+/// 恢复崩溃的主窗口 WebView
+fn recover_main_webview(app: &AppHandle, failure: WebviewFailure) {
+    let route = last_main_route(app);
+    eprintln!(
+        "[webview] 主窗口 WebView 崩溃（{:?}），恢复到 {}",
+        failure, route
+    );
+    let reloaded = match (failure, app.get_webview_window("main")) {
+        (WebviewFailure::Renderer, Some(window)) => window
+            .url()
+            .and_then(|mut url| {
+                url.set_path(&route);
+                url.set_query(None);
+                url.set_fragment(None);
+                window.navigate(url)
+            })
+            .map_err(|e| eprintln!("[webview] 重新加载主窗口失败，改为重建: {}", e))
+            .is_ok(),
+        _ => false,
+    };
+    if !reloaded {
+        rebuild_main_window(app, &route);
+    }
+}
+
+/// 收到崩溃通知：标记状态并在后台线程中恢复（不在 WebView2 回调里直接操作窗口）
+#[cfg(target_os = "windows")]
+fn on_main_webview_failed(app: &AppHandle, failure: WebviewFailure) {
+    let state = &app_state(app).main_webview;
+    if state.crashed.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if app_state(&app)
+            .main_webview
+            .crashed
+            .swap(false, Ordering::SeqCst)
+        {
+            recover_main_webview(&app, failure);
+        }
+    });
+}
+
+/// 显示主窗口前检查：崩溃未恢复或窗口已不存在时先恢复
+///
+/// 托盘事件在主线程回调，重建窗口需要等待事件循环处理销毁，因此放到后台线程中进行；
+/// 新窗口按配置创建后会自行显示。
+fn ensure_main_window_alive(app: &AppHandle) {
+    let crashed = app_state(app)
+        .main_webview
+        .crashed
+        .swap(false, Ordering::SeqCst);
+    if !crashed && app.get_webview_window("main").is_some() {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if crashed {
+            recover_main_webview(&app, WebviewFailure::Browser);
+        } else {
+            eprintln!("[webview] 主窗口不存在，重新创建");
+            if let Err(e) = create_main_window(&app, &last_main_route(&app)) {
+                eprintln!("[webview] {}", e);
+            }
+        }
+    });
+}
+
+/// 为主窗口注册 WebView2 进程崩溃监听
+#[cfg(target_os = "windows")]
+pub(crate) fn watch_webview_process(window: &tauri::WebviewWindow) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        COREWEBVIEW2_PROCESS_FAILED_KIND, COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE,
+    };
+    use webview2_com::ProcessFailedEventHandler;
+
+    let app = window.app_handle().clone();
+    let _ = window.with_webview(move |webview| unsafe {
+        let core = match webview.controller().CoreWebView2() {
+            Ok(core) => core,
+            Err(e) => {
+                eprintln!("[webview] 获取 WebView2 实例失败: {}", e);
+                return;
+            }
+        };
+        let handler = ProcessFailedEventHandler::create(Box::new(move |_, args| {
+            let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+            if let Some(args) = args {
+                args.ProcessFailedKind(&mut kind)?;
+            }
+            let failure = if kind == COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED {
+                Some(WebviewFailure::Browser)
+            } else if kind == COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED
+                || kind == COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE
+            {
+                Some(WebviewFailure::Renderer)
+            } else {
+                // GPU 等辅助进程退出由 WebView2 自行重启，无需处理
+                eprintln!("[webview] WebView2 辅助进程退出: {:?}", kind);
+                None
+            };
+            if let Some(failure) = failure {
+                on_main_webview_failed(&app, failure);
+            }
+            Ok(())
+        }));
+        let mut token = 0i64;
+        if let Err(e) = core.add_ProcessFailed(&handler, &mut token) {
+            eprintln!("[webview] 注册崩溃监听失败: {}", e);
+        }
+    });
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn watch_webview_process(_window: &tauri::WebviewWindow) {
+    // 其他平台的 WebView 没有等价的进程崩溃事件
+}
+
+/// Tauri 命令：前端在导航后上报所在窗口的当前路由（窗口崩溃恢复或关闭后重建时回到该页面）
+#[tauri::command]
+pub(crate) fn report_route(app: AppHandle, label: String, path: String) {
+    if !path.starts_with('/') || (label == "main" && path == "/tray-menu") {
+        return;
+    }
+    app_state(&app)
+        .window_routes
+        .0
+        .lock()
+        .unwrap()
+        .insert(label, path);
+}
+
+// ============== 前端心跳 ==============
+//
+// 前端每 10 秒调用一次 `heartbeat`，Rust 端按窗口记录最后一次心跳时间。
+// 可见窗口连续错过 3 次心跳即视为无响应，广播 `frontend-unresponsive`；
+// 向无响应窗口发送的事件经 `emit_throttled` 限流，避免事件在卡死的 WebView 中堆积。
+
+/// 前端心跳间隔
+const HEARTBEAT_INTERVAL_MS: u64 = 10_000;
+/// 连续错过多少次心跳视为无响应
+const HEARTBEAT_MISSED_LIMIT: u64 = 3;
+/// 无响应窗口同一事件的最小发送间隔
+const UNHEALTHY_EMIT_INTERVAL_MS: u64 = 5_000;
+
+/// 单个窗口的心跳记录
+struct HeartbeatInfo {
+    last_seen: std::time::Instant,
+    /// 已广播过无响应（恢复心跳后清除）
+    unresponsive: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct FrontendHealthState {
+    windows: Mutex<HashMap<String, HeartbeatInfo>>,
+    /// 向无响应窗口发送事件的最近时间（窗口标签, 事件名）
+    throttled: Mutex<HashMap<(String, String), std::time::Instant>>,
+}
+
+/// 窗口健康状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FrontendHealth {
+    label: String,
+    visible: bool,
+    /// 距最后一次心跳的毫秒数
+    last_seen_ms_ago: u64,
+    healthy: bool,
+}
+
+fn heartbeat_timeout() -> std::time::Duration {
+    std::time::Duration::from_millis(HEARTBEAT_INTERVAL_MS * HEARTBEAT_MISSED_LIMIT)
+}
+
+/// 窗口是否健康：可见且超时未心跳才算不健康；从未心跳过的窗口按健康处理
+fn is_window_healthy(app: &AppHandle, label: &str) -> bool {
+    let visible = app
+        .get_webview_window(label)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    if !visible {
+        return true;
+    }
+    app_state(app)
+        .frontend_health
+        .windows
+        .lock()
+        .unwrap()
+        .get(label)
+        .is_none_or(|info| info.last_seen.elapsed() < heartbeat_timeout())
+}
+
+/// 向各窗口发送事件；无响应的窗口按 `UNHEALTHY_EMIT_INTERVAL_MS` 限流（进度等高频事件使用）
+pub(crate) fn emit_throttled<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    for label in app.webview_windows().into_keys() {
+        if !is_window_healthy(app, &label) {
+            let state = &app_state(app).frontend_health;
+            let mut throttled = state.throttled.lock().unwrap();
+            let key = (label.clone(), event.to_string());
+            let interval = std::time::Duration::from_millis(UNHEALTHY_EMIT_INTERVAL_MS);
+            if throttled
+                .get(&key)
+                .is_some_and(|sent| sent.elapsed() < interval)
+            {
+                continue;
+            }
+            throttled.insert(key, std::time::Instant::now());
+        }
+        let _ = app.emit_to(label.as_str(), event, payload.clone());
+    }
+}
+
+/// 后台巡检：可见窗口连续错过心跳时广播 `frontend-unresponsive`
+pub(crate) fn start_heartbeat_monitor(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_millis(HEARTBEAT_INTERVAL_MS));
+        let stale: Vec<(String, u64)> = {
+            let state = &app_state(&app).frontend_health;
+            let mut windows = state.windows.lock().unwrap();
+            windows
+                .iter_mut()
+                .filter(|(_, info)| {
+                    !info.unresponsive && info.last_seen.elapsed() >= heartbeat_timeout()
+                })
+                .map(|(label, info)| {
+                    info.unresponsive = true;
+                    (label.clone(), info.last_seen.elapsed().as_millis() as u64)
+                })
+                .collect()
+        };
+        for (label, last_seen_ms_ago) in stale {
+            let visible = app
+                .get_webview_window(&label)
+                .and_then(|window| window.is_visible().ok())
+                .unwrap_or(false);
+            if !visible {
+                // 隐藏窗口的定时器可能被系统节流，不算无响应；重新显示后再判断
+                if let Some(info) = app_state(&app)
+                    .frontend_health
+                    .windows
+                    .lock()
+                    .unwrap()
+                    .get_mut(&label)
+                {
+                    info.unresponsive = false;
+                }
+                continue;
+            }
+            eprintln!(
+                "[heartbeat] 窗口 {} 已 {} ms 无心跳",
+                label, last_seen_ms_ago
+            );
+            let _ = app.emit(
+                "frontend-unresponsive",
+                serde_json::json!({ "label": label, "lastSeenMsAgo": last_seen_ms_ago }),
+            );
+        }
+    });
+}
+
+/// Tauri 命令：前端心跳（每 10 秒一次）
+#[tauri::command]
+pub(crate) fn heartbeat(app: AppHandle, window: tauri::WebviewWindow) {
+    let state = &app_state(&app).frontend_health;
+    let mut windows = state.windows.lock().unwrap();
+    let info = windows
+        .entry(window.label().to_string())
+        .or_insert_with(|| HeartbeatInfo {
+            last_seen: std::time::Instant::now(),
+            unresponsive: false,
+        });
+    if info.unresponsive {
+        eprintln!("[heartbeat] 窗口 {} 恢复响应", window.label());
+        info.unresponsive = false;
+    }
+    info.last_seen = std::time::Instant::now();
+}
+
+/// Tauri 命令：获取各窗口的前端健康状态
+#[tauri::command]
+pub(crate) fn get_frontend_health(app: AppHandle) -> Vec<FrontendHealth> {
+    let seen: Vec<(String, u64)> = app_state(&app)
+        .frontend_health
+        .windows
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(label, info)| (label.clone(), info.last_seen.elapsed().as_millis() as u64))
+        .collect();
+    seen.into_iter()
+        .map(|(label, last_seen_ms_ago)| FrontendHealth {
+            visible: app
+                .get_webview_window(&label)
+                .and_then(|window| window.is_visible().ok())
+                .unwrap_or(false),
+            healthy: is_window_healthy(&app, &label),
+            label,
+            last_seen_ms_ago,
+        })
+        .collect()
+}
+
+// ============== WebView2 运行时检测（仅 Windows） ==============
+//
+// 缺少 WebView2 运行时时主窗口无法创建，表现为白屏或直接退出。
+// 检测发生在 Tauri 启动之前，此时 dialog 插件尚不可用，因此直接使用系统 MessageBox。
+
+/// WebView2 Evergreen 运行时在 EdgeUpdate 中的客户端 ID
+#[cfg(target_os = "windows")]
+const WEBVIEW2_CLIENT_ID: &str = "{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+
+/// WebView2 运行时下载地址（微软官方 Evergreen Bootstrapper）
+#[cfg(target_os = "windows")]
+const WEBVIEW2_DOWNLOAD_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+
+/// 读取注册表判断 WebView2 运行时是否已安装
+///
+/// 按微软文档依次检查 HKLM（64/32 位视图）与 HKCU，`pv` 为非空且不为 0.0.0.0 即视为已安装。
+#[cfg(target_os = "windows")]
+fn webview2_installed() -> bool {
+    let locations = [
+        (
+            HKEY_LOCAL_MACHINE,
+            r"SOFTWARE\WOW6432Node\Microsoft\EdgeUpdate\Clients",
+        ),
+        (HKEY_LOCAL_MACHINE, r"SOFTWARE\Microsoft\EdgeUpdate\Clients"),
+        (HKEY_CURRENT_USER, r"Software\Microsoft\EdgeUpdate\Clients"),
+    ];
+    locations.iter().any(|(hive, path)| {
+        RegKey::predef(*hive)
+            .open_subkey(format!(r"{}\{}", path, WEBVIEW2_CLIENT_ID))
+            .and_then(|key| key.get_value::<String, _>("pv"))
+            .map(|pv| !pv.is_empty() && pv != "0.0.0.0")
+            .unwrap_or(false)
+    })
+}
+
+/// 弹出系统消息框，返回用户点击的按钮 ID
+#[cfg(target_os = "windows")]
+fn native_message_box(text: &str, flags: u32) -> i32 {
+    use windows_sys::Win32::UI::WindowsAndMessaging::MessageBoxW;
+
+    let to_wide = |s: &str| s.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let text = to_wide(text);
+    let caption = to_wide("终末地抽卡助手");
+    unsafe { MessageBoxW(std::ptr::null_mut(), text.as_ptr(), caption.as_ptr(), flags) }
+}
+
+/// 启动前检查 WebView2 运行时；缺失时引导用户下载安装
+///
+/// 返回 `true` 表示可以继续启动；用户取消或已重新启动新进程时返回 `false`。
+#[cfg(target_os = "windows")]
+pub(crate) fn ensure_webview2() -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        IDOK, IDYES, MB_ICONINFORMATION, MB_ICONWARNING, MB_OKCANCEL, MB_YESNO,
+    };
+
+    if webview2_installed() {
+        return true;
+    }
+    eprintln!("[webview2] 未检测到 WebView2 运行时");
+
+    let choice = native_message_box(
+        "未检测到 Microsoft Edge WebView2 运行时，程序界面无法显示。\n\n是否打开下载页面进行安装？",
+        MB_YESNO | MB_ICONWARNING,
+    );
+    if choice != IDYES {
+        return false;
+    }
+    if let Err(e) = tauri_plugin_opener::open_url(WEBVIEW2_DOWNLOAD_URL, None::<&str>) {
+        eprintln!("[webview2] 打开下载页面失败: {}", e);
+        native_message_box(
+            &format!(
+                "无法打开浏览器，请手动访问以下地址下载：\n{}",
+                WEBVIEW2_DOWNLOAD_URL
+            ),
+            MB_ICONWARNING,
+        );
+    }
+
+    loop {
+        let choice = native_message_box(
+            "请在下载页面完成 WebView2 运行时的安装。\n\n安装完成后点击「确定」重新启动本程序。",
+            MB_OKCANCEL | MB_ICONINFORMATION,
+        );
+        if choice != IDOK {
+            return false;
+        }
+        if webview2_installed() {
+            break;
+        }
+        native_message_box(
+            "仍未检测到 WebView2 运行时，请确认安装已完成。",
+            MB_ICONWARNING,
+        );
+    }
+
+    // 安装完成后以新进程重新启动，避免沿用检测前的环境
+    match std::env::current_exe().and_then(|exe| std::process::Command::new(exe).spawn()) {
+        Ok(_) => false,
+        Err(e) => {
+            eprintln!("[webview2] 重新启动失败，继续在当前进程启动: {}", e);
+            true
+        }
+    }
+}
+
+/// Tauri 命令：进程启动（进入 main）至今的毫秒数；前端加载完成后调用一次，作为启动耗时参考
+#[tauri::command]
+pub(crate) fn get_startup_duration_ms(app: AppHandle) -> u64 {
+    app_state(&app).started_at.elapsed().as_millis() as u64
+}
+
+/// Tauri 命令：本次运行时长（秒），用于显示会话时长
+#[tauri::command]
+pub(crate) fn get_application_uptime_seconds(app: AppHandle) -> u64 {
+    app_state(&app).started_at.elapsed().as_secs()
+}
@@ -74,6 +74,11 @@ export type EndfieldClientOptions = {
    * Note: some custom fetchers may ignore the signal; we still use it to stop pagination and delays.
    */
   signal?: AbortSignal;
+  /**
+   * Optional hook receiving each record page's raw response body and 1-based page number
+   * before parsing (used to keep raw responses for debugging parse issues).
+   */
+  onRawResponse?: (kind: 'char' | 'weapon', body: string, page: number) => void;
 };
 
 const DEFAULT_UA =
@@ -97,6 +102,7 @@ function pickOptions(options?: EndfieldClientOptions) {
     userAgent: options?.userAgent ?? DEFAULT_UA,
     fetcher: options?.fetcher ?? fetch,
     signal: options?.signal,
+    onRawResponse: options?.onRawResponse,
   };
 }
 
@@ -245,6 +251,8 @@ export type FetchCharPoolRecordsInput = {
    * The API returns descending records; you can persist seqId as cursor.
    */
   seqId?: string;
+  /** 当前页码（从 1 开始，仅用于原始响应留档） */
+  page?: number;
 };
 
 export type FetchCharPoolRecordsResult = {
@@ -261,7 +269,7 @@ export async function fetchCharPoolRecords(
   input: FetchCharPoolRecordsInput,
   options?: EndfieldClientOptions,
 ): Promise<FetchCharPoolRecordsResult> {
-  const { lang, serverId, userAgent, fetcher, provider, signal } = pickOptions(options);
+  const { lang, serverId, userAgent, fetcher, provider, signal, onRawResponse } = pickOptions(options);
   const domain = providerToDomain(provider);
   const base = `https://ef-webview.${domain}/api/record/char`;
 
@@ -283,7 +291,9 @@ export async function fetchCharPoolRecords(
   throwIfAborted(signal);
   if (!res.ok) throw new HttpError('fetchCharPoolRecords failed', res.status, url);
 
-  const json = (await res.json()) as EndFieldCharGachaResponse;
+  const text = await res.text();
+  onRawResponse?.('char', text, input.page ?? 1);
+  const json = JSON.parse(text) as EndFieldCharGachaResponse;
   if (json.code !== 0) throw new Error(`fetchCharPoolRecords: api error code=${json.code} msg=${json.msg}`);
 
   const list = json.data?.list ?? [];
@@ -304,6 +314,8 @@ export type FetchWeaponPoolRecordsInput = {
   /** @deprecated 武器池不再区分类型 */
   poolType?: EndFieldWeaponPoolType;
   seqId?: string;
+  /** 当前页码（从 1 开始，仅用于原始响应留档） */
+  page?: number;
 };
 
 export type FetchWeaponPoolRecordsResult = {
@@ -321,7 +333,7 @@ export async function fetchWeaponPoolRecords(
   input: FetchWeaponPoolRecordsInput,
   options?: EndfieldClientOptions,
 ): Promise<FetchWeaponPoolRecordsResult> {
-  const { lang, serverId, userAgent, fetcher, provider, signal } = pickOptions(options);
+  const { lang, serverId, userAgent, fetcher, provider, signal, onRawResponse } = pickOptions(options);
   const domain = providerToDomain(provider);
   const base = `https://ef-webview.${domain}/api/record/weapon`;
 
@@ -343,7 +355,9 @@ export async function fetchWeaponPoolRecords(
   throwIfAborted(signal);
   if (!res.ok) throw new HttpError('fetchWeaponPoolRecords failed', res.status, url);
 
-  const json = (await res.json()) as EndFieldWeaponGachaResponse;
+  const text = await res.text();
+  onRawResponse?.('weapon', text, input.page ?? 1);
+  const json = JSON.parse(text) as EndFieldWeaponGachaResponse;
   if (json.code !== 0) throw new Error(`fetchWeaponPoolRecords: api error code=${json.code} msg=${json.msg}`);
 
   const list = json.data?.list ?? [];
@@ -430,12 +444,14 @@ export async function fetchAllCharPoolRecords(
 
   const all: EndFieldCharInfo[] = [];
   let seqId: string | undefined;
+  let pageNo = 0;
   let stoppedEarly = false;
 
   for (;;) {
     throwIfAborted(options?.signal);
+    pageNo += 1;
     const page = await fetchCharPoolRecords(
-      seqId ? { u8Token, poolType, seqId } : { u8Token, poolType },
+      seqId ? { u8Token, poolType, seqId, page: pageNo } : { u8Token, poolType, page: pageNo },
       options,
     );
 
@@ -488,12 +504,14 @@ export async function fetchAllWeaponPoolRecords(
 
   const all: EndFieldWeaponInfo[] = [];
   let seqId: string | undefined;
+  let pageNo = 0;
   let stoppedEarly = false;
 
   for (;;) {
     throwIfAborted(options?.signal);
+    pageNo += 1;
     const page = await fetchWeaponPoolRecords(
-      seqId ? { u8Token, seqId } : { u8Token },
+      seqId ? { u8Token, seqId, page: pageNo } : { u8Token, page: pageNo },
       options,
     );
 
@@ -674,6 +692,7 @@ export async function fetchAllGachaRecords(
     ...(options?.userAgent !== undefined && { userAgent: options.userAgent }),
     ...(options?.fetcher !== undefined && { fetcher: options.fetcher }),
     ...(options?.signal !== undefined && { signal: options.signal }),
+    ...(options?.onRawResponse !== undefined && { onRawResponse: options.onRawResponse }),
   };
   
   // 1. 拉取角色池
@@ -3,6 +3,22 @@ import type { UpdateInfo, UpdateStatus, UseUpdaterReturn } from '../useUpdater';
 
 export type UpdateCheckSource = 'auto' | 'manual';
 
+/** 更新失败原因（与 Rust 端 UpdateFailureKind 对应） */
+export type UpdateFailureKind = 'signature' | 'proxy_blocked' | 'permission_denied' | 'network' | 'other';
+
+/** 自动更新失败 / 停止提醒状态（get_update_status） */
+export interface UpdateFailureStatus {
+  currentVersion: string;
+  failedVersion: string | null;
+  failureKind: UpdateFailureKind | null;
+  failureCount: number;
+  /** 同一原因反复失败，已停止自动检查与提醒 */
+  suppressed: boolean;
+  /** 应显示一次"手动更新"提示 */
+  showManualNotice: boolean;
+  releaseUrl: string;
+}
+
 export type UpdateState = Pick<
   UseUpdaterReturn,
   'status' | 'updateInfo' | 'progress' | 'error' | 'downloadAndInstall' | 'restartApp'
@@ -20,6 +36,14 @@ export type UpdateState = Pick<
   /** 控制更新 Toast */
   toastOpen: boolean;
   setToastOpen: (open: boolean) => void;
+  /** 更新失败状态（尚未读取时为 null） */
+  failureStatus: UpdateFailureStatus | null;
+  /** 关闭"手动更新"提示（之后不再弹出） */
+  dismissManualNotice: () => Promise<void>;
+  /** 在浏览器中打开发布页 */
+  openReleasePage: () => Promise<void>;
+  /** 清除失败记录并重新检查 */
+  retryAfterFailures: () => Promise<void>;
 };
 
 export const UpdateContext = createContext<UpdateState | null>(null);
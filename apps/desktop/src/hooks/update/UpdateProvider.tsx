@@ -1,7 +1,7 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useUpdater } from '../useUpdater';
-import { UpdateContext, type UpdateCheckSource } from './UpdateContext';
+import { UpdateContext, type UpdateCheckSource, type UpdateFailureStatus } from './UpdateContext';
 
 const STORAGE_KEY_LAST_CHECKED_AT = 'efgh.updater.lastCheckedAt';
 
@@ -18,6 +18,7 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
 
   const [isPortable, setIsPortable] = useState(false);
   const [toastOpen, setToastOpen] = useState(false);
+  const [failureStatus, setFailureStatus] = useState<UpdateFailureStatus | null>(null);
   const [lastCheckedAt, setLastCheckedAt] = useState<number | null>(() => {
     const raw = localStorage.getItem(STORAGE_KEY_LAST_CHECKED_AT);
     const n = raw ? Number(raw) : NaN;
@@ -42,6 +43,13 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
     invoke<boolean>('is_portable').then(setIsPortable).catch(() => setIsPortable(true));
   }, []);
 
+  // 读取更新失败记录（同一原因反复失败后不再自动检查）
+  useEffect(() => {
+    invoke<UpdateFailureStatus>('get_update_status')
+      .then(setFailureStatus)
+      .catch((e) => console.error('读取更新状态失败:', e));
+  }, []);
+
   const persistLastCheckedAt = useCallback((ts: number) => {
     setLastCheckedAt(ts);
     localStorage.setItem(STORAGE_KEY_LAST_CHECKED_AT, String(ts));
@@ -63,6 +71,10 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
   const startupDelayMsRef = useRef(startupDelayMs);
   startupDelayMsRef.current = startupDelayMs;
 
+  // 用 ref 存储最新的失败状态，便于在定时器回调中判断是否停止自动检查
+  const failureStatusRef = useRef(failureStatus);
+  failureStatusRef.current = failureStatus;
+
   /**
    * 执行自动检查更新（内部使用）
    * 使用 ref 跟踪状态，避免被 effect 的重新运行打断或闭包问题
@@ -78,6 +90,11 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
     if (currentStatus === 'checking' || currentStatus === 'downloading') {
       return;
     }
+
+    // 同一原因反复失败（签名校验、代理拦截、无写入权限），不再自动检查，等用户手动重试
+    if (failureStatusRef.current?.suppressed) {
+      return;
+    }
     
     isAutoCheckingRef.current = true;
     lastCheckSourceRef.current = 'auto';
@@ -212,6 +229,42 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
     setToastOpen(true);
   }, [updater.status, updater.updateInfo]);
 
+  // 检查或下载失败：上报给 Rust 端分类计数
+  useEffect(() => {
+    if (updater.status !== 'error' || !updater.error) return;
+    invoke<UpdateFailureStatus>('report_update_failure', {
+      version: updater.updateInfo?.version ?? null,
+      error: updater.error,
+    })
+      .then(setFailureStatus)
+      .catch((e) => console.error('记录更新失败:', e));
+  }, [updater.status, updater.error, updater.updateInfo?.version]);
+
+  const dismissManualNotice = useCallback(async () => {
+    try {
+      setFailureStatus(await invoke<UpdateFailureStatus>('dismiss_update_notice'));
+    } catch (e) {
+      console.error('关闭手动更新提示失败:', e);
+    }
+  }, []);
+
+  const openReleasePage = useCallback(async () => {
+    try {
+      await invoke('open_release_page');
+    } catch (e) {
+      console.error('打开发布页失败:', e);
+    }
+  }, []);
+
+  const retryAfterFailures = useCallback(async () => {
+    try {
+      setFailureStatus(await invoke<UpdateFailureStatus>('reset_update_failures'));
+    } catch (e) {
+      console.error('清除更新失败记录失败:', e);
+    }
+    await checkForUpdate('manual');
+  }, [checkForUpdate]);
+
   // 下载完成后：也弹一次 toast 提示重启
   useEffect(() => {
     if (updater.status !== 'ready') return;
@@ -240,6 +293,10 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
       checkForUpdate,
       toastOpen,
       setToastOpen,
+      failureStatus,
+      dismissManualNotice,
+      openReleasePage,
+      retryAfterFailures,
     }),
     [
       updater.status,
@@ -254,6 +311,10 @@ export function UpdateProvider({ children, startupDelayMs = 2500 }: Props) {
       nextAutoCheckAt,
       checkForUpdate,
       toastOpen,
+      failureStatus,
+      dismissManualNotice,
+      openReleasePage,
+      retryAfterFailures,
     ]
   );
 
@@ -3,6 +3,7 @@
  */
 
 import { useState, useCallback, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import type { 
   BindingAccount, 
   EndFieldCharPoolType, 
@@ -20,7 +21,8 @@ import {
   EndfieldRiskControlError,
   HttpError,
 } from '../features/endfield/endfieldApi';
-import { tauriFetcher } from '../lib/tauriHttp';
+import { saveRawResponse, tauriFetcher } from '../lib/tauriHttp';
+import { prefetchIcons } from '../lib/icons';
 import {
   saveAppToken,
   getAppToken,
@@ -60,7 +62,25 @@ export type SyncProgress = {
   /** 总记录数 */
   recordsFetched?: number;
   error?: string;
-  errorCode?: 'account_token_expired';
+  errorCode?: 'account_token_expired' | 'db_av_interference';
+  /** 本次同步耗时统计（完成时由 Rust 端写入同步历史后返回） */
+  stats?: SyncRunStats;
+};
+
+/** 单次同步耗时统计（与 Rust 端 SyncRun 对应） */
+export type SyncRunStats = {
+  /** 同步批次 id（可用 get_records_since 查询本次新增的记录） */
+  id: number;
+  uid: string;
+  startedAt: number;
+  /** 全过程耗时（含鉴权、网络拉取与写库） */
+  totalMs: number;
+  networkMs: number;
+  dbWriteMs: number;
+  /** 本次从接口拉取并处理的记录数 */
+  recordsProcessed: number;
+  recordsAdded: number;
+  recordsPerSecond: number;
 };
 
 const defaultOptions = { fetcher: tauriFetcher };
@@ -232,6 +252,14 @@ export function useGachaSync() {
     }
     const controller = new AbortController();
     abortRef.current = controller;
+    const startedAt = Date.now();
+    let networkMs = 0;
+    let dbWriteMs = 0;
+    let succeeded = false;
+    // 托盘图标进度（Rust 端节流重绘）；拉取阶段占 90%，写库占剩余部分
+    const reportTrayProgress = (progress: number) => {
+      void invoke('set_tray_progress', { progress }).catch(() => {});
+    };
 
     lockInteraction({
       titleKey: 'sync.lockTitle',
@@ -276,7 +304,9 @@ export function useGachaSync() {
 
       // 1. 获取 u8_token
       setProgress({ status: 'authenticating' });
+      let phaseStart = performance.now();
       const u8Token = await fetchU8TokenByUid(hgUid, appToken, { ...options, signal: controller.signal });
+      networkMs += performance.now() - phaseStart;
 
       // 2. 构建已存在记录的 seqId 集合（用于增量同步）
       const existingCharSeqIdsByPool = await buildExistingCharSeqIdsByPool(uid);
@@ -291,9 +321,12 @@ export function useGachaSync() {
         recordsFetched: 0,
       });
 
+      phaseStart = performance.now();
       const allRecords = await fetchAllGachaRecords(u8Token, {
         ...options,
         signal: controller.signal,
+        // 原始响应按账号与页码留档，记录溯源时据此找回原文
+        onRawResponse: (kind, body, page) => saveRawResponse(uid, kind, body, page),
         // 分页请求延迟（防风控）
         minDelayMs: 800,
         maxDelayMs: 1500,
@@ -311,6 +344,7 @@ export function useGachaSync() {
         onProgress: (category, poolType, poolIndex, _totalPools, recordsFetched) => {
           // 计算总进度：角色池 3 个 + 武器池 1 个
           const currentPoolIndex = category === 'weapon' ? END_FIELD_CHAR_POOL_TYPES.length + 1 : poolIndex;
+          reportTrayProgress(((currentPoolIndex - 1) / TOTAL_POOLS) * 0.9);
           
           setProgress({
             status: 'fetching_records',
@@ -325,10 +359,16 @@ export function useGachaSync() {
         },
       });
 
+      networkMs += performance.now() - phaseStart;
+      reportTrayProgress(0.9);
+
       // 4. 保存角色记录
+      phaseStart = performance.now();
+      let recordsProcessed = 0;
       for (const poolType of END_FIELD_CHAR_POOL_TYPES) {
         const records = allRecords.character[poolType];
         if (records && records.length > 0) {
+          recordsProcessed += records.length;
           const added = await addGachaRecords(uid, records);
           charAdded += added;
         }
@@ -337,17 +377,62 @@ export function useGachaSync() {
       // 5. 保存武器记录（武器池只有一个类型）
       const weaponRecords = allRecords.weapon['E_WeaponGachaPoolType_All'];
       if (weaponRecords && weaponRecords.length > 0) {
+        recordsProcessed += weaponRecords.length;
         const added = await addWeaponRecords(uid, weaponRecords);
         weaponAdded += added;
       }
 
+      // 系统时间异常时，标注本次入库记录的本地时间不可信
+      if (charAdded + weaponAdded > 0) {
+        await invoke('annotate_skewed_records').catch((e: unknown) => {
+          console.warn('[useGachaSync] annotate_skewed_records failed:', e);
+        });
+      }
+
+      // 把新入库的记录追加到防篡改哈希链
+      if (charAdded + weaponAdded > 0) {
+        await invoke('seal_hash_chain', { uid }).catch((e: unknown) => {
+          console.warn('[useGachaSync] seal_hash_chain failed:', e);
+        });
+      }
+      dbWriteMs += performance.now() - phaseStart;
+
+      // 后台预下载新出现物品的图标（未配置图标 CDN 时 Rust 端直接返回）
+      if (charAdded + weaponAdded > 0) {
+        void prefetchIcons([uid]).catch((e: unknown) => {
+          console.warn('[useGachaSync] prefetch_icons failed:', e);
+        });
+      }
+
+      // 刷新 OBS 文本导出（未启用时 Rust 端直接返回）
+      await invoke('refresh_text_export').catch((e: unknown) => {
+        console.warn('[useGachaSync] refresh_text_export failed:', e);
+      });
+
       const totalAdded = charAdded + weaponAdded;
+
+      // 写入同步历史（失败不影响本次同步结果）
+      const stats = await invoke<SyncRunStats>('record_sync_run', {
+        uid,
+        startedAt,
+        totalMs: Date.now() - startedAt,
+        networkMs: Math.round(networkMs),
+        dbWriteMs: Math.round(dbWriteMs),
+        recordsProcessed,
+        recordsAdded: totalAdded,
+      }).catch((e: unknown) => {
+        console.warn('[useGachaSync] record_sync_run failed:', e);
+        return undefined;
+      });
+
       setProgress({ 
         status: 'done', 
         charRecordsFetched: charAdded,
         weaponRecordsFetched: weaponAdded,
         recordsFetched: totalAdded,
+        stats,
       });
+      succeeded = true;
       return totalAdded;
     } catch (err) {
       console.error('[useGachaSync] Sync error:', err);
@@ -379,6 +464,14 @@ export function useGachaSync() {
           message = '网络连接失败，请检查网络设置';
         } else {
           message = err.message;
+          // 写库失败时检查是否为杀毒软件锁定/隔离数据库文件
+          const avMessage = await invoke<string | null>('diagnose_db_write_error', {
+            error: err.message,
+          }).catch(() => null);
+          if (avMessage) {
+            message = avMessage;
+            errorCode = 'db_av_interference';
+          }
         }
       }
       setProgress(
@@ -390,6 +483,7 @@ export function useGachaSync() {
     } finally {
       abortRef.current = null;
       unlockInteractionLock();
+      void invoke('finish_tray_progress', { success: succeeded }).catch(() => {});
     }
   }, []);
 
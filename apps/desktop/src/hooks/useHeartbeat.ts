@@ -0,0 +1,23 @@
+/**
+ * 前端心跳 Hook
+ * 每 10 秒通知 Rust 端当前窗口仍在响应（用于无响应检测与事件限流）
+ */
+
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+/** 心跳间隔（与 Rust 端 HEARTBEAT_INTERVAL_MS 一致） */
+const HEARTBEAT_INTERVAL_MS = 10_000;
+
+export function useHeartbeat(): void {
+  useEffect(() => {
+    const beat = () => {
+      void invoke('heartbeat').catch((e: unknown) => {
+        console.error('[heartbeat] Failed to send heartbeat:', e);
+      });
+    };
+    beat();
+    const timer = window.setInterval(beat, HEARTBEAT_INTERVAL_MS);
+    return () => window.clearInterval(timer);
+  }, []);
+}
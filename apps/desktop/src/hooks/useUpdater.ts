@@ -3,8 +3,10 @@
  */
 
 import { useState, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { check, type Update } from '@tauri-apps/plugin-updater';
 import { relaunch } from '@tauri-apps/plugin-process';
+import { errorMessage } from '../lib/errors';
 
 /** 更新状态 */
 export type UpdateStatus = 
@@ -91,7 +93,7 @@ export function useUpdater(): UseUpdaterReturn {
       }
     } catch (err) {
       console.error('检查更新失败:', err);
-      setError(err instanceof Error ? err.message : '检查更新失败');
+      setError(errorMessage(err) || '检查更新失败');
       setStatus('error');
     }
   }, []);
@@ -109,6 +111,9 @@ export function useUpdater(): UseUpdaterReturn {
     setError(null);
 
     try {
+      // 空间不足时直接报错（disk-full），不开始下载
+      await invoke('check_update_disk_space');
+
       let downloaded = 0;
       let contentLength = 0;
 
@@ -132,7 +137,7 @@ export function useUpdater(): UseUpdaterReturn {
       setStatus('ready');
     } catch (err) {
       console.error('下载更新失败:', err);
-      setError(err instanceof Error ? err.message : '下载更新失败');
+      setError(errorMessage(err) || '下载更新失败');
       setStatus('error');
     }
   }, [pendingUpdate]);
@@ -143,7 +148,7 @@ export function useUpdater(): UseUpdaterReturn {
       await relaunch();
     } catch (err) {
       console.error('重启应用失败:', err);
-      setError(err instanceof Error ? err.message : '重启应用失败');
+      setError(errorMessage(err) || '重启应用失败');
       setStatus('error');
     }
   }, []);
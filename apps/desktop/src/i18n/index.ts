@@ -15,6 +15,7 @@ const resources = {
         account: '账号管理',
         cloudSync: '云同步',
         leaderboard: '排行榜',
+        compare: '欧气对比',
         settings: '设置',
         about: '关于',
       },
@@ -59,6 +60,59 @@ const resources = {
         reset: '重置为每次询问',
         resetSuccess: '已重置为每次询问',
       },
+      // 演示模式
+      demoMode: {
+        badge: '演示模式',
+        exitHint: '演示期间的修改不会保存，点击退出演示模式',
+        title: '演示模式',
+        desc: '录屏或演示时使用：修改只写入临时数据库，退出后自动丢弃，真实数据不受影响',
+        enable: '进入演示',
+        disable: '退出演示',
+      },
+      // 统计时区
+      displayTimezone: {
+        title: '统计时区',
+        desc: '按日、按月统计时使用的时区，记录本身仍以 UTC 保存',
+        system: '跟随系统',
+      },
+      // 接口原始响应留档
+      rawResponses: {
+        title: '保留接口原始响应',
+        desc: '同步时把抽卡接口返回的原始数据保存到 userdata/raw/，用于排查记录解析问题',
+        keepDays: '保留 {{days}} 天',
+      },
+      // 云同步接口
+      syncEndpoints: {
+        title: '同步接口',
+        desc: '每行一个地址，第一个为主接口；主接口连续失败时自动切换到备用接口。留空使用内置地址',
+        saved: '同步接口已保存',
+        active: '当前使用',
+        failures: '连续失败 {{count}} 次',
+      },
+      // 换池提醒
+      bannerWatch: {
+        title: '换池提醒',
+        beforeTitle: '即将换池',
+        afterTitle: '新卡池已开放',
+        beforeDesc: '卡池将于 {{time}} 切换，记得同步当前卡池的记录',
+        afterDesc: '卡池已于 {{time}} 切换',
+        autoSyncing: '已自动开始同步',
+        syncNow: '立即同步',
+        autoSync: '提醒时自动同步',
+        next: '下次换池：{{time}}',
+        noUpcoming: '暂无已知的换池时间',
+        stale: '卡池排期已过期，获取到新排期后恢复提醒',
+      },
+      // 系统时间异常
+      clockSkew: {
+        title: '系统时间异常',
+        behindDesc: '系统时间比服务器慢了 {{offset}}，已改用服务器时间计算，请校准系统时间',
+        aheadDesc: '系统时间比服务器快了 {{offset}}，已改用服务器时间计算，请校准系统时间',
+        serverTime: '服务器时间：{{time}}',
+        minutes: '{{count}} 分钟',
+        hours: '{{count}} 小时',
+        days: '{{count}} 天',
+      },
       // 托盘菜单
       tray: {
         showWindow: '显示主界面',
@@ -66,6 +120,16 @@ const resources = {
         syncEnabled: '同步已启用',
         syncDisabled: '同步已关闭',
         quit: '退出程序',
+        user: '用户',
+        cloudLoggedIn: '已登录云同步',
+        autoSync: '自动同步',
+        cloudNotLoggedIn: '云同步未登录',
+        cloudLoginHint: '登录后可跨设备同步数据',
+        loginCloudSync: '登录云同步账号',
+        exportLatest: '快速导出',
+        openDataDir: '打开数据目录',
+        copySummary: '复制抽卡摘要',
+        activeAccount: '当前',
       },
       common: {
         loading: '加载中...',
@@ -136,6 +200,16 @@ const resources = {
         checking: '正在检查服务状态...',
         serviceOnline: '服务运行正常',
         serviceOffline: '服务暂时不可用',
+        diagnose: '网络诊断',
+        diagnosing: '诊断中...',
+        diagnoseFailed: '诊断失败',
+        diagnoseAllOk: '各项检测均通过',
+        diagnoseFailedAt: '在「{{stage}}」步骤失败',
+        diagnoseStageDns: 'DNS 解析',
+        diagnoseStageTcp: 'TCP 连接',
+        diagnoseStageTls: 'TLS 握手',
+        diagnoseStageHttp: 'HTTP 请求',
+        diagnoseSkipped: '已跳过',
         refresh: '刷新',
         accountTitle: '同步账号',
         loggedInAs: '已登录',
@@ -236,6 +310,8 @@ const resources = {
         noAccountTip: '点击添加游戏账号',
         tokenLabel: '账号 Token',
         tokenPlaceholder: '请输入从 {{providerName}} 获取的 Token',
+        urlUidSelected: '已根据链接选中 UID {{uid}} 对应的账号',
+        urlUidUnknown: '链接中的 UID {{uid}} 不属于已添加的账号',
         tokenHint: '此 Token 仅用于获取抽卡记录，不会存储或上传',
         addButton: '添加账号',
         howToGetToken: '如何获取 Token？',
@@ -255,6 +331,8 @@ const resources = {
         verifying: '验证中...',
         emptyTitle: '暂无账号',
         deleteAccountTitle: '删除账号',
+        renameTitle: '修改昵称',
+        renameDesc: '手动设置的昵称不会被导入文件覆盖',
         uidPrefix: 'UID: ',
         levelPrefix: 'Lv.',
         tokenApiUrl: 'https://web-api.hypergryph.com/account/info/hg',
@@ -267,6 +345,7 @@ const resources = {
         noAccounts: '暂无已添加的账号',
         current: '当前',
         confirmDelete: '确定要删除此账号吗？删除后该账号的抽卡记录仍会保留。',
+        deleteRecordsToo: '同时删除该账号的全部抽卡记录与同步历史（不可撤销）',
         addSuccess: '账号添加成功！',
       },
       // 同步页面
@@ -294,6 +373,8 @@ const resources = {
         fetched: '已获取',
         success: '同步完成',
         successDetail: '成功获取 {{count}} 条新记录',
+        durationDetail: '本次同步耗时 {{seconds}}s，处理 {{count}} 条',
+        durationBreakdown: '网络 {{network}}s / 写库 {{dbWrite}}s，{{rate}} 条/秒',
         error: '同步失败',
         accountTokenExpiredTitle: '账号 Token 已过期',
         accountTokenExpiredDesc: '账号 Token 已经过期，请前往账号管理重新添加。',
@@ -326,6 +407,7 @@ const resources = {
         empty: '暂无抽卡记录',
         emptyHint: '点击下方按钮同步记录',
         goSync: '去同步',
+        newFromSync: '本次同步新增 {{count}} 条（最高 {{rarity}}★）',
         searchPlaceholder: '搜索名称...',
         // 类别筛选
         allCategories: '全部类别',
@@ -416,6 +498,18 @@ const resources = {
           sharedPityTitle: '特许寻访共享保底',
           currentPityLabel: '当前 {{count}} 抽',
           cumulativeConsume: '累计消耗',
+          resourceEstimate: {
+            title: '资源估算',
+            breakdown: '付费 {{paid}} 抽 · 免费 {{free}} 抽（价值 {{freeValue}}）',
+            unclassifiedHint: '其中 {{count}} 抽无法区分付费/免费（武器池记录），已按总数计入消耗。',
+            notConfigured: '设置单抽价格后显示估算',
+            edit: '设置',
+            save: '保存',
+            pricePerPull: '单抽价格',
+            pricePlaceholder: '留空则不估算',
+            currency: '货币',
+            invalidPrice: '单抽价格须为非负数',
+          },
           originium: '嵌晶玉',
           armoryQuota: '武库配额',
           armoryQuotaProduced: '累计产出',
@@ -546,6 +640,13 @@ const resources = {
         versionUpdateDesc: '检查并安装最新版本',
         legal: '法律声明',
         legalDesc: '免责声明与用户协议',
+        resourceUsage: '资源占用',
+        resourceUsageDesc: '主进程的资源占用（不含 WebView 渲染进程）',
+        memoryUsage: '内存占用',
+        peakMemory: '内存峰值',
+        dbConnections: '数据库连接',
+        backgroundTasks: '后台任务',
+        refreshUsage: '刷新',
       },
       updater: {
         // badge
@@ -576,6 +677,18 @@ const resources = {
         recheck: '重新检查',
         tipTitle: '提示',
         tipBody: '启动时与每 12 小时会自动检查更新；发现新版本会在右下角提醒。',
+        manualTitle: '手动更新',
+        manualDesc: '{{reason}}，已停止自动提醒，请前往发布页下载新版本。',
+        suppressedDesc: '{{reason}}，已停止自动检查更新。可前往发布页手动下载，或在网络环境变化后重试。',
+        openReleasePage: '打开发布页',
+        tryAgain: '再试一次',
+        failureKind: {
+          signature: '更新包签名校验多次失败',
+          proxy_blocked: '更新下载多次被代理或网关拦截',
+          permission_denied: '多次没有权限写入安装目录',
+          network: '自动更新多次因网络问题失败',
+          other: '自动更新多次失败',
+        },
       },
       // 设置页面
       settings: {
@@ -593,15 +706,45 @@ const resources = {
         exportJSON: '导出 JSON',
         importJSON: '导入 JSON',
         exportCSV: '导出 CSV',
+        otherFormats: '其他格式',
+        exportUigf: '导出 UIGF',
+        exportWwCompat: '导出兼容格式',
+        exportPdf: '导出 PDF 报告',
+        exportProgress: '正在导出… {{done}}/{{total}}',
+        cancelExport: '取消导出',
+        exportCancelled: '导出已取消，未写入文件',
         importCSV: '导入 CSV',
         exportSuccess: '数据导出成功',
         csvExportSuccess: 'CSV 导出成功',
         exportError: '导出失败，请重试',
         importSuccess: '导入成功：{{accounts}} 个账号，{{records}} 条新记录',
+        importNickNamesUpdated: '已按文件更新 {{count}} 个账号的昵称',
+        importNickNamesKept: '{{count}} 个账号已手动设置昵称，未被覆盖',
         csvImportSuccess: 'CSV 导入成功：{{charRecords}} 条干员记录，{{weaponRecords}} 条武器记录',
         importError: '导入失败，请检查文件格式',
+        importUidMismatchTitle: '账号不一致',
+        importUidMismatch: '导入文件中的账号与当前数据不一致，可能选错了文件。仍要导入吗？',
+        importForce: '仍然导入',
+        importPreviewTitle: '导入预览',
+        importPreviewDesc: '确认后将写入以下数据，已存在的记录会被跳过。',
+        importReplaceMode: '覆盖模式：清空文件中账号的现有记录（自动备份）',
+        importReplaceDesc: '确认后将先备份数据库，再清空以下账号的现有记录，导入后只保留文件中的数据。',
+        importConfirm: '确认导入',
+        importPreviewAccounts: '账号',
+        importPreviewCharRecords: '干员记录',
+        importPreviewWeaponRecords: '武器记录',
+        importPreviewSkipped: '跳过 {{count}}',
+        importPreviewAddedSamples: '新增样本',
+        importPreviewSkippedSamples: '跳过样本（已存在）',
+        importUnresolvedItems: '以下物品名无法识别物品 id，统计 UP 时按非 UP 处理：{{names}}',
         dataInfo: 'JSON 格式包含完整数据，可用于备份和恢复',
         csvInfo: 'CSV 格式适合在 Excel 中查看，也可重新导入本软件',
+        archiveTitle: '归档旧记录',
+        archiveDesc: '超出官方接口保留期的记录无法再与服务器核对，归档后修复类操作默认跳过，缺口检查也只作提示。',
+        archiveBefore: '归档此日期之前的记录',
+        archiveAction: '归档',
+        archiveConfirm: '{{date}} 之前的记录将被标记为已归档，之后去重、批量删除、卡池纠正等修复操作会跳过它们。归档不可撤销。',
+        archiveSuccess: '已归档 {{count}} 条记录',
         openFolder: '打开文件夹',
         dangerZone: '危险操作',
         dangerDesc: '以下操作不可撤销，请谨慎操作',
@@ -658,6 +801,7 @@ const resources = {
         account: 'Account',
         cloudSync: 'Cloud Sync',
         leaderboard: 'Leaderboard',
+        compare: 'Luck Compare',
         settings: 'Settings',
         about: 'About',
       },
@@ -702,6 +846,59 @@ const resources = {
         reset: 'Reset to ask every time',
         resetSuccess: 'Reset to ask every time',
       },
+      // Demo mode
+      demoMode: {
+        badge: 'Demo Mode',
+        exitHint: 'Changes made during the demo are not saved. Click to exit demo mode',
+        title: 'Demo Mode',
+        desc: 'For recordings and demos: changes go to a temporary database and are discarded on exit; your real data is untouched',
+        enable: 'Start Demo',
+        disable: 'Exit Demo',
+      },
+      // Stats timezone
+      displayTimezone: {
+        title: 'Stats timezone',
+        desc: 'Timezone used for daily and monthly stats; records are still stored in UTC',
+        system: 'System default',
+      },
+      // Raw API response archive
+      rawResponses: {
+        title: 'Keep Raw API Responses',
+        desc: 'Save the raw gacha API responses to userdata/raw/ while syncing, to debug record parsing issues',
+        keepDays: 'Keep for {{days}} days',
+      },
+      // Cloud sync endpoints
+      syncEndpoints: {
+        title: 'Sync Endpoints',
+        desc: 'One URL per line; the first is primary. Backups are used automatically after repeated failures. Leave empty to use the built-in server',
+        saved: 'Sync endpoints saved',
+        active: 'In use',
+        failures: '{{count}} consecutive failures',
+      },
+      // Banner transition reminders
+      bannerWatch: {
+        title: 'Banner Change Reminder',
+        beforeTitle: 'Banner changing soon',
+        afterTitle: 'New banner is live',
+        beforeDesc: 'Banners change at {{time}}. Remember to sync the current banner',
+        afterDesc: 'Banners changed at {{time}}',
+        autoSyncing: 'Sync started automatically',
+        syncNow: 'Sync Now',
+        autoSync: 'Sync automatically when reminded',
+        next: 'Next change: {{time}}',
+        noUpcoming: 'No known upcoming banner change',
+        stale: 'Banner schedule is outdated; reminders resume once a new schedule is available',
+      },
+      // System clock skew
+      clockSkew: {
+        title: 'System clock is off',
+        behindDesc: 'Your system clock is {{offset}} behind the server. Server time is used instead; please correct your system clock',
+        aheadDesc: 'Your system clock is {{offset}} ahead of the server. Server time is used instead; please correct your system clock',
+        serverTime: 'Server time: {{time}}',
+        minutes: '{{count}} min',
+        hours: '{{count}} h',
+        days: '{{count}} days',
+      },
       // Tray menu
       tray: {
         showWindow: 'Show Window',
@@ -709,6 +906,16 @@ const resources = {
         syncEnabled: 'Sync Enabled',
         syncDisabled: 'Sync Disabled',
         quit: 'Quit',
+        user: 'User',
+        cloudLoggedIn: 'Signed in to cloud sync',
+        autoSync: 'Auto sync',
+        cloudNotLoggedIn: 'Not signed in to cloud sync',
+        cloudLoginHint: 'Sign in to sync data across devices',
+        loginCloudSync: 'Sign in to cloud sync',
+        exportLatest: 'Quick export',
+        openDataDir: 'Open data folder',
+        copySummary: 'Copy pull summary',
+        activeAccount: 'Active',
       },
       common: {
         loading: 'Loading...',
@@ -781,6 +988,16 @@ const resources = {
         checking: 'Checking service status...',
         serviceOnline: 'Service is running',
         serviceOffline: 'Service temporarily unavailable',
+        diagnose: 'Diagnose network',
+        diagnosing: 'Diagnosing...',
+        diagnoseFailed: 'Diagnosis failed',
+        diagnoseAllOk: 'All checks passed',
+        diagnoseFailedAt: 'Failed at "{{stage}}"',
+        diagnoseStageDns: 'DNS lookup',
+        diagnoseStageTcp: 'TCP connect',
+        diagnoseStageTls: 'TLS handshake',
+        diagnoseStageHttp: 'HTTP request',
+        diagnoseSkipped: 'Skipped',
         refresh: 'Refresh',
         accountTitle: 'Sync Account',
         loggedInAs: 'Logged in',
@@ -881,6 +1098,8 @@ const resources = {
         noAccountTip: 'Click to add account',
         tokenLabel: 'Account Token',
         tokenPlaceholder: 'Enter token from {{providerName}}',
+        urlUidSelected: 'Selected the account for UID {{uid}} from the link',
+        urlUidUnknown: 'UID {{uid}} in the link does not match any added account',
         tokenHint: 'This token is only used to fetch gacha records and won\'t be stored or uploaded',
         addButton: 'Add Account',
         howToGetToken: 'How to get token?',
@@ -900,6 +1119,8 @@ const resources = {
         verifying: 'Verifying...',
         emptyTitle: 'No accounts',
         deleteAccountTitle: 'Delete account',
+        renameTitle: 'Rename',
+        renameDesc: 'Manually set nicknames are never overwritten by imported files',
         uidPrefix: 'UID: ',
         levelPrefix: 'Lv.',
         tokenApiUrl: 'https://web-api.hypergryph.com/account/info/hg',
@@ -912,6 +1133,7 @@ const resources = {
         noAccounts: 'No accounts added',
         current: 'Current',
         confirmDelete: 'Are you sure to delete this account? Gacha records will be preserved.',
+        deleteRecordsToo: 'Also delete all gacha records and sync history of this account (cannot be undone)',
         addSuccess: 'Account added successfully!',
       },
       // Sync page
@@ -939,6 +1161,8 @@ const resources = {
         fetched: 'Fetched',
         success: 'Sync Complete',
         successDetail: 'Successfully fetched {{count}} new records',
+        durationDetail: 'Sync took {{seconds}}s, processed {{count}} records',
+        durationBreakdown: 'network {{network}}s / database {{dbWrite}}s, {{rate}} records/s',
         error: 'Sync Failed',
         accountTokenExpiredTitle: 'Account Token Expired',
         accountTokenExpiredDesc: 'Your account token has expired. Please go to Account Management and add the account again.',
@@ -970,6 +1194,7 @@ const resources = {
         empty: 'No records yet',
         emptyHint: 'Click button below to sync records',
         goSync: 'Sync Now',
+        newFromSync: '{{count}} new from this sync (best {{rarity}}★)',
         searchPlaceholder: 'Search name...',
         // Category filter
         allCategories: 'All Categories',
@@ -1060,6 +1285,18 @@ const resources = {
           sharedPityTitle: 'Shared Pity (Special Recruitment)',
           currentPityLabel: 'Current: {{count}} pulls',
           cumulativeConsume: 'Total spent',
+          resourceEstimate: {
+            title: 'Estimated cost',
+            breakdown: 'Paid {{paid}} · Free {{free}} (worth {{freeValue}})',
+            unclassifiedHint: '{{count}} pulls cannot be classified as paid or free (weapon banner records) and are counted as spent.',
+            notConfigured: 'Set a per-pull price to see an estimate',
+            edit: 'Set',
+            save: 'Save',
+            pricePerPull: 'Price per pull',
+            pricePlaceholder: 'Leave empty to disable',
+            currency: 'Currency',
+            invalidPrice: 'Price per pull must be a non-negative number',
+          },
           originium: 'Originium',
           armoryQuota: 'Armory Quota',
           armoryQuotaProduced: 'Total obtained',
@@ -1190,6 +1427,13 @@ const resources = {
         versionUpdateDesc: 'Check and install the latest version',
         legal: 'Legal',
         legalDesc: 'Disclaimer and terms of service',
+        resourceUsage: 'Resource usage',
+        resourceUsageDesc: 'Resources used by the main process (WebView renderer processes excluded)',
+        memoryUsage: 'Memory',
+        peakMemory: 'Peak memory',
+        dbConnections: 'DB connections',
+        backgroundTasks: 'Background tasks',
+        refreshUsage: 'Refresh',
       },
       updater: {
         badgeText: 'NEW',
@@ -1217,6 +1461,18 @@ const resources = {
         recheck: 'Re-check',
         tipTitle: 'Tip',
         tipBody: 'The app checks on startup and every 12 hours; updates will be shown in a bottom-right notification.',
+        manualTitle: 'Manual update',
+        manualDesc: '{{reason}}. Automatic prompts are paused; please download the new version from the release page.',
+        suppressedDesc: '{{reason}}. Automatic update checks are paused. Download manually from the release page, or try again after your network changes.',
+        openReleasePage: 'Open release page',
+        tryAgain: 'Try again',
+        failureKind: {
+          signature: 'The update signature check failed repeatedly',
+          proxy_blocked: 'The update download was repeatedly blocked by a proxy or gateway',
+          permission_denied: 'The install directory could not be written repeatedly',
+          network: 'Automatic update failed repeatedly due to network errors',
+          other: 'Automatic update failed repeatedly',
+        },
       },
       // Settings page
       settings: {
@@ -1234,15 +1490,45 @@ const resources = {
         exportJSON: 'Export JSON',
         importJSON: 'Import JSON',
         exportCSV: 'Export CSV',
+        otherFormats: 'Other formats',
+        exportUigf: 'Export UIGF',
+        exportWwCompat: 'Export compatible format',
+        exportPdf: 'Export PDF report',
+        exportProgress: 'Exporting… {{done}}/{{total}}',
+        cancelExport: 'Cancel export',
+        exportCancelled: 'Export cancelled; no file was written',
         importCSV: 'Import CSV',
         exportSuccess: 'Data exported successfully',
         csvExportSuccess: 'CSV exported successfully',
         exportError: 'Export failed, please retry',
         importSuccess: 'Imported: {{accounts}} accounts, {{records}} new records',
+        importNickNamesUpdated: 'Updated nicknames of {{count}} accounts from the file',
+        importNickNamesKept: 'Kept manually set nicknames for {{count}} accounts',
         csvImportSuccess: 'CSV imported: {{charRecords}} character records, {{weaponRecords}} weapon records',
         importError: 'Import failed, please check file format',
+        importUidMismatchTitle: 'Account mismatch',
+        importUidMismatch: 'The accounts in this file do not match your current data. You may have picked the wrong file. Import anyway?',
+        importForce: 'Import anyway',
+        importPreviewTitle: 'Import preview',
+        importPreviewDesc: 'The following data will be written after you confirm. Existing records are skipped.',
+        importReplaceMode: 'Replace mode: clear existing records of the accounts in the file (backed up first)',
+        importReplaceDesc: 'The database will be backed up, then existing records of these accounts are cleared. Only the data in the file will remain.',
+        importConfirm: 'Import',
+        importPreviewAccounts: 'Accounts',
+        importPreviewCharRecords: 'Operator records',
+        importPreviewWeaponRecords: 'Weapon records',
+        importPreviewSkipped: '{{count}} skipped',
+        importPreviewAddedSamples: 'New records (sample)',
+        importPreviewSkippedSamples: 'Skipped records (already present)',
+        importUnresolvedItems: 'Could not match these item names to item ids; they count as off-banner: {{names}}',
         dataInfo: 'JSON format contains complete data for backup and restore',
         csvInfo: 'CSV format is suitable for viewing in Excel, and can be re-imported',
+        archiveTitle: 'Archive old records',
+        archiveDesc: 'Records older than the official API retention can no longer be verified. Archived records are skipped by repair operations, and gaps among them are informational only.',
+        archiveBefore: 'Archive records before this date',
+        archiveAction: 'Archive',
+        archiveConfirm: 'Records before {{date}} will be marked as archived and skipped by dedup, batch delete and banner reassignment. This cannot be undone.',
+        archiveSuccess: 'Archived {{count}} records',
         openFolder: 'Open Folder',
         dangerZone: 'Danger Zone',
         dangerDesc: 'The following actions are irreversible',
@@ -1299,6 +1585,7 @@ const resources = {
         account: 'アカウント',
         cloudSync: 'クラウド同期',
         leaderboard: 'ランキング',
+        compare: '運気比較',
         settings: '設定',
         about: '情報',
       },
@@ -1343,6 +1630,59 @@ const resources = {
         reset: '毎回確認にリセット',
         resetSuccess: '毎回確認にリセットしました',
       },
+      // デモモード
+      demoMode: {
+        badge: 'デモモード',
+        exitHint: 'デモ中の変更は保存されません。クリックでデモモードを終了',
+        title: 'デモモード',
+        desc: '録画やデモ用：変更は一時データベースにのみ書き込まれ、終了時に破棄されます。実データには影響しません',
+        enable: 'デモを開始',
+        disable: 'デモを終了',
+      },
+      // 統計のタイムゾーン
+      displayTimezone: {
+        title: '統計のタイムゾーン',
+        desc: '日別・月別の統計に使うタイムゾーン（記録自体は UTC で保存）',
+        system: 'システムに従う',
+      },
+      // API 生レスポンスの保存
+      rawResponses: {
+        title: 'API の生レスポンスを保存',
+        desc: '同期時にガチャ API の生データを userdata/raw/ に保存し、記録の解析問題の調査に使います',
+        keepDays: '{{days}} 日間保存',
+      },
+      // クラウド同期エンドポイント
+      syncEndpoints: {
+        title: '同期エンドポイント',
+        desc: '1 行に 1 つの URL を入力します。先頭がメインで、連続して失敗するとバックアップに自動で切り替えます。空欄の場合は内蔵サーバーを使用します',
+        saved: '同期エンドポイントを保存しました',
+        active: '使用中',
+        failures: '{{count}} 回連続で失敗',
+      },
+      // ピックアップ切替リマインダー
+      bannerWatch: {
+        title: 'ピックアップ切替通知',
+        beforeTitle: 'まもなくピックアップ切替',
+        afterTitle: '新しいピックアップが開始',
+        beforeDesc: '{{time}} にピックアップが切り替わります。現在の記録を同期しましょう',
+        afterDesc: '{{time}} にピックアップが切り替わりました',
+        autoSyncing: '自動で同期を開始しました',
+        syncNow: '今すぐ同期',
+        autoSync: '通知時に自動で同期',
+        next: '次回の切替：{{time}}',
+        noUpcoming: '予定されている切替はありません',
+        stale: 'ピックアップ日程が古くなっています。新しい日程の取得後に通知を再開します',
+      },
+      // システム時刻のずれ
+      clockSkew: {
+        title: 'システム時刻がずれています',
+        behindDesc: 'システム時刻がサーバーより {{offset}} 遅れています。サーバー時刻で計算しますので、システム時刻を修正してください',
+        aheadDesc: 'システム時刻がサーバーより {{offset}} 進んでいます。サーバー時刻で計算しますので、システム時刻を修正してください',
+        serverTime: 'サーバー時刻：{{time}}',
+        minutes: '{{count}} 分',
+        hours: '{{count}} 時間',
+        days: '{{count}} 日',
+      },
       // Tray menu
       tray: {
         showWindow: 'メイン画面を表示',
@@ -1350,6 +1690,16 @@ const resources = {
         syncEnabled: '同期：有効',
         syncDisabled: '同期：無効',
         quit: '終了',
+        user: 'ユーザー',
+        cloudLoggedIn: 'クラウド同期にログイン済み',
+        autoSync: '自動同期',
+        cloudNotLoggedIn: 'クラウド同期に未ログイン',
+        cloudLoginHint: 'ログインすると端末間でデータを同期できます',
+        loginCloudSync: 'クラウド同期にログイン',
+        exportLatest: 'クイックエクスポート',
+        openDataDir: 'データフォルダを開く',
+        copySummary: 'ガチャ概要をコピー',
+        activeAccount: '選択中',
       },
       common: {
         loading: '読み込み中...',
@@ -1422,6 +1772,16 @@ const resources = {
         checking: 'サービス状態を確認中...',
         serviceOnline: 'サービスは稼働中です',
         serviceOffline: 'サービスは一時的に利用できません',
+        diagnose: 'ネットワーク診断',
+        diagnosing: '診断中...',
+        diagnoseFailed: '診断に失敗しました',
+        diagnoseAllOk: 'すべてのチェックに合格しました',
+        diagnoseFailedAt: '「{{stage}}」で失敗しました',
+        diagnoseStageDns: 'DNS 解決',
+        diagnoseStageTcp: 'TCP 接続',
+        diagnoseStageTls: 'TLS ハンドシェイク',
+        diagnoseStageHttp: 'HTTP リクエスト',
+        diagnoseSkipped: 'スキップ',
         refresh: '更新',
         accountTitle: '同期アカウント',
         loggedInAs: 'ログイン中',
@@ -1523,6 +1883,8 @@ const resources = {
         noAccountTip: 'ゲームアカウントを追加してください',
         tokenLabel: 'アカウントトークン',
         tokenPlaceholder: '{{providerName}}から取得したトークンを入力',
+        urlUidSelected: 'リンクの UID {{uid}} に対応するアカウントを選択しました',
+        urlUidUnknown: 'リンクの UID {{uid}} は追加済みのアカウントに該当しません',
         tokenHint: 'このトークンは記録取得のみに使用され、保存・アップロードされません',
         addButton: '追加',
         howToGetToken: 'トークンの取得方法',
@@ -1542,6 +1904,8 @@ const resources = {
         verifying: '認証中...',
         emptyTitle: 'アカウントなし',
         deleteAccountTitle: 'アカウント削除',
+        renameTitle: 'ニックネームを変更',
+        renameDesc: '手動で設定したニックネームはインポートで上書きされません',
         uidPrefix: 'UID: ',
         levelPrefix: 'Lv.',
         tokenApiUrl: 'https://web-api.hypergryph.com/account/info/hg',
@@ -1554,6 +1918,7 @@ const resources = {
         noAccounts: '追加済みアカウントはありません',
         current: '現在',
         confirmDelete: 'このアカウントを削除しますか？ガチャ記録は保持されます。',
+        deleteRecordsToo: 'このアカウントのガチャ記録と同期履歴もすべて削除する（元に戻せません）',
         addSuccess: 'アカウントを追加しました！',
       },
       // Sync page
@@ -1581,6 +1946,8 @@ const resources = {
         fetched: '取得済み',
         success: '同期完了',
         successDetail: '新規 {{count}} 件を取得しました',
+        durationDetail: '同期時間 {{seconds}}秒、{{count}} 件を処理',
+        durationBreakdown: 'ネットワーク {{network}}秒 / DB 書き込み {{dbWrite}}秒、{{rate}} 件/秒',
         error: '同期失敗',
         accountTokenExpiredTitle: 'アカウント Token の有効期限切れ',
         accountTokenExpiredDesc: 'アカウント Token の有効期限が切れています。アカウント管理から再追加してください。',
@@ -1612,6 +1979,7 @@ const resources = {
         empty: '記録がありません',
         emptyHint: '下のボタンから同期してください',
         goSync: '同期へ',
+        newFromSync: '今回の同期で {{count}} 件追加（最高 {{rarity}}★）',
         searchPlaceholder: '名前を検索...',
         // Category filter
         allCategories: '全カテゴリ',
@@ -1702,6 +2070,18 @@ const resources = {
           sharedPityTitle: '特許尋訪 共有天井',
           currentPityLabel: '現在 {{count}} 回',
           cumulativeConsume: '累計消費',
+          resourceEstimate: {
+            title: 'リソース換算',
+            breakdown: '有償 {{paid}} 回 · 無料 {{free}} 回（価値 {{freeValue}}）',
+            unclassifiedHint: 'うち {{count}} 回は有償/無料を区別できないため（武器ガチャの記録）、すべて消費として計上しています。',
+            notConfigured: '1回あたりの価格を設定すると換算額を表示します',
+            edit: '設定',
+            save: '保存',
+            pricePerPull: '1回あたりの価格',
+            pricePlaceholder: '空欄で換算しない',
+            currency: '通貨',
+            invalidPrice: '1回あたりの価格は0以上の数値で入力してください',
+          },
           originium: 'オリジニウム',
           armoryQuota: '武庫配給',
           armoryQuotaProduced: '累計獲得',
@@ -1832,6 +2212,13 @@ const resources = {
         versionUpdateDesc: '最新バージョンを確認してインストールします',
         legal: '法的情報',
         legalDesc: '免責事項と利用規約',
+        resourceUsage: 'リソース使用状況',
+        resourceUsageDesc: 'メインプロセスの使用量（WebView のレンダラープロセスを除く）',
+        memoryUsage: 'メモリ',
+        peakMemory: 'メモリ最大値',
+        dbConnections: 'DB 接続数',
+        backgroundTasks: 'バックグラウンドタスク',
+        refreshUsage: '更新',
       },
       updater: {
         badgeText: 'NEW',
@@ -1859,6 +2246,18 @@ const resources = {
         recheck: '再確認',
         tipTitle: 'ヒント',
         tipBody: '起動時と12時間ごとに自動確認し、更新があれば右下に通知します。',
+        manualTitle: '手動更新',
+        manualDesc: '{{reason}}ため、自動通知を停止しました。リリースページから新しいバージョンをダウンロードしてください。',
+        suppressedDesc: '{{reason}}ため、自動更新チェックを停止しました。リリースページから手動でダウンロードするか、ネットワーク環境が変わった後に再試行してください。',
+        openReleasePage: 'リリースページを開く',
+        tryAgain: '再試行',
+        failureKind: {
+          signature: '更新パッケージの署名検証が繰り返し失敗した',
+          proxy_blocked: '更新のダウンロードがプロキシやゲートウェイに繰り返しブロックされた',
+          permission_denied: 'インストール先への書き込み権限が繰り返し不足した',
+          network: 'ネットワークエラーで自動更新が繰り返し失敗した',
+          other: '自動更新が繰り返し失敗した',
+        },
       },
       // Settings page
       settings: {
@@ -1876,15 +2275,45 @@ const resources = {
         exportJSON: 'JSONを書き出し',
         importJSON: 'JSONを取り込み',
         exportCSV: 'CSVを書き出し',
+        otherFormats: 'その他の形式',
+        exportUigf: 'UIGFを書き出し',
+        exportWwCompat: '互換形式で書き出し',
+        exportPdf: 'PDFレポートを書き出し',
+        exportProgress: '書き出し中… {{done}}/{{total}}',
+        cancelExport: '書き出しを中止',
+        exportCancelled: '書き出しを中止しました（ファイルは作成されていません）',
         importCSV: 'CSVを取り込み',
         exportSuccess: 'エクスポートしました',
         csvExportSuccess: 'CSVをエクスポートしました',
         exportError: 'エクスポートに失敗しました。再試行してください',
         importSuccess: 'インポート完了：{{accounts}} アカウント、{{records}} 件の新規記録',
+        importNickNamesUpdated: '{{count}} アカウントのニックネームをファイルに合わせて更新しました',
+        importNickNamesKept: '{{count}} アカウントは手動設定のニックネームを保持しました',
         csvImportSuccess: 'CSVインポート完了：キャラ {{charRecords}} 件、武器 {{weaponRecords}} 件',
         importError: 'インポート失敗。ファイル形式を確認してください',
+        importUidMismatchTitle: 'アカウント不一致',
+        importUidMismatch: 'ファイル内のアカウントが現在のデータと一致しません。ファイルを間違えている可能性があります。それでもインポートしますか？',
+        importForce: 'それでもインポート',
+        importPreviewTitle: 'インポートのプレビュー',
+        importPreviewDesc: '確認後、以下のデータが書き込まれます。既存の記録はスキップされます。',
+        importReplaceMode: '上書きモード：ファイル内アカウントの既存記録を削除（事前にバックアップ）',
+        importReplaceDesc: 'データベースをバックアップした後、これらのアカウントの既存記録を削除します。インポート後はファイルのデータのみが残ります。',
+        importConfirm: 'インポート',
+        importPreviewAccounts: 'アカウント',
+        importPreviewCharRecords: 'オペレーター記録',
+        importPreviewWeaponRecords: '武器記録',
+        importPreviewSkipped: 'スキップ {{count}}',
+        importPreviewAddedSamples: '新規記録（サンプル）',
+        importPreviewSkippedSamples: 'スキップ記録（既存）',
+        importUnresolvedItems: '次のアイテム名はアイテム ID を特定できず、ピックアップ外として扱われます：{{names}}',
         dataInfo: 'JSON形式は完全なデータを含み、バックアップ/復元に利用できます',
         csvInfo: 'CSVはExcel等で閲覧しやすく、再インポートも可能です',
+        archiveTitle: '古い記録をアーカイブ',
+        archiveDesc: '公式APIの保存期間を過ぎた記録はサーバーと照合できません。アーカイブした記録は修復系の操作で既定でスキップされ、欠番チェックでも参考表示のみになります。',
+        archiveBefore: 'この日付より前の記録をアーカイブ',
+        archiveAction: 'アーカイブ',
+        archiveConfirm: '{{date}} より前の記録をアーカイブ済みにします。重複整理・一括削除・ガチャ種別の修正ではスキップされます。元に戻せません。',
+        archiveSuccess: '{{count}} 件の記録をアーカイブしました',
         openFolder: 'フォルダーを開く',
         dangerZone: '危険操作',
         dangerDesc: '以下の操作は取り消せません',
@@ -0,0 +1,116 @@
+/**
+ * 自定义抽卡记录接口（高级模式）
+ * 请求与解析都在 Rust 端完成，这里只做类型包装；需先在设置中开启
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { notifyStorageChange } from './storage/events';
+
+/** 响应字段映射（点分隔路径，相对于单条记录） */
+export type CustomFieldMapping = {
+  id: string;
+  time: string;
+  name: string;
+  rank: string;
+  pool: string;
+};
+
+/** 分页方式：下一页带上 cursorParam = 上一页最后一条记录的 cursorField */
+export type CustomPagination = {
+  cursorParam: string;
+  cursorField: string;
+  /** 响应中"是否还有下一页"的字段，为空时翻到空页为止 */
+  hasMorePath: string;
+};
+
+/** 自定义接口配置（与分享用的 JSON 格式相同） */
+export type CustomEndpointProfile = {
+  name: string;
+  category: 'character' | 'weapon';
+  baseUrl: string;
+  /** 查询参数模板，值中的 `{变量}` 在请求时替换 */
+  query: Record<string, string>;
+  /** 记录数组在响应中的路径，如 `data.list` */
+  listPath: string;
+  pagination: CustomPagination;
+  fields: CustomFieldMapping;
+};
+
+export type CustomEndpointConfig = {
+  enabled: boolean;
+  profiles: CustomEndpointProfile[];
+};
+
+export type CustomRecord = {
+  id: string;
+  gachaTs: number;
+  name: string;
+  rarity: number;
+  pool: string;
+};
+
+export type CustomEndpointTest = {
+  url: string;
+  pageRecords: number;
+  preview: CustomRecord[];
+};
+
+export type CustomFetchResult = {
+  pages: number;
+  fetched: number;
+  added: number;
+};
+
+/** 模板变量（如 token、serverId），不随配置保存 */
+export type CustomEndpointVars = Record<string, string>;
+
+export function getCustomEndpointConfig(): Promise<CustomEndpointConfig> {
+  return invoke<CustomEndpointConfig>('get_custom_endpoint_config');
+}
+
+export function setCustomEndpointEnabled(enabled: boolean): Promise<void> {
+  return invoke('set_custom_endpoint_enabled', { enabled });
+}
+
+/** 只发测试请求，不保存 */
+export function testCustomEndpoint(
+  profile: CustomEndpointProfile,
+  vars: CustomEndpointVars
+): Promise<CustomEndpointTest> {
+  return invoke<CustomEndpointTest>('test_custom_endpoint', { profile, vars });
+}
+
+/** 测试请求通过后保存（同名覆盖） */
+export function saveCustomEndpointProfile(
+  profile: CustomEndpointProfile,
+  vars: CustomEndpointVars
+): Promise<CustomEndpointTest> {
+  return invoke<CustomEndpointTest>('save_custom_endpoint_profile', { profile, vars });
+}
+
+export function deleteCustomEndpointProfile(name: string): Promise<void> {
+  return invoke('delete_custom_endpoint_profile', { name });
+}
+
+/** 导出配置 JSON（分享用） */
+export function exportCustomEndpointProfile(name: string): Promise<string> {
+  return invoke<string>('export_custom_endpoint_profile', { name });
+}
+
+/** 解析他人分享的配置 JSON（仅校验格式，保存前仍需测试） */
+export function parseCustomEndpointProfile(json: string): Promise<CustomEndpointProfile> {
+  return invoke<CustomEndpointProfile>('parse_custom_endpoint_profile', { json });
+}
+
+/** 按已保存的配置拉取记录并写入账号 */
+export async function fetchCustomEndpointRecords(
+  name: string,
+  uid: string,
+  vars: CustomEndpointVars
+): Promise<CustomFetchResult> {
+  const result = await invoke<CustomFetchResult>('fetch_custom_endpoint_records', { name, uid, vars });
+  if (result.added > 0) {
+    notifyStorageChange({ keys: ['gachaRecords', 'weaponRecords'], reason: 'fetchCustomEndpointRecords' });
+  }
+  return result;
+}
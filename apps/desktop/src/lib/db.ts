@@ -37,6 +37,24 @@ export async function getDB(): Promise<Database> {
   const path = await getDbPath();
   db = await Database.load(path);
   await initTables();
+
+  // 程序位于可移动存储且开启了完整同步时，降低拔出设备导致损坏的风险
+  const storage = await invoke<{ fullSync: boolean }>('get_storage_status').catch(() => null);
+  if (storage?.fullSync) {
+    await db.execute('PRAGMA synchronous = FULL');
+  }
+
+  // 启动健康检查：数据库文件（含 WAL/SHM）只读或权限不一致时记录警告
+  invoke<{ file: string; problem: string }[]>('check_database_files')
+    .then((issues) => {
+      for (const issue of issues) {
+        console.warn(`[db] 数据库文件异常 (${issue.problem}): ${issue.file}`);
+      }
+    })
+    .catch((e: unknown) => {
+      console.warn('[db] check_database_files failed:', e);
+    });
+
   return db;
 }
 
@@ -114,6 +132,13 @@ async function initTables(): Promise<void> {
   await db.execute(`
     CREATE INDEX IF NOT EXISTS idx_weapon_ts ON weapon_records(gacha_ts)
   `);
+  // 游标分页（get_pulls_before / get_pulls_after）按 gacha_id 数值比较
+  await db.execute(`
+    CREATE INDEX IF NOT EXISTS idx_gacha_uid_seq ON gacha_records(uid, CAST(seq_id AS INTEGER))
+  `);
+  await db.execute(`
+    CREATE INDEX IF NOT EXISTS idx_weapon_uid_seq ON weapon_records(uid, CAST(seq_id AS INTEGER))
+  `);
 }
 
 /**
@@ -149,6 +174,24 @@ export async function closeDB(): Promise<void> {
   }
 }
 
+/**
+ * 是否处于演示模式（读写都落在临时库，不影响真实数据）
+ */
+export async function getDemoMode(): Promise<boolean> {
+  const status = await invoke<{ enabled: boolean }>('get_demo_mode');
+  return status.enabled;
+}
+
+/**
+ * 开启/关闭演示模式
+ *
+ * 切换前关闭当前连接，完成后需重新加载页面以连接到新的数据库（临时库或真实库）。
+ */
+export async function setDemoMode(enabled: boolean): Promise<void> {
+  await closeDB();
+  await invoke('set_demo_mode', { enabled });
+}
+
 /**
  * 清理本地数据库中的重复记录
  * 按 seq_id 去重，保留最早创建的一条
@@ -270,6 +313,10 @@ export type DBGachaRecord = {
   seq_id: string;
   fetched_at: number;
   category: string;
+  /** Rust 端补充的列（0/1），旧库首次由 Rust 打开前可能不存在 */
+  archived?: number;
+  /** Rust 端补充的列：手动纠正后的卡池类型，为空时按 pool_id 前缀归类 */
+  gacha_type_override?: string | null;
 };
 
 /**
@@ -347,6 +394,8 @@ export type DBWeaponRecord = {
   seq_id: string;
   fetched_at: number;
   category: string;
+  /** Rust 端补充的列（0/1），旧库首次由 Rust 打开前可能不存在 */
+  archived?: number;
 };
 
 /**
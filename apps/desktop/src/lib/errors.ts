@@ -0,0 +1,50 @@
+/**
+ * Rust 命令的结构化错误
+ *
+ * 启动时调用 `enableStructuredErrors()` 后，命令失败会返回带稳定 `kind` 的对象，
+ * 界面按 kind / code 判断错误类别，不再匹配中文错误信息。
+ * 类型与 Rust 端 `get_error_kinds` 生成的声明保持一致。
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type AppError =
+  | { kind: 'io'; message: string }
+  | { kind: 'database'; message: string }
+  | { kind: 'network'; message: string; status?: number; code?: string }
+  | { kind: 'validation'; message: string }
+  | { kind: 'rate_limited'; message: string; retryAfter?: number }
+  | { kind: 'token_expired'; message: string }
+  | { kind: 'conflict'; message: string; code: string }
+  | { kind: 'internal'; message: string };
+
+export type AppErrorKind = AppError['kind'];
+
+/** 切换为结构化错误（旧版 Rust 端没有该命令时忽略，错误仍是字符串） */
+export async function enableStructuredErrors(): Promise<void> {
+  await invoke('set_error_format', { structured: true }).catch(() => undefined);
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return (
+    typeof err === 'object' &&
+    err !== null &&
+    typeof (err as { kind?: unknown }).kind === 'string' &&
+    typeof (err as { message?: unknown }).message === 'string'
+  );
+}
+
+/** 取得可展示的错误信息（兼容字符串错误与 Error 对象） */
+export function errorMessage(err: unknown): string {
+  if (isAppError(err)) return err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+}
+
+/** 是否为指定错误码的冲突错误（兼容旧版以错误码开头的字符串错误） */
+export function hasErrorCode(err: unknown, code: string): boolean {
+  if (isAppError(err)) {
+    return err.kind === 'conflict' && err.code === code;
+  }
+  return errorMessage(err).startsWith(code);
+}
@@ -0,0 +1,35 @@
+/**
+ * 物品图标本地缓存
+ *
+ * 内置图片（public/charimg、public/wpnimg）缺失时，由 Rust 端从配置的图标 CDN 下载一次并缓存到本地，
+ * 之后离线也能显示，不再每次请求第三方。
+ */
+
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+
+/** 查询结果缓存（同一物品只查询一次） */
+const iconCache = new Map<string, Promise<string | null>>();
+
+/**
+ * 获取缓存图标的地址（asset 协议）；未配置 CDN 或下载失败时返回 null
+ */
+export function getCachedIconSrc(itemId: string): Promise<string | null> {
+  let pending = iconCache.get(itemId);
+  if (!pending) {
+    pending = invoke<string | null>('get_item_icon', { itemId })
+      .then((path) => (path ? convertFileSrc(path) : null))
+      .catch((e: unknown) => {
+        console.warn('[icons] get_item_icon failed:', e);
+        return null;
+      });
+    iconCache.set(itemId, pending);
+  }
+  return pending;
+}
+
+/**
+ * 预下载账号记录中尚未缓存的图标（同步完成后调用，未配置 CDN 时 Rust 端直接返回）
+ */
+export function prefetchIcons(uids: string[]): Promise<number> {
+  return invoke<number>('prefetch_icons', { uids });
+}
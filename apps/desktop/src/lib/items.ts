@@ -0,0 +1,119 @@
+/**
+ * 物品元数据（多语言名称映射）
+ *
+ * 由 Rust 端 `resolve_item` 统一解析：任一语言的物品名或物品 id 均可命中，忽略大小写与空白差异。
+ * 内部比较一律使用物品 id；导入时按名称补全 id，解析不了的名称可由用户手动映射（保存为别名）。
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { notifyStorageChange } from './storage/events';
+
+/** 物品元数据（与 Rust 端 ItemMeta 对应） */
+export type ItemMeta = {
+  id: string;
+  type: 'character' | 'weapon';
+  rarity: number;
+  /** 语言代码（zh / en / ja …）→ 名称 */
+  names: Record<string, string>;
+};
+
+/** 解析结果缓存（同一名称只查询一次） */
+const resolveCache = new Map<string, Promise<ItemMeta | null>>();
+
+/**
+ * 解析物品名（未收录时返回 null）
+ */
+export function resolveItem(name: string): Promise<ItemMeta | null> {
+  const key = name.trim();
+  if (!key) return Promise.resolve(null);
+  let pending = resolveCache.get(key);
+  if (!pending) {
+    pending = invoke<ItemMeta | null>('resolve_item', { name: key }).catch(() => null);
+    resolveCache.set(key, pending);
+  }
+  return pending;
+}
+
+/**
+ * 为缺少星级的记录按物品名补全（就地修改）
+ * @returns 补全的记录数
+ */
+export async function fillMissingRarity<T extends { rarity: number }>(
+  records: T[],
+  nameOf: (record: T) => string
+): Promise<number> {
+  let filled = 0;
+  for (const record of records) {
+    if (record.rarity > 0) continue;
+    const meta = await resolveItem(nameOf(record));
+    if (meta) {
+      record.rarity = meta.rarity;
+      filled++;
+    }
+  }
+  return filled;
+}
+
+/** 记录是否为指定物品（按 id 或任一语言的名称） */
+export function isSameItem(meta: ItemMeta, itemId: string | undefined, itemName: string): boolean {
+  if (itemId && itemId === meta.id) return true;
+  const normalized = itemName.replace(/\s+/g, '').toLowerCase();
+  return Object.values(meta.names).some((name) => name.replace(/\s+/g, '').toLowerCase() === normalized);
+}
+
+/** 批量解析结果（与 Rust 端 ItemIdReport 对应） */
+export type ItemIdReport = {
+  resolved: { name: string; id: string; kind: 'exact' | 'alias' | 'fuzzy' }[];
+  /** 无法解析的名称（去重） */
+  unresolved: string[];
+};
+
+/**
+ * 为缺少物品 id 的记录按物品名补全（就地修改；精确 → 别名 → 模糊匹配）
+ * @returns 无法解析的物品名
+ */
+export async function fillMissingItemIds<T>(
+  records: T[],
+  nameOf: (record: T) => string,
+  idOf: (record: T) => string | undefined,
+  setId: (record: T, id: string) => void
+): Promise<string[]> {
+  const missing = records.filter((r) => !idOf(r) || idOf(r) === nameOf(r));
+  if (missing.length === 0) return [];
+  const names = Array.from(new Set(missing.map((r) => nameOf(r).trim()).filter(Boolean)));
+  const report = await invoke<ItemIdReport>('resolve_item_ids', { names });
+  const ids = new Map(report.resolved.map((item) => [item.name, item.id]));
+  for (const record of missing) {
+    const id = ids.get(nameOf(record).trim());
+    if (id) setId(record, id);
+  }
+  return report.unresolved;
+}
+
+/** 数据库中无法对应到物品 id 的物品名（与 Rust 端 UnresolvedItem 对应） */
+export type UnresolvedItem = {
+  name: string;
+  category: 'character' | 'weapon';
+  records: number;
+};
+
+export function getUnresolvedItems(): Promise<UnresolvedItem[]> {
+  return invoke<UnresolvedItem[]>('get_unresolved_items');
+}
+
+/**
+ * 手动指定物品名对应的物品 id：保存为别名（以后导入直接命中）并补全已有记录
+ */
+export async function resolveUnmatchedItems(
+  mappings: { name: string; itemId: string }[]
+): Promise<{ aliasesSaved: number; recordsUpdated: number }> {
+  const result = await invoke<{ aliasesSaved: number; recordsUpdated: number }>(
+    'resolve_unmatched_items',
+    { mappings }
+  );
+  resolveCache.clear();
+  if (result.recordsUpdated > 0) {
+    notifyStorageChange({ keys: ['gachaRecords', 'weaponRecords'], reason: 'resolveUnmatchedItems' });
+  }
+  return result;
+}
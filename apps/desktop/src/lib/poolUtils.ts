@@ -16,6 +16,10 @@ export type PoolConfig = {
      */
     pool_type?: 'special' | 'weapon' | 'standard' | 'beginner';
     up6_name: string;
+    /**
+     * UP 物品 id（与记录中的 charId / weaponId 对应，不受语言影响）
+     */
+    up6_id: string;
     up6_image: string;
     /**
      * 角色池字段（武器池配置里可能不存在）
@@ -81,23 +85,44 @@ export async function loadPoolConfig(poolId: string): Promise<PoolConfig | null>
   }
 }
 
+/** 参与 UP 判断的记录字段 */
+type UpMatchRecord = Pick<UnifiedGachaRecord, 'itemName' | 'charId' | 'weaponId'>;
+
+/**
+ * UP 物品 id
+ */
+export function getUpItemId(poolConfig: PoolConfig): string | undefined {
+  return poolConfig.pool.up6_id || undefined;
+}
+
+/**
+ * 判断记录是否为 UP（角色/武器通用）
+ * 按物品 id 比较，不受记录语言影响（国际服记录为英文名）；
+ * 只有记录或配置缺少 id 时才退回按名称比较
+ */
+function isUpRecord(record: UpMatchRecord, poolConfig: PoolConfig | null): boolean {
+  if (!poolConfig) return false;
+  const itemId = record.charId || record.weaponId;
+  const upId = getUpItemId(poolConfig);
+  if (itemId && upId) return itemId === upId;
+  return poolConfig.pool.up6_name === record.itemName;
+}
+
 /**
  * 判断角色是否为UP角色
- * @param itemName 角色名称
+ * @param record 抽卡记录
  * @param poolConfig 池子配置
  * @returns 是否为UP角色
  */
-export function isUpCharacter(itemName: string, poolConfig: PoolConfig | null): boolean {
-  if (!poolConfig) return false;
-  return poolConfig.pool.up6_name === itemName;
+export function isUpCharacter(record: UpMatchRecord, poolConfig: PoolConfig | null): boolean {
+  return isUpRecord(record, poolConfig);
 }
 
 /**
- * 判断条目是否为UP（角色/武器通用：均对比配置中的 up6_name）
+ * 判断条目是否为UP（角色/武器通用）
  */
-export function isUpItem(itemName: string, poolConfig: PoolConfig | null): boolean {
-  if (!poolConfig) return false;
-  return poolConfig.pool.up6_name === itemName;
+export function isUpItem(record: UpMatchRecord, poolConfig: PoolConfig | null): boolean {
+  return isUpRecord(record, poolConfig);
 }
 
 /**
@@ -241,7 +266,7 @@ export function calculatePityStatus(
     // 遇到6星
     if (record.rarity === 6) {
       hasSixStarInPool = true;
-      const isUp = isUpCharacter(record.itemName, poolConfig);
+      const isUp = isUpCharacter(record, poolConfig);
       lastSixStarWasUp = isUp;
       pityTo6Star = 0;
       
@@ -340,7 +365,7 @@ export function calculateFreeSegmentStats(
     : undefined;
   
   const freeSixStarIsUp = freeSixStar 
-    ? isUpCharacter(freeSixStar.itemName, poolConfig) 
+    ? isUpCharacter(freeSixStar, poolConfig) 
     : false;
   
   return {
@@ -472,7 +497,7 @@ export function aggregateWeaponRecordsToSessions(
     const bucketSorted = sortRecordsByTimeAndSeq(bucket);
     if (bucketSorted.length <= 11) {
       const sixStars = bucketSorted.filter((r) => r.rarity === 6);
-      const hasUp6 = poolConfig ? sixStars.some((r) => isUpItem(r.itemName, poolConfig)) : false;
+      const hasUp6 = poolConfig ? sixStars.some((r) => isUpItem(r, poolConfig)) : false;
       sessions.push({
         gachaTs: ts,
         records: bucketSorted,
@@ -496,7 +521,7 @@ export function aggregateWeaponRecordsToSessions(
 
     for (const chunk of chunks) {
       const sixStars = chunk.filter((r) => r.rarity === 6);
-      const hasUp6 = poolConfig ? sixStars.some((r) => isUpItem(r.itemName, poolConfig)) : false;
+      const hasUp6 = poolConfig ? sixStars.some((r) => isUpItem(r, poolConfig)) : false;
       sessions.push({
         gachaTs: ts,
         records: chunk,
@@ -528,7 +553,7 @@ export function calculateWeaponPoolStatus(
   const totalSessions = sessions.length;
   const sixStarCount = sessions.reduce((sum, s) => sum + s.sixStars.length, 0);
   const up6Count = poolConfig
-    ? sessions.reduce((sum, s) => sum + s.sixStars.filter((r) => isUpItem(r.itemName, poolConfig)).length, 0)
+    ? sessions.reduce((sum, s) => sum + s.sixStars.filter((r) => isUpItem(r, poolConfig)).length, 0)
     : 0;
   const hasUp6 = up6Count > 0;
 
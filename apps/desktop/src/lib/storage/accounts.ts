@@ -3,6 +3,7 @@
  * 使用 SQLite 作为唯一数据源
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import type { BindingAccount, GameRole } from '@efgachahelper/shared';
 import {
   dbGetAccounts,
@@ -183,6 +184,41 @@ export async function removeAccount(uid: string): Promise<void> {
   notifyStorageChange({ keys: ['accounts'], reason: 'removeAccount' });
 }
 
+/**
+ * 手动设置账号昵称（之后导入文件不会覆盖）
+ */
+export async function renameAccount(uid: string, nickName: string): Promise<void> {
+  await invoke('set_account_nick_name', { uid, nickName });
+  notifyStorageChange({ keys: ['accounts'], reason: 'renameAccount' });
+}
+
+/** 删除账号全部数据的结果（各表删除的行数） */
+export type DeletedUidSummary = {
+  uid: string;
+  gacha_records: number;
+  weapon_records: number;
+  record_hash_chain: number;
+  sync_history: number;
+  account_nick_names: number;
+  custom_endpoint_records: number;
+  raw_response_records: number;
+  accounts: number;
+};
+
+/**
+ * 删除账号及其全部本地数据（抽卡记录、同步历史等，不可撤销）
+ */
+export async function purgeAccount(uid: string): Promise<DeletedUidSummary> {
+  const summary = await invoke<DeletedUidSummary>('delete_uid', { uid, confirm: true });
+
+  if (getActiveUid() === uid) {
+    clearActiveUid();
+  }
+
+  notifyStorageChange({ keys: ['accounts', 'gachaRecords', 'weaponRecords'], reason: 'purgeAccount' });
+  return summary;
+}
+
 /**
  * 根据绑定信息添加/更新账号
  * 一个 binding 的每个 role 对应一个账号（uid=serverId:roleId）
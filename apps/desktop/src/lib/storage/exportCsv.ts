@@ -3,6 +3,7 @@
  */
 
 import { getTimestamp } from '../dateUtils';
+import { fillMissingItemIds, fillMissingRarity } from '../items';
 import type { GachaRecord, WeaponRecord } from './types';
 import { getGachaRecords, saveGachaRecords } from './gachaRecords';
 import { getWeaponRecords, saveWeaponRecords } from './weaponRecords';
@@ -309,8 +310,10 @@ export async function importRecordsFromCSV(csvContent: string): Promise<{
   charRecords: number;
   weaponRecords: number;
   errors: string[];
+  /** 无法解析出物品 id 的物品名 */
+  unresolvedItems: string[];
 }> {
-  const result = { charRecords: 0, weaponRecords: 0, errors: [] as string[] };
+  const result = { charRecords: 0, weaponRecords: 0, errors: [] as string[], unresolvedItems: [] as string[] };
 
   const lines = csvContent.split(/\r?\n/).filter(line => line.trim());
   if (lines.length < 2) {
@@ -435,6 +438,15 @@ export async function importRecordsFromCSV(csvContent: string): Promise<{
     }
   }
 
+  // 缺少星级的记录（如国际服英文物品名）按内置多语言元数据补全
+  await fillMissingRarity(newCharRecords, (r) => r.charName);
+  await fillMissingRarity(newWeaponRecords, (r) => r.weaponName);
+  // 缺少物品 id 的记录按名称解析（内部比较只用 id）
+  result.unresolvedItems = [
+    ...(await fillMissingItemIds(newCharRecords, (r) => r.charName, (r) => r.charId, (r, id) => { r.charId = id; })),
+    ...(await fillMissingItemIds(newWeaponRecords, (r) => r.weaponName, (r) => r.weaponId, (r, id) => { r.weaponId = id; })),
+  ];
+
   // 合并角色记录
   if (newCharRecords.length > 0) {
     const existing = await getGachaRecords();
@@ -2,11 +2,37 @@
  * JSON 导出/导入
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import type { ExportData, ExportDataV1, StoredAccount, GachaRecord, WeaponRecord } from './types';
 import { getAccounts, saveAccounts } from './accounts';
-import { getGachaRecords, saveGachaRecords } from './gachaRecords';
-import { getWeaponRecords, saveWeaponRecords } from './weaponRecords';
+import { getGachaRecords, saveGachaRecords, gachaRecordToDB } from './gachaRecords';
+import { getWeaponRecords, saveWeaponRecords, weaponRecordToDB } from './weaponRecords';
 import { notifyStorageChange } from './events';
+import { fillMissingItemIds } from '../items';
+
+/** 导入 uid 与现有账号不一致时 Rust 端返回的错误码前缀 */
+export const IMPORT_UID_MISMATCH = 'import-uid-mismatch';
+
+/**
+ * 解析导入的 JSON 文本
+ * 本工具旧版本导出的文件（含无版本标记的文件）由 Rust 端升级为当前格式后返回
+ */
+export function parseImportJSON(text: string): Promise<ExportData> {
+  return invoke<ExportData>('upgrade_import_json', { content: text });
+}
+
+/**
+ * 收集导入文件中出现的全部 uid（账号 + 记录）
+ */
+function collectImportUids(data: ExportData | ExportDataV1): string[] {
+  const uids = new Set<string>();
+  data.accounts.forEach((a) => uids.add(a.uid));
+  data.records.forEach((r) => uids.add(r.uid));
+  if ('weaponRecords' in data && data.weaponRecords) {
+    data.weaponRecords.forEach((r) => uids.add(r.uid));
+  }
+  return Array.from(uids);
+}
 
 /**
  * 导出数据为 JSON 格式
@@ -27,65 +53,316 @@ export async function exportData(): Promise<ExportData> {
   };
 }
 
+/** 导入 uid 校验结果（与 Rust 端 ImportUidCheck 对应） */
+export type ImportUidCheck = {
+  status: 'match' | 'mismatch' | 'empty_database';
+  fileUids: string[];
+  existingUids: string[];
+  unknownUids: string[];
+};
+
+/** 单类数据的新增/跳过统计 */
+export type ImportCount = {
+  added: number;
+  skipped: number;
+};
+
+/** 导入预览样本（仅用于展示） */
+export type ImportSample = {
+  uid: string;
+  category: 'character' | 'weapon';
+  name: string;
+  rarity: number;
+  gachaTs: string;
+};
+
+/** 导入预览（dry-run）结果 */
+export type ImportPreview = {
+  uidCheck: ImportUidCheck;
+  accounts: ImportCount;
+  charRecords: ImportCount;
+  weaponRecords: ImportCount;
+  /** 将新增的记录样本 */
+  addedSamples: ImportSample[];
+  /** 因已存在而跳过的记录样本 */
+  skippedSamples: ImportSample[];
+  /** 新增记录中无法解析出物品 id 的物品名（可在设置中手动映射） */
+  unresolvedItems: string[];
+};
+
 /**
- * 从 JSON 导入数据
- * @returns 导入结果统计
+ * 导入模式
+ * - merge：与现有数据合并，已存在的记录跳过（默认）
+ * - replace：先备份数据库，再清空文件中各账号的现有记录，导入后只剩文件内容
  */
-export async function importData(data: ExportData | ExportDataV1): Promise<{
-  accounts: number;
-  charRecords: number;
-  weaponRecords: number;
-}> {
+export type ImportMode = 'merge' | 'replace';
+
+/** 预览中每类样本的最大条数 */
+const PREVIEW_SAMPLE_LIMIT = 5;
+
+/** 去重时被跳过的记录中可用来补齐已有记录的字段（与 Rust 端 ImportedRecordFields 对应） */
+type DuplicateRecordFields = {
+  recordUid: string;
+  category: 'character' | 'weapon';
+  poolName: string | null;
+  itemId: string | null;
+  name: string | null;
+  itemType: string | null;
+  rarity: number | null;
+};
+
+type ImportPlan = {
+  accounts: StoredAccount[];
+  charRecords: GachaRecord[];
+  weaponRecords: WeaponRecord[] | null;
+  /** 与已有记录（或文件中前一条）重复的记录，写入后交给 Rust 端按字段补齐 */
+  duplicates: DuplicateRecordFields[];
+  preview: Omit<ImportPreview, 'uidCheck'>;
+};
+
+function toSample(record: GachaRecord | WeaponRecord): ImportSample {
+  return {
+    uid: record.uid,
+    category: record.category,
+    name: record.category === 'weapon' ? record.weaponName : record.charName,
+    rarity: record.rarity,
+    gachaTs: record.gachaTs,
+  };
+}
+
+function toDuplicateFields(record: GachaRecord | WeaponRecord): DuplicateRecordFields {
+  return record.category === 'weapon'
+    ? {
+        recordUid: record.recordUid,
+        category: 'weapon',
+        poolName: record.poolName ?? null,
+        itemId: record.weaponId ?? null,
+        name: record.weaponName ?? null,
+        itemType: record.weaponType ?? null,
+        rarity: record.rarity ?? null,
+      }
+    : {
+        recordUid: record.recordUid,
+        category: 'character',
+        poolName: record.poolName ?? null,
+        itemId: record.charId ?? null,
+        name: record.charName ?? null,
+        itemType: null,
+        rarity: record.rarity ?? null,
+      };
+}
+
+/** 一个账号的昵称变化（与 Rust 端 NickNameChange 对应） */
+export type NickNameChange = {
+  uid: string;
+  oldNickName: string | null;
+  newNickName: string;
+};
+
+/** 导入后按文件更新账号昵称的结果（与 Rust 端 ImportedProfileResult 对应） */
+export type ImportedProfileResult = {
+  updated: NickNameChange[];
+  /** 手动设置过昵称而未被覆盖的账号 */
+  keptManual: NickNameChange[];
+};
+
+/**
+ * 计算导入合并结果（只读取现有数据，不写入）
+ * - replaceUids 中账号的现有记录视为已清空（覆盖模式）
+ */
+async function planImport(
+  data: ExportData | ExportDataV1,
+  replaceUids: ReadonlySet<string> = new Set()
+): Promise<ImportPlan> {
+  const addedSamples: ImportSample[] = [];
+  const skippedSamples: ImportSample[] = [];
+  const duplicates: DuplicateRecordFields[] = [];
+  const sample = (list: ImportSample[], record: GachaRecord | WeaponRecord) => {
+    if (list.length < PREVIEW_SAMPLE_LIMIT) list.push(toSample(record));
+  };
+
   // 合并账号
   const existingAccounts = await getAccounts();
   const accountMap = new Map<string, StoredAccount>(
     existingAccounts.map((a) => [a.uid, a])
   );
-  
+  const accounts: ImportCount = { added: 0, skipped: 0 };
   for (const account of data.accounts) {
     if (!accountMap.has(account.uid)) {
       accountMap.set(account.uid, account);
+      accounts.added++;
+    } else {
+      accounts.skipped++;
     }
   }
-  await saveAccounts(Array.from(accountMap.values()));
 
   // 合并角色记录
-  const existingCharRecords = await getGachaRecords();
+  const existingCharRecords = (await getGachaRecords()).filter((r) => !replaceUids.has(r.uid));
   const charRecordMap = new Map<string, GachaRecord>(
     existingCharRecords.map((r) => [r.recordUid, r])
   );
-  
-  let newCharRecords = 0;
+  const charRecords: ImportCount = { added: 0, skipped: 0 };
+  const addedCharRecords: GachaRecord[] = [];
   for (const record of data.records) {
+    const incoming: GachaRecord = { ...record, category: 'character' };
     if (!charRecordMap.has(record.recordUid)) {
-      charRecordMap.set(record.recordUid, { ...record, category: 'character' });
-      newCharRecords++;
+      charRecordMap.set(record.recordUid, incoming);
+      addedCharRecords.push(incoming);
+      charRecords.added++;
+      sample(addedSamples, incoming);
+    } else {
+      charRecords.skipped++;
+      sample(skippedSamples, incoming);
+      duplicates.push(toDuplicateFields(incoming));
     }
   }
-  await saveGachaRecords(Array.from(charRecordMap.values()));
 
   // 合并武器记录（仅 v2 格式）
-  let newWeaponRecords = 0;
+  const weaponRecords: ImportCount = { added: 0, skipped: 0 };
+  const addedWeaponRecords: WeaponRecord[] = [];
+  let mergedWeaponRecords: WeaponRecord[] | null = null;
   if ('weaponRecords' in data && data.weaponRecords) {
-    const existingWeaponRecords = await getWeaponRecords();
+    const existingWeaponRecords = (await getWeaponRecords()).filter(
+      (r) => !replaceUids.has(r.uid)
+    );
     const weaponRecordMap = new Map<string, WeaponRecord>(
       existingWeaponRecords.map((r) => [r.recordUid, r])
     );
-    
     for (const record of data.weaponRecords) {
+      const incoming: WeaponRecord = { ...record, category: 'weapon' };
       if (!weaponRecordMap.has(record.recordUid)) {
-        weaponRecordMap.set(record.recordUid, { ...record, category: 'weapon' });
-        newWeaponRecords++;
+        weaponRecordMap.set(record.recordUid, incoming);
+        addedWeaponRecords.push(incoming);
+        weaponRecords.added++;
+        sample(addedSamples, incoming);
+      } else {
+        weaponRecords.skipped++;
+        sample(skippedSamples, incoming);
+        duplicates.push(toDuplicateFields(incoming));
       }
     }
-    await saveWeaponRecords(Array.from(weaponRecordMap.values()));
+    mergedWeaponRecords = Array.from(weaponRecordMap.values());
+  }
+
+  // 新增记录缺少物品 id 时按名称解析（内部比较只用 id）
+  const unresolvedItems = [
+    ...(await fillMissingItemIds(addedCharRecords, (r) => r.charName, (r) => r.charId, (r, id) => { r.charId = id; })),
+    ...(await fillMissingItemIds(addedWeaponRecords, (r) => r.weaponName, (r) => r.weaponId, (r, id) => { r.weaponId = id; })),
+  ];
+
+  return {
+    accounts: Array.from(accountMap.values()),
+    charRecords: Array.from(charRecordMap.values()),
+    weaponRecords: mergedWeaponRecords,
+    duplicates,
+    preview: { accounts, charRecords, weaponRecords, addedSamples, skippedSamples, unresolvedItems },
+  };
+}
+
+/** 导入选项 */
+export type ImportOptions = {
+  force?: boolean;
+  dryRun?: boolean;
+  /** 默认 merge */
+  mode?: ImportMode;
+  /** replace 模式必须显式为 true（会清空账号现有记录） */
+  confirmReplace?: boolean;
+};
+
+/**
+ * 从 JSON 导入数据
+ * - dryRun 为真时仅返回预览统计，不产生任何副作用
+ * - mode 为 replace 时由 Rust 端备份后在同一事务中清空文件中各账号的现有记录并写入文件记录
+ * @returns 导入结果统计
+ */
+export async function importData(
+  data: ExportData | ExportDataV1,
+  options: ImportOptions & { dryRun: true }
+): Promise<ImportPreview>;
+export async function importData(
+  data: ExportData | ExportDataV1,
+  options?: ImportOptions & { dryRun?: false }
+): Promise<{
+  accounts: number;
+  charRecords: number;
+  weaponRecords: number;
+  profiles: ImportedProfileResult;
+}>;
+export async function importData(
+  data: ExportData | ExportDataV1,
+  options: ImportOptions = {}
+): Promise<ImportPreview | {
+  accounts: number;
+  charRecords: number;
+  weaponRecords: number;
+  profiles: ImportedProfileResult;
+}> {
+  const dryRun = options.dryRun ?? false;
+  const replace = (options.mode ?? 'merge') === 'replace';
+  if (replace && !dryRun && options.confirmReplace !== true) {
+    throw new Error('覆盖导入会清空账号的现有记录，需要确认');
+  }
+  const fileUids = collectImportUids(data);
+
+  // 校验文件中的 uid 是否属于当前数据库已有账号（不一致时抛出 IMPORT_UID_MISMATCH 前缀的错误；
+  // dry-run 时不报错，结论随预览返回）
+  const uidCheck = await invoke<ImportUidCheck>('check_import_uid', {
+    fileUids,
+    force: options.force ?? false,
+    dryRun,
+  });
+
+  const plan = await planImport(data, replace ? new Set(fileUids) : undefined);
+  if (dryRun) {
+    return { uidCheck, ...plan.preview };
+  }
+
+  // 覆盖模式：Rust 端先备份数据库，再在同一事务中清空这些账号的现有记录并写入文件中的记录
+  // （备份失败则不清空、不导入；写入失败时整体回滚，不会留下被清空的账号）
+  if (replace) {
+    const replaceUids = new Set(fileUids);
+    await invoke('import_replace_records', {
+      uids: fileUids,
+      charRecords: plan.charRecords.filter((r) => replaceUids.has(r.uid)).map(gachaRecordToDB),
+      weaponRecords: (plan.weaponRecords ?? []).filter((r) => replaceUids.has(r.uid)).map(weaponRecordToDB),
+      confirmReplace: true,
+    });
+  }
+
+  await saveAccounts(plan.accounts);
+  // 已有账号保留本地资料，昵称按文件更新（手动设置过的除外）
+  const profiles = await invoke<ImportedProfileResult>('apply_imported_account_profiles', {
+    accounts: data.accounts.map((a) => ({ uid: a.uid, roles: a.roles ?? [] })),
+  });
+  if (!replace) {
+    await saveGachaRecords(plan.charRecords);
+    if (plan.weaponRecords) {
+      await saveWeaponRecords(plan.weaponRecords);
+    }
+  }
+
+  // 重复记录不直接丢弃：用其中的字段补齐已有记录缺失的部分（如缺星级）
+  if (plan.duplicates.length > 0) {
+    await invoke('merge_duplicate_records', { records: plan.duplicates });
+  }
+
+  // 恢复导出时的归档标记（插入语句不含该列，由 Rust 端补写）
+  const archivedUids = [
+    ...data.records,
+    ...('weaponRecords' in data && data.weaponRecords ? data.weaponRecords : []),
+  ]
+    .filter((r) => r.archived === true)
+    .map((r) => r.recordUid);
+  if (archivedUids.length > 0) {
+    await invoke('mark_records_archived', { recordUids: archivedUids });
   }
 
   notifyStorageChange({ reason: 'importData', keys: ['accounts', 'gachaRecords', 'weaponRecords'] });
 
   return {
     accounts: data.accounts.length,
-    charRecords: newCharRecords,
-    weaponRecords: newWeaponRecords,
+    charRecords: plan.preview.charRecords.added,
+    weaponRecords: plan.preview.weaponRecords.added,
+    profiles,
   };
 }
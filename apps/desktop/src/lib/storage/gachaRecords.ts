@@ -47,13 +47,15 @@ function dbRecordToGachaRecord(dbRecord: DBGachaRecord): GachaRecord {
     isFree: dbRecord.is_free === 1,
     gachaTs: dbRecord.gacha_ts,
     seqId: dbRecord.seq_id,
+    ...(dbRecord.archived === 1 ? { archived: true } : {}),
+    ...(dbRecord.gacha_type_override ? { gachaTypeOverride: dbRecord.gacha_type_override } : {}),
   };
 }
 
 /**
  * 将 GachaRecord 转换为 DB 格式
  */
-function gachaRecordToDB(record: GachaRecord): DBGachaRecord {
+export function gachaRecordToDB(record: GachaRecord): DBGachaRecord {
   return {
     record_uid: record.recordUid,
     uid: record.uid,
@@ -106,12 +106,15 @@ export {
   saveAccount,
   saveAccounts,
   removeAccount,
+  renameAccount,
+  purgeAccount,
   addAccountsFromBinding,
   ensureAccountExists,
   getAccountByUid,
   getActiveAccount,
   selectAccount,
 } from './accounts';
+export type { DeletedUidSummary } from './accounts';
 
 // ============== 角色抽卡记录导出 ==============
 
@@ -145,6 +148,7 @@ export {
   weaponRecordToUnified,
   getAllUnifiedRecords,
   getPoolTypePrefix,
+  getRecordPoolType,
   isWeaponPool,
   calculateUnifiedStats,
   calculateStats, // deprecated
@@ -156,7 +160,10 @@ export {
 export {
   exportData,
   importData,
+  parseImportJSON,
+  IMPORT_UID_MISMATCH,
 } from './exportJson';
+export type { ImportMode, ImportPreview, ImportSample, ImportUidCheck, ImportedProfileResult, NickNameChange } from './exportJson';
 
 // ============== CSV 导出导入 ==============
 
@@ -3,6 +3,7 @@
  * 这些数据仍然使用 localStorage 存储（同步读取，小数据量）
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import { STORAGE_KEYS } from './constants';
 import { notifyStorageChange } from './events';
 import type { AccountProvider } from './types';
@@ -116,6 +117,7 @@ export function getActiveUid(): string | null {
 export function setActiveUid(uid: string): void {
   localStorage.setItem(STORAGE_KEYS.ACTIVE_UID, uid);
   notifyStorageChange({ keys: [STORAGE_KEYS.ACTIVE_UID], reason: 'setActiveUid' });
+  syncActiveAccountToRust(uid);
 }
 
 /**
@@ -124,6 +126,17 @@ export function setActiveUid(uid: string): void {
 export function clearActiveUid(): void {
   localStorage.removeItem(STORAGE_KEYS.ACTIVE_UID);
   notifyStorageChange({ keys: [STORAGE_KEYS.ACTIVE_UID], reason: 'clearActiveUid' });
+  syncActiveAccountToRust(null);
+}
+
+/**
+ * 同步激活账号到 Rust 端（托盘提示、后台任务以 Rust 配置为准）
+ * Rust 端在值未变化时不会重复广播，因此与 active-account-changed 监听不会形成循环
+ */
+function syncActiveAccountToRust(uid: string | null): void {
+  invoke('set_active_account', { uid }).catch((e: unknown) => {
+    console.warn('[preferences] set_active_account failed:', e);
+  });
 }
 
 // ============== 窗口关闭行为管理 ==============
@@ -51,6 +51,10 @@ export type GachaRecord = EndFieldCharInfo & {
   recordUid: string; // 用于去重的唯一标识
   fetchedAt: number;
   category: 'character'; // 记录类型
+  /** 已归档（超出官方接口保留期，修复类操作默认跳过） */
+  archived?: boolean;
+  /** 手动纠正后的卡池类型（special / standard / beginner），优先于 poolId 前缀 */
+  gachaTypeOverride?: string;
 };
 
 /** 武器抽卡记录 */
@@ -59,6 +63,8 @@ export type WeaponRecord = EndFieldWeaponInfo & {
   recordUid: string;
   fetchedAt: number;
   category: 'weapon';
+  /** 已归档（超出官方接口保留期，修复类操作默认跳过） */
+  archived?: boolean;
 };
 
 /** 通用抽卡记录（角色或武器） */
@@ -78,6 +84,8 @@ export type UnifiedGachaRecord = {
   charId?: string;
   charName?: string;
   isFree?: boolean;
+  /** 手动纠正后的卡池类型，优先于 poolId 前缀 */
+  gachaTypeOverride?: string;
   // 武器特有字段
   weaponId?: string;
   weaponName?: string;
@@ -64,6 +64,7 @@ export function charRecordToUnified(record: GachaRecord): UnifiedGachaRecord {
     charId: record.charId,
     charName: record.charName,
     isFree: record.isFree,
+    ...(record.gachaTypeOverride ? { gachaTypeOverride: record.gachaTypeOverride } : {}),
     itemName: record.charName,
   };
 }
@@ -124,6 +125,13 @@ export function getPoolTypePrefix(poolId: string): string {
   return poolId?.toLowerCase().split('_')[0] || '';
 }
 
+/**
+ * 记录所属的卡池类型：手动纠正过的记录以纠正结果为准，否则按 poolId 前缀归类
+ */
+export function getRecordPoolType(record: Pick<UnifiedGachaRecord, 'poolId' | 'gachaTypeOverride'>): string {
+  return record.gachaTypeOverride || getPoolTypePrefix(record.poolId);
+}
+
 /**
  * 判断是否为武器池
  * 武器池 poolId 以 weponbox 或 weaponbox 开头
@@ -153,7 +161,7 @@ export function calculateUnifiedStats(records: UnifiedGachaRecord[], options?: {
   if (options?.poolType) {
     const poolType = options.poolType.toLowerCase();
     filtered = filtered.filter((r) => {
-      const prefix = getPoolTypePrefix(r.poolId);
+      const prefix = getRecordPoolType(r);
       return prefix === poolType || r.poolName.includes(options.poolType!);
     });
   }
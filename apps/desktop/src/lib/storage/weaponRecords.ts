@@ -43,13 +43,14 @@ function dbRecordToWeaponRecord(dbRecord: DBWeaponRecord): WeaponRecord {
     isNew: dbRecord.is_new === 1,
     gachaTs: dbRecord.gacha_ts,
     seqId: dbRecord.seq_id,
+    ...(dbRecord.archived === 1 ? { archived: true } : {}),
   };
 }
 
 /**
  * 将 WeaponRecord 转换为 DB 格式
  */
-function weaponRecordToDB(record: WeaponRecord): DBWeaponRecord {
+export function weaponRecordToDB(record: WeaponRecord): DBWeaponRecord {
   return {
     record_uid: record.recordUid,
     uid: record.uid,
@@ -4,6 +4,7 @@
  */
 
 import { fetch as tauriFetch } from '@tauri-apps/plugin-http';
+import { invoke } from '@tauri-apps/api/core';
 import type {
   AuthResponse,
   RefreshResponse,
@@ -232,6 +233,111 @@ async function tryRefreshTokenDetailed(): Promise<RefreshAttemptResult> {
   return refreshPromise;
 }
 
+/**
+ * 获取候选接口列表（按尝试顺序）
+ * - 用户自定义了 API 地址：只使用该地址（保持原有行为）
+ * - 设置中配置了主/备接口列表（Rust 端保存）：从当前生效接口开始依次尝试
+ * - 否则使用内置默认地址
+ */
+async function getApiCandidates(): Promise<string[]> {
+  try {
+    const customUrl = localStorage.getItem('efgh_api_url');
+    if (customUrl) return [customUrl];
+  } catch {
+    // localStorage 不可用
+  }
+  if (!isTauri()) return [DEFAULT_API_URL];
+
+  try {
+    const [endpoints, active] = await Promise.all([
+      invoke<string[]>('get_sync_endpoints'),
+      invoke<string | null>('get_active_endpoint'),
+    ]);
+    if (endpoints.length === 0) return [DEFAULT_API_URL];
+    const index = active ? endpoints.indexOf(active) : -1;
+    return index > 0 ? [...endpoints.slice(index), ...endpoints.slice(0, index)] : endpoints;
+  } catch {
+    return [DEFAULT_API_URL];
+  }
+}
+
+/** 云同步接口健康状态（Rust 端 `EndpointStatus`） */
+export interface EndpointStatus {
+  endpoint: string;
+  active: boolean;
+  consecutive_failures: number;
+  last_error: string | null;
+}
+
+/**
+ * 获取设置中的云同步接口列表（第一个为主接口，其余为备用；为空表示使用内置地址）
+ */
+export async function getSyncEndpoints(): Promise<string[]> {
+  return invoke<string[]>('get_sync_endpoints');
+}
+
+/**
+ * 保存云同步接口列表，返回规范化后的列表；保存后从主接口重新开始
+ */
+export async function setSyncEndpoints(endpoints: string[]): Promise<string[]> {
+  return invoke<string[]>('set_sync_endpoints', { endpoints });
+}
+
+/**
+ * 获取各接口的连续失败次数与最近错误（排障用）
+ */
+export async function getEndpointStatuses(): Promise<EndpointStatus[]> {
+  return invoke<EndpointStatus[]>('get_endpoint_statuses');
+}
+
+export type DiagnoseStageName = 'dns' | 'tcp' | 'tls' | 'http';
+
+export interface DiagnoseStage {
+  stage: DiagnoseStageName;
+  status: 'ok' | 'failed' | 'skipped';
+  elapsedMs: number | null;
+  detail: string | null;
+}
+
+export interface DiagnoseResult {
+  url: string;
+  stages: DiagnoseStage[];
+  /** 第一个失败的步骤，全部通过时为 null */
+  failedStage: DiagnoseStageName | null;
+}
+
+/**
+ * 诊断到当前同步接口的网络连通性（DNS / TCP / TLS / HTTP 分步计时）
+ */
+export async function diagnoseSyncEndpoint(): Promise<DiagnoseResult> {
+  const [url] = await getApiCandidates();
+  return invoke<DiagnoseResult>('network_diagnose', { url });
+}
+
+/**
+ * 上报接口请求结果
+ * @returns 是否应继续重试该接口（false 表示切换到下一个接口）
+ */
+async function reportEndpointResult(endpoint: string, ok: boolean, error?: string): Promise<boolean> {
+  if (!isTauri()) return false;
+  try {
+    return await invoke<boolean>('report_endpoint_result', { endpoint, ok, error: error ?? null });
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * 是否属于需要切换接口的错误（网络错误 / 服务端 5xx）
+ * 4xx 属于业务错误，换接口也无济于事
+ */
+function shouldFailover(error: unknown): boolean {
+  if (error instanceof SyncApiError) {
+    return error.statusCode === 0 || error.statusCode >= 500;
+  }
+  return true;
+}
+
 /**
  * 发送 API 请求（带自动 token 刷新）
  */
@@ -244,9 +350,9 @@ async function request<T>(
   } = {},
 ): Promise<T> {
   const { method = 'GET', body, accessToken } = options;
-  const url = `${getApiUrl()}${endpoint}`;
   
-  const doRequest = async (token?: string): Promise<T> => {
+  const doRequestAt = async (baseUrl: string, token?: string): Promise<T> => {
+    const url = `${baseUrl}${endpoint}`;
     const headers: Record<string, string> = {
       'Content-Type': 'application/json',
     };
@@ -279,6 +385,35 @@ async function request<T>(
     
     return data as T;
   };
+
+  // 主接口连续失败后自动尝试备用接口；全部失败时汇总各接口错误
+  const doRequest = async (token?: string): Promise<T> => {
+    const candidates = await getApiCandidates();
+    const errors: string[] = [];
+    let lastError: unknown;
+
+    for (const baseUrl of candidates) {
+      for (;;) {
+        try {
+          const result = await doRequestAt(baseUrl, token);
+          await reportEndpointResult(baseUrl, true);
+          return result;
+        } catch (error) {
+          if (!shouldFailover(error)) throw error;
+          lastError = error;
+          const message = error instanceof Error ? error.message : String(error);
+          const keepTrying = await reportEndpointResult(baseUrl, false, message);
+          if (!keepTrying) {
+            errors.push(`${baseUrl}: ${message}`);
+            break;
+          }
+        }
+      }
+    }
+
+    if (candidates.length <= 1) throw lastError;
+    throw new SyncApiError(0, `所有同步接口均请求失败：${errors.join('；')}`);
+  };
   
   try {
     return await doRequest(accessToken);
@@ -6,6 +6,7 @@
  */
 
 import { fetch as tauriFetch } from '@tauri-apps/plugin-http';
+import { invoke } from '@tauri-apps/api/core';
 import type { FetchLike } from '../features/endfield/endfieldApi';
 
 /**
@@ -16,6 +17,33 @@ export const isTauri = (): boolean => {
   return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
 };
 
+/** 本次会话是否已上报过服务器时间 */
+let serverDateReported = false;
+
+/**
+ * 上报首个响应的 HTTP Date 头，由 Rust 端检测本地时钟偏差
+ */
+const reportServerDate = (response: Response): void => {
+  if (serverDateReported) return;
+  const date = response.headers.get('date');
+  if (!date) return;
+  serverDateReported = true;
+  invoke('report_server_date', { date }).catch((e: unknown) => {
+    console.warn('[TauriHTTP] report_server_date failed:', e);
+  });
+};
+
+/**
+ * 把抽卡接口的原始响应交给 Rust 端留档（是否保存由设置 keep_raw_responses 决定）
+ * uid 与页码用于建立记录到留档原文的索引
+ */
+export const saveRawResponse = (uid: string, kind: string, body: string, page: number): void => {
+  if (!isTauri()) return;
+  invoke('save_raw_response', { uid, kind, body, page }).catch((e: unknown) => {
+    console.warn('[TauriHTTP] save_raw_response failed:', e);
+  });
+};
+
 /**
  * 创建适配 endfieldApi 的 fetch 函数
  * 在 Tauri 环境使用 tauri-plugin-http（绕过 CORS），否则使用浏览器原生 fetch
@@ -44,6 +72,7 @@ export const createTauriFetch = (): FetchLike => {
         console.log('[TauriHTTP] Fetching:', url, options.method);
         const response = await tauriFetch(url, options);
         console.log('[TauriHTTP] Response status:', response.status);
+        reportServerDate(response);
         return response;
       } catch (error) {
         console.error('[TauriHTTP] Fetch error:', error);
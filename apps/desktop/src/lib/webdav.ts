@@ -0,0 +1,136 @@
+/**
+ * WebDAV 客户端封装
+ * 请求由 Rust 端发出（PROPFIND/MKCOL 等方法不受 WebView 限制），这里只做类型包装
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+/** WebDAV 连接信息 */
+export type WebdavCredentials = {
+  url: string;
+  user: string;
+  pass: string;
+};
+
+/** 远端目录中的一项（与 Rust 端 WebdavEntry 对应） */
+export type WebdavEntry = {
+  name: string;
+  /** 相对于 WebDAV 根地址的路径（以 / 开头，目录以 / 结尾） */
+  path: string;
+  isDir: boolean;
+  size: number | null;
+  modified: string | null;
+};
+
+/** 列出远端目录内容（目录在前） */
+export function listWebdav(credentials: WebdavCredentials, path: string): Promise<WebdavEntry[]> {
+  return invoke<WebdavEntry[]>('webdav_list', { ...credentials, path });
+}
+
+/** 创建远端目录（含上级目录） */
+export function createWebdavDir(credentials: WebdavCredentials, path: string): Promise<void> {
+  return invoke('webdav_mkdir', { ...credentials, path });
+}
+
+/** 上传本地文件到远端目录（目录不存在时自动创建），返回远端路径 */
+export function uploadToWebdav(
+  credentials: WebdavCredentials,
+  path: string,
+  localPath: string
+): Promise<string> {
+  return invoke<string>('webdav_upload', { ...credentials, path, localPath });
+}
+
+/** WebDAV 异地备份设置（不含密码） */
+export type WebdavSettings = {
+  url: string;
+  username: string;
+  hasPassword: boolean;
+  uploadAfterBackup: boolean;
+  keepRemote: number;
+};
+
+export function getWebdavSettings(): Promise<WebdavSettings> {
+  return invoke<WebdavSettings>('get_webdav_settings');
+}
+
+/** 保存连接信息；password 为空时保留原密码 */
+export function setWebdav(url: string, username: string, password: string): Promise<void> {
+  return invoke('set_webdav', { url, username, password });
+}
+
+export function setWebdavBackupOptions(uploadAfterBackup: boolean, keepRemote: number): Promise<void> {
+  return invoke('set_webdav_backup_options', { uploadAfterBackup, keepRemote });
+}
+
+/** 测试连接与写入权限 */
+export function testWebdav(): Promise<void> {
+  return invoke('test_webdav');
+}
+
+/** 立即创建本地备份（开启自动上传时随后上传），返回备份路径 */
+export function createDbBackup(): Promise<string> {
+  return invoke<string>('create_db_backup');
+}
+
+export function listRemoteBackups(): Promise<WebdavEntry[]> {
+  return invoke<WebdavEntry[]>('list_remote_backups');
+}
+
+/** 下载远端备份到本地，返回本地路径 */
+export function downloadRemoteBackup(name: string): Promise<string> {
+  return invoke<string>('download_remote_backup', { name });
+}
+
+/** 用备份替换当前数据库（调用前需关闭数据库连接，完成后重新加载页面）；备份比本地旧时需 force */
+export function restoreDbBackup(path: string, force = false): Promise<void> {
+  return invoke('restore_db_backup', { path, force });
+}
+
+/** 数据库修订信息（多设备冲突检测） */
+export type RevisionInfo = {
+  revision: number;
+  deviceId: string | null;
+  updatedAt: number;
+};
+
+export type RevisionComparison = {
+  local: RevisionInfo;
+  backup: RevisionInfo;
+  /** 直接覆盖时会丢失的本地修改次数 */
+  lostChanges: number;
+  warning: string | null;
+};
+
+export function compareBackupRevision(path: string): Promise<RevisionComparison> {
+  return invoke<RevisionComparison>('compare_backup_revision', { path });
+}
+
+/** 合并备份（保留本地数据，逐行取较新的修改） */
+export function mergeDbBackup(path: string): Promise<{ inserted: number; updated: number }> {
+  return invoke('merge_db_backup', { path });
+}
+
+/** 定时自动备份设置 */
+export type BackupSchedule = {
+  intervalHours: number | null;
+  lastBackup: string | null;
+  /** 外部备份目录（U 盘、网盘同步文件夹等） */
+  externalDir: string | null;
+};
+
+export function getDbBackupSchedule(): Promise<BackupSchedule> {
+  return invoke<BackupSchedule>('get_db_backup_schedule');
+}
+
+/** 设置定时自动备份间隔（小时）；传 null 关闭 */
+export function setDbBackupSchedule(intervalHours: number | null): Promise<void> {
+  return intervalHours === null
+    ? invoke('cancel_db_backup_schedule')
+    : invoke('schedule_db_backup', { intervalHours });
+}
+
+/** 设置外部备份目录；传 null 取消（之后只备份到本地） */
+export function setBackupDir(path: string | null): Promise<void> {
+  return invoke('set_backup_dir', { path });
+}
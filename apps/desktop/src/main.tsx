@@ -4,9 +4,12 @@ import App from './ui/App';
 import './ui/styles/globals.css';
 import './i18n';
 import { initStorage } from './lib/storage';
+import { invoke } from '@tauri-apps/api/core';
+import { enableStructuredErrors } from './lib/errors';
 
 // 初始化存储系统（包括 SQLite 数据库）
 async function initApp() {
+  await enableStructuredErrors();
   try {
     const result = await initStorage();
     if (result.migrated) {
@@ -22,6 +25,13 @@ async function initApp() {
       <App />
     </React.StrictMode>,
   );
+
+  // 启动耗时基线（进程启动到前端完成首次渲染）
+  requestAnimationFrame(() => {
+    void invoke<number>('get_startup_duration_ms')
+      .then((ms) => console.log(`[App] 启动耗时 ${ms} ms`))
+      .catch(console.warn);
+  });
 }
 
 void initApp();
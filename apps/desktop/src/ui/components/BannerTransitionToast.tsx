@@ -0,0 +1,101 @@
+/**
+ * 换池提醒
+ * Rust 端按卡池排期在切换前后发出 `banner-transition` 事件；开启自动同步时直接跳转到同步页开始同步
+ */
+
+import { useEffect, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { useTranslation } from 'react-i18next';
+import { listen } from '@tauri-apps/api/event';
+import { CalendarClock, RefreshCw, X } from 'lucide-react';
+import { Button } from './Button';
+
+type BannerTransitionEvent = {
+  phase: 'before' | 'after';
+  /** 切换时间（毫秒时间戳） */
+  transitionAt: number;
+  autoSync: boolean;
+};
+
+export function BannerTransitionToast() {
+  const { t } = useTranslation();
+  const navigate = useNavigate();
+  const [notice, setNotice] = useState<BannerTransitionEvent | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen<BannerTransitionEvent>('banner-transition', (event) => {
+      setNotice(event.payload);
+      if (event.payload.autoSync) {
+        void navigate('/sync?auto=1');
+      }
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[BannerTransitionToast] Failed to listen banner-transition:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, [navigate]);
+
+  if (!notice) return null;
+
+  const time = new Date(notice.transitionAt).toLocaleString();
+  const title = notice.phase === 'before'
+    ? t('bannerWatch.beforeTitle', '即将换池')
+    : t('bannerWatch.afterTitle', '新卡池已开放');
+  const desc = notice.phase === 'before'
+    ? t('bannerWatch.beforeDesc', '卡池将于 {{time}} 切换，记得同步当前卡池的记录', { time })
+    : t('bannerWatch.afterDesc', '卡池已于 {{time}} 切换', { time });
+
+  return (
+    <div className="fixed top-16 right-5 z-[10001] w-[340px] max-w-[calc(100vw-40px)]">
+      <div className="ef-update-toast relative rounded-xl overflow-hidden">
+        <div className="absolute inset-x-0 top-0 h-1 bg-brand" />
+        <div className="relative p-4 flex items-start gap-3">
+          <div className="mt-0.5 w-10 h-10 rounded-lg bg-brand/15 text-brand flex items-center justify-center shrink-0">
+            <CalendarClock size={20} />
+          </div>
+          <div className="min-w-0 flex-1">
+            <div className="flex items-start justify-between gap-2">
+              <div className="min-w-0">
+                <div className="ef-update-toast-title text-sm font-semibold">{title}</div>
+                <div className="ef-update-toast-subtitle mt-0.5 text-xs">
+                  {notice.autoSync ? t('bannerWatch.autoSyncing', '已自动开始同步') : desc}
+                </div>
+              </div>
+              <button
+                type="button"
+                className="ef-update-toast-close p-1.5 rounded-md transition-colors"
+                aria-label={t('common.dismiss', '关闭')}
+                onClick={() => setNotice(null)}
+              >
+                <X size={16} />
+              </button>
+            </div>
+            {!notice.autoSync && (
+              <div className="mt-3">
+                <Button
+                  variant="primary"
+                  size="sm"
+                  icon={<RefreshCw size={14} />}
+                  onClick={() => {
+                    setNotice(null);
+                    void navigate('/sync?auto=1');
+                  }}
+                >
+                  {t('bannerWatch.syncNow', '立即同步')}
+                </Button>
+              </div>
+            )}
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
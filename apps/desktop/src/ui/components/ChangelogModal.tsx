@@ -0,0 +1,109 @@
+/**
+ * 新版本说明弹窗
+ * 更新后首次启动时展示一次，关闭即确认（Rust 端记录已确认的版本）
+ */
+
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { Sparkles, X } from 'lucide-react';
+import { Modal } from './Modal';
+import { Button } from './Button';
+
+type ChangelogEntry = {
+  version: string;
+  date?: string | null;
+  notes: string;
+};
+
+type Changelog = {
+  currentVersion: string;
+  entries: ChangelogEntry[];
+  source: 'remote' | 'cache' | 'bundled';
+  shouldShow: boolean;
+};
+
+export function ChangelogModal() {
+  const { t } = useTranslation();
+  const [changelog, setChangelog] = useState<Changelog | null>(null);
+  const [open, setOpen] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    void invoke<Changelog>('get_changelog', { sinceVersion: null })
+      .then((result) => {
+        if (cancelled) return;
+        if (result.shouldShow && result.entries.length > 0) {
+          setChangelog(result);
+          setOpen(true);
+        }
+      })
+      .catch((e: unknown) => {
+        console.error('[ChangelogModal] Failed to get changelog:', e);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  const handleOpenChange = (next: boolean) => {
+    setOpen(next);
+    if (!next && changelog) {
+      void invoke('acknowledge_changelog', { version: changelog.currentVersion }).catch((e: unknown) => {
+        console.error('[ChangelogModal] Failed to acknowledge changelog:', e);
+      });
+    }
+  };
+
+  if (!changelog) return null;
+
+  return (
+    <Modal open={open} onOpenChange={handleOpenChange} maxWidthClassName="max-w-xl">
+      <div className="flex flex-col">
+        {/* 头部 */}
+        <div className="px-6 py-4 border-b border-border bg-bg-2 flex items-center justify-between">
+          <div className="flex items-center gap-3">
+            <div className="w-10 h-10 rounded-xl bg-brand/20 flex items-center justify-center">
+              <Sparkles className="w-5 h-5 text-brand" />
+            </div>
+            <h2 className="text-lg font-bold text-fg-0">
+              {t('changelog.title', '新版本说明')} v{changelog.currentVersion}
+            </h2>
+          </div>
+          <button
+            type="button"
+            onClick={() => handleOpenChange(false)}
+            className="w-8 h-8 rounded-lg flex items-center justify-center text-fg-2 hover:text-fg-0 hover:bg-bg-2 transition-colors"
+            aria-label={t('common.dismiss', '关闭')}
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        {/* 各版本说明（新版本在前） */}
+        <div className="overflow-y-auto p-6 max-h-[60vh] bg-bg-1 space-y-5">
+          {changelog.entries.map((entry) => (
+            <section key={entry.version}>
+              <div className="flex items-baseline gap-2 mb-2">
+                <span className="font-semibold text-fg-0">v{entry.version}</span>
+                {entry.date && <span className="text-xs text-fg-2">{entry.date}</span>}
+              </div>
+              <div className="text-sm text-fg-1 leading-relaxed whitespace-pre-line">{entry.notes}</div>
+            </section>
+          ))}
+          {changelog.source === 'bundled' && (
+            <div className="text-xs text-fg-2">
+              {t('changelog.offlineHint', '当前离线，仅显示安装包内附带的说明。')}
+            </div>
+          )}
+        </div>
+
+        <div className="px-6 py-4 border-t border-border bg-bg-2 flex justify-end">
+          <Button onClick={() => handleOpenChange(false)}>{t('changelog.gotIt', '知道了')}</Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
+
+export default ChangelogModal;
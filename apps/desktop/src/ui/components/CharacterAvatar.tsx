@@ -5,6 +5,7 @@
 
 import { useState } from 'react';
 import { X } from 'lucide-react';
+import { getCachedIconSrc } from '../../lib/icons';
 
 export type CharacterAvatarProps = {
   /** 角色ID（如 chr_0006_wolfgd） */
@@ -49,6 +50,8 @@ export function CharacterAvatar({
 }: CharacterAvatarProps) {
   const [imgError, setImgError] = useState(false);
   const [imgLoaded, setImgLoaded] = useState(false);
+  // 内置图片缺失时改用本地图标缓存
+  const [cachedSrc, setCachedSrc] = useState<string | null>(null);
   
   const sizeClass = sizeMap[size];
   const iconSize = iconSizeMap[size];
@@ -79,6 +82,18 @@ export function CharacterAvatar({
   
   // 显示角色头像
   const imagePath = `/charimg/${charId}.png`;
+  const itemId: string = charId;
+
+  const handleImgError = () => {
+    if (cachedSrc) {
+      setImgError(true);
+      return;
+    }
+    void getCachedIconSrc(itemId).then((src) => {
+      if (src) setCachedSrc(src);
+      else setImgError(true);
+    });
+  };
 
   const borderClass = (() => {
     if (isUp) return 'border-orange-500 shadow-lg shadow-orange-500/30';
@@ -102,13 +117,13 @@ export function CharacterAvatar({
         
         {/* 角色头像 */}
         <img
-          src={imagePath}
+          src={cachedSrc ?? imagePath}
           alt=""
           className={`w-full h-full object-cover transition-opacity duration-300 ${
             imgLoaded ? 'opacity-100' : 'opacity-0'
           }`}
           onLoad={() => setImgLoaded(true)}
-          onError={() => setImgError(true)}
+          onError={handleImgError}
         />
       </div>
       
@@ -0,0 +1,93 @@
+/**
+ * 系统时间异常提示
+ * Rust 端对比首个 API 响应的 HTTP Date 头，本地时钟偏差过大时发出 `clock-skew-detected` 事件；
+ * 此后过期判断与同步历史按服务器时间计算，这里提示用户校准系统时间
+ */
+
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import type { TFunction } from 'i18next';
+import { listen } from '@tauri-apps/api/event';
+import { Clock, X } from 'lucide-react';
+
+type ClockSkewEvent = {
+  /** 服务器时间 - 本地时间（毫秒） */
+  offsetMs: number;
+  serverTime: string;
+  localTime: string;
+};
+
+/** 把偏差格式化为“x 天 y 小时 z 分钟”量级的描述 */
+function formatOffset(offsetMs: number, t: TFunction): string {
+  const minutes = Math.round(Math.abs(offsetMs) / 60_000);
+  if (minutes < 60) return t('clockSkew.minutes', '{{count}} 分钟', { count: minutes });
+  const hours = Math.round(minutes / 60);
+  if (hours < 48) return t('clockSkew.hours', '{{count}} 小时', { count: hours });
+  return t('clockSkew.days', '{{count}} 天', { count: Math.round(hours / 24) });
+}
+
+export function ClockSkewToast() {
+  const { t } = useTranslation();
+  const [skew, setSkew] = useState<ClockSkewEvent | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen<ClockSkewEvent>('clock-skew-detected', (event) => {
+      setSkew(event.payload);
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[ClockSkewToast] Failed to listen clock-skew-detected:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  if (!skew) return null;
+
+  const offset = formatOffset(skew.offsetMs, t);
+  const desc = skew.offsetMs > 0
+    ? t('clockSkew.behindDesc', '系统时间比服务器慢了 {{offset}}，已改用服务器时间计算，请校准系统时间', { offset })
+    : t('clockSkew.aheadDesc', '系统时间比服务器快了 {{offset}}，已改用服务器时间计算，请校准系统时间', { offset });
+
+  return (
+    <div className="fixed top-16 right-5 z-[10001] w-[340px] max-w-[calc(100vw-40px)]">
+      <div className="ef-update-toast relative rounded-xl overflow-hidden">
+        <div className="absolute inset-x-0 top-0 h-1 bg-amber-500" />
+        <div className="relative p-4 flex items-start gap-3">
+          <div className="mt-0.5 w-10 h-10 rounded-lg bg-amber-500/15 text-amber-500 flex items-center justify-center shrink-0">
+            <Clock size={20} />
+          </div>
+          <div className="min-w-0 flex-1">
+            <div className="flex items-start justify-between gap-2">
+              <div className="min-w-0">
+                <div className="ef-update-toast-title text-sm font-semibold">
+                  {t('clockSkew.title', '系统时间异常')}
+                </div>
+                <div className="ef-update-toast-subtitle mt-0.5 text-xs">{desc}</div>
+                <div className="ef-update-toast-subtitle mt-1 text-xs opacity-80">
+                  {t('clockSkew.serverTime', '服务器时间：{{time}}', {
+                    time: new Date(skew.serverTime).toLocaleString(),
+                  })}
+                </div>
+              </div>
+              <button
+                type="button"
+                className="ef-update-toast-close p-1.5 rounded-md transition-colors"
+                aria-label={t('common.dismiss', '关闭')}
+                onClick={() => setSkew(null)}
+              >
+                <X size={16} />
+              </button>
+            </div>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
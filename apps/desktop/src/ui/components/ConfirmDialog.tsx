@@ -10,6 +10,8 @@ export type ConfirmDialogProps = {
   cancelText?: string;
   danger?: boolean;
   icon?: React.ReactNode;
+  /** 描述下方的附加内容 */
+  children?: React.ReactNode;
   onConfirm: () => void;
   onCancel: () => void;
 };
@@ -22,6 +24,7 @@ export function ConfirmDialog({
   cancelText = '取消',
   danger = false,
   icon,
+  children,
   onConfirm,
   onCancel,
 }: ConfirmDialogProps) {
@@ -80,6 +83,7 @@ export function ConfirmDialog({
                 {description ? (
                   <div className="mt-1 text-sm text-fg-1 leading-relaxed">{description}</div>
                 ) : null}
+                {children}
               </div>
             </div>
           </div>
@@ -0,0 +1,200 @@
+/**
+ * 导出历史卡片
+ * 列出最近的导出文件，可在文件夹中重新打开、按原选项重新导出或删除
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { FileClock, FolderOpen, Trash2, RefreshCw, RotateCw } from 'lucide-react';
+import { Card, CardHeader, CardContent } from './Card';
+import { Button } from './Button';
+import { Badge } from './Badge';
+import { ConfirmDialog } from './ConfirmDialog';
+
+/** 导出记录（与 Rust 端 ExportEntry 对应） */
+export type ExportEntry = {
+  id: string;
+  exportedAt: number;
+  format: 'json' | 'csv' | 'uigf' | 'ww_compat' | 'pdf';
+  path: string;
+  /** 后端导出的选项；存在时可重新导出 */
+  options?: Record<string, unknown>;
+  rowCount: number | null;
+  success: boolean;
+  exists: boolean;
+};
+
+/** 记录一次前端完成的导出 */
+export function recordExport(format: ExportEntry['format'], path: string): Promise<void> {
+  return invoke('record_export_entry', { format, path });
+}
+
+/** 由后端弹出保存对话框完成的导出结果；用户关闭对话框或导出中途取消时为 cancelled */
+export type DialogExportResult =
+  | { status: 'exported'; path: string; taskId: string }
+  | { status: 'cancelled'; taskId: string | null };
+
+/** 导出进度（Rust 端 export-progress 事件） */
+export type ExportProgress = {
+  taskId: string;
+  kind: 'uigf' | 'ww_compat' | 'pdf';
+  stage: 'started' | 'querying' | 'building' | 'writing' | 'finished' | 'cancelled' | 'failed';
+  done: number;
+  total: number;
+};
+
+/** 弹出保存对话框并导出（默认目录为上次导出的目录，文件名含 uid 与日期） */
+export function exportWithDialog(
+  kind: 'uigf' | 'ww_compat' | 'pdf',
+  uid: string | null,
+): Promise<DialogExportResult> {
+  return invoke<DialogExportResult>('export_with_dialog', { kind, uid });
+}
+
+const FORMAT_LABELS: Record<ExportEntry['format'], string> = {
+  json: 'JSON',
+  csv: 'CSV',
+  uigf: 'UIGF',
+  ww_compat: 'WW',
+  pdf: 'PDF',
+};
+
+export function ExportHistoryCard({ refreshKey = 0 }: { refreshKey?: number }) {
+  const { t } = useTranslation();
+  const [entries, setEntries] = useState<ExportEntry[]>([]);
+  const [deleteTarget, setDeleteTarget] = useState<ExportEntry | null>(null);
+  const [rerunningId, setRerunningId] = useState<string | null>(null);
+
+  const reload = useCallback(async () => {
+    try {
+      setEntries(await invoke<ExportEntry[]>('get_export_history'));
+    } catch (e) {
+      console.error('[ExportHistoryCard] Failed to load export history:', e);
+    }
+  }, []);
+
+  useEffect(() => {
+    void reload();
+  }, [reload, refreshKey]);
+
+  const handleOpen = useCallback(async (entry: ExportEntry) => {
+    try {
+      // 文件在列表加载后被删除时返回 false，刷新列表显示为已删除
+      const revealed = await invoke<boolean>('reveal_export', { id: entry.id });
+      if (!revealed) await reload();
+    } catch (e) {
+      console.error('[ExportHistoryCard] Failed to open file:', e);
+    }
+  }, [reload]);
+
+  const handleRerun = useCallback(async (entry: ExportEntry) => {
+    setRerunningId(entry.id);
+    try {
+      await invoke('rerun_export', { id: entry.id });
+    } catch (e) {
+      console.error('[ExportHistoryCard] Failed to rerun export:', e);
+    } finally {
+      setRerunningId(null);
+    }
+    await reload();
+  }, [reload]);
+
+  const handleDelete = useCallback(async (entry: ExportEntry) => {
+    try {
+      await invoke('delete_export_entry', { path: entry.path, deleteFile: entry.exists });
+    } catch (e) {
+      console.error('[ExportHistoryCard] Failed to delete export:', e);
+    }
+    await reload();
+  }, [reload]);
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between gap-3">
+          <div className="flex items-center gap-3">
+            <div className="w-10 h-10 rounded-full bg-brand/20 flex items-center justify-center">
+              <FileClock size={20} className="text-brand" />
+            </div>
+            <div>
+              <h2 className="text-lg font-bold">{t('exportHistory.title', '导出历史')}</h2>
+              <p className="text-sm text-fg-1">{t('exportHistory.desc', '最近导出的文件')}</p>
+            </div>
+          </div>
+          <Button variant="ghost" size="sm" onClick={() => { void reload(); }} icon={<RefreshCw size={14} />}>
+            {t('common.refresh', '刷新')}
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent>
+        {entries.length === 0 ? (
+          <p className="text-sm text-fg-2">{t('exportHistory.empty', '暂无导出记录')}</p>
+        ) : (
+          <div className="space-y-2">
+            {entries.map((entry) => (
+              <div key={entry.id} className="flex items-center gap-3 p-2 rounded-md border border-border text-sm">
+                <Badge>{FORMAT_LABELS[entry.format] ?? entry.format}</Badge>
+                <div className="flex-1 min-w-0">
+                  <div className={`truncate ${entry.exists ? 'text-fg-0' : 'text-fg-2 line-through'}`} title={entry.path}>
+                    {entry.path}
+                  </div>
+                  <div className="text-xs text-fg-2">
+                    {new Date(entry.exportedAt).toLocaleString()}
+                    {entry.rowCount !== null && ` · ${t('exportHistory.rows', '{{count}} 条', { count: entry.rowCount })}`}
+                    {!entry.success
+                      ? ` · ${t('exportHistory.failed', '导出失败')}`
+                      : !entry.exists && ` · ${t('exportHistory.deleted', '已删除')}`}
+                  </div>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  disabled={!entry.exists}
+                  onClick={() => { void handleOpen(entry); }}
+                  icon={<FolderOpen size={14} />}
+                >
+                  {t('exportHistory.open', '打开')}
+                </Button>
+                {entry.options && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    loading={rerunningId === entry.id}
+                    disabled={rerunningId !== null}
+                    onClick={() => { void handleRerun(entry); }}
+                    icon={<RotateCw size={14} />}
+                  >
+                    {t('exportHistory.rerun', '再次导出')}
+                  </Button>
+                )}
+                <Button variant="ghost" size="sm" onClick={() => setDeleteTarget(entry)} icon={<Trash2 size={14} />}>
+                  {t('exportHistory.delete', '删除')}
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+      </CardContent>
+
+      <ConfirmDialog
+        open={!!deleteTarget}
+        title={t('exportHistory.deleteTitle', '删除导出')}
+        description={
+          deleteTarget?.exists
+            ? t('exportHistory.deleteFileDesc', '将从历史中移除并删除文件 {{path}}', { path: deleteTarget.path })
+            : t('exportHistory.deleteEntryDesc', '文件已不存在，将从历史中移除该记录')
+        }
+        danger
+        onConfirm={() => {
+          const entry = deleteTarget;
+          setDeleteTarget(null);
+          if (entry) void handleDelete(entry);
+        }}
+        onCancel={() => setDeleteTarget(null)}
+      />
+    </Card>
+  );
+}
+
+export default ExportHistoryCard;
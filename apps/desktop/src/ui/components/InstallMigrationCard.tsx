@@ -0,0 +1,174 @@
+/**
+ * 安装方式迁移卡片
+ * 在安装版与便携版之间迁移数据，显示迁移报告（确认无误后再删除旧版本）
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { ArrowRightLeft, CheckCircle2, AlertCircle, FolderOpen } from 'lucide-react';
+import { Card, CardHeader, CardContent } from './Card';
+import { Button } from './Button';
+import { Badge } from './Badge';
+import { ConfirmDialog } from './ConfirmDialog';
+import { errorMessage } from '../../lib/errors';
+
+type InstallMode = 'portable' | 'installed';
+
+/** 迁移报告（与 Rust 端 MigrationReport 对应） */
+export type MigrationReport = {
+  target: InstallMode;
+  sourceDir: string;
+  targetDir: string;
+  files: { path: string; bytes: number; verified: boolean }[];
+  totalBytes: number;
+  verified: boolean;
+  registry: { key: string; action: 'updated' | 'unchanged' | 'manual'; detail: string }[];
+  pointerFile: string | null;
+  warnings: string[];
+  migratedAt: number;
+};
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+export function InstallMigrationCard() {
+  const { t } = useTranslation();
+  const [portable, setPortable] = useState<boolean | null>(null);
+  const [targetDir, setTargetDir] = useState<string | null>(null);
+  const [confirmOpen, setConfirmOpen] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [report, setReport] = useState<MigrationReport | null>(null);
+
+  useEffect(() => {
+    invoke<boolean>('is_portable').then(setPortable).catch(() => setPortable(null));
+  }, []);
+
+  const target: InstallMode = portable ? 'installed' : 'portable';
+  // 迁移到安装版时可不选目录（使用安装目录）
+  const needsDir = target === 'portable';
+
+  const handleChooseDir = useCallback(async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected === 'string') setTargetDir(selected);
+  }, []);
+
+  const handleMigrate = useCallback(async () => {
+    setBusy(true);
+    setError(null);
+    setReport(null);
+    try {
+      setReport(await invoke<MigrationReport>('migrate_install_mode', { target, targetDir }));
+    } catch (e) {
+      setError(errorMessage(e));
+    } finally {
+      setBusy(false);
+    }
+  }, [target, targetDir]);
+
+  if (portable === null) return null;
+
+  const targetLabel =
+    target === 'portable' ? t('installMigration.portable', '便携版') : t('installMigration.installed', '安装版');
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center gap-3">
+          <div className="w-10 h-10 rounded-full bg-brand/20 flex items-center justify-center">
+            <ArrowRightLeft size={20} className="text-brand" />
+          </div>
+          <div>
+            <h2 className="text-lg font-bold">{t('installMigration.title', '迁移安装方式')}</h2>
+            <p className="text-sm text-fg-1">
+              {t('installMigration.desc', '当前为{{current}}，可将数据、设置、备份与缓存迁移到{{target}}', {
+                current: portable ? t('installMigration.portable', '便携版') : t('installMigration.installed', '安装版'),
+                target: targetLabel,
+              })}
+            </p>
+          </div>
+        </div>
+      </CardHeader>
+      <CardContent>
+        <div className="space-y-3">
+          <div className="flex items-center gap-2">
+            <Button variant="secondary" size="sm" onClick={() => { void handleChooseDir(); }} icon={<FolderOpen size={14} />}>
+              {t('installMigration.chooseDir', '选择{{target}}所在目录', { target: targetLabel })}
+            </Button>
+            <span className="text-sm text-fg-2 truncate" title={targetDir ?? undefined}>
+              {targetDir ?? (needsDir ? t('installMigration.noDir', '未选择') : t('installMigration.defaultDir', '默认使用安装目录'))}
+            </span>
+          </div>
+          <Button
+            variant="primary"
+            size="sm"
+            loading={busy}
+            disabled={busy || (needsDir && !targetDir)}
+            onClick={() => setConfirmOpen(true)}
+            icon={<ArrowRightLeft size={14} />}
+          >
+            {t('installMigration.migrate', '迁移到{{target}}', { target: targetLabel })}
+          </Button>
+
+          {error && <div className="text-sm text-red-400 break-words">{error}</div>}
+
+          {report && (
+            <div className="space-y-2 p-3 rounded-md border border-border text-sm">
+              <div className={`flex items-center gap-2 ${report.verified ? 'text-green-400' : 'text-red-400'}`}>
+                {report.verified ? <CheckCircle2 size={14} /> : <AlertCircle size={14} />}
+                <span>
+                  {report.verified
+                    ? t('installMigration.verified', '已复制并校验 {{count}} 个文件（{{size}}）', {
+                        count: report.files.length,
+                        size: formatBytes(report.totalBytes),
+                      })
+                    : t('installMigration.unverified', '部分文件校验失败，请勿删除旧版本')}
+                </span>
+              </div>
+              <div className="text-xs text-fg-2 break-all">
+                {report.sourceDir} → {report.targetDir}
+              </div>
+              {report.registry.map((change) => (
+                <div key={change.key} className="flex items-start gap-2 text-xs">
+                  <Badge>{change.action}</Badge>
+                  <div className="min-w-0">
+                    <div className="text-fg-1 break-all">{change.key}</div>
+                    <div className="text-fg-2 break-words">{change.detail}</div>
+                  </div>
+                </div>
+              ))}
+              {report.warnings.map((warning) => (
+                <div key={warning} className="text-xs text-amber-400 break-words">{warning}</div>
+              ))}
+              {report.verified && (
+                <div className="text-xs text-fg-1">
+                  {t('installMigration.nextStep', '请从新位置启动程序并确认数据完整后，再删除旧版本。旧位置已留下说明文件。')}
+                </div>
+              )}
+            </div>
+          )}
+        </div>
+      </CardContent>
+
+      <ConfirmDialog
+        open={confirmOpen}
+        title={t('installMigration.confirmTitle', '迁移安装方式')}
+        description={t('installMigration.confirmDesc', '将复制全部数据到{{target}}目录，旧数据不会被删除。迁移期间请勿同步。', {
+          target: targetLabel,
+        })}
+        onConfirm={() => {
+          setConfirmOpen(false);
+          void handleMigrate();
+        }}
+        onCancel={() => setConfirmOpen(false)}
+      />
+    </Card>
+  );
+}
+
+export default InstallMigrationCard;
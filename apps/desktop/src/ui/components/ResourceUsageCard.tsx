@@ -0,0 +1,81 @@
+/**
+ * 资源占用卡片
+ * 显示主进程内存、Rust 端数据库连接数与后台任务数，用于排查内存占用过高
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { Activity, RefreshCw } from 'lucide-react';
+import { Card, CardHeader, CardContent } from './Card';
+import { Button } from './Button';
+
+/** 资源占用（与 Rust 端 ResourceUsage 对应） */
+export type ResourceUsage = {
+  memoryBytes: number | null;
+  peakMemoryBytes: number | null;
+  dbConnections: number;
+  backgroundTasks: number;
+};
+
+function formatMemory(bytes: number | null): string {
+  if (bytes === null) return '—';
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+export function ResourceUsageCard() {
+  const { t } = useTranslation();
+  const [usage, setUsage] = useState<ResourceUsage | null>(null);
+
+  const refresh = useCallback(() => {
+    invoke<ResourceUsage>('get_resource_usage').then(setUsage).catch(() => setUsage(null));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  if (!usage) return null;
+
+  const rows = [
+    { label: t('about.memoryUsage', '内存占用'), value: formatMemory(usage.memoryBytes) },
+    { label: t('about.peakMemory', '内存峰值'), value: formatMemory(usage.peakMemoryBytes) },
+    { label: t('about.dbConnections', '数据库连接'), value: String(usage.dbConnections) },
+    { label: t('about.backgroundTasks', '后台任务'), value: String(usage.backgroundTasks) },
+  ];
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center justify-between gap-3">
+          <div className="flex items-center gap-3">
+            <div className="w-10 h-10 rounded-full bg-green-500/20 flex items-center justify-center">
+              <Activity size={20} className="text-green-400" />
+            </div>
+            <div>
+              <h2 className="text-lg font-semibold">{t('about.resourceUsage', '资源占用')}</h2>
+              <p className="text-sm text-fg-1">
+                {t('about.resourceUsageDesc', '主进程的资源占用（不含 WebView 渲染进程）')}
+              </p>
+            </div>
+          </div>
+          <Button variant="secondary" size="sm" onClick={refresh} icon={<RefreshCw size={14} />}>
+            {t('about.refreshUsage', '刷新')}
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent>
+        <div className="grid grid-cols-2 sm:grid-cols-4 gap-2">
+          {rows.map((row) => (
+            <div key={row.label} className="p-3 rounded-lg bg-bg-2/50 border border-border/50">
+              <div className="text-xs text-fg-2">{row.label}</div>
+              <div className="text-sm font-mono font-medium text-fg-0 mt-1">{row.value}</div>
+            </div>
+          ))}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
+
+export default ResourceUsageCard;
@@ -0,0 +1,141 @@
+/**
+ * 云同步接口设置卡片
+ * 每行一个接口地址，第一个为主接口，其余为备用；主接口连续失败后自动切换到备用接口
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { listen } from '@tauri-apps/api/event';
+import { Save, Server } from 'lucide-react';
+import { Card, CardHeader, CardContent } from './Card';
+import { Button } from './Button';
+import { Badge } from './Badge';
+import {
+  getEndpointStatuses,
+  getSyncEndpoints,
+  setSyncEndpoints,
+  type EndpointStatus,
+} from '../../lib/syncApi';
+import { errorMessage } from '../../lib/errors';
+
+type StatusState = { type: 'success' | 'error'; text: string } | null;
+
+export function SyncEndpointsCard() {
+  const { t } = useTranslation();
+  const [text, setText] = useState('');
+  const [statuses, setStatuses] = useState<EndpointStatus[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [status, setStatus] = useState<StatusState>(null);
+
+  const reloadStatuses = useCallback(async () => {
+    setStatuses(await getEndpointStatuses());
+  }, []);
+
+  useEffect(() => {
+    void getSyncEndpoints()
+      .then((endpoints) => setText(endpoints.join('\n')))
+      .catch((e: unknown) => {
+        console.error('[SyncEndpointsCard] Failed to load endpoints:', e);
+      });
+    void reloadStatuses().catch((e: unknown) => {
+      console.error('[SyncEndpointsCard] Failed to load endpoint statuses:', e);
+    });
+  }, [reloadStatuses]);
+
+  // 接口切换后刷新状态
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    void listen('sync-endpoint-changed', () => {
+      void reloadStatuses().catch(() => {});
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[SyncEndpointsCard] Failed to listen sync-endpoint-changed:', e);
+      });
+    return () => {
+      unlisten?.();
+    };
+  }, [reloadStatuses]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setStatus(null);
+    try {
+      const endpoints = text
+        .split('\n')
+        .map((line) => line.trim())
+        .filter(Boolean);
+      const saved = await setSyncEndpoints(endpoints);
+      setText(saved.join('\n'));
+      await reloadStatuses();
+      setStatus({ type: 'success', text: t('syncEndpoints.saved', '同步接口已保存') });
+    } catch (e) {
+      setStatus({ type: 'error', text: errorMessage(e) });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center gap-3">
+          <div className="w-10 h-10 rounded-full bg-brand/20 flex items-center justify-center">
+            <Server size={20} className="text-brand" />
+          </div>
+          <div>
+            <h2 className="text-lg font-bold">{t('syncEndpoints.title', '同步接口')}</h2>
+            <p className="text-sm text-fg-1">
+              {t('syncEndpoints.desc', '每行一个地址，第一个为主接口；主接口连续失败时自动切换到备用接口。留空使用内置地址')}
+            </p>
+          </div>
+        </div>
+      </CardHeader>
+      <CardContent>
+        <div className="space-y-4">
+          <textarea
+            value={text}
+            onChange={(e) => setText(e.target.value)}
+            rows={3}
+            placeholder="https://sync.example.com/api"
+            className="w-full px-3 py-2 rounded-md bg-bg-2 border border-border text-xs font-mono text-fg-0 resize-none"
+          />
+
+          <div className="flex gap-3">
+            <Button variant="primary" onClick={() => { void handleSave(); }} loading={saving} icon={<Save size={16} />}>
+              {t('common.save', '保存')}
+            </Button>
+          </div>
+
+          {statuses.length > 0 && (
+            <div className="space-y-2">
+              {statuses.map((s) => (
+                <div key={s.endpoint} className="p-3 rounded-md bg-bg-2 text-sm">
+                  <div className="flex items-center justify-between gap-3">
+                    <span className="font-mono text-xs text-fg-0 truncate">{s.endpoint}</span>
+                    {s.active ? (
+                      <Badge variant="success">{t('syncEndpoints.active', '当前使用')}</Badge>
+                    ) : s.consecutive_failures > 0 ? (
+                      <Badge variant="warning">
+                        {t('syncEndpoints.failures', '连续失败 {{count}} 次', { count: s.consecutive_failures })}
+                      </Badge>
+                    ) : null}
+                  </div>
+                  {s.last_error && <div className="mt-1 text-xs text-fg-2 break-all">{s.last_error}</div>}
+                </div>
+              ))}
+            </div>
+          )}
+
+          {status && (
+            <div className={`text-sm ${status.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>
+              {status.text}
+            </div>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
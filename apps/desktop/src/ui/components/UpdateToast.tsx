@@ -1,6 +1,6 @@
 import { useMemo } from 'react';
 import { useNavigate } from 'react-router-dom';
-import { Download, RefreshCw, Sparkles, X } from 'lucide-react';
+import { Download, ExternalLink, RefreshCw, ShieldAlert, Sparkles, X } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { Button } from './Button';
 import { useUpdate } from '../../hooks/update';
@@ -8,7 +8,19 @@ import { useUpdate } from '../../hooks/update';
 export function UpdateToast() {
   const { t } = useTranslation();
   const navigate = useNavigate();
-  const { status, updateInfo, progress, toastOpen, setToastOpen, downloadAndInstall, restartApp, isPortable } = useUpdate();
+  const {
+    status,
+    updateInfo,
+    progress,
+    toastOpen,
+    setToastOpen,
+    downloadAndInstall,
+    restartApp,
+    isPortable,
+    failureStatus,
+    dismissManualNotice,
+    openReleasePage,
+  } = useUpdate();
 
   const visible = toastOpen && (status === 'available' || status === 'downloading' || status === 'ready');
   const title = useMemo(() => {
@@ -24,7 +36,71 @@ export function UpdateToast() {
     return t('updater.toastAvailableDesc', '版本 {{version}} 已发布', { version: v });
   }, [status, t, updateInfo?.version]);
 
-  if (!visible) return null;
+  if (!visible) {
+    // 自动更新反复以同样原因失败：只提示一次改为手动更新
+    if (!failureStatus?.showManualNotice) return null;
+    return (
+      <div className="fixed bottom-5 right-5 z-[10002] w-[360px] max-w-[calc(100vw-40px)]">
+        <div className="ef-update-toast relative rounded-xl overflow-hidden">
+          <div
+            className="absolute inset-x-0 top-0 h-1"
+            style={{ background: 'linear-gradient(to right, #f59e0b, #f97316, rgba(245, 158, 11, 0.4))' }}
+          />
+          <div className="relative p-4">
+            <div className="flex items-start gap-3">
+              <div
+                className="ef-update-toast-icon mt-0.5 w-11 h-11 rounded-lg flex items-center justify-center shrink-0"
+                style={{
+                  backgroundColor: 'rgba(245, 158, 11, 0.15)',
+                  boxShadow: 'inset 0 0 0 1px rgba(245, 158, 11, 0.3)',
+                }}
+              >
+                <ShieldAlert size={20} />
+              </div>
+              <div className="min-w-0 flex-1">
+                <div className="flex items-start justify-between gap-2">
+                  <div className="min-w-0">
+                    <div className="ef-update-toast-title text-sm font-semibold">
+                      {t('updater.manualTitle', '手动更新')}
+                    </div>
+                    <div className="ef-update-toast-subtitle mt-0.5 text-xs">
+                      {t('updater.manualDesc', '{{reason}}，已停止自动提醒，请前往发布页下载新版本。', {
+                        reason: t(`updater.failureKind.${failureStatus.failureKind ?? 'other'}`, '自动更新多次失败'),
+                      })}
+                    </div>
+                  </div>
+                  <button
+                    type="button"
+                    className="ef-update-toast-close p-1.5 rounded-md transition-colors"
+                    aria-label={t('common.dismiss', '关闭')}
+                    onClick={() => void dismissManualNotice()}
+                  >
+                    <X size={16} />
+                  </button>
+                </div>
+                <div className="mt-3 flex items-center gap-2">
+                  <Button
+                    variant="primary"
+                    size="sm"
+                    onClick={() => {
+                      void openReleasePage();
+                      void dismissManualNotice();
+                    }}
+                    icon={<ExternalLink size={14} />}
+                  >
+                    {t('updater.openReleasePage', '打开发布页')}
+                  </Button>
+                  <Button variant="ghost" size="sm" onClick={() => void dismissManualNotice()}>
+                    {t('updater.toastLater', '稍后')}
+                  </Button>
+                </div>
+              </div>
+            </div>
+          </div>
+        </div>
+      </div>
+    );
+  }
 
   // 根据状态确定颜色方案
   const glowColor = status === 'ready' 
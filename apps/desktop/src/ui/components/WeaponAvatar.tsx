@@ -5,6 +5,7 @@
 
 import { useState } from 'react';
 import { X } from 'lucide-react';
+import { getCachedIconSrc } from '../../lib/icons';
 
 export type WeaponAvatarProps = {
   /** 武器ID（对应 public/wpnimg/<id>.png） */
@@ -49,6 +50,8 @@ export function WeaponAvatar({
 }: WeaponAvatarProps) {
   const [imgError, setImgError] = useState(false);
   const [imgLoaded, setImgLoaded] = useState(false);
+  // 内置图片缺失时改用本地图标缓存
+  const [cachedSrc, setCachedSrc] = useState<string | null>(null);
 
   const sizeClass = sizeMap[size];
   const iconSize = iconSizeMap[size];
@@ -77,6 +80,18 @@ export function WeaponAvatar({
   }
 
   const imagePath = `/wpnimg/${weaponId}.png`;
+  const itemId: string = weaponId;
+
+  const handleImgError = () => {
+    if (cachedSrc) {
+      setImgError(true);
+      return;
+    }
+    void getCachedIconSrc(itemId).then((src) => {
+      if (src) setCachedSrc(src);
+      else setImgError(true);
+    });
+  };
 
   const borderClass = (() => {
     if (isUp) return 'border-orange-500 shadow-lg shadow-orange-500/30';
@@ -92,11 +107,11 @@ export function WeaponAvatar({
       <div className={`w-full h-full rounded-full border-2 ${borderClass} overflow-hidden bg-bg-3 relative`}>
         {!imgLoaded && <div className="absolute inset-0 bg-bg-3 animate-pulse" />}
         <img
-          src={imagePath}
+          src={cachedSrc ?? imagePath}
           alt=""
           className={`w-full h-full object-cover transition-opacity duration-300 ${imgLoaded ? 'opacity-100' : 'opacity-0'}`}
           onLoad={() => setImgLoaded(true)}
-          onError={() => setImgError(true)}
+          onError={handleImgError}
         />
       </div>
 
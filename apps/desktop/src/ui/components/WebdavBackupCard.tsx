@@ -0,0 +1,380 @@
+/**
+ * WebDAV 异地备份设置卡片
+ * 连接配置、自动上传开关、远端备份列表与恢复
+ */
+
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import { CloudUpload, Download, FolderOpen, HardDriveDownload, PlugZap, RefreshCw, Save, X } from 'lucide-react';
+import { Card, CardHeader, CardContent } from './Card';
+import { Button } from './Button';
+import { Input } from './Input';
+import { ConfirmDialog } from './ConfirmDialog';
+import { closeDB } from '../../lib/db';
+import {
+  compareBackupRevision,
+  createDbBackup,
+  downloadRemoteBackup,
+  getDbBackupSchedule,
+  getWebdavSettings,
+  listRemoteBackups,
+  mergeDbBackup,
+  restoreDbBackup,
+  setBackupDir,
+  setDbBackupSchedule,
+  setWebdav,
+  setWebdavBackupOptions,
+  testWebdav,
+  type BackupSchedule,
+  type WebdavEntry,
+  type WebdavSettings,
+} from '../../lib/webdav';
+import { errorMessage } from '../../lib/errors';
+
+type StatusState = { type: 'success' | 'error'; text: string } | null;
+
+/** 定时自动备份可选间隔（小时） */
+const BACKUP_INTERVAL_OPTIONS = [6, 12, 24, 72, 168] as const;
+
+export function WebdavBackupCard() {
+  const { t } = useTranslation();
+  const [settings, setSettings] = useState<WebdavSettings | null>(null);
+  const [url, setUrl] = useState('');
+  const [username, setUsername] = useState('');
+  const [password, setPassword] = useState('');
+  const [busy, setBusy] = useState<string | null>(null);
+  const [status, setStatus] = useState<StatusState>(null);
+  const [remoteBackups, setRemoteBackups] = useState<WebdavEntry[]>([]);
+  const [restoreTarget, setRestoreTarget] = useState<string | null>(null);
+  // 备份比本地数据旧：改为合并前的确认
+  const [conflict, setConflict] = useState<{ path: string; warning: string } | null>(null);
+  const [schedule, setSchedule] = useState<BackupSchedule | null>(null);
+
+  const reload = useCallback(async () => {
+    const next = await getWebdavSettings();
+    setSettings(next);
+    setUrl(next.url);
+    setUsername(next.username);
+    setPassword('');
+  }, []);
+
+  useEffect(() => {
+    void reload().catch((e: unknown) => {
+      console.error('[WebdavBackupCard] Failed to load settings:', e);
+    });
+    void getDbBackupSchedule()
+      .then(setSchedule)
+      .catch((e: unknown) => {
+        console.error('[WebdavBackupCard] Failed to load backup schedule:', e);
+      });
+  }, [reload]);
+
+  // 后台自动上传的结果
+  useEffect(() => {
+    const unlisteners: Array<() => void> = [];
+    void listen<{ remotePath: string }>('webdav-backup-uploaded', (event) => {
+      setStatus({ type: 'success', text: t('webdav.uploaded', '备份已上传到 {{path}}', { path: event.payload.remotePath }) });
+    }).then((fn) => unlisteners.push(fn));
+    void listen<{ error: string }>('webdav-backup-failed', (event) => {
+      setStatus({ type: 'error', text: t('webdav.uploadFailed', '备份上传失败：{{error}}', { error: event.payload.error }) });
+    }).then((fn) => unlisteners.push(fn));
+    // 本地备份已成功，只是外部目录写入失败
+    void listen<{ error: string }>('backup-external-failed', (event) => {
+      setStatus({ type: 'error', text: t('webdav.externalBackupFailed', '本地备份已完成，但复制到外部目录失败：{{error}}', { error: event.payload.error }) });
+    }).then((fn) => unlisteners.push(fn));
+    return () => {
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [t]);
+
+  const run = useCallback(async (key: string, task: () => Promise<string | void>) => {
+    setBusy(key);
+    setStatus(null);
+    try {
+      const text = await task();
+      if (text) setStatus({ type: 'success', text });
+    } catch (e: unknown) {
+      setStatus({ type: 'error', text: errorMessage(e) });
+    } finally {
+      setBusy(null);
+    }
+  }, []);
+
+  const handleSave = () =>
+    run('save', async () => {
+      await setWebdav(url, username, password);
+      await reload();
+      return t('webdav.saved', 'WebDAV 设置已保存');
+    });
+
+  const handleTest = () =>
+    run('test', async () => {
+      await testWebdav();
+      return t('webdav.testOk', '连接正常，具有写入权限');
+    });
+
+  const handleBackupNow = () =>
+    run('backup', async () => {
+      const path = await createDbBackup();
+      return t('webdav.backupCreated', '已创建本地备份：{{path}}', { path });
+    });
+
+  const handleToggleUpload = (uploadAfterBackup: boolean) =>
+    run('options', async () => {
+      if (!settings) return;
+      await setWebdavBackupOptions(uploadAfterBackup, settings.keepRemote);
+      await reload();
+    });
+
+  const handleKeepChange = (keepRemote: number) =>
+    run('options', async () => {
+      if (!settings || !Number.isFinite(keepRemote) || keepRemote < 1) return;
+      await setWebdavBackupOptions(settings.uploadAfterBackup, keepRemote);
+      await reload();
+    });
+
+  const handleScheduleChange = (value: string) =>
+    run('schedule', async () => {
+      await setDbBackupSchedule(value ? Number(value) : null);
+      setSchedule(await getDbBackupSchedule());
+    });
+
+  const handleChooseExternalDir = () =>
+    run('externalDir', async () => {
+      const selected = await open({ directory: true, multiple: false });
+      if (typeof selected !== 'string') return;
+      await setBackupDir(selected);
+      setSchedule(await getDbBackupSchedule());
+    });
+
+  const handleClearExternalDir = () =>
+    run('externalDir', async () => {
+      await setBackupDir(null);
+      setSchedule(await getDbBackupSchedule());
+    });
+
+  const handleListRemote = () =>
+    run('list', async () => {
+      setRemoteBackups(await listRemoteBackups());
+    });
+
+  // 下载并恢复：备份比本地旧时先提示（可改为合并）；替换前关闭前端数据库连接，完成后重新加载
+  const handleRestore = (name: string) =>
+    run('restore', async () => {
+      const path = await downloadRemoteBackup(name);
+      const comparison = await compareBackupRevision(path);
+      if (comparison.warning) {
+        setConflict({ path, warning: comparison.warning });
+        return;
+      }
+      await closeDB();
+      await restoreDbBackup(path);
+      window.location.reload();
+    });
+
+  const handleMerge = (path: string) =>
+    run('restore', async () => {
+      await mergeDbBackup(path);
+      window.location.reload();
+    });
+
+  const configured = !!settings?.url;
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-center gap-3">
+          <div className="w-10 h-10 rounded-full bg-brand/20 flex items-center justify-center">
+            <CloudUpload size={20} className="text-brand" />
+          </div>
+          <div>
+            <h2 className="text-lg font-bold">{t('webdav.title', 'WebDAV 异地备份')}</h2>
+            <p className="text-sm text-fg-1">
+              {t('webdav.desc', '备份上传到 WebDAV（如坚果云）的 efgh 目录，换机时可从远端恢复')}
+            </p>
+          </div>
+        </div>
+      </CardHeader>
+      <CardContent>
+        <div className="space-y-4">
+          <div className="grid grid-cols-1 gap-3">
+            <Input
+              label={t('webdav.url', '服务器地址')}
+              placeholder="https://dav.jianguoyun.com/dav"
+              value={url}
+              onChange={(e) => setUrl(e.target.value)}
+            />
+            <div className="grid grid-cols-2 gap-3">
+              <Input
+                label={t('webdav.username', '用户名')}
+                value={username}
+                onChange={(e) => setUsername(e.target.value)}
+              />
+              <Input
+                label={t('webdav.password', '密码 / 应用密码')}
+                type="password"
+                placeholder={settings?.hasPassword ? t('webdav.passwordKeep', '留空则不修改') : ''}
+                value={password}
+                onChange={(e) => setPassword(e.target.value)}
+              />
+            </div>
+          </div>
+
+          <div className="flex gap-3">
+            <Button variant="primary" onClick={() => { void handleSave(); }} loading={busy === 'save'} icon={<Save size={16} />}>
+              {t('common.save', '保存')}
+            </Button>
+            <Button variant="secondary" onClick={() => { void handleTest(); }} loading={busy === 'test'} disabled={!configured} icon={<PlugZap size={16} />}>
+              {t('webdav.test', '测试连接')}
+            </Button>
+            <Button variant="secondary" onClick={() => { void handleBackupNow(); }} loading={busy === 'backup'} icon={<HardDriveDownload size={16} />}>
+              {t('webdav.backupNow', '立即备份')}
+            </Button>
+          </div>
+
+          {settings && (
+            <div className="flex items-center justify-between gap-4 p-3 rounded-md bg-bg-2 text-sm">
+              <label className="flex items-center gap-2 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={settings.uploadAfterBackup}
+                  disabled={!configured || busy === 'options'}
+                  onChange={(e) => { void handleToggleUpload(e.target.checked); }}
+                />
+                {t('webdav.uploadAfterBackup', '创建备份后自动上传')}
+              </label>
+              <label className="flex items-center gap-2">
+                {t('webdav.keepRemote', '远端保留份数')}
+                <input
+                  type="number"
+                  min={1}
+                  className="w-16 rounded-md border border-border bg-bg-1 px-2 py-1"
+                  defaultValue={settings.keepRemote}
+                  key={settings.keepRemote}
+                  onBlur={(e) => { void handleKeepChange(Number(e.target.value)); }}
+                />
+              </label>
+            </div>
+          )}
+
+          {schedule && (
+            <div className="flex items-center justify-between gap-4 p-3 rounded-md bg-bg-2 text-sm">
+              <label className="flex items-center gap-2">
+                {t('webdav.autoBackup', '定时自动备份')}
+                <select
+                  className="rounded-md border border-border bg-bg-1 px-2 py-1"
+                  value={schedule.intervalHours ?? ''}
+                  disabled={busy === 'schedule'}
+                  onChange={(e) => { void handleScheduleChange(e.target.value); }}
+                >
+                  <option value="">{t('webdav.autoBackupOff', '关闭')}</option>
+                  {BACKUP_INTERVAL_OPTIONS.map((hours) => (
+                    <option key={hours} value={hours}>
+                      {t('webdav.autoBackupEvery', '每 {{hours}} 小时', { hours })}
+                    </option>
+                  ))}
+                </select>
+              </label>
+              {schedule.lastBackup && (
+                <span className="truncate text-fg-2" title={schedule.lastBackup}>
+                  {t('webdav.lastAutoBackup', '最近：{{path}}', { path: schedule.lastBackup.split(/[\\/]/).pop() })}
+                </span>
+              )}
+            </div>
+          )}
+
+          {schedule && (
+            <div className="flex items-center justify-between gap-4 p-3 rounded-md bg-bg-2 text-sm">
+              <div className="min-w-0">
+                <div>{t('webdav.externalDir', '外部备份目录')}</div>
+                <div className="truncate text-fg-2" title={schedule.externalDir ?? undefined}>
+                  {schedule.externalDir ?? t('webdav.externalDirNone', '未设置，仅备份到本地')}
+                </div>
+              </div>
+              <div className="flex shrink-0 gap-2">
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => { void handleChooseExternalDir(); }}
+                  loading={busy === 'externalDir'}
+                  icon={<FolderOpen size={14} />}
+                >
+                  {t('webdav.externalDirChoose', '选择目录')}
+                </Button>
+                {schedule.externalDir && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => { void handleClearExternalDir(); }}
+                    disabled={busy !== null}
+                    icon={<X size={14} />}
+                  >
+                    {t('webdav.externalDirClear', '取消')}
+                  </Button>
+                )}
+              </div>
+            </div>
+          )}
+
+          {configured && (
+            <div className="space-y-2">
+              <Button variant="ghost" size="sm" onClick={() => { void handleListRemote(); }} loading={busy === 'list'} icon={<RefreshCw size={14} />}>
+                {t('webdav.listRemote', '查看远端备份')}
+              </Button>
+              {remoteBackups.map((entry) => (
+                <div key={entry.path} className="flex items-center justify-between p-2 rounded-md border border-border text-sm">
+                  <span className="truncate">{entry.name}</span>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => setRestoreTarget(entry.name)}
+                    disabled={busy !== null}
+                    icon={<Download size={14} />}
+                  >
+                    {t('webdav.restore', '恢复')}
+                  </Button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {status && (
+            <div className={`text-sm ${status.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>
+              {status.text}
+            </div>
+          )}
+        </div>
+      </CardContent>
+
+      <ConfirmDialog
+        open={!!restoreTarget}
+        title={t('webdav.restoreTitle', '从远端备份恢复')}
+        description={t('webdav.restoreDesc', '将用 {{name}} 替换当前数据库（当前数据会先自动备份），完成后重新加载。', { name: restoreTarget ?? '' })}
+        danger
+        onConfirm={() => {
+          const name = restoreTarget;
+          setRestoreTarget(null);
+          if (name) void handleRestore(name);
+        }}
+        onCancel={() => setRestoreTarget(null)}
+      />
+
+      <ConfirmDialog
+        open={!!conflict}
+        title={t('webdav.conflictTitle', '备份比本地数据旧')}
+        description={conflict?.warning ?? ''}
+        confirmText={t('webdav.merge', '合并')}
+        onConfirm={() => {
+          const path = conflict?.path;
+          setConflict(null);
+          if (path) void handleMerge(path);
+        }}
+        onCancel={() => setConflict(null)}
+      />
+    </Card>
+  );
+}
+
+export default WebdavBackupCard;
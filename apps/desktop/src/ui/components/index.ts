@@ -9,6 +9,13 @@ export { Popover } from './Popover';
 export { Modal } from './Modal';
 export { SyncAuthModal } from './SyncAuthModal';
 export { LegalModal } from './LegalModal';
+export { ChangelogModal } from './ChangelogModal';
+export { WebdavBackupCard } from './WebdavBackupCard';
+export { InstallMigrationCard } from './InstallMigrationCard';
+export { ResourceUsageCard } from './ResourceUsageCard';
+export type { ResourceUsage } from './ResourceUsageCard';
+export { ExportHistoryCard, recordExport, exportWithDialog } from './ExportHistoryCard';
+export type { ExportProgress } from './ExportHistoryCard';
 export { ParticleBackground } from './ParticleBackground';
 export { PageTransition } from './PageTransition';
 export { CharacterAvatar } from './CharacterAvatar';
@@ -16,4 +23,7 @@ export { WeaponAvatar } from './WeaponAvatar';
 export { PityStatusPanel } from './PityStatusPanel';
 export { HelpTooltip } from './HelpTooltip';
 export { UpdateToast } from './UpdateToast';
+export { BannerTransitionToast } from './BannerTransitionToast';
+export { ClockSkewToast } from './ClockSkewToast';
+export { SyncEndpointsCard } from './SyncEndpointsCard';
 export { InteractionLockOverlay } from './InteractionLockOverlay';
@@ -7,7 +7,9 @@
 import { useCallback, useEffect, useMemo, useState } from 'react';
 import { NavLink, useLocation, useNavigate, useOutlet } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import {
   Moon,
   Sun,
@@ -26,16 +28,33 @@ import {
   PanelLeftClose,
   PanelLeftOpen,
   Trophy,
+  Scale,
 } from 'lucide-react';
 import { setTheme, useTheme } from '../theme';
 import { useAccounts } from '../../hooks/useEndfield';
 import { useSyncConfig, useSyncHealth } from '../../hooks/useSync';
 import { useTray } from '../../hooks/useTray';
-import { TitleBar, ParticleBackground, CloseConfirmModal, PageTransition, UpdateToast, InteractionLockOverlay } from '../components';
+import { useHeartbeat } from '../../hooks/useHeartbeat';
+import { TitleBar, ParticleBackground, CloseConfirmModal, PageTransition, UpdateToast, BannerTransitionToast, ClockSkewToast, InteractionLockOverlay, ConfirmDialog, ChangelogModal } from '../components';
 import { formatDistanceToNow } from '../../lib/dateUtils';
-import { getSidebarCollapsed, parseAccountKey, setSidebarCollapsed } from '../../lib/storage';
+import { closeDB, getDemoMode, setDemoMode } from '../../lib/db';
+import { errorMessage } from '../../lib/errors';
+import {
+  clearActiveUid,
+  getActiveUid,
+  getSidebarCollapsed,
+  parseAccountKey,
+  setActiveUid,
+  setSidebarCollapsed,
+} from '../../lib/storage';
 import { useUpdate } from '../../hooks/update';
 
+type SchemaMismatch = {
+  missingColumns: string[];
+  backupPath: string | null;
+  message: string;
+};
+
 type NavItem = {
   path: string;
   labelKey: string;
@@ -49,6 +68,7 @@ const navItems: NavItem[] = [
   { path: '/account', labelKey: 'nav.account', icon: <UserPlus size={20} /> },
   { path: '/cloud-sync', labelKey: 'nav.cloudSync', icon: <Cloud size={20} /> },
   { path: '/leaderboard', labelKey: 'nav.leaderboard', icon: <Trophy size={20} /> },
+  { path: '/compare', labelKey: 'nav.compare', icon: <Scale size={20} /> },
   { path: '/settings', labelKey: 'nav.settings', icon: <Settings size={20} /> },
   { path: '/about', labelKey: 'nav.about', icon: <Info size={20} /> },
 ];
@@ -63,6 +83,7 @@ export function MainLayout() {
   const { status, lastSyncAt } = useSyncConfig();
   const { hasUpdate } = useUpdate();
   useSyncHealth(); // 初始化健康检查
+  useHeartbeat(); // 向 Rust 端报告前端存活
   const [sidebarCollapsed, setSidebarCollapsedState] = useState<boolean>(() => getSidebarCollapsed());
 
   // 托盘功能
@@ -72,6 +93,12 @@ export function MainLayout() {
     handleCloseBehavior,
   } = useTray();
 
+  // 数据库结构与当前版本不一致（恢复旧备份等），需用户确认修复
+  const [schemaMismatch, setSchemaMismatch] = useState<SchemaMismatch | null>(null);
+
+  // 演示模式：读写都落在临时库，页头显著标注
+  const [demoMode, setDemoModeState] = useState(false);
+
   const nextTheme = theme === 'dark' ? 'light' : 'dark';
   const [themeToggleAnimating, setThemeToggleAnimating] = useState(false);
 
@@ -91,6 +118,14 @@ export function MainLayout() {
     setTheme(nextTheme);
   }, [nextTheme]);
 
+  // 上报当前路由：WebView 崩溃或窗口关闭后 Rust 端重建窗口时回到该页面
+  useEffect(() => {
+    const label = getCurrentWindow().label;
+    void invoke('report_route', { label, path: location.pathname }).catch((e: unknown) => {
+      console.error('[MainLayout] Failed to report route:', e);
+    });
+  }, [location.pathname]);
+
   // 响应托盘菜单发起的跳转（例如：点击"登录云同步账号"）
   useEffect(() => {
     let unlisten: (() => void) | undefined;
@@ -113,6 +148,159 @@ export function MainLayout() {
     };
   }, [navigate]);
 
+  // 同步完成且有新增记录时跳转到抽卡记录页并高亮新记录
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen<{ uid: string; syncRunId: number; newRecordIds: string[]; topRarity: number | null }>(
+      'sync-finished',
+      (event) => {
+        const { newRecordIds, topRarity } = event.payload;
+        if (newRecordIds.length > 0) {
+          void navigate('/records', { state: { highlightRecordIds: newRecordIds, topRarity } });
+        }
+      },
+    )
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to listen sync-finished:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, [navigate]);
+
+  // 响应托盘等其他入口切换激活账号（Rust 端广播）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen<{ uid: string | null }>('active-account-changed', (event) => {
+      const uid = event.payload?.uid;
+      if (typeof uid === 'string' && uid.length > 0) {
+        if (uid !== getActiveUid()) setActiveUid(uid);
+      } else if (getActiveUid() !== null) {
+        clearActiveUid();
+      }
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to listen active-account-changed:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // 通过文件关联/启动参数打开的数据文件：跳转到设置页导入
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    const openFile = (request: { path: string; content: string } | null) => {
+      if (request) {
+        void navigate('/settings', { state: { openFile: request } });
+      }
+    };
+
+    void invoke<{ path: string; content: string } | null>('take_pending_open_file')
+      .then(openFile)
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to take pending open file:', e);
+      });
+
+    void listen<{ path: string; content: string }>('open-file', (event) => {
+      openFile(event.payload);
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to listen open-file:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, [navigate]);
+
+  // 程序所在的可移动存储即将被移除：Rust 端已做 checkpoint，这里关闭数据库连接
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen('storage-removing', () => {
+      void closeDB().catch((e: unknown) => {
+        console.error('[MainLayout] Failed to close database:', e);
+      });
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to listen storage-removing:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // 启动时检测到的数据库结构问题：提示用户修复（修复前已自动备份）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void invoke<SchemaMismatch | null>('get_schema_mismatch')
+      .then(setSchemaMismatch)
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to get schema mismatch:', e);
+      });
+
+    void listen<SchemaMismatch>('schema-mismatch', (event) => {
+      setSchemaMismatch(event.payload);
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to listen schema-mismatch:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  useEffect(() => {
+    void getDemoMode()
+      .then(setDemoModeState)
+      .catch((e: unknown) => {
+        console.error('[MainLayout] Failed to get demo mode:', e);
+      });
+  }, []);
+
+  const handleExitDemoMode = useCallback(async () => {
+    try {
+      await setDemoMode(false);
+      window.location.reload();
+    } catch (e: unknown) {
+      console.error('[MainLayout] Failed to exit demo mode:', e);
+    }
+  }, []);
+
+  const handleRepairSchema = useCallback(async () => {
+    try {
+      await closeDB();
+      await invoke('repair_schema');
+      window.location.reload();
+    } catch (e: unknown) {
+      console.error('[MainLayout] Failed to repair schema:', e);
+      setSchemaMismatch((prev) => (prev ? { ...prev, message: errorMessage(e) } : prev));
+    }
+  }, []);
+
   const toggleSidebar = useCallback(() => {
     setSidebarCollapsedState((prev) => {
       const next = !prev;
@@ -160,6 +348,23 @@ export function MainLayout() {
         onConfirm={(behavior, remember) => { void handleCloseBehavior(behavior, remember); }}
       />
 
+      {/* 数据库结构修复确认 */}
+      <ConfirmDialog
+        open={!!schemaMismatch}
+        title={t('schema.mismatchTitle', '数据库结构需要修复')}
+        description={
+          schemaMismatch
+            ? schemaMismatch.backupPath
+              ? `${schemaMismatch.message} ${t('schema.backupAt', '已备份到：{{path}}', { path: schemaMismatch.backupPath })}`
+              : schemaMismatch.message
+            : ''
+        }
+        confirmText={t('schema.repair', '修复')}
+        cancelText={t('common.cancel', '取消')}
+        onConfirm={() => { void handleRepairSchema(); }}
+        onCancel={() => setSchemaMismatch(null)}
+      />
+
       {/* 主内容区域 */}
       <div className="flex-1 flex gap-4 p-4 min-h-0 mx-auto w-full max-w-7xl">
         {/* 侧边栏 */}
@@ -379,6 +584,17 @@ export function MainLayout() {
                 {getCurrentTitle()}
               </h1>
             </div>
+
+            {demoMode && (
+              <button
+                type="button"
+                className="relative ml-4 mr-auto inline-flex items-center gap-2 rounded-md border border-amber-400/60 bg-amber-500/15 px-3 py-1 text-sm font-bold text-amber-400 animate-pulse hover:bg-amber-500/25 transition-colors"
+                onClick={() => { void handleExitDemoMode(); }}
+                title={t('demoMode.exitHint', '演示期间的修改不会保存，点击退出演示模式')}
+              >
+                {t('demoMode.badge', '演示模式')}
+              </button>
+            )}
             
             <div className="relative flex items-center gap-2">
               {/* 主题切换按钮 - 带动画 */}
@@ -416,6 +632,11 @@ export function MainLayout() {
 
       {/* 全局更新提示（非打断式） */}
       <UpdateToast />
+      <BannerTransitionToast />
+      <ClockSkewToast />
+
+      {/* 更新后首次启动：新版本说明（仅弹一次） */}
+      <ChangelogModal />
 
       {/* 全局交互锁定遮罩（同步等长任务期间） */}
       <InteractionLockOverlay />
@@ -22,7 +22,7 @@ import {
 } from 'lucide-react';
 import { useUpdate } from '../../hooks/update';
 import { useAppInfo } from '../../hooks/useAppInfo';
-import { Card, CardHeader, CardContent, Button, Badge, LegalModal } from '../components';
+import { Card, CardHeader, CardContent, Button, Badge, LegalModal, ResourceUsageCard } from '../components';
 
 /** 开源软件许可证徽章组件 */
 function OSSBadge({ name, license, url }: { name: string; license: string; url: string }) {
@@ -61,6 +61,9 @@ export function AboutPage() {
     downloadAndInstall, 
     restartApp,
     isPortable,
+    failureStatus,
+    openReleasePage,
+    retryAfterFailures,
   } = useUpdate();
 
   const lastCheckedLabel = useMemo(() => {
@@ -237,6 +240,44 @@ export function AboutPage() {
                 </div>
               )}
 
+              {/* 同一原因反复失败，已停止自动检查 */}
+              {failureStatus?.suppressed && updateStatus !== 'checking' && updateStatus !== 'downloading' && (
+                <div className="flex items-start justify-between gap-3 text-amber-400 text-sm p-3 rounded-md bg-amber-500/10 border border-amber-500/20">
+                  <div className="flex items-start gap-2 min-w-0">
+                    <AlertCircle size={14} className="mt-0.5 shrink-0" />
+                    <span className="break-words text-fg-0/90">
+                      {t('updater.suppressedDesc', '{{reason}}，已停止自动检查更新。可前往发布页手动下载，或在网络环境变化后重试。', {
+                        reason: t(`updater.failureKind.${failureStatus.failureKind ?? 'other'}`, '自动更新多次失败'),
+                      })}
+                    </span>
+                  </div>
+                  <div className="flex items-center gap-2 shrink-0">
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => {
+                        void openReleasePage();
+                      }}
+                      className="text-amber-300 hover:bg-amber-500/10 border border-amber-500/20"
+                      icon={<ExternalLink size={14} />}
+                    >
+                      {t('updater.manualTitle', '手动更新')}
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => {
+                        void retryAfterFailures();
+                      }}
+                      className="text-amber-300 hover:bg-amber-500/10 border border-amber-500/20"
+                      icon={<RefreshCw size={14} />}
+                    >
+                      {t('updater.tryAgain', '再试一次')}
+                    </Button>
+                  </div>
+                </div>
+              )}
+
               {/* 出错 */}
               {updateStatus === 'error' && updateError && (
                 <div className="flex items-start justify-between gap-3 text-red-400 text-sm p-3 rounded-md bg-red-500/10 border border-red-500/20">
@@ -396,6 +437,9 @@ export function AboutPage() {
         </CardContent>
       </Card>
 
+      {/* 资源占用 */}
+      <ResourceUsageCard />
+
       {/* 开源软件声明 */}
       <Card>
         <CardHeader>
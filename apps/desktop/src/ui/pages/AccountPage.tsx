@@ -3,8 +3,9 @@
  * 添加 Token、选择 UID、管理账号
  */
 
-import { useState, useCallback, useRef } from 'react';
+import { useState, useCallback, useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import {
   UserPlus,
@@ -23,10 +24,13 @@ import {
   ArrowRight,
   Shield,
   X,
+  BarChart3,
+  Pencil,
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button, Input, ConfirmDialog, Modal } from '../components';
 import { useAuth, useAccounts } from '../../hooks/useEndfield';
-import { removeAccount, parseAccountKey, getAccountProviderPreference, setAccountProviderPreference } from '../../lib/storage';
+import { removeAccount, purgeAccount, renameAccount, parseAccountKey, getAccountRoleId, getAccountProviderPreference, setAccountProviderPreference } from '../../lib/storage';
+import { errorMessage } from '../../lib/errors';
 
 type AccountProvider = 'hypergryph' | 'gryphline';
 
@@ -56,6 +60,14 @@ const openExternal = async (url: string) => {
   }
 };
 
+/** 多账号汇总统计（与 Rust 端 GlobalStats 对应） */
+type GlobalStats = {
+  total_pulls_all_accounts: number;
+  total_five_stars_all_accounts: number;
+  uid_count: number;
+  most_active_uid: string | null;
+};
+
 export function AccountPage() {
   const { t } = useTranslation();
   const [token, setToken] = useState('');
@@ -64,9 +76,28 @@ export function AccountPage() {
   const { loading, error, authenticate, clearError } = useAuth();
   const { accounts, activeUid, selectAccount, refresh } = useAccounts();
   const [deleteUid, setDeleteUid] = useState<string | null>(null);
+  const [deleteRecords, setDeleteRecords] = useState(false);
+  const [deleteError, setDeleteError] = useState<string | null>(null);
+  const [renameUid, setRenameUid] = useState<string | null>(null);
+  const [renameValue, setRenameValue] = useState('');
+  const [renameError, setRenameError] = useState<string | null>(null);
   const providerName =
     provider === 'gryphline' ? t('account.providerNameGryphline') : t('account.providerNameHypergryph');
 
+  // 多账号合计
+  const [globalStats, setGlobalStats] = useState<GlobalStats | null>(null);
+  useEffect(() => {
+    if (accounts.length < 2) return;
+    void invoke<GlobalStats>('get_global_pull_stats')
+      .then(setGlobalStats)
+      .catch((e: unknown) => {
+        console.error('[AccountPage] Failed to load global stats:', e);
+      });
+  }, [accounts]);
+  const mostActiveAccount = globalStats?.most_active_uid
+    ? accounts.find((a) => a.uid === globalStats.most_active_uid)
+    : undefined;
+
   // Token 获取提示卡
   const [tokenHelpOpen, setTokenHelpOpen] = useState(false);
   const tokenHelpButtonRef = useRef<HTMLButtonElement>(null);
@@ -87,6 +118,32 @@ export function AccountPage() {
     })();
   }, [token, provider, authenticate, refresh]);
 
+  // 粘贴抽卡链接时按链接中的 UID 预先选中对应账号，避免同步到错误的账号
+  const [urlAccountHint, setUrlAccountHint] = useState<string | null>(null);
+
+  const handleTokenChange = useCallback((value: string) => {
+    setToken(value);
+    setUrlAccountHint(null);
+    const text = value.trim();
+    if (!/^https?:\/\//i.test(text)) return;
+    void invoke<string | null>('extract_uid_from_url', { url: text })
+      .then((uid) => {
+        if (!uid) return;
+        const account = accounts.find((a) => getAccountRoleId(a) === uid || a.uid === uid);
+        if (!account) {
+          setUrlAccountHint(t('account.urlUidUnknown', '链接中的 UID {{uid}} 不属于已添加的账号', { uid }));
+          return;
+        }
+        if (account.uid !== activeUid) {
+          selectAccount(account.uid);
+        }
+        setUrlAccountHint(t('account.urlUidSelected', '已根据链接选中 UID {{uid}} 对应的账号', { uid }));
+      })
+      .catch((e: unknown) => {
+        console.warn('[AccountPage] extract_uid_from_url failed:', e);
+      });
+  }, [accounts, activeUid, selectAccount, t]);
+
   const toggleProvider = useCallback(() => {
     const next: AccountProvider = provider === 'hypergryph' ? 'gryphline' : 'hypergryph';
     setProvider(next);
@@ -175,9 +232,17 @@ export function AccountPage() {
   }, [provider, providerName, toggleProvider]);
 
   const handleRemoveAccount = useCallback((uid: string) => {
+    setDeleteRecords(false);
+    setDeleteError(null);
     setDeleteUid(uid);
   }, []);
 
+  const handleRenameAccount = useCallback((uid: string, current: string) => {
+    setRenameValue(current);
+    setRenameError(null);
+    setRenameUid(uid);
+  }, []);
+
   const handleKeyDown = useCallback((e: React.KeyboardEvent) => {
     if (e.key === 'Enter' && token.trim() && !loading) {
       handleAddAccount();
@@ -198,12 +263,59 @@ export function AccountPage() {
         onConfirm={() => {
           if (!deleteUid) return;
           void (async () => {
-            await removeAccount(deleteUid);
-            setDeleteUid(null);
-            refresh();
+            try {
+              if (deleteRecords) {
+                await purgeAccount(deleteUid);
+              } else {
+                await removeAccount(deleteUid);
+              }
+              setDeleteUid(null);
+              refresh();
+            } catch (e) {
+              setDeleteError(errorMessage(e));
+            }
           })();
         }}
-      />
+      >
+        <label className="flex items-center gap-2 text-sm text-fg-1 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={deleteRecords}
+            onChange={(e) => setDeleteRecords(e.target.checked)}
+          />
+          {t('account.deleteRecordsToo', '同时删除该账号的全部抽卡记录与同步历史（不可撤销）')}
+        </label>
+        {deleteError && <div className="mt-2 text-sm text-red-400">{deleteError}</div>}
+      </ConfirmDialog>
+
+      <ConfirmDialog
+        open={renameUid !== null}
+        title={t('account.renameTitle', '修改昵称')}
+        description={t('account.renameDesc', '手动设置的昵称不会被导入文件覆盖')}
+        confirmText={t('common.confirm')}
+        cancelText={t('common.cancel')}
+        icon={<Pencil size={18} />}
+        onCancel={() => setRenameUid(null)}
+        onConfirm={() => {
+          if (!renameUid || !renameValue.trim()) return;
+          void (async () => {
+            try {
+              await renameAccount(renameUid, renameValue);
+              setRenameUid(null);
+              refresh();
+            } catch (e) {
+              setRenameError(errorMessage(e));
+            }
+          })();
+        }}
+      >
+        <Input
+          value={renameValue}
+          onChange={(e) => setRenameValue(e.target.value)}
+          maxLength={32}
+        />
+        {renameError && <div className="mt-2 text-sm text-red-400">{renameError}</div>}
+      </ConfirmDialog>
 
       {/* 成功提示 Toast - 修复标题栏遮挡问题（标题栏高度 38px） */}
       {showSuccess && (
@@ -215,6 +327,36 @@ export function AccountPage() {
         </div>
       )}
 
+      {/* 多账号合计 */}
+      {accounts.length > 1 && globalStats && (
+        <Card>
+          <CardContent>
+            <div className="flex items-center gap-3 mb-3">
+              <BarChart3 size={18} className="text-brand" />
+              <span className="font-semibold">{t('account.combinedSummary', '多账号合计')}</span>
+            </div>
+            <div className="grid grid-cols-3 gap-4">
+              <div className="p-3 rounded-md bg-bg-2">
+                <div className="text-2xl font-bold text-brand">{globalStats.total_pulls_all_accounts}</div>
+                <div className="text-sm text-fg-1">
+                  {t('account.combinedPulls', '{{count}} 个账号总抽数', { count: globalStats.uid_count })}
+                </div>
+              </div>
+              <div className="p-3 rounded-md bg-bg-2">
+                <div className="text-2xl font-bold text-fg-0">{globalStats.total_five_stars_all_accounts}</div>
+                <div className="text-sm text-fg-1">{t('account.combinedTopRarity', '六星总数')}</div>
+              </div>
+              <div className="p-3 rounded-md bg-bg-2 min-w-0">
+                <div className="text-lg font-bold text-fg-0 truncate">
+                  {mostActiveAccount?.roles[0]?.nickName || globalStats.most_active_uid || '-'}
+                </div>
+                <div className="text-sm text-fg-1">{t('account.mostActive', '抽数最多')}</div>
+              </div>
+            </div>
+          </CardContent>
+        </Card>
+      )}
+
       {/* 添加账号卡片 */}
       <Card>
         <CardHeader accent>
@@ -241,13 +383,20 @@ export function AccountPage() {
                 )}
                 placeholder={t('account.tokenPlaceholder', { providerName })}
                 value={token}
-                onChange={(e) => setToken(e.target.value)}
+                onChange={(e) => handleTokenChange(e.target.value)}
                 onKeyDown={handleKeyDown}
                 icon={<Key size={18} />}
                 error={error || undefined}
                 disabled={loading}
               />
               
+              {urlAccountHint && (
+                <div className="flex items-center gap-2 px-3 py-2.5 rounded-md bg-blue-500/10 border border-blue-500/20 text-blue-400 text-sm">
+                  <Info size={16} className="shrink-0" />
+                  <span className="flex-1">{urlAccountHint}</span>
+                </div>
+              )}
+
               {error && (
                 <div className="flex items-center gap-2 px-3 py-2.5 rounded-md bg-red-500/10 border border-red-500/20 text-red-400 text-sm">
                   <AlertCircle size={16} className="shrink-0" />
@@ -515,6 +664,18 @@ export function AccountPage() {
                     </div>
                   </div>
 
+                  {/* 修改昵称按钮 */}
+                  <button
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      handleRenameAccount(account.uid, account.roles[0]?.nickName ?? '');
+                    }}
+                    className="p-2.5 rounded-md text-fg-2/50 hover:text-fg-0 hover:bg-fg-2/10 transition-all opacity-0 group-hover:opacity-100"
+                    title={t('account.renameTitle', '修改昵称')}
+                  >
+                    <Pencil size={18} />
+                  </button>
+
                   {/* 删除按钮 */}
                   <button
                     onClick={(e) => {
@@ -26,10 +26,14 @@ import {
   Trash2,
   AlertTriangle,
   UserX,
+  Activity,
+  MinusCircle,
 } from 'lucide-react';
-import { Card, CardHeader, CardContent, Button, Badge, ConfirmDialog, SyncAuthModal } from '../components';
+import { Card, CardHeader, CardContent, Button, Badge, ConfirmDialog, SyncAuthModal, SyncEndpointsCard } from '../components';
 import { useSyncConfig, useSyncAuth, useSyncHealth, useAutoSync, useCloudSyncStatus } from '../../hooks/useSync';
 import { formatDistanceToNow } from '../../lib/dateUtils';
+import { errorMessage } from '../../lib/errors';
+import { diagnoseSyncEndpoint, type DiagnoseResult, type DiagnoseStageName } from '../../lib/syncApi';
 
 export function CloudSyncPage() {
   const { t } = useTranslation();
@@ -58,6 +62,9 @@ export function CloudSyncPage() {
   } | null>(null);
   const { isHealthy, checking, checkHealth } = useSyncHealth();
   const { status: cloudStatus, refresh: refreshCloudStatus } = useCloudSyncStatus();
+  const [diagnosing, setDiagnosing] = useState(false);
+  const [diagnoseResult, setDiagnoseResult] = useState<DiagnoseResult | null>(null);
+  const [diagnoseError, setDiagnoseError] = useState<string | null>(null);
   
   // 启用自动同步
   useAutoSync();
@@ -105,6 +112,33 @@ export function CloudSyncPage() {
     }
   }, [manualSync, refreshCloudStatus]);
 
+  // 处理网络诊断
+  const handleDiagnose = useCallback(async () => {
+    setDiagnosing(true);
+    setDiagnoseResult(null);
+    setDiagnoseError(null);
+    try {
+      setDiagnoseResult(await diagnoseSyncEndpoint());
+    } catch (e) {
+      setDiagnoseError(errorMessage(e));
+    } finally {
+      setDiagnosing(false);
+    }
+  }, []);
+
+  const stageLabel = (stage: DiagnoseStageName) => {
+    switch (stage) {
+      case 'dns':
+        return t('cloudSync.diagnoseStageDns', 'DNS 解析');
+      case 'tcp':
+        return t('cloudSync.diagnoseStageTcp', 'TCP 连接');
+      case 'tls':
+        return t('cloudSync.diagnoseStageTls', 'TLS 握手');
+      case 'http':
+        return t('cloudSync.diagnoseStageHttp', 'HTTP 请求');
+    }
+  };
+
   // 处理清理重复记录
   const handleCleanup = useCallback(async () => {
     setCleaning(true);
@@ -283,8 +317,67 @@ export function CloudSyncPage() {
             >
               {t('cloudSync.refresh', '刷新')}
             </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => { void handleDiagnose(); }}
+              disabled={diagnosing}
+              icon={diagnosing ? <Loader2 size={16} className="animate-spin" /> : <Activity size={16} />}
+            >
+              {diagnosing ? t('cloudSync.diagnosing', '诊断中...') : t('cloudSync.diagnose', '网络诊断')}
+            </Button>
           </div>
         </CardHeader>
+        {(diagnoseResult || diagnoseError) && (
+          <CardContent>
+            {diagnoseError ? (
+              <div className="flex items-start gap-2 p-3 rounded-md bg-red-500/10 text-sm text-red-400">
+                <AlertCircle size={16} className="shrink-0 mt-0.5" />
+                <span>{t('cloudSync.diagnoseFailed', '诊断失败')}: {diagnoseError}</span>
+              </div>
+            ) : diagnoseResult && (
+              <div className="space-y-2">
+                <div className="flex items-center justify-between gap-2 text-sm">
+                  <span className="text-fg-2 truncate">{diagnoseResult.url}</span>
+                  <span className={diagnoseResult.failedStage ? 'text-red-400 shrink-0' : 'text-green-400 shrink-0'}>
+                    {diagnoseResult.failedStage
+                      ? t('cloudSync.diagnoseFailedAt', '在「{{stage}}」步骤失败', { stage: stageLabel(diagnoseResult.failedStage) })
+                      : t('cloudSync.diagnoseAllOk', '各项检测均通过')}
+                  </span>
+                </div>
+                {diagnoseResult.stages.map((stage) => (
+                  <div
+                    key={stage.stage}
+                    className={`flex items-start gap-2 p-2 rounded-md text-sm ${
+                      stage.status === 'failed'
+                        ? 'bg-red-500/10 text-red-400'
+                        : stage.status === 'ok'
+                          ? 'bg-bg-2 text-fg-1'
+                          : 'bg-bg-2 text-fg-2'
+                    }`}
+                  >
+                    {stage.status === 'ok' ? (
+                      <CheckCircle2 size={14} className="shrink-0 mt-0.5 text-green-400" />
+                    ) : stage.status === 'failed' ? (
+                      <AlertCircle size={14} className="shrink-0 mt-0.5" />
+                    ) : (
+                      <MinusCircle size={14} className="shrink-0 mt-0.5" />
+                    )}
+                    <span className="w-24 shrink-0 font-medium">{stageLabel(stage.stage)}</span>
+                    <span className="flex-1 min-w-0 break-all text-xs mt-0.5">
+                      {stage.status === 'skipped'
+                        ? `${t('cloudSync.diagnoseSkipped', '已跳过')}${stage.detail ? `（${stage.detail}）` : ''}`
+                        : stage.detail}
+                    </span>
+                    {stage.elapsedMs !== null && (
+                      <span className="shrink-0 text-xs tabular-nums">{stage.elapsedMs} ms</span>
+                    )}
+                  </div>
+                ))}
+              </div>
+            )}
+          </CardContent>
+        )}
       </Card>
       
       {/* 账号状态 */}
@@ -637,6 +730,8 @@ export function CloudSyncPage() {
           )}
         </CardContent>
       </Card>
+
+      <SyncEndpointsCard />
     </div>
   );
 }
@@ -0,0 +1,210 @@
+/**
+ * 欧气对比页面
+ * 生成自己的分享码，粘贴朋友的分享码后并排对比各池出金位置分布
+ */
+
+import { useCallback, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { Copy, Scale, Share2, AlertCircle } from 'lucide-react';
+import { Card, CardHeader, CardContent, Button } from '../components';
+import { errorMessage } from '../../lib/errors';
+
+/** 单个卡池摘要（与 Rust 端 ComparePool 对应） */
+type ComparePool = {
+  gachaType: string;
+  totalPulls: number;
+  fiveStarPositions: number[];
+};
+
+/** 分享码内容（与 Rust 端 ComparePayload 对应） */
+type ComparePayload = {
+  version: number;
+  createdAt: number;
+  totalPulls: number;
+  luckPercentile: number | null;
+  pools: ComparePool[];
+};
+
+const POOL_ORDER = ['special', 'weapon', 'standard', 'beginner'] as const;
+
+function average(values: number[]): string {
+  if (values.length === 0) return '-';
+  return (values.reduce((sum, v) => sum + v, 0) / values.length).toFixed(1);
+}
+
+// 单侧摘要
+function PayloadColumn({ title, payload }: { title: string; payload: ComparePayload }) {
+  const { t } = useTranslation();
+
+  return (
+    <div className="flex-1 min-w-0 space-y-3">
+      <div className="text-sm font-medium text-fg-1">{title}</div>
+      <div className="grid grid-cols-2 gap-3">
+        <div className="p-3 rounded-md bg-bg-2">
+          <div className="text-2xl font-bold text-fg-0">{payload.totalPulls}</div>
+          <div className="text-sm text-fg-1">{t('stats.totalPulls')}</div>
+        </div>
+        <div className="p-3 rounded-md bg-bg-2">
+          <div className="text-2xl font-bold text-brand">
+            {payload.luckPercentile === null ? '-' : `${payload.luckPercentile}%`}
+          </div>
+          <div className="text-sm text-fg-1">{t('compare.luckPercentile', '欧气百分位')}</div>
+        </div>
+      </div>
+      {POOL_ORDER.map((gachaType) => {
+        const pool = payload.pools.find((p) => p.gachaType === gachaType);
+        if (!pool) return null;
+        return (
+          <div key={gachaType} className="p-3 rounded-md border border-border">
+            <div className="flex items-center justify-between text-sm mb-2">
+              <span className="font-medium text-fg-0">{t(`stats.ui.poolTabs.${gachaType}`)}</span>
+              <span className="text-fg-2">
+                {t('compare.poolSummary', '{{pulls}} 抽 · 平均 {{avg}} 抽出金', {
+                  pulls: pool.totalPulls,
+                  avg: average(pool.fiveStarPositions),
+                })}
+              </span>
+            </div>
+            <div className="flex flex-wrap gap-1">
+              {pool.fiveStarPositions.length === 0 ? (
+                <span className="text-xs text-fg-2">{t('stats.no6Star')}</span>
+              ) : (
+                pool.fiveStarPositions.map((position, index) => (
+                  <span
+                    key={index}
+                    className={`px-1.5 py-0.5 rounded text-xs font-mono ${
+                      position <= 40 ? 'bg-green-500/15 text-green-500' : 'bg-bg-2 text-fg-1'
+                    }`}
+                  >
+                    {position}
+                  </span>
+                ))
+              )}
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
+
+export function ComparePage() {
+  const { t } = useTranslation();
+  const [myCode, setMyCode] = useState('');
+  const [mine, setMine] = useState<ComparePayload | null>(null);
+  const [friendCode, setFriendCode] = useState('');
+  const [friend, setFriend] = useState<ComparePayload | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
+
+  const handleCreate = useCallback(async () => {
+    setError(null);
+    try {
+      const code = await invoke<string>('create_compare_payload');
+      setMyCode(code);
+      setMine(await invoke<ComparePayload>('parse_compare_payload', { text: code }));
+    } catch (e) {
+      setError(errorMessage(e));
+    }
+  }, []);
+
+  const handleCopy = useCallback(async () => {
+    if (!myCode) return;
+    await navigator.clipboard.writeText(myCode);
+    setCopied(true);
+    window.setTimeout(() => setCopied(false), 1500);
+  }, [myCode]);
+
+  const handleParse = useCallback(async () => {
+    setError(null);
+    setFriend(null);
+    try {
+      setFriend(await invoke<ComparePayload>('parse_compare_payload', { text: friendCode }));
+    } catch (e) {
+      setError(errorMessage(e));
+    }
+  }, [friendCode]);
+
+  return (
+    <div className="space-y-4">
+      <Card>
+        <CardHeader>
+          <div className="flex items-center gap-2">
+            <Share2 size={18} className="text-brand" />
+            <span className="font-bold">{t('compare.myCode', '我的分享码')}</span>
+          </div>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          <p className="text-sm text-fg-1">
+            {t('compare.myCodeHint', '分享码只包含各池出金位置与总抽数，不含 UID 等可识别信息。')}
+          </p>
+          <textarea
+            readOnly
+            value={myCode}
+            rows={3}
+            className="w-full px-3 py-2 rounded-md bg-bg-2 border border-border text-xs font-mono text-fg-0 resize-none"
+          />
+          <div className="flex gap-2">
+            <Button size="sm" onClick={() => void handleCreate()} icon={<Share2 size={14} />}>
+              {t('compare.generate', '生成分享码')}
+            </Button>
+            <Button
+              size="sm"
+              variant="secondary"
+              disabled={!myCode}
+              onClick={() => void handleCopy()}
+              icon={<Copy size={14} />}
+            >
+              {copied ? t('compare.copied', '已复制') : t('compare.copy', '复制')}
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <div className="flex items-center gap-2">
+            <Scale size={18} className="text-brand" />
+            <span className="font-bold">{t('compare.friendCode', '朋友的分享码')}</span>
+          </div>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          <textarea
+            value={friendCode}
+            onChange={(e) => setFriendCode(e.target.value)}
+            rows={3}
+            placeholder="EFGC..."
+            className="w-full px-3 py-2 rounded-md bg-bg-2 border border-border text-xs font-mono text-fg-0 resize-none"
+          />
+          <Button size="sm" disabled={!friendCode.trim()} onClick={() => void handleParse()}>
+            {t('compare.parse', '对比')}
+          </Button>
+          {error && (
+            <div className="flex items-start gap-2 text-sm text-red-400">
+              <AlertCircle size={16} className="shrink-0 mt-0.5" />
+              <span>{error}</span>
+            </div>
+          )}
+        </CardContent>
+      </Card>
+
+      {friend && (
+        <Card>
+          <CardContent>
+            <div className="flex gap-4">
+              {mine ? (
+                <PayloadColumn title={t('compare.me', '我')} payload={mine} />
+              ) : (
+                <div className="flex-1 text-sm text-fg-2">
+                  {t('compare.generateFirst', '生成自己的分享码后即可并排对比')}
+                </div>
+              )}
+              <PayloadColumn title={t('compare.friend', '朋友')} payload={friend} />
+            </div>
+          </CardContent>
+        </Card>
+      )}
+    </div>
+  );
+}
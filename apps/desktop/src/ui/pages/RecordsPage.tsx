@@ -3,9 +3,9 @@
  * 支持角色和武器记录的统一显示
  */
 
-import { useEffect, useMemo, useState } from 'react';
+import { useEffect, useMemo, useRef, useState, type Ref } from 'react';
 import { useTranslation } from 'react-i18next';
-import { useNavigate } from 'react-router-dom';
+import { useLocation, useNavigate } from 'react-router-dom';
 import {
   History,
   ChevronDown,
@@ -18,11 +18,13 @@ import {
   Search,
   AlertCircle,
   Loader2,
+  Sparkles,
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button, Badge, RarityBadge, Input, CharacterAvatar, WeaponAvatar } from '../components';
 import { useAccounts, useGachaRecordsData } from '../../hooks/useEndfield';
-import { charRecordToUnified, weaponRecordToUnified, type UnifiedGachaRecord } from '../../lib/storage';
+import { charRecordToUnified, weaponRecordToUnified, getRecordPoolType, type UnifiedGachaRecord } from '../../lib/storage';
 import { formatDate, getTimestamp } from '../../lib/dateUtils';
+import { isSameItem, resolveItem, type ItemMeta } from '../../lib/items';
 
 /** 卡池类型筛选 */
 type PoolFilter = 'all' | 'special' | 'weapon' | 'standard' | 'beginner';
@@ -96,26 +98,26 @@ function filterByPoolType(records: UnifiedGachaRecord[], poolFilter: PoolFilter)
     case 'all':
       return records;
     case 'special':
-      // 限定池：角色池，poolId 不是 standard 或 beginner
+      // 限定池：角色池，卡池类型不是 standard 或 beginner（手动纠正过的记录按纠正结果）
       return records.filter(r => 
         r.category === 'character' && 
-        r.poolId !== 'standard' && 
-        r.poolId !== 'beginner'
+        getRecordPoolType(r) !== 'standard' && 
+        getRecordPoolType(r) !== 'beginner'
       );
     case 'weapon':
       // 武器池：所有武器记录
       return records.filter(r => r.category === 'weapon');
     case 'standard':
-      // 常驻池：角色池，poolId === "standard"
+      // 常驻池：角色池，卡池类型为 standard
       return records.filter(r => 
         r.category === 'character' && 
-        r.poolId === 'standard'
+        getRecordPoolType(r) === 'standard'
       );
     case 'beginner':
-      // 新手池：角色池，poolId === "beginner"
+      // 新手池：角色池，卡池类型为 beginner
       return records.filter(r => 
         r.category === 'character' && 
-        r.poolId === 'beginner'
+        getRecordPoolType(r) === 'beginner'
       );
     default:
       return records;
@@ -125,6 +127,7 @@ function filterByPoolType(records: UnifiedGachaRecord[], poolFilter: PoolFilter)
 export function RecordsPage() {
   const { t } = useTranslation();
   const navigate = useNavigate();
+  const location = useLocation();
   const { activeUid, activeAccount, loading: accountsLoading } = useAccounts();
   const { gachaRecords, weaponRecords, loading: recordsLoading } = useGachaRecordsData(activeUid);
   
@@ -132,10 +135,27 @@ export function RecordsPage() {
   const [dateFilter, setDateFilter] = useState<DateFilter>('all');
   const [rarityFilter, setRarityFilter] = useState(0);
   const [searchQuery, setSearchQuery] = useState('');
+  // 搜索词解析出的物品（跨语言匹配，如用英文名搜索中文记录）
+  const [searchItem, setSearchItem] = useState<ItemMeta | null>(null);
   const [page, setPage] = useState(1);
   const [pageSize, setPageSize] = useState<(typeof PAGE_SIZE_OPTIONS)[number]>(DEFAULT_PAGE_SIZE);
   const [jumpInput, setJumpInput] = useState('');
 
+  // 同步完成后跳转过来时高亮本次新增的记录（sync-finished 事件）
+  const syncState = location.state as { highlightRecordIds?: string[]; topRarity?: number | null } | null;
+  const highlightIds = useMemo(() => new Set(syncState?.highlightRecordIds ?? []), [syncState]);
+  const firstHighlightRef = useRef<HTMLDivElement | null>(null);
+
+  useEffect(() => {
+    if (highlightIds.size === 0) return;
+    // 新记录按时间排在最前，回到第一页并清除筛选
+    setPoolFilter('all');
+    setDateFilter('all');
+    setRarityFilter(0);
+    setSearchQuery('');
+    setPage(1);
+  }, [highlightIds]);
+
   // 将记录转换为统一格式并排序
   const allRecords = useMemo(() => {
     const charUnified = gachaRecords.map(charRecordToUnified);
@@ -159,6 +179,16 @@ export function RecordsPage() {
     return all;
   }, [gachaRecords, weaponRecords]);
 
+  useEffect(() => {
+    let cancelled = false;
+    void resolveItem(searchQuery).then((meta) => {
+      if (!cancelled) setSearchItem(meta);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [searchQuery]);
+
   const filteredRecords = useMemo(() => {
     let records = allRecords;
 
@@ -184,12 +214,13 @@ export function RecordsPage() {
       const query = searchQuery.toLowerCase();
       records = records.filter((r) => 
         r.itemName.toLowerCase().includes(query) ||
-        r.poolName.toLowerCase().includes(query)
+        r.poolName.toLowerCase().includes(query) ||
+        (searchItem !== null && isSameItem(searchItem, r.charId ?? r.weaponId, r.itemName))
       );
     }
 
     return records;
-  }, [allRecords, poolFilter, dateFilter, rarityFilter, searchQuery]);
+  }, [allRecords, poolFilter, dateFilter, rarityFilter, searchQuery, searchItem]);
 
   const totalPages = Math.max(1, Math.ceil(filteredRecords.length / pageSize));
 
@@ -203,6 +234,12 @@ export function RecordsPage() {
     if (page < 1) setPage(1);
   }, [page, totalPages]);
 
+  const firstHighlightUid = paginatedRecords.find((r) => highlightIds.has(r.recordUid))?.recordUid;
+
+  useEffect(() => {
+    firstHighlightRef.current?.scrollIntoView({ behavior: 'smooth', block: 'center' });
+  }, [firstHighlightUid]);
+
   const commitJump = () => {
     const raw = jumpInput.trim();
     if (!raw) return;
@@ -344,6 +381,15 @@ export function RecordsPage() {
                 </p>
               </div>
             </div>
+            {highlightIds.size > 0 && (
+              <div className="flex items-center gap-2 text-sm text-brand">
+                <Sparkles size={16} />
+                {t('records.newFromSync', '本次同步新增 {{count}} 条（最高 {{rarity}}★）', {
+                  count: highlightIds.size,
+                  rarity: syncState?.topRarity ?? '-',
+                })}
+              </div>
+            )}
           </div>
         </CardHeader>
         <CardContent className="p-0">
@@ -369,7 +415,12 @@ export function RecordsPage() {
               {/* 记录列表 */}
               <div className="divide-y divide-border">
                 {paginatedRecords.map((record) => (
-                  <RecordRow key={record.recordUid} record={record} />
+                  <RecordRow
+                    key={record.recordUid}
+                    record={record}
+                    highlighted={highlightIds.has(record.recordUid)}
+                    rowRef={record.recordUid === firstHighlightUid ? firstHighlightRef : undefined}
+                  />
                 ))}
               </div>
 
@@ -502,7 +553,16 @@ export function RecordsPage() {
   );
 }
 
-function RecordRow({ record }: { record: UnifiedGachaRecord }) {
+function RecordRow({
+  record,
+  highlighted = false,
+  rowRef,
+}: {
+  record: UnifiedGachaRecord;
+  /** 本次同步新增的记录 */
+  highlighted?: boolean;
+  rowRef?: Ref<HTMLDivElement>;
+}) {
   const { t } = useTranslation();
   const rarityColors: Record<number, string> = {
     6: 'text-orange-500',
@@ -514,7 +574,12 @@ function RecordRow({ record }: { record: UnifiedGachaRecord }) {
   const isWeapon = record.category === 'weapon';
 
   return (
-    <div className="grid grid-cols-12 gap-2 px-5 py-3 hover:bg-bg-2/50 transition-colors items-center">
+    <div
+      ref={rowRef}
+      className={`grid grid-cols-12 gap-2 px-5 py-3 transition-colors items-center ${
+        highlighted ? 'bg-brand/10 hover:bg-brand/15' : 'hover:bg-bg-2/50'
+      }`}
+    >
       <div className="col-span-4 flex items-center gap-2 min-w-0">
         <div className="shrink-0">
           {isWeapon ? (
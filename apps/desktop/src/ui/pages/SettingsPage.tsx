@@ -3,7 +3,10 @@
  */
 
 import { useState, useCallback, useRef, useEffect } from 'react';
+import { useLocation, useNavigate } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { emit, listen } from '@tauri-apps/api/event';
 import { save } from '@tauri-apps/plugin-dialog';
 import { writeTextFile } from '@tauri-apps/plugin-fs';
 import { revealItemInDir } from '@tauri-apps/plugin-opener';
@@ -22,22 +25,39 @@ import {
   Check,
   Monitor,
   RotateCcw,
+  Presentation,
+  Archive,
+  FileOutput,
+  X,
 } from 'lucide-react';
-import { Card, CardHeader, CardContent, Button, ConfirmDialog, Popover } from '../components';
+import { Card, CardHeader, CardContent, Button, ConfirmDialog, Popover, WebdavBackupCard, InstallMigrationCard, ExportHistoryCard, recordExport, exportWithDialog } from '../components';
+import type { ExportProgress } from '../components';
 import { useAccounts, useGachaRecordsData } from '../../hooks/useEndfield';
 import { markForceFullDownload } from '../../hooks/useSync';
 import {
   exportData,
   importData,
+  parseImportJSON,
+  IMPORT_UID_MISMATCH,
   exportAllRecordsToCSV,
   importRecordsFromCSV,
   clearGachaRecords,
   clearWeaponRecords,
   getCloseBehavior,
   clearCloseBehavior,
-  type ExportData,
   type CloseBehavior,
+  type ImportMode,
+  type ImportPreview,
 } from '../../lib/storage';
+import { errorMessage, hasErrorCode } from '../../lib/errors';
+import { getDemoMode, setDemoMode } from '../../lib/db';
+
+/** 可选的界面缩放比例 */
+const ZOOM_OPTIONS = [0.8, 0.9, 1, 1.1, 1.25, 1.5, 1.75, 2] as const;
+/** 统计时区可选的 UTC 偏移（小时，与 Rust 端 -12~14 一致） */
+const UTC_OFFSET_OPTIONS = Array.from({ length: 27 }, (_, i) => i - 12);
+/** 原始响应留档可选的保留天数 */
+const RAW_RESPONSE_KEEP_DAYS = [3, 7, 14, 30] as const;
 
 /** 支持的语言列表 */
 const LANGUAGES = [
@@ -47,11 +67,32 @@ const LANGUAGES = [
 ] as const;
 
 type MessageState = {
-  type: 'success' | 'error';
+  type: 'success' | 'error' | 'info';
   text: string;
   filePath?: string; // 导出文件路径
 };
 
+/** 换池提醒设置（与 Rust 端 BannerWatchConfig 对应） */
+type BannerWatchSettings = {
+  enabled: boolean;
+  beforeMinutes: number;
+  afterMinutes: number;
+  autoSync: boolean;
+};
+
+type BannerWatchStatus = {
+  settings: BannerWatchSettings;
+  nextTransitionAt: number | null;
+  stale: boolean;
+};
+
+/** 原始响应留档设置（与 Rust 端 RawResponseSettings 对应） */
+type RawResponseSettings = {
+  enabled: boolean;
+  keepDays: number;
+  dir: string | null;
+};
+
 export function SettingsPage() {
   const { t, i18n } = useTranslation();
   const { activeUid, activeAccount, accounts } = useAccounts();
@@ -85,10 +126,138 @@ export function SettingsPage() {
 
   // 清除记录确认弹窗
   const [clearDialogOpen, setClearDialogOpen] = useState(false);
+  // 待确认的导入（dry-run 预览结果，确认后才真正写入）
+  const [pendingImport, setPendingImport] = useState<{ text: string; preview: ImportPreview; mode: ImportMode } | null>(null);
+  // 导出完成后刷新导出历史
+  const [exportVersion, setExportVersion] = useState(0);
   
   // 窗口关闭行为
   const [closeBehavior, setCloseBehaviorState] = useState<CloseBehavior | null>(() => getCloseBehavior());
   
+  // 界面缩放（Rust 端持久化，启动时恢复）
+  const [zoom, setZoom] = useState(1);
+  const [trayFollowZoom, setTrayFollowZoom] = useState(false);
+
+  useEffect(() => {
+    void invoke<{ factor: number; trayMenuFollow: boolean }>('get_zoom')
+      .then((settings) => {
+        setZoom(settings.factor);
+        setTrayFollowZoom(settings.trayMenuFollow);
+      })
+      .catch((e: unknown) => {
+        console.error('Failed to load zoom settings:', e);
+      });
+  }, []);
+
+  const handleZoomChange = useCallback((factor: number) => {
+    void invoke<number>('set_zoom', { factor })
+      .then(setZoom)
+      .catch((e: unknown) => {
+        setMessage({ type: 'error', text: errorMessage(e) });
+      });
+  }, []);
+
+  const handleTrayFollowZoomChange = useCallback((follow: boolean) => {
+    void invoke('set_tray_menu_follow_zoom', { follow })
+      .then(() => setTrayFollowZoom(follow))
+      .catch((e: unknown) => {
+        setMessage({ type: 'error', text: errorMessage(e) });
+      });
+  }, []);
+
+  // 统计时区（UTC 偏移小时数，null 为跟随系统）
+  const [displayOffset, setDisplayOffset] = useState<number | null>(null);
+
+  useEffect(() => {
+    void invoke<number | null>('get_display_timezone')
+      .then(setDisplayOffset)
+      .catch((e: unknown) => {
+        console.error('Failed to load display timezone:', e);
+      });
+  }, []);
+
+  const handleDisplayOffsetChange = useCallback((offset: number | null) => {
+    void invoke('set_display_timezone', { offset })
+      .then(() => setDisplayOffset(offset))
+      .catch((e: unknown) => {
+        setMessage({ type: 'error', text: errorMessage(e) });
+      });
+  }, []);
+
+  // 换池提醒（Rust 端按卡池排期在切换前后提醒）
+  const [bannerWatch, setBannerWatch] = useState<BannerWatchStatus | null>(null);
+
+  useEffect(() => {
+    void invoke<BannerWatchStatus>('get_banner_watch_status')
+      .then(setBannerWatch)
+      .catch((e: unknown) => {
+        console.error('Failed to load banner watch settings:', e);
+      });
+  }, []);
+
+  const handleBannerWatchChange = useCallback((patch: Partial<BannerWatchSettings>) => {
+    if (!bannerWatch) return;
+    const settings = { ...bannerWatch.settings, ...patch };
+    void invoke('set_banner_watch_settings', { settings })
+      .then(() => setBannerWatch({ ...bannerWatch, settings }))
+      .catch((e: unknown) => {
+        setMessage({ type: 'error', text: errorMessage(e) });
+      });
+  }, [bannerWatch]);
+
+  // 接口原始响应留档（默认关闭，过期留档由 Rust 端清理）
+  const [rawResponses, setRawResponses] = useState<RawResponseSettings | null>(null);
+
+  useEffect(() => {
+    void invoke<RawResponseSettings>('get_raw_response_settings')
+      .then(setRawResponses)
+      .catch((e: unknown) => {
+        console.error('Failed to load raw response settings:', e);
+      });
+  }, []);
+
+  const handleRawResponsesChange = useCallback((enabled: boolean, keepDays: number) => {
+    void invoke<RawResponseSettings>('set_raw_response_settings', { enabled, keepDays })
+      .then(setRawResponses)
+      .catch((e: unknown) => {
+        setMessage({ type: 'error', text: errorMessage(e) });
+      });
+  }, []);
+
+  // 演示模式（切换后重新加载页面以连接到对应的数据库）
+  const [demoMode, setDemoModeState] = useState(false);
+
+  useEffect(() => {
+    void getDemoMode()
+      .then(setDemoModeState)
+      .catch((e: unknown) => {
+        console.error('Failed to load demo mode:', e);
+      });
+  }, []);
+
+  const handleToggleDemoMode = useCallback(async () => {
+    try {
+      await setDemoMode(!demoMode);
+      window.location.reload();
+    } catch (e: unknown) {
+      setMessage({ type: 'error', text: errorMessage(e) });
+    }
+  }, [demoMode]);
+
+  // 归档旧记录（超出官方接口保留期的记录标记为不可再修复）
+  const [archiveDate, setArchiveDate] = useState('');
+  const [archiveDialogOpen, setArchiveDialogOpen] = useState(false);
+
+  const handleArchiveRecords = useCallback(async () => {
+    setArchiveDialogOpen(false);
+    try {
+      const count = await invoke<number>('archive_records', { beforeDate: archiveDate, uid: null });
+      setMessage({ type: 'success', text: t('settings.archiveSuccess', '已归档 {{count}} 条记录', { count }) });
+    } catch (e: unknown) {
+      setMessage({ type: 'error', text: errorMessage(e) });
+    }
+  }, [archiveDate, t]);
+
   // 重置关闭行为
   const handleResetCloseBehavior = useCallback(() => {
     clearCloseBehavior();
@@ -99,6 +268,8 @@ export function SettingsPage() {
   // 切换语言
   const handleLanguageChange = useCallback((langCode: string) => {
     void i18n.changeLanguage(langCode);
+    // 托盘菜单是独立窗口，通知其同步切换（菜单宽度随文字长度调整）
+    void emit('language-changed', langCode).catch(console.warn);
     setLangMenuOpen(false);
   }, [i18n]);
 
@@ -121,6 +292,7 @@ export function SettingsPage() {
         
         if (filePath) {
           await writeTextFile(filePath, JSON.stringify(data, null, 2));
+          void recordExport('json', filePath).then(() => setExportVersion((v) => v + 1));
           setMessage({ 
             type: 'success', 
             text: t('settings.exportSuccess'),
@@ -154,6 +326,7 @@ export function SettingsPage() {
           // 添加 BOM 以支持 Excel 正确识别 UTF-8
           const bom = '\uFEFF';
           await writeTextFile(filePath, bom + csvContent);
+          void recordExport('csv', filePath).then(() => setExportVersion((v) => v + 1));
           setMessage({ 
             type: 'success', 
             text: t('settings.csvExportSuccess'),
@@ -169,6 +342,53 @@ export function SettingsPage() {
     })();
   }, [t]);
 
+  // 后端导出的进度（开始写出后才有任务 id，可取消）
+  const [exportProgress, setExportProgress] = useState<ExportProgress | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    void listen<ExportProgress>('export-progress', (event) => {
+      const progress = event.payload;
+      const running = !['finished', 'cancelled', 'failed'].includes(progress.stage);
+      setExportProgress(running ? progress : null);
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  const handleCancelExport = useCallback(() => {
+    if (!exportProgress) return;
+    void invoke<boolean>('cancel_task', { taskId: exportProgress.taskId }).catch((e: unknown) => {
+      console.error('取消导出失败:', e);
+    });
+  }, [exportProgress]);
+
+  // UIGF / 兼容格式 / PDF 导出（保存对话框由后端弹出）
+  const handleExportWithDialog = useCallback((kind: 'uigf' | 'ww_compat' | 'pdf') => {
+    void (async () => {
+      setExporting(true);
+      try {
+        const result = await exportWithDialog(kind, kind === 'uigf' ? null : activeUid ?? null);
+        if (result.status === 'exported') {
+          setExportVersion((v) => v + 1);
+          setMessage({
+            type: 'success',
+            text: t('settings.exportSuccess'),
+            filePath: result.path,
+          });
+        } else if (result.taskId) {
+          setMessage({ type: 'info', text: t('settings.exportCancelled', '导出已取消，未写入文件') });
+        }
+      } catch (err) {
+        console.error('导出失败:', err);
+        setMessage({ type: 'error', text: `${t('settings.exportError')}: ${errorMessage(err)}` });
+      } finally {
+        setExporting(false);
+      }
+    })();
+  }, [activeUid, t]);
+
   // 打开文件所在目录
   const handleOpenFolder = useCallback(async (filePath: string) => {
     try {
@@ -178,6 +398,50 @@ export function SettingsPage() {
     }
   }, []);
 
+  // 导入 JSON 文本：先 dry-run 预览，用户确认后再真正导入（文件选择与文件关联打开共用）
+  const importJSONText = useCallback(async (text: string, mode: ImportMode = 'merge') => {
+    try {
+      const data = await parseImportJSON(text);
+      const preview = await importData(data, { dryRun: true, mode });
+      setPendingImport({ text, preview, mode });
+    } catch (err) {
+      console.error('导入预览失败:', err);
+      setMessage({ type: 'error', text: t('settings.importError') });
+    }
+  }, [t]);
+
+  // 确认预览后执行导入（uid 不一致已在预览中提示，确认即视为强制导入）
+  // 覆盖模式在预览中勾选，确认即视为显式同意清空这些账号的现有记录
+  const confirmImport = useCallback(async (text: string, force: boolean, mode: ImportMode) => {
+    try {
+      const data = await parseImportJSON(text);
+      const result = await importData(data, { force, mode, confirmReplace: mode === 'replace' });
+      const { updated, keptManual } = result.profiles;
+      const profileNotes = [
+        updated.length > 0 && t('settings.importNickNamesUpdated', { count: updated.length }),
+        keptManual.length > 0 && t('settings.importNickNamesKept', { count: keptManual.length }),
+      ].filter(Boolean);
+      
+      setMessage({
+        type: 'success',
+        text: [
+          t('settings.importSuccess', { 
+            accounts: result.accounts, 
+            records: result.charRecords + result.weaponRecords,
+          }),
+          ...profileNotes,
+        ].join('；'),
+      });
+    } catch (err) {
+      if (hasErrorCode(err, IMPORT_UID_MISMATCH)) {
+        // 预览之后数据库发生了变化，重新预览
+        void importJSONText(text, mode);
+        return;
+      }
+      setMessage({ type: 'error', text: t('settings.importError') });
+    }
+  }, [t, importJSONText]);
+
   // JSON 导入
   const handleImportJSON = useCallback(() => {
     const input = document.createElement('input');
@@ -187,26 +451,22 @@ export function SettingsPage() {
     input.onchange = async (e) => {
       const file = (e.target as HTMLInputElement).files?.[0];
       if (!file) return;
-
-      try {
-        const text = await file.text();
-        const data = JSON.parse(text) as ExportData;
-        const result = await importData(data);
-        
-        setMessage({
-          type: 'success',
-          text: t('settings.importSuccess', { 
-            accounts: result.accounts, 
-            records: result.charRecords + result.weaponRecords,
-          }),
-        });
-      } catch {
-        setMessage({ type: 'error', text: t('settings.importError') });
-      }
+      await importJSONText(await file.text());
     };
 
     input.click();
-  }, [t]);
+  }, [importJSONText]);
+
+  // 通过文件关联/启动参数打开的文件（MainLayout 跳转时带上文件内容）
+  const location = useLocation();
+  const navigate = useNavigate();
+  useEffect(() => {
+    const openFile = (location.state as { openFile?: { path: string; content: string } } | null)?.openFile;
+    if (!openFile) return;
+    // 清除路由 state，避免刷新或返回时重复导入
+    void navigate(location.pathname, { replace: true, state: null });
+    void importJSONText(openFile.content);
+  }, [location.state, location.pathname, navigate, importJSONText]);
 
   // CSV 导入
   const handleImportCSV = useCallback(() => {
@@ -228,13 +488,18 @@ export function SettingsPage() {
             text: result.errors.join('；'),
           });
         } else {
-          setMessage({
-            type: 'success',
-            text: t('settings.csvImportSuccess', {
-              charRecords: result.charRecords,
-              weaponRecords: result.weaponRecords,
-            }),
+          const imported = t('settings.csvImportSuccess', {
+            charRecords: result.charRecords,
+            weaponRecords: result.weaponRecords,
           });
+          setMessage(
+            result.unresolvedItems.length > 0
+              ? {
+                  type: 'info',
+                  text: `${imported} ${t('settings.importUnresolvedItems', { names: result.unresolvedItems.join('、') })}`,
+                }
+              : { type: 'success', text: imported }
+          );
         }
       } catch {
         setMessage({ type: 'error', text: t('settings.importError') });
@@ -277,22 +542,129 @@ export function SettingsPage() {
         }}
       />
 
+      <ConfirmDialog
+        open={archiveDialogOpen}
+        title={t('settings.archiveTitle', '归档旧记录')}
+        description={t('settings.archiveConfirm', '{{date}} 之前的记录将被标记为已归档，之后去重、批量删除、卡池纠正等修复操作会跳过它们。归档不可撤销。', { date: archiveDate })}
+        confirmText={t('common.confirm')}
+        cancelText={t('common.cancel')}
+        icon={<Archive size={18} />}
+        onCancel={() => setArchiveDialogOpen(false)}
+        onConfirm={() => {
+          void handleArchiveRecords();
+        }}
+      />
+
+      <ConfirmDialog
+        open={pendingImport !== null}
+        title={
+          pendingImport?.preview.uidCheck.status === 'mismatch'
+            ? t('settings.importUidMismatchTitle')
+            : t('settings.importPreviewTitle', '导入预览')
+        }
+        description={
+          pendingImport?.preview.uidCheck.status === 'mismatch'
+            ? t('settings.importUidMismatch')
+            : pendingImport?.mode === 'replace'
+              ? t('settings.importReplaceDesc', '确认后将先备份数据库，再清空以下账号的现有记录，导入后只保留文件中的数据。')
+              : t('settings.importPreviewDesc', '确认后将写入以下数据，已存在的记录会被跳过。')
+        }
+        confirmText={
+          pendingImport?.preview.uidCheck.status === 'mismatch'
+            ? t('settings.importForce')
+            : t('settings.importConfirm', '确认导入')
+        }
+        cancelText={t('common.cancel')}
+        danger={pendingImport?.preview.uidCheck.status === 'mismatch' || pendingImport?.mode === 'replace'}
+        onCancel={() => setPendingImport(null)}
+        onConfirm={() => {
+          const pending = pendingImport;
+          setPendingImport(null);
+          if (pending !== null) {
+            void confirmImport(pending.text, pending.preview.uidCheck.status === 'mismatch', pending.mode);
+          }
+        }}
+      >
+        {pendingImport && (
+          <div className="mt-3 space-y-3 text-sm text-fg-1">
+            <label className="flex items-center gap-2 text-xs cursor-pointer">
+              <input
+                type="checkbox"
+                checked={pendingImport.mode === 'replace'}
+                onChange={(e) => {
+                  void importJSONText(pendingImport.text, e.target.checked ? 'replace' : 'merge');
+                }}
+              />
+              <span>{t('settings.importReplaceMode', '覆盖模式：清空文件中账号的现有记录（自动备份）')}</span>
+            </label>
+            <div className="grid grid-cols-3 gap-2">
+              {([
+                ['accounts', t('settings.importPreviewAccounts', '账号')],
+                ['charRecords', t('settings.importPreviewCharRecords', '干员记录')],
+                ['weaponRecords', t('settings.importPreviewWeaponRecords', '武器记录')],
+              ] as const).map(([key, label]) => (
+                <div key={key} className="rounded-md border border-border bg-bg-2/40 px-3 py-2">
+                  <div className="text-xs text-fg-2">{label}</div>
+                  <div className="mt-0.5">
+                    <span className="text-green-400">+{pendingImport.preview[key].added}</span>
+                    <span className="ml-2 text-fg-2">
+                      {t('settings.importPreviewSkipped', '跳过 {{count}}', { count: pendingImport.preview[key].skipped })}
+                    </span>
+                  </div>
+                </div>
+              ))}
+            </div>
+            {([
+              ['addedSamples', t('settings.importPreviewAddedSamples', '新增样本')],
+              ['skippedSamples', t('settings.importPreviewSkippedSamples', '跳过样本（已存在）')],
+            ] as const).map(([key, label]) =>
+              pendingImport.preview[key].length > 0 ? (
+                <div key={key}>
+                  <div className="text-xs text-fg-2 mb-1">{label}</div>
+                  <ul className="space-y-0.5">
+                    {pendingImport.preview[key].map((sample, index) => (
+                      <li key={index} className="flex justify-between gap-2 text-xs">
+                        <span className="truncate">{sample.rarity}★ {sample.name}</span>
+                        <span className="text-fg-2 shrink-0">{sample.uid}</span>
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              ) : null
+            )}
+            {pendingImport.preview.unresolvedItems.length > 0 && (
+              <div className="text-xs text-yellow-400">
+                {t('settings.importUnresolvedItems', { names: pendingImport.preview.unresolvedItems.join('、') })}
+              </div>
+            )}
+          </div>
+        )}
+      </ConfirmDialog>
+
       {/* 消息提示 - 修复遮挡问题 */}
       {message && (
         <div
           className={`flex items-start gap-3 p-4 rounded-md ${
             message.type === 'success'
               ? 'bg-green-500/10 border border-green-500/30'
-              : 'bg-red-500/10 border border-red-500/30'
+              : message.type === 'info'
+                ? 'bg-bg-2 border border-border'
+                : 'bg-red-500/10 border border-red-500/30'
           }`}
         >
           {message.type === 'success' ? (
             <CheckCircle2 size={20} className="text-green-400 shrink-0 mt-0.5" />
+          ) : message.type === 'info' ? (
+            <Info size={20} className="text-fg-1 shrink-0 mt-0.5" />
           ) : (
             <AlertCircle size={20} className="text-red-400 shrink-0 mt-0.5" />
           )}
           <div className="flex-1 min-w-0">
-            <span className={message.type === 'success' ? 'text-green-400' : 'text-red-400'}>
+            <span
+              className={
+                message.type === 'success' ? 'text-green-400' : message.type === 'info' ? 'text-fg-1' : 'text-red-400'
+              }
+            >
               {message.text}
             </span>
             {/* 显示导出文件路径和打开文件夹按钮 */}
@@ -407,6 +779,86 @@ export function SettingsPage() {
               </div>
             </div>
 
+            {/* 其他格式导出 */}
+            <div className="space-y-2">
+              <div className="text-sm font-medium text-fg-0 flex items-center gap-2">
+                <FileOutput size={16} className="text-fg-1" />
+                {t('settings.otherFormats', '其他格式')}
+              </div>
+              <div className="flex gap-3">
+                <Button
+                  variant="secondary"
+                  onClick={() => handleExportWithDialog('uigf')}
+                  loading={exporting}
+                  icon={<Download size={18} />}
+                  className="flex-1"
+                >
+                  {t('settings.exportUigf', '导出 UIGF')}
+                </Button>
+                <Button
+                  variant="secondary"
+                  onClick={() => handleExportWithDialog('ww_compat')}
+                  loading={exporting}
+                  disabled={!activeUid}
+                  icon={<Download size={18} />}
+                  className="flex-1"
+                >
+                  {t('settings.exportWwCompat', '导出兼容格式')}
+                </Button>
+                <Button
+                  variant="secondary"
+                  onClick={() => handleExportWithDialog('pdf')}
+                  loading={exporting}
+                  disabled={!activeUid}
+                  icon={<Download size={18} />}
+                  className="flex-1"
+                >
+                  {t('settings.exportPdf', '导出 PDF 报告')}
+                </Button>
+              </div>
+              {exporting && exportProgress && (
+                <div className="flex items-center justify-between gap-3 text-sm text-fg-1">
+                  <span>
+                    {t('settings.exportProgress', '正在导出… {{done}}/{{total}}', {
+                      done: exportProgress.done,
+                      total: exportProgress.total,
+                    })}
+                  </span>
+                  <Button variant="ghost" size="sm" onClick={handleCancelExport} icon={<X size={14} />}>
+                    {t('settings.cancelExport', '取消导出')}
+                  </Button>
+                </div>
+              )}
+            </div>
+
+            {/* 归档旧记录 */}
+            <div className="space-y-2">
+              <div className="text-sm font-medium text-fg-0 flex items-center gap-2">
+                <Archive size={16} className="text-fg-1" />
+                {t('settings.archiveTitle', '归档旧记录')}
+              </div>
+              <div className="text-xs text-fg-2">
+                {t('settings.archiveDesc', '超出官方接口保留期的记录无法再与服务器核对，归档后修复类操作默认跳过，缺口检查也只作提示。')}
+              </div>
+              <div className="flex gap-3">
+                <input
+                  type="date"
+                  value={archiveDate}
+                  onChange={(e) => setArchiveDate(e.target.value)}
+                  aria-label={t('settings.archiveBefore', '归档此日期之前的记录')}
+                  className="flex-1 rounded-md border border-border bg-bg-2 px-3 py-2 text-sm text-fg-0"
+                />
+                <Button
+                  variant="secondary"
+                  onClick={() => setArchiveDialogOpen(true)}
+                  disabled={!archiveDate}
+                  icon={<Archive size={18} />}
+                >
+                  {t('settings.archiveAction', '归档')}
+                </Button>
+              </div>
+            </div>
+
             {/* 说明 */}
             <div className="flex items-start gap-2 p-3 rounded-md bg-blue-500/10 text-sm text-blue-400">
               <Info size={16} className="shrink-0 mt-0.5" />
@@ -419,6 +871,15 @@ export function SettingsPage() {
         </CardContent>
       </Card>
 
+      {/* 导出历史 */}
+      <ExportHistoryCard refreshKey={exportVersion} />
+
+      {/* WebDAV 异地备份 */}
+      <WebdavBackupCard />
+
+      {/* 安装版 ↔ 便携版迁移 */}
+      <InstallMigrationCard />
+
       {/* 危险操作 */}
       <Card>
         <CardHeader>
@@ -568,6 +1029,134 @@ export function SettingsPage() {
               )}
             </div>
             
+            {/* 界面缩放 */}
+            <div className="flex items-center justify-between p-4 rounded-md bg-bg-2">
+              <div>
+                <div className="font-medium">{t('windowBehavior.zoom', '界面缩放')}</div>
+                <label className="text-sm text-fg-2 mt-0.5 flex items-center gap-2 cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={trayFollowZoom}
+                    onChange={(e) => handleTrayFollowZoomChange(e.target.checked)}
+                  />
+                  {t('windowBehavior.trayFollowZoom', '托盘菜单跟随缩放')}
+                </label>
+              </div>
+              <select
+                value={zoom}
+                onChange={(e) => handleZoomChange(Number(e.target.value))}
+                className="rounded-md border border-border bg-bg-1 px-3 py-1.5 text-sm"
+              >
+                {ZOOM_OPTIONS.map((option) => (
+                  <option key={option} value={option}>
+                    {Math.round(option * 100)}%
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            {/* 统计时区 */}
+            <div className="flex items-center justify-between p-4 rounded-md bg-bg-2">
+              <div>
+                <div className="font-medium">{t('displayTimezone.title', '统计时区')}</div>
+                <div className="text-sm text-fg-2 mt-0.5">
+                  {t('displayTimezone.desc', '按日、按月统计时使用的时区，记录本身仍以 UTC 保存')}
+                </div>
+              </div>
+              <select
+                value={displayOffset ?? ''}
+                onChange={(e) => handleDisplayOffsetChange(e.target.value === '' ? null : Number(e.target.value))}
+                className="rounded-md border border-border bg-bg-1 px-3 py-1.5 text-sm"
+              >
+                <option value="">{t('displayTimezone.system', '跟随系统')}</option>
+                {UTC_OFFSET_OPTIONS.map((offset) => (
+                  <option key={offset} value={offset}>
+                    UTC{offset >= 0 ? '+' : '−'}{Math.abs(offset)}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            {/* 换池提醒 */}
+            {bannerWatch && (
+              <div className="flex items-center justify-between p-4 rounded-md bg-bg-2">
+                <div>
+                  <div className="font-medium">{t('bannerWatch.title', '换池提醒')}</div>
+                  <div className="text-sm text-fg-2 mt-0.5">
+                    {bannerWatch.stale
+                      ? t('bannerWatch.stale', '卡池排期已过期，获取到新排期后恢复提醒')
+                      : bannerWatch.nextTransitionAt
+                        ? t('bannerWatch.next', '下次换池：{{time}}', { time: new Date(bannerWatch.nextTransitionAt).toLocaleString() })
+                        : t('bannerWatch.noUpcoming', '暂无已知的换池时间')}
+                  </div>
+                  <label className="text-sm text-fg-2 mt-1 flex items-center gap-2 cursor-pointer">
+                    <input
+                      type="checkbox"
+                      checked={bannerWatch.settings.autoSync}
+                      disabled={!bannerWatch.settings.enabled}
+                      onChange={(e) => handleBannerWatchChange({ autoSync: e.target.checked })}
+                    />
+                    {t('bannerWatch.autoSync', '提醒时自动同步')}
+                  </label>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={bannerWatch.settings.enabled}
+                  onChange={(e) => handleBannerWatchChange({ enabled: e.target.checked })}
+                  aria-label={t('bannerWatch.title', '换池提醒')}
+                />
+              </div>
+            )}
+
+            {/* 接口原始响应留档 */}
+            {rawResponses && (
+              <div className="flex items-center justify-between p-4 rounded-md bg-bg-2">
+                <div>
+                  <div className="font-medium">{t('rawResponses.title', '保留接口原始响应')}</div>
+                  <div className="text-sm text-fg-2 mt-0.5">
+                    {t('rawResponses.desc', '同步时把抽卡接口返回的原始数据保存到 userdata/raw/，用于排查记录解析问题')}
+                  </div>
+                  <select
+                    className="mt-1 rounded-md border border-border bg-bg-1 px-2 py-1 text-sm"
+                    value={rawResponses.keepDays}
+                    disabled={!rawResponses.enabled}
+                    onChange={(e) => handleRawResponsesChange(rawResponses.enabled, Number(e.target.value))}
+                  >
+                    {RAW_RESPONSE_KEEP_DAYS.map((days) => (
+                      <option key={days} value={days}>
+                        {t('rawResponses.keepDays', '保留 {{days}} 天', { days })}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={rawResponses.enabled}
+                  onChange={(e) => handleRawResponsesChange(e.target.checked, rawResponses.keepDays)}
+                  aria-label={t('rawResponses.title', '保留接口原始响应')}
+                />
+              </div>
+            )}
+
+            {/* 演示模式 */}
+            <div className="flex items-center justify-between p-4 rounded-md bg-bg-2">
+              <div>
+                <div className="font-medium">{t('demoMode.title', '演示模式')}</div>
+                <div className="text-sm text-fg-2 mt-0.5">
+                  {t('demoMode.desc', '录屏或演示时使用：修改只写入临时数据库，退出后自动丢弃，真实数据不受影响')}
+                </div>
+              </div>
+              <Button
+                variant={demoMode ? 'primary' : 'ghost'}
+                size="sm"
+                onClick={() => { void handleToggleDemoMode(); }}
+                icon={<Presentation size={16} />}
+                className="shrink-0"
+              >
+                {demoMode ? t('demoMode.disable', '退出演示') : t('demoMode.enable', '进入演示')}
+              </Button>
+            </div>
+
             {/* 说明 */}
             <div className="flex items-start gap-2 p-3 rounded-md bg-purple-500/10 text-sm text-purple-400">
               <Info size={16} className="shrink-0 mt-0.5" />
@@ -18,6 +18,8 @@ import { CharacterAvatar, WeaponAvatar, PityStatusPanel, HelpTooltip } from '../
 import { usePrefersReducedMotion } from '../../lib/usePrefersReducedMotion';
 import {
   getCharacterId,
+  getUpItemId,
+  isUpItem,
   sortRecordsByTimeAndSeq,
   calculateArmoryQuota,
   formatArmoryQuota,
@@ -181,14 +183,13 @@ export function WeaponPoolCard({ group, showFiveStars, defaultExpanded = true }:
   const showBgImage = !imgError;
 
   const { status } = group;
-  const upName = group.poolConfig?.pool?.up6_name;
+  const hasUp = !!group.poolConfig?.pool?.up6_id;
   
   // 获取 UP 武器的 ID（用于标题头像）
-  const upWeaponId = useMemo(() => {
-    if (!group.poolConfig || !upName) return undefined;
-    const weapon = group.poolConfig.pool.all.find(w => w.name === upName);
-    return weapon?.id;
-  }, [group.poolConfig, upName]);
+  const upWeaponId = useMemo(
+    () => (group.poolConfig ? getUpItemId(group.poolConfig) : undefined),
+    [group.poolConfig],
+  );
   const boxName = group.poolConfig?.pool?.gift_weapon_box_name || t('stats.ui.weaponPool.fallbackBox');
   const giftName = group.poolConfig?.pool?.gift_weapon_reward_name || t('stats.ui.weaponPool.fallbackGift');
 
@@ -379,7 +380,7 @@ export function WeaponPoolCard({ group, showFiveStars, defaultExpanded = true }:
                   const sixStarWeapons = s.sixStars.map((r) => ({
                     id: resolveWeaponId(r, group.poolConfig),
                     name: r.itemName,
-                    isUp: upName ? r.itemName === upName : false,
+                    isUp: isUpItem(r, group.poolConfig),
                   }));
                   const hasSixStar = s.sixStars.length > 0;
                   const fiveStarWeapons = showFiveStars
@@ -408,7 +409,7 @@ export function WeaponPoolCard({ group, showFiveStars, defaultExpanded = true }:
                                       rarity={6} 
                                       size="md" 
                                       isUp={w.isUp}
-                                      showOffBanner={!w.isUp && !!upName}
+                                      showOffBanner={!w.isUp && hasUp}
                                     />
                                   </span>
                                 ))}
@@ -545,9 +546,8 @@ export function FreeSegmentDisplay({
   // 只在有免费十连数据时显示
   if (!hasFree || freeCount === 0) return null;
   
-  const charId: string | undefined = freeSixStar && poolConfig 
-    ? getCharacterId(freeSixStar.itemName, poolConfig) 
-    : undefined;
+  const charId: string | undefined = freeSixStar?.charId
+    || (freeSixStar && poolConfig ? getCharacterId(freeSixStar.itemName, poolConfig) : undefined);
   
   return (
     <div className="flex items-center gap-3 py-1.5 bg-green-500/5 rounded-md px-2 border border-green-500/20">
@@ -850,11 +850,10 @@ export function PoolGroupCard({ group, showFiveStars, defaultExpanded = true }:
   const isSpecialPool = group.poolId.startsWith('special');
   
   // 获取 UP 角色的 ID（用于标题头像）
-  const upCharId = useMemo(() => {
-    if (!group.poolConfig) return undefined;
-    const upName = group.poolConfig.pool.up6_name;
-    return getCharacterId(upName, group.poolConfig);
-  }, [group.poolConfig]);
+  const upCharId = useMemo(
+    () => (group.poolConfig ? getUpItemId(group.poolConfig) : undefined),
+    [group.poolConfig],
+  );
 
   return (
     <div className="border border-border rounded-md overflow-hidden relative group">
@@ -0,0 +1,152 @@
+/**
+ * 资源估算
+ * 按用户设置的单抽价格把抽数折算成金额/资源（由 Rust 端 `get_statistics` 计算）
+ */
+
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { Coins, Pencil } from 'lucide-react';
+import { Button, HelpTooltip } from '../../components';
+import { errorMessage } from '../../../lib/errors';
+
+type PullPriceSettings = {
+  /** 单抽价格，为空表示未设置 */
+  pricePerPull: number | null;
+  currency: string;
+};
+
+type ResourceEstimateResult = {
+  pricePerPull: number;
+  currency: string;
+  estimatedCost: number;
+  freeValue: number;
+  includesUnclassified: boolean;
+};
+
+type Statistics = {
+  totalPulls: number;
+  paidPulls: number;
+  freePulls: number;
+  unclassifiedPulls: number;
+  resourceEstimate: ResourceEstimateResult | null;
+};
+
+export function ResourceEstimate({ uid, recordCount }: { uid: string; recordCount: number }) {
+  const { t, i18n } = useTranslation();
+  const [statistics, setStatistics] = useState<Statistics | null>(null);
+  const [editing, setEditing] = useState(false);
+  const [priceInput, setPriceInput] = useState('');
+  const [currencyInput, setCurrencyInput] = useState('CNY');
+  const [error, setError] = useState<string | null>(null);
+  const [reloadKey, setReloadKey] = useState(0);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<Statistics>('get_statistics', { uid })
+      .then((result) => {
+        if (!cancelled) setStatistics(result);
+      })
+      .catch(console.warn);
+    return () => {
+      cancelled = true;
+    };
+  }, [uid, recordCount, reloadKey]);
+
+  const startEditing = () => {
+    setError(null);
+    invoke<PullPriceSettings>('get_pull_price_settings')
+      .then((settings) => {
+        setPriceInput(settings.pricePerPull == null ? '' : String(settings.pricePerPull));
+        setCurrencyInput(settings.currency);
+        setEditing(true);
+      })
+      .catch(console.warn);
+  };
+
+  const save = () => {
+    const trimmed = priceInput.trim();
+    const price = trimmed === '' ? null : Number(trimmed);
+    if (price != null && (!Number.isFinite(price) || price < 0)) {
+      setError(t('stats.ui.resourceEstimate.invalidPrice', '单抽价格须为非负数'));
+      return;
+    }
+    invoke('set_pull_price_settings', { settings: { pricePerPull: price, currency: currencyInput } })
+      .then(() => {
+        setEditing(false);
+        setReloadKey((k) => k + 1);
+      })
+      .catch((e: unknown) => setError(errorMessage(e)));
+  };
+
+  const estimate = statistics?.resourceEstimate ?? null;
+  const format = (value: number) => value.toLocaleString(i18n.language, { maximumFractionDigits: 2 });
+
+  if (editing) {
+    return (
+      <div className="mt-4 flex flex-wrap items-center gap-2 text-sm">
+        <Coins size={16} className="text-amber-400" />
+        <span className="text-fg-2">{t('stats.ui.resourceEstimate.pricePerPull', '单抽价格')}</span>
+        <input
+          type="number"
+          min={0}
+          step="any"
+          value={priceInput}
+          onChange={(e) => setPriceInput(e.target.value)}
+          placeholder={t('stats.ui.resourceEstimate.pricePlaceholder', '留空则不估算')}
+          className="w-28 rounded-md border border-border bg-bg-2 px-2 py-1 text-fg-0 tabular-nums"
+        />
+        <input
+          type="text"
+          maxLength={16}
+          value={currencyInput}
+          onChange={(e) => setCurrencyInput(e.target.value)}
+          aria-label={t('stats.ui.resourceEstimate.currency', '货币')}
+          className="w-20 rounded-md border border-border bg-bg-2 px-2 py-1 text-fg-0"
+        />
+        <Button size="sm" onClick={save}>
+          {t('stats.ui.resourceEstimate.save', '保存')}
+        </Button>
+        <Button size="sm" variant="ghost" onClick={() => setEditing(false)}>
+          {t('common.cancel', '取消')}
+        </Button>
+        {error && <span className="text-red-400">{error}</span>}
+      </div>
+    );
+  }
+
+  return (
+    <div className="mt-4 flex flex-wrap items-center gap-3 text-sm">
+      <Coins size={16} className="text-amber-400" />
+      <span className="text-fg-2">{t('stats.ui.resourceEstimate.title', '资源估算')}</span>
+      {estimate && statistics ? (
+        <>
+          <span className="text-fg-0 font-semibold tabular-nums">
+            ≈ {format(estimate.estimatedCost)} {estimate.currency}
+          </span>
+          <span className="text-fg-2 tabular-nums">
+            {t('stats.ui.resourceEstimate.breakdown', '付费 {{paid}} 抽 · 免费 {{free}} 抽（价值 {{freeValue}}）', {
+              paid: statistics.paidPulls,
+              free: statistics.freePulls,
+              freeValue: `${format(estimate.freeValue)} ${estimate.currency}`,
+            })}
+          </span>
+          {estimate.includesUnclassified && (
+            <HelpTooltip
+              text={t(
+                'stats.ui.resourceEstimate.unclassifiedHint',
+                '其中 {{count}} 抽无法区分付费/免费（武器池记录），已按总数计入消耗。',
+                { count: statistics.unclassifiedPulls },
+              )}
+            />
+          )}
+        </>
+      ) : (
+        <span className="text-fg-2">{t('stats.ui.resourceEstimate.notConfigured', '设置单抽价格后显示估算')}</span>
+      )}
+      <Button size="sm" variant="ghost" onClick={startEditing} icon={<Pencil size={14} />}>
+        {t('stats.ui.resourceEstimate.edit', '设置')}
+      </Button>
+    </div>
+  );
+}
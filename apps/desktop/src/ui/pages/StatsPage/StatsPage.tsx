@@ -20,13 +20,14 @@ import {
 } from 'lucide-react';
 import { Card, CardHeader, CardContent, Button, CharacterAvatar, WeaponAvatar, HelpTooltip } from '../../components';
 import { useAccounts, useGachaRecordsData } from '../../../hooks/useEndfield';
-import { charRecordToUnified, weaponRecordToUnified, calculateUnifiedStats, getPoolTypePrefix, type UnifiedGachaRecord } from '../../../lib/storage';
+import { charRecordToUnified, weaponRecordToUnified, calculateUnifiedStats, getRecordPoolType, type UnifiedGachaRecord } from '../../../lib/storage';
 import { formatDateShort, getTimestamp } from '../../../lib/dateUtils';
 import type { GachaCategory } from '@efgachahelper/shared';
 import { usePrefersReducedMotion } from '../../lib/usePrefersReducedMotion';
 import {
   loadPoolConfig,
   isUpCharacter,
+  isUpItem,
   calculateSharedPityStatus,
   aggregateWeaponRecordsToSessions,
   calculateWeaponPoolStatus,
@@ -51,6 +52,7 @@ import {
 
 // 导入统计组件
 import { StatCard, RarityDistRow, PoolSummaryTile } from './StatComponents';
+import { ResourceEstimate } from './ResourceEstimate';
 
 /** 每抽消耗常量 */
 const CRYSTAL_PER_CHARACTER_PULL = 500;
@@ -140,7 +142,7 @@ export function StatsPage() {
     const sharedRecords = allRecords.filter(
       (r) =>
         r.category === 'character' &&
-        getPoolTypePrefix(r.poolId) === 'special' &&
+        getRecordPoolType(r) === 'special' &&
         !r.isFree
     );
     return calculateSharedPityStatus(sharedRecords);
@@ -156,10 +158,10 @@ export function StatsPage() {
   
   useEffect(() => {
     const loadPoolData = async () => {
-      // 限定池（角色，poolId 前缀 special_*）
+      // 限定池（角色，poolId 前缀 special_*，或手动纠正为限定池）
       const specialRecords = allRecords.filter(
         r => r.category === 'character' && 
-             getPoolTypePrefix(r.poolId) === 'special'
+             getRecordPoolType(r) === 'special'
       );
       
       // 武器池
@@ -169,12 +171,12 @@ export function StatsPage() {
       
       // 常驻池
       const standardRecords = allRecords.filter(
-        r => r.category === 'character' && getPoolTypePrefix(r.poolId) === 'standard'
+        r => r.category === 'character' && getRecordPoolType(r) === 'standard'
       );
       
       // 新手池
       const beginnerRecords = allRecords.filter(
-        r => r.category === 'character' && getPoolTypePrefix(r.poolId) === 'beginner'
+        r => r.category === 'character' && getRecordPoolType(r) === 'beginner'
       );
 
       const special = await groupRecordsByPool(specialRecords);
@@ -238,11 +240,11 @@ export function StatsPage() {
     }
 
     const specialRecordsAll = allRecords.filter(
-      (r) => r.category === 'character' && getPoolTypePrefix(r.poolId) === 'special'
+      (r) => r.category === 'character' && getRecordPoolType(r) === 'special'
     );
     const weaponRecordsAll = allRecords.filter((r) => r.category === 'weapon');
     const standardRecordsAll = allRecords.filter(
-      (r) => r.category === 'character' && getPoolTypePrefix(r.poolId) === 'standard'
+      (r) => r.category === 'character' && getRecordPoolType(r) === 'standard'
     );
 
     const specialCounts = getRarityCounts(specialRecordsAll);
@@ -259,7 +261,7 @@ export function StatsPage() {
       for (const r of specialRecordsAll) {
         if (r.rarity !== 6) continue;
         const cfg = specialPoolConfigs.get(r.poolId) ?? null;
-        const isUp = cfg ? isUpCharacter(r.itemName, cfg) : false;
+        const isUp = cfg ? isUpCharacter(r, cfg) : false;
         if (isUp) up += 1;
         else off += 1;
       }
@@ -275,7 +277,7 @@ export function StatsPage() {
         pulls += 1;
         if (r.rarity === 6) {
           const cfg = specialPoolConfigs.get(r.poolId) ?? null;
-          const isUp = cfg ? isUpCharacter(r.itemName, cfg) : false;
+          const isUp = cfg ? isUpCharacter(r, cfg) : false;
           if (isUp) {
             segments.push(pulls);
             pulls = 0;
@@ -290,10 +292,9 @@ export function StatsPage() {
       let up = 0;
       let off = 0;
       for (const g of poolGroupedData.weapon) {
-        const upName = g.poolConfig?.pool?.up6_name;
         for (const s of g.sessions) {
           for (const r of s.sixStars) {
-            if (upName && r.itemName === upName) up += 1;
+            if (isUpItem(r, g.poolConfig)) up += 1;
             else off += 1;
           }
         }
@@ -304,14 +305,13 @@ export function StatsPage() {
     const computeWeaponUpAvg = () => {
       const segments: number[] = [];
       for (const g of poolGroupedData.weapon) {
-        const upName = g.poolConfig?.pool?.up6_name;
-        if (!upName) continue;
+        if (!g.poolConfig) continue;
         let pulls = 0;
         for (const s of g.sessions) {
           for (const r of s.records) {
             if (r.isFree) continue;
             pulls += 1;
-            if (r.rarity === 6 && r.itemName === upName) {
+            if (r.rarity === 6 && isUpItem(r, g.poolConfig)) {
               segments.push(pulls);
               pulls = 0;
             }
@@ -559,6 +559,10 @@ export function StatsPage() {
             />
           </div>
 
+          {activeUid && (
+            <ResourceEstimate uid={activeUid} recordCount={gachaRecords.length + weaponRecords.length} />
+          )}
+
           {/* 限定/武器/常驻累计抽数与关键指标 */}
           <div className="mt-4 grid grid-cols-1 md:grid-cols-3 gap-4">
             <PoolSummaryTile
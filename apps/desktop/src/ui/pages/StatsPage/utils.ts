@@ -93,8 +93,8 @@ export function calculatePoolSegments(
     }
 
     if (record.rarity === 6) {
-      const isUp = poolConfig ? isUpCharacter(record.itemName, poolConfig) : false;
-      const charId = poolConfig ? getCharacterId(record.itemName, poolConfig) : record.charId;
+      const isUp = poolConfig ? isUpCharacter(record, poolConfig) : false;
+      const charId = record.charId || (poolConfig ? getCharacterId(record.itemName, poolConfig) : undefined);
       
       segments.push({
         pulls: currentPulls,
@@ -169,7 +169,7 @@ export async function groupRecordsByPool(records: UnifiedGachaRecord[]): Promise
       ? (() => {
           const nonFreePulls = nonFreeRecords.length;
           const hasUp6 = nonFreeRecords.some(
-            (r) => r.rarity === 6 && isUpCharacter(r.itemName, poolConfig)
+            (r) => r.rarity === 6 && isUpCharacter(r, poolConfig)
           );
           const pullsToUp120 = hasUp6 ? 0 : Math.max(0, 120 - nonFreePulls);
           const hasInfoBook60 = nonFreePulls >= 60;
@@ -3,9 +3,9 @@
  * 拉取抽卡记录（角色 + 武器）
  */
 
-import { useCallback } from 'react';
+import { useCallback, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
-import { useNavigate } from 'react-router-dom';
+import { useNavigate, useSearchParams } from 'react-router-dom';
 import {
   RefreshCw,
   Download,
@@ -41,6 +41,7 @@ const CATEGORY_LABEL_KEYS: Record<string, string> = {
 export function SyncPage() {
   const { t } = useTranslation();
   const navigate = useNavigate();
+  const [searchParams, setSearchParams] = useSearchParams();
   const { progress, syncRecords, reset } = useGachaSync();
   const { activeUid, activeAccount } = useAccounts();
   const { gachaRecords, weaponRecords, loading: recordsLoading } = useGachaRecordsData(activeUid);
@@ -68,6 +69,20 @@ export function SyncPage() {
   }, [activeUid, syncRecords, navigate]);
 
   const isLoading = progress.status === 'authenticating' || progress.status === 'fetching_records';
+
+  // 支持外部（例如换池提醒）触发：打开同步页后直接开始同步
+  useEffect(() => {
+    if (searchParams.get('auto') !== '1') return;
+
+    // 清理 query 参数，避免重复触发
+    const next = new URLSearchParams(searchParams);
+    next.delete('auto');
+    setSearchParams(next, { replace: true });
+
+    if (!isLoading) {
+      void handleSync();
+    }
+  }, [searchParams, setSearchParams, isLoading, handleSync]);
   const isAccountTokenExpiredError =
     progress.status === 'error' && progress.errorCode === 'account_token_expired';
 
@@ -193,6 +208,20 @@ export function SyncPage() {
                   <div className="font-medium text-green-400">{t('sync.success')}</div>
                   <div className="text-sm text-fg-2 space-y-0.5">
                     <div>{t('sync.successDetail', { count: progress.recordsFetched || 0 })}</div>
+                    {progress.stats && (
+                      <div className="text-xs">
+                        {t('sync.durationDetail', {
+                          seconds: (progress.stats.totalMs / 1000).toFixed(1),
+                          count: progress.stats.recordsProcessed,
+                        })}
+                        {' · '}
+                        {t('sync.durationBreakdown', {
+                          network: (progress.stats.networkMs / 1000).toFixed(1),
+                          dbWrite: (progress.stats.dbWriteMs / 1000).toFixed(1),
+                          rate: progress.stats.recordsPerSecond.toFixed(1),
+                        })}
+                      </div>
+                    )}
                     {(progress.charRecordsFetched !== undefined || progress.weaponRecordsFetched !== undefined) && (
                       <div className="flex gap-4 text-xs">
                         <span className="flex items-center gap-1">
@@ -3,13 +3,15 @@
  * 自定义的托盘右键菜单，使用独立窗口显示
  */
 
-import { useEffect, useState, useCallback } from 'react';
+import { useEffect, useLayoutEffect, useRef, useState, useCallback } from 'react';
+import { useTranslation } from 'react-i18next';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { CloudOff as LucideCloudOff, Cloud as LucideCloud } from 'lucide-react';
 import { getSyncConfig, subscribeSyncConfig } from '../../hooks/sync';
 import type { SyncConfig } from '@efgachahelper/shared';
 import { useTheme } from '../theme';
+import { useHeartbeat } from '../../hooks/useHeartbeat';
 
 // 菜单项类型
 type MenuItemProps = {
@@ -74,6 +76,27 @@ const Icons = {
       <path d="M21 12a9 9 0 01-9 9m9-9a9 9 0 00-9-9m9 9H3m9 9a9 9 0 01-9-9m9 9c1.657 0 3-4.03 3-9s-1.343-9-3-9m0 18c-1.657 0-3-4.03-3-9s1.343-9 3-9"/>
     </svg>
   ),
+  // 导出
+  Export: () => (
+    <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" className="w-4 h-4">
+      <path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4"/>
+      <polyline points="7 10 12 15 17 10"/>
+      <line x1="12" y1="15" x2="12" y2="3"/>
+    </svg>
+  ),
+  // 文件夹
+  Folder: () => (
+    <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" className="w-4 h-4">
+      <path d="M22 19a2 2 0 0 1-2 2H4a2 2 0 0 1-2-2V5a2 2 0 0 1 2-2h5l2 3h9a2 2 0 0 1 2 2z"/>
+    </svg>
+  ),
+  // 复制
+  Copy: () => (
+    <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" className="w-4 h-4">
+      <rect x="9" y="9" width="13" height="13" rx="2" ry="2"/>
+      <path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1"/>
+    </svg>
+  ),
   // 同步关闭
   SyncOff: () => (
     <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" className="w-4 h-4">
@@ -83,6 +106,37 @@ const Icons = {
   ),
 };
 
+/** 托盘快捷操作（与 Rust 端 get_tray_menu_data 对应，id 即命令名） */
+type TrayMenuActionId = 'export_latest' | 'open_data_dir' | 'copy_summary';
+
+/** 本地账号（与 Rust 端 AccountSummary 对应） */
+type TrayAccount = {
+  uid: string;
+  channel_name: string;
+  nick_name: string | null;
+  is_active: boolean;
+};
+
+type TrayMenuData = {
+  activeUid: string | null;
+  accounts: TrayAccount[];
+  actions: { id: TrayMenuActionId; enabled: boolean }[];
+};
+
+/** 今日（服务器日）抽卡概况（与 Rust 端 get_today_summary 对应） */
+type TodaySummary = {
+  dayStart: number;
+  pulls: number;
+  highestRarity: number | null;
+  pity: { gachaType: string; before: number; now: number }[];
+};
+
+const ACTION_ICONS: Record<TrayMenuActionId, () => React.ReactNode> = {
+  export_latest: Icons.Export,
+  open_data_dir: Icons.Folder,
+  copy_summary: Icons.Copy,
+};
+
 // 开关组件 - 精致的开关按钮
 function Toggle({
   checked,
@@ -182,7 +236,7 @@ function MenuItem({
       </span>
       
       {/* 标签 */}
-      <span className="relative flex-1 text-sm font-medium">{label}</span>
+      <span className="relative flex-1 text-sm font-medium whitespace-nowrap">{label}</span>
       
       {/* 额外内容（如开关） */}
       {children && <span className="relative">{children}</span>}
@@ -196,10 +250,14 @@ function Divider({ isDark }: { isDark: boolean }) {
 }
 
 export function TrayMenuPage() {
+  const { t, i18n } = useTranslation();
   const theme = useTheme();
+  useHeartbeat();
   const isDark = theme === 'dark';
   const [syncConfig, setSyncConfig] = useState<SyncConfig | null>(null);
   const [isReady, setIsReady] = useState(false);
+  const [menuData, setMenuData] = useState<TrayMenuData | null>(null);
+  const [today, setToday] = useState<TodaySummary | null>(null);
 
   // 判断是否已登录
   const isLoggedIn = syncConfig?.user && syncConfig?.accessToken;
@@ -249,6 +307,74 @@ export function TrayMenuPage() {
     };
   }, [loadSyncConfig]);
 
+  // 快捷操作列表：每次弹出菜单时刷新（当前账号可能已在主窗口切换）
+  const loadMenuData = useCallback(() => {
+    invoke<TrayMenuData>('get_tray_menu_data')
+      .then((data) => {
+        setMenuData(data);
+        if (!data.activeUid) {
+          setToday(null);
+          return;
+        }
+        return invoke<TodaySummary>('get_today_summary', { uid: data.activeUid }).then(setToday);
+      })
+      .catch((e: unknown) => console.error('[TrayMenu] Failed to load menu data:', e));
+  }, []);
+
+  useEffect(() => {
+    loadMenuData();
+    let unlisten: (() => void) | undefined;
+
+    void listen('tray-menu-position', () => {
+      loadMenuData();
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[TrayMenu] Failed to listen tray-menu-position:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, [loadMenuData]);
+
+  // 跟随主窗口切换语言
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    void listen<string>('language-changed', (event) => {
+      void i18n.changeLanguage(event.payload);
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch((e: unknown) => {
+        console.error('[TrayMenu] Failed to listen language-changed:', e);
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, [i18n]);
+
+  // 内容渲染后上报所需宽度（不同语言文字长度不同），由 Rust 端调整窗口尺寸并重新计算贴边位置
+  const contentRef = useRef<HTMLDivElement>(null);
+  useLayoutEffect(() => {
+    const el = contentRef.current;
+    if (!el) return;
+    // 临时按内容最大宽度排版测量，读完立即恢复（同步完成，不会闪烁）
+    const { width, minWidth } = el.style;
+    el.style.width = 'max-content';
+    el.style.minWidth = '0';
+    // 外框左右各 1px 边框
+    const needed = Math.ceil(el.getBoundingClientRect().width) + 2;
+    el.style.width = width;
+    el.style.minWidth = minWidth;
+    invoke('report_tray_menu_width', { width: needed }).catch(console.warn);
+  }, [isReady, i18n.language, isLoggedIn, menuData, today]);
+
   // 关闭菜单
   const closeMenu = useCallback(async () => {
     try {
@@ -258,6 +384,52 @@ export function TrayMenuPage() {
     }
   }, []);
 
+  // 执行快捷操作（在 Rust 端完成，主窗口隐藏时同样可用；菜单由 Rust 端关闭）
+  const handleAction = useCallback(async (id: TrayMenuActionId) => {
+    try {
+      await invoke(id);
+    } catch (e) {
+      console.error(`[TrayMenu] Failed to run ${id}:`, e);
+      await closeMenu();
+    }
+  }, [closeMenu]);
+
+  // 切换当前账号（Rust 端持久化并广播 active-account-changed，主窗口与后台任务随之切换）
+  const handleSwitchAccount = useCallback(async (uid: string) => {
+    try {
+      await invoke('set_active_account', { uid });
+      loadMenuData();
+    } catch (e) {
+      console.error('[TrayMenu] Failed to switch account:', e);
+    }
+  }, [loadMenuData]);
+
+  const poolLabel = (gachaType: string) => {
+    switch (gachaType) {
+      case 'special':
+        return t('tray.poolSpecial', '限定');
+      case 'standard':
+        return t('tray.poolStandard', '常驻');
+      case 'beginner':
+        return t('tray.poolBeginner', '新手');
+      case 'weapon':
+        return t('tray.poolWeapon', '武器');
+      default:
+        return gachaType;
+    }
+  };
+
+  const actionLabel = (id: TrayMenuActionId) => {
+    switch (id) {
+      case 'export_latest':
+        return t('tray.exportLatest', '快速导出');
+      case 'open_data_dir':
+        return t('tray.openDataDir', '打开数据目录');
+      case 'copy_summary':
+        return t('tray.copySummary', '复制抽卡摘要');
+    }
+  };
+
   // 显示主窗口
   const handleShowWindow = useCallback(async () => {
     try {
@@ -327,22 +499,93 @@ export function TrayMenuPage() {
         boxShadow: 'none',
       };
 
+  // 圆角外的透明区域：命中的是外层容器本身，视为点击菜单外部
+  // （Windows 下 Rust 端已用窗口区域裁掉四角，这里兜底其他平台）
+  const handleRootMouseDown = (e: React.MouseEvent<HTMLDivElement>) => {
+    if (e.target === e.currentTarget) {
+      e.preventDefault();
+      void closeMenu();
+    }
+  };
+
   return (
-    <div className="tray-menu-root">
+    <div className="tray-menu-root" onMouseDown={handleRootMouseDown}>
       <div 
         className="w-full h-full flex flex-col rounded-lg overflow-hidden tray-menu-animate"
         style={containerStyle}
       >
         {/* 菜单内容 */}
-        <div className="flex-1 p-1.5 overflow-hidden space-y-0.5">
+        <div ref={contentRef} className="flex-1 p-1.5 overflow-hidden space-y-0.5">
           {/* 显示主窗口 */}
           <MenuItem
             isDark={isDark}
             icon={<Icons.Window />}
-            label="显示主窗口"
+            label={t('tray.showWindow', '显示主界面')}
             onClick={() => { void handleShowWindow(); }}
           />
 
+          {/* 账号切换（多个账号时显示） */}
+          {menuData && menuData.accounts.length > 1 && (
+            <>
+              <Divider isDark={isDark} />
+              {menuData.accounts.map((account) => (
+                <MenuItem
+                  key={account.uid}
+                  isDark={isDark}
+                  icon={<Icons.User />}
+                  label={account.nick_name || account.uid}
+                  onClick={() => { void handleSwitchAccount(account.uid); }}
+                >
+                  {account.is_active && (
+                    <span className={`text-xs ${isDark ? 'text-blue-400' : 'text-blue-600'}`}>
+                      {t('tray.activeAccount', '当前')}
+                    </span>
+                  )}
+                </MenuItem>
+              ))}
+              <Divider isDark={isDark} />
+            </>
+          )}
+
+          {/* 快捷操作 */}
+          {menuData?.actions.map((action) => {
+            const Icon = ACTION_ICONS[action.id];
+            return (
+              <MenuItem
+                key={action.id}
+                isDark={isDark}
+                icon={<Icon />}
+                label={actionLabel(action.id)}
+                disabled={!action.enabled}
+                onClick={() => { void handleAction(action.id); }}
+              />
+            );
+          })}
+
+          {/* 今日抽卡概况 */}
+          {today && (
+            <div className={`px-2.5 py-1 text-xs leading-5 ${isDark ? 'text-gray-400' : 'text-gray-500'}`}>
+              <p className="whitespace-nowrap">
+                {today.pulls > 0
+                  ? t('tray.todayPulls', '今日 {{count}} 抽 · 最高 {{rarity}}★', {
+                      count: today.pulls,
+                      rarity: today.highestRarity ?? '-',
+                    })
+                  : t('tray.todayNone', '今日暂无抽卡')}
+              </p>
+              <p className="whitespace-nowrap">
+                {today.pity.length > 0
+                  ? today.pity
+                      .map((p) => {
+                        const delta = p.now - p.before;
+                        return `${poolLabel(p.gachaType)} ${delta >= 0 ? '+' : ''}${delta}（${t('tray.pityNow', '已垫 {{count}}', { count: p.now })}）`;
+                      })
+                      .join(' · ')
+                  : t('tray.todayPityUnchanged', '保底无变化')}
+              </p>
+            </div>
+          )}
+
           <Divider isDark={isDark} />
 
           {/* 云同步区域 */}
@@ -356,11 +599,11 @@ export function TrayMenuPage() {
                   </div>
                   <div className="flex-1 min-w-0">
                     <p className={`text-sm font-medium truncate ${isDark ? 'text-white' : 'text-gray-900'}`}>
-                      {syncConfig?.user?.email || '用户'}
+                      {syncConfig?.user?.email || t('tray.user', '用户')}
                     </p>
                     <p className={`text-xs flex items-center gap-1 ${isDark ? 'text-green-400' : 'text-green-600'}`}>
                       <span className="w-1.5 h-1.5 rounded-full bg-green-400" />
-                      已登录云同步
+                      {t('tray.cloudLoggedIn', '已登录云同步')}
                     </p>
                   </div>
                 </div>
@@ -392,7 +635,7 @@ export function TrayMenuPage() {
                       : (isDark ? 'text-gray-300' : 'text-gray-700')}
                   `}
                 >
-                  自动同步
+                  {t('tray.autoSync', '自动同步')}
                 </span>
                 <Toggle 
                   checked={isAutoSyncEnabled || false} 
@@ -410,15 +653,15 @@ export function TrayMenuPage() {
                     <LucideCloudOff size={16} />
                   </span>
                   <div className="flex-1">
-                    <p className={`text-sm font-medium ${isDark ? 'text-gray-200' : 'text-gray-800'}`}>云同步未登录</p>
-                    <p className={`text-xs mt-0.5 ${isDark ? 'text-gray-400' : 'text-gray-600'}`}>登录后可跨设备同步数据</p>
+                    <p className={`text-sm font-medium ${isDark ? 'text-gray-200' : 'text-gray-800'}`}>{t('tray.cloudNotLoggedIn', '云同步未登录')}</p>
+                    <p className={`text-xs mt-0.5 ${isDark ? 'text-gray-400' : 'text-gray-600'}`}>{t('tray.cloudLoginHint', '登录后可跨设备同步数据')}</p>
                   </div>
                 </div>
               </div>
               <MenuItem
                 isDark={isDark}
                 icon={<Icons.Login />}
-                label="登录云同步账号"
+                label={t('tray.loginCloudSync', '登录云同步账号')}
                 onClick={() => { void handleOpenCloudSync(); }}
               />
             </>
@@ -430,7 +673,7 @@ export function TrayMenuPage() {
           <MenuItem
             isDark={isDark}
             icon={<Icons.Exit />}
-            label="退出程序"
+            label={t('tray.quit', '退出程序')}
             onClick={() => { void handleQuit(); }}
             danger
           />
@@ -5,5 +5,6 @@ export { AccountPage } from './AccountPage';
 export { SettingsPage } from './SettingsPage';
 export { CloudSyncPage } from './CloudSyncPage';
 export { LeaderboardPage } from './LeaderboardPage';
+export { ComparePage } from './ComparePage';
 export { AboutPage } from './AboutPage';
 export { TrayMenuPage } from './TrayMenuPage';